@@ -55,11 +55,11 @@ dict_items = type({}.items())
 mappingproxy = type(type.__dict__)
 # generator = type((lambda: (yield))())
 ## coroutine ##
-# async def _coro(): pass
-# _coro = _coro()
-# coroutine = type(_coro)
-# _coro.close()  # Prevent ResourceWarning
-# del _coro
+async def _coro(): pass
+_coro = _coro()
+coroutine = type(_coro)
+_coro.close()  # Prevent ResourceWarning
+del _coro
 # ## asynchronous generator ##
 # async def _ag(): yield
 # _ag = _ag()
@@ -96,145 +96,145 @@ class Hashable(metaclass=ABCMeta):
         return NotImplemented
 
 
-# class Awaitable(metaclass=ABCMeta):
+class Awaitable(metaclass=ABCMeta):
 
-#     __slots__ = ()
+    __slots__ = ()
+
+    @abstractmethod
+    def __await__(self):
+        yield
+
+    @classmethod
+    def __subclasshook__(cls, C):
+        if cls is Awaitable:
+            return _check_methods(C, "__await__")
+        return NotImplemented
+
+
+class Coroutine(Awaitable):
+
+    __slots__ = ()
+
+    @abstractmethod
+    def send(self, value):
+        """Send a value into the coroutine.
+        Return next yielded value or raise StopIteration.
+        """
+        raise StopIteration
+
+    @abstractmethod
+    def throw(self, typ, val=None, tb=None):
+        """Raise an exception in the coroutine.
+        Return next yielded value or raise StopIteration.
+        """
+        if val is None:
+            if tb is None:
+                raise typ
+            val = typ()
+        if tb is not None:
+            val = val.with_traceback(tb)
+        raise val
+
+    def close(self):
+        """Raise GeneratorExit inside coroutine.
+        """
+        try:
+            self.throw(GeneratorExit)
+        except (GeneratorExit, StopIteration):
+            pass
+        else:
+            raise RuntimeError("coroutine ignored GeneratorExit")
+
+    @classmethod
+    def __subclasshook__(cls, C):
+        if cls is Coroutine:
+            return _check_methods(C, '__await__', 'send', 'throw', 'close')
+        return NotImplemented
+
+
+Coroutine.register(coroutine)
 
-#     @abstractmethod
-#     def __await__(self):
-#         yield
 
-#     @classmethod
-#     def __subclasshook__(cls, C):
-#         if cls is Awaitable:
-#             return _check_methods(C, "__await__")
-#         return NotImplemented
+class AsyncIterable(metaclass=ABCMeta):
 
+    __slots__ = ()
+
+    @abstractmethod
+    def __aiter__(self):
+        return AsyncIterator()
+
+    @classmethod
+    def __subclasshook__(cls, C):
+        if cls is AsyncIterable:
+            return _check_methods(C, "__aiter__")
+        return NotImplemented
+
+
+class AsyncIterator(AsyncIterable):
+
+    __slots__ = ()
 
-# class Coroutine(Awaitable):
+    @abstractmethod
+    async def __anext__(self):
+        """Return the next item or raise StopAsyncIteration when exhausted."""
+        raise StopAsyncIteration
+
+    def __aiter__(self):
+        return self
 
-#     __slots__ = ()
+    @classmethod
+    def __subclasshook__(cls, C):
+        if cls is AsyncIterator:
+            return _check_methods(C, "__anext__", "__aiter__")
+        return NotImplemented
 
-#     @abstractmethod
-#     def send(self, value):
-#         """Send a value into the coroutine.
-#         Return next yielded value or raise StopIteration.
-#         """
-#         raise StopIteration
 
-#     @abstractmethod
-#     def throw(self, typ, val=None, tb=None):
-#         """Raise an exception in the coroutine.
-#         Return next yielded value or raise StopIteration.
-#         """
-#         if val is None:
-#             if tb is None:
-#                 raise typ
-#             val = typ()
-#         if tb is not None:
-#             val = val.with_traceback(tb)
-#         raise val
-
-#     def close(self):
-#         """Raise GeneratorExit inside coroutine.
-#         """
-#         try:
-#             self.throw(GeneratorExit)
-#         except (GeneratorExit, StopIteration):
-#             pass
-#         else:
-#             raise RuntimeError("coroutine ignored GeneratorExit")
-
-#     @classmethod
-#     def __subclasshook__(cls, C):
-#         if cls is Coroutine:
-#             return _check_methods(C, '__await__', 'send', 'throw', 'close')
-#         return NotImplemented
-
-
-# Coroutine.register(coroutine)
-
-
-# class AsyncIterable(metaclass=ABCMeta):
-
-#     __slots__ = ()
-
-#     @abstractmethod
-#     def __aiter__(self):
-#         return AsyncIterator()
-
-#     @classmethod
-#     def __subclasshook__(cls, C):
-#         if cls is AsyncIterable:
-#             return _check_methods(C, "__aiter__")
-#         return NotImplemented
-
-
-# class AsyncIterator(AsyncIterable):
-
-#     __slots__ = ()
-
-#     @abstractmethod
-#     async def __anext__(self):
-#         """Return the next item or raise StopAsyncIteration when exhausted."""
-#         raise StopAsyncIteration
-
-#     def __aiter__(self):
-#         return self
-
-#     @classmethod
-#     def __subclasshook__(cls, C):
-#         if cls is AsyncIterator:
-#             return _check_methods(C, "__anext__", "__aiter__")
-#         return NotImplemented
-
-
-# class AsyncGenerator(AsyncIterator):
-
-#     __slots__ = ()
-
-#     async def __anext__(self):
-#         """Return the next item from the asynchronous generator.
-#         When exhausted, raise StopAsyncIteration.
-#         """
-#         return await self.asend(None)
-
-#     @abstractmethod
-#     async def asend(self, value):
-#         """Send a value into the asynchronous generator.
-#         Return next yielded value or raise StopAsyncIteration.
-#         """
-#         raise StopAsyncIteration
-
-#     @abstractmethod
-#     async def athrow(self, typ, val=None, tb=None):
-#         """Raise an exception in the asynchronous generator.
-#         Return next yielded value or raise StopAsyncIteration.
-#         """
-#         if val is None:
-#             if tb is None:
-#                 raise typ
-#             val = typ()
-#         if tb is not None:
-#             val = val.with_traceback(tb)
-#         raise val
-
-#     async def aclose(self):
-#         """Raise GeneratorExit inside coroutine.
-#         """
-#         try:
-#             await self.athrow(GeneratorExit)
-#         except (GeneratorExit, StopAsyncIteration):
-#             pass
-#         else:
-#             raise RuntimeError("asynchronous generator ignored GeneratorExit")
-
-#     @classmethod
-#     def __subclasshook__(cls, C):
-#         if cls is AsyncGenerator:
-#             return _check_methods(C, '__aiter__', '__anext__',
-#                                   'asend', 'athrow', 'aclose')
-#         return NotImplemented
+class AsyncGenerator(AsyncIterator):
+
+    __slots__ = ()
+
+    async def __anext__(self):
+        """Return the next item from the asynchronous generator.
+        When exhausted, raise StopAsyncIteration.
+        """
+        return await self.asend(None)
+
+    @abstractmethod
+    async def asend(self, value):
+        """Send a value into the asynchronous generator.
+        Return next yielded value or raise StopAsyncIteration.
+        """
+        raise StopAsyncIteration
+
+    @abstractmethod
+    async def athrow(self, typ, val=None, tb=None):
+        """Raise an exception in the asynchronous generator.
+        Return next yielded value or raise StopAsyncIteration.
+        """
+        if val is None:
+            if tb is None:
+                raise typ
+            val = typ()
+        if tb is not None:
+            val = val.with_traceback(tb)
+        raise val
+
+    async def aclose(self):
+        """Raise GeneratorExit inside coroutine.
+        """
+        try:
+            await self.athrow(GeneratorExit)
+        except (GeneratorExit, StopAsyncIteration):
+            pass
+        else:
+            raise RuntimeError("asynchronous generator ignored GeneratorExit")
+
+    @classmethod
+    def __subclasshook__(cls, C):
+        if cls is AsyncGenerator:
+            return _check_methods(C, '__aiter__', '__anext__',
+                                  'asend', 'athrow', 'aclose')
+        return NotImplemented
 
 
 # AsyncGenerator.register(async_generator)
@@ -0,0 +1,26 @@
+"""A minimal, single threaded asyncio.
+
+Coroutines are run as tasks on an event loop, which only supports
+callbacks and timers: there is no network I/O.
+"""
+
+from .coroutines import iscoroutine
+from .events import (
+    Handle, TimerHandle, EventLoop, get_event_loop, set_event_loop,
+    new_event_loop, get_running_loop,
+)
+from .futures import CancelledError, InvalidStateError, Future, isfuture
+from .tasks import (
+    Task, create_task, ensure_future, current_task, sleep, gather,
+)
+from .runners import run
+
+__all__ = (
+    'iscoroutine',
+    'Handle', 'TimerHandle', 'EventLoop', 'get_event_loop',
+    'set_event_loop', 'new_event_loop', 'get_running_loop',
+    'CancelledError', 'InvalidStateError', 'Future', 'isfuture',
+    'Task', 'create_task', 'ensure_future', 'current_task', 'sleep',
+    'gather',
+    'run',
+)
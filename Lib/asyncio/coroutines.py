@@ -0,0 +1,8 @@
+__all__ = ['iscoroutine']
+
+import types
+
+
+def iscoroutine(obj):
+    """Return True if obj is a coroutine object."""
+    return isinstance(obj, types.CoroutineType)
@@ -0,0 +1,238 @@
+"""Event loop and related classes.
+
+There is no selector: the loop only runs callbacks, either as soon as
+possible or at a given time, which is enough for cooperative coroutines.
+"""
+
+__all__ = [
+    'Handle', 'TimerHandle', 'EventLoop',
+    'get_event_loop', 'set_event_loop', 'new_event_loop',
+    'get_running_loop',
+]
+
+import contextvars
+import heapq
+import sys
+import time
+
+
+class Handle:
+    """Object returned by callback registration methods.
+
+    The callback runs in the given context, by default a copy of the current
+    one.
+    """
+
+    def __init__(self, callback, args, loop, context=None):
+        if context is None:
+            context = contextvars.copy_context()
+        self._context = context
+        self._callback = callback
+        self._args = args
+        self._loop = loop
+        self._cancelled = False
+
+    def __repr__(self):
+        state = ' cancelled' if self._cancelled else ''
+        return '<{}{} {!r}>'.format(type(self).__name__, state, self._callback)
+
+    def cancel(self):
+        if not self._cancelled:
+            self._cancelled = True
+            self._callback = None
+            self._args = None
+
+    def cancelled(self):
+        return self._cancelled
+
+    def _run(self):
+        try:
+            self._context.run(self._callback, *self._args)
+        except Exception as exc:
+            self._loop.call_exception_handler({
+                'message': 'Exception in callback {!r}'.format(self._callback),
+                'exception': exc,
+                'handle': self,
+            })
+
+
+class TimerHandle(Handle):
+    """Object returned by timed callback registration methods."""
+
+    def __init__(self, when, callback, args, loop, context=None):
+        super().__init__(callback, args, loop, context)
+        self._when = when
+
+    def __lt__(self, other):
+        return self._when < other._when
+
+    def when(self):
+        return self._when
+
+
+class EventLoop:
+    def __init__(self):
+        self._ready = []
+        # a heap of TimerHandles, ordered by when they are due
+        self._scheduled = []
+        self._stopping = False
+        self._closed = False
+        self._running = False
+
+    def __repr__(self):
+        return '<{} running={} closed={}>'.format(
+            type(self).__name__, self._running, self._closed)
+
+    def time(self):
+        return time.monotonic()
+
+    def call_soon(self, callback, *args, context=None):
+        self._check_closed()
+        handle = Handle(callback, args, self, context)
+        self._ready.append(handle)
+        return handle
+
+    def call_later(self, delay, callback, *args, context=None):
+        return self.call_at(self.time() + delay, callback, *args,
+                            context=context)
+
+    def call_at(self, when, callback, *args, context=None):
+        self._check_closed()
+        timer = TimerHandle(when, callback, args, self, context)
+        heapq.heappush(self._scheduled, timer)
+        return timer
+
+    def create_future(self):
+        from . import futures
+        return futures.Future(loop=self)
+
+    def create_task(self, coro):
+        from . import tasks
+        self._check_closed()
+        return tasks.Task(coro, loop=self)
+
+    def run_forever(self):
+        self._check_closed()
+        if self._running:
+            raise RuntimeError('This event loop is already running')
+        if _get_running_loop() is not None:
+            raise RuntimeError(
+                'Cannot run the event loop while another loop is running')
+        self._running = True
+        _set_running_loop(self)
+        try:
+            while True:
+                self._run_once()
+                if self._stopping:
+                    break
+        finally:
+            self._stopping = False
+            self._running = False
+            _set_running_loop(None)
+
+    def run_until_complete(self, future):
+        from . import tasks
+        self._check_closed()
+        future = tasks.ensure_future(future, loop=self)
+        future.add_done_callback(_run_until_complete_cb)
+        self.run_forever()
+        if not future.done():
+            raise RuntimeError('Event loop stopped before Future completed.')
+        return future.result()
+
+    def stop(self):
+        self._stopping = True
+
+    def is_running(self):
+        return self._running
+
+    def is_closed(self):
+        return self._closed
+
+    def close(self):
+        if self._running:
+            raise RuntimeError('Cannot close a running event loop')
+        self._closed = True
+        self._ready.clear()
+        self._scheduled.clear()
+
+    def call_exception_handler(self, context):
+        message = context.get('message', 'Unhandled exception in event loop')
+        exception = context.get('exception')
+        if exception is not None:
+            message = '{}: {!r}'.format(message, exception)
+        print(message, file=sys.stderr)
+
+    def _check_closed(self):
+        if self._closed:
+            raise RuntimeError('Event loop is closed')
+
+    def _run_once(self):
+        """Run one full iteration of the event loop.
+
+        This sleeps until the first timer is due if there is nothing
+        ready yet, then runs every callback that was ready beforehand.
+        """
+        if not self._ready and not self._stopping:
+            if not self._scheduled:
+                # there is no I/O to wait for, so nothing can ever wake us
+                raise RuntimeError('Event loop has nothing left to run')
+            timeout = self._scheduled[0]._when - self.time()
+            if timeout > 0:
+                time.sleep(timeout)
+
+        end_time = self.time()
+        while self._scheduled and self._scheduled[0]._when <= end_time:
+            handle = heapq.heappop(self._scheduled)
+            if not handle._cancelled:
+                self._ready.append(handle)
+
+        ready, self._ready = self._ready, []
+        for handle in ready:
+            if not handle._cancelled:
+                handle._run()
+
+
+def _run_until_complete_cb(future):
+    future.get_loop().stop()
+
+
+_running_loop = None
+_event_loop = None
+
+
+def _get_running_loop():
+    return _running_loop
+
+
+def _set_running_loop(loop):
+    global _running_loop
+    _running_loop = loop
+
+
+def get_running_loop():
+    """Return the running event loop, raising RuntimeError if there is none."""
+    loop = _get_running_loop()
+    if loop is None:
+        raise RuntimeError('no running event loop')
+    return loop
+
+
+def get_event_loop():
+    """Return the running event loop, or the current one if none is running."""
+    global _event_loop
+    loop = _get_running_loop()
+    if loop is not None:
+        return loop
+    if _event_loop is None:
+        _event_loop = new_event_loop()
+    return _event_loop
+
+
+def set_event_loop(loop):
+    global _event_loop
+    _event_loop = loop
+
+
+def new_event_loop():
+    return EventLoop()
@@ -0,0 +1,133 @@
+"""A Future class similar to the one in concurrent.futures."""
+
+__all__ = ['CancelledError', 'InvalidStateError', 'Future', 'isfuture']
+
+import contextvars
+
+from . import events
+
+_PENDING = 'PENDING'
+_CANCELLED = 'CANCELLED'
+_FINISHED = 'FINISHED'
+
+
+class CancelledError(Exception):
+    """The Future or Task was cancelled."""
+
+
+class InvalidStateError(Exception):
+    """The operation is not allowed in this state."""
+
+
+def isfuture(obj):
+    """Check for a Future, or an object compatible with one."""
+    return getattr(obj, '_asyncio_future_blocking', None) is not None
+
+
+class Future:
+    """The result of an asynchronous operation, set by whoever runs it.
+
+    Awaiting a future that is not done yet suspends the awaiting task
+    until a result or an exception is set.
+    """
+
+    _state = _PENDING
+    _result = None
+    _exception = None
+    # set while a task is suspended on this future
+    _asyncio_future_blocking = False
+
+    def __init__(self, *, loop=None):
+        if loop is None:
+            loop = events.get_event_loop()
+        self._loop = loop
+        self._callbacks = []
+
+    def __repr__(self):
+        info = self._state.lower()
+        if self._state == _FINISHED:
+            if self._exception is not None:
+                info += ' exception={!r}'.format(self._exception)
+            else:
+                info += ' result={!r}'.format(self._result)
+        return '<{} {}>'.format(type(self).__name__, info)
+
+    def get_loop(self):
+        return self._loop
+
+    def cancel(self):
+        if self._state != _PENDING:
+            return False
+        self._state = _CANCELLED
+        self._schedule_callbacks()
+        return True
+
+    def cancelled(self):
+        return self._state == _CANCELLED
+
+    def done(self):
+        return self._state != _PENDING
+
+    def result(self):
+        if self._state == _CANCELLED:
+            raise CancelledError
+        if self._state != _FINISHED:
+            raise InvalidStateError('Result is not ready.')
+        if self._exception is not None:
+            raise self._exception
+        return self._result
+
+    def exception(self):
+        if self._state == _CANCELLED:
+            raise CancelledError
+        if self._state != _FINISHED:
+            raise InvalidStateError('Exception is not set.')
+        return self._exception
+
+    def add_done_callback(self, fn, *, context=None):
+        if context is None:
+            context = contextvars.copy_context()
+        if self._state != _PENDING:
+            self._loop.call_soon(fn, self, context=context)
+        else:
+            self._callbacks.append((fn, context))
+
+    def remove_done_callback(self, fn):
+        remaining = [(f, ctx) for (f, ctx) in self._callbacks if f != fn]
+        removed_count = len(self._callbacks) - len(remaining)
+        self._callbacks[:] = remaining
+        return removed_count
+
+    def set_result(self, result):
+        if self._state != _PENDING:
+            raise InvalidStateError('{}: {!r}'.format(self._state, self))
+        self._result = result
+        self._state = _FINISHED
+        self._schedule_callbacks()
+
+    def set_exception(self, exception):
+        if self._state != _PENDING:
+            raise InvalidStateError('{}: {!r}'.format(self._state, self))
+        if isinstance(exception, type):
+            exception = exception()
+        if type(exception) is StopIteration:
+            raise TypeError('StopIteration interacts badly with generators '
+                            'and cannot be raised into a Future')
+        self._exception = exception
+        self._state = _FINISHED
+        self._schedule_callbacks()
+
+    def _schedule_callbacks(self):
+        callbacks, self._callbacks = self._callbacks, []
+        for callback, ctx in callbacks:
+            self._loop.call_soon(callback, self, context=ctx)
+
+    def __await__(self):
+        if not self.done():
+            self._asyncio_future_blocking = True
+            yield self  # the task wakes us up once we are done
+        if not self.done():
+            raise RuntimeError("await wasn't used with future")
+        return self.result()
+
+    __iter__ = __await__
@@ -0,0 +1,25 @@
+__all__ = ['run']
+
+from . import coroutines
+from . import events
+
+
+def run(main):
+    """Run a coroutine on a new event loop and return its result.
+
+    The loop is closed afterwards, so this should be the main entry point
+    of an asyncio program and called only once.
+    """
+    if events._get_running_loop() is not None:
+        raise RuntimeError(
+            'asyncio.run() cannot be called from a running event loop')
+    if not coroutines.iscoroutine(main):
+        raise ValueError('a coroutine was expected, got {!r}'.format(main))
+
+    loop = events.new_event_loop()
+    try:
+        events.set_event_loop(loop)
+        return loop.run_until_complete(main)
+    finally:
+        events.set_event_loop(None)
+        loop.close()
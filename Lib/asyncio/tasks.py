@@ -0,0 +1,232 @@
+"""Tasks, which run coroutines on the event loop, and helpers built on them."""
+
+__all__ = [
+    'Task', 'create_task', 'ensure_future', 'current_task',
+    'sleep', 'gather',
+]
+
+import contextvars
+
+from . import coroutines
+from . import events
+from . import futures
+
+# The task currently being stepped by each running loop
+_current_tasks = {}
+
+
+def current_task(loop=None):
+    """Return the currently running task, or None."""
+    if loop is None:
+        loop = events.get_running_loop()
+    return _current_tasks.get(loop)
+
+
+class Task(futures.Future):
+    """A coroutine wrapped in a Future.
+
+    The task steps the coroutine on the event loop. Whenever the coroutine
+    awaits a pending future the task is suspended until that future is
+    done.
+    """
+
+    def __init__(self, coro, *, loop=None):
+        super().__init__(loop=loop)
+        if not coroutines.iscoroutine(coro):
+            raise TypeError('a coroutine was expected, got {!r}'.format(coro))
+        self._coro = coro
+        self._fut_waiter = None
+        self._must_cancel = False
+        # every step of the coroutine runs in the task's own context
+        self._context = contextvars.copy_context()
+        self._loop.call_soon(self._step, context=self._context)
+
+    def get_coro(self):
+        return self._coro
+
+    def cancel(self):
+        if self.done():
+            return False
+        if self._fut_waiter is not None and self._fut_waiter.cancel():
+            # the wakeup will throw CancelledError into the coroutine
+            return True
+        self._must_cancel = True
+        return True
+
+    def _step(self, exc=None):
+        if self._must_cancel:
+            if not isinstance(exc, futures.CancelledError):
+                exc = futures.CancelledError()
+            self._must_cancel = False
+        self._fut_waiter = None
+
+        _current_tasks[self._loop] = self
+        try:
+            if exc is None:
+                result = self._coro.send(None)
+            else:
+                result = self._coro.throw(exc)
+        except StopIteration as exc:
+            if self._must_cancel:
+                self._must_cancel = False
+                super().cancel()
+            else:
+                self.set_result(exc.value)
+        except futures.CancelledError:
+            super().cancel()
+        except Exception as exc:
+            self.set_exception(exc)
+        else:
+            self._handle_yield(result)
+        finally:
+            del _current_tasks[self._loop]
+
+    def _handle_yield(self, result):
+        blocking = getattr(result, '_asyncio_future_blocking', None)
+        if blocking is not None:
+            if not blocking:
+                error = RuntimeError(
+                    'yield was used instead of await in task {!r}'.format(self))
+                self._loop.call_soon(self._step, error, context=self._context)
+                return
+            result._asyncio_future_blocking = False
+            result.add_done_callback(self._wakeup, context=self._context)
+            self._fut_waiter = result
+            if self._must_cancel and self._fut_waiter.cancel():
+                self._must_cancel = False
+        elif result is None:
+            # a bare yield gives the other tasks a chance to run
+            self._loop.call_soon(self._step, context=self._context)
+        else:
+            error = RuntimeError('Task got bad yield: {!r}'.format(result))
+            self._loop.call_soon(self._step, error, context=self._context)
+
+    def _wakeup(self, future):
+        try:
+            future.result()
+        except Exception as exc:
+            self._step(exc)
+        else:
+            self._step()
+
+
+def create_task(coro):
+    """Schedule the execution of a coroutine on the running loop."""
+    return events.get_running_loop().create_task(coro)
+
+
+def ensure_future(coro_or_future, *, loop=None):
+    """Wrap a coroutine or an awaitable in a future.
+
+    Futures are returned as they are.
+    """
+    if futures.isfuture(coro_or_future):
+        if loop is not None and loop is not coro_or_future.get_loop():
+            raise ValueError('loop argument must agree with Future')
+        return coro_or_future
+    if loop is None:
+        loop = events.get_event_loop()
+    if coroutines.iscoroutine(coro_or_future):
+        return loop.create_task(coro_or_future)
+    if hasattr(coro_or_future, '__await__'):
+        return loop.create_task(_wrap_awaitable(coro_or_future))
+    raise TypeError('An asyncio.Future, a coroutine or an awaitable is '
+                    'required')
+
+
+async def _wrap_awaitable(awaitable):
+    return await awaitable
+
+
+class _Yield:
+    """Awaiting this suspends the current task for one loop iteration."""
+
+    def __await__(self):
+        yield
+
+
+def _set_result_unless_cancelled(fut, result):
+    if not fut.cancelled():
+        fut.set_result(result)
+
+
+async def sleep(delay, result=None):
+    """Coroutine that completes after a given time (in seconds)."""
+    if delay <= 0:
+        await _Yield()
+        return result
+
+    loop = events.get_running_loop()
+    future = loop.create_future()
+    handle = loop.call_later(delay, _set_result_unless_cancelled,
+                             future, result)
+    try:
+        return await future
+    finally:
+        handle.cancel()
+
+
+class _GatheringFuture(futures.Future):
+    """The future returned by gather(), cancelling it cancels the children."""
+
+    def __init__(self, children, *, loop):
+        super().__init__(loop=loop)
+        self._children = children
+        self._nfinished = 0
+
+    def cancel(self):
+        if self.done():
+            return False
+        ret = False
+        for child in self._children:
+            if child.cancel():
+                ret = True
+        return ret
+
+    def _child_done(self, return_exceptions):
+        def callback(fut):
+            if self.done():
+                return
+            if not return_exceptions:
+                if fut.cancelled():
+                    self.set_exception(futures.CancelledError())
+                    return
+                exc = fut.exception()
+                if exc is not None:
+                    self.set_exception(exc)
+                    return
+
+            self._nfinished += 1
+            if self._nfinished < len(self._children):
+                return
+            results = []
+            for child in self._children:
+                if child.cancelled():
+                    res = futures.CancelledError()
+                else:
+                    res = child.exception()
+                    if res is None:
+                        res = child.result()
+                results.append(res)
+            self.set_result(results)
+        return callback
+
+
+def gather(*coros_or_futures, return_exceptions=False):
+    """Run awaitables concurrently, and collect their results in order.
+
+    Unless return_exceptions is true, the first exception raised by a
+    child is propagated to the returned future.
+    """
+    loop = events.get_event_loop()
+    if not coros_or_futures:
+        outer = loop.create_future()
+        outer.set_result([])
+        return outer
+
+    children = [ensure_future(arg, loop=loop) for arg in coros_or_futures]
+    outer = _GatheringFuture(children, loop=loop)
+    callback = outer._child_done(return_exceptions)
+    for child in children:
+        child.add_done_callback(callback)
+    return outer
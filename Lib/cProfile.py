@@ -0,0 +1,134 @@
+#! /usr/bin/env python3
+
+"""Python interface compatible with CPython's 'cProfile' module.
+
+RustPython has no '_lsprof' extension, so this profiler is built on the
+pure Python one from the 'profile' module and times calls with
+time.perf_counter().
+"""
+
+__all__ = ["run", "runctx", "Profile"]
+
+import sys
+import time
+import profile as _pyprofile
+
+# ____________________________________________________________
+# Simple interface
+
+def run(statement, filename=None, sort=-1):
+    return _pyprofile._Utils(Profile).run(statement, filename, sort)
+
+def runctx(statement, globals, locals, filename=None, sort=-1):
+    return _pyprofile._Utils(Profile).runctx(statement, globals, locals,
+                                             filename, sort)
+
+run.__doc__ = _pyprofile.run.__doc__
+runctx.__doc__ = _pyprofile.runctx.__doc__
+
+# ____________________________________________________________
+
+class Profile(_pyprofile.Profile):
+    """Profile(timer=None, bias=None)
+
+    Builds a profiler object using the specified timer function.
+    The default timer is time.perf_counter().
+    """
+
+    # Most of the functionality is in the base class.
+    # This subclass only adds the methods of cProfile.Profile.
+
+    def __init__(self, timer=None, bias=None):
+        super().__init__(timer or time.perf_counter, bias)
+
+    def enable(self):
+        sys.setprofile(self.dispatcher)
+
+    def disable(self):
+        sys.setprofile(None)
+
+    def create_stats(self):
+        self.disable()
+        super().create_stats()
+
+    def trace_dispatch_return(self, frame, t):
+        # The frame that called enable(), and the ones above it, were
+        # already running when profiling started, so they return without
+        # a matching call.
+        if frame is not self.cur[-2] and not self._is_running(frame):
+            return 0
+        return super().trace_dispatch_return(frame, t)
+
+    def _is_running(self, frame):
+        cur = self.cur
+        while cur is not None:
+            if cur[-2] is frame:
+                return True
+            cur = cur[-1]
+        return False
+
+    dispatch = dict(_pyprofile.Profile.dispatch)
+    dispatch["return"] = trace_dispatch_return
+    dispatch["c_return"] = trace_dispatch_return
+    dispatch["c_exception"] = trace_dispatch_return
+
+    def __enter__(self):
+        self.enable()
+        return self
+
+    def __exit__(self, *exc_info):
+        self.disable()
+
+# ____________________________________________________________
+
+def main():
+    import os
+    import runpy
+    import pstats
+    from argparse import ArgumentParser, REMAINDER
+    parser = ArgumentParser(prog="cProfile.py")
+    parser.add_argument('-o', '--outfile', dest="outfile",
+        help="Save stats to <outfile>", default=None)
+    parser.add_argument('-s', '--sort', dest="sort",
+        help="Sort order when printing to stdout, based on pstats.Stats class",
+        default=-1,
+        choices=sorted(pstats.Stats.sort_arg_dict_default))
+    parser.add_argument('-m', dest="module", action="store_true",
+        help="Profile a library module", default=False)
+    parser.add_argument('args', nargs=REMAINDER)
+
+    options = parser.parse_args()
+    args = options.args
+    if not args:
+        parser.print_usage()
+        sys.exit(2)
+    sys.argv[:] = args
+
+    # The script that we're profiling may chdir, so capture the absolute path
+    # to the output file at startup.
+    if options.outfile is not None:
+        options.outfile = os.path.abspath(options.outfile)
+
+    if options.module:
+        code = "run_module(modname, run_name='__main__')"
+        globs = {
+            'run_module': runpy.run_module,
+            'modname': args[0]
+        }
+    else:
+        progname = args[0]
+        sys.path.insert(0, os.path.dirname(progname))
+        with open(progname, 'rb') as fp:
+            code = compile(fp.read(), progname, 'exec')
+        globs = {
+            '__file__': progname,
+            '__name__': '__main__',
+            '__package__': None,
+            '__cached__': None,
+        }
+    runctx(code, globs, None, options.outfile, options.sort)
+    return parser
+
+# When invoked as main program, invoke the profiler on a script
+if __name__ == '__main__':
+    main()
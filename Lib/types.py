@@ -19,10 +19,10 @@ def _g():
     yield 1
 GeneratorType = type(_g())
 
-# async def _c(): pass
-# _c = _c()
-# CoroutineType = type(_c)
-# _c.close()  # Prevent ResourceWarning
+async def _c(): pass
+_c = _c()
+CoroutineType = type(_c)
+_c.close()  # Prevent ResourceWarning
 
 # async def _ag():
 #     yield
@@ -42,18 +42,19 @@ MethodDescriptorType = type(str.join)
 ClassMethodDescriptorType = type(dict.__dict__['fromkeys'])
 
 ModuleType = type(sys)
+GenericAlias = type(list[int])
 
-# try:
-#     raise TypeError
-# except TypeError:
-#     tb = sys.exc_info()[2]
-#     TracebackType = type(tb)
-#     FrameType = type(tb.tb_frame)
-#     tb = None; del tb
+try:
+    raise TypeError
+except TypeError:
+    tb = sys.exc_info()[2]
+    TracebackType = type(tb)
+    FrameType = type(tb.tb_frame)
+    tb = None; del tb
 
 # For Jython, the following two types are identical
 GetSetDescriptorType = type(FunctionType.__code__)
-# MemberDescriptorType = type(FunctionType.__globals__)
+MemberDescriptorType = type(FunctionType.__globals__)
 
 del sys, _f, _g, _C  # Not for export
 
@@ -0,0 +1,528 @@
+"""Runtime support for type hints.
+
+This is a lightweight version of CPython's typing module: the names can be
+used in annotations and subscripted, producing generic aliases that remember
+their origin and arguments, but no type checking is performed.
+"""
+
+import collections
+import collections.abc
+import sys
+
+__all__ = [
+    'Any', 'Callable', 'ClassVar', 'Final', 'Generic', 'Literal', 'NoReturn',
+    'Optional', 'Tuple', 'Type', 'TypeVar', 'Union',
+    'AbstractSet', 'AsyncIterable', 'AsyncIterator', 'Awaitable',
+    'ByteString', 'Collection', 'Container', 'Coroutine', 'Generator',
+    'Hashable', 'ItemsView', 'Iterable', 'Iterator', 'KeysView', 'Mapping',
+    'MappingView', 'MutableMapping', 'MutableSequence', 'MutableSet',
+    'Reversible', 'Sequence', 'Sized', 'ValuesView',
+    'Counter', 'DefaultDict', 'Deque', 'Dict', 'FrozenSet', 'List', 'Set',
+    'AnyStr', 'ForwardRef', 'NewType', 'Text', 'TYPE_CHECKING',
+    'cast', 'get_type_hints', 'no_type_check', 'overload',
+]
+
+TYPE_CHECKING = False
+
+
+def _type_check(arg, msg):
+    """Check that arg is usable as a type argument and normalize it."""
+    if arg is None:
+        return type(None)
+    if isinstance(arg, str):
+        return ForwardRef(arg)
+    if isinstance(arg, _SpecialForm) and arg._name in ('ClassVar', 'Final'):
+        raise TypeError("%s is only valid at the top level of an annotation" % arg)
+    if not callable(arg) and not isinstance(arg, (_GenericAlias, _SpecialForm, TypeVar)):
+        raise TypeError("%s Got %.100r." % (msg, arg))
+    return arg
+
+
+def _type_repr(obj):
+    """Return the repr of a type as used in the repr of generic aliases."""
+    if isinstance(obj, type):
+        if obj.__module__ == 'builtins':
+            return obj.__qualname__
+        return '%s.%s' % (obj.__module__, obj.__qualname__)
+    if obj is ...:
+        return '...'
+    return repr(obj)
+
+
+def _collect_type_vars(types):
+    tvars = []
+    for t in types:
+        if isinstance(t, TypeVar) and t not in tvars:
+            tvars.append(t)
+        if isinstance(t, _GenericAlias) and not t._special:
+            tvars.extend(v for v in t.__parameters__ if v not in tvars)
+    return tuple(tvars)
+
+
+def _subs_tvars(tp, subst):
+    """Substitute type variables in tp using the subst mapping."""
+    if isinstance(tp, TypeVar):
+        return subst.get(tp, tp)
+    if isinstance(tp, _GenericAlias) and tp.__parameters__:
+        return tp.copy_with(tuple(_subs_tvars(arg, subst) for arg in tp.__args__))
+    return tp
+
+
+class _SpecialForm:
+    """A special typing construct such as Any, Union or Optional."""
+
+    def __init__(self, name, doc, getitem=None):
+        self._name = name
+        self.__doc__ = doc
+        self._getitem = getitem
+
+    def __repr__(self):
+        return 'typing.' + self._name
+
+    def __reduce__(self):
+        return self._name
+
+    def __call__(self, *args, **kwds):
+        raise TypeError("Cannot instantiate %r" % self)
+
+    def __instancecheck__(self, obj):
+        raise TypeError("%s cannot be used with isinstance()" % self)
+
+    def __subclasscheck__(self, cls):
+        raise TypeError("%s cannot be used with issubclass()" % self)
+
+    def __getitem__(self, parameters):
+        if self._getitem is None:
+            raise TypeError("%s is not subscriptable" % self)
+        return self._getitem(self, parameters)
+
+
+def _union_getitem(self, parameters):
+    if parameters == ():
+        raise TypeError("Cannot take a Union of no types.")
+    if not isinstance(parameters, tuple):
+        parameters = (parameters,)
+    msg = "Union[arg, ...]: each arg must be a type."
+    flat = []
+    for p in parameters:
+        p = _type_check(p, msg)
+        if isinstance(p, _GenericAlias) and p.__origin__ is Union:
+            args = p.__args__
+        else:
+            args = (p,)
+        for arg in args:
+            if arg not in flat:
+                flat.append(arg)
+    if len(flat) == 1:
+        return flat[0]
+    return _GenericAlias(Union, tuple(flat))
+
+
+def _optional_getitem(self, parameters):
+    arg = _type_check(parameters, "Optional[t] requires a single type.")
+    return Union[arg, type(None)]
+
+
+def _single_arg_getitem(self, parameters):
+    item = _type_check(parameters, "%s accepts only single type." % self._name)
+    return _GenericAlias(self, (item,))
+
+
+def _literal_getitem(self, parameters):
+    if not isinstance(parameters, tuple):
+        parameters = (parameters,)
+    return _GenericAlias(self, parameters)
+
+
+Any = _SpecialForm('Any', "Special type indicating an unconstrained type.")
+NoReturn = _SpecialForm('NoReturn', "Special type indicating functions that never return.")
+ClassVar = _SpecialForm('ClassVar', "Special type construct to mark class variables.",
+                        _single_arg_getitem)
+Final = _SpecialForm('Final', "Special typing construct to indicate final names.",
+                     _single_arg_getitem)
+Union = _SpecialForm('Union', "Union type; Union[X, Y] means either X or Y.",
+                     _union_getitem)
+Optional = _SpecialForm('Optional', "Optional type; Optional[X] is Union[X, None].",
+                        _optional_getitem)
+Literal = _SpecialForm('Literal', "Special typing form to define literal types.",
+                       _literal_getitem)
+
+
+class ForwardRef:
+    """A forward reference, written as a string in an annotation."""
+
+    def __init__(self, arg):
+        if not isinstance(arg, str):
+            raise TypeError("Forward reference must be a string -- got %r" % (arg,))
+        self.__forward_arg__ = arg
+
+    def _evaluate(self, globalns, localns):
+        return eval(self.__forward_arg__, globalns, localns)
+
+    def __eq__(self, other):
+        if not isinstance(other, ForwardRef):
+            return NotImplemented
+        return self.__forward_arg__ == other.__forward_arg__
+
+    def __hash__(self):
+        return hash(self.__forward_arg__)
+
+    def __repr__(self):
+        return 'ForwardRef(%r)' % (self.__forward_arg__,)
+
+
+class TypeVar:
+    """Type variable, used as a parameter of generic types and functions.
+
+        T = TypeVar('T')
+        A = TypeVar('A', str, bytes)
+    """
+
+    def __init__(self, name, *constraints, bound=None,
+                 covariant=False, contravariant=False):
+        if covariant and contravariant:
+            raise ValueError("Bivariant types are not supported.")
+        if len(constraints) == 1:
+            raise TypeError("A single constraint is not allowed")
+        msg = "TypeVar(name, constraint, ...): constraints must be types."
+        self.__name__ = name
+        self.__constraints__ = tuple(_type_check(t, msg) for t in constraints)
+        self.__bound__ = _type_check(bound, "Bound must be a type.") if bound else None
+        self.__covariant__ = bool(covariant)
+        self.__contravariant__ = bool(contravariant)
+
+    def __repr__(self):
+        if self.__covariant__:
+            prefix = '+'
+        elif self.__contravariant__:
+            prefix = '-'
+        else:
+            prefix = '~'
+        return prefix + self.__name__
+
+
+class _GenericAlias:
+    """The result of subscripting a generic type, e.g. List[int].
+
+    With special=True it stands for the unsubscripted generic version of a
+    builtin or abstract class, e.g. List itself.
+    """
+
+    def __init__(self, origin, params, *, name=None, special=False, nparams=-1):
+        if not isinstance(params, tuple):
+            params = (params,)
+        self.__origin__ = origin
+        self.__args__ = params
+        self.__parameters__ = _collect_type_vars(params)
+        self._name = name
+        self._special = special
+        self._nparams = nparams
+
+    def copy_with(self, params):
+        return _GenericAlias(self.__origin__, params, name=self._name)
+
+    def __getitem__(self, params):
+        if self.__origin__ in (Generic, Union):
+            raise TypeError("Cannot subscript already-subscripted %s" % self)
+        if not isinstance(params, tuple):
+            params = (params,)
+        msg = "Parameters to generic types must be types."
+        params = tuple(_type_check(p, msg) for p in params)
+        if self._special:
+            if self._nparams >= 0 and len(params) != self._nparams:
+                raise TypeError("Too %s parameters for %s; actual %d, expected %d"
+                                % ("many" if len(params) > self._nparams else "few",
+                                   self, len(params), self._nparams))
+            return self.copy_with(params)
+        if not self.__parameters__:
+            raise TypeError("%s is not a generic class" % self)
+        if len(params) != len(self.__parameters__):
+            raise TypeError("Too %s parameters for %s; actual %d, expected %d"
+                            % ("many" if len(params) > len(self.__parameters__) else "few",
+                               self, len(params), len(self.__parameters__)))
+        subst = dict(zip(self.__parameters__, params))
+        return _subs_tvars(self, subst)
+
+    def __repr__(self):
+        if self._name:
+            name = 'typing.' + self._name
+        else:
+            name = _type_repr(self.__origin__)
+        if self._special:
+            return name
+        return '%s[%s]' % (name, ', '.join(_type_repr(a) for a in self.__args__))
+
+    def __eq__(self, other):
+        if not isinstance(other, _GenericAlias):
+            return NotImplemented
+        if self.__origin__ is Union and other.__origin__ is Union:
+            return set(self.__args__) == set(other.__args__)
+        return (self.__origin__ == other.__origin__
+                and self.__args__ == other.__args__
+                and self._special == other._special)
+
+    def __hash__(self):
+        if self.__origin__ is Union:
+            return hash((Union, frozenset(self.__args__)))
+        return hash((self.__origin__, self.__args__))
+
+    def __call__(self, *args, **kwargs):
+        if self._special or self.__origin__ is Union:
+            raise TypeError("Type %s cannot be instantiated; use %s() instead"
+                            % (self._name or self, self.__origin__.__name__.lower()))
+        result = self.__origin__(*args, **kwargs)
+        try:
+            result.__orig_class__ = self
+        except AttributeError:
+            pass
+        return result
+
+    def __mro_entries__(self, bases):
+        if self.__origin__ is Generic:
+            # Only add Generic once, and not at all if another base
+            # already derives from it.
+            index = bases.index(self)
+            for base in bases[index + 1:]:
+                if isinstance(base, _GenericAlias) or issubclass(base, Generic):
+                    return ()
+        return (self.__origin__,)
+
+    def __getattr__(self, attr):
+        # Attributes like __name__ and __qualname__ are looked up on the origin.
+        if '__origin__' in self.__dict__ and not attr.startswith('_abc_'):
+            return getattr(self.__origin__, attr)
+        raise AttributeError(attr)
+
+    def __instancecheck__(self, obj):
+        return self.__subclasscheck__(type(obj))
+
+    def __subclasscheck__(self, cls):
+        if self._special:
+            if isinstance(cls, _GenericAlias):
+                return issubclass(cls.__origin__, self.__origin__)
+            return issubclass(cls, self.__origin__)
+        raise TypeError("Subscripted generics cannot be used with"
+                        " class and instance checks")
+
+
+class _CallableAlias(_GenericAlias):
+    """Callable[[arg, ...], result] stores its arguments flattened."""
+
+    def copy_with(self, params):
+        return _CallableAlias(self.__origin__, params, name=self._name)
+
+    def __getitem__(self, params):
+        if not self._special:
+            return super().__getitem__(params)
+        if not isinstance(params, tuple) or len(params) != 2:
+            raise TypeError("Callable must be used as Callable[[arg, ...], result].")
+        args, result = params
+        if args is ...:
+            params = (..., result)
+        else:
+            if not isinstance(args, list):
+                raise TypeError("Callable[args, result]: args must be a list."
+                                " Got %.100r." % (args,))
+            params = tuple(args) + (result,)
+        msg = "Callable[[arg, ...], result]: each arg must be a type."
+        params = tuple(p if p is ... else _type_check(p, msg) for p in params)
+        return self.copy_with(params)
+
+    def __repr__(self):
+        if self._special:
+            return super().__repr__()
+        args, result = self.__args__[:-1], self.__args__[-1]
+        if args == (...,):
+            args = '...'
+        else:
+            args = '[%s]' % ', '.join(_type_repr(a) for a in args)
+        return 'typing.Callable[%s, %s]' % (args, _type_repr(result))
+
+
+class _TupleAlias(_GenericAlias):
+    """Tuple[X, Y] and Tuple[X, ...] take any number of parameters."""
+
+    def copy_with(self, params):
+        return _TupleAlias(self.__origin__, params, name=self._name)
+
+    def __getitem__(self, params):
+        if not self._special:
+            return super().__getitem__(params)
+        if params == ():
+            return self.copy_with(((),))
+        if not isinstance(params, tuple):
+            params = (params,)
+        msg = "Tuple[t0, t1, ...]: each t must be a type."
+        if len(params) == 2 and params[1] is ...:
+            return self.copy_with((_type_check(params[0], msg), ...))
+        return self.copy_with(tuple(_type_check(p, msg) for p in params))
+
+    def __repr__(self):
+        if self.__args__ == ((),):
+            return 'typing.Tuple[()]'
+        return super().__repr__()
+
+
+class Generic:
+    """Abstract base class for generic types.
+
+        class Mapping(Generic[KT, VT]):
+            def __getitem__(self, key: KT) -> VT:
+                ...
+    """
+
+    __slots__ = ()
+
+    def __new__(cls, *args, **kwds):
+        if cls is Generic:
+            raise TypeError("Type Generic cannot be instantiated; "
+                            "it can be used only as a base class")
+        return super().__new__(cls)
+
+    @classmethod
+    def __class_getitem__(cls, params):
+        if not isinstance(params, tuple):
+            params = (params,)
+        if not params:
+            raise TypeError("Parameter list to %s[...] cannot be empty" % cls.__qualname__)
+        msg = "Parameters to generic types must be types."
+        params = tuple(_type_check(p, msg) for p in params)
+        if cls is Generic:
+            if not all(isinstance(p, TypeVar) for p in params):
+                raise TypeError("Parameters to Generic[...] must all be type variables")
+            if len(set(params)) != len(params):
+                raise TypeError("Parameters to Generic[...] must all be unique")
+        return _GenericAlias(cls, params)
+
+
+def _alias(origin, nparams, name=None):
+    return _GenericAlias(origin, (), name=name or origin.__name__,
+                         special=True, nparams=nparams)
+
+
+Hashable = _alias(collections.abc.Hashable, 0)
+Awaitable = _alias(collections.abc.Awaitable, 1)
+Coroutine = _alias(collections.abc.Coroutine, 3)
+AsyncIterable = _alias(collections.abc.AsyncIterable, 1)
+AsyncIterator = _alias(collections.abc.AsyncIterator, 1)
+Iterable = _alias(collections.abc.Iterable, 1)
+Iterator = _alias(collections.abc.Iterator, 1)
+Reversible = _alias(collections.abc.Reversible, 1)
+Sized = _alias(collections.abc.Sized, 0)
+Container = _alias(collections.abc.Container, 1)
+Collection = _alias(collections.abc.Collection, 1)
+AbstractSet = _alias(collections.abc.Set, 1, name='AbstractSet')
+MutableSet = _alias(collections.abc.MutableSet, 1)
+Mapping = _alias(collections.abc.Mapping, 2)
+MutableMapping = _alias(collections.abc.MutableMapping, 2)
+Sequence = _alias(collections.abc.Sequence, 1)
+MutableSequence = _alias(collections.abc.MutableSequence, 1)
+ByteString = _alias(collections.abc.ByteString, 0)
+MappingView = _alias(collections.abc.MappingView, 1)
+KeysView = _alias(collections.abc.KeysView, 1)
+ItemsView = _alias(collections.abc.ItemsView, 2)
+ValuesView = _alias(collections.abc.ValuesView, 1)
+Generator = _alias(collections.abc.Generator, 3)
+List = _alias(list, 1, name='List')
+Dict = _alias(dict, 2, name='Dict')
+Set = _alias(set, 1, name='Set')
+FrozenSet = _alias(frozenset, 1, name='FrozenSet')
+Type = _alias(type, 1, name='Type')
+Deque = _alias(collections.deque, 1, name='Deque')
+DefaultDict = _alias(collections.defaultdict, 2, name='DefaultDict')
+Counter = _alias(collections.Counter, 1, name='Counter')
+Callable = _CallableAlias(collections.abc.Callable, (), name='Callable', special=True)
+Tuple = _TupleAlias(tuple, (), name='Tuple', special=True)
+
+AnyStr = TypeVar('AnyStr', bytes, str)
+Text = str
+
+T = TypeVar('T')
+KT = TypeVar('KT')
+VT = TypeVar('VT')
+T_co = TypeVar('T_co', covariant=True)
+V_co = TypeVar('V_co', covariant=True)
+VT_co = TypeVar('VT_co', covariant=True)
+T_contra = TypeVar('T_contra', contravariant=True)
+CT_co = TypeVar('CT_co', covariant=True, bound=type)
+
+
+def cast(typ, val):
+    """Cast a value to a type.
+
+    This returns the value unchanged. To the type checker this signals that
+    the return value has the designated type.
+    """
+    return val
+
+
+def overload(func):
+    """Decorator for overloaded functions and methods.
+
+    The overloaded definitions are for type checkers only; calling one raises
+    NotImplementedError.
+    """
+    def _overload_dummy(*args, **kwds):
+        raise NotImplementedError(
+            "You should not call an overloaded function. "
+            "A series of @overload-decorated functions "
+            "outside a stub module should always be followed "
+            "by an implementation that is not @overload-ed.")
+    return _overload_dummy
+
+
+def no_type_check(arg):
+    """Decorator to indicate that annotations are not type hints."""
+    try:
+        arg.__no_type_check__ = True
+    except TypeError:
+        pass
+    return arg
+
+
+def NewType(name, tp):
+    """Create a distinct type for type checkers; at runtime it returns
+    its argument unchanged.
+    """
+    def new_type(x):
+        return x
+
+    new_type.__name__ = name
+    new_type.__supertype__ = tp
+    return new_type
+
+
+def _eval_type(t, globalns, localns):
+    if isinstance(t, str):
+        t = ForwardRef(t)
+    if isinstance(t, ForwardRef):
+        return t._evaluate(globalns, localns)
+    if t is None:
+        return type(None)
+    return t
+
+
+def get_type_hints(obj, globalns=None, localns=None):
+    """Return the type hints of a module, class, method or function.
+
+    Forward references given as strings are evaluated, and None is replaced
+    by type(None).
+    """
+    if getattr(obj, '__no_type_check__', None):
+        return {}
+    if isinstance(obj, type):
+        hints = {}
+        for base in reversed(obj.__mro__):
+            if globalns is None:
+                base_globals = sys.modules[base.__module__].__dict__
+            else:
+                base_globals = globalns
+            ann = base.__dict__.get('__annotations__', {})
+            for name, value in ann.items():
+                hints[name] = _eval_type(value, base_globals, localns)
+        return hints
+    if globalns is None:
+        globalns = getattr(obj, '__globals__', {})
+    ann = getattr(obj, '__annotations__', None) or {}
+    return {name: _eval_type(value, globalns, localns)
+            for name, value in ann.items()}
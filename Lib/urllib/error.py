@@ -0,0 +1,60 @@
+"""Exception classes raised by urllib.
+
+URLError is raised for problems reaching the server, e.g. an unknown url
+type or a failed connection.  HTTPError is raised when the server answers
+with an error status; it also behaves like a response, so the error page
+can be read.
+"""
+
+__all__ = ['URLError', 'HTTPError']
+
+
+class URLError(OSError):
+    def __init__(self, reason, filename=None):
+        OSError.__init__(self, reason)
+        self.reason = reason
+        if filename is not None:
+            self.filename = filename
+
+    def __str__(self):
+        return '<urlopen error %s>' % self.reason
+
+
+class HTTPError(URLError):
+    """Raised when the server responds with an HTTP error status."""
+
+    def __init__(self, url, code, msg, hdrs, fp):
+        BaseException.__init__(self, url, code, msg, hdrs, fp)
+        self.code = code
+        self.msg = msg
+        self.hdrs = hdrs
+        self.fp = fp
+        self.filename = self.url = url
+
+    def __str__(self):
+        return 'HTTP Error %s: %s' % (self.code, self.msg)
+
+    def __repr__(self):
+        return '<HTTPError %s: %r>' % (self.code, self.msg)
+
+    @property
+    def reason(self):
+        return self.msg
+
+    @property
+    def headers(self):
+        return self.hdrs
+
+    def getcode(self):
+        return self.code
+
+    def geturl(self):
+        return self.url
+
+    def info(self):
+        return self.hdrs
+
+    def read(self, amt=None):
+        if self.fp is None:
+            return b''
+        return self.fp.read(amt)
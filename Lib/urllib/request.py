@@ -0,0 +1,310 @@
+"""An extensible library for opening URLs.
+
+urlopen(url) performs an HTTP GET request and returns a response object
+with read(), getcode(), geturl() and headers.  Redirects are followed
+automatically.  https:// URLs are verified against the default trust store.
+
+Only GET requests are supported so far.
+"""
+
+import socket
+import sys
+
+from urllib.error import URLError, HTTPError
+
+__all__ = ['urlopen', 'HTTPMessage', 'HTTPResponse', 'URLError', 'HTTPError']
+
+__version__ = '%d.%d' % sys.version_info[:2]
+
+_MAXREDIRECTIONS = 10
+_REDIRECT_CODES = (301, 302, 303, 307, 308)
+_DEFAULT_PORTS = {'http': 80, 'https': 443}
+_GLOBAL_DEFAULT_TIMEOUT = object()
+
+
+class HTTPMessage:
+    """The headers of a response, looked up case-insensitively."""
+
+    def __init__(self, headers=()):
+        self._headers = list(headers)
+
+    def get(self, name, failobj=None):
+        name = name.lower()
+        for key, value in self._headers:
+            if key.lower() == name:
+                return value
+        return failobj
+
+    def get_all(self, name, failobj=None):
+        name = name.lower()
+        values = [value for key, value in self._headers if key.lower() == name]
+        return values or failobj
+
+    def __getitem__(self, name):
+        return self.get(name)
+
+    def __contains__(self, name):
+        return self.get(name) is not None
+
+    def __iter__(self):
+        return iter(self.keys())
+
+    def __len__(self):
+        return len(self._headers)
+
+    def keys(self):
+        return [key for key, value in self._headers]
+
+    def values(self):
+        return [value for key, value in self._headers]
+
+    def items(self):
+        return list(self._headers)
+
+    def __str__(self):
+        return ''.join('%s: %s\n' % item for item in self._headers) + '\n'
+
+
+class HTTPResponse:
+    """A fully received response to an HTTP request."""
+
+    def __init__(self, url, status, reason, headers, body):
+        self.url = url
+        self.status = status
+        self.reason = reason
+        self.headers = self.msg = headers
+        self._body = body
+        self._pos = 0
+        self.closed = False
+
+    def read(self, amt=None):
+        if amt is None or amt < 0:
+            end = len(self._body)
+        else:
+            end = min(self._pos + amt, len(self._body))
+        data = self._body[self._pos:end]
+        self._pos = end
+        return data
+
+    def readline(self):
+        end = self._body.find(b'\n', self._pos)
+        end = len(self._body) if end < 0 else end + 1
+        data = self._body[self._pos:end]
+        self._pos = end
+        return data
+
+    def readlines(self):
+        return list(self)
+
+    def __iter__(self):
+        while True:
+            line = self.readline()
+            if not line:
+                return
+            yield line
+
+    def getcode(self):
+        return self.status
+
+    @property
+    def code(self):
+        return self.status
+
+    def geturl(self):
+        return self.url
+
+    def info(self):
+        return self.headers
+
+    def getheader(self, name, default=None):
+        return self.headers.get(name, default)
+
+    def getheaders(self):
+        return self.headers.items()
+
+    def close(self):
+        self.closed = True
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc_info):
+        self.close()
+
+    def __repr__(self):
+        return '<HTTPResponse %s %s>' % (self.status, self.reason)
+
+
+def _split_url(url):
+    """Split an absolute URL into (scheme, host, port, selector)."""
+    scheme, sep, rest = url.partition('://')
+    if not sep:
+        raise ValueError('unknown url type: %r' % url)
+    scheme = scheme.lower()
+    if scheme not in _DEFAULT_PORTS:
+        raise URLError('unknown url type: %s' % scheme)
+
+    end = len(rest)
+    for delim in '/?#':
+        index = rest.find(delim)
+        if 0 <= index < end:
+            end = index
+    netloc, selector = rest[:end], rest[end:]
+    selector = selector.partition('#')[0]
+    if not selector.startswith('/'):
+        selector = '/' + selector
+
+    hostport = netloc.rpartition('@')[2]
+    if hostport.startswith('['):
+        host, _, port = hostport[1:].partition(']')
+        port = port[1:]
+    else:
+        host, _, port = hostport.partition(':')
+    if not host:
+        raise URLError('no host given')
+    if port:
+        if not port.isdigit():
+            raise URLError("nonnumeric port: '%s'" % port)
+        port = int(port)
+    else:
+        port = _DEFAULT_PORTS[scheme]
+    return scheme, host, port, selector
+
+
+def _join_url(base, location):
+    """Resolve the target of a redirect against the URL that sent it."""
+    if '://' in location:
+        return location
+    scheme, _, rest = base.partition('://')
+    if location.startswith('//'):
+        return scheme + ':' + location
+    end = len(rest)
+    for delim in '/?#':
+        index = rest.find(delim)
+        if 0 <= index < end:
+            end = index
+    netloc, path = rest[:end], rest[end:]
+    if not location.startswith('/'):
+        path = path.partition('?')[0].partition('#')[0]
+        location = path[:path.rfind('/') + 1] + location
+        if not location.startswith('/'):
+            location = '/' + location
+    return scheme + '://' + netloc + location
+
+
+def _connect(scheme, host, port, timeout):
+    if timeout is _GLOBAL_DEFAULT_TIMEOUT:
+        sock = socket.create_connection((host, port))
+    else:
+        sock = socket.create_connection((host, port), timeout)
+    if scheme == 'https':
+        try:
+            import ssl
+        except ImportError:
+            sock.close()
+            raise URLError('unknown url type: https')
+        context = ssl.create_default_context()
+        sock = context.wrap_socket(sock, server_hostname=host)
+    return sock
+
+
+def _receive(sock):
+    chunks = []
+    while True:
+        chunk = sock.recv(8192)
+        if not chunk:
+            return b''.join(chunks)
+        chunks.append(chunk)
+
+
+def _decode_chunked(body):
+    chunks = []
+    while True:
+        line, sep, body = body.partition(b'\r\n')
+        if not sep:
+            raise URLError('incomplete chunked response')
+        size = int(line.split(b';', 1)[0].strip().decode('ascii'), 16)
+        if size == 0:
+            return b''.join(chunks)
+        chunks.append(body[:size])
+        body = body[size + 2:]
+
+
+def _parse_response(url, data):
+    while True:
+        head, sep, body = data.partition(b'\r\n\r\n')
+        if not head:
+            raise URLError('remote end closed connection without response')
+        # Header bytes are latin-1: each byte is the code point of the same value.
+        lines = ''.join(map(chr, head)).split('\r\n')
+        parts = lines[0].split(' ', 2)
+        if len(parts) < 2 or not parts[0].startswith('HTTP/') \
+                or not parts[1].isdigit():
+            raise URLError('bad status line: %r' % lines[0])
+        status = int(parts[1])
+        # informational responses are followed by the real one
+        if 100 <= status < 200:
+            data = body
+            continue
+        reason = parts[2] if len(parts) > 2 else ''
+        break
+
+    headers = []
+    for line in lines[1:]:
+        name, _, value = line.partition(':')
+        headers.append((name.strip(), value.strip()))
+    headers = HTTPMessage(headers)
+
+    if headers.get('Transfer-Encoding', '').lower() == 'chunked':
+        body = _decode_chunked(body)
+    elif headers.get('Content-Length', '').isdigit():
+        body = body[:int(headers['Content-Length'])]
+    return HTTPResponse(url, status, reason, headers, body)
+
+
+def _get(url, timeout):
+    scheme, host, port, selector = _split_url(url)
+    hostheader = '[%s]' % host if ':' in host else host
+    if port != _DEFAULT_PORTS[scheme]:
+        hostheader = '%s:%d' % (hostheader, port)
+    request = ('GET %s HTTP/1.1\r\n'
+               'Host: %s\r\n'
+               'User-Agent: Python-urllib/%s\r\n'
+               'Accept-Encoding: identity\r\n'
+               'Connection: close\r\n'
+               '\r\n') % (selector, hostheader, __version__)
+    try:
+        sock = _connect(scheme, host, port, timeout)
+        try:
+            sock.sendall(request.encode())
+            data = _receive(sock)
+        finally:
+            sock.close()
+    except URLError:
+        raise
+    except OSError as err:
+        raise URLError(err)
+    return _parse_response(url, data)
+
+
+def urlopen(url, data=None, timeout=_GLOBAL_DEFAULT_TIMEOUT):
+    """Fetch url with a GET request, following redirects.
+
+    Raises HTTPError if the server answers with an error status and
+    URLError if the server can't be reached.
+    """
+    if data is not None:
+        raise NotImplementedError('only GET requests are supported')
+    for _ in range(_MAXREDIRECTIONS + 1):
+        response = _get(url, timeout)
+        location = response.headers.get('Location')
+        if response.status in _REDIRECT_CODES and location:
+            url = _join_url(url, location.strip())
+            continue
+        if response.status >= 400:
+            raise HTTPError(url, response.status, response.reason,
+                            response.headers, response)
+        return response
+    raise HTTPError(url, response.status,
+                    'The HTTP server returned a redirect error that would '
+                    'lead to an infinite loop.', response.headers, response)
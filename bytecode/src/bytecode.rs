@@ -45,6 +45,7 @@ pub struct CodeObject {
     pub first_line_number: usize,
     pub obj_name: String, // Name of the object that created this code object
     pub is_generator: bool,
+    pub is_coroutine: bool,
 }
 
 bitflags! {
@@ -181,6 +182,7 @@ pub enum Instruction {
     ReturnValue,
     YieldValue,
     YieldFrom,
+    GetAwaitable,
     SetupLoop {
         start: Label,
         end: Label,
@@ -214,6 +216,21 @@ pub enum Instruction {
     CleanupWith {
         end: Label,
     },
+    /// Replace the async context manager on top of the stack with its bound
+    /// `__aexit__`, followed by the awaitable returned by `__aenter__`.
+    BeforeAsyncWith,
+    /// Like `SetupFinally`, but keeps the value on top of the stack outside of the block.
+    SetupAsyncWith {
+        end: Label,
+    },
+    /// Call the `__aexit__` on top of the stack with the exception being
+    /// handled by the current finally block, if any.
+    WithCleanupStart,
+    /// Swallow the exception of the current finally block if the awaited
+    /// result of `__aexit__` was true.
+    WithCleanupFinish,
+    GetAIter,
+    GetANext,
     PopBlock,
     Raise {
         argc: usize,
@@ -236,6 +253,9 @@ pub enum Instruction {
     BuildMap {
         size: usize,
         unpack: bool,
+        /// The map holds the `**` keyword arguments of a call, so a key
+        /// given twice is an error.
+        for_call: bool,
     },
     BuildSlice {
         size: usize,
@@ -251,6 +271,7 @@ pub enum Instruction {
     },
     PrintExpr,
     LoadBuildClass,
+    SetupAnnotations,
     UnpackSequence {
         size: usize,
     },
@@ -259,14 +280,34 @@ pub enum Instruction {
         after: usize,
     },
     Unpack,
+    /// Format the value below the top of the stack, using the spec on top
     FormatValue {
         conversion: Option<ConversionFlag>,
-        spec: String,
     },
     PopException,
     Reverse {
         amount: usize,
     },
+    /// Push whether the value on top of the stack is a sequence that
+    /// sequence patterns can match, which excludes `str`, `bytes` and `bytearray`.
+    MatchSequence,
+    /// Push whether the value on top of the stack is a mapping.
+    MatchMapping,
+    /// Push the length of the value on top of the stack.
+    GetLen,
+    /// Look up the tuple of keys on top of the stack in the mapping below it,
+    /// and push a tuple of the values, or None if a key is missing.
+    MatchKeys,
+    /// Replace the tuple of keys on top of the stack with a dict holding the
+    /// items of the mapping below it, except for those keys.
+    CopyDictWithoutKeys,
+    /// Pop the keyword attribute names, the class and the subject of a class
+    /// pattern, and push a tuple with the attributes to match the `count`
+    /// positional and the keyword sub-patterns against, or None if the
+    /// subject is not an instance of the class.
+    MatchClass {
+        count: usize,
+    },
 }
 
 use self::Instruction::*;
@@ -368,6 +409,7 @@ impl CodeObject {
             first_line_number,
             obj_name,
             is_generator: false,
+            is_coroutine: false,
         }
     }
 
@@ -500,6 +542,7 @@ impl Instruction {
             ReturnValue => w!(ReturnValue),
             YieldValue => w!(YieldValue),
             YieldFrom => w!(YieldFrom),
+            GetAwaitable => w!(GetAwaitable),
             SetupLoop { start, end } => w!(SetupLoop, label_map[start], label_map[end]),
             SetupExcept { handler } => w!(SetupExcept, label_map[handler]),
             SetupFinally { handler } => w!(SetupFinally, label_map[handler]),
@@ -507,25 +550,42 @@ impl Instruction {
             EndFinally => w!(EndFinally),
             SetupWith { end } => w!(SetupWith, label_map[end]),
             CleanupWith { end } => w!(CleanupWith, label_map[end]),
+            BeforeAsyncWith => w!(BeforeAsyncWith),
+            SetupAsyncWith { end } => w!(SetupAsyncWith, label_map[end]),
+            WithCleanupStart => w!(WithCleanupStart),
+            WithCleanupFinish => w!(WithCleanupFinish),
+            GetAIter => w!(GetAIter),
+            GetANext => w!(GetANext),
             PopBlock => w!(PopBlock),
             Raise { argc } => w!(Raise, argc),
             BuildString { size } => w!(BuildString, size),
             BuildTuple { size, unpack } => w!(BuildTuple, size, unpack),
             BuildList { size, unpack } => w!(BuildList, size, unpack),
             BuildSet { size, unpack } => w!(BuildSet, size, unpack),
-            BuildMap { size, unpack } => w!(BuildMap, size, unpack),
+            BuildMap {
+                size,
+                unpack,
+                for_call,
+            } => w!(BuildMap, size, unpack, for_call),
             BuildSlice { size } => w!(BuildSlice, size),
             ListAppend { i } => w!(ListAppend, i),
             SetAdd { i } => w!(SetAdd, i),
             MapAdd { i } => w!(MapAdd, i),
             PrintExpr => w!(PrintExpr),
             LoadBuildClass => w!(LoadBuildClass),
+            SetupAnnotations => w!(SetupAnnotations),
             UnpackSequence { size } => w!(UnpackSequence, size),
             UnpackEx { before, after } => w!(UnpackEx, before, after),
             Unpack => w!(Unpack),
-            FormatValue { spec, .. } => w!(FormatValue, spec), // TODO: write conversion
+            FormatValue { .. } => w!(FormatValue), // TODO: write conversion
             PopException => w!(PopException),
             Reverse { amount } => w!(Reverse, amount),
+            MatchSequence => w!(MatchSequence),
+            MatchMapping => w!(MatchMapping),
+            GetLen => w!(GetLen),
+            MatchKeys => w!(MatchKeys),
+            CopyDictWithoutKeys => w!(CopyDictWithoutKeys),
+            MatchClass { count } => w!(MatchClass, count),
         }
     }
 }
@@ -10,10 +10,12 @@ use crate::output_stream::{CodeObjectStream, OutputStream};
 use crate::peephole::PeepholeOptimizer;
 use crate::symboltable::{
     make_symbol_table, statements_to_symbol_table, Symbol, SymbolScope, SymbolTable,
+    SymbolTableType,
 };
 use num_complex::Complex64;
 use rustpython_bytecode::bytecode::{self, CallType, CodeObject, Instruction, Label, Varargs};
 use rustpython_parser::{ast, parser};
+use std::collections::HashSet;
 
 type BasicOutputStream = PeepholeOptimizer<CodeObjectStream>;
 
@@ -27,9 +29,26 @@ struct Compiler<O: OutputStream = BasicOutputStream> {
     current_qualified_path: Option<String>,
     in_loop: bool,
     in_function_def: bool,
+    in_async_func: bool,
+    in_comprehension: bool,
     optimize: u8,
 }
 
+/// State of the compilation of the pattern of a case block.
+#[derive(Default)]
+struct PatternContext {
+    /// `fail_pop[n]` pops `n` items off the stack and continues with the
+    /// next case block.
+    fail_pop: Vec<Label>,
+    /// Number of items on top of the stack to pop when the pattern fails,
+    /// not counting the subject currently being matched.
+    on_top: usize,
+    /// Names bound by the pattern so far.
+    stores: Vec<String>,
+    /// Whether a pattern that always matches is allowed here.
+    allow_irrefutable: bool,
+}
+
 /// Compile a given sourcecode into a bytecode object.
 pub fn compile(
     source: &str,
@@ -154,6 +173,8 @@ impl<O: OutputStream> Compiler<O> {
             current_qualified_path: None,
             in_loop: false,
             in_function_def: false,
+            in_async_func: false,
+            in_comprehension: false,
             optimize,
         }
     }
@@ -197,6 +218,9 @@ impl<O: OutputStream> Compiler<O> {
                 scope: bytecode::NameScope::Global,
             });
         }
+        if find_ann(statements) {
+            self.emit(Instruction::SetupAnnotations);
+        }
         self.compile_statements(statements)?;
 
         assert_eq!(self.output_stack.len(), size_before);
@@ -216,6 +240,10 @@ impl<O: OutputStream> Compiler<O> {
     ) -> Result<(), CompileError> {
         self.symbol_table_stack.push(symbol_table);
 
+        if find_ann(&program.statements) {
+            self.emit(Instruction::SetupAnnotations);
+        }
+
         let mut emitted_return = false;
 
         for (i, statement) in program.statements.iter().enumerate() {
@@ -405,13 +433,20 @@ impl<O: OutputStream> Compiler<O> {
                 self.set_label(end_label);
             }
             While { test, body, orelse } => self.compile_while(test, body, orelse)?,
+            Match { subject, cases } => self.compile_match(subject, cases)?,
             With {
                 is_async,
                 items,
                 body,
             } => {
                 if *is_async {
-                    unimplemented!("async with");
+                    if !self.in_async_func {
+                        return Err(CompileError {
+                            error: CompileErrorType::InvalidAsyncWith,
+                            location: statement.location.clone(),
+                        });
+                    }
+                    self.compile_async_with(items, body)?
                 } else {
                     let end_label = self.new_label();
                     for item in items {
@@ -442,7 +477,13 @@ impl<O: OutputStream> Compiler<O> {
                 orelse,
             } => {
                 if *is_async {
-                    unimplemented!("async for");
+                    if !self.in_async_func {
+                        return Err(CompileError {
+                            error: CompileErrorType::InvalidAsyncFor,
+                            location: statement.location.clone(),
+                        });
+                    }
+                    self.compile_async_for(target, iter, body, orelse)?
                 } else {
                     self.compile_for(target, iter, body, orelse)?
                 }
@@ -477,13 +518,7 @@ impl<O: OutputStream> Compiler<O> {
                 body,
                 decorator_list,
                 returns,
-            } => {
-                if *is_async {
-                    unimplemented!("async def");
-                } else {
-                    self.compile_function_def(name, args, body, decorator_list, returns)?
-                }
-            }
+            } => self.compile_function_def(name, args, body, decorator_list, returns, *is_async)?,
             ClassDef {
                 name,
                 body,
@@ -657,6 +692,7 @@ impl<O: OutputStream> Compiler<O> {
             self.emit(Instruction::BuildMap {
                 size: num_kw_only_defaults,
                 unpack: false,
+                for_call: false,
             });
         }
 
@@ -825,14 +861,17 @@ impl<O: OutputStream> Compiler<O> {
         args: &ast::Parameters,
         body: &[ast::Statement],
         decorator_list: &[ast::Expression],
-        returns: &Option<ast::Expression>, // TODO: use type hint somehow..
+        returns: &Option<ast::Expression>,
+        is_async: bool,
     ) -> Result<(), CompileError> {
         // Create bytecode for this function:
         // remember to restore self.in_loop to the original after the function is compiled
         let was_in_loop = self.in_loop;
         let was_in_function_def = self.in_function_def;
+        let was_in_async_func = self.in_async_func;
         self.in_loop = false;
         self.in_function_def = true;
+        self.in_async_func = is_async;
 
         let old_qualified_path = self.current_qualified_path.clone();
         let qualified_name = self.create_qualified_name(name, "");
@@ -841,6 +880,9 @@ impl<O: OutputStream> Compiler<O> {
         self.prepare_decorators(decorator_list)?;
 
         let mut flags = self.enter_function(name, args)?;
+        if is_async {
+            self.current_output().mark_coroutine();
+        }
 
         let (new_body, doc_str) = get_doc(body);
 
@@ -854,9 +896,35 @@ impl<O: OutputStream> Compiler<O> {
         let code = self.pop_code_object();
         self.leave_scope();
 
-        // Prepare type annotations:
+        // Prepare type annotations, in the order of the parameters:
         let mut num_annotations = 0;
 
+        let vararg = match &args.vararg {
+            ast::Varargs::Named(vararg) => Some(vararg),
+            _ => None,
+        };
+        let kwarg = match &args.kwarg {
+            ast::Varargs::Named(kwarg) => Some(kwarg),
+            _ => None,
+        };
+        let params = args
+            .args
+            .iter()
+            .chain(vararg)
+            .chain(args.kwonlyargs.iter())
+            .chain(kwarg);
+        for param in params {
+            if let Some(annotation) = &param.annotation {
+                self.emit(Instruction::LoadConst {
+                    value: bytecode::Constant::String {
+                        value: param.arg.to_string(),
+                    },
+                });
+                self.compile_expression(annotation)?;
+                num_annotations += 1;
+            }
+        }
+
         // Return annotation:
         if let Some(annotation) = returns {
             // key:
@@ -870,23 +938,12 @@ impl<O: OutputStream> Compiler<O> {
             num_annotations += 1;
         }
 
-        for arg in args.args.iter() {
-            if let Some(annotation) = &arg.annotation {
-                self.emit(Instruction::LoadConst {
-                    value: bytecode::Constant::String {
-                        value: arg.arg.to_string(),
-                    },
-                });
-                self.compile_expression(&annotation)?;
-                num_annotations += 1;
-            }
-        }
-
         if num_annotations > 0 {
             flags |= bytecode::FunctionOpArg::HAS_ANNOTATIONS;
             self.emit(Instruction::BuildMap {
                 size: num_annotations,
                 unpack: false,
+                for_call: false,
             });
         }
 
@@ -911,6 +968,7 @@ impl<O: OutputStream> Compiler<O> {
         self.current_qualified_path = old_qualified_path;
         self.in_loop = was_in_loop;
         self.in_function_def = was_in_function_def;
+        self.in_async_func = was_in_async_func;
         Ok(())
     }
 
@@ -923,7 +981,9 @@ impl<O: OutputStream> Compiler<O> {
         decorator_list: &[ast::Expression],
     ) -> Result<(), CompileError> {
         let was_in_loop = self.in_loop;
+        let was_in_async_func = self.in_async_func;
         self.in_loop = false;
+        self.in_async_func = false;
 
         let old_qualified_path = self.current_qualified_path.clone();
         let qualified_name = self.create_qualified_name(name, "");
@@ -953,6 +1013,9 @@ impl<O: OutputStream> Compiler<O> {
             name: "__module__".to_string(),
             scope: bytecode::NameScope::Free,
         });
+        if find_ann(new_body) {
+            self.emit(Instruction::SetupAnnotations);
+        }
         self.compile_statements(new_body)?;
         self.emit(Instruction::LoadConst {
             value: bytecode::Constant::None,
@@ -1022,6 +1085,7 @@ impl<O: OutputStream> Compiler<O> {
         self.store_name(name);
         self.current_qualified_path = old_qualified_path;
         self.in_loop = was_in_loop;
+        self.in_async_func = was_in_async_func;
         Ok(())
     }
 
@@ -1078,6 +1142,403 @@ impl<O: OutputStream> Compiler<O> {
         Ok(())
     }
 
+    fn compile_match(
+        &mut self,
+        subject: &ast::Expression,
+        cases: &[ast::MatchCase],
+    ) -> Result<(), CompileError> {
+        self.compile_expression(subject)?;
+        let end_label = self.new_label();
+        for (i, case) in cases.iter().enumerate() {
+            // Only the last case block may match anything:
+            let mut pc = PatternContext {
+                allow_irrefutable: case.guard.is_some() || i == cases.len() - 1,
+                ..Default::default()
+            };
+            // Every case block matches against its own copy of the subject:
+            self.emit(Instruction::Duplicate);
+            self.compile_pattern(&case.pattern, &mut pc)?;
+            if let Some(guard) = &case.guard {
+                let fail_label = self.pattern_fail_label(&mut pc, 0);
+                self.compile_jump_if(guard, false, fail_label)?;
+            }
+            self.emit(Instruction::Pop);
+            self.compile_statements(&case.body)?;
+            self.emit(Instruction::Jump { target: end_label });
+            self.emit_pattern_fail_pop(&pc);
+        }
+        // Nothing matched:
+        self.emit(Instruction::Pop);
+        self.set_label(end_label);
+        Ok(())
+    }
+
+    /// Compile a pattern, which pops the subject on top of the stack. When
+    /// the subject doesn't match, this jumps to `pc.fail_pop[pc.on_top]`.
+    fn compile_pattern(
+        &mut self,
+        pattern: &ast::Pattern,
+        pc: &mut PatternContext,
+    ) -> Result<(), CompileError> {
+        self.set_source_location(&pattern.location);
+        match &pattern.node {
+            ast::PatternType::MatchValue { value } => {
+                self.compile_expression(value)?;
+                self.emit(Instruction::CompareOperation {
+                    op: bytecode::ComparisonOperator::Equal,
+                });
+                self.jump_to_pattern_fail_pop(pc);
+            }
+            ast::PatternType::MatchSingleton { value } => {
+                self.compile_expression(value)?;
+                self.emit(Instruction::CompareOperation {
+                    op: bytecode::ComparisonOperator::Is,
+                });
+                self.jump_to_pattern_fail_pop(pc);
+            }
+            ast::PatternType::MatchAs { pattern, name } => match pattern {
+                Some(pattern) => {
+                    self.emit(Instruction::Duplicate);
+                    pc.on_top += 1;
+                    self.compile_subpattern(pattern, pc)?;
+                    pc.on_top -= 1;
+                    if let Some(name) = name {
+                        self.store_pattern_name(name, pc)?;
+                    }
+                }
+                None => {
+                    if !pc.allow_irrefutable {
+                        return Err(self.pattern_error(match name {
+                            Some(name) => format!(
+                                "name capture '{}' makes remaining patterns unreachable",
+                                name
+                            ),
+                            None => "wildcard makes remaining patterns unreachable".to_string(),
+                        }));
+                    }
+                    self.store_optional_pattern_name(name, pc)?;
+                }
+            },
+            ast::PatternType::MatchStar { name } => {
+                self.store_optional_pattern_name(name, pc)?;
+            }
+            ast::PatternType::MatchSequence { patterns } => {
+                self.compile_sequence_pattern(patterns, pc)?;
+            }
+            ast::PatternType::MatchMapping {
+                keys,
+                patterns,
+                rest,
+            } => {
+                self.compile_mapping_pattern(keys, patterns, rest, pc)?;
+            }
+            ast::PatternType::MatchClass {
+                cls,
+                patterns,
+                kwd_attrs,
+                kwd_patterns,
+            } => {
+                for (i, attr) in kwd_attrs.iter().enumerate() {
+                    if kwd_attrs[..i].contains(attr) {
+                        return Err(self.pattern_error(format!(
+                            "attribute name repeated in class pattern: {}",
+                            attr
+                        )));
+                    }
+                }
+                self.compile_expression(cls)?;
+                self.emit(Instruction::LoadConst {
+                    value: bytecode::Constant::Tuple {
+                        elements: kwd_attrs
+                            .iter()
+                            .map(|attr| bytecode::Constant::String {
+                                value: attr.to_string(),
+                            })
+                            .collect(),
+                    },
+                });
+                self.emit(Instruction::MatchClass {
+                    count: patterns.len(),
+                });
+                // The subject was replaced with the tuple of attributes:
+                pc.on_top += 1;
+                self.emit(Instruction::Duplicate);
+                self.emit(Instruction::LoadConst {
+                    value: bytecode::Constant::None,
+                });
+                self.emit(Instruction::CompareOperation {
+                    op: bytecode::ComparisonOperator::IsNot,
+                });
+                self.jump_to_pattern_fail_pop(pc);
+                pc.on_top -= 1;
+                let patterns: Vec<_> = patterns.iter().chain(kwd_patterns).collect();
+                self.unpack_subpatterns(&patterns, pc)?;
+            }
+            ast::PatternType::MatchOr { patterns } => {
+                self.compile_or_pattern(patterns, pc)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Compile a pattern nested in another one, where patterns that always
+    /// match are fine.
+    fn compile_subpattern(
+        &mut self,
+        pattern: &ast::Pattern,
+        pc: &mut PatternContext,
+    ) -> Result<(), CompileError> {
+        let allow_irrefutable = pc.allow_irrefutable;
+        pc.allow_irrefutable = true;
+        self.compile_pattern(pattern, pc)?;
+        pc.allow_irrefutable = allow_irrefutable;
+        Ok(())
+    }
+
+    fn compile_sequence_pattern(
+        &mut self,
+        patterns: &[ast::Pattern],
+        pc: &mut PatternContext,
+    ) -> Result<(), CompileError> {
+        let mut star = None;
+        for (i, pattern) in patterns.iter().enumerate() {
+            if let ast::PatternType::MatchStar { .. } = pattern.node {
+                if star.is_some() {
+                    return Err(self
+                        .pattern_error("multiple starred names in sequence pattern".to_string()));
+                }
+                star = Some(i);
+            }
+        }
+
+        // Keep the subject on the stack for the type and length checks:
+        pc.on_top += 1;
+        self.emit(Instruction::MatchSequence);
+        self.jump_to_pattern_fail_pop(pc);
+        let (op, size) = match star {
+            None => (bytecode::ComparisonOperator::Equal, patterns.len()),
+            Some(_) => (
+                bytecode::ComparisonOperator::GreaterOrEqual,
+                patterns.len() - 1,
+            ),
+        };
+        if star.is_none() || size > 0 {
+            self.emit(Instruction::GetLen);
+            self.emit(Instruction::LoadConst {
+                value: bytecode::Constant::Integer { value: size.into() },
+            });
+            self.emit(Instruction::CompareOperation { op });
+            self.jump_to_pattern_fail_pop(pc);
+        }
+        pc.on_top -= 1;
+
+        match star {
+            None => self.emit(Instruction::UnpackSequence { size }),
+            Some(i) => self.emit(Instruction::UnpackEx {
+                before: i,
+                after: size - i,
+            }),
+        }
+        // UnpackSequence pushed a new subject for every sub-pattern:
+        pc.on_top += patterns.len();
+        for pattern in patterns {
+            pc.on_top -= 1;
+            self.compile_subpattern(pattern, pc)?;
+        }
+        Ok(())
+    }
+
+    fn compile_mapping_pattern(
+        &mut self,
+        keys: &[ast::Expression],
+        patterns: &[ast::Pattern],
+        rest: &Option<String>,
+        pc: &mut PatternContext,
+    ) -> Result<(), CompileError> {
+        // Keep the subject on the stack until we're done with it:
+        pc.on_top += 1;
+        self.emit(Instruction::MatchMapping);
+        self.jump_to_pattern_fail_pop(pc);
+        if keys.is_empty() && rest.is_none() {
+            pc.on_top -= 1;
+            self.emit(Instruction::Pop);
+            return Ok(());
+        }
+        if !keys.is_empty() {
+            self.emit(Instruction::GetLen);
+            self.emit(Instruction::LoadConst {
+                value: bytecode::Constant::Integer {
+                    value: keys.len().into(),
+                },
+            });
+            self.emit(Instruction::CompareOperation {
+                op: bytecode::ComparisonOperator::GreaterOrEqual,
+            });
+            self.jump_to_pattern_fail_pop(pc);
+        }
+
+        for key in keys {
+            self.compile_expression(key)?;
+        }
+        self.emit(Instruction::BuildTuple {
+            size: keys.len(),
+            unpack: false,
+        });
+        pc.on_top += 1;
+        if !keys.is_empty() {
+            self.emit(Instruction::MatchKeys);
+            pc.on_top += 1;
+            self.emit(Instruction::Duplicate);
+            self.emit(Instruction::LoadConst {
+                value: bytecode::Constant::None,
+            });
+            self.emit(Instruction::CompareOperation {
+                op: bytecode::ComparisonOperator::IsNot,
+            });
+            self.jump_to_pattern_fail_pop(pc);
+            pc.on_top -= 1;
+            let patterns: Vec<_> = patterns.iter().collect();
+            self.unpack_subpatterns(&patterns, pc)?;
+        }
+
+        // Only the subject and the keys are left:
+        match rest {
+            Some(name) => {
+                self.emit(Instruction::CopyDictWithoutKeys);
+                pc.on_top -= 1;
+                self.store_pattern_name(name, pc)?;
+            }
+            None => {
+                pc.on_top -= 1;
+                self.emit(Instruction::Pop);
+            }
+        }
+        pc.on_top -= 1;
+        self.emit(Instruction::Pop);
+        Ok(())
+    }
+
+    /// Match the items of the tuple on top of the stack against `patterns`.
+    fn unpack_subpatterns(
+        &mut self,
+        patterns: &[&ast::Pattern],
+        pc: &mut PatternContext,
+    ) -> Result<(), CompileError> {
+        self.emit(Instruction::UnpackSequence {
+            size: patterns.len(),
+        });
+        pc.on_top += patterns.len();
+        for pattern in patterns {
+            pc.on_top -= 1;
+            self.compile_subpattern(pattern, pc)?;
+        }
+        Ok(())
+    }
+
+    fn compile_or_pattern(
+        &mut self,
+        patterns: &[ast::Pattern],
+        pc: &mut PatternContext,
+    ) -> Result<(), CompileError> {
+        let end_label = self.new_label();
+        let outer = std::mem::replace(pc, Default::default());
+        let mut bound_names: Option<Vec<String>> = None;
+        for (i, pattern) in patterns.iter().enumerate() {
+            // Every alternative matches against a copy of the subject, and
+            // goes on with the next one when it fails:
+            *pc = PatternContext {
+                stores: outer.stores.clone(),
+                allow_irrefutable: outer.allow_irrefutable && i == patterns.len() - 1,
+                ..Default::default()
+            };
+            self.emit(Instruction::Duplicate);
+            self.compile_pattern(pattern, pc)?;
+            let mut names = pc.stores[outer.stores.len()..].to_vec();
+            names.sort();
+            match &bound_names {
+                Some(bound_names) if *bound_names != names => {
+                    return Err(
+                        self.pattern_error("alternative patterns bind different names".to_string())
+                    );
+                }
+                _ => bound_names = Some(names),
+            }
+            self.emit(Instruction::Pop);
+            self.emit(Instruction::Jump { target: end_label });
+            self.emit_pattern_fail_pop(pc);
+        }
+        let stores = std::mem::replace(&mut pc.stores, vec![]);
+        *pc = outer;
+        pc.stores = stores;
+
+        // None of the alternatives matched, fail with the subject on the stack:
+        let fail_label = self.pattern_fail_label(pc, pc.on_top + 1);
+        self.emit(Instruction::Jump { target: fail_label });
+        self.set_label(end_label);
+        Ok(())
+    }
+
+    fn store_pattern_name(
+        &mut self,
+        name: &str,
+        pc: &mut PatternContext,
+    ) -> Result<(), CompileError> {
+        if pc.stores.iter().any(|stored| stored == name) {
+            return Err(self.pattern_error(format!(
+                "multiple assignments to name '{}' in pattern",
+                name
+            )));
+        }
+        pc.stores.push(name.to_string());
+        self.store_name(name);
+        Ok(())
+    }
+
+    /// Bind the subject to `name`, or drop it for a wildcard.
+    fn store_optional_pattern_name(
+        &mut self,
+        name: &Option<String>,
+        pc: &mut PatternContext,
+    ) -> Result<(), CompileError> {
+        match name {
+            Some(name) => self.store_pattern_name(name, pc),
+            None => {
+                self.emit(Instruction::Pop);
+                Ok(())
+            }
+        }
+    }
+
+    fn pattern_fail_label(&mut self, pc: &mut PatternContext, pops: usize) -> Label {
+        while pc.fail_pop.len() <= pops {
+            let label = self.new_label();
+            pc.fail_pop.push(label);
+        }
+        pc.fail_pop[pops]
+    }
+
+    /// Pop the boolean on top of the stack, and fail the pattern if it is false.
+    fn jump_to_pattern_fail_pop(&mut self, pc: &mut PatternContext) {
+        let target = self.pattern_fail_label(pc, pc.on_top);
+        self.emit(Instruction::JumpIfFalse { target });
+    }
+
+    fn emit_pattern_fail_pop(&mut self, pc: &PatternContext) {
+        for (i, label) in pc.fail_pop.iter().enumerate().rev() {
+            self.set_label(*label);
+            if i > 0 {
+                self.emit(Instruction::Pop);
+            }
+        }
+    }
+
+    fn pattern_error(&self, message: String) -> CompileError {
+        CompileError {
+            error: CompileErrorType::SyntaxError(message),
+            location: self.current_source_location.clone(),
+        }
+    }
+
     fn compile_for(
         &mut self,
         target: &ast::Expression,
@@ -1123,6 +1584,119 @@ impl<O: OutputStream> Compiler<O> {
         Ok(())
     }
 
+    fn compile_async_for(
+        &mut self,
+        target: &ast::Expression,
+        iter: &ast::Expression,
+        body: &[ast::Statement],
+        orelse: &Option<Vec<ast::Statement>>,
+    ) -> Result<(), CompileError> {
+        let start_label = self.new_label();
+        let handler_label = self.new_label();
+        let reraise_label = self.new_label();
+        let end_label = self.new_label();
+        self.emit(Instruction::SetupLoop {
+            start: start_label,
+            end: end_label,
+        });
+
+        self.compile_expression(iter)?;
+        self.emit(Instruction::GetAIter);
+
+        // Await the next value, StopAsyncIteration ends the loop:
+        self.set_label(start_label);
+        self.emit(Instruction::SetupExcept {
+            handler: handler_label,
+        });
+        self.emit(Instruction::GetANext);
+        self.emit_await();
+        self.compile_store(target)?;
+        self.emit(Instruction::PopBlock);
+
+        let was_in_loop = self.in_loop;
+        self.in_loop = true;
+        self.compile_statements(body)?;
+        self.in_loop = was_in_loop;
+
+        self.emit(Instruction::Jump {
+            target: start_label,
+        });
+
+        // Exception is on top of stack now
+        self.set_label(handler_label);
+        self.emit(Instruction::Duplicate);
+        self.emit(Instruction::LoadName {
+            name: String::from("isinstance"),
+            scope: bytecode::NameScope::Global,
+        });
+        self.emit(Instruction::Rotate { amount: 2 });
+        self.emit(Instruction::LoadName {
+            name: String::from("StopAsyncIteration"),
+            scope: bytecode::NameScope::Global,
+        });
+        self.emit(Instruction::CallFunction {
+            typ: CallType::Positional(2),
+        });
+        self.emit(Instruction::JumpIfFalse {
+            target: reraise_label,
+        });
+        // Drop the exception and the iterator:
+        self.emit(Instruction::Pop);
+        self.emit(Instruction::PopException);
+        self.emit(Instruction::Pop);
+        self.emit(Instruction::PopBlock);
+        if let Some(orelse) = orelse {
+            self.compile_statements(orelse)?;
+        }
+        self.emit(Instruction::Jump { target: end_label });
+
+        self.set_label(reraise_label);
+        self.emit(Instruction::Raise { argc: 0 });
+
+        self.set_label(end_label);
+        Ok(())
+    }
+
+    fn compile_async_with(
+        &mut self,
+        items: &[ast::WithItem],
+        body: &[ast::Statement],
+    ) -> Result<(), CompileError> {
+        // async with a, b: is the same as async with a: async with b:
+        let (item, rest) = items.split_first().unwrap();
+        let cleanup_label = self.new_label();
+
+        self.compile_expression(&item.context_expr)?;
+        self.emit(Instruction::BeforeAsyncWith);
+        self.emit_await();
+        self.emit(Instruction::SetupAsyncWith { end: cleanup_label });
+        match &item.optional_vars {
+            Some(var) => {
+                self.compile_store(var)?;
+            }
+            None => {
+                self.emit(Instruction::Pop);
+            }
+        }
+
+        if rest.is_empty() {
+            self.compile_statements(body)?;
+        } else {
+            self.compile_async_with(rest, body)?;
+        }
+
+        self.emit(Instruction::PopBlock);
+        self.emit(Instruction::EnterFinally);
+
+        // __aexit__ is awaited whether or not the block raised:
+        self.set_label(cleanup_label);
+        self.emit(Instruction::WithCleanupStart);
+        self.emit_await();
+        self.emit(Instruction::WithCleanupFinish);
+        self.emit(Instruction::EndFinally);
+        Ok(())
+    }
+
     fn compile_chained_comparison(
         &mut self,
         vals: &[ast::Expression],
@@ -1204,6 +1778,11 @@ impl<O: OutputStream> Compiler<O> {
             self.compile_store(target)?;
         }
 
+        // Annotations of local variables are neither evaluated nor kept.
+        if self.symbol_table_stack.last().unwrap().typ == SymbolTableType::Function {
+            return Ok(());
+        }
+
         // Compile annotation:
         self.compile_expression(annotation)?;
 
@@ -1514,6 +2093,7 @@ impl<O: OutputStream> Compiler<O> {
                             self.emit(Instruction::BuildMap {
                                 size: 1,
                                 unpack: false,
+                                for_call: false,
                             });
                         }
                     } else {
@@ -1524,6 +2104,7 @@ impl<O: OutputStream> Compiler<O> {
                 self.emit(Instruction::BuildMap {
                     size,
                     unpack: has_double_star,
+                    for_call: false,
                 });
             }
             Slice { elements } => {
@@ -1540,6 +2121,12 @@ impl<O: OutputStream> Compiler<O> {
                         location: self.current_source_location.clone(),
                     });
                 }
+                if self.in_async_func {
+                    return Err(CompileError {
+                        error: CompileErrorType::AsyncYield,
+                        location: self.current_source_location.clone(),
+                    });
+                }
                 self.mark_generator();
                 match value {
                     Some(expression) => self.compile_expression(expression)?,
@@ -1549,10 +2136,29 @@ impl<O: OutputStream> Compiler<O> {
                 };
                 self.emit(Instruction::YieldValue);
             }
-            Await { .. } => {
-                unimplemented!("await");
+            Await { value } => {
+                if !self.in_async_func {
+                    return Err(CompileError {
+                        error: CompileErrorType::InvalidAwait,
+                        location: self.current_source_location.clone(),
+                    });
+                }
+                if self.in_comprehension {
+                    return Err(CompileError {
+                        error: CompileErrorType::AwaitInComprehension,
+                        location: self.current_source_location.clone(),
+                    });
+                }
+                self.compile_expression(value)?;
+                self.emit_await();
             }
             YieldFrom { value } => {
+                if self.in_async_func {
+                    return Err(CompileError {
+                        error: CompileErrorType::AsyncYieldFrom,
+                        location: self.current_source_location.clone(),
+                    });
+                }
                 self.mark_generator();
                 self.compile_expression(value)?;
                 self.emit(Instruction::GetIter);
@@ -1592,13 +2198,25 @@ impl<O: OutputStream> Compiler<O> {
                 });
             }
             Identifier { name } => {
-                self.load_name(name);
+                if name == "__debug__" {
+                    // __debug__ is a constant that reflects the optimization level
+                    self.emit(Instruction::LoadConst {
+                        value: bytecode::Constant::Boolean {
+                            value: self.optimize == 0,
+                        },
+                    });
+                } else {
+                    self.load_name(name);
+                }
             }
             Lambda { args, body } => {
                 let name = "<lambda>".to_string();
                 // no need to worry about the self.loop_depth because there are no loops in lambda expressions
+                let was_in_async_func = self.in_async_func;
+                self.in_async_func = false;
                 let flags = self.enter_function(&name, args)?;
                 self.compile_expression(body)?;
+                self.in_async_func = was_in_async_func;
                 self.emit(Instruction::ReturnValue);
                 let code = self.pop_code_object();
                 self.leave_scope();
@@ -1647,6 +2265,19 @@ impl<O: OutputStream> Compiler<O> {
         self.compile_expression(function)?;
         let count = args.len() + keywords.len();
 
+        let mut keyword_names = HashSet::new();
+        for name in keywords.iter().filter_map(|keyword| keyword.name.as_ref()) {
+            if !keyword_names.insert(name) {
+                return Err(CompileError {
+                    error: CompileErrorType::SyntaxError(format!(
+                        "keyword argument repeated: {}",
+                        name
+                    )),
+                    location: self.current_source_location.clone(),
+                });
+            }
+        }
+
         // Normal arguments:
         let must_unpack = self.gather_elements(args)?;
         let has_double_star = keywords.iter().any(|k| k.name.is_none());
@@ -1672,6 +2303,7 @@ impl<O: OutputStream> Compiler<O> {
                             self.emit(Instruction::BuildMap {
                                 size: 1,
                                 unpack: false,
+                                for_call: false,
                             });
                         }
                     } else {
@@ -1683,6 +2315,7 @@ impl<O: OutputStream> Compiler<O> {
                 self.emit(Instruction::BuildMap {
                     size: keywords.len(),
                     unpack: has_double_star,
+                    for_call: true,
                 });
 
                 self.emit(Instruction::CallFunction {
@@ -1784,6 +2417,10 @@ impl<O: OutputStream> Compiler<O> {
         ));
         self.enter_scope();
 
+        // The comprehension runs as a plain function, so it can't await:
+        let was_in_comprehension = self.in_comprehension;
+        self.in_comprehension = true;
+
         // Create empty object of proper type:
         match kind {
             ast::ComprehensionKind::GeneratorExpression { .. } => {}
@@ -1803,6 +2440,7 @@ impl<O: OutputStream> Compiler<O> {
                 self.emit(Instruction::BuildMap {
                     size: 0,
                     unpack: false,
+                    for_call: false,
                 });
             }
         }
@@ -1866,8 +2504,9 @@ impl<O: OutputStream> Compiler<O> {
                 });
             }
             ast::ComprehensionKind::Dict { key, value } => {
-                self.compile_expression(value)?;
+                // the key is evaluated before the value
                 self.compile_expression(key)?;
+                self.compile_expression(value)?;
 
                 self.emit(Instruction::MapAdd {
                     i: 1 + generators.len(),
@@ -1888,6 +2527,7 @@ impl<O: OutputStream> Compiler<O> {
 
         // Return freshly filled list:
         self.emit(Instruction::ReturnValue);
+        self.in_comprehension = was_in_comprehension;
 
         // Fetch code for listcomp function:
         let code = self.pop_code_object();
@@ -1951,9 +2591,16 @@ impl<O: OutputStream> Compiler<O> {
                     spec,
                 } => {
                     self.compile_expression(value)?;
+                    match spec {
+                        Some(spec) => self.compile_string(spec)?,
+                        None => self.emit(Instruction::LoadConst {
+                            value: bytecode::Constant::String {
+                                value: String::new(),
+                            },
+                        }),
+                    }
                     self.emit(Instruction::FormatValue {
                         conversion: conversion.map(compile_conversion_flag),
-                        spec: spec.clone(),
                     });
                 }
             }
@@ -2029,6 +2676,15 @@ impl<O: OutputStream> Compiler<O> {
         }
     }
 
+    /// Await the object on top of the stack.
+    fn emit_await(&mut self) {
+        self.emit(Instruction::GetAwaitable);
+        self.emit(Instruction::LoadConst {
+            value: bytecode::Constant::None,
+        });
+        self.emit(Instruction::YieldFrom);
+    }
+
     fn mark_generator(&mut self) {
         self.current_output().mark_generator();
     }
@@ -2047,6 +2703,33 @@ fn get_doc(body: &[ast::Statement]) -> (&[ast::Statement], Option<String>) {
     (body, None)
 }
 
+/// Whether a body has annotated assignments, looking into compound statements
+/// but not into nested functions and classes.
+fn find_ann(body: &[ast::Statement]) -> bool {
+    use ast::StatementType::*;
+
+    body.iter().any(|statement| match &statement.node {
+        AnnAssign { .. } => true,
+        If { body, orelse, .. } | While { body, orelse, .. } | For { body, orelse, .. } => {
+            find_ann(body) || orelse.iter().any(|orelse| find_ann(orelse))
+        }
+        With { body, .. } => find_ann(body),
+        Try {
+            body,
+            handlers,
+            orelse,
+            finalbody,
+        } => {
+            find_ann(body)
+                || handlers.iter().any(|handler| find_ann(&handler.body))
+                || orelse.iter().any(|orelse| find_ann(orelse))
+                || finalbody.iter().any(|body| find_ann(body))
+        }
+        Match { cases, .. } => cases.iter().any(|case| find_ann(&case.body)),
+        _ => false,
+    })
+}
+
 fn try_get_constant_string(string: &ast::StringGroup) -> Option<String> {
     fn get_constant_string_inner(out_string: &mut String, string: &ast::StringGroup) -> bool {
         match string {
@@ -2090,7 +2773,8 @@ fn compile_conversion_flag(conversion_flag: ast::ConversionFlag) -> bytecode::Co
 
 #[cfg(test)]
 mod tests {
-    use super::Compiler;
+    use super::{compile, Compiler, Mode};
+    use crate::error::CompileErrorType;
     use crate::symboltable::make_symbol_table;
     use rustpython_bytecode::bytecode::Constant::*;
     use rustpython_bytecode::bytecode::Instruction::*;
@@ -2098,7 +2782,11 @@ mod tests {
     use rustpython_parser::parser;
 
     fn compile_exec(source: &str) -> CodeObject {
-        let mut compiler: Compiler = Default::default();
+        compile_exec_optimized(source, 0)
+    }
+
+    fn compile_exec_optimized(source: &str, optimize: u8) -> CodeObject {
+        let mut compiler: Compiler = Compiler::new(optimize);
         compiler.source_path = Some("source_path".to_string());
         compiler.push_new_code_object("<module>".to_string());
         let ast = parser::parse_program(&source.to_string()).unwrap();
@@ -2107,6 +2795,33 @@ mod tests {
         compiler.pop_code_object()
     }
 
+    #[test]
+    fn test_assert_optimized_away() {
+        let code = compile_exec_optimized("assert f(), g()\n", 1);
+        assert_eq!(
+            vec![LoadConst { value: None }, ReturnValue],
+            code.instructions
+        );
+    }
+
+    #[test]
+    fn test_debug_constant() {
+        let code = compile_exec("__debug__\n");
+        assert_eq!(
+            LoadConst {
+                value: Boolean { value: true }
+            },
+            code.instructions[0]
+        );
+        let code = compile_exec_optimized("__debug__\n", 1);
+        assert_eq!(
+            LoadConst {
+                value: Boolean { value: false }
+            },
+            code.instructions[0]
+        );
+    }
+
     #[test]
     fn test_if_ors() {
         let code = compile_exec("if True or False or False:\n pass\n");
@@ -2222,4 +2937,34 @@ mod tests {
             ]
         );
     }
+
+    fn compile_error(source: &str) -> CompileErrorType {
+        compile(source, Mode::Exec, "source_path".to_string(), 0)
+            .unwrap_err()
+            .error
+    }
+
+    #[test]
+    fn test_yield_in_async_function() {
+        match compile_error("async def f():\n    yield 1\n") {
+            CompileErrorType::AsyncYield => {}
+            error => panic!("unexpected error: {:?}", error),
+        }
+        match compile_error("async def f():\n    yield from g()\n") {
+            CompileErrorType::AsyncYieldFrom => {}
+            error => panic!("unexpected error: {:?}", error),
+        }
+        // a nested plain function may still be a generator
+        compile_exec("async def f():\n    def g():\n        yield 1\n");
+    }
+
+    #[test]
+    fn test_await_in_comprehension() {
+        match compile_error("async def f():\n    return [await x for x in y]\n") {
+            CompileErrorType::AwaitInComprehension => {}
+            error => panic!("unexpected error: {:?}", error),
+        }
+        // the outermost iterable is evaluated in the enclosing function
+        compile_exec("async def f():\n    return [x for x in await y]\n");
+    }
 }
@@ -38,6 +38,14 @@ pub enum CompileErrorType {
     InvalidContinue,
     InvalidReturn,
     InvalidYield,
+    InvalidAwait,
+    /// `await` in a comprehension, which is compiled as a plain function.
+    AwaitInComprehension,
+    /// `yield` in an `async def`; async generators are not supported yet.
+    AsyncYield,
+    AsyncYieldFrom,
+    InvalidAsyncFor,
+    InvalidAsyncWith,
 }
 
 impl fmt::Display for CompileError {
@@ -53,6 +61,16 @@ impl fmt::Display for CompileError {
             CompileErrorType::InvalidContinue => write!(f, "'continue' outside loop"),
             CompileErrorType::InvalidReturn => write!(f, "'return' outside function"),
             CompileErrorType::InvalidYield => write!(f, "'yield' outside function"),
+            CompileErrorType::InvalidAwait => write!(f, "'await' outside async function"),
+            CompileErrorType::AwaitInComprehension => {
+                write!(f, "'await' expressions in comprehensions are not supported")
+            }
+            CompileErrorType::AsyncYield => write!(f, "'yield' inside async function"),
+            CompileErrorType::AsyncYieldFrom => write!(f, "'yield from' inside async function"),
+            CompileErrorType::InvalidAsyncFor => write!(f, "'async for' outside async function"),
+            CompileErrorType::InvalidAsyncWith => {
+                write!(f, "'async with' outside async function")
+            }
         }?;
 
         // Print line number:
@@ -7,6 +7,8 @@ pub trait OutputStream: From<CodeObject> + Into<CodeObject> {
     fn set_label(&mut self, label: Label);
     /// Mark the inner CodeObject as a generator
     fn mark_generator(&mut self);
+    /// Mark the inner CodeObject as a coroutine
+    fn mark_coroutine(&mut self);
 }
 
 pub struct CodeObjectStream {
@@ -36,4 +38,7 @@ impl OutputStream for CodeObjectStream {
     fn mark_generator(&mut self) {
         self.code.is_generator = true;
     }
+    fn mark_coroutine(&mut self) {
+        self.code.is_coroutine = true;
+    }
 }
@@ -99,6 +99,9 @@ where
     fn mark_generator(&mut self) {
         self.inner.mark_generator()
     }
+    fn mark_coroutine(&mut self) {
+        self.inner.mark_coroutine()
+    }
 }
 
 impl<O: OutputStream> OptimizationBuffer for PeepholeOptimizer<O> {
@@ -206,12 +206,20 @@ impl<'a> SymbolTableAnalyzer<'a> {
     fn analyze_symbol(&self, symbol: &mut Symbol) -> SymbolTableResult {
         match symbol.scope {
             SymbolScope::Nonlocal => {
-                // check if name is defined in parent table!
-                let parent_symbol_table = self.tables.last();
-                // symbol.table.borrow().parent.clone();
-
-                if let Some((symbols, _)) = parent_symbol_table {
-                    if !symbols.contains_key(&symbol.name) {
+                // check if name is bound in an enclosing function!
+                if !self.tables.is_empty() {
+                    let found_in_enclosing_function =
+                        self.tables.iter().rev().any(|(symbols, typ)| {
+                            *typ == SymbolTableType::Function
+                                && symbols.get(&symbol.name).map_or(false, |outer| {
+                                    match outer.scope {
+                                        SymbolScope::Nonlocal => true,
+                                        SymbolScope::Global => false,
+                                        _ => outer.is_assigned || outer.is_parameter,
+                                    }
+                                })
+                        });
+                    if !found_in_enclosing_function {
                         return Err(SymbolTableError {
                             error: format!("no binding for nonlocal '{}' found", symbol.name),
                             location: Default::default(),
@@ -426,6 +434,16 @@ impl SymbolTableBuilder {
                     self.scan_statements(code)?;
                 }
             }
+            Match { subject, cases } => {
+                self.scan_expression(subject, &ExpressionContext::Load)?;
+                for case in cases {
+                    self.scan_pattern(&case.pattern)?;
+                    if let Some(guard) = &case.guard {
+                        self.scan_expression(guard, &ExpressionContext::Load)?;
+                    }
+                    self.scan_statements(&case.body)?;
+                }
+            }
             Break | Continue | Pass => {
                 // No symbols here.
             }
@@ -520,6 +538,58 @@ impl SymbolTableBuilder {
         Ok(())
     }
 
+    fn scan_pattern(&mut self, pattern: &ast::Pattern) -> SymbolTableResult {
+        use ast::PatternType::*;
+        match &pattern.node {
+            MatchValue { value } | MatchSingleton { value } => {
+                self.scan_expression(value, &ExpressionContext::Load)?;
+            }
+            MatchSequence { patterns } | MatchOr { patterns } => {
+                for pattern in patterns {
+                    self.scan_pattern(pattern)?;
+                }
+            }
+            MatchMapping {
+                keys,
+                patterns,
+                rest,
+            } => {
+                self.scan_expressions(keys, &ExpressionContext::Load)?;
+                for pattern in patterns {
+                    self.scan_pattern(pattern)?;
+                }
+                if let Some(name) = rest {
+                    self.register_name(name, SymbolUsage::Assigned)?;
+                }
+            }
+            MatchClass {
+                cls,
+                patterns,
+                kwd_patterns,
+                ..
+            } => {
+                self.scan_expression(cls, &ExpressionContext::Load)?;
+                for pattern in patterns.iter().chain(kwd_patterns) {
+                    self.scan_pattern(pattern)?;
+                }
+            }
+            MatchStar { name } => {
+                if let Some(name) = name {
+                    self.register_name(name, SymbolUsage::Assigned)?;
+                }
+            }
+            MatchAs { pattern, name } => {
+                if let Some(pattern) = pattern {
+                    self.scan_pattern(pattern)?;
+                }
+                if let Some(name) = name {
+                    self.register_name(name, SymbolUsage::Assigned)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn scan_expressions(
         &mut self,
         expressions: &[ast::Expression],
@@ -606,32 +676,34 @@ impl SymbolTableBuilder {
                 // Register the passed argument to the generator function as the name ".0"
                 self.register_name(".0", SymbolUsage::Parameter)?;
 
-                match **kind {
-                    ast::ComprehensionKind::GeneratorExpression { ref element }
-                    | ast::ComprehensionKind::List { ref element }
-                    | ast::ComprehensionKind::Set { ref element } => {
-                        self.scan_expression(element, &ExpressionContext::Load)?;
-                    }
-                    ast::ComprehensionKind::Dict { ref key, ref value } => {
-                        self.scan_expression(&key, &ExpressionContext::Load)?;
-                        self.scan_expression(&value, &ExpressionContext::Load)?;
-                    }
-                }
-
+                // Scan in the order the compiler emits code, so that nested
+                // scopes line up with the sub tables created here.
                 let mut is_first_generator = true;
                 for generator in generators {
-                    self.scan_expression(&generator.target, &ExpressionContext::Store)?;
                     if is_first_generator {
                         is_first_generator = false;
                     } else {
                         self.scan_expression(&generator.iter, &ExpressionContext::Load)?;
                     }
+                    self.scan_expression(&generator.target, &ExpressionContext::Store)?;
 
                     for if_expr in &generator.ifs {
                         self.scan_expression(if_expr, &ExpressionContext::Load)?;
                     }
                 }
 
+                match **kind {
+                    ast::ComprehensionKind::GeneratorExpression { ref element }
+                    | ast::ComprehensionKind::List { ref element }
+                    | ast::ComprehensionKind::Set { ref element } => {
+                        self.scan_expression(element, &ExpressionContext::Load)?;
+                    }
+                    ast::ComprehensionKind::Dict { ref key, ref value } => {
+                        self.scan_expression(&key, &ExpressionContext::Load)?;
+                        self.scan_expression(&value, &ExpressionContext::Load)?;
+                    }
+                }
+
                 self.leave_scope();
 
                 // The first iterable is passed as an argument into the created function:
@@ -721,8 +793,11 @@ impl SymbolTableBuilder {
     fn scan_string_group(&mut self, group: &ast::StringGroup) -> SymbolTableResult {
         match group {
             ast::StringGroup::Constant { .. } => {}
-            ast::StringGroup::FormattedValue { value, .. } => {
+            ast::StringGroup::FormattedValue { value, spec, .. } => {
                 self.scan_expression(value, &ExpressionContext::Load)?;
+                if let Some(spec) = spec {
+                    self.scan_string_group(spec)?;
+                }
             }
             ast::StringGroup::Joined { values } => {
                 for subgroup in values {
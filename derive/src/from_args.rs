@@ -31,6 +31,7 @@ struct ArgAttribute {
     kind: ParameterKind,
     default: Option<Expr>,
     optional: bool,
+    name: Option<String>,
 }
 
 impl ArgAttribute {
@@ -60,6 +61,7 @@ impl ArgAttribute {
                     kind,
                     default: None,
                     optional: false,
+                    name: None,
                 };
 
                 for arg in iter {
@@ -117,6 +119,13 @@ impl ArgAttribute {
                             "Expected boolean value for optional argument"
                         ),
                     }
+                } else if name_value.ident == "name" {
+                    match name_value.lit {
+                        Lit::Str(ref val) => {
+                            self.name = Some(val.value());
+                        }
+                        _ => bail_span!(name_value.lit, "Expected string value for name argument"),
+                    }
                 } else {
                     bail_span!(name_value, "Unrecognised pyarg attribute");
                 }
@@ -139,6 +148,7 @@ fn generate_field(field: &Field) -> Result<TokenStream2, Diagnostic> {
             kind: ParameterKind::PositionalOrKeyword,
             default: None,
             optional: false,
+            name: None,
         }
     } else if pyarg_attrs.len() == 1 {
         pyarg_attrs.remove(0)
@@ -147,6 +157,10 @@ fn generate_field(field: &Field) -> Result<TokenStream2, Diagnostic> {
     };
 
     let name = &field.ident;
+    let py_name = match attr.name {
+        Some(ref py_name) => py_name.clone(),
+        None => name.as_ref().unwrap().to_string(),
+    };
     let middle = quote! {
         .map(|x| ::rustpython_vm::pyobject::TryFromObject::try_from_object(vm, x)).transpose()?
     };
@@ -165,7 +179,7 @@ fn generate_field(field: &Field) -> Result<TokenStream2, Diagnostic> {
                 ::rustpython_vm::function::ArgumentError::TooFewArgs
             },
             ParameterKind::KeywordOnly => quote! {
-                ::rustpython_vm::function::ArgumentError::RequiredKeywordArgument(tringify!(#name))
+                ::rustpython_vm::function::ArgumentError::RequiredKeywordArgument(#py_name.to_string())
             },
         };
         quote! {
@@ -181,12 +195,12 @@ fn generate_field(field: &Field) -> Result<TokenStream2, Diagnostic> {
         }
         ParameterKind::PositionalOrKeyword => {
             quote! {
-                #name: args.take_positional_keyword(stringify!(#name))#middle#ending,
+                #name: args.take_positional_keyword(#py_name)#middle#ending,
             }
         }
         ParameterKind::KeywordOnly => {
             quote! {
-                #name: args.take_keyword(stringify!(#name))#middle#ending,
+                #name: args.take_keyword(#py_name)#middle#ending,
             }
         }
     };
@@ -99,6 +99,10 @@ pub enum StatementType {
         body: Suite,
         orelse: Option<Suite>,
     },
+    Match {
+        subject: Expression,
+        cases: Vec<MatchCase>,
+    },
     With {
         is_async: bool,
         items: Vec<WithItem>,
@@ -144,6 +148,55 @@ pub struct WithItem {
     pub optional_vars: Option<Expression>,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct MatchCase {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: Suite,
+}
+
+pub type Pattern = Located<PatternType>;
+
+/// Patterns of the case blocks of a match statement.
+#[derive(Debug, PartialEq)]
+pub enum PatternType {
+    /// A literal or dotted name, compared with `==`.
+    MatchValue {
+        value: Expression,
+    },
+    /// `None`, `True` or `False`, compared with `is`.
+    MatchSingleton {
+        value: Expression,
+    },
+    MatchSequence {
+        patterns: Vec<Pattern>,
+    },
+    MatchMapping {
+        keys: Vec<Expression>,
+        patterns: Vec<Pattern>,
+        rest: Option<String>,
+    },
+    MatchClass {
+        cls: Expression,
+        patterns: Vec<Pattern>,
+        kwd_attrs: Vec<String>,
+        kwd_patterns: Vec<Pattern>,
+    },
+    /// `*name` in a sequence pattern, `*_` has no name.
+    MatchStar {
+        name: Option<String>,
+    },
+    /// A capture pattern (no sub-pattern), `pattern as name`, or the
+    /// wildcard `_` (neither).
+    MatchAs {
+        pattern: Option<Box<Pattern>>,
+        name: Option<String>,
+    },
+    MatchOr {
+        patterns: Vec<Pattern>,
+    },
+}
+
 pub type Expression = Located<ExpressionType>;
 
 #[derive(Debug, PartialEq)]
@@ -406,7 +459,7 @@ pub enum StringGroup {
     FormattedValue {
         value: Box<Expression>,
         conversion: Option<ConversionFlag>,
-        spec: String,
+        spec: Option<Box<StringGroup>>,
     },
     Joined {
         values: Vec<StringGroup>,
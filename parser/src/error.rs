@@ -55,19 +55,27 @@ pub enum FStringErrorType {
     InvalidConversionFlag,
     EmptyExpression,
     MismatchedDelimiter,
+    ExpectedRbrace,
+    UnterminatedString,
+    ExpressionNestedTooDeeply,
 }
 
 impl fmt::Display for FStringErrorType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            FStringErrorType::UnclosedLbrace => write!(f, "Unclosed '('"),
-            FStringErrorType::UnopenedRbrace => write!(f, "Unopened ')'"),
+            FStringErrorType::UnclosedLbrace => write!(f, "Unclosed '{{'"),
+            FStringErrorType::UnopenedRbrace => write!(f, "Unopened '}}'"),
             FStringErrorType::InvalidExpression(error) => {
                 write!(f, "Invalid expression: {}", error)
             }
             FStringErrorType::InvalidConversionFlag => write!(f, "Invalid conversion flag"),
             FStringErrorType::EmptyExpression => write!(f, "Empty expression"),
             FStringErrorType::MismatchedDelimiter => write!(f, "Mismatched delimiter"),
+            FStringErrorType::ExpectedRbrace => write!(f, "Expected '}}'"),
+            FStringErrorType::UnterminatedString => write!(f, "Unterminated string"),
+            FStringErrorType::ExpressionNestedTooDeeply => {
+                write!(f, "Expressions nested too deeply")
+            }
         }
     }
 }
@@ -21,15 +21,15 @@ impl<'a> FStringParser<'a> {
         }
     }
 
-    fn parse_formatted_value(&mut self) -> Result<StringGroup, FStringErrorType> {
+    fn parse_formatted_value(&mut self, nested: u8) -> Result<StringGroup, FStringErrorType> {
         let mut expression = String::new();
-        let mut spec = String::new();
         let mut delims = Vec::new();
         let mut conversion = None;
 
         while let Some(ch) = self.chars.next() {
             match ch {
-                '!' if delims.is_empty() => {
+                // `!=` is an operator, not the start of a conversion
+                '!' if delims.is_empty() && self.chars.peek() != Some(&'=') => {
                     conversion = Some(match self.chars.next() {
                         Some('s') => ConversionFlag::Str,
                         Some('a') => ConversionFlag::Ascii,
@@ -40,18 +40,23 @@ impl<'a> FStringParser<'a> {
                         None => {
                             break;
                         }
-                    })
+                    });
+                    match self.chars.peek() {
+                        Some(':') | Some('}') => {}
+                        Some(_) => return Err(ExpectedRbrace),
+                        None => break,
+                    }
                 }
-                ':' if delims.is_empty() => {
-                    while let Some(&next) = self.chars.peek() {
-                        if next != '}' {
-                            spec.push(next);
-                            self.chars.next();
-                        } else {
-                            break;
-                        }
+                '!' => {
+                    expression.push(ch);
+                    if delims.is_empty() {
+                        expression.push(self.chars.next().unwrap());
                     }
                 }
+                ':' if delims.is_empty() => {
+                    let spec = Box::new(self.parse_spec(nested)?);
+                    return self.finish_formatted_value(&expression, conversion, Some(spec));
+                }
                 '(' | '{' | '[' => {
                     expression.push(ch);
                     delims.push(ch);
@@ -75,29 +80,107 @@ impl<'a> FStringParser<'a> {
                     expression.push(ch);
                 }
                 '}' => {
-                    if expression.is_empty() {
-                        return Err(EmptyExpression);
-                    }
-                    return Ok(FormattedValue {
-                        value: Box::new(
-                            parse_expression(expression.trim())
-                                .map_err(|e| InvalidExpression(Box::new(e.error)))?,
-                        ),
-                        conversion,
-                        spec,
-                    });
+                    return self.finish_formatted_value(&expression, conversion, None);
                 }
-                '"' | '\'' => {
+                '"' | '\'' => self.parse_quoted(ch, &mut expression)?,
+                _ => {
                     expression.push(ch);
-                    while let Some(next) = self.chars.next() {
-                        expression.push(next);
-                        if next == ch {
-                            break;
-                        }
+                }
+            }
+        }
+
+        Err(UnclosedLbrace)
+    }
+
+    fn finish_formatted_value(
+        &mut self,
+        expression: &str,
+        conversion: Option<ConversionFlag>,
+        spec: Option<Box<StringGroup>>,
+    ) -> Result<StringGroup, FStringErrorType> {
+        if expression.trim().is_empty() {
+            return Err(EmptyExpression);
+        }
+        Ok(FormattedValue {
+            value: Box::new(
+                parse_expression(expression.trim())
+                    .map_err(|e| InvalidExpression(Box::new(e.error)))?,
+            ),
+            conversion,
+            spec,
+        })
+    }
+
+    /// Copy a string literal inside of an expression verbatim, so that any
+    /// braces, colons or exclamation marks in it are left alone.
+    fn parse_quoted(
+        &mut self,
+        quote: char,
+        expression: &mut String,
+    ) -> Result<(), FStringErrorType> {
+        expression.push(quote);
+        let mut triple_quoted = false;
+        if self.chars.peek() == Some(&quote) {
+            self.chars.next();
+            expression.push(quote);
+            if self.chars.peek() != Some(&quote) {
+                // just an empty string
+                return Ok(());
+            }
+            self.chars.next();
+            expression.push(quote);
+            triple_quoted = true;
+        }
+
+        let mut quotes_seen = 0;
+        while let Some(next) = self.chars.next() {
+            expression.push(next);
+            if next == '\\' {
+                if let Some(escaped) = self.chars.next() {
+                    expression.push(escaped);
+                }
+                quotes_seen = 0;
+            } else if next == quote {
+                quotes_seen += 1;
+                if !triple_quoted || quotes_seen == 3 {
+                    return Ok(());
+                }
+            } else {
+                quotes_seen = 0;
+            }
+        }
+
+        Err(UnterminatedString)
+    }
+
+    /// Parse a format spec up to the closing brace of its replacement field.
+    /// The spec may contain replacement fields of its own, but those can't
+    /// nest any further.
+    fn parse_spec(&mut self, nested: u8) -> Result<StringGroup, FStringErrorType> {
+        let mut content = String::new();
+        let mut values = vec![];
+
+        while let Some(ch) = self.chars.next() {
+            match ch {
+                '{' => {
+                    if nested >= 1 {
+                        return Err(ExpressionNestedTooDeeply);
                     }
+                    if !content.is_empty() {
+                        values.push(Constant {
+                            value: mem::replace(&mut content, String::new()),
+                        });
+                    }
+                    values.push(self.parse_formatted_value(nested + 1)?);
+                }
+                '}' => {
+                    if !content.is_empty() {
+                        values.push(Constant { value: content });
+                    }
+                    return Ok(join_values(values));
                 }
                 _ => {
-                    expression.push(ch);
+                    content.push(ch);
                 }
             }
         }
@@ -122,7 +205,7 @@ impl<'a> FStringParser<'a> {
                             });
                         }
 
-                        values.push(self.parse_formatted_value()?);
+                        values.push(self.parse_formatted_value(0)?);
                     }
                 }
                 '}' => {
@@ -143,13 +226,17 @@ impl<'a> FStringParser<'a> {
             values.push(Constant { value: content })
         }
 
-        Ok(match values.len() {
-            0 => Constant {
-                value: String::new(),
-            },
-            1 => values.into_iter().next().unwrap(),
-            _ => Joined { values },
-        })
+        Ok(join_values(values))
+    }
+}
+
+fn join_values(values: Vec<StringGroup>) -> StringGroup {
+    match values.len() {
+        0 => Constant {
+            value: String::new(),
+        },
+        1 => values.into_iter().next().unwrap(),
+        _ => Joined { values },
     }
 }
 
@@ -194,12 +281,12 @@ mod tests {
                     FormattedValue {
                         value: Box::new(mk_ident("a", 1, 1)),
                         conversion: None,
-                        spec: String::new(),
+                        spec: None,
                     },
                     FormattedValue {
                         value: Box::new(mk_ident("b", 1, 1)),
                         conversion: None,
-                        spec: String::new(),
+                        spec: None,
                     },
                     Constant {
                         value: "{foo}".to_owned()
@@ -224,7 +311,59 @@ mod tests {
         assert_eq!(parse_fstring("{"), Err(UnclosedLbrace));
         assert_eq!(parse_fstring("}"), Err(UnopenedRbrace));
 
+        assert_eq!(parse_fstring("{a!r"), Err(UnclosedLbrace));
+        assert_eq!(parse_fstring("{a!x}"), Err(InvalidConversionFlag));
+        assert_eq!(parse_fstring("{a!r b}"), Err(ExpectedRbrace));
+        assert_eq!(parse_fstring("{ }"), Err(EmptyExpression));
+        assert_eq!(parse_fstring("{a)}"), Err(MismatchedDelimiter));
+        assert_eq!(parse_fstring("{'a}"), Err(UnterminatedString));
+        assert_eq!(parse_fstring("{a:{b:{c}}}"), Err(ExpressionNestedTooDeeply));
+
         // TODO: check for InvalidExpression enum?
         assert!(parse_fstring("{class}").is_err());
     }
+
+    #[test]
+    fn test_parse_fstring_nested_spec() {
+        let source = String::from("{foo:{spec}x}");
+        let parse_ast = parse_fstring(&source).unwrap();
+
+        assert_eq!(
+            parse_ast,
+            FormattedValue {
+                value: Box::new(mk_ident("foo", 1, 1)),
+                conversion: None,
+                spec: Some(Box::new(Joined {
+                    values: vec![
+                        FormattedValue {
+                            value: Box::new(mk_ident("spec", 1, 1)),
+                            conversion: None,
+                            spec: None,
+                        },
+                        Constant {
+                            value: "x".to_owned()
+                        },
+                    ]
+                })),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_fstring_complex_expressions() {
+        let sources = [
+            "{ {1, 2} }",
+            "{ {'a': 1}['a'] }",
+            "{obj.attr[key]:>{width}}",
+            "{x!r:^{width}.{precision}}",
+            "{1 != 2}",
+            "{a['}'] + b[':']!s}",
+            "{'''!:{'''}",
+            "{(lambda x: x)(1)}",
+            "{f'{x:{y}}'}",
+        ];
+        for source in sources.iter() {
+            assert!(parse_fstring(source).is_ok(), "failed to parse {}", source);
+        }
+    }
 }
@@ -10,11 +10,10 @@ use crate::location::Location;
 use num_bigint::BigInt;
 use num_traits::Num;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
 use unic_emoji_char::is_emoji_presentation;
 use unicode_xid::UnicodeXID;
-use wtf8;
 
 #[derive(Clone, Copy, PartialEq, Debug, Default)]
 struct IndentationLevel {
@@ -122,7 +121,7 @@ pub type LexResult = Result<Spanned, LexicalError>;
 pub fn make_tokenizer<'a>(source: &'a str) -> impl Iterator<Item = LexResult> + 'a {
     let nlh = NewlineHandler::new(source.chars());
     let lch = LineContinationHandler::new(nlh);
-    Lexer::new(lch)
+    SoftKeywordHandler::new(Lexer::new(lch))
 }
 
 // The newline handler is an iterator which collapses different newline
@@ -237,6 +236,110 @@ where
     }
 }
 
+// Turns the soft keywords `match` and `case` into keyword tokens where they
+// start a match statement or one of its case blocks, and leaves them as
+// names everywhere else:
+pub struct SoftKeywordHandler<T: Iterator<Item = LexResult>> {
+    source: T,
+    buffer: VecDeque<LexResult>,
+    at_begin_of_line: bool,
+    indentation: usize,
+    // Indentation levels of the bodies of the enclosing match statements:
+    match_bodies: Vec<usize>,
+    expect_match_body: bool,
+}
+
+impl<T> SoftKeywordHandler<T>
+where
+    T: Iterator<Item = LexResult>,
+{
+    pub fn new(source: T) -> Self {
+        SoftKeywordHandler {
+            source,
+            buffer: VecDeque::new(),
+            at_begin_of_line: true,
+            indentation: 0,
+            match_bodies: vec![],
+            expect_match_body: false,
+        }
+    }
+
+    fn shift(&mut self) -> Option<LexResult> {
+        self.buffer.pop_front().or_else(|| self.source.next())
+    }
+
+    // A line starting with `match` is a match statement if it ends with a
+    // colon, like `match command.split():`. Brackets can't be open at the
+    // end of a line, so the colon can't be part of a slice or a dict.
+    fn is_match_statement(&mut self) -> bool {
+        let mut index = 0;
+        let mut last = None;
+        loop {
+            if index == self.buffer.len() {
+                match self.source.next() {
+                    Some(token) => self.buffer.push_back(token),
+                    None => return false,
+                }
+            }
+            match &self.buffer[index] {
+                Ok((_, Tok::Newline, _)) => break,
+                Ok((_, tok, _)) => last = Some(tok.clone()),
+                Err(_) => return false,
+            }
+            index += 1;
+        }
+        index > 1 && last == Some(Tok::Colon)
+    }
+}
+
+impl<T> Iterator for SoftKeywordHandler<T>
+where
+    T: Iterator<Item = LexResult>,
+{
+    type Item = LexResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, mut tok, end) = match self.shift() {
+            Some(Ok(spanned)) => spanned,
+            other => return other,
+        };
+        match &tok {
+            Tok::Indent => {
+                self.indentation += 1;
+                if self.expect_match_body {
+                    self.match_bodies.push(self.indentation);
+                    self.expect_match_body = false;
+                }
+            }
+            Tok::Dedent => {
+                if self.match_bodies.last() == Some(&self.indentation) {
+                    self.match_bodies.pop();
+                }
+                self.indentation -= 1;
+            }
+            Tok::Newline => {}
+            _ if self.at_begin_of_line => {
+                self.expect_match_body = false;
+                if let Tok::Name { name } = &tok {
+                    if name == "match" && self.is_match_statement() {
+                        self.expect_match_body = true;
+                        tok = Tok::Match;
+                    } else if name == "case" && self.match_bodies.last() == Some(&self.indentation)
+                    {
+                        tok = Tok::Case;
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.at_begin_of_line = match tok {
+            Tok::Newline | Tok::Indent | Tok::Dedent => true,
+            _ => false,
+        };
+        Some(Ok((start, tok, end)))
+    }
+}
+
 impl<T> Lexer<T>
 where
     T: Iterator<Item = char>,
@@ -340,7 +443,12 @@ where
 
     /// Lex a hex/octal/decimal/binary number without a decimal point.
     fn lex_number_radix(&mut self, start_pos: Location, radix: u32) -> LexResult {
+        // PEP 515 allows an underscore right after the base prefix: 0x_ff
+        if self.chr0 == Some('_') && Lexer::<T>::is_digit_of_radix(self.chr1, radix) {
+            self.next_char();
+        }
         let value_text = self.radix_run(radix);
+        self.check_number_end(radix)?;
         let end_pos = self.get_pos();
         let value = BigInt::from_str_radix(&value_text, radix).map_err(|e| LexicalError {
             error: LexicalErrorType::OtherError(format!("{:?}", e)),
@@ -365,7 +473,7 @@ where
             }
 
             // 1e6 for example:
-            if self.chr0 == Some('e') || self.chr0 == Some('E') {
+            if self.at_exponent() {
                 value_text.push(self.next_char().unwrap().to_ascii_lowercase());
 
                 // Optional +/-
@@ -375,6 +483,7 @@ where
 
                 value_text.push_str(&self.radix_run(10));
             }
+            self.check_number_end(10)?;
 
             let value = f64::from_str(&value_text).unwrap();
             // Parse trailing 'j':
@@ -394,6 +503,7 @@ where
                 Ok((start_pos, Tok::Float { value }, end_pos))
             }
         } else {
+            self.check_number_end(10)?;
             // Parse trailing 'j':
             if self.chr0 == Some('j') || self.chr0 == Some('J') {
                 self.next_char();
@@ -416,7 +526,10 @@ where
         loop {
             if let Some(c) = self.take_number(radix) {
                 value_text.push(c);
-            } else if self.chr0 == Some('_') && Lexer::<T>::is_digit_of_radix(self.chr1, radix) {
+            } else if self.chr0 == Some('_')
+                && !value_text.is_empty()
+                && Lexer::<T>::is_digit_of_radix(self.chr1, radix)
+            {
                 self.next_char();
             } else {
                 break;
@@ -425,6 +538,26 @@ where
         value_text
     }
 
+    /// Reject characters that can't directly follow a number, such as a
+    /// leftover underscore (`1_`, `1__0`) or a decimal digit in a binary literal.
+    fn check_number_end(&self, radix: u32) -> Result<(), LexicalError> {
+        let kind = match radix {
+            2 => "binary",
+            8 => "octal",
+            16 => "hexadecimal",
+            _ => "decimal",
+        };
+        let msg = match self.chr0 {
+            Some('_') => format!("invalid {} literal", kind),
+            Some(c @ '0'..='9') => format!("invalid digit '{}' in {} literal", c, kind),
+            _ => return Ok(()),
+        };
+        Err(LexicalError {
+            error: LexicalErrorType::OtherError(msg),
+            location: self.get_pos(),
+        })
+    }
+
     /// Consume a single character with the given radix.
     fn take_number(&mut self, radix: u32) -> Option<char> {
         let take_char = Lexer::<T>::is_digit_of_radix(self.chr0, radix);
@@ -487,25 +620,108 @@ where
         }
     }
 
-    fn unicode_literal(&mut self, literal_number: usize) -> Result<char, LexicalError> {
-        let mut p: u32 = 0u32;
-        let unicode_error = Err(LexicalError {
-            error: LexicalErrorType::UnicodeError,
+    fn escape_error(&self, msg: &str) -> LexicalError {
+        LexicalError {
+            error: LexicalErrorType::OtherError(msg.to_string()),
             location: self.get_pos(),
-        });
-        for i in 1..=literal_number {
+        }
+    }
+
+    /// Read exactly `digits` hex digits, as required after `\x`, `\u` and `\U`.
+    fn lex_hex_escape(&mut self, digits: usize, escape: char) -> Result<u32, LexicalError> {
+        let mut value = 0u32;
+        for _ in 0..digits {
+            match self.chr0.and_then(|c| c.to_digit(16)) {
+                Some(d) => {
+                    self.next_char();
+                    value = value * 16 + d;
+                }
+                None => {
+                    let xs = "X".repeat(digits);
+                    return Err(self.escape_error(&format!("truncated \\{}{} escape", escape, xs)));
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    /// Read the `{NAME}` part of a `\N{NAME}` escape and look the character up.
+    fn lex_named_escape(&mut self) -> Result<char, LexicalError> {
+        if self.chr0 != Some('{') {
+            return Err(self.escape_error("malformed \\N character escape"));
+        }
+        self.next_char();
+        let mut name = String::new();
+        loop {
             match self.next_char() {
-                Some(c) => match c.to_digit(16) {
-                    Some(d) => p += d << ((literal_number - i) * 4),
-                    None => return unicode_error,
-                },
-                None => return unicode_error,
+                Some('}') => break,
+                Some(c) if c != '\n' && c != '\'' && c != '"' => name.push(c),
+                _ => return Err(self.escape_error("malformed \\N character escape")),
             }
         }
-        match wtf8::CodePoint::from_u32(p) {
-            Some(cp) => Ok(cp.to_char_lossy()),
-            None => unicode_error,
+        unicode_names2::character(&name)
+            .ok_or_else(|| self.escape_error("unknown Unicode character name"))
+    }
+
+    /// Process the escape sequence following a backslash in a non-raw literal.
+    /// Bytes literals are built as one char per byte, and don't know `\u`, `\U` or `\N`.
+    fn lex_escape(&mut self, is_bytes: bool, content: &mut String) -> Result<(), LexicalError> {
+        let c = match self.next_char() {
+            Some(c) => c,
+            None => {
+                return Err(LexicalError {
+                    error: LexicalErrorType::StringError,
+                    location: self.get_pos(),
+                })
+            }
+        };
+        match c {
+            // an escaped newline continues the literal on the next line
+            '\n' => {}
+            '\\' | '\'' | '"' => content.push(c),
+            'a' => content.push('\x07'),
+            'b' => content.push('\x08'),
+            'f' => content.push('\x0c'),
+            'n' => content.push('\n'),
+            'r' => content.push('\r'),
+            't' => content.push('\t'),
+            'v' => content.push('\x0b'),
+            '0'..='7' => {
+                let mut value = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match self.chr0.and_then(|c| c.to_digit(8)) {
+                        Some(d) => {
+                            self.next_char();
+                            value = value * 8 + d;
+                        }
+                        None => break,
+                    }
+                }
+                // '\777' is fine in a str, bytes only keep the low byte
+                let value = if is_bytes { value & 0xff } else { value };
+                content.push(std::char::from_u32(value).unwrap());
+            }
+            'x' => {
+                let value = self.lex_hex_escape(2, 'x')?;
+                content.push(std::char::from_u32(value).unwrap());
+            }
+            'u' | 'U' if !is_bytes => {
+                let digits = if c == 'u' { 4 } else { 8 };
+                let value = self.lex_hex_escape(digits, c)?;
+                if value > 0x10_ffff {
+                    return Err(self.escape_error("illegal Unicode character"));
+                }
+                // lone surrogates can't be stored in a Rust string
+                content.push(std::char::from_u32(value).unwrap_or('\u{fffd}'));
+            }
+            'N' if !is_bytes => content.push(self.lex_named_escape()?),
+            _ => {
+                // unknown escapes are kept as they are
+                content.push('\\');
+                content.push(c);
+            }
         }
+        Ok(())
     }
 
     fn lex_string(
@@ -532,10 +748,9 @@ where
         loop {
             match self.next_char() {
                 Some('\\') => {
-                    if self.chr0 == Some(quote_char) {
-                        string_content.push(quote_char);
-                        self.next_char();
-                    } else if is_raw {
+                    if is_raw {
+                        // raw literals keep the backslash, but it still stops the
+                        // next character from closing the literal
                         string_content.push('\\');
                         if let Some(c) = self.next_char() {
                             string_content.push(c)
@@ -546,40 +761,7 @@ where
                             });
                         }
                     } else {
-                        match self.next_char() {
-                            Some('\\') => {
-                                string_content.push('\\');
-                            }
-                            Some('\'') => string_content.push('\''),
-                            Some('\"') => string_content.push('\"'),
-                            Some('\n') => {
-                                // Ignore Unix EOL character
-                            }
-                            Some('a') => string_content.push('\x07'),
-                            Some('b') => string_content.push('\x08'),
-                            Some('f') => string_content.push('\x0c'),
-                            Some('n') => {
-                                string_content.push('\n');
-                            }
-                            Some('r') => string_content.push('\r'),
-                            Some('t') => {
-                                string_content.push('\t');
-                            }
-                            Some('u') => string_content.push(self.unicode_literal(4)?),
-                            Some('U') => string_content.push(self.unicode_literal(8)?),
-                            Some('x') if !is_bytes => string_content.push(self.unicode_literal(2)?),
-                            Some('v') => string_content.push('\x0b'),
-                            Some(c) => {
-                                string_content.push('\\');
-                                string_content.push(c);
-                            }
-                            None => {
-                                return Err(LexicalError {
-                                    error: LexicalErrorType::StringError,
-                                    location: self.get_pos(),
-                                });
-                            }
-                        }
+                        self.lex_escape(is_bytes, &mut string_content)?;
                     }
                 }
                 Some(c) => {
@@ -604,6 +786,14 @@ where
                                 location: self.get_pos(),
                             });
                         }
+                        if is_bytes && !c.is_ascii() {
+                            return Err(LexicalError {
+                                error: LexicalErrorType::OtherError(
+                                    "bytes can only contain ASCII literal characters".to_string(),
+                                ),
+                                location: self.get_pos(),
+                            });
+                        }
                         string_content.push(c);
                     }
                 }
@@ -618,18 +808,9 @@ where
         let end_pos = self.get_pos();
 
         let tok = if is_bytes {
-            if string_content.is_ascii() {
-                Tok::Bytes {
-                    value: lex_byte(string_content).map_err(|error| LexicalError {
-                        error,
-                        location: self.get_pos(),
-                    })?,
-                }
-            } else {
-                return Err(LexicalError {
-                    error: LexicalErrorType::StringError,
-                    location: self.get_pos(),
-                });
+            // every char is either ASCII or an escaped byte
+            Tok::Bytes {
+                value: string_content.chars().map(|c| c as u8).collect(),
             }
         } else {
             Tok::String {
@@ -1270,54 +1451,6 @@ where
     }
 }
 
-fn lex_byte(s: String) -> Result<Vec<u8>, LexicalErrorType> {
-    let mut res = vec![];
-    let mut escape = false; //flag if previous was \
-    let mut hex_on = false; // hex mode on or off
-    let mut hex_value = String::new();
-
-    for c in s.chars() {
-        if hex_on {
-            if c.is_ascii_hexdigit() {
-                if hex_value.is_empty() {
-                    hex_value.push(c);
-                    continue;
-                } else {
-                    hex_value.push(c);
-                    res.push(u8::from_str_radix(&hex_value, 16).unwrap());
-                    hex_on = false;
-                    hex_value.clear();
-                }
-            } else {
-                return Err(LexicalErrorType::StringError);
-            }
-        } else {
-            match (c, escape) {
-                ('\\', true) => res.push(b'\\'),
-                ('\\', false) => {
-                    escape = true;
-                    continue;
-                }
-                ('x', true) => hex_on = true,
-                ('x', false) => res.push(b'x'),
-                ('t', true) => res.push(b'\t'),
-                ('t', false) => res.push(b't'),
-                ('n', true) => res.push(b'\n'),
-                ('n', false) => res.push(b'n'),
-                ('r', true) => res.push(b'\r'),
-                ('r', false) => res.push(b'r'),
-                (x, true) => {
-                    res.push(b'\\');
-                    res.push(x as u8);
-                }
-                (x, false) => res.push(x as u8),
-            }
-            escape = false;
-        }
-    }
-    Ok(res)
-}
-
 #[cfg(test)]
 mod tests {
     use super::{make_tokenizer, NewlineHandler, Tok};
@@ -1397,6 +1530,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_numbers_with_underscores() {
+        let source = String::from("1_000 0xFF_FF 0x_1 0b1_0 0o7_7 1_0.0_1e1_0 1_0j");
+        let tokens = lex_source(&source);
+        assert_eq!(
+            tokens,
+            vec![
+                Tok::Int {
+                    value: BigInt::from(1000),
+                },
+                Tok::Int {
+                    value: BigInt::from(65535),
+                },
+                Tok::Int {
+                    value: BigInt::from(1),
+                },
+                Tok::Int {
+                    value: BigInt::from(2),
+                },
+                Tok::Int {
+                    value: BigInt::from(63),
+                },
+                Tok::Float {
+                    value: 100_100_000_000.0,
+                },
+                Tok::Complex {
+                    real: 0.0,
+                    imag: 10.0,
+                },
+                Tok::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_number_underscores() {
+        for source in &[
+            "1__0", "1_", "1_.5", "1._5", "1e5_", "0x__1", "0b1_", "0b12",
+        ] {
+            let mut lexer = make_tokenizer(source);
+            assert!(
+                lexer.any(|tok| tok.is_err()),
+                "{:?} should not be a valid number",
+                source
+            );
+        }
+        // a leading underscore makes an identifier rather than a number
+        let tokens = lex_source(&String::from("_1"));
+        assert_eq!(
+            tokens,
+            vec![
+                Tok::Name {
+                    name: String::from("_1"),
+                },
+                Tok::Newline,
+            ]
+        );
+    }
+
     macro_rules! test_line_comment {
         ($($name:ident: $eol:expr,)*) => {
             $(
@@ -1677,7 +1869,7 @@ mod tests {
                     is_fstring: false,
                 },
                 Tok::String {
-                    value: String::from("raw\'"),
+                    value: String::from("raw\\'"),
                     is_fstring: false,
                 },
                 Tok::Newline,
@@ -1742,4 +1934,96 @@ mod tests {
         let res = vec![111, 109, 107, 109, 111, 107, 92, 88, 97, 97];
         assert_eq!(tokens, vec![Tok::Bytes { value: res }, Tok::Newline]);
     }
+
+    #[test]
+    fn test_escape_sequences() {
+        let cases: &[(&str, Tok)] = &[
+            (
+                r"'\x41'",
+                Tok::String {
+                    value: "A".to_string(),
+                    is_fstring: false,
+                },
+            ),
+            (
+                r"'\101\0\7777'",
+                Tok::String {
+                    value: "A\0\u{1ff}7".to_string(),
+                    is_fstring: false,
+                },
+            ),
+            (
+                r"'\u00e9\U0001F600'",
+                Tok::String {
+                    value: "\u{e9}\u{1f600}".to_string(),
+                    is_fstring: false,
+                },
+            ),
+            (
+                r"'\N{GREEK SMALL LETTER ALPHA}'",
+                Tok::String {
+                    value: "\u{3b1}".to_string(),
+                    is_fstring: false,
+                },
+            ),
+            (
+                r"'\a\b\f\v\q'",
+                Tok::String {
+                    value: "\x07\x08\x0c\x0b\\q".to_string(),
+                    is_fstring: false,
+                },
+            ),
+            (
+                r"r'\x41\n\''",
+                Tok::String {
+                    value: r"\x41\n\'".to_string(),
+                    is_fstring: false,
+                },
+            ),
+            (
+                r"b'\x41\101\\x41'",
+                Tok::Bytes {
+                    value: b"AA\\x41".to_vec(),
+                },
+            ),
+            (
+                r"b'\u00e9\N{DASH}'",
+                Tok::Bytes {
+                    value: br"\u00e9\N{DASH}".to_vec(),
+                },
+            ),
+            (
+                r"br'\x41'",
+                Tok::Bytes {
+                    value: br"\x41".to_vec(),
+                },
+            ),
+        ];
+        for (source, expected) in cases {
+            let tokens = lex_source(&source.to_string());
+            assert_eq!(
+                tokens,
+                vec![expected.clone(), Tok::Newline],
+                "lexing {}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_escape_sequences() {
+        for source in &[
+            r"'\x4'",
+            r"'\xg0'",
+            r"'\u12'",
+            r"'\U00110000'",
+            r"'\N{NOT A NAME}'",
+            r"'\N'",
+            r"b'\x'",
+            "b'\u{e9}'",
+        ] {
+            let mut lexer = make_tokenizer(source);
+            assert!(lexer.any(|tok| tok.is_err()), "{} should not lex", source);
+        }
+    }
 }
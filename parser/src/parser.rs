@@ -445,4 +445,54 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_parse_implicit_concatenation() {
+        let source = String::from("('a'\n 'b' \"c\")");
+        let parse_ast = parse_expression(&source).unwrap();
+        assert_eq!(parse_ast, make_string("abc", 1, 3));
+
+        let source = String::from("b'a' b'b'");
+        let parse_ast = parse_expression(&source).unwrap();
+        assert_eq!(
+            parse_ast,
+            ast::Expression {
+                location: ast::Location::new(1, 3),
+                node: ast::ExpressionType::Bytes {
+                    value: b"ab".to_vec()
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_mixed_bytes_and_str() {
+        assert!(parse_expression(&String::from("'a' b'b'")).is_err());
+        assert!(parse_expression(&String::from("b'a' f'b'")).is_err());
+    }
+
+    #[test]
+    fn test_parse_match_soft_keywords() {
+        let source = String::from("match x:\n    case [a, *_] | {'k': a}:\n        pass\n");
+        let statements = parse_statement(&source).unwrap();
+        match &statements[0].node {
+            ast::StatementType::Match { cases, .. } => {
+                assert_eq!(cases.len(), 1);
+                if let ast::PatternType::MatchOr { patterns } = &cases[0].pattern.node {
+                    assert_eq!(patterns.len(), 2);
+                } else {
+                    panic!("Expected an or-pattern");
+                }
+            }
+            node => panic!("Expected a match statement, got {:?}", node),
+        }
+
+        // Elsewhere, match and case are plain names:
+        let source = String::from("match = case(match[1:], case)\n");
+        let statements = parse_statement(&source).unwrap();
+        if let ast::StatementType::Assign { .. } = statements[0].node {
+        } else {
+            panic!("Expected an assignment");
+        }
+    }
 }
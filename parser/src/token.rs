@@ -102,4 +102,8 @@ pub enum Tok {
     While,
     With,
     Yield,
+
+    // Soft keywords:
+    Match,
+    Case,
 }
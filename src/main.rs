@@ -10,10 +10,11 @@ use rustpython_parser::error::ParseErrorType;
 use rustpython_vm::{
     import,
     obj::objstr::PyStringRef,
+    obj::objtype,
     print_exception,
-    pyobject::{ItemProtocol, PyObjectRef, PyResult},
+    pyobject::{ItemProtocol, PyObjectRef, PyResult, TryFromObject},
     scope::Scope,
-    util, PySettings, VirtualMachine,
+    stdlib, util, PySettings, VirtualMachine,
 };
 use std::convert::TryInto;
 
@@ -118,10 +119,25 @@ fn parse_arguments<'a>(app: App<'a, '_>) -> ArgMatches<'a> {
                 .short("B")
                 .help("don't write .pyc files on import"),
         )
+        .arg(
+            Arg::with_name("check-hash-based-pycs")
+                .long("check-hash-based-pycs")
+                .takes_value(true)
+                .possible_values(&["always", "default", "never"])
+                .help("control how the interpreter validates hash-based .pyc files"),
+        )
         .arg(
             Arg::with_name("ignore-environment")
                 .short("E")
                 .help("Ignore environment variables PYTHON* such as PYTHONPATH"),
+        )
+        .arg(
+            Arg::with_name("warning-control")
+                .short("W")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("warning control; arg is action:message:category:module:lineno"),
         );
     #[cfg(feature = "flame-it")]
     let app = app
@@ -201,10 +217,45 @@ fn create_settings(matches: &ArgMatches) -> PySettings {
         settings.dont_write_bytecode = true;
     }
 
+    if let Some(mode) = matches.value_of("check-hash-based-pycs") {
+        settings.check_hash_based_pycs = mode.to_owned();
+    }
+
+    if !ignore_environment {
+        settings.hash_seed = match env::var("PYTHONHASHSEED") {
+            Ok(ref value) if value != "random" => match u32::from_str(value) {
+                Ok(seed) => Some(seed),
+                Err(_) => {
+                    error!(
+                        "PYTHONHASHSEED must be \"random\" or an integer in range [0; 4294967295]"
+                    );
+                    process::exit(1);
+                }
+            },
+            _ => None,
+        };
+    }
+
+    // Later entries take precedence, so the environment comes before -W options.
+    if !ignore_environment {
+        if let Ok(value) = env::var("PYTHONWARNINGS") {
+            settings.warnopts.extend(
+                value
+                    .split(',')
+                    .filter(|opt| !opt.is_empty())
+                    .map(ToOwned::to_owned),
+            );
+        }
+    }
+    if let Some(warnings) = matches.values_of("warning-control") {
+        settings.warnopts.extend(warnings.map(ToOwned::to_owned));
+    }
+
     let argv = if let Some(script) = matches.values_of("script") {
         script.map(ToOwned::to_owned).collect()
     } else if let Some(module) = matches.values_of("m") {
-        std::iter::once("PLACEHOLDER".to_owned())
+        // runpy replaces this with the path of the module once it is found
+        std::iter::once("-m".to_owned())
             .chain(module.skip(1).map(ToOwned::to_owned))
             .collect()
     } else if let Some(cmd) = matches.values_of("c") {
@@ -326,6 +377,11 @@ fn run_rustpython(vm: &VirtualMachine, matches: &ArgMatches) -> PyResult<()> {
         );
     }
 
+    // Apply -W options and PYTHONWARNINGS before any code runs.
+    if !vm.settings.warnopts.is_empty() {
+        vm.import("warnings", &[], 0)?;
+    }
+
     // Figure out if a -c option was given:
     if let Some(command) = matches.value_of("c") {
         run_command(&vm, scope, command.to_string())?;
@@ -345,27 +401,67 @@ fn _run_string(vm: &VirtualMachine, scope: Scope, source: &str, source_path: Str
         .compile(source, compile::Mode::Exec, source_path.clone())
         .map_err(|err| vm.new_syntax_error(&err))?;
     // trace!("Code object: {:?}", code_obj.borrow());
-    scope
-        .globals
-        .set_item("__file__", vm.new_str(source_path), vm)?;
     vm.run_code_obj(code_obj, scope)
 }
 
 fn handle_exception<T>(vm: &VirtualMachine, result: PyResult<T>) {
-    if let Err(err) = result {
-        print_exception(vm, &err);
-        process::exit(1);
+    let exit_code = match result {
+        Ok(_) => 0,
+        Err(err) => {
+            if is_system_exit(vm, &err) {
+                system_exit_code(vm, &err)
+            } else {
+                print_exception(vm, &err);
+                1
+            }
+        }
+    };
+    // Errors are reported by the callbacks themselves.
+    let _ = stdlib::atexit::run_exitfuncs(vm);
+    if exit_code != 0 {
+        process::exit(exit_code);
     }
 }
 
+/// The exit code requested by an uncaught SystemExit. A code that isn't an
+/// integer (or None) is printed, and the process fails.
+fn system_exit_code(vm: &VirtualMachine, exc: &PyObjectRef) -> i32 {
+    let code = vm
+        .get_attribute(exc.clone(), "code")
+        .unwrap_or_else(|_| vm.get_none());
+    if vm.is_none(&code) {
+        return 0;
+    }
+    match i32::try_from_object(vm, code.clone()) {
+        Ok(code) => code,
+        Err(_) => {
+            if let Ok(message) = vm.to_str(&code) {
+                eprintln!("{}", message.as_str());
+            }
+            1
+        }
+    }
+}
+
+fn is_system_exit(vm: &VirtualMachine, exc: &PyObjectRef) -> bool {
+    objtype::isinstance(exc, &vm.ctx.exceptions.system_exit)
+}
+
 fn run_command(vm: &VirtualMachine, scope: Scope, source: String) -> PyResult<()> {
     debug!("Running command {}", source);
-    _run_string(vm, scope, &source, "<stdin>".to_string())?;
+    _run_string(vm, scope, &source, "<string>".to_string())?;
     Ok(())
 }
 
 fn run_module(vm: &VirtualMachine, module: &str) -> PyResult<()> {
     debug!("Running module {}", module);
+    // Search the current directory by its full path, so that the module's
+    // __file__ and sys.argv[0] are absolute.
+    if let Ok(cwd) = env::current_dir() {
+        let sys_path = vm.get_attribute(vm.sys_module.clone(), "path")?;
+        let cwd = cwd.to_str().unwrap().to_string();
+        vm.call_method(&sys_path, "insert", vec![vm.new_int(0), vm.new_str(cwd)])?;
+    }
     let runpy = vm.import("runpy", &[], 0)?;
     let run_module_as_main = vm.get_attribute(runpy, "_run_module_as_main")?;
     vm.invoke(&run_module_as_main, vec![vm.new_str(module.to_owned())])?;
@@ -397,13 +493,26 @@ fn run_script(vm: &VirtualMachine, scope: Scope, script_file: &str) -> PyResult<
         process::exit(1);
     };
 
-    let dir = file_path.parent().unwrap().to_str().unwrap().to_string();
+    // Like CPython, put the script's directory on sys.path as an absolute path so
+    // that the __path__ and __file__ of modules imported from it are absolute too.
+    let dir = file_path
+        .canonicalize()
+        .unwrap_or_else(|_| file_path.clone())
+        .parent()
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
     let sys_path = vm.get_attribute(vm.sys_module.clone(), "path").unwrap();
     vm.call_method(&sys_path, "insert", vec![vm.new_int(0), vm.new_str(dir)])?;
 
     match util::read_file(&file_path) {
         Ok(source) => {
-            _run_string(vm, scope, &source, file_path.to_str().unwrap().to_string())?;
+            let file_path = file_path.to_str().unwrap().to_string();
+            scope
+                .globals
+                .set_item("__file__", vm.new_str(file_path.clone()), vm)?;
+            _run_string(vm, scope, &source, file_path)?;
         }
         Err(err) => {
             error!(
@@ -434,6 +543,37 @@ fn test_run_script() {
     assert!(r.is_ok());
 }
 
+#[test]
+fn test_run_module() {
+    let mut settings: PySettings = Default::default();
+    settings.argv = vec!["-m".to_owned()];
+    let vm = VirtualMachine::new(settings);
+    import::init_importlib(&vm, true).unwrap();
+
+    let cwd = env::current_dir().unwrap();
+    let sys_path = vm.get_attribute(vm.sys_module.clone(), "path").unwrap();
+    for dir in &["Lib", "tests/snippets"] {
+        let dir = cwd.join(dir).to_str().unwrap().to_string();
+        vm.call_method(&sys_path, "insert", vec![vm.new_int(0), vm.new_str(dir)])
+            .unwrap();
+    }
+    let scope = vm.new_scope_with_builtins();
+    let main_module = vm.new_module("__main__", scope.globals.clone());
+    vm.get_attribute(vm.sys_module.clone(), "modules")
+        .unwrap()
+        .set_item("__main__", main_module, &vm)
+        .unwrap();
+
+    let r = run_module(&vm, "argv_main");
+    assert!(r.is_ok());
+
+    let argv = vm.get_attribute(vm.sys_module.clone(), "argv").unwrap();
+    let argv: Vec<PyObjectRef> = vm.extract_elements(&argv).unwrap();
+    let argv0 = vm.to_str(&argv[0]).unwrap();
+    let expected = cwd.join("tests/snippets").join("argv_main.py");
+    assert_eq!(PathBuf::from(argv0.as_str()), expected);
+}
+
 enum ShellExecResult {
     Ok,
     PyErr(PyObjectRef),
@@ -555,10 +695,7 @@ fn run_shell(vm: &VirtualMachine, scope: Scope) -> PyResult<()> {
             Err(ReadlineError::Interrupted) => {
                 continuing = false;
                 input.clear();
-                let keyboard_interrupt = vm
-                    .new_empty_exception(vm.ctx.exceptions.keyboard_interrupt.clone())
-                    .unwrap();
-                Err(keyboard_interrupt)
+                stdlib::signal::interrupt(vm)
             }
             Err(ReadlineError::Eof) => {
                 break;
@@ -570,6 +707,10 @@ fn run_shell(vm: &VirtualMachine, scope: Scope) -> PyResult<()> {
         };
 
         if let Err(exc) = result {
+            if is_system_exit(vm, &exc) {
+                repl.save_history(&repl_history_path).unwrap();
+                return Err(exc);
+            }
             print_exception(vm, &exc);
         }
     }
@@ -606,7 +747,12 @@ fn run_shell(vm: &VirtualMachine, scope: Scope) -> PyResult<()> {
         match shell_exec(vm, &line, scope.clone()) {
             ShellExecResult::Ok => {}
             ShellExecResult::Continue => println!("Unexpected EOF"),
-            ShellExecResult::PyErr(exc) => print_exception(vm, &exc),
+            ShellExecResult::PyErr(exc) => {
+                if is_system_exit(vm, &exc) {
+                    return Err(exc);
+                }
+                print_exception(vm, &exc);
+            }
         }
         print_prompt(vm);
     }
@@ -0,0 +1,6 @@
+import sys
+
+# Whether run as a script or with -m, sys.argv[0] names this file.
+assert __name__ == "__main__"
+assert sys.argv[0].endswith("argv_main.py")
+assert __file__.endswith("argv_main.py")
@@ -13,3 +13,70 @@ assert a1 == array("B", [3, 2, 1, 0])
 a1.extend([4, 5, 6, 7])
 
 assert a1 == array("h", [3, 2, 1, 0, 4, 5, 6, 7])
+
+from testutils import assert_raises
+import struct
+
+a2 = array("i", [1, 2, 3])
+assert a2.itemsize == struct.calcsize("i")
+assert a2.tobytes() == struct.pack("3i", 1, 2, 3)
+assert array("l").itemsize >= array("i").itemsize
+assert array("q").itemsize == 8
+assert array("d", [1, 2.5]).tolist() == [1.0, 2.5]
+assert len(a2) == 3
+assert a2.tolist() == [1, 2, 3]
+assert repr(a2) == "array('i', [1, 2, 3])"
+assert repr(array("d")) == "array('d')"
+assert all(type(x) is int for x in a2)
+
+assert_raises(OverflowError, lambda: array("b", [128]))
+assert_raises(OverflowError, lambda: array("B", [-1]))
+assert_raises(OverflowError, lambda: array("H").append(2 ** 16))
+assert_raises(TypeError, lambda: array("i", [1.5]))
+assert_raises(ValueError, lambda: array("x"))
+
+# indexing and slicing
+assert a2[-1] == 3
+assert_raises(IndexError, lambda: a2[3])
+assert_raises(IndexError, lambda: a2[-4])
+assert a2[1:] == array("i", [2, 3])
+assert a2[::-1] == array("i", [3, 2, 1])
+a2[0] = 10
+assert a2.tolist() == [10, 2, 3]
+a2[1:2] = array("i", [7, 8, 9])
+assert a2.tolist() == [10, 7, 8, 9, 3]
+a2[::2] = array("i", [0, 0, 0])
+assert a2.tolist() == [0, 7, 0, 9, 0]
+del a2[1]
+del a2[1::2]
+assert a2.tolist() == [0, 9]
+assert_raises(TypeError, lambda: a2.__setitem__(slice(0, 1), [1]))
+
+# insert clamps out of range positions, pop doesn't
+a3 = array("h")
+a3.insert(5, 1)
+a3.insert(-5, 0)
+assert a3.tolist() == [0, 1]
+assert a3.pop() == 1
+assert a3.pop(0) == 0
+assert_raises(IndexError, lambda: a3.pop())
+
+a3.frombytes(array("h", [4, 5]).tobytes())
+a3.fromlist([6, 7])
+assert_raises(OverflowError, lambda: a3.fromlist([8, 2 ** 20]))
+assert a3.tolist() == [4, 5, 6, 7]
+a3.remove(5)
+assert a3.tolist() == [4, 6, 7]
+
+address, length = a3.buffer_info()
+assert address != 0 and length == 3
+
+# the buffer protocol
+assert bytes(array("B", [1, 2])) == b"\x01\x02"
+m = memoryview(array("i", [5, 6]))
+assert m.format == "i"
+assert m.itemsize == a2.itemsize
+assert m.nbytes == 2 * a2.itemsize
+assert m.tolist() == [5, 6]
+assert m.tobytes() == array("i", [5, 6]).tobytes()
+assert len(m) == 2
@@ -0,0 +1,50 @@
+from testutils import assertRaises
+
+assert True
+assert 1 == 1, "not evaluated"
+
+with assertRaises(AssertionError):
+    assert False
+
+try:
+    assert 1 == 2, "one is not two"
+except AssertionError as e:
+    assert e.args == ("one is not two",)
+else:
+    assert False, "assert did not raise"
+
+try:
+    assert []
+except AssertionError as e:
+    assert e.args == ()
+
+# the message is only evaluated when the assertion fails
+calls = []
+def message():
+    calls.append(1)
+    return "failed"
+
+assert True, message()
+assert calls == []
+with assertRaises(AssertionError):
+    assert 0, message()
+assert calls == [1]
+
+assert __debug__ is True
+
+# with optimizations turned on assertions are compiled away
+source = "calls = []\nassert False, calls.append(1)\ndebug = __debug__\n"
+namespace = {}
+exec(compile(source, "<test>", "exec", optimize=1), namespace)
+assert namespace["calls"] == []
+assert namespace["debug"] is False
+
+namespace = {}
+exec(compile(source, "<test>", "exec", optimize=2), namespace)
+assert namespace["calls"] == []
+
+with assertRaises(AssertionError):
+    exec(compile(source, "<test>", "exec", optimize=0), {})
+
+with assertRaises(ValueError):
+    compile(source, "<test>", "exec", optimize=3)
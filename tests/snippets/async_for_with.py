@@ -0,0 +1,105 @@
+import asyncio
+from testutils import assertRaises
+
+
+class AsyncRange:
+    def __init__(self, n):
+        self.n = n
+        self.i = 0
+
+    def __aiter__(self):
+        return self
+
+    async def __anext__(self):
+        if self.i >= self.n:
+            raise StopAsyncIteration
+        self.i += 1
+        return self.i - 1
+
+
+async def collect(n):
+    result = []
+    async for x in AsyncRange(n):
+        result.append(x)
+    else:
+        result.append('done')
+    return result
+
+
+assert asyncio.run(collect(3)) == [0, 1, 2, 'done']
+assert asyncio.run(collect(0)) == ['done']
+
+
+async def break_early():
+    result = []
+    async for x in AsyncRange(10):
+        if x == 2:
+            break
+        result.append(x)
+    else:
+        result.append('not reached')
+    return result
+
+
+assert asyncio.run(break_early()) == [0, 1]
+
+
+class Manager:
+    def __init__(self, suppress=False):
+        self.log = []
+        self.suppress = suppress
+
+    async def __aenter__(self):
+        self.log.append('enter')
+        return self
+
+    async def __aexit__(self, exc_type, exc_val, exc_tb):
+        self.log.append(('exit', exc_type))
+        return self.suppress
+
+
+async def use_manager(manager, fail):
+    async with manager as m:
+        m.log.append('body')
+        if fail:
+            raise ValueError
+    return 'finished'
+
+
+m = Manager()
+assert asyncio.run(use_manager(m, False)) == 'finished'
+assert m.log == ['enter', 'body', ('exit', None)]
+
+m = Manager()
+with assertRaises(ValueError):
+    asyncio.run(use_manager(m, True))
+assert m.log == ['enter', 'body', ('exit', ValueError)]
+
+m = Manager(suppress=True)
+assert asyncio.run(use_manager(m, True)) == 'finished'
+assert m.log == ['enter', 'body', ('exit', ValueError)]
+
+
+async def return_from_with(manager):
+    async with manager, Manager():
+        return 'returned'
+
+
+m = Manager()
+assert asyncio.run(return_from_with(m)) == 'returned'
+assert m.log == ['enter', ('exit', None)]
+
+
+async def bad_async_for():
+    async for x in [1, 2]:
+        pass
+
+
+with assertRaises(TypeError):
+    asyncio.run(bad_async_for())
+
+for source in ['async for x in y: pass', 'async with x: pass',
+               'def f():\n    async for x in y: pass\n',
+               'def f():\n    async with x: pass\n']:
+    with assertRaises(SyntaxError):
+        compile(source, '<test>', 'exec')
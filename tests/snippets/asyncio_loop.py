@@ -0,0 +1,119 @@
+import asyncio
+import time
+from testutils import assertRaises
+
+
+async def main():
+    await asyncio.sleep(0)
+    await asyncio.sleep(0)
+    return 'done'
+
+
+assert asyncio.run(main()) == 'done'
+
+# tasks interleave whenever one of them suspends
+log = []
+
+
+async def worker(name, n):
+    for i in range(n):
+        log.append((name, i))
+        await asyncio.sleep(0)
+    return name
+
+
+async def run_gather():
+    return await asyncio.gather(worker('a', 2), worker('b', 3))
+
+
+assert asyncio.run(run_gather()) == ['a', 'b']
+assert log == [('a', 0), ('b', 0), ('a', 1), ('b', 1), ('b', 2)]
+
+
+# timers fire in order of their deadline, not of their creation
+async def delayed(delay, value):
+    await asyncio.sleep(delay)
+    log.append(value)
+    return value
+
+
+async def run_timers():
+    start = time.monotonic()
+    results = await asyncio.gather(delayed(0.02, 'slow'), delayed(0.01, 'fast'))
+    return results, time.monotonic() - start
+
+
+log = []
+results, elapsed = asyncio.run(run_timers())
+assert results == ['slow', 'fast']
+assert log == ['fast', 'slow']
+assert elapsed >= 0.02
+
+
+# create_task runs a coroutine concurrently
+async def run_create_task():
+    task = asyncio.create_task(worker('task', 1))
+    assert not task.done()
+    result = await task
+    assert task.done()
+    return result
+
+
+assert asyncio.run(run_create_task()) == 'task'
+
+
+# exceptions propagate through awaited tasks and gather
+async def fail():
+    await asyncio.sleep(0)
+    raise ValueError('failed')
+
+
+async def run_failing():
+    return await asyncio.gather(fail(), worker('c', 1))
+
+
+with assertRaises(ValueError):
+    asyncio.run(run_failing())
+
+
+async def run_return_exceptions():
+    return await asyncio.gather(fail(), return_exceptions=True)
+
+
+(exc,) = asyncio.run(run_return_exceptions())
+assert isinstance(exc, ValueError)
+
+
+# futures can be resolved from a callback
+async def run_future():
+    loop = asyncio.get_running_loop()
+    future = loop.create_future()
+    loop.call_later(0.01, future.set_result, 42)
+    return await future
+
+
+assert asyncio.run(run_future()) == 42
+
+
+# cancelling a task throws CancelledError into it
+async def run_cancel():
+    task = asyncio.create_task(asyncio.sleep(10))
+    await asyncio.sleep(0)
+    task.cancel()
+    try:
+        await task
+    except asyncio.CancelledError:
+        return task.cancelled()
+
+
+assert asyncio.run(run_cancel()) is True
+
+loop = asyncio.new_event_loop()
+assert loop.run_until_complete(worker('loop', 1)) == 'loop'
+loop.close()
+assert loop.is_closed()
+
+with assertRaises(RuntimeError):
+    asyncio.get_running_loop()
+with assertRaises(ValueError):
+    asyncio.run(worker)
@@ -89,3 +89,80 @@ with assertRaises(KeyError):
     getattr(GetRaise(KeyError()), 'a')
 with assertRaises(KeyError):
     getattr(GetRaise(KeyError()), 'a', 11)
+
+
+class Target:
+    x = 1
+
+    def method(self):
+        return 2
+
+
+class LoggingProxy:
+    def __init__(self, target):
+        object.__setattr__(self, 'reads', [])
+        object.__setattr__(self, 'target', target)
+
+    def __getattribute__(self, name):
+        object.__getattribute__(self, 'reads').append(name)
+        return getattr(object.__getattribute__(self, 'target'), name)
+
+
+proxy = LoggingProxy(Target())
+assert proxy.x == 1
+assert proxy.method() == 2
+assert not hasattr(proxy, 'missing')
+assert object.__getattribute__(proxy, 'reads') == ['x', 'method', 'missing']
+
+
+# __getattr__ runs when __getattribute__ raises AttributeError
+class Fallback:
+    y = 5
+
+    def __getattribute__(self, name):
+        if name == 'hidden':
+            raise AttributeError(name)
+        return object.__getattribute__(self, name)
+
+    def __getattr__(self, name):
+        return 'fallback_' + name
+
+    @property
+    def broken(self):
+        raise AttributeError('broken')
+
+
+fallback = Fallback()
+assert fallback.y == 5
+assert fallback.hidden == 'fallback_hidden'
+assert fallback.other == 'fallback_other'
+assert fallback.broken == 'fallback_broken'
+# explicit object.__getattribute__ doesn't consult __getattr__
+with assertRaises(AttributeError):
+    object.__getattribute__(fallback, 'other')
+
+
+class Meta(type):
+    def __getattr__(cls, name):
+        return (cls.__name__, name)
+
+
+class WithMeta(metaclass=Meta):
+    z = 3
+
+
+assert WithMeta.z == 3
+assert WithMeta.missing == ('WithMeta', 'missing')
+
+
+class Recursive:
+    def __getattr__(self, name):
+        return getattr(self, name)
+
+
+import sys
+limit = sys.getrecursionlimit()
+sys.setrecursionlimit(100)
+with assertRaises(RecursionError):
+    Recursive().x
+sys.setrecursionlimit(limit)
@@ -142,4 +142,27 @@ class TestLenThrowError:
 
 
 with assertRaises(TypeError):
-    bool(TestLenThrowError())
\ No newline at end of file
+    bool(TestLenThrowError())
+
+class OnlyLen:
+    def __init__(self, n):
+        self.n = n
+
+    def __len__(self):
+        return self.n
+
+
+assert not OnlyLen(0)
+assert OnlyLen(3)
+assert bool(OnlyLen(0)) is False
+with assertRaises(ValueError):
+    bool(OnlyLen(-1))
+
+
+class BoolReturnsInt:
+    def __bool__(self):
+        return 1
+
+
+with assertRaises(TypeError):
+    bool(BoolReturnsInt())
@@ -11,3 +11,17 @@ assert bin(a) == '0b1' + '0' * 65
 assert bin(a-1) == '0b' + '1' * 65
 assert bin(-(a)) == '-0b1' + '0' * 65
 assert bin(-(a-1)) == '-0b' + '1' * 65
+
+
+class Index:
+    def __index__(self):
+        return 10
+
+
+assert bin(Index()) == '0b1010'
+assert bin(True) == '0b1'
+
+from testutils import assert_raises
+
+assert_raises(TypeError, lambda: bin(1.0), 'bin() called with float')
+assert_raises(TypeError, lambda: bin('1'), 'bin() called with str')
@@ -27,6 +27,8 @@ assert 1j != 10 ** 1000
 
 # __mul__, __rmul__
 
+assert (1 + 2j) * (1 - 2j) == (5 + 0j)
+
 assert complex(2, -3) * complex(-5, 7) == complex(11, 29)
 assert complex(2, -3) * 5 == complex(10, -15)
 assert 5 * complex(2, -3) == complex(2, -3) * 5
@@ -35,6 +37,9 @@ assert 5 * complex(2, -3) == complex(2, -3) * 5
 
 assert complex(2, -3) / 2 == complex(1, -1.5)
 assert 5 / complex(3, -4) == complex(0.6, 0.8)
+assert_raises(ZeroDivisionError, lambda: complex(1, 2) / 0j)
+assert_raises(ZeroDivisionError, lambda: 1 / complex(0, 0))
+assert_raises(ZeroDivisionError, lambda: complex(1, 2) / 0)
 
 # __mod__, __rmod__
 
@@ -71,9 +76,14 @@ assert_raises(
 
 # __pow__, __rpow__
 
-# assert 1j ** 2 == -1
+assert 1j ** 2 == -1
 assert complex(1) ** 2 == 1
 assert 2 ** complex(2) == 4
+assert (1 + 2j) ** 2 == complex(-3, 4)
+assert (1 + 2j) ** 0 == 1
+assert 0j ** 2 == 0
+assert_raises(ZeroDivisionError, lambda: 0j ** -1)
+assert_raises(ZeroDivisionError, lambda: 0j ** 1j)
 
 # __neg__
 
@@ -145,6 +155,73 @@ assert '(1+1j)' == str(1+1j)
 assert '(1-1j)' == str(1-1j)
 assert '(1+1j)' == repr(1+1j)
 assert '(1-1j)' == repr(1-1j)
+assert repr(2j) == '2j'
+assert repr(-2j) == '(-0-2j)'
+assert repr(complex(1.5, -0.25)) == '(1.5-0.25j)'
+assert repr(complex(-1, 0)) == '(-1+0j)'
+assert repr(complex(float('inf'), float('nan'))) == '(inf+nanj)'
+assert repr(1e100j) == '1e+100j'
+assert repr(complex(1e-7, 1)) == '(1e-07+1j)'
+assert repr(complex(0.1, 1e16)) == '(0.1+1e+16j)'
+
+# __new__
+assert complex() == 0j
+assert complex(1, 2) == 1 + 2j
+assert complex(1.5) == 1.5 + 0j
+assert complex('1+2j') == 1 + 2j
+assert complex(' ( -1.5e3-2.5E-2J ) ') == complex(-1500, -0.025)
+assert complex('j') == 1j
+assert complex('-j') == -1j
+assert complex('3') == 3
+assert complex('1e+5j') == 100000j
+assert complex(1 + 2j) == 1 + 2j
+assert complex(1 + 2j, 1j) == complex(0, 2)
+assert complex(True, 1.5) == complex(1, 1.5)
+assert_raises(ValueError, lambda: complex('1++2j'))
+assert_raises(ValueError, lambda: complex(''))
+assert_raises(TypeError, lambda: complex('1', 2))
+assert_raises(TypeError, lambda: complex(1, '2'))
+assert_raises(TypeError, lambda: complex([]))
+
+
+class ComplexLike:
+    def __complex__(self):
+        return 4 - 3j
+
+
+assert complex(ComplexLike()) == 4 - 3j
+
+
+class FloatComplex:
+    def __complex__(self):
+        return 1.5
+
+
+assert_raises(TypeError, lambda: complex(FloatComplex()))
+
+
+class FloatLike:
+    def __init__(self, value):
+        self.value = value
+
+    def __float__(self):
+        return self.value
+
+
+class IndexLike:
+    def __index__(self):
+        return 7
+
+
+assert complex(FloatLike(1.5)) == 1.5 + 0j
+assert complex(IndexLike()) == 7 + 0j
+assert complex(FloatLike(1.5), FloatLike(2.5)) == 1.5 + 2.5j
+assert complex(1, 2j) == -1 + 0j
+assert_raises(TypeError, lambda: complex(1, ComplexLike()))
+assert_raises(TypeError, lambda: complex(FloatLike('1')))
+assert_raises(TypeError, lambda: complex(object()))
+assert +(1 - 1j) == 1 - 1j
+assert abs(3 + 4j) == 5.0
 
 # __getnewargs__
 assert (3 + 5j).__getnewargs__() == (3.0, 5.0)
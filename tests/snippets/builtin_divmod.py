@@ -7,3 +7,36 @@ assert divmod(-86340, 86400) == (-1, 60)
 
 assert_raises(ZeroDivisionError, lambda: divmod(5, 0), 'divmod by zero')
 assert_raises(ZeroDivisionError, lambda: divmod(5.0, 0.0), 'divmod by zero')
+
+assert divmod(-7, 3) == (-3, 2)
+assert divmod(7, -3) == (-3, -2)
+assert divmod(-7, -3) == (2, -1)
+assert divmod(2 ** 100 + 1, 2 ** 50) == (2 ** 50, 1)
+assert divmod(-(10 ** 30), 7) == (-(10 ** 30) // 7, -(10 ** 30) % 7)
+assert divmod(True, 2) == (0, 1)
+
+assert divmod(7.5, 2) == (3.0, 1.5)
+assert divmod(-7.5, 2) == (-4.0, 0.5)
+assert divmod(7.5, -2) == (-4.0, -0.5)
+assert divmod(7, 2.5) == (2.0, 2.0)
+assert -7.5 % 2 == 0.5
+assert 7.5 % -2 == -0.5
+assert -7.5 // 2 == -4.0
+assert str(divmod(-0.0, 1.0)) == '(-0.0, 0.0)'
+assert str(divmod(1.0, -1.0)) == '(-1.0, -0.0)'
+
+assert_raises(ZeroDivisionError, lambda: divmod(2 ** 100, 0))
+assert_raises(ZeroDivisionError, lambda: divmod(5, 0.0))
+assert_raises(TypeError, lambda: divmod('a', 1))
+
+
+class Divisible:
+    def __divmod__(self, other):
+        return 'divmod', other
+
+    def __rdivmod__(self, other):
+        return 'rdivmod', other
+
+
+assert divmod(Divisible(), 3) == ('divmod', 3)
+assert divmod(3, Divisible()) == ('rdivmod', 3)
@@ -20,3 +20,10 @@ class Counter(object):
 it = enumerate(Counter())
 assert next(it) == (0, 1)
 assert next(it) == (1, 2)
+
+it = enumerate("abc", 1)
+assert next(it) == (1, "a")
+assert list(it) == [(2, "b"), (3, "c")]
+
+assert list(enumerate("ab", start=5)) == [(5, "a"), (6, "b")]
+assert list(enumerate(iterable="ab")) == [(0, "a"), (1, "b")]
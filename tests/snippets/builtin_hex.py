@@ -4,3 +4,21 @@ assert hex(16) == '0x10'
 assert hex(-16) == '-0x10'
 
 assert_raises(TypeError, lambda: hex({}), 'ord() called with dict')
+assert_raises(TypeError, lambda: hex(255.0), 'hex() called with float')
+
+
+class Index:
+    def __init__(self, value):
+        self.value = value
+
+    def __index__(self):
+        return self.value
+
+
+assert hex(Index(255)) == '0xff'
+assert hex(Index(-255)) == '-0xff'
+assert oct(Index(8)) == '0o10'
+assert oct(Index(-8)) == '-0o10'
+assert oct(0) == '0o0'
+assert_raises(TypeError, lambda: oct(8.0), 'oct() called with float')
+assert_raises(TypeError, lambda: hex(Index(1.5)), '__index__ returned float')
@@ -1,2 +1,33 @@
+from testutils import assert_raises
+
 assert 3 == len([1,2,3])
 assert 2 == len((1,2))
+
+class Sized:
+    def __init__(self, n):
+        self.n = n
+
+    def __len__(self):
+        return self.n
+
+
+class Index:
+    def __index__(self):
+        return 4
+
+
+assert len(Sized(7)) == 7
+assert len(Sized(Index())) == 4
+assert len(Sized(True)) == 1
+assert_raises(ValueError, lambda: len(Sized(-1)))
+assert_raises(TypeError, lambda: len(Sized('3')))
+assert_raises(TypeError, lambda: len(Sized(1.5)))
+assert_raises(OverflowError, lambda: len(Sized(2 ** 100)))
+assert_raises(TypeError, lambda: len(5))
+
+
+class NoLen:
+    __len__ = None
+
+
+assert_raises(TypeError, lambda: len(NoLen()))
@@ -32,3 +32,8 @@ def mapping(x):
 
 
 assert list(map(mapping, [1, 2, 0, 4, 5])) == [1, 2]
+
+# map stops at the shortest iterable and can be partially consumed
+it = map(lambda x, y: x * y, [1, 2, 3], "abcd")
+assert next(it) == "a"
+assert list(it) == ["bb", "ccc"]
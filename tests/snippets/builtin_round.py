@@ -19,3 +19,54 @@ with assertRaises(TypeError):
     round(0, 0.0)
 with assertRaises(TypeError):
     round(0.0, 0.0)
+
+assert round(2.5) == 2
+assert round(3.5) == 4
+assert round(-2.5) == -2
+assert round(0.125, 2) == 0.12
+assert round(0.375, 2) == 0.38
+assert round(2.675, 2) == 2.67
+assert isinstance(round(2.675, 2), float)
+assert round(1234.5678, -2) == 1200.0
+assert isinstance(round(1234.5678, -2), float)
+assert round(-0.4, 0) == 0.0
+assert round(1.5, 400) == 1.5
+assert round(1.5, -400) == 0.0
+assert round(float('inf'), 2) == float('inf')
+assert round(25.0, -1) == 20.0
+assert round(35.0, -1) == 40.0
+assert round(-25.0, -1) == -20.0
+assert round(25.0001, -1) == 30.0
+assert round(1e300, -300) == 1e300
+assert round(1e22, -22) == 1e22
+with assertRaises(OverflowError):
+    round(1.7e308, -308)
+
+assert round(1234, -2) == 1200
+assert round(1250, -2) == 1200
+assert round(1350, -2) == 1400
+assert round(-1250, -2) == -1200
+assert round(-1251, -2) == -1300
+assert round(5, -1) == 0
+assert round(15, -1) == 20
+assert round(123, 2) == 123
+assert round(10 ** 30 + 5 * 10 ** 9, -10) == 10 ** 30
+assert round(10 ** 30 + 15 * 10 ** 9, -10) == 10 ** 30 + 2 * 10 ** 10
+assert round(12345, -100) == 0
+
+
+class Roundable:
+    def __round__(self, ndigits=None):
+        return ('rounded', ndigits)
+
+
+assert round(Roundable()) == ('rounded', None)
+assert round(Roundable(), None) == ('rounded', None)
+assert round(Roundable(), 3) == ('rounded', 3)
+
+with assertRaises(TypeError):
+    round('1.5')
+with assertRaises(OverflowError):
+    round(float('inf'))
+with assertRaises(ValueError):
+    round(float('nan'))
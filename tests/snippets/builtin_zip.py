@@ -1,3 +1,5 @@
+from testutils import assertRaises
+
 assert list(zip(['a', 'b', 'c'], range(3), [9, 8, 7, 99])) == [('a', 0, 9), ('b', 1, 8), ('c', 2, 7)]
 
 assert list(zip(['a', 'b', 'c'])) == [('a',), ('b',), ('c',)]
@@ -22,3 +24,24 @@ class Counter(object):
 it = zip(Counter(), Counter(3))
 assert next(it) == (1, 4)
 assert next(it) == (2, 5)
+
+# zip is lazy and can be partially consumed
+it = zip([1, 2, 3], "abc")
+assert next(it) == (1, "a")
+assert list(it) == [(2, "b"), (3, "c")]
+assert list(it) == []
+
+# strict mode
+assert list(zip([1, 2], "ab", strict=True)) == [(1, "a"), (2, "b")]
+assert list(zip(strict=True)) == []
+with assertRaises(ValueError):
+    list(zip([1, 2], "a", strict=True))
+with assertRaises(ValueError):
+    list(zip([1], "ab", strict=True))
+with assertRaises(ValueError):
+    list(zip([1], [2], "ab", strict=True))
+
+it = zip([1, 2], [3], strict=True)
+assert next(it) == (1, 3)
+with assertRaises(ValueError):
+    next(it)
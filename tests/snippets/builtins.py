@@ -11,3 +11,7 @@ assert 3 == eval('1+2')
 
 code = compile('5+3', 'x.py', 'eval')
 assert eval(code) == 8
+
+assert len.__name__ == 'len'
+assert repr(len) == '<built-in function len>'
+assert [].append.__name__ == 'append'
@@ -706,3 +706,13 @@ a[1:3] = b'test'
 assert a == bytearray(b'\x00testt')
 a[:6] = memoryview(b'test')
 assert a == bytearray(b'test')
+
+# removeprefix / removesuffix
+a = bytearray(b'test_foo')
+assert a.removeprefix(b'test_') == bytearray(b'foo')
+assert a.removesuffix(b'_foo') == bytearray(b'test')
+assert a.removesuffix(b'test') == a
+assert a.removesuffix(b'test') is not a
+assert type(a.removeprefix(b'')) is bytearray
+with assertRaises(TypeError):
+    a.removesuffix('foo')
@@ -62,6 +62,7 @@ assert list(bytes(3)) == [0, 0, 0]
 
 # add
 assert a + b == b"abcdab"
+assert type(a + b) is bytes
 
 # contains
 assert b"ab" in b"abcd"
@@ -605,3 +606,59 @@ assert b'\xc2\xae\x75\x73\x74'.decode('ascii', 'ignore') == 'ust'
 assert b'\xc2\xae\x75\x73\x74'.decode('utf-8') == '®ust'
 assert b'\xc2\xae\x75\x73\x74'.decode() == '®ust'
 assert b'\xe4\xb8\xad\xe6\x96\x87\xe5\xad\x97'.decode('utf-8') == '中文字'
+
+# printf-style formatting
+assert b'%d' % 42 == b'42'
+assert b'%5.2f|%x' % (3.14159, 255) == b' 3.14|ff'
+assert b'%s and %b' % (b'spam', bytearray(b'eggs')) == b'spam and eggs'
+assert b'%r %a' % ('\xe9', b'x') == b"'\\xe9' b'x'"
+assert b'%c%c' % (104, b'i') == b'hi'
+assert b'\xff%s' % b'\xfe' == b'\xff\xfe'
+assert b'%(name)s is %(age)d' % {b'name': b'Guido', b'age': 63} == b'Guido is 63'
+assert bytearray(b'%03d') % 7 == bytearray(b'007')
+with assertRaises(TypeError):
+    b'%s' % 'text'
+with assertRaises(TypeError):
+    b'%d %d' % (1,)
+with assertRaises(OverflowError):
+    b'%c' % 256
+
+# escape sequences
+assert b'\x41' == b'A'
+assert b'\101' == b'A'
+assert len(b'\u00e9') == 6
+assert b'\N{DASH}' == b'\\N{DASH}'
+assert rb'\x41' == b'\\x41'
+with assertRaises(SyntaxError):
+    compile(r"b'\x4'", '', 'eval')
+with assertRaises(SyntaxError):
+    compile("b'é'", '', 'eval')
+
+# __bytes__
+class BytesConvertible:
+    def __bytes__(self):
+        return b'converted'
+
+assert bytes(BytesConvertible()) == b'converted'
+assert bytearray([1]) + bytes(BytesConvertible()) == b'\x01converted'
+
+class BadBytes:
+    def __bytes__(self):
+        return 'text'
+
+with assertRaises(TypeError):
+    bytes(BadBytes())
+with assertRaises(TypeError):
+    bytes(object())
+
+# removeprefix / removesuffix
+assert b'test_foo'.removeprefix(b'test_') == b'foo'
+assert b'test_foo'.removeprefix(b'foo') == b'test_foo'
+assert b'test_foo'.removeprefix(b'') == b'test_foo'
+assert b'foo_test'.removesuffix(b'_test') == b'foo'
+assert b'foo_test'.removesuffix(b'foo') == b'foo_test'
+assert b'foo_test'.removesuffix(bytearray(b'')) == b'foo_test'
+assert b'foo_test'.removesuffix(memoryview(b'test')) == b'foo_'
+assert type(b'foo'.removeprefix(b'f')) is bytes
+with assertRaises(TypeError):
+    b'abc'.removeprefix('a')
@@ -1,5 +1,6 @@
 
 from io import BytesIO
+from testutils import assertRaises
 
 def test_01():
     bytes_string =  b'Test String 1'
@@ -42,9 +43,29 @@ def test_04():
     assert f.seek(0) == 0
     assert f.read(4) == b'Test'
 
+def test_05():
+    """
+        Tests that truncate resizes to the given size or the
+        current position and rejects negative and non-integer sizes
+    """
+    f = BytesIO(b'Test String 5')
+
+    assert f.truncate(9) == 9
+    assert f.getvalue() == b'Test Stri'
+    f.seek(4)
+    assert f.truncate() == 4
+    assert f.getvalue() == b'Test'
+    assert f.truncate(None) == 4
+    with assertRaises(ValueError):
+        f.truncate(-1)
+    with assertRaises(TypeError):
+        f.truncate('x')
+    assert f.getvalue() == b'Test'
+
 if __name__ == "__main__":
     test_01()
     test_02()
     test_03()
     test_04()
+    test_05()
 
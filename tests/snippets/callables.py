@@ -1,3 +1,5 @@
+from testutils import assert_raises
+
 class Callable():
     def __init__(self):
         self.count = 0
@@ -9,3 +11,32 @@ class Callable():
 c = Callable()
 assert 1 == c()
 assert 2 == c()
+
+
+class Adder:
+    def __call__(self, a, b=2):
+        return a + b
+
+
+assert Adder()(1) == 3
+assert Adder()(1, b=5) == 6
+assert callable(Adder())
+
+
+class NotCallable:
+    pass
+
+
+try:
+    NotCallable()()
+except TypeError as e:
+    assert str(e) == "'NotCallable' object is not callable"
+else:
+    assert False, "calling an instance without __call__ should fail"
+
+
+class CallNone:
+    __call__ = None
+
+
+assert_raises(TypeError, lambda: CallNone()())
@@ -0,0 +1,65 @@
+from testutils import assert_raises
+
+
+class Registry:
+    def __class_getitem__(cls, item):
+        return (cls, item)
+
+
+assert Registry[int] == (Registry, int)
+assert Registry[int, str] == (Registry, (int, str))
+
+
+class SubRegistry(Registry):
+    pass
+
+
+assert SubRegistry['x'] == (SubRegistry, 'x')
+
+
+class Explicit:
+    @classmethod
+    def __class_getitem__(cls, item):
+        return '%s[%s]' % (cls.__name__, item.__name__)
+
+
+assert Explicit[int] == 'Explicit[int]'
+
+
+class Meta(type):
+    def __getitem__(cls, item):
+        return 'meta'
+
+
+class WithMeta(Registry, metaclass=Meta):
+    pass
+
+
+# the metaclass __getitem__ takes priority
+assert WithMeta[int] == 'meta'
+
+assert_raises(TypeError, lambda: int[int])
+assert_raises(TypeError, lambda: object()[0])
+
+alias = list[int]
+assert alias.__origin__ is list
+assert alias.__args__ == (int,)
+assert repr(alias) == 'list[int]'
+assert repr(dict[str, list[int]]) == 'dict[str, list[int]]'
+assert repr(tuple[int, ...]) == 'tuple[int, ...]'
+assert dict[str, int].__args__ == (str, int)
+assert list[int] == list[int]
+assert list[int] != list[str]
+assert hash(list[int]) == hash(list[int])
+assert alias('ab') == ['a', 'b']
+assert set[int]() == set()
+assert frozenset[str]('a') == frozenset('a')
+assert_raises(TypeError, lambda: isinstance([], list[int]))
+
+
+class MyList(list[int]):
+    pass
+
+
+assert MyList.__mro__ == (MyList, list, object)
+assert MyList([1]) == [1]
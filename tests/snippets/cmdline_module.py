@@ -0,0 +1,22 @@
+import os
+import subprocess
+import sys
+
+here = os.path.dirname(os.path.abspath(__file__))
+
+# `-m package` runs the package's __main__.py as the __main__ module
+p = subprocess.Popen(
+    [sys.executable, '-m', 'main_package', 'arg'], stdout=subprocess.PIPE, cwd=here
+)
+out, _ = p.communicate()
+p.wait()
+assert p.returncode == 0
+main_file = os.path.join(here, 'main_package', '__main__.py')
+assert eval(out.decode()) == (
+    '__main__',
+    'main_package',
+    'main_package.__main__',
+    main_file,
+    [main_file, 'arg'],
+    True,
+)
@@ -14,13 +14,14 @@ assert type(c2) == code_class
 # print(dir(c2))
 assert c2.co_argcount == 2
 # assert c2.co_cellvars == ()
-# assert isinstance(c2.co_code, bytes)
+assert isinstance(c2.co_code, bytes)
 assert "Constant String" in c2.co_consts, c2.co_consts
 print(c2.co_consts)
 assert 2 in c2.co_consts, c2.co_consts
 assert "code.py" in c2.co_filename
 assert c2.co_firstlineno == 5, str(c2.co_firstlineno)
-# assert isinstance(c2.co_flags, int) # 'OPTIMIZED, NEWLOCALS, NOFREE'
+assert isinstance(c2.co_flags, int) # 'OPTIMIZED, NEWLOCALS, NOFREE'
+assert c2.co_flags & 0x04 and c2.co_flags & 0x08  # VARARGS, VARKEYWORDS
 # assert c2.co_freevars == (), str(c2.co_freevars)
 assert c2.co_kwonlyargcount == 1, (c2.co_kwonlyargcount)
 # assert c2.co_lnotab == 0, c2.co_lnotab  # b'\x00\x01' # Line number table
@@ -29,3 +30,13 @@ assert c2.co_name == 'f', c2.co_name
 # assert c2.co_nlocals == 4, c2.co_nlocals #
 # assert c2.co_stacksize == 2, 'co_stacksize',
 # assert c2.co_varnames == ('x', 'y', 'power', 'z'), c2.co_varnames
+assert c2.co_varnames[:5] == ('x', 'y', 'power', 'args', 'kwargs'), c2.co_varnames
+assert {'print', 'code_class', 'type', 'c1'} <= set(c2.co_names), c2.co_names
+assert c2.co_nlocals >= 5, c2.co_nlocals
+assert c2.co_posonlyargcount == 0
+
+assert (lambda a, b: a).__code__.co_argcount == 2
+assert f.__globals__ is globals()
+assert f.__closure__ is None
+assert f.__defaults__ is None
+assert f.__kwdefaults__ == {'power': 1}
@@ -21,3 +21,88 @@ def test_type_error(x, y):
 
 test_type_error([], 0)
 test_type_error((), 0)
+test_type_error('a', 0)
+test_type_error(object(), object())
+
+try:
+    [] < 1
+except TypeError as e:
+    assert str(e) == "'<' not supported between instances of 'list' and 'int'"
+
+
+calls = []
+
+
+class Left:
+    def __lt__(self, other):
+        calls.append('Left.__lt__')
+        return NotImplemented
+
+    def __eq__(self, other):
+        calls.append('Left.__eq__')
+        return NotImplemented
+
+
+class Right:
+    def __gt__(self, other):
+        calls.append('Right.__gt__')
+        return True
+
+
+assert Left() < Right()
+assert calls == ['Left.__lt__', 'Right.__gt__']
+
+# neither side implements it: identity for ==/!=, TypeError for ordering
+calls = []
+left = Left()
+assert left == left
+assert not (left == Left())
+assert left != Left()
+assert_raises(TypeError, lambda: left < 1)
+assert_raises(TypeError, lambda: 1 > left)
+
+
+class Sub(Left):
+    def __gt__(self, other):
+        calls.append('Sub.__gt__')
+        return 'reflected'
+
+    def __eq__(self, other):
+        calls.append('Sub.__eq__')
+        return 'sub eq'
+
+
+# the reflected method of a subclass is tried first
+calls = []
+assert (Left() < Sub()) == 'reflected'
+assert calls == ['Sub.__gt__']
+calls = []
+assert (Left() == Sub()) == 'sub eq'
+assert calls == ['Sub.__eq__']
+
+
+class OnlyEq:
+    def __init__(self, value):
+        self.value = value
+
+    def __eq__(self, other):
+        if isinstance(other, OnlyEq):
+            return self.value == other.value
+        return NotImplemented
+
+
+assert OnlyEq(1) == OnlyEq(1)
+assert OnlyEq(1) != OnlyEq(2)
+assert not (OnlyEq(1) != OnlyEq(1))
+assert OnlyEq(1) != 1
+assert 1 != OnlyEq(1)
+assert 'x' != OnlyEq(1)
+
+
+class EqualToStrings:
+    def __eq__(self, other):
+        return isinstance(other, str)
+
+
+assert 'abc' == EqualToStrings()
+assert EqualToStrings() != 5
@@ -41,3 +41,53 @@ assert 'b' not in locals()
 assert 'c' not in locals()
 assert 'b' not in globals()
 assert 'c' not in globals()
+
+from testutils import assert_raises
+
+# the loop variable does not leak into the enclosing scope
+def f():
+    [i for i in range(3)]
+    return i
+assert_raises(NameError, f)
+
+def g():
+    {i: i for i in range(3)}
+    {i for i in range(3)}
+    list(i for i in range(3))
+    return 'i' in locals()
+assert not g()
+
+# the first iterable is evaluated in the enclosing scope
+def h():
+    n = 3
+    return [x * n for x in range(n)]
+assert h() == [0, 3, 6]
+
+class C:
+    values = [1, 2, 3]
+    doubled = [v * 2 for v in values]
+assert C.doubled == [2, 4, 6]
+
+# nested comprehensions see the variables of the outer ones
+matrix = [[i * j for j in range(3)] for i in range(3)]
+assert matrix == [[0, 0, 0], [0, 1, 2], [0, 2, 4]]
+assert [y for x in [[1, 2], [3]] for y in x] == [1, 2, 3]
+assert {k: [v for v in range(k)] for k in range(3)} == {0: [], 1: [0], 2: [0, 1]}
+
+# nested scopes in every part of a comprehension resolve their own names
+a, b, c = 1, 2, 3
+result = [
+    (lambda: a)() + (lambda: x)()
+    for x in [(lambda: b)()]
+    if [c for _ in (lambda: [1])()]
+]
+assert result == [3]
+assert {(lambda: a)(): (lambda: b)() for _ in 'x'} == {1: 2}
+
+# dict comprehensions evaluate the key before the value
+order = []
+def record(value):
+    order.append(value)
+    return value
+{record('key'): record('value') for _ in range(1)}
+assert order == ['key', 'value']
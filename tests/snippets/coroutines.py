@@ -0,0 +1,120 @@
+import asyncio
+import types
+from testutils import assertRaises
+
+
+async def double(x):
+    return x * 2
+
+
+async def main():
+    a = await double(1)
+    b = await double(a)
+    return a + b
+
+
+assert asyncio.run(main()) == 6
+
+coro = double(21)
+assert type(coro) is types.CoroutineType
+assert asyncio.iscoroutine(coro)
+with assertRaises(StopIteration):
+    coro.send(None)
+# a finished coroutine can't be awaited again
+with assertRaises(RuntimeError):
+    coro.send(None)
+
+
+class Awaitable:
+    def __await__(self):
+        value = yield 'suspended'
+        return value
+
+
+async def wait_for_awaitable():
+    return await Awaitable()
+
+
+coro = wait_for_awaitable()
+assert coro.send(None) == 'suspended'
+try:
+    coro.send(42)
+except StopIteration as e:
+    assert e.value == 42
+else:
+    assert False, "coroutine should have finished"
+
+
+# throw() raises the exception inside the coroutine
+async def catcher():
+    try:
+        await Awaitable()
+    except ValueError as e:
+        return 'caught ' + str(e)
+
+
+coro = catcher()
+coro.send(None)
+try:
+    coro.throw(ValueError, 'oops')
+except StopIteration as e:
+    assert e.value == 'caught oops'
+
+# close() stops a suspended coroutine
+closed = []
+
+
+async def closer():
+    try:
+        await Awaitable()
+    finally:
+        closed.append(True)
+
+
+coro = closer()
+coro.send(None)
+coro.close()
+assert closed == [True]
+coro.close()
+
+
+async def bad_await():
+    await 1
+
+
+with assertRaises(TypeError):
+    bad_await().send(None)
+
+with assertRaises(SyntaxError):
+    compile('await x', '<test>', 'exec')
+
+with assertRaises(SyntaxError):
+    compile('def f():\n    await x\n', '<test>', 'exec')
+
+
+# yield from returns the value of the sub-generator
+def inner():
+    yield 1
+    return 'done'
+
+
+def outer():
+    result = yield from inner()
+    yield result
+
+
+assert list(outer()) == [1, 'done']
+assert StopIteration(5).value == 5
+assert StopIteration().value is None
+
+# coroutines are registered with the collections.abc classes
+from collections.abc import Awaitable, Coroutine
+
+async def nothing():
+    pass
+
+c = nothing()
+assert isinstance(c, Coroutine)
+assert isinstance(c, Awaitable)
+assert not isinstance(outer(), Awaitable)
+c.close()
@@ -0,0 +1,4 @@
+# Imported by import_module.py to exercise imports relative to a parent package
+from .. import relative
+from ..relative import value
+from . import value2
@@ -32,3 +32,26 @@ class A(object):
 
 print("A.f\n")
 dis.dis(A.f)
+
+import io
+
+instructions = list(dis.get_instructions(lambda x: x + 1))
+opnames = [i.opname for i in instructions]
+assert "LOAD_CONST" in opnames
+assert opnames[-1].startswith("RETURN")
+const = instructions[opnames.index("LOAD_CONST")]
+assert const.argval == 1
+assert const.argrepr == "1"
+assert instructions[0].starts_line is not None
+assert [i.offset for i in instructions] == sorted(i.offset for i in instructions)
+
+bytecode = dis.Bytecode(A.g)
+assert bytecode.codeobj is A.g.__code__
+assert bytecode.first_line == A.g.__code__.co_firstlineno
+assert [i.opname for i in bytecode] == [i.opname for i in dis.get_instructions(A.g)]
+assert "FOR_ITER" in bytecode.dis()
+
+out = io.StringIO()
+dis.dis(lambda: [x for x in ()], file=out)
+assert "Disassembly of <code object <listcomp>" in out.getvalue()
+assert "RETURN_VALUE" in out.getvalue()
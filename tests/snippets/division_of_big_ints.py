@@ -17,3 +17,10 @@ assert (2 * 10**308) / 2 == 1e308
 
 # when dividing too big int by a float, the operation should fail
 assert_raises(OverflowError, lambda: (2 * 10**308) / 2.0, 'division of big int by float')
+
+# true division of ints is correctly rounded
+assert 10**30 / 3 == 3.333333333333333e+29
+assert (2**53 + 1) / 1 == 9007199254740992.0
+assert 1 / 10**400 == 0.0
+assert 0 / 10**400 == 0.0
+assert_raises(OverflowError, lambda: 10**400 / 1, 'int division too large for a float')
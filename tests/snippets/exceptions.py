@@ -15,6 +15,10 @@ exc = KeyError('message')
 assert str(exc) == "'message'"
 assert round_trip_repr(exc)
 
+# Other exceptions show a single argument with str()
+assert str(ValueError('message')) == 'message'
+assert str(ValueError(1)) == '1'
+
 exc = KeyError('message', 'another message')
 assert str(exc) == "('message', 'another message')"
 assert round_trip_repr(exc)
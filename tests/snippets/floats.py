@@ -141,7 +141,7 @@ assert_raises(ZeroDivisionError, lambda: divmod(2.0, 0))
 assert_raises(ZeroDivisionError, lambda: 2 / 0.0)
 assert_raises(ZeroDivisionError, lambda: 2 // 0.0)
 assert_raises(ZeroDivisionError, lambda: 2 % 0.0)
-# assert_raises(ZeroDivisionError, lambda: divmod(2, 0.0))
+assert_raises(ZeroDivisionError, lambda: divmod(2, 0.0))
 
 assert 1.2.__int__() == 1
 assert 1.2.__float__() == 1.2
@@ -226,3 +226,72 @@ assert float('nan').hex() == 'nan'
 # Test float exponent:
 assert 1 if 1else 0 == 1
 
+
+# repr() gives the shortest string that round-trips
+assert repr(0.1) == '0.1'
+assert repr(1.0) == '1.0'
+assert repr(-0.0) == '-0.0'
+assert repr(0.1 + 0.2) == '0.30000000000000004'
+assert repr(1e16) == '1e+16'
+assert repr(1234567890123456.0) == '1234567890123456.0'
+assert repr(0.0001) == '0.0001'
+assert repr(1e-5) == '1e-05'
+assert repr(1.5e300) == '1.5e+300'
+assert repr(5e-324) == '5e-324'
+assert repr(float('inf')) == 'inf'
+assert repr(float('-inf')) == '-inf'
+assert repr(float('nan')) == 'nan'
+assert str(0.1) == repr(0.1)
+for f in [0.1, 1/3, 2/3, 1e22, 1e23, 123.456, 2**0.5, 9007199254740993.0]:
+    assert float(repr(f)) == f
+    assert float(repr(-f)) == -f
+
+assert format(0.1, '.17g') == '0.10000000000000001'
+assert format(1.5, '') == '1.5'
+assert format(1.0, '.3') == '1.0'
+assert format(1e20, '.3') == '1e+20'
+assert format(3.14159, '.2f') == '3.14'
+assert format(-3.14159, '+.3e') == '-3.142e+00'
+assert format(1234.5, 'E') == '1.234500E+03'
+assert format(0.5, '>8.2f') == '    0.50'
+assert format(-0.5, '=+8.1f') == '-    0.5'
+assert format(2.5, '^7') == '  2.5  '
+assert format(1.0, '+') == '+1.0'
+assert format(float('inf'), 'F') == 'INF'
+assert format(float('-inf'), 'f') == '-inf'
+assert format(float('nan'), 'g') == 'nan'
+assert '{:.1f}'.format(2.25) == '2.2'
+assert_raises(ValueError, lambda: format(1.0, 'd'))
+assert_raises(ValueError, lambda: format(1.0, 'q'))
+assert_raises(ValueError, lambda: format(1.0, '.2fx'))
+assert format(1234.5, ',.2f') == '1,234.50'
+assert format(-1234567.0, '_') == '-1_234_567.0'
+assert format(1234.5, ',e') == '1.234500e+03'
+assert format(0.256, '.1%') == '25.6%'
+assert format(12.5, '%') == '1250.000000%'
+assert format(-0.5, '>+8.0%') == '    -50%'
+assert format(float('inf'), '%') == 'inf%'
+assert '{:,.1%}'.format(123.4) == '12,340.0%'
+
+
+class FloatLike:
+    def __init__(self, value):
+        self.value = value
+
+    def __float__(self):
+        return self.value
+
+
+class IndexLike:
+    def __index__(self):
+        return 7
+
+
+assert float(FloatLike(1.5)) == 1.5
+assert float(IndexLike()) == 7.0
+assert_raises(TypeError, lambda: float(FloatLike(1)), '__float__ returned int')
+assert_raises(TypeError, lambda: float(FloatLike('1.5')), '__float__ returned str')
+assert_raises(TypeError, lambda: float(object()), 'float() of object')
+assert math.sqrt(FloatLike(4.0)) == 2.0
+assert_raises(TypeError, lambda: math.sqrt(FloatLike(4)), '__float__ returned int')
+assert float(' 1.5\n') == 1.5
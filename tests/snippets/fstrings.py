@@ -1,3 +1,5 @@
+from testutils import assert_raises
+
 foo = 'bar'
 
 assert f"{''}" == ''
@@ -19,7 +21,7 @@ assert f"{{{(lambda x: f'hello, {x}')('world}')}" == '{hello, world}'
 # Normally `!` cannot appear outside of delimiters in the expression but
 # cpython makes an exception for `!=`, so we should too.
 
-# assert f'{1 != 2}' == 'True'
+assert f'{1 != 2}' == 'True'
 
 
 # conversion flags
@@ -53,3 +55,24 @@ assert f'>{v}' == '>\u262e'
 assert f'>{v!r}' == ">'\u262e'"
 assert f'>{v!s}' == '>\u262e'
 assert f'>{v!a}' == r">'\u262e'"
+
+# nested replacement fields and complex expressions
+class Obj:
+    attr = {'k': 'v'}
+
+obj = Obj()
+key = 'k'
+width = 5
+precision = 2
+assert f'{obj.attr[key]:>{width}}' == '    v'
+assert f'{ {1,2} }' == '{1, 2}'
+assert f'{ {"a": 1}["a"] }' == '1'
+assert f'{3.14159:{width}.{precision}}' == '  3.1'
+assert f'{"x"!r:^{width}}' == " 'x' "
+assert f'{"}"!r}' == "'}'"
+assert f"{'''a:b!c'''}" == 'a:b!c'
+assert f'{f"{width:{precision}}"}' == ' 5'
+assert f'{{{width}}}' == '{5}'
+
+for source in ["f'}'", "f'{'", "f'{}'", "f'{x!z}'", "f'{x!r y}'", "f'{a:{b:{c}}}'", "f'{a)}'"]:
+    assert_raises(SyntaxError, lambda: compile(source, '', 'eval'))
@@ -71,3 +71,37 @@ def kwargs_are_variable(x=[]):
 
 assert [1] == kwargs_are_variable()
 assert [1, 1] == kwargs_are_variable()
+
+
+# Defaults are evaluated once, when the def statement runs, so a mutable
+# default is shared by every call. This is intentional and matches CPython.
+def accumulate(x, acc=[]):
+    acc.append(x)
+    return acc
+
+accumulate(1)
+assert accumulate(2) == [1, 2]
+assert accumulate.__defaults__ == ([1, 2],)
+assert accumulate(3, []) == [3]
+assert accumulate.__defaults__ == ([1, 2],)
+
+evaluations = []
+def make_default():
+    evaluations.append(1)
+    return 'made'
+
+def uses_default(a=make_default(), *, b=make_default()):
+    return a, b
+
+assert evaluations == [1, 1]
+assert uses_default() == ('made', 'made')
+assert uses_default() == ('made', 'made')
+assert evaluations == [1, 1]
+assert uses_default.__kwdefaults__ == {'b': 'made'}
+
+# the defaults live on the function object and can be replaced
+uses_default.__defaults__ = ('new',)
+uses_default.__kwdefaults__ = {'b': 'other'}
+assert uses_default() == ('new', 'other')
+uses_default.__defaults__ = None
+assert_raises(TypeError, uses_default)
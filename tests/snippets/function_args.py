@@ -95,3 +95,67 @@ assert kwargs == [('a', 1), ('b', 2)]
 
 kwargs = func(a=1, b=2, c=3)
 assert kwargs == [('a', 1), ('b', 2), ('c', 3)]
+
+# keyword arguments keep the order of the call site
+def keys(**kw):
+    return list(kw)
+
+assert keys(b=1, a=2) == ['b', 'a']
+assert keys(z=0, **{'y': 1, 'x': 2}, w=3) == ['z', 'y', 'x', 'w']
+assert keys(**{'b': 1}, **{'a': 2}) == ['b', 'a']
+
+
+class Mapping:
+    def keys(self):
+        return ['q', 'p']
+
+    def __getitem__(self, key):
+        return key.upper()
+
+assert func(**Mapping()) == [('q', 'Q'), ('p', 'P')]
+
+# repeating a keyword is an error
+with assertRaises(SyntaxError):
+    compile("keys(a=1, a=2)", "<test>", "exec")
+
+with assertRaises(TypeError):
+    keys(a=1, **{'a': 2})
+
+with assertRaises(TypeError):
+    keys(**{'a': 1}, **{'a': 2})
+
+with assertRaises(TypeError):
+    keys(**{1: 2})
+
+with assertRaises(TypeError):
+    keys(**[1, 2])
+
+# dict displays may still repeat keys
+assert {'a': 1, **{'a': 2}} == {'a': 2}
+assert {**{'a': 1}, **{'a': 2}} == {'a': 2}
+
+# several * and ** unpackings in one call
+def collect(*args, **kwargs):
+    return args, kwargs
+
+assert collect(*[1], *[2], **{'x': 3}) == ((1, 2), {'x': 3})
+assert collect(1, *[2, 3], 4, *(5,)) == ((1, 2, 3, 4, 5), {})
+assert collect(*range(2), *'ab', *{7: None}) == ((0, 1, 'a', 'b', 7), {})
+assert collect(*(i for i in [1, 2])) == ((1, 2), {})
+assert collect(**{'a': 1}, b=2, **{'c': 3}) == ((), {'a': 1, 'b': 2, 'c': 3})
+assert collect(*[], **{}) == ((), {})
+
+def bind(a, b, c, x=None, y=None):
+    return a, b, c, x, y
+
+assert bind(*[1], *[2], 3, **{'x': 4}, **{'y': 5}) == (1, 2, 3, 4, 5)
+assert bind(1, *[2], c=3, **{'y': 5}) == (1, 2, 3, None, 5)
+
+with assertRaises(TypeError):
+    bind(*[1, 2, 3], **{'x': 1}, **{'x': 2})
+
+with assertRaises(TypeError):
+    bind(*[1, 2], **{'c': 3}, c=4)
+
+with assertRaises(TypeError):
+    collect(*1)
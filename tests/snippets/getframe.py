@@ -19,3 +19,22 @@ class TestClass():
         assert sys._getframe().f_locals['self'] == self
 
 TestClass()
+
+def callee():
+    frame = sys._getframe()
+    assert frame.f_lineno == 25
+    assert frame.f_code is callee.__code__
+    assert frame.f_globals is globals()
+    assert frame.f_builtins['len'] is len
+    assert isinstance(frame.f_lasti, int)
+    assert frame.f_back is sys._getframe(1)
+    assert frame.f_back.f_lineno == 35
+    return frame.f_back
+
+def caller():
+    back = callee()
+    assert back.f_code.co_name == 'caller'
+    assert back.f_back.f_code.co_name == '<module>'
+
+caller()
+assert 'getframe.py' in repr(sys._getframe())
@@ -61,3 +61,94 @@ with assertRaises(SyntaxError):
 #     nonlocal c
 #     c = 2
 
+
+
+# closures sharing one variable through nonlocal
+def make_counter():
+    count = 0
+    def increment():
+        nonlocal count
+        count += 1
+        return count
+    def get():
+        return count
+    return increment, get
+
+increment, get = make_counter()
+assert get() == 0
+assert increment() == 1
+assert increment() == 2
+assert get() == 2
+
+other_increment, other_get = make_counter()
+assert other_increment() == 1
+assert get() == 2
+
+
+# nonlocal rebinds the nearest enclosing function that binds the name
+def outer():
+    value = 'outer'
+    def middle():
+        def inner():
+            nonlocal value
+            value = 'inner'
+        inner()
+        return value
+    seen = middle()
+    return seen, value
+
+assert outer() == ('inner', 'inner')
+
+
+def shadowing():
+    value = 'outer'
+    def middle():
+        value = 'middle'
+        def inner():
+            nonlocal value
+            value = 'inner'
+        inner()
+        return value
+    return middle(), value
+
+assert shadowing() == ('inner', 'outer')
+
+
+def chained():
+    value = 0
+    def middle():
+        nonlocal value
+        def inner():
+            nonlocal value
+            value += 10
+        value += 1
+        inner()
+    middle()
+    return value
+
+assert chained() == 11
+
+
+# a name that is only global does not satisfy nonlocal
+src = """
+x = 1
+def f():
+    global x
+    def g():
+        nonlocal x
+"""
+
+with assertRaises(SyntaxError):
+    exec(src)
+
+# class scopes are skipped when looking for the binding
+src = """
+def f():
+    class C:
+        y = 1
+        def g(self):
+            nonlocal y
+"""
+
+with assertRaises(SyntaxError):
+    exec(src)
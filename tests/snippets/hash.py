@@ -21,3 +21,51 @@ with assertRaises(TypeError):
 
 with assertRaises(TypeError):
     hash([])
+
+# equal numbers hash alike
+assert hash(1) == hash(1.0) == hash(True) == hash(complex(1, 0)) == 1
+assert hash(0) == hash(0.0) == hash(-0.0) == hash(False) == 0
+assert hash(-1) == hash(-1.0) == -2
+assert hash(-2) == -2
+assert hash(2**61 - 1) == 0
+assert hash(2**100) == hash(float(2**100))
+assert hash(-2**100) == hash(float(-2**100))
+assert hash(0.5) == 2**60
+assert hash(float('inf')) == 314159
+assert hash(float('-inf')) == -314159
+
+assert {1: 'a'}[1.0] == 'a'
+assert {1.0: 'a'}[True] == 'a'
+assert len({1, 1.0, True}) == 1
+
+# tuples use the xxHash-based algorithm of CPython
+assert hash(()) == 5740354900026072187
+assert hash((1, 2)) == -3550055125485641917
+assert hash((1, 2)) == hash((1.0, 2.0))
+assert hash((1, (2, 3))) == hash((True, (2.0, 3)))
+assert {(1, 2): 'b'}[(1.0, 2)] == 'b'
+with assertRaises(TypeError):
+    hash((1, []))
+
+# str and bytes share the seeded SipHash, which is randomized per process
+# unless PYTHONHASHSEED is set
+import sys
+assert hash('') == hash(b'') == 0
+assert hash('abc') == hash(b'abc')
+assert hash('abc') == hash('ab' + 'c')
+assert hash('\xe9') == hash(b'\xe9')
+assert sys.flags.hash_randomization in (0, 1)
+
+import subprocess
+
+def hash_with_seed(seed):
+    p = subprocess.Popen(
+        ['env', 'PYTHONHASHSEED=' + seed, sys.executable, '-c', 'print(hash("abc"))'],
+        stdout=subprocess.PIPE)
+    stdout, _ = p.communicate()
+    assert p.returncode == 0
+    return int(stdout)
+
+assert hash_with_seed('1') == hash_with_seed('1')
+assert hash_with_seed('1') != hash_with_seed('2')
+assert hash_with_seed('0') == hash_with_seed('0')
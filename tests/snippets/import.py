@@ -70,3 +70,37 @@ from testutils import assertRaises
 with assertRaises(SyntaxError):
 	exec('import')
 
+
+import sys
+import importlib
+
+import import_cycle_a
+assert import_cycle_a.get_b() == 2
+assert sys.modules['import_cycle_b'].saw_partial_a
+assert sys.modules['import_cycle_a'] is import_cycle_a
+
+import_target.X = 'changed'
+import_target.extra = 'kept'
+reloaded = importlib.reload(import_target)
+assert reloaded is import_target
+assert import_target.X == '123'
+assert import_target.extra == 'kept'
+with assertRaises(TypeError):
+	importlib.reload('import_target')
+
+# dropping the cache entry forces a fresh module object
+del sys.modules['import_target']
+import import_target as fresh
+assert fresh is not import_target
+assert sys.modules['import_target'] is fresh
+assert not hasattr(fresh, 'extra')
+
+sys.modules['import_blocked'] = None
+with assertRaises(ImportError):
+	import import_blocked
+del sys.modules['import_blocked']
+
+sys.modules['import_fake'] = 42
+import import_fake
+assert import_fake == 42
+del sys.modules['import_fake']
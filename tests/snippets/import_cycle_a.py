@@ -0,0 +1,7 @@
+import import_cycle_b
+
+A = 1
+
+
+def get_b():
+    return import_cycle_b.B
@@ -0,0 +1,5 @@
+# Imported by import_cycle_a while that module is only partially initialized
+import import_cycle_a
+
+B = 2
+saw_partial_a = not hasattr(import_cycle_a, 'A')
@@ -1,3 +1,28 @@
 import dir_module
 assert dir_module.value == 5
 assert dir_module.value2 == 7
+
+import importlib
+import os.path
+
+from testutils import assert_raises
+
+relative = importlib.import_module('dir_module.relative')
+assert relative.__name__ == 'dir_module.relative'
+assert relative is dir_module.relative
+assert importlib.import_module('os.path') is os.path
+assert importlib.import_module('.relative', 'dir_module') is relative
+assert importlib.import_module('..relative', 'dir_module.dir_module_inner') is relative
+assert_raises(TypeError, lambda: importlib.import_module('.relative'))
+assert_raises(ModuleNotFoundError, lambda: importlib.import_module('dir_module.missing'))
+
+parent_import = importlib.import_module('dir_module.dir_module_inner.parent_import')
+assert parent_import.relative is relative
+assert parent_import.value == 5
+assert parent_import.value2 == 7
+
+assert __import__('dir_module.relative').__name__ == 'dir_module'
+assert __import__('dir_module.relative', fromlist=['value']) is relative
+assert __import__('os.path', fromlist=['join']) is os.path
+from dir_module import dir_module_inner
+assert dir_module_inner.__name__ == 'dir_module.dir_module_inner'
@@ -0,0 +1,28 @@
+import os
+import sys
+
+here = os.path.dirname(os.path.abspath(__file__))
+
+# a regular package runs its __init__.py before the submodule
+import dir_module.relative
+assert dir_module.value == 5
+assert dir_module.__package__ == 'dir_module'
+assert dir_module.__path__ == [os.path.join(here, 'dir_module')]
+assert dir_module.__file__ == os.path.join(here, 'dir_module', '__init__.py')
+assert dir_module.relative.__package__ == 'dir_module'
+assert dir_module.relative.__file__ == os.path.join(here, 'dir_module', 'relative.py')
+
+# a namespace package (no __init__.py) is assembled from several path entries
+sys.path.append(os.path.join(here, 'namespace_path_a'))
+sys.path.append(os.path.join(here, 'namespace_path_b'))
+import nspkg.mod_a
+import nspkg.mod_b
+assert nspkg.mod_a.X == 'a'
+assert nspkg.mod_b.Y == 'b'
+assert nspkg.__package__ == 'nspkg'
+assert sorted(nspkg.__path__) == [
+    os.path.join(here, 'namespace_path_a', 'nspkg'),
+    os.path.join(here, 'namespace_path_b', 'nspkg'),
+]
+assert getattr(nspkg, '__file__', None) is None
+assert nspkg.mod_b.__package__ == 'nspkg'
@@ -0,0 +1,81 @@
+import os
+import subprocess
+import sys
+import tempfile
+import _imp
+import _frozen_importlib_external as bootstrap_external
+
+module_dir = tempfile.mkdtemp()
+sys.path.insert(0, module_dir)
+sys.dont_write_bytecode = False
+source_path = os.path.join(module_dir, 'pyc_target.py')
+
+
+def write_source(text):
+    with open(source_path, 'w') as f:
+        f.write(text)
+
+
+def write_hash_pyc(source, checked, code_source="VALUE = 'cached'\n"):
+    code = compile(code_source, source_path, 'exec')
+    source_hash = _imp.source_hash(bootstrap_external._RAW_MAGIC_NUMBER, source)
+    with open(cache_path, 'wb') as f:
+        f.write(bootstrap_external._code_to_hash_pyc(code, source_hash, checked))
+
+
+def fresh_value():
+    sys.modules.pop('pyc_target', None)
+    import pyc_target
+    return pyc_target.VALUE
+
+
+def value_with_mode(mode):
+    p = subprocess.Popen(
+        [sys.executable, '--check-hash-based-pycs', mode, '-c',
+         'import _imp, pyc_target; '
+         'print(_imp.check_hash_based_pycs, pyc_target.VALUE)'],
+        stdout=subprocess.PIPE,
+        cwd=module_dir,
+    )
+    out, _ = p.communicate()
+    return out.decode().split()
+
+
+try:
+    # the cache file name carries the interpreter's tag
+    write_source("VALUE = 'source'\n")
+    assert fresh_value() == 'source'
+    cache_path = bootstrap_external.cache_from_source(source_path)
+    tag = sys.implementation.cache_tag
+    assert os.path.basename(cache_path) == 'pyc_target.' + tag + '.pyc'
+    assert os.path.exists(cache_path)
+
+    assert _imp.check_hash_based_pycs == 'default'
+    assert len(_imp.source_hash(bootstrap_external._RAW_MAGIC_NUMBER, b'x')) == 8
+    # the same keyed SipHash-1-3 as CPython
+    assert _imp.source_hash(1234, b'x') == b'\x03\x89\x15l\x8f@\xdb\xd0'
+
+    # a checked hash-based pyc is used while the source hash matches ...
+    source = open(source_path, 'rb').read()
+    write_hash_pyc(source, checked=True)
+    assert fresh_value() == 'cached'
+    # ... and silently recompiled once it doesn't
+    write_hash_pyc(b'other source', checked=True)
+    assert fresh_value() == 'source'
+
+    # an unchecked one is only validated with --check-hash-based-pycs always
+    write_hash_pyc(b'other source', checked=False)
+    assert fresh_value() == 'cached'
+    assert value_with_mode('always') == ['always', 'source']
+    assert value_with_mode('never')[0] == 'never'
+
+    # a corrupt cache is recompiled as well
+    with open(cache_path, 'wb') as f:
+        f.write(b'garbage')
+    assert fresh_value() == 'source'
+finally:
+    sys.path.remove(module_dir)
+    for directory in [os.path.join(module_dir, '__pycache__'), module_dir]:
+        for name in os.listdir(directory):
+            os.remove(os.path.join(directory, name))
+        os.rmdir(directory)
@@ -0,0 +1,70 @@
+import math
+
+from testutils import assert_raises
+
+
+class Index:
+    def __init__(self, value):
+        self.value = value
+
+    def __index__(self):
+        return self.value
+
+
+one = Index(1)
+three = Index(3)
+
+# Subscripts
+assert [1, 2, 3, 4][one] == 2
+assert (1, 2, 3, 4)[one] == 2
+assert 'abcd'[one] == 'b'
+assert b'abcd'[one] == ord('b')
+assert bytearray(b'abcd')[one] == ord('b')
+assert range(10)[three] == 3
+assert [1, 2, 3, 4][Index(-1)] == 4
+
+# Slices
+assert [1, 2, 3, 4][one:three] == [2, 3]
+assert [1, 2, 3, 4][::Index(2)] == [1, 3]
+assert 'abcd'[one:] == 'bcd'
+assert b'abcd'[:three] == b'abc'
+assert range(10)[one:three] == range(1, 3)
+
+# Repetition
+assert [1] * three == [1, 1, 1]
+assert three * [1] == [1, 1, 1]
+assert (1,) * three == (1, 1, 1)
+assert 'a' * three == 'aaa'
+assert three * 'a' == 'aaa'
+assert b'a' * three == b'aaa'
+assert three * b'a' == b'aaa'
+assert bytearray(b'a') * three == bytearray(b'aaa')
+
+# range
+assert list(range(three)) == [0, 1, 2]
+assert list(range(one, Index(7), Index(2))) == [1, 3, 5]
+
+# Other int arguments
+l = [1, 2, 3]
+assert l.pop(one) == 2
+l.insert(one, 5)
+assert l == [1, 5, 3]
+assert 'a'.zfill(three) == '00a'
+assert chr(Index(65)) == 'A'
+assert round(1234, Index(-2)) == 1200
+assert round(2.675, Index(2)) == 2.67
+assert math.factorial(Index(4)) == 24
+
+# Floats are not indices
+float_one = 1.0
+assert_raises(TypeError, lambda: [1][float_one], 'list index with float')
+assert_raises(TypeError, lambda: (1,)[float_one], 'tuple index with float')
+assert_raises(TypeError, lambda: 'a'[float_one], 'str index with float')
+assert_raises(TypeError, lambda: b'a'[float_one], 'bytes index with float')
+assert_raises(TypeError, lambda: range(2)[float_one], 'range index with float')
+assert_raises(TypeError, lambda: [1] * 2.0, 'list repeated by float')
+assert_raises(TypeError, lambda: 'a' * 2.0, 'str repeated by float')
+assert_raises(TypeError, lambda: b'a' * 2.0, 'bytes repeated by float')
+assert_raises(TypeError, lambda: range(1.0), 'range of float')
+assert_raises(TypeError, lambda: range(0, 1, 1.0), 'range step of float')
+assert_raises(TypeError, lambda: [1][Index(1.0)], '__index__ returned float')
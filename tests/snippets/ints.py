@@ -147,6 +147,43 @@ assert int('10', base=0) == 10
 # type byte, signed, implied base
 assert int(b'     -0XFF ', base=0) == -255
 
+# prefixes matching the base, base auto-detection and underscores
+assert int('0x1A', 16) == 26
+assert int('0b101', 2) == 5
+assert int('0o17', 0) == 15
+assert int('0o17', 8) == 15
+assert int('0b1', 16) == 0xb1
+assert int('0x_1a', 0) == 26
+assert int('1_000') == 1000
+assert int('-1_0', 0) == -10
+assert int('0', 0) == 0
+assert int('00', 0) == 0
+assert int('0_0', 0) == 0
+assert int('\t+12\n') == 12
+assert int('z', 36) == 35
+assert int('١٢٣') == 123
+assert int(2 ** 70 * 3) == 3 * 2 ** 70
+
+# PEP 515 underscores in literals
+assert 1_000_000 == 1000000
+assert 0xFF_FF == 65535
+assert 0x_1 == 1
+assert 0b_1_0 == 2
+assert 1_0.0_1e1_0 == 10.01e10
+for source in ['1__0', '1_', '1_.5', '1._5', '1e5_', '0x__1', '0b12']:
+    assert_raises(SyntaxError, lambda: compile(source, '<number>', 'eval'))
+
+for literal, base in [('010', 0), ('0x', 0), ('0x_', 16), ('1_', 10), ('- 1', 10),
+                      ('12a', 10), ('2', 2), ('0b102', 0), ('', 10), ('+-1', 10)]:
+    assert_raises(ValueError, lambda: int(literal, base))
+
+with assertRaises(ValueError) as ctx:
+    int('12a', 0)
+assert str(ctx.exception) == "invalid literal for int() with base 0: '12a'"
+with assertRaises(ValueError) as ctx:
+    int(b'\xff')
+assert str(ctx.exception) == "invalid literal for int() with base 10: b'\\xff'"
+
 
 assert int.from_bytes(b'\x00\x10', 'big') == 16
 assert int.from_bytes(b'\x00\x10', 'little') == 4096
@@ -211,6 +248,35 @@ class F(float):
 
 assert int(F(1.2)) == 3
 
+class Index:
+    def __index__(self):
+        return 7
+
+assert int(Index()) == 7
+
+class IntAndIndex:
+    def __int__(self):
+        return 5
+
+    def __index__(self):
+        return 7
+
+assert int(IntAndIndex()) == 5
+
+class FloatInt:
+    def __int__(self):
+        return 5.0
+
+with assertRaises(TypeError):
+    int(FloatInt())
+
+class Floaty:
+    def __float__(self):
+        return 1.5
+
+with assertRaises(TypeError):
+    int(Floaty())
+
 assert isinstance((0).__round__(), int)
 assert isinstance((1).__round__(), int)
 assert (0).__round__() == 0
@@ -219,7 +285,15 @@ assert isinstance((0).__round__(0), int)
 assert isinstance((1).__round__(0), int)
 assert (0).__round__(0) == 0
 assert (1).__round__(0) == 1
-assert_raises(TypeError, lambda: (0).__round__(None))
-assert_raises(TypeError, lambda: (1).__round__(None))
+# None means no ndigits
+assert isinstance((1).__round__(None), int)
+assert (0).__round__(None) == 0
+assert (15).__round__(None) == 15
 assert_raises(TypeError, lambda: (0).__round__(0.0))
 assert_raises(TypeError, lambda: (1).__round__(0.0))
+
+assert format(1234567, ',') == '1,234,567'
+assert format(-1234, '_') == '-1_234'
+assert format(0x12345, '_x') == '1_2345'
+assert format(123, ',') == '123'
+assert_raises(ValueError, lambda: format(1, 'q'))
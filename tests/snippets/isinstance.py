@@ -1,3 +1,8 @@
+import abc
+
+from testutils import assert_raises
+
+
 
 class Regular:
     pass
@@ -52,3 +57,63 @@ class ReturnInt(metaclass=MCReturnInt):
 
 
 assert isinstance("a", ReturnInt) is True
+
+assert isinstance(1, (str, int))
+assert isinstance(1, (str, (bytes, int)))
+assert not isinstance(1.0, (str, (bytes, int)))
+assert not isinstance(1, ())
+assert isinstance(1, (int, 1))
+assert_raises(TypeError, lambda: isinstance(1, 1))
+assert_raises(TypeError, lambda: isinstance(1, (str, 1)))
+assert_raises(TypeError, lambda: isinstance(1, [int]))
+
+assert issubclass(bool, (str, int))
+assert issubclass(bool, (str, (bytes, int)))
+assert not issubclass(float, (str, (bytes, int)))
+assert_raises(TypeError, lambda: issubclass(bool, 1))
+assert_raises(TypeError, lambda: issubclass(1, int))
+
+
+class EvenMeta(abc.ABCMeta):
+    def __instancecheck__(cls, instance):
+        return isinstance(instance, int) and instance % 2 == 0
+
+
+class Even(metaclass=EvenMeta):
+    pass
+
+
+assert isinstance(2, Even)
+assert not isinstance(3, Even)
+assert isinstance(3, (Even, int))
+assert isinstance(4, (str, Even))
+assert not isinstance('4', (bytes, Even))
+
+
+class Checker:
+    def __init__(self, result):
+        self.result = result
+
+    def __instancecheck__(self, instance):
+        return self.result
+
+    def __subclasscheck__(self, subclass):
+        return self.result
+
+
+assert isinstance(1, Checker(True))
+assert not isinstance(1, Checker(False))
+assert isinstance(1, (str, Checker(True)))
+assert issubclass(int, Checker(True))
+assert not issubclass(int, (str, Checker(False)))
+
+
+class Proxy:
+    @property
+    def __class__(self):
+        return int
+
+
+assert isinstance(Proxy(), int)
+assert isinstance(Proxy(), Proxy)
+assert not isinstance(Proxy(), str)
@@ -30,3 +30,47 @@ test_container(C())
 class C: pass
 assert_raises(TypeError, lambda: 5 in C())
 assert_raises(TypeError, lambda: iter(C))
+assert_raises(TypeError, lambda: 5 in 5)
+
+
+class Contains:
+    def __contains__(self, x):
+        return x == 3
+
+    def __iter__(self):
+        raise AssertionError('__contains__ takes precedence')
+
+
+assert 3 in Contains()
+assert 4 not in Contains()
+
+
+class ContainsTruthy:
+    def __contains__(self, x):
+        return [x]
+
+
+assert (1 in ContainsTruthy()) is True
+
+
+class NotAnIterator:
+    def __iter__(self):
+        return 5
+
+
+assert_raises(TypeError, lambda: iter(NotAnIterator()))
+
+
+class IterNone:
+    __iter__ = None
+
+    def __getitem__(self, x):
+        return x
+
+
+assert_raises(TypeError, lambda: iter(IterNone()))
+
+try:
+    iter(5)
+except TypeError as e:
+    assert str(e) == "'int' object is not iterable"
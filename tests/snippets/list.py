@@ -154,6 +154,11 @@ assert sorted([(1, 2, 3), (0, 3, 6)], key=lambda x: x[0]) == [(0, 3, 6), (1, 2,
 assert sorted([(1, 2, 3), (0, 3, 6)], key=lambda x: x[1]) == [(1, 2, 3), (0, 3, 6)]
 assert sorted([(1, 2), (), (5,)], key=len) == [(), (5,), (1, 2)]
 
+# sorting is stable, also when reversed
+pairs = [(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd'), (1, 'e')]
+assert sorted(pairs, key=lambda p: p[0]) == [(0, 'b'), (0, 'd'), (1, 'a'), (1, 'c'), (1, 'e')]
+assert sorted(pairs, key=lambda p: p[0], reverse=True) == [(1, 'a'), (1, 'c'), (1, 'e'), (0, 'b'), (0, 'd')]
+
 lst = [3, 1, 5, 2, 4]
 class C:
   def __init__(self, x): self.x = x
@@ -488,3 +493,9 @@ del x[-1:-5:-1]
 assert x == [0, 1, 2, 3, 4, 5]
 x = list(range(10))
 del x[-5:-1:-1]
+
+assert [1, 5] < [2, 0]
+assert [2, 0] > [1, 5]
+assert [1, 2, 3] <= [1, 3]
+assert not [1, 3] <= [1, 2, 3]
+assert [1, 3] >= [1, 2, 9]
@@ -0,0 +1,2 @@
+# Run by cmdline_module.py with `-m main_package`
+PACKAGE_INIT = True
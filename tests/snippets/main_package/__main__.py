@@ -0,0 +1,5 @@
+import sys
+
+import main_package
+
+print(repr((__name__, __package__, __spec__.name, __file__, sys.argv, main_package.PACKAGE_INIT)))
@@ -18,3 +18,20 @@ assert 'b' in A.__dict__
 assert 'c' not in A.__dict__
 
 assert '__dict__' in A.__dict__
+
+
+class B(A):
+    def c():
+        pass
+
+
+assert 'c' in B.__dict__
+assert 'a' not in B.__dict__
+assert '__dict__' not in B.__dict__
+assert B.__dict__.get('a') is None
+assert B.__dict__.get('a', 1) == 1
+assert B.__dict__.get('c') is B.c
+assert 'c' in B.__dict__.keys()
+assert 'c' in list(B.__dict__)
+assert len(B.__dict__) == len(B.__dict__.copy())
+assert B.mro() == [B, A, dict, object]
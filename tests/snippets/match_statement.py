@@ -0,0 +1,156 @@
+from collections import OrderedDict
+from testutils import assert_raises
+
+
+def describe(value):
+    match value:
+        case 0:
+            return "zero"
+        case -1 | 1:
+            return "unit"
+        case 2 + 3j:
+            return "complex"
+        case "hello" | b"hello":
+            return "greeting"
+        case None:
+            return "nothing"
+        case True:
+            return "yes"
+        case []:
+            return "empty list"
+        case [x]:
+            return "one: {}".format(x)
+        case [x, *rest]:
+            return "first {} of {}".format(x, 1 + len(rest))
+        case {"action": action, **rest}:
+            return "action {} {}".format(action, sorted(rest))
+        case {}:
+            return "mapping"
+        case int(x) if x > 100:
+            return "big"
+        case _:
+            return "other"
+
+
+assert describe(0) == "zero"
+assert describe(1) == "unit"
+assert describe(-1) == "unit"
+assert describe(2 + 3j) == "complex"
+assert describe("hello") == "greeting"
+assert describe(b"hello") == "greeting"
+assert describe(None) == "nothing"
+assert describe(True) == "unit"  # True == 1
+assert describe([]) == "empty list"
+assert describe(()) == "empty list"
+assert describe([5]) == "one: 5"
+assert describe((1, 2, 3)) == "first 1 of 3"
+assert describe({"action": "go", "x": 1}) == "action go ['x']"
+assert describe(OrderedDict(action="stop")) == "action stop []"
+assert describe({"other": 1}) == "mapping"
+assert describe(1000) == "big"
+assert describe(50) == "other"
+assert describe("hi") == "other"
+assert describe(range(2)) == "first 0 of 2"
+
+# Strings are not matched as sequences:
+match "ab":
+    case [a, b]:
+        assert False
+    case _:
+        pass
+
+match [1, [2, 3], 4]:
+    case [1, [a, *b], c] if a == 2:
+        assert a == 2
+        assert b == [3]
+        assert c == 4
+    case _:
+        assert False
+
+match (1, 2):
+    case (1, 2) as pair:
+        assert pair == (1, 2)
+
+match 3:
+    case int(n) | float(n):
+        assert n == 3
+
+
+class Point:
+    __match_args__ = ("x", "y")
+
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+
+def where(point):
+    match point:
+        case Point(x=0, y=0):
+            return "origin"
+        case Point(x=0, y=y):
+            return "y={}".format(y)
+        case Point(x, 0):
+            return "x={}".format(x)
+        case Point(z=1):
+            return "z"
+        case Point():
+            return "somewhere"
+        case _:
+            return "not a point"
+
+
+assert where(Point(0, 0)) == "origin"
+assert where(Point(0, 5)) == "y=5"
+assert where(Point(3, 0)) == "x=3"
+assert where(Point(3, 3)) == "somewhere"
+assert where(7) == "not a point"
+
+
+class Color:
+    RED = 0
+    GREEN = 1
+
+
+match 1:
+    case Color.RED:
+        assert False
+    case Color.GREEN:
+        pass
+
+match {"a": 1, "b": 2}:
+    case {"a": 1, "b": 3}:
+        assert False
+    case {"a": 1, "b": b}:
+        assert b == 2
+
+# The guard is evaluated after binding:
+match 5:
+    case x if x < 0:
+        assert False
+    case x:
+        assert x == 5
+
+# `match` and `case` are still fine as names:
+match = [1, 2]
+case = match[0]
+match[1] = case
+assert match == [1, 1]
+
+
+def match_too_many(point):
+    match point:
+        case Point(1, 2, 3):
+            pass
+
+
+assert_raises(TypeError, lambda: match_too_many(Point(1, 2)))
+
+for source in [
+    "match x:\n    case _:\n        pass\n    case 1:\n        pass\n",
+    "match x:\n    case a | b:\n        pass\n",
+    "match x:\n    case [a, a]:\n        pass\n",
+    "match x:\n    case [*a, *b]:\n        pass\n",
+    "match x:\n    case Point(x=1, x=2):\n        pass\n",
+]:
+    assert_raises(SyntaxError, lambda: compile(source, "<test>", "exec"))
@@ -29,6 +29,9 @@ assert math.floor(4) == 4
 assert math.trunc(2.2) == 2
 assert math.ceil(3.3) == 4
 assert math.floor(4.4) == 4
+assert isinstance(math.ceil(3.3), int)
+assert isinstance(math.floor(4.4), int)
+assert math.floor(-4.4) == -5
 
 class A(object):
     def __trunc__(self):
@@ -79,6 +82,13 @@ with assertRaises(TypeError):
 with assertRaises(TypeError):
     math.floor(object())
 
+class FloatLike:
+    def __float__(self):
+        return 4.4
+
+assert math.ceil(FloatLike()) == 5
+assert math.floor(FloatLike()) == 4
+
 assert str(math.frexp(0.0)) == str((+0.0, 0))
 assert str(math.frexp(-0.0)) == str((-0.0, 0))
 assert math.frexp(1) == (0.5, 1)
@@ -118,3 +128,11 @@ assert math.modf(-1.25) == (-0.25, -1.0)
 assert math.modf(2.56) == (0.56, 2.0)
 assert math.modf(-2.56) == (-0.56, -2.0)
 assert math.modf(1) == (0.0, 1.0)
+
+assert math.fsum([.1, .1, .1, .1, .1, .1, .1, .1, .1, .1]) == 1.0
+assert math.fsum([1e100, 1.0, -1e100, 1e-100, 1e50, -1.0, -1e50]) == 1e-100
+assert math.fsum([]) == 0.0
+assert_raises(ValueError, lambda: math.fsum([float('inf'), float('-inf')]))
+assert_raises(OverflowError, lambda: math.fsum([1.7e308, 1.7e308]))
+assert abs(math.erf(0.5) - 0.5204998778130465) < 1e-15
+assert abs(math.erfc(3.0) - 2.2090496998585438e-05) < 1e-19
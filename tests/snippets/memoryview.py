@@ -4,3 +4,15 @@ a = memoryview(obj)
 assert a.obj == obj
 
 assert a[2:3] == b"c"
+
+from testutils import assert_raises
+
+assert a.tobytes() == obj
+assert a.tolist() == [97, 98, 99, 100, 101]
+assert len(a) == 5
+assert a.format == "B" and a.itemsize == 1 and a.nbytes == 5
+assert a.readonly
+assert not memoryview(bytearray(b"ab")).readonly
+assert bytes(memoryview(b"xy")) == b"xy"
+assert memoryview(memoryview(b"z")).tobytes() == b"z"
+assert_raises(TypeError, lambda: memoryview(1))
@@ -0,0 +1,110 @@
+from decimal import Decimal
+from fractions import Fraction
+import math
+
+from testutils import assertRaises
+
+# int and float
+assert 7 // 2.0 == 3.0
+assert isinstance(7 // 2.0, float)
+assert 7.0 // 2 == 3.0
+assert -7 // 2.0 == -4.0
+assert 7 % 2.0 == 1.0
+assert -7 % 2.0 == 1.0
+assert 7 % -2.0 == -1.0
+assert divmod(7, 2.0) == (3.0, 1.0)
+assert divmod(-7, 2.0) == (-4.0, 1.0)
+assert divmod(7.5, 2) == (3.0, 1.5)
+assert (10 ** 20) // 3.0 == 3.3333333333333332e19
+assert (7).__floordiv__(2.0) is NotImplemented
+assert (7).__mod__(2.0) is NotImplemented
+assert (7).__divmod__(2.0) is NotImplemented
+assert (7.0).__rfloordiv__(2) == 0.0
+assert (7.0).__rmod__(2) == 2.0
+assert (7.0).__rdivmod__(2) == (0.0, 2.0)
+assert 5 // True == 5
+assert divmod(True, 2) == (0, 1)
+with assertRaises(ZeroDivisionError):
+    7 // 0.0
+with assertRaises(ZeroDivisionError):
+    7.0 % 0
+with assertRaises(TypeError):
+    1j // 1
+
+# int and Fraction
+assert 7 // Fraction(2) == 3
+assert isinstance(7 // Fraction(2), int)
+assert Fraction(7) // 2 == 3
+assert 7 % Fraction(2) == 1
+assert Fraction(7, 2) % 2 == Fraction(3, 2)
+assert -7 % Fraction(2) == 1
+assert divmod(7, Fraction(2)) == (3, 1)
+assert divmod(Fraction(7), 2) == (3, 1)
+assert True // Fraction(1, 2) == 2
+assert Fraction(7, 2).__floor__() == 3
+assert Fraction(7, 2).__ceil__() == 4
+assert Fraction(7, 2).__trunc__() == 3
+assert math.floor(Fraction(-7, 2)) == -4
+assert math.ceil(Fraction(-7, 2)) == -3
+assert round(Fraction(7, 2)) == 4
+assert round(Fraction(5, 2)) == 2
+assert round(Fraction(-7, 2)) == -4
+assert round(Fraction(7, 4), 1) == Fraction(9, 5)
+assert round(Fraction(1234), -2) == 1200
+assert isinstance(round(Fraction(7, 2)), int)
+assert isinstance(round(Fraction(7, 4), 1), Fraction)
+
+# float and Fraction
+assert Fraction(7) // 2.0 == 3.0
+assert divmod(Fraction(7), 2.0) == (3.0, 1.0)
+assert (7.0).__floordiv__(Fraction(2)) is NotImplemented
+assert 7.0 // Fraction(2) == 3.0
+assert Fraction(1, 2) + 0.5 == 1.0
+assert 2 ** Fraction(1, 2) == math.sqrt(2)
+
+# int and Decimal
+assert 7 // Decimal(2) == Decimal(3)
+assert isinstance(7 // Decimal(2), Decimal)
+assert Decimal(7) // 2 == Decimal(3)
+assert 7 % Decimal(2) == Decimal(1)
+# Decimal rounds towards zero, unlike int
+assert -7 // Decimal(2) == Decimal(-3)
+assert -7 % Decimal(2) == Decimal(-1)
+assert divmod(7, Decimal(2)) == (Decimal(3), Decimal(1))
+assert divmod(Decimal(7), 2) == (Decimal(3), Decimal(1))
+assert round(Decimal('2.5')) == 2
+assert round(Decimal('3.5')) == 4
+assert isinstance(round(Decimal('2.5')), int)
+assert round(Decimal('2.55'), 1) == Decimal('2.6')
+assert math.floor(Decimal('-2.5')) == -3
+assert math.ceil(Decimal('2.5')) == 3
+
+# float and Decimal don't mix in arithmetic
+with assertRaises(TypeError):
+    Decimal(7) // 2.0
+with assertRaises(TypeError):
+    7.0 % Decimal(2)
+
+
+# Reflected operations of other types
+class Number:
+    def __rfloordiv__(self, other):
+        return 'rfloordiv', other
+
+    def __rmod__(self, other):
+        return 'rmod', other
+
+    def __rdivmod__(self, other):
+        return 'rdivmod', other
+
+    def __round__(self, ndigits=None):
+        return 'round', ndigits
+
+
+assert 7 // Number() == ('rfloordiv', 7)
+assert 7.0 // Number() == ('rfloordiv', 7.0)
+assert 7 % Number() == ('rmod', 7)
+assert divmod(7, Number()) == ('rdivmod', 7)
+assert divmod(7.0, Number()) == ('rdivmod', 7.0)
+assert round(Number()) == ('round', None)
+assert round(Number(), 2) == ('round', 2)
@@ -0,0 +1 @@
+Y = 'b'
@@ -14,3 +14,75 @@ except:
 buf = io.StringIO()
 print('hello, world', file=buf)
 assert buf.getvalue() == 'hello, world\n', buf.getvalue()
+
+buf = io.StringIO()
+print('a', 'b', sep='-', end='!', file=buf)
+assert buf.getvalue() == 'a-b!', buf.getvalue()
+
+buf = io.StringIO()
+print(file=buf)
+print('x', None, 1.5, [1], sep='', file=buf)
+assert buf.getvalue() == '\nxNone1.5[1]\n', buf.getvalue()
+
+
+class Recorder:
+    """Anything with a write method can be printed to."""
+
+    def __init__(self):
+        self.calls = []
+
+    def write(self, data):
+        self.calls.append(('write', data))
+
+    def flush(self):
+        self.calls.append(('flush',))
+
+
+class Thing:
+    def __str__(self):
+        return 'thing'
+
+    def __repr__(self):
+        return 'Thing()'
+
+
+recorder = Recorder()
+print(1, Thing(), 'c', sep=', ', end='.\n', file=recorder)
+assert recorder.calls == [
+    ('write', '1'),
+    ('write', ', '),
+    ('write', 'thing'),
+    ('write', ', '),
+    ('write', 'c'),
+    ('write', '.\n'),
+], recorder.calls
+
+recorder = Recorder()
+print('flushed', file=recorder, flush=True)
+assert recorder.calls[-1] == ('flush',), recorder.calls
+
+recorder = Recorder()
+print('not flushed', file=recorder)
+assert ('flush',) not in recorder.calls
+
+# file=None means sys.stdout
+import sys
+saved_stdout = sys.stdout
+sys.stdout = Recorder()
+try:
+    print('to stdout', file=None)
+    calls = sys.stdout.calls
+finally:
+    sys.stdout = saved_stdout
+assert calls == [('write', 'to stdout'), ('write', '\n')], calls
+
+saved_stderr = sys.stderr
+sys.stderr = Recorder()
+try:
+    print('to stderr', file=sys.stderr)
+    calls = sys.stderr.calls
+finally:
+    sys.stderr = saved_stderr
+assert calls == [('write', 'to stderr'), ('write', '\n')], calls
+
+assert_raises(AttributeError, lambda: print('x', file=object()))
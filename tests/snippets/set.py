@@ -70,6 +70,8 @@ assert len(a) == 0
 assert set([1,2,3]).union(set([4,5])) == set([1,2,3,4,5])
 assert set([1,2,3]).union(set([1,2,3,4,5])) == set([1,2,3,4,5])
 assert set([1,2,3]).union([1,2,3,4,5]) == set([1,2,3,4,5])
+assert set([1,2,3]).union([4], (5,), {6}) == set([1,2,3,4,5,6])
+assert set([1,2,3]).union() == set([1,2,3])
 
 assert set([1,2,3]) | set([4,5]) == set([1,2,3,4,5])
 assert set([1,2,3]) | set([1,2,3,4,5]) == set([1,2,3,4,5])
@@ -78,6 +80,7 @@ assert_raises(TypeError, lambda: set([1,2,3]) | [1,2,3,4,5])
 assert set([1,2,3]).intersection(set([1,2])) == set([1,2])
 assert set([1,2,3]).intersection(set([5,6])) == set([])
 assert set([1,2,3]).intersection([1,2]) == set([1,2])
+assert set([1,2,3]).intersection([1,2], (2,3)) == set([2])
 
 assert set([1,2,3]) & set([4,5]) == set([])
 assert set([1,2,3]) & set([1,2,3,4,5]) == set([1,2,3])
@@ -86,6 +89,7 @@ assert_raises(TypeError, lambda: set([1,2,3]) & [1,2,3,4,5])
 assert set([1,2,3]).difference(set([1,2])) == set([3])
 assert set([1,2,3]).difference(set([5,6])) == set([1,2,3])
 assert set([1,2,3]).difference([1,2]) == set([3])
+assert set([1,2,3]).difference([1], (3,)) == set([2])
 
 assert set([1,2,3]) - set([4,5]) == set([1,2,3])
 assert set([1,2,3]) - set([1,2,3,4,5]) == set([])
@@ -136,6 +140,8 @@ a = set([1,2,3])
 a.update([3,4,5])
 assert a == set([1,2,3,4,5])
 assert_raises(TypeError, lambda: a.update(1))
+a.update([6], (7,))
+assert a == set([1,2,3,4,5,6,7])
 
 a = set([1,2,3])
 b = set()
@@ -156,6 +162,9 @@ a = set([1,2,3])
 a.intersection_update([2,3,4,5])
 assert a == set([2,3])
 assert_raises(TypeError, lambda: a.intersection_update(1))
+a = set([1,2,3])
+a.intersection_update([1,2], [2,3])
+assert a == set([2])
 
 a = set([1,2,3])
 a &= set([2,3,4,5])
@@ -169,6 +178,8 @@ a = set([1,2,3])
 a.difference_update([3,4,5])
 assert a == set([1,2])
 assert_raises(TypeError, lambda: a.difference_update(1))
+a.difference_update([1], [2])
+assert a == set()
 
 a = set([1,2,3])
 a -= set([3,4,5])
@@ -228,6 +239,7 @@ assert b == a
 assert frozenset([1,2,3]).union(frozenset([4,5])) == frozenset([1,2,3,4,5])
 assert frozenset([1,2,3]).union(frozenset([1,2,3,4,5])) == frozenset([1,2,3,4,5])
 assert frozenset([1,2,3]).union([1,2,3,4,5]) == frozenset([1,2,3,4,5])
+assert frozenset([1,2,3]).union([4], [5]) == frozenset([1,2,3,4,5])
 
 assert frozenset([1,2,3]) | frozenset([4,5]) == frozenset([1,2,3,4,5])
 assert frozenset([1,2,3]) | frozenset([1,2,3,4,5]) == frozenset([1,2,3,4,5])
@@ -0,0 +1,58 @@
+import atexit
+import subprocess
+import sys
+
+from testutils import assert_raises
+
+calls = []
+
+def record(*args, **kwargs):
+    calls.append((args, kwargs))
+
+def fail():
+    raise ValueError('callback failed')
+
+atexit._clear()
+assert atexit.register(record, 1, key='a') is record
+atexit.register(fail)
+atexit.register(record, 2)
+assert atexit._ncallbacks() == 3
+
+# callbacks run last registered first, even when one of them fails
+try:
+    atexit._run_exitfuncs()
+except ValueError:
+    pass
+assert calls == [((2,), {}), ((1,), {'key': 'a'})], calls
+assert atexit._ncallbacks() == 0
+
+calls.clear()
+atexit.register(record, 1)
+atexit.register(fail)
+atexit.register(record, 2)
+atexit.unregister(fail)
+atexit.unregister(record)
+assert atexit._ncallbacks() == 0
+atexit._run_exitfuncs()
+assert calls == []
+
+assert_raises(TypeError, lambda: atexit.register(1))
+assert_raises(TypeError, atexit.register)
+
+# callbacks run when the interpreter exits
+def run(code):
+    p = subprocess.Popen([sys.executable, '-c', code], stdout=subprocess.PIPE)
+    out, _ = p.communicate()
+    return p.returncode, out.decode().splitlines()
+
+assert run(
+    'import atexit\n'
+    'atexit.register(print, "bye")\n'
+    'atexit.register(print, "first")\n'
+) == (0, ['first', 'bye'])
+
+assert run(
+    'import atexit, sys\n'
+    'atexit.register(print, "bye")\n'
+    'sys.exit(3)\n'
+) == (3, ['bye'])
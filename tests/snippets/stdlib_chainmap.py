@@ -0,0 +1,41 @@
+from collections import ChainMap
+
+from testutils import assert_raises
+
+first = {'a': 1}
+second = {'a': 2, 'b': 3}
+c = ChainMap(first, second)
+assert c['a'] == 1
+assert c['b'] == 3
+assert 'b' in c
+assert 'z' not in c
+assert c.get('z', 9) == 9
+assert_raises(KeyError, lambda: c['z'])
+
+# iteration yields each key once
+assert sorted(c) == ['a', 'b']
+assert len(c) == 2
+assert dict(c) == {'a': 1, 'b': 3}
+
+# writes and deletes only touch the first mapping
+c['b'] = 10
+assert first == {'a': 1, 'b': 10}
+assert second == {'a': 2, 'b': 3}
+del c['a']
+assert c['a'] == 2
+assert_raises(KeyError, lambda: c.__delitem__('z'))
+
+child = c.new_child()
+child['q'] = 1
+assert child.maps[0] == {'q': 1}
+assert child.maps[1] is first
+assert child.parents.maps == [first, second]
+assert 'q' not in c
+
+c.maps.append({'late': 4})
+assert c['late'] == 4
+
+assert repr(ChainMap({1: 2})) == 'ChainMap({1: 2})'
+assert ChainMap().maps == [{}]
+assert not ChainMap()
+assert c.copy().maps[0] is not first
@@ -0,0 +1,95 @@
+import contextvars
+from contextvars import ContextVar, Context, Token, copy_context
+
+from testutils import assertRaises
+
+cv = ContextVar('x', default=0)
+assert cv.name == 'x'
+assert cv.get() == 0
+token = cv.set(1)
+assert cv.get() == 1
+assert isinstance(token, Token)
+assert token.var is cv
+assert token.old_value is Token.MISSING
+assert repr(Token.MISSING) == '<Token.MISSING>'
+
+nodefault = ContextVar('y')
+with assertRaises(LookupError):
+    nodefault.get()
+assert nodefault.get(5) == 5
+
+# a copied context is independent of the caller's
+def in_copy():
+    assert cv.get() == 1
+    cv.set(2)
+    assert cv.get() == 2
+    return 'done'
+
+ctx = copy_context()
+assert ctx[cv] == 1
+assert ctx.run(in_copy) == 'done'
+assert cv.get() == 1
+assert ctx[cv] == 2
+assert cv in ctx and nodefault not in ctx
+assert len(ctx) == 1
+assert list(ctx) == [cv]
+assert list(ctx.keys()) == [cv]
+assert list(ctx.values()) == [2]
+assert list(ctx.items()) == [(cv, 2)]
+assert ctx.get(nodefault) is None
+assert ctx.get(nodefault, 3) == 3
+with assertRaises(KeyError):
+    ctx[nodefault]
+with assertRaises(TypeError):
+    ctx['x']
+
+# arguments are passed along, and a context can't be entered twice
+assert Context().run(lambda *a, **k: (a, k), 1, b=2) == ((1,), {'b': 2})
+with assertRaises(RuntimeError):
+    ctx.run(ctx.run, len, ())
+
+# reset restores the previous value, once
+token2 = cv.set(3)
+assert token2.old_value == 1
+cv.reset(token2)
+assert cv.get() == 1
+with assertRaises(RuntimeError):
+    cv.reset(token2)
+cv.reset(token)
+assert cv.get() == 0
+with assertRaises(ValueError):
+    nodefault.reset(cv.set(4))
+
+def reset_elsewhere(token):
+    cv.reset(token)
+
+with assertRaises(ValueError):
+    Context().run(reset_elsewhere, cv.set(5))
+
+# decimal keeps its context in a context variable
+import decimal
+
+def with_precision():
+    decimal.getcontext().prec = 3
+    return decimal.Decimal(1) / decimal.Decimal(3)
+
+assert copy_context().run(with_precision) == decimal.Decimal('0.333')
+assert decimal.getcontext().prec == 28
+
+# each asyncio task runs in a copy of the context it was created in
+import asyncio
+
+request = ContextVar('request', default=None)
+
+async def handle(name):
+    request.set(name)
+    await asyncio.sleep(0)
+    return request.get()
+
+async def serve():
+    request.set('outer')
+    results = await asyncio.gather(handle('a'), handle('b'))
+    return results, request.get()
+
+assert asyncio.run(serve()) == (['a', 'b'], 'outer')
+assert request.get() is None
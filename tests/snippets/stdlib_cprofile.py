@@ -0,0 +1,43 @@
+import cProfile
+import io
+import pstats
+import sys
+
+
+def fact(n):
+    return 1 if n <= 1 else n * fact(n - 1)
+
+
+with cProfile.Profile() as prof:
+    fact(5)
+    len([])
+
+stats = pstats.Stats(prof)
+entries = {func[2]: value for func, value in stats.stats.items()}
+cc, nc, tt, ct, callers = entries['fact']
+assert (cc, nc) == (1, 5)
+assert ct >= tt >= 0
+assert any('len' in func[2] for func in stats.stats)
+
+out = io.StringIO()
+pstats.Stats(prof, stream=out).strip_dirs().sort_stats('cumulative').print_stats()
+table = out.getvalue()
+assert 'primitive calls) in' in table
+assert 'ncalls  tottime  percall  cumtime  percall filename:lineno(function)' in table
+assert '5/1' in table
+assert pstats.SortKey.CUMULATIVE == 'cumulative'
+
+prof = cProfile.Profile()
+assert prof.runcall(fact, 3) == 6
+prof.create_stats()
+assert {func[2]: value for func, value in prof.stats.items()}['fact'][:2] == (1, 3)
+
+stdout = sys.stdout
+sys.stdout = io.StringIO()
+try:
+    cProfile.run('sum(range(1000))')
+    printed = sys.stdout.getvalue()
+finally:
+    sys.stdout = stdout
+assert 'function calls in' in printed
+assert 'sum' in printed
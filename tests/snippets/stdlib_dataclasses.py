@@ -0,0 +1,83 @@
+from dataclasses import (
+    dataclass, field, fields, asdict, astuple, replace, FrozenInstanceError
+)
+
+from testutils import assertRaises
+
+
+class A:
+    x: int
+    y: str = 'a'
+
+assert A.__annotations__ == {'x': int, 'y': str}
+assert A.y == 'a'
+assert not hasattr(A, 'x')
+
+
+@dataclass
+class P:
+    x: int
+    y: int = 0
+
+p = P(1)
+assert p.x == 1
+assert p.y == 0
+assert repr(p) == 'P(x=1, y=0)'
+assert p == P(1, 0)
+assert p != P(1, 2)
+assert P(y=2, x=1) == P(1, 2)
+assert [f.name for f in fields(P)] == ['x', 'y']
+assert [f.type for f in fields(P)] == [int, int]
+assert asdict(p) == {'x': 1, 'y': 0}
+assert astuple(p) == (1, 0)
+assert replace(p, y=3) == P(1, 3)
+assert p.y == 0
+
+with assertRaises(TypeError):
+    P()
+
+with assertRaises(TypeError):
+    p < P(2)
+
+
+@dataclass(order=True, frozen=True)
+class Q:
+    a: int
+    b: list = field(default_factory=list, compare=False, hash=False)
+    c: str = field(default='c', repr=False)
+
+q = Q(1)
+assert q.b == []
+assert q.b is not Q(1).b
+assert repr(q) == 'Q(a=1, b=[])'
+assert q < Q(2)
+assert Q(1, [1]) == Q(1, [2])
+assert hash(q) == hash(Q(1))
+
+with assertRaises(FrozenInstanceError):
+    q.a = 2
+
+with assertRaises(FrozenInstanceError):
+    del q.a
+
+assert q.a == 1
+
+
+@dataclass
+class Nested:
+    p: P
+    ps: list
+
+assert asdict(Nested(P(1), [P(2)])) == {
+    'p': {'x': 1, 'y': 0}, 'ps': [{'x': 2, 'y': 0}]
+}
+assert astuple(Nested(P(1), [P(2)])) == ((1, 0), [(2, 0)])
+
+
+@dataclass(init=False, repr=False, eq=False)
+class Plain:
+    x: int = 1
+
+assert Plain().x == 1
+assert Plain() != Plain()
+assert repr(Plain()).startswith('<')
@@ -0,0 +1,60 @@
+import faulthandler
+import os
+import subprocess
+import sys
+
+here = os.path.dirname(os.path.abspath(__file__))
+dump_path = os.path.join(here, 'faulthandler_tmp.txt')
+
+
+def dump(all_threads):
+    with open(dump_path, 'w') as f:
+        faulthandler.dump_traceback(f, all_threads=all_threads)
+    with open(dump_path) as f:
+        return f.read().splitlines()
+
+
+try:
+    lines = dump(all_threads=False)
+    assert lines[0] == 'Stack (most recent call first):', lines
+    assert lines[1].endswith(', line 12 in dump'), lines
+    assert lines[2].endswith(', line 18 in <module>'), lines
+    assert __file__ in lines[1]
+
+    lines = dump(all_threads=True)
+    assert lines[0].startswith('Current thread 0x'), lines
+    assert lines[0].endswith(' (most recent call first):'), lines
+finally:
+    os.remove(dump_path)
+
+assert not faulthandler.is_enabled()
+faulthandler.enable()
+assert faulthandler.is_enabled()
+assert faulthandler.disable()
+assert not faulthandler.is_enabled()
+assert not faulthandler.disable()
+
+try:
+    faulthandler.enable(-1)
+except ValueError:
+    pass
+else:
+    assert False, "a negative file descriptor should be rejected"
+assert not faulthandler.is_enabled()
+
+# a crashing interpreter reports where it was
+p = subprocess.Popen(
+    [sys.executable, '-c',
+     'import faulthandler\n'
+     'faulthandler.enable()\n'
+     'def crash():\n'
+     '    faulthandler._sigsegv()\n'
+     'crash()\n'],
+    stderr=subprocess.PIPE,
+)
+_, err = p.communicate()
+err = err.decode().splitlines()
+assert p.returncode != 0
+assert 'Fatal Python error: Segmentation fault' in err, err
+assert '  File "<string>", line 4 in crash' in err, err
+assert '  File "<string>", line 5 in <module>' in err, err
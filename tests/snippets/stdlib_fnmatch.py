@@ -0,0 +1,57 @@
+import fnmatch
+import os
+import re
+
+from testutils import assert_raises
+
+assert fnmatch.fnmatch('foo.txt', '*.txt')
+assert not fnmatch.fnmatch('foo.txt', '*.py')
+assert fnmatch.fnmatch('foo.txt', 'f??.txt')
+assert not fnmatch.fnmatch('fo.txt', 'f??.txt')
+assert fnmatch.fnmatch('foo.txt', '[ef]oo.*')
+assert fnmatch.fnmatch('goo.txt', '[!ef]oo.*')
+assert not fnmatch.fnmatch('foo.txt', '[!ef]oo.*')
+assert fnmatch.fnmatch('b', '[a-c]')
+assert not fnmatch.fnmatch('d', '[a-c]')
+assert fnmatch.fnmatch('.hidden', '*')
+assert fnmatch.fnmatch('a\nb', 'a*b')
+
+# the case is only normalized where the OS ignores it
+assert fnmatch.fnmatch('FOO.TXT', '*.txt') == (os.name == 'nt')
+assert fnmatch.fnmatchcase('foo.txt', '*.txt')
+assert not fnmatch.fnmatchcase('FOO.TXT', '*.txt')
+
+# brackets and regex syntax are plain characters where they aren't wildcards
+assert fnmatch.fnmatch('[', '[')
+assert fnmatch.fnmatch('a[b', 'a[b')
+assert fnmatch.fnmatch(']', '[]]')
+assert fnmatch.fnmatch('a', '[!]]')
+assert not fnmatch.fnmatch(']', '[!]]')
+assert fnmatch.fnmatch('^', '[^a]')
+assert not fnmatch.fnmatch('b', '[^a]')
+assert fnmatch.fnmatch('-', '[a-]')
+assert fnmatch.fnmatch('\\', '[\\]')
+assert fnmatch.fnmatch('&', '[a&&b]')
+assert fnmatch.fnmatch('(x)+{2}$', '(x)+{2}$')
+assert fnmatch.fnmatch('a.b', 'a.b')
+assert not fnmatch.fnmatch('axb', 'a.b')
+
+assert fnmatch.filter(['a.py', 'b.txt', 'c.py', '.py'], '*.py') == ['a.py', 'c.py', '.py']
+assert fnmatch.filter(iter(['x', 'y']), '[xz]') == ['x']
+assert fnmatch.filter([], '*') == []
+assert_raises(TypeError, lambda: fnmatch.filter([1], '*'))
+
+# translate gives a regular expression for the re module
+assert fnmatch.translate('*.txt') == r'(?s:.*\.txt)\Z'
+assert fnmatch.translate('[!ab]?') == r'(?s:[^ab].)\Z'
+for pattern, name, matches in [
+    ('*.txt', 'foo.txt', True),
+    ('*.txt', 'foo.txt\n', False),
+    ('[!ab]?', 'cd', True),
+    ('[!ab]?', 'ad', False),
+    ('[]]x', ']x', True),
+    ('[', '[', True),
+    ('a|b', 'a|b', True),
+    ('a|b', 'a', False),
+]:
+    assert bool(re.match(fnmatch.translate(pattern), name)) == matches, pattern
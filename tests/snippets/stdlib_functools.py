@@ -1,4 +1,4 @@
-from functools import reduce
+from functools import reduce, total_ordering, cmp_to_key
 from testutils import assertRaises
 
 class Squares:
@@ -77,3 +77,80 @@ assert reduce(add, SequenceClass(1), 42) == 42
 
 d = {"one": 1, "two": 2, "three": 3}
 assert reduce(add, d) == "".join(d.keys())
+
+
+@total_ordering
+class Version:
+    def __init__(self, value):
+        self.value = value
+
+    def __eq__(self, other):
+        if not isinstance(other, Version):
+            return NotImplemented
+        return self.value == other.value
+
+    def __lt__(self, other):
+        if not isinstance(other, Version):
+            return NotImplemented
+        return self.value < other.value
+
+
+v1, v2 = Version(1), Version(2)
+assert v1 < v2 and not v2 < v1
+assert v1 <= v2 and v1 <= Version(1) and not v2 <= v1
+assert v2 > v1 and not v1 > v2
+assert v2 >= v1 and v1 >= Version(1) and not v1 >= v2
+assert v1 == Version(1) and v1 != v2
+assert sorted([v2, v1])[0] is v1
+assert 'total_ordering' in Version.__ge__.__doc__
+
+# NotImplemented from the root method makes the operator fail
+with assertRaises(TypeError):
+    v1 >= 3
+with assertRaises(TypeError):
+    v1 > 3
+
+
+@total_ordering
+class Reversed:
+    def __init__(self, value):
+        self.value = value
+
+    def __ge__(self, other):
+        return self.value <= other.value
+
+
+assert Reversed(2) <= Reversed(1)
+assert Reversed(1) > Reversed(2)
+assert not Reversed(2) < Reversed(2)
+
+with assertRaises(ValueError):
+    @total_ordering
+    class Unordered:
+        pass
+
+
+def three_way(a, b):
+    return (a > b) - (a < b)
+
+
+def reverse_numeric(a, b):
+    return b - a
+
+
+assert sorted([3, 1, 2], key=cmp_to_key(three_way)) == [1, 2, 3]
+assert sorted([3, 1, 2], key=cmp_to_key(reverse_numeric)) == [3, 2, 1]
+assert sorted(['bb', 'a', 'ccc'], key=cmp_to_key(lambda a, b: len(a) - len(b))) == ['a', 'bb', 'ccc']
+assert max([3, 9, 1], key=cmp_to_key(reverse_numeric)) == 1
+
+Key = cmp_to_key(reverse_numeric)
+assert Key(5).obj == 5
+assert Key(2) < Key(1) and not Key(1) < Key(2)
+assert Key(2) <= Key(2) and Key(1) >= Key(2)
+assert Key(1) > Key(2)
+assert Key(1) == Key(1) and Key(1) != Key(2)
+
+with assertRaises(TypeError):
+    Key(1) < 1
+with assertRaises(TypeError):
+    hash(Key(1))
@@ -0,0 +1,88 @@
+import glob
+import os
+import time
+
+base_folder = os.environ["TEMP"] if os.name == "nt" else "/tmp"
+top = os.path.join(base_folder, "rustpython_test_glob_" + str(int(time.time())))
+
+def path(*parts):
+    return os.path.join(top, *parts)
+
+def touch(*parts):
+    with open(path(*parts), "w"):
+        pass
+
+# top/
+#   a.py  b.py  c.txt  .hidden.py  [x].py
+#   pkg/
+#     mod.py
+#     sub/
+#       deep.py
+#   .cache/
+#     cached.py
+os.makedirs(path("pkg", "sub"))
+os.mkdir(path(".cache"))
+for name in ["a.py", "b.py", "c.txt", ".hidden.py", "[x].py"]:
+    touch(name)
+touch("pkg", "mod.py")
+touch("pkg", "sub", "deep.py")
+touch(".cache", "cached.py")
+
+def g(pattern, **kwargs):
+    return sorted(glob.glob(path(pattern), **kwargs))
+
+assert g("*.py") == [path("[x].py"), path("a.py"), path("b.py")]
+assert g("?.py") == [path("a.py"), path("b.py")]
+assert g("[ab].py") == [path("a.py"), path("b.py")]
+assert g("[!a].*") == [path("b.py"), path("c.txt")]
+assert g("c.*") == [path("c.txt")]
+assert g("nothing*") == []
+assert g("c.txt") == [path("c.txt")]
+assert g("missing.txt") == []
+
+# hidden files only match patterns that start with a dot
+assert g(".*") == [path(".cache"), path(".hidden.py")]
+assert g("*") == [path("[x].py"), path("a.py"), path("b.py"), path("c.txt"), path("pkg")]
+
+# directories in the pattern
+assert g("*/*.py") == [path("pkg", "mod.py")]
+assert g("*/") == [path("pkg") + os.sep]
+
+# ** only recurses when asked to
+assert g(os.path.join("**", "*.py")) == [path("pkg", "mod.py")]
+assert g(os.path.join("**", "*.py"), recursive=True) == [
+    path("[x].py"), path("a.py"), path("b.py"),
+    path("pkg", "mod.py"), path("pkg", "sub", "deep.py"),
+]
+assert g(os.path.join("pkg", "**"), recursive=True) == [
+    path("pkg") + os.sep, path("pkg", "mod.py"), path("pkg", "sub"), path("pkg", "sub", "deep.py"),
+]
+
+# iglob is lazy
+it = glob.iglob(path("*.txt"))
+assert iter(it) is it
+assert list(it) == [path("c.txt")]
+
+# escape
+assert glob.escape("a[b]*?.py") == "a[[]b][*][?].py"
+assert glob.escape("plain.py") == "plain.py"
+assert glob.glob(glob.escape(path("[x].py"))) == [path("[x].py")]
+assert glob.has_magic("*.py") and not glob.has_magic("a.py")
+
+# relative to the current directory
+cwd = os.getcwd()
+os.chdir(top)
+try:
+    assert sorted(glob.glob("*.py")) == ["[x].py", "a.py", "b.py"]
+    assert sorted(glob.glob("**/*.py", recursive=True)) == [
+        "[x].py", "a.py", "b.py", os.path.join("pkg", "mod.py"), os.path.join("pkg", "sub", "deep.py"),
+    ]
+finally:
+    os.chdir(cwd)
+
+for dirpath, dirnames, filenames in os.walk(top, topdown=False):
+    for name in filenames:
+        os.remove(os.path.join(dirpath, name))
+    for name in dirnames:
+        os.rmdir(os.path.join(dirpath, name))
+os.rmdir(top)
@@ -0,0 +1,44 @@
+import hashlib
+import hmac
+
+from testutils import assert_raises
+
+msg = b'The quick brown fox jumps over the lazy dog'
+expected = 'f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8'
+assert hmac.new(b'key', msg, 'sha256').hexdigest() == expected
+assert hmac.new(b'key', msg, hashlib.sha256).hexdigest() == expected
+assert hmac.new(b'key', msg, digestmod='md5').hexdigest() == '80070713463e7749b90c2dc24911e275'
+assert hmac.digest(b'key', msg, 'sha256') == bytes.fromhex(expected)
+
+# the message can be fed in pieces, and copies are independent
+h = hmac.new(b'key', digestmod='sha256')
+h.update(b'The quick brown fox ')
+c = h.copy()
+h.update(b'jumps over the lazy dog')
+assert h.hexdigest() == expected
+assert h.digest() == bytes.fromhex(expected)
+assert c.hexdigest() != expected
+c.update(b'jumps over the lazy dog')
+assert c.hexdigest() == expected
+
+assert h.name == 'hmac-sha256'
+assert h.digest_size == 32
+assert h.block_size == 64
+assert hmac.new(b'', digestmod='sha512').block_size == 128
+
+# RFC 4231 test case 6: keys longer than the block size are hashed first
+key = b'\xaa' * 131
+data = b'Test Using Larger Than Block-Size Key - Hash Key First'
+assert hmac.new(key, data, 'sha256').hexdigest() == \
+    '60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54'
+assert hmac.new(key, data, 'sha512').hexdigest() == \
+    '80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352' \
+    '6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598'
+assert hmac.digest(key, data, 'sha256') == hmac.new(key, data, 'sha256').digest()
+
+assert_raises(TypeError, lambda: hmac.new('key', msg, 'sha256'))
+
+assert hmac.compare_digest(b'abc', b'abc')
+assert not hmac.compare_digest(b'abc', b'abd')
+assert hmac.compare_digest('abc', 'abc')
+assert_raises(TypeError, lambda: hmac.compare_digest('abc', b'abc'))
@@ -0,0 +1,102 @@
+import inspect
+
+from testutils import assert_raises
+
+assert str(inspect.signature(lambda a, b=1, *c, **d: None)) == '(a, b=1, *c, **d)'
+
+
+def f(x: int, y: "s" = 2, *, z=3, **kw) -> str:
+    """Summary.
+
+    Details.
+    """
+    return x
+
+
+sig = inspect.signature(f)
+assert str(sig) == "(x: int, y: 's' = 2, *, z=3, **kw) -> str"
+assert sig.return_annotation is str
+params = list(sig.parameters.values())
+assert [p.name for p in params] == ['x', 'y', 'z', 'kw']
+assert [p.kind for p in params] == [
+    inspect.Parameter.POSITIONAL_OR_KEYWORD,
+    inspect.Parameter.POSITIONAL_OR_KEYWORD,
+    inspect.Parameter.KEYWORD_ONLY,
+    inspect.Parameter.VAR_KEYWORD,
+]
+assert str(params[2].kind) == 'KEYWORD_ONLY'
+assert params[0].default is inspect.Parameter.empty
+assert params[1].default == 2
+assert params[0].annotation is int
+assert params[2].annotation is inspect.Parameter.empty
+
+assert str(inspect.signature(lambda a, *, b: None)) == '(a, *, b)'
+assert str(inspect.signature(lambda: None)) == '()'
+
+spec = inspect.getfullargspec(f)
+assert spec.args == ['x', 'y']
+assert spec.varargs is None
+assert spec.varkw == 'kw'
+assert spec.defaults == (2,)
+assert spec.kwonlyargs == ['z']
+assert spec.kwonlydefaults == {'z': 3}
+assert spec.annotations == {'x': int, 'y': 's', 'return': str}
+
+
+class C:
+    def __init__(self, a, b=2):
+        pass
+
+    def method(self, q, *r):
+        pass
+
+    def __call__(self, z):
+        pass
+
+
+c = C(1)
+assert str(inspect.signature(C)) == '(a, b=2)'
+assert str(inspect.signature(c.method)) == '(q, *r)'
+assert str(inspect.signature(C.method)) == '(self, q, *r)'
+assert str(inspect.signature(c)) == '(z)'
+assert inspect.getfullargspec(c.method).args == ['self', 'q']
+
+assert_raises(TypeError, lambda: inspect.signature(1))
+assert_raises(ValueError, lambda: inspect.signature(int))
+
+bound = sig.bind(1, z=5, w=6)
+assert bound.arguments == {'x': 1, 'z': 5, 'kw': {'w': 6}}
+assert bound.args == (1,)
+assert bound.kwargs == {'z': 5, 'w': 6}
+bound.apply_defaults()
+assert bound.arguments['y'] == 2
+assert_raises(TypeError, lambda: sig.bind())
+assert_raises(TypeError, lambda: sig.bind(1, 2, 3))
+
+assert inspect.isfunction(f)
+assert not inspect.isfunction(c.method)
+assert inspect.ismethod(c.method)
+assert inspect.isclass(C)
+assert not inspect.isclass(c)
+assert inspect.isbuiltin(len)
+assert inspect.isroutine(len)
+assert inspect.ismodule(inspect)
+
+
+def gen():
+    yield 1
+
+
+assert inspect.isgeneratorfunction(gen)
+assert not inspect.isgeneratorfunction(f)
+assert inspect.isgenerator(gen())
+
+assert inspect.getdoc(f) == 'Summary.\n\nDetails.'
+
+source = inspect.getsource(f)
+assert source.startswith('def f(x: int, y: "s" = 2, *, z=3, **kw) -> str:\n')
+assert source.endswith('    return x\n')
+lines, lineno = inspect.getsourcelines(C.method)
+assert lines == ['    def method(self, q, *r):\n', '        pass\n']
+assert lineno == 50
+assert inspect.getsource(C).startswith('class C:\n')
@@ -39,3 +39,154 @@ with FileIO('README.md') as fio:
 	assert len(nres) == 1
 	nres = fio.read(2)
 	assert len(nres) == 2
+
+with FileIO('README.md') as fio:
+	data = fio.read()
+	assert fio.tell() == len(data)
+	assert fio.seek(2) == 2
+	assert fio.read(3) == data[2:5]
+	assert fio.seek(-4, 2) == len(data) - 4
+	assert fio.read() == data[-4:]
+	assert fio.seek(-2, 1) == len(data) - 2
+	with assertRaises(OSError):
+		fio.seek(-1)
+
+with open('README.md', 'rb') as fp:
+	fp.seek(-3, 2)
+	assert fp.read() == data[-3:]
+	fp.seek(1)
+	assert fp.tell() == 1
+
+# text files encode what they write
+path = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'io_encoding_tmp.txt')
+try:
+	with open(path, 'w', encoding='latin-1') as f:
+		assert f.encoding == 'latin-1'
+		assert f.errors == 'strict'
+		f.write('caf\xe9 ')
+		with assertRaises(UnicodeEncodeError):
+			f.write('€')
+		# what was written before is kept
+		f.reconfigure(encoding='utf-8')
+		assert f.errors == 'strict'
+		assert f.write('€') == 1
+		f.reconfigure(encoding='ascii', errors='backslashreplace')
+		f.write(' €\xe9')
+		f.reconfigure(errors='replace')
+		assert f.encoding == 'ascii'
+		# they only change through reconfigure()
+		with assertRaises(AttributeError):
+			f.errors = None
+		with assertRaises(AttributeError):
+			f.encoding = None
+		f.write(' €')
+	with open(path, 'rb') as f:
+		assert f.read() == b'caf\xe9 \xe2\x82\xac \\u20ac\\xe9 ?'
+	# and decode what they read
+	with open(path, encoding='latin-1') as f:
+		assert f.read() == 'caf\xe9 \xe2\x82\xac \\u20ac\\xe9 ?'
+	with open(path, encoding='ascii', errors='replace') as f:
+		assert f.readline() == 'caf\ufffd \ufffd\ufffd\ufffd \\u20ac\\xe9 ?'
+	with open(path, encoding='utf-8') as f:
+		with assertRaises(UnicodeDecodeError):
+			f.read()
+finally:
+	os.remove(path)
+
+# newline translation
+path = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'io_newline_tmp.txt')
+try:
+	with open(path, 'wb') as f:
+		f.write(b'one\r\ntwo\rthree\nfour')
+	with open(path) as f:
+		assert f.read() == 'one\ntwo\nthree\nfour'
+	with open(path) as f:
+		assert f.readline() == 'one\n'
+		assert f.tell() == 5
+		assert f.readline() == 'two\n'
+		assert f.readline() == 'three\n'
+		assert f.readline() == 'four'
+		assert f.readline() == ''
+		f.seek(5)
+		assert f.read() == 'two\nthree\nfour'
+	with open(path, newline='') as f:
+		assert f.readline() == 'one\r\n'
+		assert f.readline() == 'two\r'
+		assert f.read() == 'three\nfour'
+	with open(path, newline='\r') as f:
+		assert f.readline() == 'one\r'
+		assert f.readline() == '\ntwo\r'
+		assert f.readline() == 'three\nfour'
+	with open(path, newline='\n') as f:
+		assert f.readline() == 'one\r\n'
+		assert f.readline() == 'two\rthree\n'
+		assert f.readline() == 'four'
+
+	with open(path, 'w', newline='\r\n') as f:
+		assert f.write('a\nb\n') == 4
+	with open(path, 'rb') as f:
+		assert f.read() == b'a\r\nb\r\n'
+	with open(path, 'w', newline='') as f:
+		f.write('a\nb\r\n')
+	with open(path, 'rb') as f:
+		assert f.read() == b'a\nb\r\n'
+
+	with assertRaises(ValueError):
+		open(path, newline='\n\n')
+
+	# lines longer than a read from the buffer
+	line = 'x' * 10000 + '\n'
+	with open(path, 'w') as f:
+		f.write(line * 3)
+	with open(path) as f:
+		assert f.readline() == line
+		assert f.tell() == len(line)
+		assert f.read() == line * 2
+finally:
+	os.remove(path)
+
+with assertRaises(ValueError):
+	open('README.md', 'rb', encoding='utf-8')
+with assertRaises(LookupError):
+	open('README.md', encoding='no-such-encoding')
+
+# updating and appending; closing a file closes all of its layers
+path = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'io_modes_tmp.txt')
+try:
+	with open(path, 'w+') as f:
+		assert f.write('hello') == 5
+		f.seek(0)
+		assert f.read() == 'hello'
+		assert f.tell() == 5
+	assert f.closed
+	assert f.buffer.closed
+	assert f.buffer.raw.closed
+	f.close()
+	with open(path, 'a') as f:
+		f.write(' world')
+	with open(path, 'r+b') as f:
+		assert f.read() == b'hello world'
+		f.seek(0)
+		f.write(b'J')
+	with open(path) as f:
+		assert f.read() == 'Jello world'
+
+	fd = os.open(path, os.O_RDONLY)
+	with open(fd, 'rb', closefd=False) as f:
+		assert f.read(5) == b'Jello'
+	# still open, so this doesn't raise
+	os.close(fd)
+	with assertRaises(ValueError):
+		open(path, closefd=False)
+finally:
+	os.remove(path)
+
+with StringIO('abc') as s:
+	s.read(2)
+	assert s.tell() == 2
+	assert not s.closed
+assert s.closed
+with BytesIO(b'abc') as b:
+	b.read(1)
+	assert b.tell() == 1
+assert b.closed
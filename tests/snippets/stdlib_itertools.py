@@ -21,6 +21,36 @@ assert next(x) == 'b'
 with assertRaises(TypeError):
     next(x)
 
+# from_iterable
+from_iterable = chain.from_iterable
+assert list(from_iterable([[1, 2], [3]])) == [1, 2, 3]
+assert list(from_iterable([])) == []
+assert list(from_iterable(["ab", (), "c"])) == ['a', 'b', 'c']
+
+def gen():
+    yield [1]
+    yield range(2, 4)
+
+x = from_iterable(gen())
+assert next(x) == 1
+assert list(x) == [2, 3]
+with assertRaises(StopIteration):
+    next(x)
+
+# each sub-iterable is consumed before the next one is fetched
+fetched = []
+def sources():
+    for s in ("ab", "c"):
+        fetched.append(s)
+        yield s
+
+x = from_iterable(sources())
+assert next(x) == 'a'
+assert next(x) == 'b'
+assert fetched == ["ab"]
+assert next(x) == 'c'
+assert fetched == ["ab", "c"]
+
 # itertools.count tests
 
 # default arguments
@@ -46,34 +76,60 @@ c = itertools.count(5, 0)
 assert next(c) == 5
 assert next(c) == 5
 
-# itertools.count TODOs: kwargs and floats
-
 # step kwarg
-# c = itertools.count(step=5)
-# assert next(c) == 0
-# assert next(c) == 5
+c = itertools.count(step=5)
+assert next(c) == 0
+assert next(c) == 5
 
 # start kwarg
-# c = itertools.count(start=10)
-# assert next(c) == 10
+c = itertools.count(start=10)
+assert next(c) == 10
 
 # float start
-# c = itertools.count(0.5)
-# assert next(c) == 0.5
-# assert next(c) == 1.5
-# assert next(c) == 2.5
+c = itertools.count(0.5)
+assert next(c) == 0.5
+assert next(c) == 1.5
+assert next(c) == 2.5
 
 # float step
-# c = itertools.count(1, 0.5)
-# assert next(c) == 1
-# assert next(c) == 1.5
-# assert next(c) == 2
+c = itertools.count(1, 0.5)
+assert next(c) == 1
+assert next(c) == 1.5
+assert next(c) == 2
 
 # float start + step
-# c = itertools.count(0.5, 0.5)
-# assert next(c) == 0.5
-# assert next(c) == 1
-# assert next(c) == 1.5
+c = itertools.count(0.5, 0.5)
+assert next(c) == 0.5
+assert next(c) == 1
+assert next(c) == 1.5
+
+assert list(itertools.islice(itertools.count(1, 2), 3)) == [1, 3, 5]
+
+assert repr(itertools.count()) == "count(0)"
+assert repr(itertools.count(2, 3)) == "count(2, 3)"
+assert repr(itertools.count(1.5)) == "count(1.5)"
+
+with assertRaises(TypeError):
+    itertools.count("a")
+with assertRaises(TypeError):
+    itertools.count(1, "a")
+
+
+# itertools.cycle tests
+c = itertools.cycle("abc")
+assert [next(c) for _ in range(7)] == list("abcabca")
+
+c = itertools.cycle(iter([1, 2]))
+assert list(itertools.islice(c, 5)) == [1, 2, 1, 2, 1]
+
+c = itertools.cycle([])
+with assertRaises(StopIteration):
+    next(c)
+with assertRaises(StopIteration):
+    next(c)
+
+with assertRaises(TypeError):
+    itertools.cycle(1)
 
 
 # itertools.repeat tests
@@ -101,6 +157,9 @@ r = itertools.repeat(1, -1)
 with assertRaises(StopIteration):
     next(r)
 
+assert list(itertools.repeat("x", 3)) == ["x", "x", "x"]
+assert list(itertools.repeat("x", 0)) == []
+
 
 # itertools.starmap tests
 starmap = itertools.starmap
@@ -184,6 +243,26 @@ assert_matches_seq(it, [1, 2])
 it = i([1, 2, 3], None, None, 3)
 assert_matches_seq(it, [1])
 
+it = i(itertools.count(), 2, 5)
+assert_matches_seq(it, [2, 3, 4])
+
+it = i(itertools.count(), 1, None, 3)
+assert next(it) == 1
+assert next(it) == 4
+
+# doesn't consume more than needed
+source = iter(range(10))
+assert_matches_seq(i(source, 0, 3, 2), [0, 2])
+assert next(source) == 3
+
+source = iter(range(10))
+assert_matches_seq(i(source, 2), [0, 1])
+assert next(source) == 2
+
+for args in [(-1,), (-1, 3), (1, -3), (0, 3, 0), (0, 3, -1), ("a",)]:
+    with assertRaises(ValueError):
+        i([], *args)
+
 # itertools.filterfalse
 it = itertools.filterfalse(lambda x: x%2, range(10))
 assert 0 == next(it)
@@ -209,4 +288,68 @@ assert 6 == next(it)
 assert 4 == next(it)
 assert 1 == next(it)
 with assertRaises(StopIteration):
-    next(it)
\ No newline at end of file
+    next(it)
+
+it = itertools.dropwhile(lambda x: x < 3, itertools.count())
+assert next(it) == 3
+assert next(it) == 4
+
+# the predicate is not consulted after it first fails
+it = itertools.dropwhile(lambda x: x < 3, [1, 5, 1])
+assert list(it) == [5, 1]
+
+assert list(tw(lambda x: x < 3, itertools.count())) == [0, 1, 2]
+assert list(itertools.filterfalse(lambda x: x < 3, range(6))) == [3, 4, 5]
+
+
+# itertools.tee
+a, b = itertools.tee([1, 2, 3, 4])
+assert next(a) == 1
+assert next(a) == 2
+assert list(b) == [1, 2, 3, 4]
+assert list(a) == [3, 4]
+
+a, b, c = itertools.tee(iter(range(5)), 3)
+assert [next(a), next(b), next(a)] == [0, 0, 1]
+assert list(c) == [0, 1, 2, 3, 4]
+assert list(a) == [2, 3, 4]
+assert list(b) == [1, 2, 3, 4]
+
+assert len(itertools.tee([1])) == 2
+assert itertools.tee([1], 0) == ()
+with assertRaises(ValueError):
+    itertools.tee([1], -1)
+
+# the underlying iterator is only advanced once per element
+pulled = []
+def source():
+    for x in range(3):
+        pulled.append(x)
+        yield x
+
+a, b = itertools.tee(source())
+assert list(a) == [0, 1, 2]
+assert list(b) == [0, 1, 2]
+assert pulled == [0, 1, 2]
+
+# teeing a tee copies it
+a, b = itertools.tee("abc")
+next(a)
+c, d = itertools.tee(a)
+assert list(c) == ['b', 'c']
+assert list(d) == ['b', 'c']
+
+# long buffers can be freed
+a, b = itertools.tee(range(100000))
+for _ in a:
+    pass
+del b
+
+# itertools.groupby
+assert [(k, list(g)) for k, g in itertools.groupby('aabbbc')] == \
+    [('a', ['a', 'a']), ('b', ['b', 'b', 'b']), ('c', ['c'])]
+assert [k for k, _ in itertools.groupby([1, 3, 2, 4, 5], key=lambda x: x % 2)] == [1, 0, 1]
+groups = itertools.groupby('aabb')
+_, first = next(groups)
+next(groups)
+assert list(first) == []
@@ -0,0 +1,38 @@
+from collections import namedtuple, OrderedDict
+from testutils import assert_raises
+
+Point = namedtuple('Point', 'x y')
+p = Point(1, 2)
+assert p.x == 1 and p.y == 2
+assert p == (1, 2)
+assert isinstance(p, tuple)
+assert repr(p) == 'Point(x=1, y=2)'
+assert Point._fields == ('x', 'y')
+assert Point(y=5, x=4) == (4, 5)
+x, y = p
+assert (x, y) == (1, 2)
+assert hash(p) == hash((1, 2))
+
+assert p._asdict() == {'x': 1, 'y': 2}
+assert type(p._asdict()) is dict
+assert p._replace(x=5) == Point(5, 2)
+assert_raises(ValueError, lambda: p._replace(z=1))
+assert Point._make([3, 4]) == Point(3, 4)
+assert_raises(TypeError, lambda: Point._make([1]))
+assert Point.x.__doc__ == 'Alias for field number 0'
+
+Record = namedtuple('Record', ['a', 'b', 'c'], defaults=[10, 20])
+assert Record(1) == (1, 10, 20)
+assert Record(1, 2) == (1, 2, 20)
+assert Record._field_defaults == {'b': 10, 'c': 20}
+assert_raises(TypeError, lambda: Record())
+
+assert_raises(ValueError, lambda: namedtuple('Bad', 'def x'))
+assert_raises(ValueError, lambda: namedtuple('Bad', 'x x'))
+assert_raises(ValueError, lambda: namedtuple('Bad', '_x'))
+assert namedtuple('Renamed', 'def x x _y', rename=True)._fields == ('_0', 'x', '_2', '_3')
+
+# dict subclasses must see their initial items through __setitem__
+od = OrderedDict(zip('ab', [1, 2]))
+assert list(od.items()) == [('a', 1), ('b', 2)]
+assert list(OrderedDict(b=1, a=2)) == ['b', 'a']
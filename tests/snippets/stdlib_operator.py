@@ -0,0 +1,134 @@
+import operator
+import _operator
+
+from testutils import assertRaises
+
+# The pure-Python module picks up the native implementations:
+assert operator.add is _operator.add
+assert operator.itemgetter is _operator.itemgetter
+
+assert operator.add(1, 2) == 3
+assert operator.sub(5, 3) == 2
+assert operator.mul("ab", 2) == "abab"
+assert operator.truediv(7, 2) == 3.5
+assert operator.floordiv(7, 2) == 3
+assert operator.mod(7, 2) == 1
+assert operator.pow(2, 10) == 1024
+assert operator.neg(3) == -3
+assert operator.pos(-3) == -3
+assert operator.abs(-3) == 3
+assert operator.invert(0) == -1
+assert operator.inv(0) == -1
+assert operator.lshift(1, 3) == 8
+assert operator.rshift(8, 3) == 1
+assert operator.and_(6, 3) == 2
+assert operator.or_(6, 3) == 7
+assert operator.xor(6, 3) == 5
+assert operator.index(5) == 5
+assert operator.__add__(1, 2) == 3
+
+assert operator.lt(1, 2)
+assert operator.le(2, 2)
+assert operator.eq(2, 2)
+assert operator.ne(1, 2)
+assert operator.ge(2, 2)
+assert operator.gt(3, 2)
+assert operator.not_(0) is True
+assert operator.truth([1]) is True
+assert operator.is_(None, None)
+assert operator.is_not(None, 1)
+
+assert operator.concat([1], [2]) == [1, 2]
+with assertRaises(TypeError):
+    operator.concat(1, 2)
+assert operator.contains([1, 2], 2)
+assert operator.countOf([1, 2, 1], 1) == 2
+assert operator.indexOf([1, 2, 1], 2) == 1
+with assertRaises(ValueError):
+    operator.indexOf([1], 2)
+
+l = [1, 2, 3]
+assert operator.getitem(l, 1) == 2
+operator.setitem(l, 1, 5)
+assert l == [1, 5, 3]
+operator.delitem(l, 0)
+assert l == [5, 3]
+
+l = [1]
+assert operator.iadd(l, [2]) is l
+assert l == [1, 2]
+assert operator.iadd(1, 2) == 3
+assert operator.iconcat([1], [2]) == [1, 2]
+
+assert operator.length_hint([1, 2]) == 2
+
+
+class Hinted:
+    def __length_hint__(self):
+        return 3
+
+
+assert operator.length_hint(Hinted()) == 3
+assert operator.length_hint(object(), 5) == 5
+
+
+# dunder methods are used
+class Vector:
+    def __init__(self, x):
+        self.x = x
+
+    def __add__(self, other):
+        return Vector(self.x + other.x)
+
+    def __getitem__(self, key):
+        return self.x * key
+
+
+assert operator.add(Vector(1), Vector(2)).x == 3
+assert operator.getitem(Vector(3), 2) == 6
+
+# itemgetter
+assert operator.itemgetter(1, 0)(('a', 'b')) == ('b', 'a')
+assert operator.itemgetter(1)("abc") == "b"
+assert operator.itemgetter("k")({"k": 1}) == 1
+assert sorted([(1, 'b'), (2, 'a')], key=operator.itemgetter(1)) == [(2, 'a'), (1, 'b')]
+assert repr(operator.itemgetter(1, 'a')) == "operator.itemgetter(1, 'a')"
+with assertRaises(TypeError):
+    operator.itemgetter()
+
+
+# attrgetter
+class Name:
+    first = "Ada"
+    last = "Lovelace"
+
+
+class Person:
+    name = Name()
+    age = 36
+
+
+assert operator.attrgetter("age")(Person()) == 36
+assert operator.attrgetter("name.first")(Person()) == "Ada"
+assert operator.attrgetter("name.first", "name.last")(Person()) == ("Ada", "Lovelace")
+assert repr(operator.attrgetter("a.b")) == "operator.attrgetter('a.b')"
+with assertRaises(AttributeError):
+    operator.attrgetter("name.middle")(Person())
+with assertRaises(TypeError):
+    operator.attrgetter(1)
+
+# methodcaller
+assert operator.methodcaller("split", ",")("a,b") == ["a", "b"]
+
+
+class Greeter:
+    def greet(self, greeting, name="world"):
+        return "{}, {}".format(greeting, name)
+
+
+assert operator.methodcaller("greet", "hi", name="you")(Greeter()) == "hi, you"
+assert repr(operator.methodcaller("f", 1, x=2)) == "operator.methodcaller('f', 1, x=2)"
+with assertRaises(TypeError):
+    operator.methodcaller()
+with assertRaises(TypeError):
+    operator.methodcaller(1)
@@ -12,6 +12,8 @@ os.close(fd)
 assert_raises(OSError, lambda: os.read(fd, 10))
 assert_raises(FileNotFoundError, lambda: os.open('DOES_NOT_EXIST', os.O_RDONLY))
 assert_raises(FileNotFoundError, lambda: os.open('DOES_NOT_EXIST', os.O_WRONLY))
+assert_raises(OverflowError, lambda: os.open('DOES_NOT_EXIST', os.O_RDONLY, 2**40))
+assert_raises((ValueError, OverflowError), lambda: os.open('README.md', 2**40))
 assert_raises(FileNotFoundError, lambda: os.rename('DOES_NOT_EXIST', 'DOES_NOT_EXIST 2'))
 
 try:
@@ -252,6 +254,67 @@ with TestWithTempDir() as tmpdir:
 		os.chdir(tmpdir)
 		assert os.getcwd() == os.path.realpath(tmpdir)
 		os.path.exists(FILE_NAME)
+		assert set(os.listdir()) == set(os.listdir(tmpdir))
+
+	# directories
+	nested = os.path.join(tmpdir, "a", "b", "c")
+	os.makedirs(nested)
+	assert os.path.isdir(nested)
+	assert_raises(FileExistsError, lambda: os.makedirs(nested))
+	os.makedirs(nested, exist_ok=True)
+	assert_raises(FileExistsError, lambda: os.makedirs(fname, exist_ok=True))
+	assert os.listdir(nested) == []
+	assert os.listdir(os.path.join(tmpdir, "a")) == ["b"]
+	os.removedirs(nested)
+	assert not os.path.exists(os.path.join(tmpdir, "a"))
+	assert os.path.isdir(tmpdir)
+
+	if os.name != "nt":
+		restricted = os.path.join(tmpdir, "restricted")
+		os.mkdir(restricted, 0o700)
+		assert oct(os.stat(restricted).st_mode & 0o777) == '0o700'
+		os.rmdir(restricted)
+
+	# failures raise the OSError subclass matching errno
+	missing = os.path.join(tmpdir, "missing")
+	for func in (os.listdir, os.rmdir, os.remove, os.unlink, os.stat, os.lstat, os.chdir):
+		try:
+			func(missing)
+		except FileNotFoundError as err:
+			assert err.errno == 2, func
+		else:
+			assert False, func
+	try:
+		os.mkdir(folder)
+	except FileExistsError as err:
+		assert err.errno == 17
+	else:
+		assert False, "mkdir should fail on an existing directory"
+	assert_raises(OSError, lambda: os.rmdir(tmpdir))
+
+	# utime
+	if os.name != "nt":
+		os.utime(fname, (1000000000, 1500000000.5))
+		assert os.stat(fname).st_atime == 1000000000
+		assert os.stat(fname).st_mtime == 1500000000.5
+		os.utime(fname, ns=(2 * 10 ** 18, 10 ** 18 + 5 * 10 ** 8))
+		assert os.stat(fname).st_atime == 2 * 10 ** 9
+		assert os.stat(fname).st_mtime == 10 ** 9 + 0.5
+		assert os.stat(fname).st_atime_ns == 2 * 10 ** 18
+		assert os.stat(fname).st_mtime_ns == 10 ** 18 + 5 * 10 ** 8
+		os.utime(fname)
+		assert os.stat(fname).st_mtime > 1557500000
+		assert_raises(ValueError, lambda: os.utime(fname, (1, 2), ns=(1, 2)))
+		assert_raises(TypeError, lambda: os.utime(fname, (1,)))
+		assert_raises(FileNotFoundError, lambda: os.utime(missing))
+
+	# rename and replace overwrite the destination
+	os.replace(fname2, fname3)
+	assert not os.path.exists(fname2)
+	with open(fname2, "wb"):
+		pass
+	os.rename(fname3, fname2)
+	assert os.path.exists(fname2) and not os.path.exists(fname3)
 
 # supports
 assert isinstance(os.supports_fd, set)
@@ -286,3 +349,9 @@ if "win" not in sys.platform:
     assert_raises(OSError, lambda: os.ttyname(9999))
     os.close(b)
     os.close(a)
+    try:
+        os.close(a)
+    except OSError as err:
+        assert err.errno == 9
+    else:
+        assert False, "closing a closed file descriptor should fail"
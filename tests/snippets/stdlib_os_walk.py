@@ -0,0 +1,111 @@
+import os
+import time
+
+from testutils import assert_raises
+
+base_folder = os.environ["TEMP"] if os.name == "nt" else "/tmp"
+top = os.path.join(base_folder, "rustpython_test_os_walk_" + str(int(time.time())))
+
+def touch(*parts):
+    with open(os.path.join(top, *parts), "w"):
+        pass
+
+# top/
+#   a.txt
+#   sub/
+#     b.txt
+#     deep/
+#       c.txt
+#   skip/
+#     d.txt
+os.mkdir(top)
+os.mkdir(os.path.join(top, "sub"))
+os.mkdir(os.path.join(top, "sub", "deep"))
+os.mkdir(os.path.join(top, "skip"))
+touch("a.txt")
+touch("sub", "b.txt")
+touch("sub", "deep", "c.txt")
+touch("skip", "d.txt")
+os.symlink(os.path.join(top, "sub"), os.path.join(top, "link"))
+os.symlink(os.path.join(top, "missing"), os.path.join(top, "dangling"))
+
+# scandir
+entries = {entry.name: entry for entry in os.scandir(top)}
+assert set(entries) == {"a.txt", "sub", "skip", "link", "dangling"}
+assert entries["sub"].path == os.path.join(top, "sub")
+assert entries["sub"].is_dir()
+assert not entries["sub"].is_file()
+assert entries["a.txt"].is_file()
+assert entries["link"].is_dir()
+assert not entries["link"].is_dir(follow_symlinks=False)
+assert not entries["dangling"].is_dir()
+assert not entries["dangling"].is_file()
+assert entries["a.txt"].stat().st_size == 0
+assert entries["a.txt"].inode() == os.stat(os.path.join(top, "a.txt")).st_ino
+assert repr(entries["a.txt"]) == "<DirEntry 'a.txt'>"
+assert os.fspath(entries["sub"]) == entries["sub"].path
+
+with os.scandir(top) as it:
+    next(it)
+assert_raises(StopIteration, lambda: next(it))
+
+cwd = os.getcwd()
+os.chdir(top)
+try:
+    assert {entry.path for entry in os.scandir()} == {
+        os.path.join(".", name) for name in entries
+    }
+finally:
+    os.chdir(cwd)
+
+assert_raises(FileNotFoundError, lambda: os.scandir(os.path.join(top, "missing")))
+
+# walk, top down
+def sorted_walk(*args, **kwargs):
+    return [(path, sorted(dirs), sorted(files)) for path, dirs, files in os.walk(*args, **kwargs)]
+
+walked = sorted(sorted_walk(top))
+assert walked == [
+    (top, ["link", "skip", "sub"], ["a.txt", "dangling"]),
+    (os.path.join(top, "skip"), [], ["d.txt"]),
+    (os.path.join(top, "sub"), ["deep"], ["b.txt"]),
+    (os.path.join(top, "sub", "deep"), [], ["c.txt"]),
+], walked
+
+# symlinked directories are walked into when asked to
+walked = sorted(path for path, _, _ in os.walk(top, followlinks=True))
+assert os.path.join(top, "link", "deep") in walked, walked
+
+# pruning dirnames in place skips those directories
+visited = []
+for path, dirs, files in os.walk(top):
+    visited.append(path)
+    if "skip" in dirs:
+        dirs.remove("skip")
+assert os.path.join(top, "skip") not in visited
+assert os.path.join(top, "sub", "deep") in visited
+
+# bottom up yields children before their parent
+paths = [path for path, _, _ in os.walk(top, topdown=False)]
+assert paths.index(os.path.join(top, "sub", "deep")) < paths.index(os.path.join(top, "sub"))
+assert paths[-1] == top
+
+# errors are ignored unless there is an onerror callback
+missing = os.path.join(top, "missing")
+assert list(os.walk(missing)) == []
+errors = []
+assert list(os.walk(missing, onerror=errors.append)) == []
+assert len(errors) == 1 and isinstance(errors[0], FileNotFoundError), errors
+
+# clean up, bottom up
+for path, dirs, files in os.walk(top, topdown=False):
+    for name in files:
+        os.remove(os.path.join(path, name))
+    for name in dirs:
+        full = os.path.join(path, name)
+        if os.path.islink(full):
+            os.remove(full)
+        else:
+            os.rmdir(full)
+os.rmdir(top)
+assert not os.path.exists(top)
@@ -0,0 +1,106 @@
+import os
+import time
+from pathlib import Path, PurePath, PurePosixPath, PureWindowsPath, PosixPath, WindowsPath
+
+from testutils import assert_raises
+
+# pure paths
+assert Path('a') / 'b' == Path('a/b')
+assert 'a' / PurePosixPath('b') == PurePosixPath('a/b')
+assert PurePosixPath('a//b/./c') == PurePosixPath('a/b/c')
+assert str(PurePosixPath('a//b/./c')) == 'a/b/c'
+
+p = PurePosixPath('/usr/lib/archive.tar.gz')
+assert p.name == 'archive.tar.gz'
+assert p.stem == 'archive.tar'
+assert p.suffix == '.gz'
+assert p.suffixes == ['.tar', '.gz']
+assert p.parts == ('/', 'usr', 'lib', 'archive.tar.gz')
+assert p.parent == PurePosixPath('/usr/lib')
+assert list(p.parents) == [PurePosixPath('/usr/lib'), PurePosixPath('/usr'), PurePosixPath('/')]
+assert p.is_absolute()
+assert not PurePosixPath('a/b').is_absolute()
+assert p.relative_to('/usr') == PurePosixPath('lib/archive.tar.gz')
+assert_raises(ValueError, lambda: p.relative_to('/etc'))
+assert p.with_name('other.txt') == PurePosixPath('/usr/lib/other.txt')
+assert p.with_suffix('.zip') == PurePosixPath('/usr/lib/archive.tar.zip')
+assert PurePosixPath('a').joinpath('b', 'c') == PurePosixPath('a/b/c')
+assert PurePosixPath('/a/b.py').match('*.py')
+assert PurePosixPath('/a/b.py').match('a/*.py')
+assert not PurePosixPath('/a/b.py').match('/*.py')
+assert PurePosixPath('/a b').as_uri() == 'file:///a%20b'
+assert bytes(PurePosixPath('/a')) == b'/a'
+assert repr(PurePosixPath('a/b')) == "PurePosixPath('a/b')"
+assert hash(PurePosixPath('a/b')) == hash(PurePosixPath('a//b'))
+assert os.fspath(PurePosixPath('a/b')) == 'a/b'
+assert isinstance(PurePosixPath('a'), os.PathLike)
+
+# the Windows flavour
+w = PureWindowsPath('C:/Users\\me/file.txt')
+assert str(w) == 'C:\\Users\\me\\file.txt'
+assert w.drive == 'C:'
+assert w.parts == ('C:\\', 'Users', 'me', 'file.txt')
+assert w == PureWindowsPath('c:\\users\\ME\\FILE.TXT')
+assert PureWindowsPath('c:/a') / 'b' == PureWindowsPath('c:\\a\\b')
+
+# the concrete class matches the platform
+if os.name == 'nt':
+    assert type(Path('.')) is WindowsPath
+    assert_raises(NotImplementedError, lambda: PosixPath('.'))
+    assert type(PurePath('a')) is PureWindowsPath
+else:
+    assert type(Path('.')) is PosixPath
+    assert_raises(NotImplementedError, lambda: WindowsPath('.'))
+    assert type(PurePath('a')) is PurePosixPath
+
+# filesystem access
+base = Path(os.environ["TEMP"] if os.name == "nt" else "/tmp")
+top = base / ("rustpython_test_pathlib_" + str(int(time.time())))
+top.mkdir()
+try:
+    assert top.exists() and top.is_dir() and not top.is_file()
+
+    text = top / 'hello.txt'
+    assert text.write_text('hello') == 5
+    assert text.read_text() == 'hello'
+    assert text.write_text('hi') == 2
+    assert text.read_text() == 'hi'
+    assert text.is_file() and not text.is_dir()
+    assert text.stat().st_size == 2
+    with text.open() as f:
+        assert f.read() == 'hi'
+    with open(text) as f:
+        assert f.read() == 'hi'
+
+    data = top / 'data.bin'
+    assert data.write_bytes(b'\x00\x01') == 2
+    assert data.read_bytes() == b'\x00\x01'
+
+    sub = top / 'sub' / 'deeper'
+    sub.mkdir(parents=True)
+    (sub / 'x.py').touch()
+    assert_raises(FileExistsError, lambda: sub.mkdir())
+    sub.mkdir(exist_ok=True)
+
+    assert sorted(p.name for p in top.iterdir()) == ['data.bin', 'hello.txt', 'sub']
+    assert sorted(p.name for p in top.glob('*.txt')) == ['hello.txt']
+    assert sorted(p.name for p in top.glob('*')) == ['data.bin', 'hello.txt', 'sub']
+    assert list(top.glob('**/*.py')) == [sub / 'x.py']
+    assert list(top.rglob('x.py')) == [sub / 'x.py']
+
+    assert (top / 'sub' / '..' / 'hello.txt').resolve() == text.resolve()
+    assert top.resolve().is_absolute()
+    assert not (top / 'missing').exists()
+    assert_raises(FileNotFoundError, lambda: (top / 'missing').resolve(strict=True))
+
+    text.rename(top / 'moved.txt')
+    assert not text.exists()
+    assert (top / 'moved.txt').read_text() == 'hi'
+    (top / 'moved.txt').unlink()
+    data.unlink()
+    (sub / 'x.py').unlink()
+    sub.rmdir()
+    (top / 'sub').rmdir()
+finally:
+    top.rmdir()
+assert not top.exists()
@@ -0,0 +1,75 @@
+import queue
+import time
+
+from testutils import assertRaises
+
+q = queue.Queue()
+for i in range(3):
+    q.put(i)
+assert q.qsize() == 3
+assert not q.empty()
+assert [q.get() for _ in range(3)] == [0, 1, 2]
+assert q.empty()
+
+with assertRaises(queue.Empty):
+    q.get_nowait()
+with assertRaises(queue.Empty):
+    q.get(block=False)
+
+start = time.monotonic()
+with assertRaises(queue.Empty):
+    q.get(timeout=0.05)
+assert time.monotonic() - start >= 0.04
+
+q = queue.LifoQueue()
+for i in range(3):
+    q.put_nowait(i)
+assert [q.get_nowait() for _ in range(3)] == [2, 1, 0]
+
+q = queue.PriorityQueue()
+for item in [(3, 'c'), (1, 'a'), (2, 'b')]:
+    q.put(item)
+assert [q.get() for _ in range(3)] == [(1, 'a'), (2, 'b'), (3, 'c')]
+
+q = queue.Queue(maxsize=2)
+q.put(1)
+q.put(2)
+assert q.full()
+with assertRaises(queue.Full):
+    q.put_nowait(3)
+with assertRaises(queue.Full):
+    q.put(3, timeout=0.01)
+
+# join returns once every item has been marked done
+q.get()
+q.task_done()
+q.get()
+q.task_done()
+q.join()
+with assertRaises(ValueError):
+    q.task_done()
+
+# producer and consumer threads
+import threading
+
+q = queue.Queue()
+consumed = []
+
+def consume():
+    while True:
+        item = q.get()
+        if item is None:
+            break
+        consumed.append(item)
+        q.task_done()
+
+producer = threading.Thread(target=lambda: [q.put(i) for i in range(5)] and q.put(None))
+producer.start()
+producer.join()
+consumer = threading.Thread(target=consume)
+consumer.start()
+consumer.join()
+assert consumed == [0, 1, 2, 3, 4]
+
+with threading.Condition():
+    pass
@@ -0,0 +1,50 @@
+import secrets
+import string
+
+from testutils import assert_raises
+
+token = secrets.token_bytes(16)
+assert type(token) == bytes and len(token) == 16
+assert len(secrets.token_bytes()) == secrets.DEFAULT_ENTROPY
+assert len(secrets.token_bytes(None)) == secrets.DEFAULT_ENTROPY
+assert secrets.token_bytes(0) == b''
+assert secrets.token_bytes(16) != secrets.token_bytes(16)
+
+token = secrets.token_hex(16)
+assert len(token) == 32
+assert all(c in string.hexdigits for c in token)
+assert len(secrets.token_hex()) == 2 * secrets.DEFAULT_ENTROPY
+
+alphabet = string.ascii_letters + string.digits + '-_'
+for n in range(8):
+    token = secrets.token_urlsafe(n)
+    assert len(token) == (4 * n + 2) // 3
+    assert all(c in alphabet for c in token)
+assert len(secrets.token_urlsafe()) == 43
+
+for _ in range(100):
+    assert 0 <= secrets.randbelow(10) < 10
+    assert 0 <= secrets.randbits(3) < 8
+assert secrets.randbelow(1) == 0
+assert 0 <= secrets.randbelow(2 ** 100) < 2 ** 100
+assert 0 <= secrets.randbits(100) < 2 ** 100
+assert_raises(ValueError, lambda: secrets.randbelow(0))
+assert_raises(ValueError, lambda: secrets.randbelow(-1))
+assert len({secrets.randbelow(2 ** 64) for _ in range(10)}) == 10
+
+seq = ['a', 'b', 'c']
+assert all(secrets.choice(seq) in seq for _ in range(20))
+assert secrets.choice('x') == 'x'
+assert_raises(IndexError, lambda: secrets.choice([]))
+
+assert secrets.compare_digest(b'abc', b'abc')
+assert not secrets.compare_digest(b'abc', b'abd')
+assert not secrets.compare_digest(b'abc', b'ab')
+assert not secrets.compare_digest(b'', b'a')
+assert secrets.compare_digest(b'', b'')
+assert secrets.compare_digest(bytearray(b'abc'), b'abc')
+assert secrets.compare_digest('abc', 'abc')
+assert not secrets.compare_digest('abc', 'abd')
+assert_raises(TypeError, lambda: secrets.compare_digest('abc', b'abc'))
+assert_raises(TypeError, lambda: secrets.compare_digest('\xe9', '\xe9'))
+assert_raises(TypeError, lambda: secrets.compare_digest(1, 1))
@@ -0,0 +1,114 @@
+import os
+import shutil
+import stat
+import time
+
+from testutils import assert_raises
+
+base_folder = os.environ["TEMP"] if os.name == "nt" else "/tmp"
+top = os.path.join(base_folder, "rustpython_test_shutil_" + str(int(time.time())))
+
+def path(*parts):
+    return os.path.join(top, *parts)
+
+def write(name, content):
+    with open(name, "w") as f:
+        f.write(content)
+
+def read(name):
+    with open(name) as f:
+        return f.read()
+
+def tree(root):
+    return sorted(
+        (os.path.relpath(dirpath, root), sorted(dirnames), sorted(filenames))
+        for dirpath, dirnames, filenames in os.walk(root)
+    )
+
+# top/src/
+#   a.txt
+#   skip.pyc
+#   sub/
+#     b.txt
+#     deep/
+#       c.txt
+os.makedirs(path("src", "sub", "deep"))
+write(path("src", "a.txt"), "a")
+write(path("src", "skip.pyc"), "")
+write(path("src", "sub", "b.txt"), "b")
+write(path("src", "sub", "deep", "c.txt"), "c")
+os.chmod(path("src", "a.txt"), 0o640)
+os.utime(path("src", "a.txt"), (1000000, 1000000))
+
+# single files
+assert shutil.copyfile(path("src", "a.txt"), path("copyfile.txt")) == path("copyfile.txt")
+assert read(path("copyfile.txt")) == "a"
+assert_raises(shutil.SameFileError, lambda: shutil.copyfile(path("src", "a.txt"), path("src", "a.txt")))
+assert issubclass(shutil.SameFileError, shutil.Error)
+assert issubclass(shutil.Error, OSError)
+
+os.mkdir(path("into"))
+assert shutil.copy(path("src", "a.txt"), path("into")) == path("into", "a.txt")
+assert stat.S_IMODE(os.stat(path("into", "a.txt")).st_mode) == 0o640
+
+assert shutil.copy2(path("src", "a.txt"), path("copy2.txt")) == path("copy2.txt")
+st = os.stat(path("copy2.txt"))
+assert stat.S_IMODE(st.st_mode) == 0o640
+assert st.st_mtime == 1000000
+assert st.st_mtime_ns == 1000000 * 10 ** 9
+
+# copytree recreates the tree and copies metadata with copy2
+assert shutil.copytree(path("src"), path("dst")) == path("dst")
+assert tree(path("dst")) == tree(path("src"))
+assert read(path("dst", "sub", "deep", "c.txt")) == "c"
+assert os.stat(path("dst", "a.txt")).st_mtime == 1000000
+assert_raises(FileExistsError, lambda: shutil.copytree(path("src"), path("dst")))
+
+shutil.copytree(path("src"), path("filtered"), ignore=shutil.ignore_patterns("*.pyc", "deep"))
+assert tree(path("filtered")) == [
+    (".", ["sub"], ["a.txt"]),
+    ("sub", [], ["b.txt"]),
+]
+
+# rmtree removes non-empty directories
+shutil.rmtree(path("dst"))
+assert not os.path.exists(path("dst"))
+assert_raises(FileNotFoundError, lambda: shutil.rmtree(path("dst")))
+shutil.rmtree(path("dst"), ignore_errors=True)
+errors = []
+shutil.rmtree(path("dst"), onerror=lambda *args: errors.append(args))
+assert errors and errors[0][1] == path("dst")
+
+# move renames, or moves into an existing directory
+assert shutil.move(path("filtered"), path("moved")) == path("moved")
+assert not os.path.exists(path("filtered"))
+assert shutil.move(path("copyfile.txt"), path("moved")) == path("moved", "copyfile.txt")
+assert read(path("moved", "copyfile.txt")) == "a"
+assert_raises(shutil.Error, lambda: shutil.move(path("moved"), path("moved", "sub")))
+write(path("copyfile.txt"), "again")
+assert_raises(shutil.Error, lambda: shutil.move(path("copyfile.txt"), path("moved")))
+
+# which searches PATH for executables
+if os.name != "nt":
+    write(path("into", "runme"), "#!/bin/sh\n")
+    assert shutil.which("runme", path=path("into")) is None
+    os.chmod(path("into", "runme"), 0o755)
+    assert shutil.which("runme", path=path("into")) == path("into", "runme")
+    old_path = os.environ.get("PATH", "")
+    os.environ["PATH"] = path("into") + os.pathsep + old_path
+    try:
+        assert shutil.which("runme") == path("into", "runme")
+    finally:
+        os.environ["PATH"] = old_path
+    assert shutil.which("rustpython-no-such-program") is None
+
+# zip archives round trip, leaving out a.txt as zip can't store times before 1980
+assert "zip" in [name for name, _ in shutil.get_archive_formats()]
+archive = shutil.make_archive(path("archive"), "zip", path("src", "sub"))
+assert archive == path("archive.zip")
+shutil.unpack_archive(archive, path("unpacked"))
+assert tree(path("unpacked")) == tree(path("src", "sub"))
+assert read(path("unpacked", "deep", "c.txt")) == "c"
+
+shutil.rmtree(top)
+assert not os.path.exists(top)
@@ -42,3 +42,40 @@ if "win" not in sys.platform:
 
 
 
+
+assert signal.NSIG > signal.SIGINT
+
+# handlers run with the interrupted frame
+frames = []
+def frame_handler(signum, frame):
+	frames.append((signum, frame.f_code.co_name))
+
+def interrupted():
+	signal.raise_signal(signal.SIGINT)
+
+old_handler = signal.signal(signal.SIGINT, frame_handler)
+assert old_handler is signal.default_int_handler
+interrupted()
+assert frames == [(signal.SIGINT, 'interrupted')], frames
+
+# a handler may replace itself
+def replacing_handler(signum, frame):
+	signal.signal(signal.SIGINT, frame_handler)
+
+signal.signal(signal.SIGINT, replacing_handler)
+signal.raise_signal(signal.SIGINT)
+assert signal.getsignal(signal.SIGINT) is frame_handler
+
+signal.signal(signal.SIGINT, signal.SIG_IGN)
+signal.raise_signal(signal.SIGINT)
+
+# the default handler raises KeyboardInterrupt
+signal.signal(signal.SIGINT, signal.default_int_handler)
+try:
+	signal.raise_signal(signal.SIGINT)
+except KeyboardInterrupt:
+	pass
+else:
+	assert False, "SIGINT should raise KeyboardInterrupt"
+
+assert_raises(OSError, lambda: signal.raise_signal(1000))
@@ -147,3 +147,84 @@ with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as listener:
 	connection.settimeout(1.0)
 	with assertRaises(OSError):
 		connection.recv(len(MESSAGE_A))
+	with assertRaises(socket.timeout):
+		connection.recv(len(MESSAGE_A))
+	assert connection.gettimeout() == 1.0
+	connection.settimeout(0.25)
+	assert connection.gettimeout() == 0.25
+	with assertRaises(ValueError):
+		connection.settimeout(-1)
+	connection.close()
+	connector.close()
+
+# Loopback echo server
+with socket.socket(socket.AF_INET, socket.SOCK_STREAM) as server:
+	server.setsockopt(socket.SOL_SOCKET, socket.SO_REUSEADDR, 1)
+	server.bind(("127.0.0.1", 0))
+	server.listen()
+	client = socket.socket()
+	client.connect(server.getsockname())
+	client.setsockopt(socket.IPPROTO_TCP, socket.TCP_NODELAY, 1)
+	(conn, _) = server.accept()
+	client.sendall(MESSAGE_A * 100)
+	received = b''
+	while len(received) < len(MESSAGE_A) * 100:
+		received += conn.recv(1024)
+	conn.sendall(received)
+	echoed = b''
+	while len(echoed) < len(received):
+		echoed += client.recv(1024)
+	assert echoed == MESSAGE_A * 100
+	assert client.send(MESSAGE_B) == len(MESSAGE_B)
+	assert conn.recv(1024) == MESSAGE_B
+	conn.close()
+	# the peer closed the connection
+	assert client.recv(1024) == b''
+	client.close()
+
+# Name resolution
+assert socket.gethostbyname("127.0.0.1") == "127.0.0.1"
+assert socket.gethostbyname("localhost").startswith("127.")
+assert isinstance(socket.gethostname(), str)
+
+infos = socket.getaddrinfo("127.0.0.1", 80, socket.AF_INET, socket.SOCK_STREAM)
+assert len(infos) == 1
+(family, kind, proto, canonname, sockaddr) = infos[0]
+assert family == socket.AF_INET
+assert kind == socket.SOCK_STREAM
+assert proto == socket.IPPROTO_TCP
+assert sockaddr == ("127.0.0.1", 80)
+
+infos = socket.getaddrinfo("127.0.0.1", "53", type=socket.SOCK_DGRAM)
+assert [info[2] for info in infos] == [socket.IPPROTO_UDP]
+assert infos[0][4] == ("127.0.0.1", 53)
+
+with assertRaises(socket.gaierror):
+	socket.getaddrinfo("127.0.0.1", 80, socket.AF_INET6)
+
+# Passive lookups without a host give the wildcard address
+infos = socket.getaddrinfo(None, 80, socket.AF_INET, socket.SOCK_STREAM, flags=socket.AI_PASSIVE)
+assert infos[0][4] == ("0.0.0.0", 80)
+infos = socket.getaddrinfo(None, 80, socket.AF_INET, socket.SOCK_STREAM)
+assert infos[0][4] == ("127.0.0.1", 80)
+with assertRaises(socket.gaierror):
+	socket.getaddrinfo(None, 80, flags=0x10000)
+
+# IPv6 loopback, if the machine has one
+try:
+	server = socket.socket(socket.AF_INET6, socket.SOCK_STREAM)
+	server.bind(("::1", 0))
+except OSError:
+	server = None
+if server is not None:
+	server.listen(1)
+	client = socket.socket(socket.AF_INET6, socket.SOCK_STREAM)
+	client.connect(("::1", server.getsockname()[1]))
+	(conn, addr) = server.accept()
+	assert addr[0] == "::1"
+	assert len(addr) == 4
+	client.sendall(MESSAGE_A)
+	assert conn.recv(1024) == MESSAGE_A
+	conn.close()
+	client.close()
+	server.close()
@@ -0,0 +1,107 @@
+import os
+import sqlite3
+import time
+
+from testutils import assert_raises
+
+assert sqlite3.paramstyle == "qmark"
+assert issubclass(sqlite3.IntegrityError, sqlite3.DatabaseError)
+assert issubclass(sqlite3.OperationalError, sqlite3.DatabaseError)
+assert issubclass(sqlite3.DatabaseError, sqlite3.Error)
+assert issubclass(sqlite3.Error, Exception)
+
+conn = sqlite3.connect(":memory:")
+cur = conn.cursor()
+cur.execute("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT UNIQUE, score REAL, data BLOB)")
+assert cur.rowcount == -1
+assert cur.description is None
+
+# parameters, with type adaptation both ways
+cur.execute("INSERT INTO t (name, score, data) VALUES (?, ?, ?)", ("a", 1.5, b"\x00\x01"))
+assert cur.rowcount == 1
+assert cur.lastrowid == 1
+assert conn.in_transaction
+cur.executemany(
+    "INSERT INTO t (name, score) VALUES (:name, :score)",
+    [{"name": "b", "score": 2}, {"name": "c", "score": None}],
+)
+assert cur.rowcount == 2
+conn.commit()
+assert not conn.in_transaction
+
+cur.execute("SELECT id, name, score, data FROM t ORDER BY id")
+assert [column[0] for column in cur.description] == ["id", "name", "score", "data"]
+assert cur.fetchone() == (1, "a", 1.5, b"\x00\x01")
+assert cur.fetchmany(1) == [(2, "b", 2.0, None)]
+assert cur.fetchall() == [(3, "c", None, None)]
+assert cur.fetchone() is None
+
+assert list(conn.execute("SELECT name FROM t WHERE score > ?", (1,))) == [("a",), ("b",)]
+assert conn.execute("SELECT ?, ?, ?", (2 ** 62, "é", None)).fetchone() == (2 ** 62, "é", None)
+assert_raises(OverflowError, lambda: conn.execute("SELECT ?", (2 ** 64,)))
+
+# errors
+assert_raises(sqlite3.IntegrityError, lambda: conn.execute("INSERT INTO t (name) VALUES ('a')"))
+assert_raises(sqlite3.OperationalError, lambda: conn.execute("SELECT * FROM missing"))
+assert_raises(sqlite3.ProgrammingError, lambda: conn.execute("SELECT ?", (1, 2)))
+assert_raises(sqlite3.ProgrammingError, lambda: conn.execute("SELECT :x", {"y": 1}))
+assert_raises(sqlite3.ProgrammingError, lambda: conn.executemany("SELECT ?", [(1,)]))
+# newer CPythons raise ProgrammingError for these
+assert_raises(
+    (sqlite3.InterfaceError, sqlite3.ProgrammingError),
+    lambda: conn.execute("SELECT ?", (object(),)),
+)
+assert_raises(
+    (sqlite3.Warning, sqlite3.ProgrammingError), lambda: conn.execute("SELECT 1; SELECT 2")
+)
+
+# transactions
+conn.execute("UPDATE t SET score = 10 WHERE name = 'a'")
+conn.rollback()
+assert conn.execute("SELECT score FROM t WHERE name = 'a'").fetchone() == (1.5,)
+with conn:
+    conn.execute("DELETE FROM t WHERE name = 'c'")
+assert conn.execute("SELECT count(*) FROM t").fetchone() == (2,)
+try:
+    with conn:
+        conn.execute("DELETE FROM t")
+        raise KeyError
+except KeyError:
+    pass
+assert conn.execute("SELECT count(*) FROM t").fetchone() == (2,)
+
+conn.executescript("CREATE TABLE u (x); INSERT INTO u VALUES (1); INSERT INTO u VALUES (2);")
+assert conn.execute("SELECT sum(x) FROM u").fetchone() == (3,)
+conn.isolation_level = None
+conn.execute("INSERT INTO u VALUES (3)")
+assert not conn.in_transaction
+assert_raises(ValueError, lambda: setattr(conn, "isolation_level", "SOMETIMES"))
+
+cur.close()
+assert_raises(sqlite3.ProgrammingError, lambda: cur.execute("SELECT 1"))
+conn.close()
+assert_raises(sqlite3.ProgrammingError, lambda: conn.execute("SELECT 1"))
+
+# committed data is in the file when it is opened again
+base_folder = os.environ["TEMP"] if os.name == "nt" else "/tmp"
+path = os.path.join(base_folder, "rustpython_test_sqlite3_" + str(int(time.time())) + ".db")
+conn = sqlite3.connect(path)
+conn.execute("CREATE TABLE kv (k TEXT, v TEXT)")
+conn.execute("INSERT INTO kv VALUES ('kept', 'yes')")
+conn.commit()
+conn.execute("INSERT INTO kv VALUES ('lost', 'no')")
+conn.close()
+conn = sqlite3.connect(path)
+assert conn.execute("SELECT k FROM kv").fetchall() == [("kept",)]
+conn.close()
+os.remove(path)
+
+# a clobbered exception class is reported, not a crash
+conn = sqlite3.connect(":memory:")
+error = sqlite3.OperationalError
+sqlite3.OperationalError = None
+try:
+    assert_raises(Exception, lambda: conn.execute("not sql"))
+finally:
+    sqlite3.OperationalError = error
+conn.close()
@@ -0,0 +1,24 @@
+import socket
+import ssl
+
+from testutils import assertRaises
+
+assert issubclass(ssl.SSLError, OSError)
+assert issubclass(ssl.SSLCertVerificationError, ssl.SSLError)
+assert issubclass(ssl.SSLEOFError, ssl.SSLError)
+
+context = ssl.create_default_context()
+assert isinstance(context, ssl.SSLContext)
+assert context.verify_mode == ssl.CERT_REQUIRED
+assert context.check_hostname
+
+context = ssl.SSLContext(ssl.PROTOCOL_TLS_CLIENT)
+with assertRaises(TypeError):
+    context.load_verify_locations()
+with assertRaises(ssl.SSLError):
+    context.load_verify_locations(cadata='not a certificate')
+
+# hostnames are always checked, so one is required
+with socket.socket() as sock:
+    with assertRaises(ValueError):
+        context.wrap_socket(sock)
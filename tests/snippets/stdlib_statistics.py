@@ -0,0 +1,47 @@
+import statistics
+from decimal import Decimal
+from fractions import Fraction
+
+from testutils import assertRaises
+
+data = [1, 2, 3, 4]
+
+assert statistics.mean(data) == 2.5
+assert statistics.mean([1, 2, 3]) == 2
+assert statistics.mean([Fraction(1, 3), Fraction(2, 3)]) == Fraction(1, 2)
+assert statistics.mean([Decimal('0.5'), Decimal('0.75')]) == Decimal('0.625')
+assert statistics.fmean([1, 2, 3, 4]) == 2.5
+
+assert statistics.median(data) == 2.5
+assert statistics.median([3, 1, 2]) == 2
+assert statistics.median_low(data) == 2
+assert statistics.median_high(data) == 3
+assert statistics.median_grouped([1, 2, 2, 3, 4, 4, 4, 4, 4, 5]) == 3.7
+
+assert statistics.mode([1, 1, 2, 3, 3, 3, 3, 4]) == 3
+assert statistics.mode(['red', 'blue', 'blue', 'red', 'green', 'red']) == 'red'
+assert statistics.multimode('aabbbbbbbbcc') == ['b']
+assert statistics.multimode('aabbbbccddddeeffffgg') == ['b', 'd', 'f']
+
+assert statistics.pvariance([0, 0, 1, 1]) == 0.25
+assert statistics.variance([2, 4, 4, 4, 5, 5, 7, 9]) == 32 / 7
+assert statistics.pstdev([2, 4, 4, 4, 5, 5, 7, 9]) == 2.0
+assert statistics.variance([Fraction(1, 4), Fraction(5, 4), Fraction(1, 2)]) == Fraction(13, 48)
+assert statistics.pvariance([Decimal('1'), Decimal('2'), Decimal('3')]) == Decimal(2) / Decimal(3)
+assert abs(statistics.stdev([1.5, 2.5, 2.5, 2.75, 3.25, 4.75]) - 1.0810874155219827) < 1e-15
+
+assert statistics.quantiles([1, 2, 3, 4, 5, 6, 7, 8, 9, 10]) == [2.75, 5.5, 8.25]
+assert abs(statistics.geometric_mean([54, 24, 36]) - 36) < 1e-9
+assert statistics.harmonic_mean([40, 60]) == 48
+
+with assertRaises(statistics.StatisticsError):
+    statistics.mean([])
+with assertRaises(statistics.StatisticsError):
+    statistics.median([])
+with assertRaises(statistics.StatisticsError):
+    statistics.variance([1])
+
+nd = statistics.NormalDist(100, 15)
+assert nd.mean == 100 and nd.stdev == 15
+assert abs(nd.cdf(130) - 0.9772498680518208) < 1e-12
+assert abs(nd.inv_cdf(0.5) - 100) < 1e-9
@@ -0,0 +1,113 @@
+import os
+import stat
+import tempfile
+
+from testutils import assert_raises
+
+tmpdir = tempfile.gettempdir()
+assert os.path.isdir(tmpdir)
+assert tempfile.gettempprefix() == "tmp"
+
+# mkstemp gives an open file only its owner can use
+fd, name = tempfile.mkstemp(suffix=".txt", prefix="pre")
+try:
+    assert os.path.dirname(name) == tmpdir
+    base = os.path.basename(name)
+    assert base.startswith("pre") and base.endswith(".txt")
+    if os.name != "nt":
+        assert stat.S_IMODE(os.stat(name).st_mode) == 0o600
+    assert os.write(fd, b"data") == 4
+finally:
+    os.close(fd)
+    os.unlink(name)
+
+# names don't repeat
+names = set()
+for _ in range(20):
+    fd, name = tempfile.mkstemp()
+    os.close(fd)
+    os.unlink(name)
+    names.add(name)
+assert len(names) == 20
+
+d = tempfile.mkdtemp(prefix="pre", suffix="suf")
+try:
+    assert os.path.isdir(d)
+    assert os.path.basename(d).startswith("pre") and d.endswith("suf")
+    if os.name != "nt":
+        assert stat.S_IMODE(os.stat(d).st_mode) == 0o700
+
+    # dir= places the files, and is where TMPDIR points gettempdir()
+    fd, name = tempfile.mkstemp(dir=d)
+    os.close(fd)
+    assert os.path.dirname(name) == d
+    os.unlink(name)
+
+    old_tempdir, old_tmpdir = tempfile.tempdir, os.environ.get("TMPDIR")
+    tempfile.tempdir = None
+    os.environ["TMPDIR"] = d
+    try:
+        assert tempfile.gettempdir() == d
+        fd, name = tempfile.mkstemp()
+        os.close(fd)
+        assert os.path.dirname(name) == d
+        os.unlink(name)
+    finally:
+        tempfile.tempdir = old_tempdir
+        if old_tmpdir is None:
+            del os.environ["TMPDIR"]
+        else:
+            os.environ["TMPDIR"] = old_tmpdir
+finally:
+    os.rmdir(d)
+
+# TemporaryDirectory removes the whole tree
+with tempfile.TemporaryDirectory() as d:
+    assert os.path.isdir(d)
+    os.makedirs(os.path.join(d, "sub", "deep"))
+    with open(os.path.join(d, "sub", "deep", "file.txt"), "w") as f:
+        f.write("x")
+assert not os.path.exists(d)
+
+t = tempfile.TemporaryDirectory()
+assert os.path.isdir(t.name)
+t.cleanup()
+assert not os.path.exists(t.name)
+
+# NamedTemporaryFile
+with tempfile.NamedTemporaryFile() as f:
+    assert os.path.exists(f.name)
+    f.write(b"abc")
+    f.seek(0)
+    assert f.read() == b"abc"
+assert not os.path.exists(f.name)
+
+f = tempfile.NamedTemporaryFile("w+", suffix=".txt")
+f.write("text")
+f.seek(0)
+assert f.read() == "text"
+assert f.name.endswith(".txt")
+f.close()
+assert not os.path.exists(f.name)
+
+f = tempfile.NamedTemporaryFile("w", delete=False)
+try:
+    f.write("kept")
+    f.close()
+    with open(f.name) as kept:
+        assert kept.read() == "kept"
+finally:
+    os.unlink(f.name)
+
+# TemporaryFile
+with tempfile.TemporaryFile() as f:
+    f.write(b"xyz")
+    f.seek(0)
+    assert f.read() == b"xyz"
+with tempfile.TemporaryFile("w+") as f:
+    f.write("xyz")
+    f.seek(1)
+    assert f.read() == "yz"
+assert f.closed
+
+assert_raises(FileNotFoundError, lambda: tempfile.mkstemp(dir=os.path.join(tmpdir, "no", "such", "dir")))
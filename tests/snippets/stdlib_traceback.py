@@ -0,0 +1,93 @@
+import sys
+import traceback
+
+
+def inner():
+    raise ValueError("bad")
+
+
+def outer():
+    try:
+        inner()
+    except ValueError as e:
+        raise KeyError("k") from e
+
+
+try:
+    outer()
+except KeyError as e:
+    exc = e
+    formatted = traceback.format_exc()
+    tb = sys.exc_info()[2]
+
+assert exc.__traceback__ is tb
+assert type(tb).__name__ == "traceback"
+assert tb.tb_frame.f_code.co_name == "<module>"
+assert tb.tb_next.tb_frame.f_code.co_name == "outer"
+assert tb.tb_next.tb_next is None
+assert exc.__cause__.__traceback__.tb_next.tb_lineno == 6
+
+lines = formatted.splitlines()
+assert lines[0] == "Traceback (most recent call last):"
+assert lines[4] == "    raise ValueError(\"bad\")"
+assert lines[5] == "ValueError: bad"
+assert "The above exception was the direct cause of the following exception:" in lines
+assert lines[-1] == "KeyError: 'k'"
+assert lines[-3].endswith("line 13, in outer")
+assert lines[-2] == "    raise KeyError(\"k\") from e"
+
+summary = traceback.extract_tb(tb)
+assert isinstance(summary, traceback.StackSummary)
+assert [f.name for f in summary] == ["<module>", "outer"]
+assert [f.lineno for f in summary] == [17, 13]
+assert summary[1].line == "raise KeyError(\"k\") from e"
+
+assert traceback.format_tb(tb) == summary.format()
+assert "".join(traceback.format_exception(type(exc), exc, tb)) == formatted
+assert traceback.format_exception_only(KeyError, exc) == ["KeyError: 'k'\n"]
+
+
+def stack():
+    return traceback.format_stack()
+
+
+frames = stack()
+assert len(frames) == 2
+assert frames[0].endswith("in <module>\n    frames = stack()\n")
+assert frames[1].endswith("in stack\n    return traceback.format_stack()\n")
+
+# a handler that doesn't match must not add a second entry for its frame
+def reraise():
+    try:
+        inner()
+    except TypeError:
+        pass
+
+
+try:
+    reraise()
+except ValueError as e:
+    assert [f.name for f in traceback.extract_tb(e.__traceback__)] == [
+        "<module>",
+        "reraise",
+        "inner",
+    ]
+
+e = ValueError()
+assert e.__traceback__ is None
+assert e.with_traceback(tb) is e
+assert e.__traceback__ is tb
+
+
+# An exception whose __init__ doesn't call the base one still gets a traceback.
+class NoInit(Exception):
+    def __init__(self, value):
+        self.value = value
+
+
+try:
+    raise NoInit(1)
+except NoInit as e:
+    assert e.value == 1
+    assert e.args == (1,)
+    assert traceback.extract_tb(e.__traceback__)[0].name == "<module>"
@@ -0,0 +1,39 @@
+import sys
+import tracemalloc
+
+from testutils import assert_raises
+
+assert not tracemalloc.is_tracing()
+assert_raises(ValueError, lambda: tracemalloc.start(0))
+
+tracemalloc.start()
+assert tracemalloc.is_tracing()
+assert tracemalloc.get_traceback_limit() == 1
+
+current, peak = tracemalloc.get_traced_memory()
+data = [str(i) * 10 for i in range(1000)]
+data_line = sys._getframe().f_lineno - 1
+grown, peak = tracemalloc.get_traced_memory()
+assert grown > current
+assert peak >= grown
+
+traceback = tracemalloc.get_object_traceback(data[0])
+assert traceback[0].filename == __file__
+assert traceback[0].lineno == data_line
+
+snapshot = tracemalloc.take_snapshot()
+top = [
+    stat for stat in snapshot.statistics('lineno')
+    if stat.traceback[0].filename == __file__
+][0]
+assert top.traceback[0].lineno == data_line
+assert top.count >= 1000
+assert top.size > 0
+
+del data
+shrunk, _ = tracemalloc.get_traced_memory()
+assert shrunk < grown
+
+tracemalloc.stop()
+assert not tracemalloc.is_tracing()
+assert tracemalloc.get_traced_memory() == (0, 0)
@@ -0,0 +1,75 @@
+from typing import (
+    Any, Callable, Dict, Generic, Iterable, List, Optional, Tuple, TypeVar,
+    Union, cast, get_type_hints, TYPE_CHECKING,
+)
+
+from testutils import assert_raises
+
+assert not TYPE_CHECKING
+
+assert Optional[int] == Union[int, None]
+assert Union[int, str, int] == Union[int, str]
+assert Union[int] is int
+assert Optional[int].__args__ == (int, type(None))
+assert List[int].__origin__ is list
+assert List[int].__args__ == (int,)
+assert Dict[str, List[int]].__args__ == (str, List[int])
+assert Tuple[int, ...].__args__ == (int, ...)
+assert Callable[[int, str], bool].__args__ == (int, str, bool)
+assert List[int] == List[int]
+assert List[int] != List[str]
+
+T = TypeVar('T')
+KT = TypeVar('KT')
+assert T.__name__ == 'T'
+assert Dict[KT, T][str, int] == Dict[str, int]
+assert List[T].__parameters__ == (T,)
+
+assert isinstance([], List)
+assert issubclass(list, Iterable)
+assert not isinstance({}, List)
+assert_raises(TypeError, lambda: isinstance([], List[int]))
+assert_raises(TypeError, lambda: isinstance(1, Any))
+
+
+class Box(Generic[T]):
+    def __init__(self, value):
+        self.value = value
+
+    def get(self) -> T:
+        return self.value
+
+
+assert Box.__mro__ == (Box, Generic, object)
+assert Box.__orig_bases__ == (Generic[T],)
+assert Box[int].__origin__ is Box
+assert Box[int](3).get() == 3
+assert Box('x').get() == 'x'
+
+
+class IntBox(Box[int]):
+    pass
+
+
+assert IntBox.__mro__ == (IntBox, Box, Generic, object)
+assert IntBox(5).get() == 5
+
+
+class IntList(List[int]):
+    pass
+
+
+assert IntList.__mro__[:2] == (IntList, list)
+assert IntList([1, 2]) == [1, 2]
+
+
+def f(x: 'int', y: Optional[List[int]] = None) -> None:
+    pass
+
+
+assert get_type_hints(f) == {
+    'x': int, 'y': Optional[List[int]], 'return': type(None),
+}
+
+assert cast(int, 'abc') == 'abc'
+assert cast(List[int], None) is None
@@ -0,0 +1,143 @@
+import io
+import socket
+import urllib.request
+from urllib.error import URLError, HTTPError
+from testutils import assertRaises
+
+# Serve canned responses through a fake socket layer, so no network is needed.
+responses = []
+requests = []
+
+
+class FakeSocket:
+    def __init__(self, response):
+        self.response = response
+
+    def setsockopt(self, *args):
+        pass
+
+    def settimeout(self, timeout):
+        pass
+
+    def sendall(self, data):
+        requests.append(data)
+
+    def recv(self, bufsize):
+        data, self.response = self.response[:bufsize], self.response[bufsize:]
+        return data
+
+    def makefile(self, mode):
+        return io.BytesIO(self.response)
+
+    def close(self):
+        pass
+
+
+def create_connection(address, timeout=None, source_address=None):
+    return FakeSocket(responses.pop(0))
+
+
+real_create_connection = socket.create_connection
+socket.create_connection = create_connection
+
+responses.append(
+    b'HTTP/1.1 200 OK\r\n'
+    b'Content-Type: text/html\r\n'
+    b'Content-Length: 13\r\n'
+    b'\r\n'
+    b'Hello, world!'
+)
+with urllib.request.urlopen('http://example.com/index.html?a=1#top') as response:
+    assert response.getcode() == 200
+    assert response.status == 200
+    assert response.reason == 'OK'
+    assert response.geturl() == 'http://example.com/index.html?a=1#top'
+    assert response.headers['content-type'] == 'text/html'
+    assert response.headers.get('X-Missing') is None
+    assert response.read(5) == b'Hello'
+    assert response.read() == b', world!'
+    assert response.read() == b''
+assert requests[-1].startswith(b'GET /index.html?a=1 HTTP/1.1\r\n')
+assert b'Host: example.com\r\n' in requests[-1]
+
+# chunked transfer encoding
+responses.append(
+    b'HTTP/1.1 200 OK\r\n'
+    b'Transfer-Encoding: chunked\r\n'
+    b'\r\n'
+    b'5\r\nHello\r\n'
+    b'8\r\n, world!\r\n'
+    b'0\r\n\r\n'
+)
+assert urllib.request.urlopen('http://example.com:8080').read() == b'Hello, world!'
+assert requests[-1].startswith(b'GET / HTTP/1.1\r\n')
+assert b'Host: example.com:8080\r\n' in requests[-1]
+
+# redirects are followed
+responses.append(
+    b'HTTP/1.1 301 Moved Permanently\r\n'
+    b'Location: /new/place\r\n'
+    b'Content-Length: 0\r\n'
+    b'\r\n'
+)
+responses.append(
+    b'HTTP/1.1 302 Found\r\n'
+    b'Location: other\r\n'
+    b'Content-Length: 0\r\n'
+    b'\r\n'
+)
+responses.append(
+    b'HTTP/1.1 200 OK\r\n'
+    b'\r\n'
+    b'done'
+)
+response = urllib.request.urlopen('http://example.com/old')
+assert response.geturl() == 'http://example.com/new/other'
+assert response.read() == b'done'
+assert requests[-2].startswith(b'GET /new/place HTTP/1.1\r\n')
+assert requests[-1].startswith(b'GET /new/other HTTP/1.1\r\n')
+
+# error statuses raise HTTPError
+responses.append(
+    b'HTTP/1.1 404 Not Found\r\n'
+    b'Content-Length: 9\r\n'
+    b'\r\n'
+    b'not found'
+)
+with assertRaises(HTTPError):
+    urllib.request.urlopen('http://example.com/missing')
+
+responses.append(
+    b'HTTP/1.1 500 Internal Server Error\r\n'
+    b'Content-Length: 4\r\n'
+    b'\r\n'
+    b'oops'
+)
+try:
+    urllib.request.urlopen('http://example.com/broken')
+except HTTPError as err:
+    assert err.code == 500
+    assert err.getcode() == 500
+    assert err.reason == 'Internal Server Error'
+    assert err.read() == b'oops'
+    assert isinstance(err, URLError)
+    assert isinstance(err, OSError)
+else:
+    assert False, 'HTTPError not raised'
+
+assert not responses
+
+# unreachable servers and unknown schemes raise URLError
+socket.create_connection = real_create_connection
+
+with assertRaises(URLError):
+    # Lets hope nobody is listening on port 1
+    urllib.request.urlopen('http://127.0.0.1:1/')
+
+with assertRaises(URLError):
+    urllib.request.urlopen('gopher://example.com/')
+
+with assertRaises(ValueError):
+    urllib.request.urlopen('example.com')
+
+assert str(URLError('reason')) == '<urlopen error reason>'
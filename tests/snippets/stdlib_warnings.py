@@ -0,0 +1,85 @@
+import warnings
+
+from testutils import assertRaises
+
+
+def warn_here(*args, **kwargs):
+    warnings.warn(*args, stacklevel=2, **kwargs)
+
+
+# simplefilter("error") turns a warning into an exception
+with warnings.catch_warnings():
+    warnings.simplefilter("error")
+    with assertRaises(UserWarning):
+        warnings.warn("boom")
+    with assertRaises(RuntimeWarning):
+        warnings.warn("boom", RuntimeWarning)
+    with assertRaises(RuntimeWarning):
+        warnings.warn(RuntimeWarning("instance"))
+
+# recording
+with warnings.catch_warnings(record=True) as log:
+    warnings.simplefilter("always")
+    warnings.warn("first")
+    warn_here("second", FutureWarning)
+    warnings.warn_explicit("third", SyntaxWarning, "somefile.py", 42)
+
+assert [str(w.message) for w in log] == ["first", "second", "third"]
+assert [w.category for w in log] == [UserWarning, FutureWarning, SyntaxWarning]
+assert log[1].filename.endswith("stdlib_warnings.py")
+assert log[2].filename == "somefile.py"
+assert log[2].lineno == 42
+
+# by default a location only warns once
+with warnings.catch_warnings(record=True) as log:
+    warnings.simplefilter("default")
+    for _ in range(3):
+        warnings.warn("repeated")
+assert len(log) == 1
+
+# "once" warns once per message text, regardless of location
+with warnings.catch_warnings(record=True) as log:
+    warnings.simplefilter("once")
+    warnings.warn("once")
+    warnings.warn("once")
+    warn_here("once")
+assert len(log) == 1
+
+# "ignore"
+with warnings.catch_warnings(record=True) as log:
+    warnings.simplefilter("ignore")
+    warnings.warn("ignored")
+assert log == []
+
+# filterwarnings with a message pattern and category
+with warnings.catch_warnings(record=True) as log:
+    warnings.simplefilter("always")
+    warnings.filterwarnings("ignore", message="skip", category=UserWarning)
+    warnings.warn("skip me")
+    warnings.warn("keep me")
+    warnings.warn("skip me too", RuntimeWarning)
+assert [str(w.message) for w in log] == ["keep me", "skip me too"]
+
+# DeprecationWarning is hidden unless it's triggered from __main__
+with warnings.catch_warnings(record=True) as log:
+    warnings.resetwarnings()
+    warnings.filterwarnings("default", category=DeprecationWarning, module="__main__")
+    warnings.simplefilter("ignore", category=DeprecationWarning, append=True)
+    warnings.warn_explicit("from main", DeprecationWarning, "main.py", 1, module="__main__")
+    warnings.warn_explicit("from lib", DeprecationWarning, "lib.py", 1, module="lib")
+assert [str(w.message) for w in log] == ["from main"]
+
+# catch_warnings restores the filters
+filters = warnings.filters[:]
+with warnings.catch_warnings():
+    warnings.simplefilter("error")
+assert warnings.filters == filters
+
+with assertRaises(TypeError):
+    warnings.warn("bad category", int)
+
+# filters whose line number isn't an int are rejected
+with warnings.catch_warnings():
+    warnings.filters.insert(0, ("error", None, Warning, None, "1"))
+    with assertRaises(TypeError):
+        warnings.warn("bad filter lineno")
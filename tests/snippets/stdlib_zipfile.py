@@ -0,0 +1,75 @@
+import io
+import os
+import shutil
+import time
+import zipfile
+
+from testutils import assert_raises
+
+base_folder = os.environ["TEMP"] if os.name == "nt" else "/tmp"
+top = os.path.join(base_folder, "rustpython_test_zipfile_" + str(int(time.time())))
+os.mkdir(top)
+
+def path(*parts):
+    return os.path.join(top, *parts)
+
+with open(path("src.txt"), "w") as f:
+    f.write("some text\n" * 100)
+
+# writing, with a default compression that entries can override
+with zipfile.ZipFile(path("test.zip"), "w", compression=zipfile.ZIP_DEFLATED) as zf:
+    zf.write(path("src.txt"), "src.txt")
+    zf.writestr("stored.bin", b"\x00\x01\x02", compress_type=zipfile.ZIP_STORED)
+    zf.writestr(zipfile.ZipInfo("dir/info.txt", (2019, 8, 1, 12, 30, 0)), "from info")
+assert zipfile.is_zipfile(path("test.zip"))
+assert not zipfile.is_zipfile(path("src.txt"))
+
+with zipfile.ZipFile(path("test.zip"), "a") as zf:
+    zf.writestr("appended.txt", "more")
+
+# reading
+with zipfile.ZipFile(path("test.zip")) as zf:
+    assert zf.namelist() == ["src.txt", "stored.bin", "dir/info.txt", "appended.txt"]
+    assert [info.filename for info in zf.infolist()] == zf.namelist()
+
+    info = zf.getinfo("src.txt")
+    assert info.compress_type == zipfile.ZIP_DEFLATED
+    assert info.file_size == 1000
+    assert info.compress_size < info.file_size
+    info = zf.getinfo("dir/info.txt")
+    assert info.date_time == (2019, 8, 1, 12, 30, 0)
+    assert info.compress_type == zipfile.ZIP_STORED
+    assert_raises(KeyError, lambda: zf.getinfo("missing"))
+
+    assert zf.read("stored.bin") == b"\x00\x01\x02"
+    assert zf.read("appended.txt") == b"more"
+    with zf.open("src.txt") as f:
+        assert f.readline() == b"some text\n"
+        assert len(f.read()) == 990
+    assert zf.testzip() is None
+
+    assert zf.extract("dir/info.txt", path("one")) == path("one", "dir", "info.txt")
+    with open(path("one", "dir", "info.txt")) as f:
+        assert f.read() == "from info"
+    zf.extractall(path("all"))
+    assert sorted(os.listdir(path("all"))) == ["appended.txt", "dir", "src.txt", "stored.bin"]
+    assert os.listdir(path("all", "dir")) == ["info.txt"]
+
+# in memory, writing through open
+buf = io.BytesIO()
+with zipfile.ZipFile(buf, "w") as zf:
+    with zf.open("w.txt", "w") as f:
+        f.write(b"written")
+with zipfile.ZipFile(io.BytesIO(buf.getvalue())) as zf:
+    assert zf.read("w.txt") == b"written"
+
+# a corrupted member fails its CRC check
+data = bytearray(buf.getvalue())
+data[data.index(b"written")] = ord("W")
+with zipfile.ZipFile(io.BytesIO(bytes(data))) as zf:
+    assert_raises(zipfile.BadZipFile, lambda: zf.read("w.txt"))
+    assert zf.testzip() == "w.txt"
+assert_raises(zipfile.BadZipFile, lambda: zipfile.ZipFile(io.BytesIO(b"not a zip")))
+
+shutil.rmtree(top)
+assert not os.path.exists(top)
@@ -0,0 +1,33 @@
+import os
+import sys
+import zipimport
+
+from testutils import assert_raises
+
+archive = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'zipimport_archive.zip')
+sys.path.insert(0, archive)
+
+import zipmod
+assert zipmod.X == 'from zip'
+assert zipmod.__file__ == os.path.join(archive, 'zipmod.py')
+assert isinstance(zipmod.__loader__, zipimport.zipimporter)
+
+# nested packages, with compressed members
+import zippkg.sub.leaf
+assert zippkg.INIT
+assert zippkg.__path__ == [os.path.join(archive, 'zippkg')]
+assert zippkg.sub.leaf.LEAF == 'leaf' * 10000
+assert zippkg.sub.__file__ == os.path.join(archive, 'zippkg', 'sub', '__init__.py')
+
+# the archive's directory is read once and cached
+assert archive in zipimport._zip_directory_cache
+
+importer = zipimport.zipimporter(archive)
+assert importer.is_package('zippkg')
+assert not importer.is_package('zipmod')
+assert importer.get_source('zipmod') == "X = 'from zip'\n"
+assert importer.find_module('missing') is None
+
+assert_raises(zipimport.ZipImportError, lambda: zipimport.zipimporter(archive + '.missing'))
+assert_raises(zipimport.ZipImportError, lambda: zipimport.zipimporter(__file__))
+sys.path.remove(archive)
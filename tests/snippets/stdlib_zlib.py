@@ -58,5 +58,42 @@ assert zlib.compress(lorem) == zlib.compress(lorem, -1) == zlib.compress(lorem,
 for text in compressed_lorem_list:
     assert zlib.decompress(text) == lorem
 
+# raw deflate streams (negative wbits) and output larger than the buffer
+big = lorem * 200
+assert zlib.decompress(zlib.compress(big)) == big
+assert zlib.decompress(zlib.compress(big), 15, 1) == big
+assert zlib.decompress(zlib.compress(lorem)[2:-4], -15) == lorem
+assert_raises(zlib.error, lambda: zlib.decompress(zlib.compress(lorem)[:-10]))
+
 assert_raises(zlib.error, lambda: zlib.compress(b"123", -40))
 assert_raises(zlib.error, lambda: zlib.compress(b"123", 10))
+
+# streaming compression objects
+compressor = zlib.compressobj(9, zlib.DEFLATED, -15)
+stream = compressor.compress(big[:1000]) + compressor.compress(big[1000:])
+stream += compressor.flush()
+assert zlib.decompress(stream, -15) == big
+
+compressor = zlib.compressobj()
+synced = compressor.compress(b"abc") + compressor.flush(zlib.Z_SYNC_FLUSH)
+assert zlib.decompressobj().decompress(synced) == b"abc"
+
+decompressor = zlib.decompressobj(-15)
+out = decompressor.decompress(stream[:50], 100)
+assert len(out) == 100
+assert decompressor.unconsumed_tail
+assert not decompressor.eof
+out += decompressor.decompress(decompressor.unconsumed_tail + stream[50:])
+assert out == big
+assert decompressor.eof
+assert decompressor.unused_data == b""
+
+decompressor = zlib.decompressobj()
+assert decompressor.decompress(zlib.compress(lorem) + b"extra") == lorem
+assert decompressor.unused_data == b"extra"
+
+decompressor = zlib.decompressobj()
+out = decompressor.decompress(zlib.compress(big), 10)
+assert out + decompressor.flush() == big
+
+assert_raises(zlib.error, lambda: zlib.decompressobj().decompress(b"garbage!"))
@@ -1,5 +1,6 @@
 
 from io import StringIO
+from testutils import assertRaises
 
 def test_01():
     """
@@ -49,8 +50,28 @@ def test_04():
     assert f.seek(0) == 0
     assert f.read(4) == 'Test'
 
+def test_05():
+    """
+        Tests that truncate cuts at a number of code points
+        and rejects negative and non-integer sizes
+    """
+    f = StringIO('h\xe9llo w\u00f6rld')
+
+    assert f.truncate(7) == 7
+    assert f.getvalue() == 'h\xe9llo w'
+    f.seek(0)
+    f.read(1)
+    assert f.truncate() == 1
+    assert f.getvalue() == 'h'
+    with assertRaises(ValueError):
+        f.truncate(-1)
+    with assertRaises(TypeError):
+        f.truncate('x')
+    assert f.getvalue() == 'h'
+
 if __name__ == "__main__":
     test_01()
     test_02()
     test_03()
     test_04()
+    test_05()
@@ -63,6 +63,32 @@ assert a.isalpha()
 s = '1 2 3'
 assert s.split(' ', 1) == ['1', '2 3']
 assert s.rsplit(' ', 1) == ['1 2', '3']
+assert s.split(None, 1) == ['1', '2 3']
+assert s.rsplit(None, 1) == ['1 2', '3']
+
+# Without a separator, runs of whitespace count as one separator and
+# whitespace at either end is ignored.
+assert 'a b  c'.split() == ['a', 'b', 'c']
+assert 'a b  c'.rsplit() == ['a', 'b', 'c']
+assert '  a \t\n b\r\x0b\x0c c  '.split() == ['a', 'b', 'c']
+assert '  a \t\n b\r\x0b\x0c c  '.rsplit() == ['a', 'b', 'c']
+assert 'a\u3000b\x1cc'.split() == ['a', 'b', 'c']
+assert ''.split() == []
+assert ' \t\n '.rsplit() == []
+assert '  a  b  '.split(None, 1) == ['a', 'b  ']
+assert '  a  b  '.rsplit(None, 1) == ['  a', 'b']
+assert '  a  b  c  '.split(maxsplit=2) == ['a', 'b', 'c  ']
+assert '  a  b  c  '.rsplit(maxsplit=2) == ['  a', 'b', 'c']
+assert '  a  b  '.split(None, 0) == ['a  b  ']
+assert '  a  b  '.rsplit(None, 0) == ['  a  b']
+assert 'a b'.split(None, 5) == ['a', 'b']
+assert 'a,b,c'.rsplit(',', 1) == ['a,b', 'c']
+assert 'a,b,c'.split(sep=',', maxsplit=1) == ['a', 'b,c']
+assert 'a,b,c'.rsplit(',', 0) == ['a,b,c']
+assert 'a,,b'.split(',') == ['a', '', 'b']
+assert ',a,'.rsplit(',') == ['', 'a', '']
+assert_raises(ValueError, lambda: 'a'.split(''))
+assert_raises(ValueError, lambda: 'a'.rsplit(''))
 
 b = '  hallo  '
 assert b.strip() == 'hallo'
@@ -110,6 +136,19 @@ assert ["kok".center(i, "|") for i in range(2, 10)] == [
     "|||kok|||",
 ]
 
+assert 'hi'.center(6, '*') == '**hi**'
+assert 'hi'.center(5, '*') == '**hi*'
+assert 'hi'.center(1) == 'hi'
+assert 'hi'.center(-3) == 'hi'
+assert 'hi'.ljust(2, '*') == 'hi'
+assert 'hi'.rjust(0) == 'hi'
+# width and fill character count code points, not bytes
+assert 'é'.center(3, '→') == '→é→'
+assert 'éé'.ljust(4, 'ü') == 'ééüü'
+assert 'éé'.rjust(3) == ' éé'
+assert_raises(TypeError, lambda: 'hi'.center(6, ''))
+assert_raises(TypeError, lambda: 'hi'.center(6, '**'))
+
 
 # requires CPython 3.7, and the CI currently runs with 3.6
 # assert c.isascii()
@@ -163,6 +202,16 @@ assert not 'abcd'.startswith('', 4, 3)
 
 assert '   '.isspace()
 assert 'hello\nhallo\nHallo'.splitlines() == ['hello', 'hallo', 'Hallo']
+assert 'a\nb\n'.splitlines() == ['a', 'b']
+assert 'a\nb\n'.split('\n') == ['a', 'b', '']
+assert ''.splitlines() == []
+assert '\n'.splitlines() == ['']
+assert 'a\r\nb\rc\n\nd'.splitlines() == ['a', 'b', 'c', '', 'd']
+assert 'a\r\nb\rc\n\nd'.splitlines(True) == ['a\r\n', 'b\r', 'c\n', '\n', 'd']
+assert 'a\r\n'.splitlines(keepends=True) == ['a\r\n']
+assert 'a\x0bb\x0cc\x1cd\x1de\x1ef\x85g\u2028h\u2029i'.splitlines() == list('abcdefghi')
+assert 'a\x1fb'.splitlines() == ['a\x1fb']
+assert 'ü\u2028é'.splitlines(keepends=True) == ['ü\u2028', 'é']
 assert 'abc\t12345\txyz'.expandtabs() == 'abc     12345   xyz'
 assert '-'.join(['1', '2', '3']) == '1-2-3'
 assert 'HALLO'.isupper()
@@ -170,6 +219,14 @@ assert "hello, my name is".partition("my ") == ('hello, ', 'my ', 'name is')
 assert "hello".partition("is") == ('hello', '', '')
 assert "hello, my name is".rpartition("is") == ('hello, my name ', 'is', '')
 assert "hello".rpartition("is") == ('', '', 'hello')
+assert 'a=b=c'.partition('=') == ('a', '=', 'b=c')
+assert 'a=b=c'.rpartition('=') == ('a=b', '=', 'c')
+assert 'abc'.partition('abc') == ('', 'abc', '')
+assert 'abc'.rpartition('abc') == ('', 'abc', '')
+assert 'key: ✓: x'.partition(': ') == ('key', ': ', '✓: x')
+assert 'key: ✓: x'.rpartition(': ') == ('key: ✓', ': ', 'x')
+assert_raises(ValueError, lambda: 'abc'.partition(''))
+assert_raises(ValueError, lambda: 'abc'.rpartition(''))
 assert not ''.isdecimal()
 assert '123'.isdecimal()
 assert not '\u00B2'.isdecimal()
@@ -180,12 +237,46 @@ assert '_'.isidentifier()
 assert '유니코드'.isidentifier()
 assert not '😂'.isidentifier()
 assert not '123'.isidentifier()
+assert 'café_2'.isidentifier()
+assert not 'a-b'.isidentifier()
+assert not 'a b'.isidentifier()
+
+# Unicode aware classification
+assert 'café'.isalpha()
+assert 'Δέλτα'.isalpha()
+assert not 'a1'.isalpha()
+assert not ''.isalpha()
+assert '²'.isdigit()
+assert not '²'.isdecimal()
+assert '²'.isnumeric()
+assert '٣'.isdecimal()
+assert '٣'.isdigit()
+assert '½'.isnumeric()
+assert not '½'.isdigit()
+assert '三'.isnumeric()
+assert not 'x'.isnumeric()
+assert 'café2½'.isalnum()
+assert not 'café!'.isalnum()
+assert ' \t\n\x1c\u3000'.isspace()
+assert not ''.isspace()
+assert not 'a '.isspace()
+assert 'straße 1'.islower()
+assert not '123'.islower()
+assert 'ÉCOLE 42'.isupper()
+assert not 'École'.isupper()
+assert 'École Normale'.istitle()
+assert not 'école Normale'.istitle()
+assert 'é'.isprintable()
+assert not '\u200b'.isprintable()
+assert not '\u3000'.isprintable()
 
 # String Formatting
 assert "{} {}".format(1, 2) == "1 2"
 assert "{0} {1}".format(2, 3) == "2 3"
 assert "--{:s>4}--".format(1) == "--sss1--"
 assert "{keyword} {0}".format(1, keyword=2) == "2 1"
+assert "{:>5}|{:*^5}|{:.2}".format("ab", "é", "xyz") == "   ab|**é**|xy"
+assert_raises(ValueError, lambda: format("x", "+"))
 assert "repr() shows quotes: {!r}; str() doesn't: {!s}".format(
     'test1', 'test2'
 ) == "repr() shows quotes: 'test1'; str() doesn't: test2", 'Output: {!r}, {!s}'.format('test1', 'test2')
@@ -202,6 +293,22 @@ class Foo:
 f = Foo()
 assert "{} {!s} {!r} {!a}".format(f, f, f, f) == 'str(Foo) str(Foo) repr(Foo) repr(Foo)'
 assert "{foo} {foo!s} {foo!r} {foo!a}".format(foo=f) == 'str(Foo) str(Foo) repr(Foo) repr(Foo)'
+
+assert "{0.real} {0.__class__.__name__}".format(3) == "3 int"
+assert "{0[1]} {0[a]!r} {x[0][1]}".format({1: 'one', 'a': 'b'}, x=[(1, 2)]) == "one 'b' 2"
+assert "{.imag:>3}".format(4) == "  0"
+assert_raises(ValueError, lambda: "{0.}".format(1))
+assert_raises(ValueError, lambda: "{0[}".format(1))
+assert_raises(ValueError, lambda: "{0[0]x}".format([1]))
+assert_raises(ValueError, lambda: "{} {0}".format(1))
+assert_raises(ValueError, lambda: "{0} {}".format(1))
+assert_raises(AttributeError, lambda: "{0.missing}".format(1))
+assert "{:{}{}}".format(1, ">", 4) == "   1"
+assert "{0:{w}.{p}}".format(1.2345, w=6, p=3) == "  1.23"
+assert "{0!r:>5}".format('a') == "  'a'"
+assert "{0!a}".format('é') == "'\\xe9'"
+assert_raises(ValueError, lambda: "{0!rr}".format(1))
+assert_raises(ValueError, lambda: "{0!x}".format(1))
 # assert '{} {!r} {:10} {!r:10} {foo!r:10} {foo!r} {foo}'.format('txt1', 'txt2', 'txt3', 'txt4', 'txt5', foo='bar')
 
 
@@ -219,6 +326,28 @@ assert "%f" % (-1.2345) == "-1.234500"
 assert "%f" % (1.23456789012) == "1.234568"
 assert "%f" % (123) == "123.000000"
 assert "%f" % (-123) == "-123.000000"
+assert "%5.2f" % 3.14159 == " 3.14"
+assert "%-8.3f|" % 2.5 == "2.500   |"
+assert "%08.2f" % -1.5 == "-0001.50"
+assert "%.0f %#.0f" % (3.7, 3.0) == "4 3."
+assert "%e" % 1234.5 == "1.234500e+03"
+assert "%.2E" % 0.000123 == "1.23E-04"
+assert "%g %g %g" % (0.0001, 0.00001, 123456789) == "0.0001 1e-05 1.23457e+08"
+assert "%#.3g %G" % (2.0, 1e100) == "2.00 1E+100"
+assert "%f %F %5.1f" % (float('inf'), float('nan'), float('-inf')) == "inf NAN  -inf"
+assert "%*.*f" % (7, 2, 3.14159) == "   3.14"
+assert "%x %X %#o %#x" % (255, 255, 8, 255) == "ff FF 0o10 0xff"
+assert "%+d % d %05d %-5d|" % (3, 3, -42, 7) == "+3  3 -0042 7    |"
+assert "%i %u" % (1.9, -2.1) == "1 -2"
+assert "%c%c" % (65, "b") == "Ab"
+assert "%a" % "é" == "'\\xe9'"
+assert "%(ключ)s=%(x)d%%" % {"ключ": "k", "x": 5} == "k=5%"
+assert "%s" % {"a": 1} == "{'a': 1}"
+
+assert_raises(TypeError, lambda: "%d %d" % (1,), msg="not enough arguments for format string")
+assert_raises(TypeError, lambda: "%d" % (1, 2), msg="not all arguments converted during string formatting")
+assert_raises(TypeError, lambda: "%f" % "1.0")
+assert_raises(ValueError, lambda: "%b" % b"x")
 
 assert_raises(TypeError, lambda: "My name is %s and I'm %(age)d years old" % ("Foo", 25), msg="format requires a mapping")
 assert_raises(TypeError, lambda: "My name is %(name)s" % "Foo", msg="format requires a mapping")
@@ -255,6 +384,15 @@ for s, b, e in zip(ss, bs, ['u8', 'U8', 'utf-8', 'UTF-8', 'utf_8']):
     assert s.encode(e) == b
     # assert s.encode(encoding=e) == b
 
+# error handlers for characters the encoding can't represent
+assert_raises(UnicodeEncodeError, lambda: 'caf\xe9 €'.encode('ascii'))
+assert 'caf\xe9 €'.encode('ascii', 'ignore') == b'caf '
+assert 'caf\xe9 €'.encode('ascii', 'replace') == b'caf? ?'
+assert 'caf\xe9 €👋'.encode('ascii', 'backslashreplace') == b'caf\\xe9 \\u20ac\\U0001f44b'
+assert 'caf\xe9 €'.encode('latin-1', 'xmlcharrefreplace') == b'caf\xe9 &#8364;'
+assert_raises(LookupError, lambda: '€'.encode('ascii', 'no-such-handler'))
+assert_raises(LookupError, lambda: 'x'.encode('no-such-encoding'))
+
 # str.isisprintable
 assert "".isprintable()
 assert " ".isprintable()
@@ -303,3 +441,65 @@ assert_raises(StopIteration, lambda: next(str_iter_reversed))
 
 assert str.__rmod__('%i', 30) == NotImplemented
 assert_raises(TypeError, lambda: str.__rmod__(30, '%i'))
+
+# str.casefold applies the full unicode case folding
+for folded, originals in [
+    ('ss', ['ß', 'SS', 'ss', 'ẞ']),
+    ('σίσυφοσ', ['ΣΊΣΥΦΟΣ', 'σίσυφος', 'Σίσυφος']),
+    ('ffi', ['FFI', 'ffi', 'ﬃ']),
+    ('i̇', ['İ']),
+    ('strasse', ['STRASSE', 'Strasse', 'straße']),
+]:
+    for original in originals:
+        assert original.casefold() == folded, (original, folded)
+assert 'ß'.lower() == 'ß'
+assert 'ß'.casefold() == 'ss'
+assert 'ΣΊΣΥΦΟΣ'.casefold() == 'σίσυφοσ'
+
+# str.expandtabs
+assert 'a\tb'.expandtabs(4) == 'a   b'
+assert 'a\tb'.expandtabs() == 'a       b'
+assert 'a\tb'.expandtabs(tabsize=2) == 'a b'
+assert 'a\tb'.expandtabs(0) == 'ab'
+assert 'a\tb'.expandtabs(-1) == 'ab'
+assert '\t'.expandtabs(4) == '    '
+assert 'abcd\te'.expandtabs(4) == 'abcd    e'
+# the column restarts after newlines and carriage returns
+assert 'ab\ncd\te'.expandtabs(4) == 'ab\ncd  e'
+assert 'abc\r\td'.expandtabs(4) == 'abc\r    d'
+assert 'é\tx'.expandtabs(4) == 'é   x'
+
+# implicit concatenation of adjacent literals
+assert ('a' 'b' 'c') == 'abc'
+x = 2
+assert ('a'
+        f'{x}' "c"
+        f'{x + 1}') == 'a2c3'
+assert b'a' b'b' == b'ab'
+assert_raises(SyntaxError, lambda: compile("'a' b'b'", '<test>', 'eval'))
+assert_raises(SyntaxError, lambda: compile("b'a' 'b'", '<test>', 'eval'))
+
+# escape sequences
+assert '\x41' == 'A'
+assert '\101' == 'A'
+assert '\u00e9' == 'é'
+assert '\U0001F600' == '😀'
+assert '\N{GREEK SMALL LETTER ALPHA}' == 'α'
+assert r'\x41' == '\\x41'
+assert r'\'' == "\\'"
+assert_raises(SyntaxError, lambda: compile(r"'\x4'", '', 'eval'))
+assert_raises(SyntaxError, lambda: compile(r"'\N{NOT A NAME}'", '', 'eval'))
+
+# removeprefix / removesuffix
+assert 'test_foo'.removeprefix('test_') == 'foo'
+assert 'test_foo'.removeprefix('foo') == 'test_foo'
+assert 'test_foo'.removeprefix('') == 'test_foo'
+assert 'test'.removeprefix('test') == ''
+assert 'te'.removeprefix('test') == 'te'
+assert 'foo_test'.removesuffix('_test') == 'foo'
+assert 'foo_test'.removesuffix('foo') == 'foo_test'
+assert 'foo_test'.removesuffix('') == 'foo_test'
+assert 'αβγ'.removesuffix('γ') == 'αβ'
+assert 'αβγ'.removeprefix('α') == 'βγ'
+assert_raises(TypeError, lambda: 'abc'.removeprefix(b'a'))
+assert_raises(TypeError, lambda: 'abc'.removesuffix(('c',)))
@@ -0,0 +1,93 @@
+import io
+import os
+import sys
+from testutils import assertRaises
+
+# the standard streams are text files
+for stream in (sys.stdin, sys.stdout, sys.stderr):
+    assert isinstance(stream.encoding, str)
+    assert isinstance(stream.errors, str)
+    assert isinstance(stream.isatty(), bool)
+    assert isinstance(stream.fileno(), int)
+
+if os.name == 'posix':
+    assert sys.stdin.fileno() == 0
+    assert sys.stdout.fileno() == 1
+    assert sys.stderr.fileno() == 2
+
+assert sys.__stdin__ is sys.stdin
+assert sys.__stdout__ is sys.stdout
+assert sys.__stderr__ is sys.stderr
+
+assert sys.stdout.write('written to stdout\n') == 18
+sys.stdout.flush()
+sys.stderr.write('written to stderr\n')
+sys.stderr.flush()
+
+# print goes through sys.stdout, so it can be captured
+saved_stdout = sys.stdout
+sys.stdout = io.StringIO()
+try:
+    print('captured', 42)
+    print('a', 'b', sep='-', end='!')
+    output = sys.stdout.getvalue()
+finally:
+    sys.stdout = saved_stdout
+assert output == 'captured 42\na-b!', output
+
+# printing with no sys.stdout is silently ignored
+sys.stdout = None
+try:
+    print('nowhere')
+finally:
+    sys.stdout = saved_stdout
+
+# input reads lines from sys.stdin and writes the prompt to sys.stdout
+saved_stdin = sys.stdin
+sys.stdin = io.StringIO('first line\nsecond\nlast')
+sys.stdout = io.StringIO()
+try:
+    first = input()
+    second = input('prompt> ')
+    last = input()
+    with assertRaises(EOFError):
+        input()
+    prompt = sys.stdout.getvalue()
+finally:
+    sys.stdin = saved_stdin
+    sys.stdout = saved_stdout
+assert first == 'first line'
+assert second == 'second'
+assert last == 'last'
+assert prompt == 'prompt> '
+
+buf = io.StringIO('one\ntwo\n')
+assert buf.readline() == 'one\n'
+assert buf.readline() == 'two\n'
+assert buf.readline() == ''
+
+buf = io.BytesIO(b'one\ntwo')
+assert buf.readline() == b'one\n'
+assert buf.readline() == b'two'
+
+# stderr escapes what it can't encode
+assert sys.stderr.errors == 'backslashreplace'
+
+# the standard streams can be switched to another encoding
+encoding, errors = sys.stdout.encoding, sys.stdout.errors
+try:
+    sys.stdout.reconfigure(encoding='ascii', errors='backslashreplace')
+    assert sys.stdout.encoding == 'ascii'
+    assert sys.stdout.errors == 'backslashreplace'
+    print('escaped: €')
+    sys.stdout.reconfigure(encoding='utf-8')
+    assert sys.stdout.errors == 'strict'
+    print('encoded: €')
+    sys.stdout.reconfigure(line_buffering=True)
+    assert sys.stdout.line_buffering
+    assert sys.stdout.encoding == 'utf-8'
+finally:
+    sys.stdout.reconfigure(encoding=encoding, errors=errors, line_buffering=False)
+with assertRaises(LookupError):
+    sys.stdout.reconfigure(encoding='no-such-encoding')
+assert sys.stdout.encoding == encoding
@@ -38,6 +38,47 @@ sys.settrace(trc)
 demo(5)
 sys.settrace(None)
 
+# Profiling:
+
+events = []
+builtin_names = {len: 'len', divmod: 'divmod', sys.getprofile: 'getprofile'}
+
+def prof(frame, event, arg):
+    if event in ('call', 'return'):
+        events.append((event, frame.f_code.co_name, arg))
+    elif arg in builtin_names:
+        events.append((event, builtin_names[arg]))
+
+def fact(n):
+    return 1 if n <= 1 else n * fact(n - 1)
+
+sys.setprofile(prof)
+assert sys.getprofile() is prof
+fact(3)
+len([])
+try:
+    divmod(1, 0)
+except ZeroDivisionError:
+    pass
+sys.setprofile(None)
+assert sys.getprofile() is None
+
+assert events == [
+    ('c_call', 'getprofile'),
+    ('c_return', 'getprofile'),
+    ('call', 'fact', None),
+    ('call', 'fact', None),
+    ('call', 'fact', None),
+    ('return', 'fact', 1),
+    ('return', 'fact', 2),
+    ('return', 'fact', 6),
+    ('c_call', 'len'),
+    ('c_return', 'len'),
+    ('c_call', 'divmod'),
+    ('c_exception', 'divmod'),
+], events
+assert len([e for e in events if e[0] == 'call']) == 3
+
 assert sys.exc_info() == (None, None, None)
 
 try:
@@ -46,3 +87,51 @@ except ZeroDivisionError as exc:
 	exc_info = sys.exc_info()
 	assert exc_info[0] == type(exc) == ZeroDivisionError
 	assert exc_info[1] == exc
+	assert exc_info[2] is exc.__traceback__
+
+	try:
+		raise KeyError(1)
+	except KeyError:
+		assert sys.exc_info()[0] is KeyError
+	assert sys.exc_info()[0] is ZeroDivisionError
+
+assert sys.exc_info() == (None, None, None)
+
+try:
+	try:
+		raise TypeError
+	finally:
+		assert sys.exc_info()[0] is TypeError
+except TypeError:
+	pass
+
+assert sys.exc_info() == (None, None, None)
+
+def gen():
+	try:
+		raise IndexError
+	except IndexError:
+		yield sys.exc_info()[0]
+		yield sys.exc_info()[0]
+
+it = gen()
+assert next(it) is IndexError
+assert sys.exc_info() == (None, None, None)
+assert next(it) is IndexError
+del it
+assert sys.exc_info() == (None, None, None)
+
+def depth():
+	return sys._getframe(1).f_code.co_name
+
+assert depth() == '<module>'
+try:
+	sys._getframe(1000)
+except ValueError:
+	pass
+else:
+	assert False, "_getframe should fail when the stack is too shallow"
+
+assert sys.hash_info.width in (32, 64)
+assert hash(float('inf')) == sys.hash_info.inf
+assert sys.hash_info.modulus == 2 ** (sys.hash_info.hash_bits - 3) - 1 or sys.hash_info.width == 32
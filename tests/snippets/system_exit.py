@@ -0,0 +1,54 @@
+import subprocess
+import sys
+
+from testutils import assert_raises
+
+assert issubclass(SystemExit, BaseException)
+assert not issubclass(SystemExit, Exception)
+
+assert SystemExit().code is None
+assert SystemExit(3).code == 3
+assert SystemExit(1, 2).code == (1, 2)
+assert SystemExit('message').args == ('message',)
+
+try:
+    sys.exit(3)
+except SystemExit as exc:
+    assert exc.code == 3
+else:
+    assert False, "sys.exit should raise SystemExit"
+
+assert_raises(SystemExit, sys.exit)
+
+try:
+    exit('bye')
+except SystemExit as exc:
+    assert exc.code == 'bye'
+
+
+def run(code):
+    p = subprocess.Popen(
+        [sys.executable, '-c', code],
+        stdout=subprocess.PIPE,
+        stderr=subprocess.PIPE,
+    )
+    out, err = p.communicate()
+    return p.returncode, out.decode(), err.decode()
+
+
+# an uncaught SystemExit ends the process with its code, without a traceback
+assert run('import sys; sys.exit(3)') == (3, '', '')
+assert run('import sys; sys.exit()') == (0, '', '')
+assert run('import sys; sys.exit(None)') == (0, '', '')
+assert run('raise SystemExit(5)') == (5, '', '')
+# other codes are printed to stderr
+assert run('import sys; sys.exit("failed")') == (1, '', 'failed\n')
+# code after sys.exit doesn't run, but finally blocks do
+assert run(
+    'import sys\n'
+    'try:\n'
+    '    sys.exit(2)\n'
+    'finally:\n'
+    '    print("cleanup")\n'
+    'print("unreachable")\n'
+) == (2, 'cleanup\n', '')
@@ -13,9 +13,8 @@ class CustomInterface(abc.ABC):
         return NotImplemented
 
 
-# TODO raise an error if there are in any abstract methods not fulfilled
-# with assertRaises(TypeError):
-#     CustomInterface()
+with assertRaises(TypeError):
+    CustomInterface()
 
 
 class Concrete:
@@ -37,3 +36,92 @@ assert not issubclass(tuple, CustomInterface)
 assert isinstance(Concrete(), CustomInterface)
 assert isinstance(SubConcrete(), CustomInterface)
 assert not isinstance((), CustomInterface)
+
+
+class Incomplete(CustomInterface):
+    def b(self):
+        pass
+
+
+assert Incomplete.__abstractmethods__ == {'a'}
+with assertRaises(TypeError):
+    Incomplete()
+
+
+class Complete(Incomplete):
+    def a(self):
+        return 1
+
+
+assert Complete.__abstractmethods__ == frozenset()
+assert Complete().a() == 1
+assert isinstance(Complete(), CustomInterface)
+
+
+class Shape(abc.ABC):
+    @abc.abstractproperty
+    def area(self):
+        pass
+
+    @abc.abstractclassmethod
+    def unit(cls):
+        pass
+
+    @property
+    @abc.abstractmethod
+    def sides(self):
+        pass
+
+    @staticmethod
+    @abc.abstractmethod
+    def kind():
+        pass
+
+
+assert Shape.__abstractmethods__ == {'area', 'unit', 'sides', 'kind'}
+with assertRaises(TypeError):
+    Shape()
+
+
+class Square(Shape):
+    area = 4
+    sides = 4
+
+    @classmethod
+    def unit(cls):
+        return cls.__name__
+
+    @staticmethod
+    def kind():
+        return 'square'
+
+
+assert Square().area == 4
+assert Square.unit() == 'Square'
+assert Square.kind() == 'square'
+
+
+class Sized(abc.ABC):
+    @classmethod
+    def __subclasshook__(cls, subclass):
+        if hasattr(subclass, '__len__'):
+            return True
+        return NotImplemented
+
+
+assert issubclass(list, Sized)
+assert isinstance('abc', Sized)
+assert not issubclass(int, Sized)
+assert not isinstance(1, Sized)
+
+
+with assertRaises(TypeError):
+    CustomInterface.register(1)
+
+with assertRaises(RuntimeError):
+    Complete.register(CustomInterface)
+
+token = abc.get_cache_token()
+CustomInterface.register(int)
+assert abc.get_cache_token() != token
+assert isinstance(1, CustomInterface)
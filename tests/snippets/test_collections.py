@@ -1,4 +1,5 @@
 from collections import deque
+from testutils import assert_raises
 
 
 d = deque([0, 1, 2])
@@ -37,3 +38,67 @@ assert deque(maxlen=3) == deque()
 assert deque([1, 2, 3, 4], maxlen=2) == deque([3, 4])
 
 assert len(deque([1, 2, 3, 4])) == 4
+
+# maxlen evicts from the opposite end
+d = deque([1, 2, 3], maxlen=3)
+d.append(4)
+assert d == deque([2, 3, 4])
+d.appendleft(0)
+assert d == deque([0, 2, 3])
+d.extend([5, 6])
+assert d == deque([3, 5, 6])
+d.extendleft([7, 8])
+assert d == deque([8, 7, 3])
+assert d.maxlen == 3
+
+d = deque(maxlen=0)
+d.append(1)
+d.appendleft(2)
+assert len(d) == 0
+assert_raises(ValueError, lambda: deque(maxlen=-1))
+
+d = deque([1, 2, 3, 4, 5])
+d.rotate(1)
+assert d == deque([5, 1, 2, 3, 4])
+d.rotate(-2)
+assert d == deque([2, 3, 4, 5, 1])
+d.rotate(11)
+assert d == deque([1, 2, 3, 4, 5])
+empty = deque()
+empty.rotate(3)
+assert not empty
+
+assert d[0] == 1 and d[-1] == 5
+d[0] = 'a'
+del d[1]
+assert list(d) == ['a', 3, 4, 5]
+assert list(reversed(d)) == [5, 4, 3, 'a']
+assert 3 in d and 9 not in d
+assert_raises(IndexError, lambda: d[10])
+assert_raises(IndexError, lambda: d[-5])
+
+assert d.pop() == 5
+assert d.popleft() == 'a'
+assert_raises(IndexError, lambda: deque().pop())
+assert_raises(IndexError, lambda: deque().popleft())
+
+d = deque()
+d.insert(0, 'x')
+d.insert(5, 'y')
+assert d == deque(['x', 'y'])
+assert deque('abcab').index('b', 2) == 4
+
+assert not deque([1]) < deque([1])
+assert deque([1, 2]) + deque([3]) == deque([1, 2, 3])
+d = deque([1])
+d += [2, 3]
+assert d == deque([1, 2, 3])
+
+
+def mutate_while_iterating():
+    d = deque([1, 2])
+    for x in d:
+        d.append(x)
+
+
+assert_raises(RuntimeError, mutate_while_iterating)
@@ -31,3 +31,13 @@ assert h.hexdigest() == 'a0e1ad0c123c9c65e8ef850db2ce4b5cef2c35b06527c615b015435
 h.update(b'bla')
 print(h.hexdigest())
 assert h.hexdigest() == '25738bfe4cc104131e1b45bece4dfd4e7e1d6f0dffda1211e996e9d5d3b66e81'
+
+h = hashlib.sha256(b'a')
+assert h.hexdigest() == 'ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb'
+assert h.block_size == 64
+assert hashlib.sha512().block_size == 128
+c = h.copy()
+c.update(b'b')
+assert h.hexdigest() == 'ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb'
+assert c.hexdigest() == hashlib.sha256(b'ab').hexdigest()
+assert c.name == 'sha256'
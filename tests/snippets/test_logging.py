@@ -1,4 +1,3 @@
-
 import io
 import sys
 
@@ -16,3 +15,51 @@ assert  'WOOT' in res
 assert  'WARN' in res
 print(res)
 
+# Module level helpers use the root logger and the basic format
+f.seek(0)
+f.truncate()
+logging.warning('x=%d', 5)
+assert f.getvalue() == 'WARNING:root:x=5\n'
+
+# The root logger defaults to WARNING
+f.seek(0)
+f.truncate()
+logging.info('not shown')
+logging.debug('not shown')
+assert f.getvalue() == ''
+
+assert logging.DEBUG < logging.INFO < logging.WARNING < logging.ERROR < logging.CRITICAL
+assert logging.getLevelName(logging.ERROR) == 'ERROR'
+
+# Child loggers propagate to the handlers of their ancestors
+out = io.StringIO()
+handler = logging.StreamHandler(out)
+handler.setFormatter(logging.Formatter('%(name)s|%(levelname)s|%(message)s'))
+parent = logging.getLogger('app')
+parent.addHandler(handler)
+parent.setLevel(logging.DEBUG)
+
+child = logging.getLogger('app.sub')
+assert child.parent is parent
+assert logging.getLogger('app.sub') is child
+assert child.getEffectiveLevel() == logging.DEBUG
+
+child.debug('%s and %s', 'a', 'b')
+child.info('info')
+assert out.getvalue() == 'app.sub|DEBUG|a and b\napp.sub|INFO|info\n'
+
+# Level filtering on the child
+out.seek(0)
+out.truncate()
+child.setLevel(logging.ERROR)
+child.warning('dropped')
+child.error('kept')
+child.critical('also kept')
+assert out.getvalue() == 'app.sub|ERROR|kept\napp.sub|CRITICAL|also kept\n'
+
+# Disabling propagation stops records at the child
+out.seek(0)
+out.truncate()
+child.propagate = False
+child.error('stays put')
+assert out.getvalue() == ''
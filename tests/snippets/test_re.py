@@ -26,3 +26,44 @@ print(mo)
 # TODO:
 # assert mo.group(0) == '_boe0'
 
+
+assert re.fullmatch('a|ab', 'ab').group() == 'ab'
+assert re.fullmatch('a', 'ab') is None
+assert re.compile('[a-c]+').fullmatch('abcab').group() == 'abcab'
+assert re.compile('A', re.IGNORECASE).fullmatch('a')
+assert re.match(r'ab\Z', 'ab')
+assert re.match(r'ab\Z', 'ab\n') is None
+assert re.match(r'a\\Z', 'a\\Z')
+
+assert re.sub('(a)(b)', r'\2\1', 'xaby') == 'xbay'
+assert re.sub('(?P<first>a)', r'<\g<first>>', 'ba') == 'b<a>'
+assert re.sub('(a)', r'[\g<1>]', 'a') == '[a]'
+assert re.sub('a', '$1', 'a') == '$1'
+assert re.sub('a', r'\n\t\\', 'a') == '\n\t\\'
+assert re.sub('a', r'\0', 'a') == '\0'
+assert re.compile('(a)').sub(r'\1\1', 'ab') == 'aab'
+try:
+    re.sub('a', r'\q', 'a')
+except Exception:
+    pass
+else:
+    assert False, 'unknown escapes should be rejected'
+
+# a set is made of plain characters
+assert re.match('[[]', '[')
+assert re.match('[]a]+', ']a]').group() == ']a]'
+assert re.match('[^]]', ']') is None
+assert re.match('[a&&b]+', 'a&b').group() == 'a&b'
+assert re.match('[~]', '~')
+
+m = re.match(r'(?P<first>\w+) (?P<last>\w+)', 'Jane Doe')
+assert m.group('first') == 'Jane'
+assert m.groupdict() == {'first': 'Jane', 'last': 'Doe'}
+assert re.match(r'(?P<a>x)(?P<b>y)?', 'x').groupdict('-') == {'a': 'x', 'b': '-'}
+
+p = re.compile(r'\d+')
+assert p.match('ab12', 2).group() == '12'
+assert p.match('ab12', 1) is None
+assert p.search('1 23 456', 1, 4).group() == '23'
+assert p.fullmatch('a123b', 1, 4).group() == '123'
+assert re.match('^a', 'ba') is None
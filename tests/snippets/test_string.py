@@ -23,3 +23,50 @@ s = Template('$who likes $what')
 from string import Formatter
 
 f = Formatter()
+assert f.format('{0} {name}', 'x', name='y') == 'x y'
+assert f.format('{0.real:{1}} {x[1]!r}', 5, 4, x=['a', 'b']) == '   5 \'b\''
+assert f.vformat('{}-{}', (1, 2), {}) == '1-2'
+assert list(f.parse('a{0!s:>3}b{}')) == [
+    ('a', '0', '>3', 's'),
+    ('b', '', '', None),
+]
+assert list(f.parse('tail')) == [('tail', None, None, None)]
+assert f.get_field('0.imag', (3,), {}) == (0, 0)
+assert f.convert_field(1, 'r') == '1'
+
+
+class DefaultFormatter(Formatter):
+    def get_value(self, key, args, kwargs):
+        if isinstance(key, str):
+            return kwargs.get(key, '<' + key + '>')
+        return super().get_value(key, args, kwargs)
+
+    def format_field(self, value, format_spec):
+        return str(value).upper()
+
+
+assert DefaultFormatter().format('{a} {b} {0}', 'z', a='q') == 'Q <B> Z'
+
+
+class StrictFormatter(Formatter):
+    def check_unused_args(self, used_args, args, kwargs):
+        unused = set(kwargs) - used_args
+        if unused:
+            raise ValueError(sorted(unused))
+
+
+try:
+    StrictFormatter().format('{a}', a=1, b=2)
+except ValueError as e:
+    assert e.args == (['b'],)
+else:
+    assert False, 'unused argument not reported'
+
+import _string
+
+first, rest = _string.formatter_field_name_split('0.a[b][1]')
+assert first == 0
+assert list(rest) == [(True, 'a'), (False, 'b'), (False, 1)]
+first, rest = _string.formatter_field_name_split('name')
+assert first == 'name'
+assert list(rest) == []
@@ -1,6 +1,8 @@
 
 import struct
 
+from testutils import assert_raises
+
 data = struct.pack('IH', 14, 12)
 assert data == bytes([14, 0, 0, 0, 12, 0])
 
@@ -22,3 +24,30 @@ v1, v2 = struct.unpack('>IH', data)
 assert v1 == 14
 assert v2 == 12
 
+
+# repeat counts, padding, strings and bools
+data = struct.pack('<4s2xH?', b'PK', 7, True)
+assert data == b'PK\x00\x00\x00\x00\x07\x00\x01'
+assert struct.unpack('<4s2xH?', data) == (b'PK\x00\x00', 7, True)
+assert struct.pack('3B', 1, 2, 3) == bytes([1, 2, 3])
+assert struct.unpack(b'<2H', bytearray(b'\x01\x00\x02\x00')) == (1, 2)
+assert struct.pack('2s', b'abc') == b'ab'
+
+assert struct.calcsize('<4s4H2LH') == 22
+assert struct.calcsize(b'<4sLQL') == 20
+
+s = struct.Struct('<HH')
+assert s.size == 4
+assert s.format == '<HH'
+assert s.pack(1, 2) == b'\x01\x00\x02\x00'
+assert s.unpack(b'\x01\x00\x02\x00') == (1, 2)
+
+assert issubclass(struct.error, Exception)
+assert_raises(struct.error, lambda: struct.pack('<H', 1, 2))
+assert_raises(struct.error, lambda: struct.pack('B', 256))
+assert_raises(struct.error, lambda: struct.unpack('<I', b'ab'))
+assert_raises(struct.error, lambda: struct.calcsize('z'))
+# sizes that overflow are rejected rather than wrapping around
+assert_raises(struct.error, lambda: struct.calcsize('4611686018427387904q'))
+assert_raises(struct.error, lambda: struct.calcsize('5000000000000000000x5000000000000000000x'))
+assert_raises(struct.error, lambda: struct.pack('4611686018427387904q'))
@@ -20,3 +20,40 @@ s = time.asctime(x)
 # print(s)
 assert s == 'Thu Jan  1 00:16:40 1970'
 
+
+a = time.monotonic()
+b = time.monotonic()
+assert b >= a
+assert isinstance(time.monotonic_ns(), int)
+
+a = time.perf_counter()
+b = time.perf_counter()
+assert b >= a
+assert isinstance(time.perf_counter_ns(), int)
+
+assert time.process_time() >= 0
+assert isinstance(time.process_time_ns(), int)
+
+from testutils import assert_raises
+
+assert time.strftime('%Y-%m-%d', time.gmtime(0)) == '1970-01-01'
+assert time.strftime('%a %A %b %B %j %w %p %%', time.gmtime(0)) == 'Thu Thursday Jan January 001 4 AM %'
+assert time.strftime('%z', time.gmtime(0)) == '+0000'
+
+x = time.strptime('Mar 05 2019 01:02:03 PM', '%b %d %Y %I:%M:%S %p')
+assert x.tm_year == 2019
+assert x.tm_mon == 3
+assert x.tm_mday == 5
+assert x.tm_hour == 13
+assert x.tm_yday == 64
+
+x = time.strptime('2019 100', '%Y %j')
+assert x.tm_mon == 4
+assert x.tm_mday == 10
+
+assert_raises(ValueError, lambda: time.strptime('2019-13', '%Y-%m'))
+assert_raises(ValueError, lambda: time.strptime('2019', '%Y-%m'))
+assert_raises(ValueError, lambda: time.strptime('2019-01 extra', '%Y-%m'))
+assert_raises(ValueError, lambda: time.strptime('+12€', '%z'))
+assert_raises(ValueError, lambda: time.strptime('İMar', '%b'))
+assert time.strptime('MARCH', '%B').tm_mon == 3
@@ -47,3 +47,13 @@ assert () is ()
 a = ()
 b = ()
 assert a is b
+
+# the first differing item decides the order
+assert (3, 1) < (10, 1)
+assert not (10, 1) < (3, 1)
+assert (1, 5) < (2, 0)
+assert (2, 0) > (1, 5)
+assert (1, 2) <= (1, 3)
+assert (1, 3) >= (1, 2, 4)
+assert (1, 2) < (1, 2, 0)
+assert sorted([(3, 1), (10, 1), (1, 1)], reverse=True) == [(10, 1), (3, 1), (1, 1)]
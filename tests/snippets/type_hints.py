@@ -10,3 +10,53 @@ print(curry.__annotations__)
 assert curry.__annotations__['foo'] is int
 assert curry.__annotations__['return'] is float
 assert curry.__annotations__['bla'] is int
+
+def keywords(a: int, *args: str, b: float, c=1, **kwargs: bool) -> list:
+    pass
+
+assert keywords.__annotations__ == {
+    'a': int, 'args': str, 'b': float, 'kwargs': bool, 'return': list
+}
+assert list(keywords.__annotations__) == ['a', 'args', 'b', 'kwargs', 'return']
+
+def unannotated(a, b=2):
+    pass
+
+assert unannotated.__annotations__ == {}
+
+# Variable annotations:
+x: int
+y: str = 'y'
+
+assert __annotations__['x'] is int
+assert __annotations__['y'] is str
+assert 'x' not in globals()
+assert y == 'y'
+
+def local_annotations():
+    # Annotations of local variables are not evaluated.
+    z: undefined_name
+    w: undefined_name = 3
+    return w
+
+assert local_annotations() == 3
+assert local_annotations.__annotations__ == {}
+
+class Point:
+    x: int
+    if True:
+        y: int = 0
+    z: 'float'
+
+    def method(self):
+        pass
+
+assert list(Point.__annotations__) == ['x', 'y', 'z']
+assert Point.__annotations__['z'] == 'float'
+assert not hasattr(Point, 'x')
+assert Point.y == 0
+
+namespace = {}
+exec('v: int', namespace)
+assert namespace['__annotations__'] == {'v': int}
+assert 'v' not in namespace
@@ -19,3 +19,28 @@ assert unicodedata.name('a') == 'LATIN SMALL LETTER A'
 assert unicodedata.lookup('LATIN SMALL LETTER A') == 'a'
 assert unicodedata.bidirectional('a') == 'L'
 assert unicodedata.normalize('NFC', 'bla') == 'bla'
+
+assert unicodedata.category('é') == 'Ll'
+assert unicodedata.category('²') == 'No'
+assert unicodedata.name('α') == 'GREEK SMALL LETTER ALPHA'
+assert unicodedata.name('￿', None) is None
+assert unicodedata.lookup('GREEK SMALL LETTER ALPHA') == 'α'
+
+assert unicodedata.decimal('7') == 7
+assert unicodedata.decimal('٣') == 3
+assert unicodedata.decimal('²', None) is None
+assert unicodedata.digit('²') == 2
+assert unicodedata.digit('①') == 1
+assert unicodedata.numeric('½') == 0.5
+assert unicodedata.numeric('Ⅻ') == 12.0
+assert unicodedata.numeric('三') == 3.0
+assert unicodedata.numeric('a', -1) == -1
+
+assert unicodedata.combining('a') == 0
+assert unicodedata.combining('́') == 230
+
+decomposed = 'é'
+assert unicodedata.normalize('NFC', decomposed) == 'é'
+assert unicodedata.normalize('NFD', 'é') == decomposed
+assert unicodedata.normalize('NFKC', 'ﬁ') == 'fi'
+assert unicodedata.normalize('NFKD', '①') == '1'
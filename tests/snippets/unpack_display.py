@@ -0,0 +1,46 @@
+from testutils import assert_raises
+
+# unpacking inside list, tuple and set displays keeps the order
+a = [1, 2]
+b = (3, 4)
+assert [*a, *b] == [1, 2, 3, 4]
+assert [*range(2), *range(2)] == [0, 1, 0, 1]
+assert (*a, *b) == (1, 2, 3, 4)
+assert (*a,) == (1, 2)
+assert {*a, *b} == {1, 2, 3, 4}
+assert {*'ab', *'bc'} == {'a', 'b', 'c'}
+
+# literal elements can be mixed with unpacked ones
+assert [0, *a, 5, *b, 6] == [0, 1, 2, 5, 3, 4, 6]
+assert (0, *a, 5) == (0, 1, 2, 5)
+assert {0, *a, 5} == {0, 1, 2, 5}
+assert [*(x * 2 for x in a), *{'k': 1}] == [2, 4, 'k']
+assert [*[], *()] == []
+
+# dict displays: later keys win
+d1 = {'a': 1, 'b': 2}
+d2 = {'b': 3, 'c': 4}
+assert {**d1, **d2} == {'a': 1, 'b': 3, 'c': 4}
+assert list({**d1, **d2}) == ['a', 'b', 'c']
+assert {**{'a': 1}, 'a': 2} == {'a': 2}
+assert {'a': 2, **{'a': 1}} == {'a': 1}
+assert {'z': 0, **d1, 'y': 5, **d2} == {'z': 0, 'a': 1, 'b': 3, 'y': 5, 'c': 4}
+assert list({'z': 0, **d1, 'y': 5}) == ['z', 'a', 'b', 'y']
+assert {**{}} == {}
+
+
+class Mapping:
+    def keys(self):
+        return ['m']
+
+    def __getitem__(self, key):
+        return 'value'
+
+assert {**Mapping(), 'n': 1} == {'m': 'value', 'n': 1}
+
+# unpacking something that is not iterable is a TypeError
+assert_raises(TypeError, lambda: [*1])
+assert_raises(TypeError, lambda: (*None,))
+assert_raises(TypeError, lambda: {*1})
+assert_raises(TypeError, lambda: {**1})
+assert_raises(TypeError, lambda: {**[('a', 1)]})
@@ -17,11 +17,12 @@ use crate::obj::objdict::PyDictRef;
 use crate::obj::objint::{self, PyIntRef};
 use crate::obj::objiter;
 use crate::obj::objstr::{PyString, PyStringRef};
-use crate::obj::objtype::{self, PyClassRef};
+use crate::obj::objtuple::PyTuple;
+use crate::obj::objtype::{self, PyClass, PyClassRef};
 #[cfg(feature = "rustpython-compiler")]
 use rustpython_compiler::compile;
 
-use crate::function::{single_or_tuple_any, Args, KwArgs, OptionalArg, PyFuncArgs};
+use crate::function::{Args, KwArgs, OptionalArg, PyFuncArgs};
 use crate::pyobject::{
     Either, IdProtocol, IntoPyObject, ItemProtocol, PyIterable, PyObjectRef, PyResult, PyValue,
     TryFromObject, TypeProtocol,
@@ -73,7 +74,9 @@ pub fn to_ascii(value: &str) -> String {
             ascii.push(c)
         } else {
             let c = c as i64;
-            let hex = if c < 0x10000 {
+            let hex = if c < 0x100 {
+                format!("\\x{:02x}", c)
+            } else if c < 0x10000 {
                 format!("\\u{:04x}", c)
             } else {
                 format!("\\U{:08x}", c)
@@ -84,12 +87,13 @@ pub fn to_ascii(value: &str) -> String {
     ascii
 }
 
-fn builtin_bin(x: PyIntRef, _vm: &VirtualMachine) -> String {
+fn builtin_bin(x: PyObjectRef, vm: &VirtualMachine) -> PyResult<String> {
+    let x = vm.to_index(&x)?;
     let x = x.as_bigint();
     if x.is_negative() {
-        format!("-0b{:b}", x.abs())
+        Ok(format!("-0b{:b}", x.abs()))
     } else {
-        format!("0b{:b}", x)
+        Ok(format!("0b{:b}", x))
     }
 }
 
@@ -137,7 +141,16 @@ fn builtin_compile(args: CompileArgs, vm: &VirtualMachine) -> PyResult<PyCodeRef
         .parse::<compile::Mode>()
         .map_err(|err| vm.new_value_error(err.to_string()))?;
 
-    vm.compile(&source, mode, args.filename.value.to_string())
+    let optimize = match args.optimize.into_option() {
+        Some(optimize) => match optimize.as_bigint().to_i32() {
+            Some(-1) => vm.settings.optimize,
+            Some(level @ 0..=2) => level as u8,
+            _ => return Err(vm.new_value_error("compile(): invalid optimize value".to_string())),
+        },
+        None => vm.settings.optimize,
+    };
+
+    vm.compile_with_optimize(&source, mode, args.filename.value.to_string(), optimize)
         .map_err(|err| vm.new_syntax_error(&err))
 }
 
@@ -307,7 +320,8 @@ fn builtin_hash(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult {
 
 // builtin_help
 
-fn builtin_hex(number: PyIntRef, vm: &VirtualMachine) -> PyResult {
+fn builtin_hex(number: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let number = vm.to_index(&number)?;
     let n = number.as_bigint();
     let s = if n.is_negative() {
         format!("-0x{:x}", n.abs())
@@ -324,35 +338,89 @@ fn builtin_id(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult {
 
 // builtin_input
 
+fn builtin_input(prompt: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<String> {
+    let stdin = vm
+        .get_attribute(vm.sys_module.clone(), "stdin")
+        .map_err(|_| vm.new_runtime_error("input(): lost sys.stdin".to_string()))?;
+    let stdout = vm
+        .get_attribute(vm.sys_module.clone(), "stdout")
+        .map_err(|_| vm.new_runtime_error("input(): lost sys.stdout".to_string()))?;
+
+    if let OptionalArg::Present(prompt) = prompt {
+        let prompt = vm.to_str(&prompt)?;
+        vm.call_method(&stdout, "write", vec![prompt.into_object()])?;
+    }
+    vm.call_method(&stdout, "flush", vec![])?;
+
+    let line = vm.call_method(&stdin, "readline", vec![])?;
+    let line = PyStringRef::try_from_object(vm, line)?;
+    let line = line.as_str();
+    if line.is_empty() {
+        let eof_error = vm.ctx.exceptions.eof_error.clone();
+        return Err(vm.new_exception(eof_error, "EOF when reading a line".to_string()));
+    }
+    let line = if line.ends_with('\n') {
+        &line[..line.len() - 1]
+    } else {
+        line
+    };
+    Ok(line.to_string())
+}
+
 fn builtin_isinstance(obj: PyObjectRef, typ: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
-    single_or_tuple_any(
-        typ,
-        |cls: PyClassRef| vm.isinstance(&obj, &cls),
-        |o| {
-            format!(
-                "isinstance() arg 2 must be a type or tuple of types, not {}",
-                o.class()
-            )
+    match_class!(typ,
+        cls @ PyClass => vm.isinstance(&obj, &cls),
+        tuple @ PyTuple => {
+            for typ in tuple.elements.iter() {
+                if builtin_isinstance(obj.clone(), typ.clone(), vm)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
         },
-        vm,
+        typ => match vm.get_method(typ.clone(), "__instancecheck__") {
+            Some(method) => {
+                let ret = vm.invoke(&method?, vec![obj])?;
+                objbool::boolval(vm, ret)
+            }
+            None => Err(vm.new_type_error(format!(
+                "isinstance() arg 2 must be a type or tuple of types, not {}",
+                typ.class()
+            ))),
+        }
     )
 }
 
 fn builtin_issubclass(
-    subclass: PyClassRef,
+    subclass: PyObjectRef,
     typ: PyObjectRef,
     vm: &VirtualMachine,
 ) -> PyResult<bool> {
-    single_or_tuple_any(
-        typ,
-        |cls: PyClassRef| vm.issubclass(&subclass, &cls),
-        |o| {
-            format!(
-                "issubclass() arg 2 must be a class or tuple of classes, not {}",
-                o.class()
-            )
+    match_class!(typ,
+        cls @ PyClass => {
+            let subclass = PyClassRef::try_from_object(vm, subclass).map_err(|_| {
+                vm.new_type_error("issubclass() arg 1 must be a class".to_string())
+            })?;
+            vm.issubclass(&subclass, &cls)
+        },
+        tuple @ PyTuple => {
+            for typ in tuple.elements.iter() {
+                if builtin_issubclass(subclass.clone(), typ.clone(), vm)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
         },
-        vm,
+        typ => match vm.get_method(typ.clone(), "__subclasscheck__") {
+            Some(method) => {
+                let ret = vm.invoke(&method?, vec![subclass])?;
+                objbool::boolval(vm, ret)
+            }
+            None => Err(vm.new_type_error(format!(
+                "issubclass() arg 2 must be a class or tuple of classes, not {}",
+                typ.class()
+            ))),
+        }
     )
 }
 
@@ -360,11 +428,20 @@ fn builtin_iter(iter_target: PyObjectRef, vm: &VirtualMachine) -> PyResult {
     objiter::get_iter(vm, &iter_target)
 }
 
-fn builtin_len(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+pub fn builtin_len(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<usize> {
     let method = vm.get_method_or_type_error(obj.clone(), "__len__", || {
         format!("object of type '{}' has no len()", obj.class().name)
     })?;
-    vm.invoke(&method, PyFuncArgs::default())
+    let len = vm.to_index(&vm.invoke(&method, PyFuncArgs::default())?)?;
+    if len.as_bigint().is_negative() {
+        return Err(vm.new_value_error("__len__() should return >= 0".to_string()));
+    }
+    len.as_bigint()
+        .to_isize()
+        .map(|len| len as usize)
+        .ok_or_else(|| {
+            vm.new_overflow_error("cannot fit 'int' into an index-sized integer".to_string())
+        })
 }
 
 fn builtin_locals(vm: &VirtualMachine) -> PyDictRef {
@@ -488,7 +565,8 @@ fn builtin_next(
     }
 }
 
-fn builtin_oct(number: PyIntRef, vm: &VirtualMachine) -> PyResult {
+fn builtin_oct(number: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let number = vm.to_index(&number)?;
     let n = number.as_bigint();
     let s = if n.is_negative() {
         format!("-0o{:o}", n.abs())
@@ -586,12 +664,14 @@ trait Printer {
 
 impl Printer for &'_ PyObjectRef {
     fn write(&mut self, vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<()> {
-        vm.call_method(self, "write", vec![obj])?;
+        let write = vm.get_attribute((*self).clone(), "write")?;
+        vm.invoke(&write, vec![obj])?;
         Ok(())
     }
 
     fn flush(&mut self, vm: &VirtualMachine) -> PyResult<()> {
-        vm.call_method(self, "flush", vec![])?;
+        let flush = vm.get_attribute((*self).clone(), "flush")?;
+        vm.invoke(&flush, vec![])?;
         Ok(())
     }
 }
@@ -609,23 +689,25 @@ impl Printer for std::io::StdoutLock<'_> {
     }
 }
 
-pub fn builtin_exit(exit_code_arg: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<()> {
-    if let OptionalArg::Present(exit_code_obj) = exit_code_arg {
-        match i32::try_from_object(&vm, exit_code_obj.clone()) {
-            Ok(code) => std::process::exit(code),
-            _ => println!("{}", vm.to_str(&exit_code_obj)?.as_str()),
-        }
-    }
-    std::process::exit(0);
+pub fn builtin_exit(code: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<()> {
+    let code = code.unwrap_or_else(|| vm.get_none());
+    let system_exit = vm.ctx.exceptions.system_exit.clone();
+    Err(vm.new_exception_obj(system_exit, vec![code])?)
 }
 
 pub fn builtin_print(objects: Args, options: PrintOptions, vm: &VirtualMachine) -> PyResult<()> {
     let stdout = io::stdout();
 
-    let mut printer: Box<dyn Printer> = if let Some(file) = &options.file {
-        Box::new(file)
-    } else {
-        Box::new(stdout.lock())
+    let file = match options.file {
+        Some(ref file) if !vm.is_none(file) => Some(file.clone()),
+        // fall back to the real stdout if sys.stdout hasn't been set up
+        _ => vm.get_attribute(vm.sys_module.clone(), "stdout").ok(),
+    };
+    let mut printer: Box<dyn Printer> = match &file {
+        // like CPython, printing to a missing sys.stdout does nothing
+        Some(file) if vm.is_none(file) => return Ok(()),
+        Some(file) => Box::new(file),
+        None => Box::new(stdout.lock()),
     };
 
     let sep = options
@@ -643,7 +725,7 @@ pub fn builtin_print(objects: Args, options: PrintOptions, vm: &VirtualMachine)
             printer.write(vm, sep.clone())?;
         }
 
-        printer.write(vm, object)?;
+        printer.write(vm, vm.to_str(&object)?.into_object())?;
     }
 
     let end = options
@@ -684,27 +766,21 @@ fn builtin_reversed(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult {
 
 fn builtin_round(
     number: PyObjectRef,
-    ndigits: OptionalArg<Option<PyIntRef>>,
+    ndigits: OptionalArg<Option<PyObjectRef>>,
     vm: &VirtualMachine,
 ) -> PyResult {
-    match ndigits {
-        OptionalArg::Present(ndigits) => match ndigits {
-            Some(int) => {
-                let ndigits = vm.call_method(int.as_object(), "__int__", vec![])?;
-                let rounded = vm.call_method(&number, "__round__", vec![ndigits])?;
-                Ok(rounded)
-            }
-            None => {
-                let rounded = &vm.call_method(&number, "__round__", vec![])?;
-                Ok(vm.ctx.new_int(objint::get_value(rounded).clone()))
-            }
-        },
-        OptionalArg::Missing => {
-            // without a parameter, the result type is coerced to int
-            let rounded = &vm.call_method(&number, "__round__", vec![])?;
-            Ok(vm.ctx.new_int(objint::get_value(rounded).clone()))
-        }
-    }
+    let method = vm.get_method_or_type_error(number.clone(), "__round__", || {
+        format!(
+            "type {} doesn't define __round__ method",
+            number.class().name
+        )
+    })?;
+    // round(x, None) is the same as round(x)
+    let args = match ndigits.flat_option() {
+        Some(ndigits) => vec![ndigits],
+        None => vec![],
+    };
+    vm.invoke(&method, args)
 }
 
 fn builtin_setattr(
@@ -803,6 +879,7 @@ pub fn make_module(vm: &VirtualMachine, module: PyObjectRef) {
         "hash" => ctx.new_rustfunc(builtin_hash),
         "hex" => ctx.new_rustfunc(builtin_hex),
         "id" => ctx.new_rustfunc(builtin_id),
+        "input" => ctx.new_rustfunc(builtin_input),
         "int" => ctx.int_type(),
         "isinstance" => ctx.new_rustfunc(builtin_isinstance),
         "issubclass" => ctx.new_rustfunc(builtin_issubclass),
@@ -856,6 +933,7 @@ pub fn make_module(vm: &VirtualMachine, module: PyObjectRef) {
         "OverflowError" => ctx.exceptions.overflow_error.clone(),
         "RuntimeError" => ctx.exceptions.runtime_error.clone(),
         "ReferenceError" => ctx.exceptions.reference_error.clone(),
+        "RecursionError" => ctx.exceptions.recursion_error.clone(),
         "SyntaxError" =>  ctx.exceptions.syntax_error.clone(),
         "NotImplementedError" => ctx.exceptions.not_implemented_error.clone(),
         "TypeError" => ctx.exceptions.type_error.clone(),
@@ -863,8 +941,10 @@ pub fn make_module(vm: &VirtualMachine, module: PyObjectRef) {
         "IndexError" => ctx.exceptions.index_error.clone(),
         "ImportError" => ctx.exceptions.import_error.clone(),
         "LookupError" => ctx.exceptions.lookup_error.clone(),
+        "MemoryError" => ctx.exceptions.memory_error.clone(),
         "FileNotFoundError" => ctx.exceptions.file_not_found_error.clone(),
         "FileExistsError" => ctx.exceptions.file_exists_error.clone(),
+        "StopAsyncIteration" => ctx.exceptions.stop_async_iteration.clone(),
         "StopIteration" => ctx.exceptions.stop_iteration.clone(),
         "SystemError" => ctx.exceptions.system_error.clone(),
         "PermissionError" => ctx.exceptions.permission_error.clone(),
@@ -892,26 +972,30 @@ pub fn make_module(vm: &VirtualMachine, module: PyObjectRef) {
         "UserWarning" => ctx.exceptions.user_warning.clone(),
 
         "KeyboardInterrupt" => ctx.exceptions.keyboard_interrupt.clone(),
+        "SystemExit" => ctx.exceptions.system_exit.clone(),
+        "GeneratorExit" => ctx.exceptions.generator_exit.clone(),
     });
 }
 
 pub fn builtin_build_class_(
     function: PyObjectRef,
     qualified_name: PyStringRef,
-    bases: Args<PyClassRef>,
+    orig_bases: Args,
     mut kwargs: KwArgs,
     vm: &VirtualMachine,
 ) -> PyResult {
     let name = qualified_name.value.split('.').next_back().unwrap();
     let name_obj = vm.new_str(name.to_string());
 
+    let (bases, orig_bases) = resolve_mro_entries(orig_bases, vm)?;
+
     let mut metaclass = if let Some(metaclass) = kwargs.pop_kwarg("metaclass") {
         PyClassRef::try_from_object(vm, metaclass)?
     } else {
         vm.get_type()
     };
 
-    for base in bases.clone() {
+    for base in bases.iter() {
         if objtype::issubclass(&base.class(), &metaclass) {
             metaclass = base.class();
         } else if !objtype::issubclass(&metaclass, &base.class()) {
@@ -923,7 +1007,9 @@ pub fn builtin_build_class_(
         }
     }
 
-    let bases = bases.into_tuple(vm);
+    let bases = vm
+        .ctx
+        .new_tuple(bases.into_iter().map(PyClassRef::into_object).collect());
 
     // Prepare uses full __getattribute__ resolution chain.
     let prepare = vm.get_attribute(metaclass.clone().into_object(), "__prepare__")?;
@@ -937,6 +1023,9 @@ pub fn builtin_build_class_(
 
     namespace.set_item("__name__", name_obj.clone(), vm)?;
     namespace.set_item("__qualname__", qualified_name.into_object(), vm)?;
+    if let Some(orig_bases) = orig_bases {
+        namespace.set_item("__orig_bases__", orig_bases, vm)?;
+    }
 
     let class = vm.call_method(
         metaclass.as_object(),
@@ -946,3 +1035,37 @@ pub fn builtin_build_class_(
     cells.set_item("__class__", class.clone(), vm)?;
     Ok(class)
 }
+
+/// Replace bases that aren't classes by the result of their `__mro_entries__`
+/// method (PEP 560). The original bases are returned too if any got replaced.
+fn resolve_mro_entries(
+    orig_bases: Args,
+    vm: &VirtualMachine,
+) -> PyResult<(Vec<PyClassRef>, Option<PyObjectRef>)> {
+    let orig_bases = orig_bases.into_vec();
+    let orig_tuple = vm.ctx.new_tuple(orig_bases.clone());
+    let mut bases = vec![];
+    let mut replaced = false;
+    for base in orig_bases {
+        if objtype::isinstance(&base, &vm.ctx.type_type()) {
+            bases.push(PyClassRef::try_from_object(vm, base)?);
+            continue;
+        }
+        match vm.get_method(base.clone(), "__mro_entries__") {
+            Some(method) => {
+                let entries = vm.invoke(&method?, vec![orig_tuple.clone()])?;
+                if !objtype::isinstance(&entries, &vm.ctx.tuple_type()) {
+                    return Err(
+                        vm.new_type_error("__mro_entries__ must return a tuple".to_string())
+                    );
+                }
+                for entry in vm.extract_elements(&entries)? {
+                    bases.push(PyClassRef::try_from_object(vm, entry)?);
+                }
+                replaced = true;
+            }
+            None => bases.push(PyClassRef::try_from_object(vm, base)?),
+        }
+    }
+    Ok((bases, if replaced { Some(orig_tuple) } else { None }))
+}
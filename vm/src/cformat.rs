@@ -47,6 +47,7 @@ pub enum CFormatPreconversor {
     Repr,
     Str,
     Ascii,
+    Bytes,
 }
 
 #[derive(Debug, PartialEq)]
@@ -101,7 +102,7 @@ pub struct CFormatSpec {
     pub precision: Option<CFormatQuantity>,
     pub format_type: CFormatType,
     pub format_char: char,
-    chars_consumed: usize,
+    pub chars_consumed: usize,
 }
 
 impl CFormatSpec {
@@ -210,35 +211,44 @@ impl CFormatSpec {
         }
     }
 
-    pub fn format_float(&self, num: f64) -> Result<String, String> {
-        let magnitude = num.abs();
-
-        let sign_string = if num.is_sign_positive() {
-            if self.flags.contains(CConversionFlags::SIGN_CHAR) {
-                "+"
-            } else if self.flags.contains(CConversionFlags::BLANK_SIGN) {
-                " "
-            } else {
-                ""
-            }
-        } else {
+    pub fn format_float(&self, num: f64) -> String {
+        let sign_string = if num.is_sign_negative() && !num.is_nan() {
             "-"
+        } else if self.flags.contains(CConversionFlags::SIGN_CHAR) {
+            "+"
+        } else if self.flags.contains(CConversionFlags::BLANK_SIGN) {
+            " "
+        } else {
+            ""
         };
 
-        // TODO: Support precision
+        let precision = match self.precision {
+            Some(CFormatQuantity::Amount(precision)) => precision,
+            _ => 6,
+        };
+        let alternate_form = self.flags.contains(CConversionFlags::ALTERNATE_FORM);
+
+        if !num.is_finite() {
+            let text = if num.is_nan() { "nan" } else { "inf" };
+            let text = if self.format_char.is_ascii_uppercase() {
+                text.to_uppercase()
+            } else {
+                text.to_string()
+            };
+            // infinities and nans are never zero padded
+            return self.fill_string(format!("{}{}", sign_string, text), ' ', None);
+        }
+
+        let magnitude = num.abs();
         let magnitude_string = match self.format_type {
             CFormatType::Float(CFloatType::PointDecimal) => {
-                if Some(CFormatQuantity::Amount(6)) != self.precision {
-                    return Err("Not yet implemented for %#.#f types".to_string());
-                } else {
-                    format!("{:.6}", magnitude)
-                }
+                format_fixed(magnitude, precision, alternate_form)
             }
-            CFormatType::Float(CFloatType::Exponent(_)) => {
-                return Err("Not yet implemented for %e and %E".to_string())
+            CFormatType::Float(CFloatType::Exponent(ref case)) => {
+                format_exponent(magnitude, precision, case, alternate_form)
             }
-            CFormatType::Float(CFloatType::General(_)) => {
-                return Err("Not yet implemented for %g and %G".to_string())
+            CFormatType::Float(CFloatType::General(ref case)) => {
+                format_general(magnitude, precision, case, alternate_form)
             }
             _ => unreachable!(),
         };
@@ -249,7 +259,7 @@ impl CFormatSpec {
             } else {
                 ' '
             };
-            Ok(format!(
+            format!(
                 "{}{}",
                 sign_string,
                 self.fill_string(
@@ -257,13 +267,99 @@ impl CFormatSpec {
                     fill_char,
                     Some(sign_string.chars().count())
                 )
-            ))
+            )
         } else {
-            Ok(self.fill_string(format!("{}{}", sign_string, magnitude_string), ' ', None))
+            self.fill_string(format!("{}{}", sign_string, magnitude_string), ' ', None)
         }
     }
 }
 
+/// Format a non-negative, finite float as `%f` does.
+pub(crate) fn format_fixed(magnitude: f64, precision: usize, alternate_form: bool) -> String {
+    let mut result = format!("{:.*}", precision, magnitude);
+    if alternate_form && precision == 0 {
+        result.push('.');
+    }
+    result
+}
+
+/// Split a float into the mantissa digits and decimal exponent Rust picks for `{:.*e}`.
+fn split_exponent(magnitude: f64, precision: usize) -> (String, i32) {
+    let formatted = format!("{:.*e}", precision, magnitude);
+    let mut parts = formatted.splitn(2, 'e');
+    let mantissa = parts.next().unwrap().to_string();
+    // Rust always emits an integer exponent after the 'e'
+    let exponent = parts.next().unwrap().parse::<i32>().unwrap();
+    (mantissa, exponent)
+}
+
+fn join_exponent(mantissa: &str, exponent: i32, case: &CFormatCase) -> String {
+    let e = match case {
+        CFormatCase::Lowercase => 'e',
+        CFormatCase::Uppercase => 'E',
+    };
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{}{}{}{:02}", mantissa, e, sign, exponent.abs())
+}
+
+/// Format a non-negative, finite float as `%e` does, e.g. `1.500000e+03`.
+pub(crate) fn format_exponent(
+    magnitude: f64,
+    precision: usize,
+    case: &CFormatCase,
+    alternate_form: bool,
+) -> String {
+    let (mut mantissa, exponent) = split_exponent(magnitude, precision);
+    if alternate_form && precision == 0 {
+        mantissa.push('.');
+    }
+    join_exponent(&mantissa, exponent, case)
+}
+
+/// Format a non-negative, finite float as `%g` does: exponent notation is
+/// used for exponents below -4 or not less than the precision, and trailing
+/// zeros are removed unless the alternate form was requested.
+pub(crate) fn format_general(
+    magnitude: f64,
+    precision: usize,
+    case: &CFormatCase,
+    alternate_form: bool,
+) -> String {
+    let precision = cmp::max(precision, 1);
+    let (mantissa, exponent) = split_exponent(magnitude, precision - 1);
+    if exponent < -4 || exponent >= precision as i32 {
+        let mantissa = if alternate_form {
+            if precision == 1 {
+                format!("{}.", mantissa)
+            } else {
+                mantissa
+            }
+        } else {
+            strip_fraction_zeros(mantissa)
+        };
+        join_exponent(&mantissa, exponent, case)
+    } else {
+        let fraction_digits = (precision as i32 - 1 - exponent) as usize;
+        let result = format_fixed(magnitude, fraction_digits, alternate_form);
+        if alternate_form {
+            result
+        } else {
+            strip_fraction_zeros(result)
+        }
+    }
+}
+
+fn strip_fraction_zeros(number: String) -> String {
+    if number.contains('.') {
+        number
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        number
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum CFormatPart {
     Literal(String),
@@ -526,12 +622,12 @@ fn parse_format_type(text: &str) -> Result<(CFormatType, &str, char), CFormatErr
         )),
         Some('g') => Ok((
             CFormatType::Float(General(Lowercase)),
-            text,
+            chars.as_str(),
             next_char.unwrap(),
         )),
         Some('G') => Ok((
             CFormatType::Float(General(Uppercase)),
-            text,
+            chars.as_str(),
             next_char.unwrap(),
         )),
         Some('c') => Ok((CFormatType::Character, chars.as_str(), next_char.unwrap())),
@@ -550,6 +646,11 @@ fn parse_format_type(text: &str) -> Result<(CFormatType, &str, char), CFormatErr
             chars.as_str(),
             next_char.unwrap(),
         )),
+        Some('b') => Ok((
+            CFormatType::String(CFormatPreconversor::Bytes),
+            chars.as_str(),
+            next_char.unwrap(),
+        )),
         Some(c) => Err(CFormatErrorType::UnsupportedFormatChar(c)),
         None => Err(CFormatErrorType::IncompleteFormat), // should not happen because it is handled earlier in the parsing
     }
@@ -604,9 +705,13 @@ impl FromStr for CFormatSpec {
 fn parse_specifier(text: &str) -> Result<(CFormatPart, &str, usize), ParsingError> {
     let spec = text.parse::<CFormatSpec>()?;
     let chars_consumed = spec.chars_consumed;
+    let bytes_consumed = text
+        .char_indices()
+        .nth(chars_consumed)
+        .map_or(text.len(), |(index, _)| index);
     Ok((
         CFormatPart::Spec(spec),
-        &text[chars_consumed..],
+        &text[bytes_consumed..],
         chars_consumed,
     ))
 }
@@ -821,42 +926,56 @@ mod tests {
         assert_eq!(
             "%f".parse::<CFormatSpec>()
                 .unwrap()
-                .format_float(f64::from(1.2345))
-                .ok(),
-            Some("1.234500".to_string())
+                .format_float(f64::from(1.2345)),
+            "1.234500".to_string()
         );
         assert_eq!(
             "%+f"
                 .parse::<CFormatSpec>()
                 .unwrap()
-                .format_float(f64::from(1.2345))
-                .ok(),
-            Some("+1.234500".to_string())
+                .format_float(f64::from(1.2345)),
+            "+1.234500".to_string()
         );
         assert_eq!(
             "% f"
                 .parse::<CFormatSpec>()
                 .unwrap()
-                .format_float(f64::from(1.2345))
-                .ok(),
-            Some(" 1.234500".to_string())
+                .format_float(f64::from(1.2345)),
+            " 1.234500".to_string()
         );
         assert_eq!(
             "%f".parse::<CFormatSpec>()
                 .unwrap()
-                .format_float(f64::from(-1.2345))
-                .ok(),
-            Some("-1.234500".to_string())
+                .format_float(f64::from(-1.2345)),
+            "-1.234500".to_string()
         );
         assert_eq!(
             "%f".parse::<CFormatSpec>()
                 .unwrap()
-                .format_float(f64::from(1.2345678901))
-                .ok(),
-            Some("1.234568".to_string())
+                .format_float(f64::from(1.2345678901)),
+            "1.234568".to_string()
         );
     }
 
+    #[test]
+    fn test_parse_and_format_float_precision() {
+        let format = |spec: &str, num: f64| spec.parse::<CFormatSpec>().unwrap().format_float(num);
+        assert_eq!(format("%5.2f", 3.14159), " 3.14");
+        assert_eq!(format("%.0f", 2.7), "3");
+        assert_eq!(format("%#.0f", 3.0), "3.");
+        assert_eq!(format("%08.3f", -3.14159), "-003.142");
+        assert_eq!(format("%e", 1234.5), "1.234500e+03");
+        assert_eq!(format("%.2E", 0.000123), "1.23E-04");
+        assert_eq!(format("%g", 0.0001), "0.0001");
+        assert_eq!(format("%g", 0.00001), "1e-05");
+        assert_eq!(format("%g", 123456789.0), "1.23457e+08");
+        assert_eq!(format("%.3g", 2.0), "2");
+        assert_eq!(format("%#.3g", 2.0), "2.00");
+        assert_eq!(format("%G", 1e100), "1E+100");
+        assert_eq!(format("%05f", std::f64::INFINITY), "  inf");
+        assert_eq!(format("%F", std::f64::NAN), "NAN");
+    }
+
     #[test]
     fn test_format_parse() {
         let fmt = "Hello, my name is %s and I'm %d years old";
@@ -3,7 +3,6 @@ use crate::obj::objstr::PyString;
 use crate::pyhash;
 use crate::pyobject::{IdProtocol, IntoPyObject, PyObjectRef, PyResult};
 use crate::vm::VirtualMachine;
-use num_bigint::ToBigInt;
 /// Ordered dictionary implementation.
 /// Inspired by: https://morepypy.blogspot.com/2015/01/faster-more-memory-efficient-and-more.html
 /// And: https://www.youtube.com/watch?v=p33CVV29OG8
@@ -313,10 +312,9 @@ impl DictKey for &PyObjectRef {
 /// Implement trait for the str type, so that we can use strings
 /// to index dictionaries.
 impl DictKey for &str {
-    fn do_hash(self, _vm: &VirtualMachine) -> PyResult<HashValue> {
+    fn do_hash(self, vm: &VirtualMachine) -> PyResult<HashValue> {
         // follow a similar route as the hashing of PyStringRef
-        let raw_hash = pyhash::hash_value(&self.to_string()).to_bigint().unwrap();
-        let raw_hash = pyhash::hash_bigint(&raw_hash);
+        let raw_hash = vm.hash_secret.hash_str(self);
         let mut hasher = DefaultHasher::new();
         raw_hash.hash(&mut hasher);
         Ok(hasher.finish() as HashValue)
@@ -340,10 +338,9 @@ impl DictKey for &str {
 }
 
 impl DictKey for &String {
-    fn do_hash(self, _vm: &VirtualMachine) -> PyResult<HashValue> {
+    fn do_hash(self, vm: &VirtualMachine) -> PyResult<HashValue> {
         // follow a similar route as the hashing of PyStringRef
-        let raw_hash = pyhash::hash_value(self).to_bigint().unwrap();
-        let raw_hash = pyhash::hash_bigint(&raw_hash);
+        let raw_hash = vm.hash_secret.hash_str(self);
         let mut hasher = DefaultHasher::new();
         raw_hash.hash(&mut hasher);
         Ok(hasher.finish() as HashValue)
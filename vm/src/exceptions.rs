@@ -1,7 +1,7 @@
 use crate::function::PyFuncArgs;
-use crate::obj::objsequence;
+use crate::obj::objobject;
+use crate::obj::objtraceback::{PyTraceback, PyTracebackRef};
 use crate::obj::objtuple::{PyTuple, PyTupleRef};
-use crate::obj::objtype;
 use crate::obj::objtype::PyClassRef;
 use crate::pyobject::{IdProtocol, PyContext, PyObjectRef, PyResult, TypeProtocol};
 use crate::types::create_type;
@@ -10,13 +10,25 @@ use itertools::Itertools;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
+// The attributes are set up here as well as in `__init__`, so that an
+// exception whose `__init__` doesn't call the base one is still usable.
+fn exception_new(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
+    if args.args.is_empty() {
+        return Err(vm.new_type_error("BaseException.__new__(): not enough arguments".to_string()));
+    }
+    let mut init_args = args.args;
+    let exc = objobject::new_instance(vm, PyFuncArgs::from(vec![init_args[0].clone()]))?;
+    init_args[0] = exc.clone();
+    exception_init(vm, PyFuncArgs::from(init_args))?;
+    Ok(exc)
+}
+
 fn exception_init(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     let exc_self = args.args[0].clone();
     let exc_args = vm.ctx.new_tuple(args.args[1..].to_vec());
     vm.set_attr(&exc_self, "args", exc_args)?;
 
-    // TODO: have an actual `traceback` object for __traceback__
-    vm.set_attr(&exc_self, "__traceback__", vm.ctx.new_list(vec![]))?;
+    vm.set_attr(&exc_self, "__traceback__", vm.get_none())?;
     vm.set_attr(&exc_self, "__cause__", vm.get_none())?;
     vm.set_attr(&exc_self, "__context__", vm.get_none())?;
     vm.set_attr(&exc_self, "__suppress_context__", vm.new_bool(false))?;
@@ -67,49 +79,22 @@ fn print_source_line(filename: String, lineno: usize) {
 }
 
 /// Print exception occurrence location from traceback element
-fn print_traceback_entry(vm: &VirtualMachine, tb_entry: &PyObjectRef) {
-    if objtype::isinstance(&tb_entry, &vm.ctx.tuple_type()) {
-        let location_attrs = objsequence::get_elements_tuple(&tb_entry);
-        let filename = if let Ok(x) = vm.to_str(&location_attrs[0]) {
-            x.value.clone()
-        } else {
-            "<error>".to_string()
-        };
-
-        let lineno = if let Ok(x) = vm.to_str(&location_attrs[1]) {
-            x.value.clone()
-        } else {
-            "<error>".to_string()
-        };
-
-        let obj_name = if let Ok(x) = vm.to_str(&location_attrs[2]) {
-            x.value.clone()
-        } else {
-            "<error>".to_string()
-        };
-
-        println!(
-            r##"  File "{}", line {}, in {}"##,
-            filename, lineno, obj_name
-        );
-        print_source_line(filename, lineno.parse().unwrap());
-    } else {
-        println!("  File ??");
-    }
+fn print_traceback_entry(tb_entry: &PyTracebackRef) {
+    let code = &tb_entry.frame.code;
+    println!(
+        r##"  File "{}", line {}, in {}"##,
+        code.source_path, tb_entry.lineno, code.obj_name
+    );
+    print_source_line(code.source_path.clone(), tb_entry.lineno);
 }
 
 /// Print exception with traceback
 pub fn print_exception_inner(vm: &VirtualMachine, exc: &PyObjectRef) {
     if let Ok(tb) = vm.get_attribute(exc.clone(), "__traceback__") {
-        if objtype::isinstance(&tb, &vm.ctx.list_type()) {
-            let mut tb_entries = objsequence::get_elements_list(&tb).to_vec();
-            tb_entries.reverse();
-
-            if !tb_entries.is_empty() {
-                println!("Traceback (most recent call last):");
-            }
-            for exc_location in tb_entries.iter() {
-                print_traceback_entry(vm, exc_location);
+        if let Ok(tb) = tb.downcast::<PyTraceback>() {
+            println!("Traceback (most recent call last):");
+            for tb_entry in tb.iter() {
+                print_traceback_entry(&tb_entry);
             }
         }
     } else {
@@ -164,6 +149,28 @@ fn exception_args_as_string(
 }
 
 fn exception_str(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
+    exception_args_str(vm, args, true)
+}
+
+// KeyError shows a single argument (the missing key) with its repr.
+fn key_error_str(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
+    exception_args_str(vm, args, false)
+}
+
+fn exception_with_traceback(
+    exc: PyObjectRef,
+    tb: Option<PyTracebackRef>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let tb = match tb {
+        Some(tb) => tb.into_object(),
+        None => vm.get_none(),
+    };
+    vm.set_attr(&exc, "__traceback__", tb)?;
+    Ok(exc)
+}
+
+fn exception_args_str(vm: &VirtualMachine, args: PyFuncArgs, str_single: bool) -> PyResult {
     arg_check!(
         vm,
         args,
@@ -174,7 +181,7 @@ fn exception_str(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
         .unwrap()
         .downcast::<PyTuple>()
         .expect("'args' must be a tuple");
-    let args_str = exception_args_as_string(vm, args, false);
+    let args_str = exception_args_as_string(vm, args, str_single);
     let joined_str = match args_str.len() {
         0 => "".to_string(),
         1 => args_str.into_iter().next().unwrap(),
@@ -218,14 +225,17 @@ pub struct ExceptionZoo {
     pub index_error: PyClassRef,
     pub key_error: PyClassRef,
     pub lookup_error: PyClassRef,
+    pub memory_error: PyClassRef,
     pub module_not_found_error: PyClassRef,
     pub name_error: PyClassRef,
     pub not_implemented_error: PyClassRef,
     pub os_error: PyClassRef,
     pub overflow_error: PyClassRef,
     pub permission_error: PyClassRef,
+    pub recursion_error: PyClassRef,
     pub reference_error: PyClassRef,
     pub runtime_error: PyClassRef,
+    pub stop_async_iteration: PyClassRef,
     pub stop_iteration: PyClassRef,
     pub syntax_error: PyClassRef,
     pub system_error: PyClassRef,
@@ -251,6 +261,8 @@ pub struct ExceptionZoo {
     pub user_warning: PyClassRef,
 
     pub keyboard_interrupt: PyClassRef,
+    pub generator_exit: PyClassRef,
+    pub system_exit: PyClassRef,
 }
 
 impl ExceptionZoo {
@@ -265,10 +277,12 @@ impl ExceptionZoo {
         let index_error = create_type("IndexError", &type_type, &exception_type);
         let key_error = create_type("KeyError", &type_type, &exception_type);
         let lookup_error = create_type("LookupError", &type_type, &exception_type);
+        let memory_error = create_type("MemoryError", &type_type, &exception_type);
         let name_error = create_type("NameError", &type_type, &exception_type);
         let os_error = create_type("OSError", &type_type, &exception_type);
         let runtime_error = create_type("RuntimeError", &type_type, &exception_type);
         let reference_error = create_type("ReferenceError", &type_type, &exception_type);
+        let stop_async_iteration = create_type("StopAsyncIteration", &type_type, &exception_type);
         let stop_iteration = create_type("StopIteration", &type_type, &exception_type);
         let syntax_error = create_type("SyntaxError", &type_type, &exception_type);
         let system_error = create_type("SystemError", &type_type, &exception_type);
@@ -278,6 +292,7 @@ impl ExceptionZoo {
         let zero_division_error = create_type("ZeroDivisionError", &type_type, &arithmetic_error);
         let module_not_found_error = create_type("ModuleNotFoundError", &type_type, &import_error);
         let not_implemented_error = create_type("NotImplementedError", &type_type, &runtime_error);
+        let recursion_error = create_type("RecursionError", &type_type, &runtime_error);
         let file_not_found_error = create_type("FileNotFoundError", &type_type, &os_error);
         let permission_error = create_type("PermissionError", &type_type, &os_error);
         let file_exists_error = create_type("FileExistsError", &type_type, &os_error);
@@ -302,6 +317,8 @@ impl ExceptionZoo {
         let user_warning = create_type("UserWarning", &type_type, &warning);
 
         let keyboard_interrupt = create_type("KeyboardInterrupt", &type_type, &base_exception_type);
+        let generator_exit = create_type("GeneratorExit", &type_type, &base_exception_type);
+        let system_exit = create_type("SystemExit", &type_type, &base_exception_type);
 
         ExceptionZoo {
             arithmetic_error,
@@ -315,6 +332,7 @@ impl ExceptionZoo {
             index_error,
             key_error,
             lookup_error,
+            memory_error,
             module_not_found_error,
             name_error,
             not_implemented_error,
@@ -322,6 +340,7 @@ impl ExceptionZoo {
             overflow_error,
             permission_error,
             runtime_error,
+            stop_async_iteration,
             stop_iteration,
             syntax_error,
             system_error,
@@ -344,12 +363,40 @@ impl ExceptionZoo {
             resource_warning,
             runtime_warning,
             reference_error,
+            recursion_error,
             user_warning,
             keyboard_interrupt,
+            generator_exit,
+            system_exit,
         }
     }
 }
 
+fn stop_iteration_init(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
+    exception_init(vm, args.clone())?;
+
+    let exc_self = args.args[0].clone();
+    vm.set_attr(
+        &exc_self,
+        "value",
+        args.args.get(1).cloned().unwrap_or_else(|| vm.get_none()),
+    )?;
+    Ok(vm.get_none())
+}
+
+fn system_exit_init(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
+    exception_init(vm, args.clone())?;
+
+    let exc_self = args.args[0].clone();
+    let code = match args.args.len() {
+        1 => vm.get_none(),
+        2 => args.args[1].clone(),
+        _ => vm.ctx.new_tuple(args.args[1..].to_vec()),
+    };
+    vm.set_attr(&exc_self, "code", code)?;
+    Ok(vm.get_none())
+}
+
 fn import_error_init(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     // TODO: call super().__init__(*args) instead
     exception_init(vm, args.clone())?;
@@ -382,7 +429,9 @@ fn import_error_init(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
 pub fn init(context: &PyContext) {
     let base_exception_type = &context.exceptions.base_exception_type;
     extend_class!(context, base_exception_type, {
-        "__init__" => context.new_rustfunc(exception_init)
+        "__new__" => context.new_rustfunc(exception_new),
+        "__init__" => context.new_rustfunc(exception_init),
+        "with_traceback" => context.new_rustfunc(exception_with_traceback)
     });
 
     let exception_type = &context.exceptions.exception_type;
@@ -391,8 +440,23 @@ pub fn init(context: &PyContext) {
         "__repr__" => context.new_rustfunc(exception_repr),
     });
 
+    let stop_iteration_type = &context.exceptions.stop_iteration;
+    extend_class!(context, stop_iteration_type, {
+        "__init__" => context.new_rustfunc(stop_iteration_init)
+    });
+
+    let system_exit_type = &context.exceptions.system_exit;
+    extend_class!(context, system_exit_type, {
+        "__init__" => context.new_rustfunc(system_exit_init)
+    });
+
     let import_error_type = &context.exceptions.import_error;
     extend_class!(context, import_error_type, {
         "__init__" => context.new_rustfunc(import_error_init)
     });
+
+    let key_error_type = &context.exceptions.key_error;
+    extend_class!(context, key_error_type, {
+        "__str__" => context.new_rustfunc(key_error_str)
+    });
 }
@@ -1,3 +1,5 @@
+use crate::cformat::{self, CFormatCase};
+use crate::obj::objfloat;
 use num_bigint::{BigInt, Sign};
 use num_traits::Signed;
 use std::cmp;
@@ -95,6 +97,7 @@ pub enum FormatType {
     GeneralFormatUpper,
     FixedPointLower,
     FixedPointUpper,
+    Percentage,
 }
 
 #[derive(Debug, PartialEq)]
@@ -229,11 +232,12 @@ fn parse_format_type(text: &str) -> (Option<FormatType>, &str) {
         Some('g') => (Some(FormatType::GeneralFormatLower), chars.as_str()),
         Some('G') => (Some(FormatType::GeneralFormatUpper), chars.as_str()),
         Some('n') => (Some(FormatType::Number), chars.as_str()),
+        Some('%') => (Some(FormatType::Percentage), chars.as_str()),
         _ => (None, text),
     }
 }
 
-fn parse_format_spec(text: &str) -> FormatSpec {
+fn parse_format_spec(text: &str) -> Result<FormatSpec, &'static str> {
     let (preconversor, after_preconversor) = parse_preconversor(text);
     let (fill, align, after_align) = parse_fill_and_align(after_preconversor);
     let (sign, after_sign) = parse_sign(after_align);
@@ -242,9 +246,12 @@ fn parse_format_spec(text: &str) -> FormatSpec {
     let (width, after_width) = parse_number(after_zero);
     let (grouping_option, after_grouping_option) = parse_grouping_option(after_width);
     let (precision, after_precision) = parse_precision(after_grouping_option);
-    let (format_type, _) = parse_format_type(after_precision);
+    let (format_type, after_format_type) = parse_format_type(after_precision);
+    if !after_format_type.is_empty() {
+        return Err("Invalid format specifier");
+    }
 
-    FormatSpec {
+    Ok(FormatSpec {
         preconversor,
         fill,
         align,
@@ -254,11 +261,11 @@ fn parse_format_spec(text: &str) -> FormatSpec {
         grouping_option,
         precision,
         format_type,
-    }
+    })
 }
 
 impl FormatSpec {
-    pub fn parse(text: &str) -> FormatSpec {
+    pub fn parse(text: &str) -> Result<FormatSpec, &'static str> {
         parse_format_spec(text)
     }
 
@@ -279,11 +286,12 @@ impl FormatSpec {
             remaining = interval;
         }
         for c in magnitude_string.chars() {
-            result.push(c);
             if remaining == 0 {
                 result.push(separator);
                 remaining = interval;
             }
+            result.push(c);
+            remaining -= 1;
         }
         result
     }
@@ -296,6 +304,13 @@ impl FormatSpec {
             Some(FormatType::HexLower) => 4,
             Some(FormatType::HexUpper) => 4,
             Some(FormatType::Number) => 3,
+            Some(FormatType::ExponentLower)
+            | Some(FormatType::ExponentUpper)
+            | Some(FormatType::GeneralFormatLower)
+            | Some(FormatType::GeneralFormatUpper)
+            | Some(FormatType::FixedPointLower)
+            | Some(FormatType::FixedPointUpper)
+            | Some(FormatType::Percentage) => 3,
             None => 3,
             _ => panic!("Separators only valid for numbers!"),
         }
@@ -318,7 +333,6 @@ impl FormatSpec {
     }
 
     pub fn format_int(&self, num: &BigInt) -> Result<String, &'static str> {
-        let fill_char = self.fill.unwrap_or(' ');
         let magnitude = num.abs();
         let prefix = if self.alternate_form {
             match self.format_type {
@@ -362,6 +376,7 @@ impl FormatSpec {
             Some(FormatType::FixedPointLower) => {
                 Err("Unknown format code 'f' for object of type 'int'")
             }
+            Some(FormatType::Percentage) => Err("Unknown format code '%' for object of type 'int'"),
             None => Ok(magnitude.to_str_radix(10)),
         };
         if raw_magnitude_string_result.is_err() {
@@ -372,10 +387,6 @@ impl FormatSpec {
             prefix,
             self.add_magnitude_separators(raw_magnitude_string_result.unwrap())
         );
-        let align = self.align.unwrap_or(FormatAlign::Right);
-
-        // Use the byte length as the string length since we're in ascii
-        let num_chars = magnitude_string.len();
 
         let format_sign = self.sign.unwrap_or(FormatSign::Minus);
         let sign_str = match num.sign() {
@@ -387,10 +398,141 @@ impl FormatSpec {
             },
         };
 
+        Ok(self.format_sign_and_align(magnitude_string, sign_str, FormatAlign::Right))
+    }
+
+    pub fn format_float(&self, num: f64) -> Result<String, &'static str> {
+        let precision = self.precision.unwrap_or(6);
+        let magnitude = num.abs();
+        let raw_magnitude_string_result: Result<String, &'static str> = match self.format_type {
+            _ if num.is_nan() => Ok("nan".to_string()),
+            _ if num.is_infinite() => Ok("inf".to_string()),
+            Some(FormatType::FixedPointUpper) | Some(FormatType::FixedPointLower) => Ok(
+                cformat::format_fixed(magnitude, precision, self.alternate_form),
+            ),
+            Some(FormatType::Percentage) => Ok(cformat::format_fixed(
+                magnitude * 100.0,
+                precision,
+                self.alternate_form,
+            )),
+            Some(FormatType::ExponentLower) => Ok(cformat::format_exponent(
+                magnitude,
+                precision,
+                &CFormatCase::Lowercase,
+                self.alternate_form,
+            )),
+            Some(FormatType::ExponentUpper) => Ok(cformat::format_exponent(
+                magnitude,
+                precision,
+                &CFormatCase::Uppercase,
+                self.alternate_form,
+            )),
+            Some(FormatType::GeneralFormatLower) => Ok(cformat::format_general(
+                magnitude,
+                precision,
+                &CFormatCase::Lowercase,
+                self.alternate_form,
+            )),
+            Some(FormatType::GeneralFormatUpper) => Ok(cformat::format_general(
+                magnitude,
+                precision,
+                &CFormatCase::Uppercase,
+                self.alternate_form,
+            )),
+            // Without a type, a precision works like 'g' but always keeps a
+            // digit after the point; without a precision the result is repr().
+            None => match self.precision {
+                Some(precision) => {
+                    let result = cformat::format_general(
+                        magnitude,
+                        precision,
+                        &CFormatCase::Lowercase,
+                        self.alternate_form,
+                    );
+                    if result.contains(|c| c == '.' || c == 'e') {
+                        Ok(result)
+                    } else {
+                        Ok(format!("{}.0", result))
+                    }
+                }
+                None => Ok(objfloat::float_repr(magnitude)),
+            },
+            Some(FormatType::String) => Err("Unknown format code 's' for object of type 'float'"),
+            Some(FormatType::Binary) => Err("Unknown format code 'b' for object of type 'float'"),
+            Some(FormatType::Character) => {
+                Err("Unknown format code 'c' for object of type 'float'")
+            }
+            Some(FormatType::Decimal) => Err("Unknown format code 'd' for object of type 'float'"),
+            Some(FormatType::Octal) => Err("Unknown format code 'o' for object of type 'float'"),
+            Some(FormatType::HexLower) => Err("Unknown format code 'x' for object of type 'float'"),
+            Some(FormatType::HexUpper) => Err("Unknown format code 'X' for object of type 'float'"),
+            Some(FormatType::Number) => {
+                Err("Format code 'n' for object of type 'float' not implemented yet")
+            }
+        };
+        let mut magnitude_string = raw_magnitude_string_result?;
+        match self.format_type {
+            Some(FormatType::FixedPointUpper)
+            | Some(FormatType::ExponentUpper)
+            | Some(FormatType::GeneralFormatUpper) => magnitude_string.make_ascii_uppercase(),
+            Some(FormatType::Percentage) => magnitude_string.push('%'),
+            _ => {}
+        }
+        // Only the digits before the point or exponent are grouped.
+        let integer_len = get_num_digits(&magnitude_string);
+        let fraction = magnitude_string.split_off(integer_len);
+        let magnitude_string = self.add_magnitude_separators(magnitude_string) + &fraction;
+
+        let format_sign = self.sign.unwrap_or(FormatSign::Minus);
+        let sign_str = if num.is_sign_negative() && !num.is_nan() {
+            "-"
+        } else {
+            match format_sign {
+                FormatSign::Plus => "+",
+                FormatSign::Minus => "",
+                FormatSign::MinusOrSpace => " ",
+            }
+        };
+
+        Ok(self.format_sign_and_align(magnitude_string, sign_str, FormatAlign::Right))
+    }
+
+    pub fn format_string(&self, s: &str) -> Result<String, &'static str> {
+        match self.format_type {
+            Some(FormatType::String) | None => {}
+            _ => return Err("Unknown format code for object of type 'str'"),
+        }
+        if self.sign.is_some() {
+            return Err("Sign not allowed in string format specifier");
+        }
+        if self.alternate_form {
+            return Err("Alternate form (#) not allowed in string format specifier");
+        }
+        if self.align == Some(FormatAlign::AfterSign) {
+            return Err("'=' alignment not allowed in string format specifier");
+        }
+        let value = match self.precision {
+            Some(precision) => s.chars().take(precision).collect(),
+            None => s.to_string(),
+        };
+        Ok(self.format_sign_and_align(value, "", FormatAlign::Left))
+    }
+
+    fn format_sign_and_align(
+        &self,
+        magnitude_string: String,
+        sign_str: &str,
+        default_align: FormatAlign,
+    ) -> String {
+        let fill_char = self.fill.unwrap_or(' ');
+        let align = self.align.unwrap_or(default_align);
+
+        let num_chars = magnitude_string.chars().count();
+
         let fill_chars_needed: i32 = self.width.map_or(0, |w| {
             cmp::max(0, (w as i32) - (num_chars as i32) - (sign_str.len() as i32))
         });
-        Ok(match align {
+        match align {
             FormatAlign::Left => format!(
                 "{}{}{}",
                 sign_str,
@@ -421,7 +563,7 @@ impl FormatSpec {
                     left_fill_string, sign_str, magnitude_string, right_fill_string
                 )
             }
-        })
+        }
     }
 }
 
@@ -430,39 +572,100 @@ pub enum FormatParseError {
     UnmatchedBracket,
     MissingStartBracket,
     UnescapedStartBracketInLiteral,
+    EmptyAttribute,
+    MissingRightBracket,
+    InvalidCharacterAfterRightBracket,
+    InvalidConversionSpecifier,
 }
 
 impl FromStr for FormatSpec {
     type Err = &'static str;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(FormatSpec::parse(s))
+        FormatSpec::parse(s)
     }
 }
 
+/// The first component of a replacement field name, which picks the argument.
 #[derive(Debug, PartialEq)]
-pub enum FormatPart {
-    AutoSpec(String),
-    IndexSpec(usize, String),
-    KeywordSpec(String, String),
-    Literal(String),
+pub enum FieldType {
+    Auto,
+    Index(usize),
+    Keyword(String),
 }
 
-impl FormatPart {
-    pub fn is_auto(&self) -> bool {
-        match self {
-            FormatPart::AutoSpec(_) => true,
-            _ => false,
-        }
-    }
+/// A `.attr` or `[key]` lookup following the first component of a field name.
+#[derive(Debug, PartialEq)]
+pub enum FieldNamePart {
+    Attribute(String),
+    Index(usize),
+    StringIndex(String),
+}
 
-    pub fn is_index(&self) -> bool {
-        match self {
-            FormatPart::IndexSpec(_, _) => true,
-            _ => false,
+#[derive(Debug, PartialEq)]
+pub struct FieldName {
+    pub field_type: FieldType,
+    pub parts: Vec<FieldNamePart>,
+}
+
+impl FieldName {
+    pub fn parse(text: &str) -> Result<FieldName, FormatParseError> {
+        let first_end = text.find(&['.', '['][..]).unwrap_or(text.len());
+        let (first, mut rest) = text.split_at(first_end);
+
+        let field_type = if first.is_empty() {
+            FieldType::Auto
+        } else if let Ok(index) = first.parse::<usize>() {
+            FieldType::Index(index)
+        } else {
+            FieldType::Keyword(first.to_string())
+        };
+
+        let mut parts = Vec::new();
+        while !rest.is_empty() {
+            if rest.starts_with('.') {
+                let name_end = rest[1..]
+                    .find(&['.', '['][..])
+                    .map_or(rest.len(), |pos| pos + 1);
+                let name = &rest[1..name_end];
+                if name.is_empty() {
+                    return Err(FormatParseError::EmptyAttribute);
+                }
+                parts.push(FieldNamePart::Attribute(name.to_string()));
+                rest = &rest[name_end..];
+            } else {
+                // the loop only ever sees a '.' or a '[' here
+                let key_end = rest
+                    .find(']')
+                    .ok_or(FormatParseError::MissingRightBracket)?;
+                let key = &rest[1..key_end];
+                if key.is_empty() {
+                    return Err(FormatParseError::EmptyAttribute);
+                }
+                parts.push(match key.parse::<usize>() {
+                    Ok(index) => FieldNamePart::Index(index),
+                    Err(_) => FieldNamePart::StringIndex(key.to_string()),
+                });
+                rest = &rest[key_end + 1..];
+                if !rest.is_empty() && !rest.starts_with('.') && !rest.starts_with('[') {
+                    return Err(FormatParseError::InvalidCharacterAfterRightBracket);
+                }
+            }
         }
+
+        Ok(FieldName { field_type, parts })
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum FormatPart {
+    Field {
+        field_name: String,
+        preconversion_spec: Option<char>,
+        format_spec: String,
+    },
+    Literal(String),
+}
+
 #[derive(Debug, PartialEq)]
 pub struct FormatString {
     pub format_parts: Vec<FormatPart>,
@@ -507,37 +710,50 @@ impl FormatString {
     }
 
     fn parse_part_in_brackets(text: &str) -> Result<FormatPart, FormatParseError> {
-        let parts: Vec<&str> = text.splitn(2, ':').collect();
-        // before the comma is a keyword or arg index, after the comma is maybe a spec.
-        let arg_part = parts[0];
+        // the field name ends at the first '!' or ':' that isn't inside an index
+        let mut in_index = false;
+        let name_end = text
+            .find(|c| match c {
+                '[' => {
+                    in_index = true;
+                    false
+                }
+                ']' => {
+                    in_index = false;
+                    false
+                }
+                '!' | ':' => !in_index,
+                _ => false,
+            })
+            .unwrap_or(text.len());
+        let (arg_part, rest) = text.split_at(name_end);
 
-        let format_spec = if parts.len() > 1 {
-            parts[1].to_string()
-        } else {
-            String::new()
+        // after the field name is maybe a preconversor (!r, !s, !a) and then a spec.
+        let (preconversor_part, format_spec) = match rest.chars().next() {
+            Some('!') => {
+                let mut parts = rest[1..].splitn(2, ':');
+                (parts.next(), parts.next().unwrap_or(""))
+            }
+            Some(':') => (None, &rest[1..]),
+            _ => (None, ""),
         };
 
-        // On parts[0] can still be the preconversor (!r, !s, !a)
-        let parts: Vec<&str> = arg_part.splitn(2, '!').collect();
-        // before the bang is a keyword or arg index, after the comma is maybe a conversor spec.
-        let arg_part = parts[0];
-
-        let preconversor_spec = if parts.len() > 1 {
-            "!".to_string() + parts[1]
-        } else {
-            String::new()
+        let preconversion_spec = match preconversor_part {
+            Some(part) => {
+                let mut chars = part.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => return Err(FormatParseError::InvalidConversionSpecifier),
+                }
+            }
+            None => None,
         };
-        let format_spec = preconversor_spec + &format_spec;
 
-        if arg_part.is_empty() {
-            return Ok(FormatPart::AutoSpec(format_spec));
-        }
-
-        if let Ok(index) = arg_part.parse::<usize>() {
-            Ok(FormatPart::IndexSpec(index, format_spec))
-        } else {
-            Ok(FormatPart::KeywordSpec(arg_part.to_string(), format_spec))
-        }
+        Ok(FormatPart::Field {
+            field_name: arg_part.to_string(),
+            preconversion_spec,
+            format_spec: format_spec.to_string(),
+        })
     }
 
     fn parse_spec(text: &str) -> Result<(FormatPart, &str), FormatParseError> {
@@ -548,8 +764,22 @@ impl FormatString {
 
         // Get remaining characters after opening bracket.
         let cur_text = chars.as_str();
-        // Find the matching bracket and parse the text within for a spec
-        match cur_text.find('}') {
+        // Find the matching bracket and parse the text within for a spec,
+        // skipping over any nested replacement fields in the format spec
+        let mut nested = 0;
+        let closing = cur_text.find(|c| match c {
+            '{' => {
+                nested += 1;
+                false
+            }
+            '}' if nested > 0 => {
+                nested -= 1;
+                false
+            }
+            '}' => true,
+            _ => false,
+        });
+        match closing {
             Some(position) => {
                 let (left, right) = cur_text.split_at(position);
                 let format_part = FormatString::parse_part_in_brackets(left)?;
@@ -623,7 +853,7 @@ mod tests {
             precision: None,
             format_type: None,
         };
-        assert_eq!(parse_format_spec("33"), expected);
+        assert_eq!(parse_format_spec("33"), Ok(expected));
     }
 
     #[test]
@@ -639,7 +869,7 @@ mod tests {
             precision: None,
             format_type: None,
         };
-        assert_eq!(parse_format_spec("<>33"), expected);
+        assert_eq!(parse_format_spec("<>33"), Ok(expected));
     }
 
     #[test]
@@ -655,37 +885,51 @@ mod tests {
             precision: Some(11),
             format_type: Some(FormatType::Binary),
         };
-        assert_eq!(parse_format_spec("<>-#23,.11b"), expected);
+        assert_eq!(parse_format_spec("<>-#23,.11b"), Ok(expected));
     }
 
     #[test]
     fn test_format_int() {
         assert_eq!(
-            parse_format_spec("d").format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
+            parse_format_spec("d")
+                .unwrap()
+                .format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
             Ok("16".to_string())
         );
         assert_eq!(
-            parse_format_spec("x").format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
+            parse_format_spec("x")
+                .unwrap()
+                .format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
             Ok("10".to_string())
         );
         assert_eq!(
-            parse_format_spec("b").format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
+            parse_format_spec("b")
+                .unwrap()
+                .format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
             Ok("10000".to_string())
         );
         assert_eq!(
-            parse_format_spec("o").format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
+            parse_format_spec("o")
+                .unwrap()
+                .format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
             Ok("20".to_string())
         );
         assert_eq!(
-            parse_format_spec("+d").format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
+            parse_format_spec("+d")
+                .unwrap()
+                .format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
             Ok("+16".to_string())
         );
         assert_eq!(
-            parse_format_spec("^ 5d").format_int(&BigInt::from_bytes_be(Sign::Minus, b"\x10")),
+            parse_format_spec("^ 5d")
+                .unwrap()
+                .format_int(&BigInt::from_bytes_be(Sign::Minus, b"\x10")),
             Ok(" -16 ".to_string())
         );
         assert_eq!(
-            parse_format_spec("0>+#10x").format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
+            parse_format_spec("0>+#10x")
+                .unwrap()
+                .format_int(&BigInt::from_bytes_be(Sign::Plus, b"\x10")),
             Ok("00000+0x10".to_string())
         );
     }
@@ -695,9 +939,17 @@ mod tests {
         let expected = Ok(FormatString {
             format_parts: vec![
                 FormatPart::Literal("abcd".to_string()),
-                FormatPart::IndexSpec(1, String::new()),
+                FormatPart::Field {
+                    field_name: "1".to_string(),
+                    preconversion_spec: None,
+                    format_spec: String::new(),
+                },
                 FormatPart::Literal(":".to_string()),
-                FormatPart::KeywordSpec("key".to_string(), String::new()),
+                FormatPart::Field {
+                    field_name: "key".to_string(),
+                    preconversion_spec: None,
+                    format_spec: String::new(),
+                },
             ],
         });
 
@@ -717,11 +969,66 @@ mod tests {
         let expected = Ok(FormatString {
             format_parts: vec![
                 FormatPart::Literal("{".to_string()),
-                FormatPart::KeywordSpec("key".to_string(), String::new()),
+                FormatPart::Field {
+                    field_name: "key".to_string(),
+                    preconversion_spec: None,
+                    format_spec: String::new(),
+                },
                 FormatPart::Literal("}ddfe".to_string()),
             ],
         });
 
         assert_eq!(FormatString::from_str("{{{key}}}ddfe"), expected);
     }
+
+    #[test]
+    fn test_format_parse_nested() {
+        let expected = Ok(FormatString {
+            format_parts: vec![FormatPart::Field {
+                field_name: "0".to_string(),
+                preconversion_spec: Some('r'),
+                format_spec: ">{width}".to_string(),
+            }],
+        });
+
+        assert_eq!(FormatString::from_str("{0!r:>{width}}"), expected);
+        assert_eq!(
+            FormatString::from_str("{0!rr}"),
+            Err(FormatParseError::InvalidConversionSpecifier)
+        );
+    }
+
+    #[test]
+    fn test_parse_field_name() {
+        assert_eq!(
+            FieldName::parse(""),
+            Ok(FieldName {
+                field_type: FieldType::Auto,
+                parts: Vec::new(),
+            })
+        );
+        assert_eq!(
+            FieldName::parse("0.__class__[name][1]"),
+            Ok(FieldName {
+                field_type: FieldType::Index(0),
+                parts: vec![
+                    FieldNamePart::Attribute("__class__".to_string()),
+                    FieldNamePart::StringIndex("name".to_string()),
+                    FieldNamePart::Index(1),
+                ],
+            })
+        );
+        assert_eq!(
+            FieldName::parse("key."),
+            Err(FormatParseError::EmptyAttribute)
+        );
+        assert_eq!(
+            FieldName::parse("key[0"),
+            Err(FormatParseError::MissingRightBracket)
+        );
+        assert_eq!(
+            FieldName::parse("key[0]x"),
+            Err(FormatParseError::InvalidCharacterAfterRightBracket)
+        );
+    }
 }
@@ -5,13 +5,17 @@ use crate::builtins;
 use crate::bytecode;
 use crate::function::PyFuncArgs;
 use crate::obj::objbool;
+use crate::obj::objbuiltinfunc::PyBuiltinFunction;
 use crate::obj::objcode::PyCodeRef;
+use crate::obj::objcoroutine::PyCoroutine;
 use crate::obj::objdict::{PyDict, PyDictRef};
+use crate::obj::objfunction::PyMethod;
 use crate::obj::objiter;
 use crate::obj::objlist;
 use crate::obj::objslice::PySlice;
 use crate::obj::objstr;
 use crate::obj::objstr::PyString;
+use crate::obj::objtraceback::PyTraceback;
 use crate::obj::objtuple::PyTuple;
 use crate::obj::objtype;
 use crate::obj::objtype::PyClassRef;
@@ -19,7 +23,8 @@ use crate::pyobject::{
     IdProtocol, ItemProtocol, PyObjectRef, PyRef, PyResult, PyValue, TryFromObject, TypeProtocol,
 };
 use crate::scope::{NameProtocol, Scope};
-use crate::vm::VirtualMachine;
+use crate::stdlib::tracemalloc;
+use crate::vm::{TraceEvent, VirtualMachine};
 use indexmap::IndexMap;
 use itertools::Itertools;
 
@@ -80,7 +85,7 @@ enum UnwindReason {
 }
 
 pub struct Frame {
-    pub code: bytecode::CodeObject,
+    pub code: PyCodeRef,
     // We need 1 stack per frame
     stack: RefCell<Vec<PyObjectRef>>, // The main data frame of the stack machine
     blocks: RefCell<Vec<Block>>,      // Block frames, for controlling loops and exceptions
@@ -117,7 +122,7 @@ impl Frame {
         // locals.extend(callargs);
 
         Frame {
-            code: code.code.clone(),
+            code,
             stack: RefCell::new(vec![]),
             blocks: RefCell::new(vec![]),
             // save the callargs as locals
@@ -131,14 +136,25 @@ impl Frame {
     pub fn run(&self, vm: &VirtualMachine) -> PyResult<ExecutionResult> {
         flame_guard!(format!("Frame::run({})", self.code.obj_name));
 
-        let filename = &self.code.source_path.to_string();
-
-        // This is the name of the object being run:
-        let run_obj_name = &self.code.obj_name.to_string();
+        vm.trace_event(TraceEvent::Call, vm.get_none())?;
+        let result = self.execute_frame(vm);
+        // Like CPython, a frame left by an exception reports None.
+        let value = match &result {
+            Ok(ExecutionResult::Return(value)) | Ok(ExecutionResult::Yield(value)) => value.clone(),
+            Err(_) => vm.get_none(),
+        };
+        vm.trace_event(TraceEvent::Return, value)?;
+        result
+    }
 
+    fn execute_frame(&self, vm: &VirtualMachine) -> PyResult<ExecutionResult> {
         // Execute until return or exception:
         loop {
+            let lasti = *self.lasti.borrow();
             let lineno = self.get_lineno();
+            if tracemalloc::is_tracing() {
+                tracemalloc::set_location(&self.code.source_path, lineno.row());
+            }
             let result = self.execute_instruction(vm);
             match result {
                 Ok(None) => {}
@@ -148,22 +164,24 @@ impl Frame {
                 // Instruction raised an exception
                 Err(exception) => {
                     // 1. Extract traceback from exception's '__traceback__' attr.
-                    // 2. Add new entry with current execution position (filename, lineno, code_object) to traceback.
+                    // 2. Prepend an entry for this frame and its current line to the traceback.
                     // 3. Unwind block stack till appropriate handler is found.
                     assert!(objtype::isinstance(
                         &exception,
                         &vm.ctx.exceptions.base_exception_type
                     ));
-                    let traceback = vm
-                        .get_attribute(exception.clone(), "__traceback__")
-                        .unwrap();
-                    vm_trace!("Adding to traceback: {:?} {:?}", traceback, lineno);
-                    let raise_location = vm.ctx.new_tuple(vec![
-                        vm.ctx.new_str(filename.clone()),
-                        vm.ctx.new_int(lineno.row()),
-                        vm.ctx.new_str(run_obj_name.clone()),
-                    ]);
-                    objlist::PyListRef::try_from_object(vm, traceback)?.append(raise_location, vm);
+                    let next = vm
+                        .get_attribute(exception.clone(), "__traceback__")?
+                        .downcast::<PyTraceback>()
+                        .ok();
+                    let frame = vm.current_frame().expect("frame is running").clone();
+                    // An exception re-raised from a handler in this frame
+                    // already has an entry for it.
+                    if !next.as_ref().map_or(false, |tb| tb.frame.is(&frame)) {
+                        vm_trace!("Adding to traceback: {:?} {:?}", next, lineno);
+                        let traceback = PyTraceback::new(next, frame, lasti, lineno.row());
+                        vm.set_attr(&exception, "__traceback__", traceback.into_ref(vm))?;
+                    }
                     match self.unwind_blocks(vm, UnwindReason::Raising { exception }) {
                         Ok(None) => {}
                         Ok(Some(result)) => {
@@ -285,9 +303,11 @@ impl Frame {
                 self.push_value(list_obj);
                 Ok(None)
             }
-            bytecode::Instruction::BuildMap { size, unpack } => {
-                self.execute_build_map(vm, *size, *unpack)
-            }
+            bytecode::Instruction::BuildMap {
+                size,
+                unpack,
+                for_call,
+            } => self.execute_build_map(vm, *size, *unpack, *for_call),
             bytecode::Instruction::BuildSlice { size } => self.execute_build_slice(vm, *size),
             bytecode::Instruction::ListAppend { i } => {
                 let list_obj = self.nth_value(*i);
@@ -303,8 +323,8 @@ impl Frame {
             }
             bytecode::Instruction::MapAdd { i } => {
                 let dict_obj = self.nth_value(*i + 1);
-                let key = self.pop_value();
                 let value = self.pop_value();
+                let key = self.pop_value();
                 vm.call_method(&dict_obj, "__setitem__", vec![key, value])?;
                 Ok(None)
             }
@@ -325,6 +345,29 @@ impl Frame {
                 Ok(Some(ExecutionResult::Yield(value)))
             }
             bytecode::Instruction::YieldFrom => self.execute_yield_from(vm),
+            bytecode::Instruction::GetAwaitable => {
+                let awaited_obj = self.pop_value();
+                let awaitable = if awaited_obj.payload_is::<PyCoroutine>() {
+                    awaited_obj
+                } else {
+                    let await_method =
+                        vm.get_method_or_type_error(awaited_obj.clone(), "__await__", || {
+                            format!(
+                                "object {} can't be used in 'await' expression",
+                                awaited_obj.class().name,
+                            )
+                        })?;
+                    let iter = vm.invoke(&await_method, vec![])?;
+                    if iter.payload_is::<PyCoroutine>() {
+                        return Err(
+                            vm.new_type_error("__await__() returned a coroutine".to_string())
+                        );
+                    }
+                    iter
+                };
+                self.push_value(awaitable);
+                Ok(None)
+            }
             bytecode::Instruction::SetupLoop { start, end } => {
                 self.push_block(BlockType::Loop {
                     start: *start,
@@ -351,6 +394,9 @@ impl Frame {
 
                 if let BlockType::FinallyHandler { reason } = block.typ {
                     if let Some(reason) = reason {
+                        if let UnwindReason::Raising { .. } = reason {
+                            vm.pop_exception().expect("Should have exception in stack");
+                        }
                         self.unwind_blocks(vm, reason)
                     } else {
                         Ok(None)
@@ -387,6 +433,53 @@ impl Frame {
 
                 Ok(None)
             }
+            bytecode::Instruction::BeforeAsyncWith => {
+                let context_manager = self.pop_value();
+                let aexit = vm.get_attribute(context_manager.clone(), "__aexit__")?;
+                let awaitable = vm.call_method(&context_manager, "__aenter__", vec![])?;
+                self.push_value(aexit);
+                self.push_value(awaitable);
+                Ok(None)
+            }
+            bytecode::Instruction::SetupAsyncWith { end } => {
+                let enter_res = self.pop_value();
+                self.push_block(BlockType::Finally { handler: *end });
+                self.push_value(enter_res);
+                Ok(None)
+            }
+            bytecode::Instruction::WithCleanupStart => {
+                let block = self.current_block().unwrap();
+                let reason = match block.typ {
+                    BlockType::FinallyHandler { reason } => reason,
+                    _ => panic!("Block type must be FinallyHandler here."),
+                };
+                let aexit = self.pop_value();
+                let args = match reason {
+                    Some(UnwindReason::Raising { exception }) => {
+                        vec![exception.class().into_object(), exception, vm.get_none()]
+                    }
+                    _ => vec![vm.get_none(), vm.get_none(), vm.get_none()],
+                };
+                let exit_res = vm.invoke(&aexit, args)?;
+                self.push_value(exit_res);
+                Ok(None)
+            }
+            bytecode::Instruction::WithCleanupFinish => {
+                let suppress_exception = objbool::boolval(vm, self.pop_value())?;
+                let block = self.pop_block();
+                if let BlockType::FinallyHandler {
+                    reason: Some(UnwindReason::Raising { .. }),
+                } = block.typ
+                {
+                    if suppress_exception {
+                        vm.pop_exception().expect("Should have exception in stack");
+                        self.push_block(BlockType::FinallyHandler { reason: None });
+                        return Ok(None);
+                    }
+                }
+                self.push_block(block.typ);
+                Ok(None)
+            }
             bytecode::Instruction::PopBlock => {
                 self.pop_block();
                 Ok(None)
@@ -397,6 +490,32 @@ impl Frame {
                 self.push_value(iter_obj);
                 Ok(None)
             }
+            bytecode::Instruction::GetAIter => {
+                let aiterable = self.pop_value();
+                let aiter_method =
+                    vm.get_method_or_type_error(aiterable.clone(), "__aiter__", || {
+                        format!(
+                            "'async for' requires an object with __aiter__ method, got {}",
+                            aiterable.class().name
+                        )
+                    })?;
+                let aiter = vm.invoke(&aiter_method, vec![])?;
+                self.push_value(aiter);
+                Ok(None)
+            }
+            bytecode::Instruction::GetANext => {
+                let aiter = self.last_value();
+                let anext_method =
+                    vm.get_method_or_type_error(aiter.clone(), "__anext__", || {
+                        format!(
+                            "'async for' requires an iterator with __anext__ method, got {}",
+                            aiter.class().name
+                        )
+                    })?;
+                let awaitable = vm.invoke(&anext_method, vec![])?;
+                self.push_value(awaitable);
+                Ok(None)
+            }
             bytecode::Instruction::ForIter { target } => self.execute_for_iter(vm, *target),
             bytecode::Instruction::MakeFunction { flags } => self.execute_make_function(vm, *flags),
             bytecode::Instruction::CallFunction { typ } => self.execute_call_function(vm, typ),
@@ -463,6 +582,13 @@ impl Frame {
                 self.push_value(vm.ctx.new_rustfunc(builtins::builtin_build_class_));
                 Ok(None)
             }
+            bytecode::Instruction::SetupAnnotations => {
+                let locals = self.scope.get_locals();
+                if !locals.contains_key("__annotations__", vm) {
+                    locals.set_item("__annotations__", vm.ctx.new_dict().into_object(), vm)?;
+                }
+                Ok(None)
+            }
             bytecode::Instruction::UnpackSequence { size } => {
                 let value = self.pop_value();
                 let elements = vm.extract_elements(&value)?;
@@ -479,8 +605,9 @@ impl Frame {
                 self.execute_unpack_ex(vm, *before, *after)
             }
             bytecode::Instruction::Unpack => self.execute_unpack(vm),
-            bytecode::Instruction::FormatValue { conversion, spec } => {
+            bytecode::Instruction::FormatValue { conversion } => {
                 use bytecode::ConversionFlag::*;
+                let spec = self.pop_value();
                 let value = match conversion {
                     Some(Str) => vm.to_str(&self.pop_value())?.into_object(),
                     Some(Repr) => vm.to_repr(&self.pop_value())?.into_object(),
@@ -488,7 +615,6 @@ impl Frame {
                     None => self.pop_value(),
                 };
 
-                let spec = vm.new_str(spec.clone());
                 let formatted = vm.call_method(&value, "__format__", vec![spec])?;
                 self.push_value(formatted);
                 Ok(None)
@@ -508,6 +634,32 @@ impl Frame {
                 stack[stack_len - amount..stack_len].reverse();
                 Ok(None)
             }
+            bytecode::Instruction::MatchSequence => {
+                let is_sequence = self.is_sequence_subject(vm, &self.last_value())?;
+                self.push_value(vm.new_bool(is_sequence));
+                Ok(None)
+            }
+            bytecode::Instruction::MatchMapping => {
+                let is_mapping = self.is_mapping_subject(vm, &self.last_value())?;
+                self.push_value(vm.new_bool(is_mapping));
+                Ok(None)
+            }
+            bytecode::Instruction::GetLen => {
+                let len = builtins::builtin_len(self.last_value(), vm)?;
+                self.push_value(vm.new_int(len));
+                Ok(None)
+            }
+            bytecode::Instruction::MatchKeys => self.execute_match_keys(vm),
+            bytecode::Instruction::CopyDictWithoutKeys => {
+                let keys = self.pop_value();
+                let rest = vm.invoke(vm.ctx.dict_type().as_object(), vec![self.last_value()])?;
+                for key in vm.extract_elements(&keys)? {
+                    vm.call_method(&rest, "__delitem__", vec![key])?;
+                }
+                self.push_value(rest);
+                Ok(None)
+            }
+            bytecode::Instruction::MatchClass { count } => self.execute_match_class(vm, *count),
         }
     }
 
@@ -598,6 +750,10 @@ impl Frame {
                 },
                 BlockType::Finally { handler } => {
                     self.pop_block();
+                    // the exception being propagated is visible in the finally clause
+                    if let UnwindReason::Raising { exception } = &reason {
+                        vm.push_exception(exception.clone());
+                    }
                     self.push_block(BlockType::FinallyHandler {
                         reason: Some(reason.clone()),
                     });
@@ -668,8 +824,11 @@ impl Frame {
                         }
                     }
                 }
-                BlockType::FinallyHandler { .. } => {
+                BlockType::FinallyHandler { reason } => {
                     self.pop_block();
+                    if let Some(UnwindReason::Raising { .. }) = reason {
+                        vm.pop_exception().expect("Should have exception in stack");
+                    }
                 }
                 BlockType::ExceptHandler => {
                     self.pop_block();
@@ -699,7 +858,7 @@ impl Frame {
         let (exc_type, exc_val, exc_tb) = if let Some(exc) = exc {
             let exc_type = exc.class().into_object();
             let exc_val = exc.clone();
-            let exc_tb = vm.ctx.none(); // TODO: retrieve traceback?
+            let exc_tb = vm.get_attribute(exc.clone(), "__traceback__")?;
             (exc_type, exc_val, exc_tb)
         } else {
             (vm.ctx.none(), vm.ctx.none(), vm.ctx.none())
@@ -811,13 +970,43 @@ impl Frame {
         Ok(None)
     }
 
-    fn execute_build_map(&self, vm: &VirtualMachine, size: usize, unpack: bool) -> FrameResult {
+    fn execute_build_map(
+        &self,
+        vm: &VirtualMachine,
+        size: usize,
+        unpack: bool,
+        for_call: bool,
+    ) -> FrameResult {
         let map_obj = vm.ctx.new_dict();
         if unpack {
             for obj in self.pop_multiple(size) {
-                // Take all key-value pairs from the dict:
-                let dict: PyDictRef = obj.downcast().expect("Need a dictionary to build a map.");
+                // Take all key-value pairs from the mapping:
+                let dict: PyDictRef = match obj.downcast() {
+                    Ok(dict) => dict,
+                    Err(obj) => {
+                        if !objtype::class_has_attr(&obj.class(), "keys") {
+                            let msg = if for_call {
+                                format!(
+                                    "argument after ** must be a mapping, not {}",
+                                    obj.class().name
+                                )
+                            } else {
+                                format!("'{}' object is not a mapping", obj.class().name)
+                            };
+                            return Err(vm.new_type_error(msg));
+                        }
+                        let dict_type = vm.ctx.dict_type().into_object();
+                        vm.invoke(&dict_type, vec![obj])?.downcast().unwrap()
+                    }
+                };
                 for (key, value) in dict {
+                    if for_call && map_obj.contains_key(&key, vm) {
+                        let key_repr = vm.to_repr(&key)?;
+                        return Err(vm.new_type_error(format!(
+                            "got multiple values for keyword argument {}",
+                            key_repr
+                        )));
+                    }
                     map_obj.set_item(&key, value, vm).unwrap();
                 }
             }
@@ -876,10 +1065,14 @@ impl Frame {
                 let kwargs = if *has_kwargs {
                     let kw_dict: PyDictRef =
                         self.pop_value().downcast().expect("Kwargs must be a dict.");
-                    kw_dict
-                        .into_iter()
-                        .map(|elem| (objstr::get_value(&elem.0), elem.1))
-                        .collect()
+                    let mut kwargs = IndexMap::new();
+                    for (key, value) in kw_dict {
+                        if !objtype::isinstance(&key, &vm.ctx.str_type()) {
+                            return Err(vm.new_type_error("keywords must be strings".to_string()));
+                        }
+                        kwargs.insert(objstr::get_value(&key), value);
+                    }
+                    kwargs
                 } else {
                     IndexMap::new()
                 };
@@ -891,7 +1084,19 @@ impl Frame {
 
         // Call function:
         let func_ref = self.pop_value();
-        let value = vm.invoke(&func_ref, args)?;
+        let value = if *vm.use_tracing.borrow() && is_builtin_function(&func_ref) {
+            vm.trace_event(TraceEvent::CCall, func_ref.clone())?;
+            let result = vm.invoke(&func_ref, args);
+            let event = if result.is_ok() {
+                TraceEvent::CReturn
+            } else {
+                TraceEvent::CException
+            };
+            vm.trace_event(event, func_ref)?;
+            result?
+        } else {
+            vm.invoke(&func_ref, args)?
+        };
         self.push_value(value);
         Ok(None)
     }
@@ -933,18 +1138,33 @@ impl Frame {
 
     fn execute_yield_from(&self, vm: &VirtualMachine) -> FrameResult {
         // Value send into iterator:
-        self.pop_value();
+        let value = self.pop_value();
 
         let top_of_stack = self.last_value();
-        let next_obj = objiter::get_next_object(vm, &top_of_stack)?;
+        // coroutines have no __next__, so they are always driven by send()
+        let result = if vm.is_none(&value) && !top_of_stack.payload_is::<PyCoroutine>() {
+            objiter::call_next(vm, &top_of_stack)
+        } else {
+            vm.call_method(&top_of_stack, "send", vec![value])
+        };
 
-        match next_obj {
-            Some(value) => {
+        match result {
+            Ok(value) => {
                 // Set back program counter:
                 *self.lasti.borrow_mut() -= 1;
                 Ok(Some(ExecutionResult::Yield(value)))
             }
-            None => Ok(None),
+            Err(err) => {
+                if objtype::isinstance(&err, &vm.ctx.exceptions.stop_iteration) {
+                    // The sub-iterator is done, its return value is the result
+                    self.pop_value();
+                    let value = vm.get_attribute(err, "value")?;
+                    self.push_value(value);
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            }
         }
     }
 
@@ -988,6 +1208,188 @@ impl Frame {
         Ok(None)
     }
 
+    /// Whether `isinstance(obj, collections.abc.<name>)`, which is what
+    /// decides about the types that aren't builtin.
+    fn is_abc_instance(
+        &self,
+        vm: &VirtualMachine,
+        obj: &PyObjectRef,
+        name: &str,
+    ) -> PyResult<bool> {
+        let module = match vm.import("_collections_abc", &[], 0) {
+            Ok(module) => module,
+            Err(_) => return Ok(false),
+        };
+        let abc = PyClassRef::try_from_object(vm, vm.get_attribute(module, name)?)?;
+        vm.isinstance(obj, &abc)
+    }
+
+    fn is_sequence_subject(&self, vm: &VirtualMachine, subject: &PyObjectRef) -> PyResult<bool> {
+        for cls in &[
+            vm.ctx.str_type(),
+            vm.ctx.bytes_type(),
+            vm.ctx.bytearray_type(),
+            vm.ctx.dict_type(),
+        ] {
+            if objtype::isinstance(subject, cls) {
+                return Ok(false);
+            }
+        }
+        for cls in &[
+            vm.ctx.list_type(),
+            vm.ctx.tuple_type(),
+            vm.ctx.range_type(),
+            vm.ctx.memoryview_type(),
+        ] {
+            if objtype::isinstance(subject, cls) {
+                return Ok(true);
+            }
+        }
+        self.is_abc_instance(vm, subject, "Sequence")
+    }
+
+    fn is_mapping_subject(&self, vm: &VirtualMachine, subject: &PyObjectRef) -> PyResult<bool> {
+        if objtype::isinstance(subject, &vm.ctx.dict_type()) {
+            return Ok(true);
+        }
+        self.is_abc_instance(vm, subject, "Mapping")
+    }
+
+    fn execute_match_keys(&self, vm: &VirtualMachine) -> FrameResult {
+        let keys = self.last_value();
+        let subject = self.nth_value(1);
+        let keys = vm.extract_elements(&keys)?;
+        for (i, key) in keys.iter().enumerate() {
+            for other in &keys[..i] {
+                if objbool::boolval(vm, vm._eq(key.clone(), other.clone())?)? {
+                    return Err(vm.new_value_error(format!(
+                        "mapping pattern checks duplicate key ({})",
+                        vm.to_repr(key)?
+                    )));
+                }
+            }
+        }
+
+        // Use get() with a default, so that e.g. a defaultdict doesn't grow.
+        let get = vm.get_attribute(subject, "get")?;
+        let missing = vm.ctx.new_instance(vm.ctx.object(), None);
+        let mut values = vec![];
+        for key in keys {
+            let value = vm.invoke(&get, vec![key, missing.clone()])?;
+            if value.is(&missing) {
+                self.push_value(vm.get_none());
+                return Ok(None);
+            }
+            values.push(value);
+        }
+        self.push_value(vm.ctx.new_tuple(values));
+        Ok(None)
+    }
+
+    fn execute_match_class(&self, vm: &VirtualMachine, count: usize) -> FrameResult {
+        let kwd_attrs = self.pop_value();
+        let cls = self.pop_value();
+        let subject = self.pop_value();
+        let cls = PyClassRef::try_from_object(vm, cls)
+            .map_err(|_| vm.new_type_error("called match pattern must be a type".to_string()))?;
+        if !vm.isinstance(&subject, &cls)? {
+            self.push_value(vm.get_none());
+            return Ok(None);
+        }
+
+        let mut attrs = vec![];
+        let mut names = vec![];
+        if count > 0 {
+            match objtype::class_get_attr(&cls, "__match_args__") {
+                Some(match_args) => {
+                    let match_args = match_args.downcast::<PyTuple>().map_err(|obj| {
+                        vm.new_type_error(format!(
+                            "{}.__match_args__ must be a tuple (got {})",
+                            cls.name,
+                            obj.class().name
+                        ))
+                    })?;
+                    if count > match_args.elements.len() {
+                        return Err(vm.new_type_error(format!(
+                            "{}() accepts {} positional sub-pattern{} ({} given)",
+                            cls.name,
+                            match_args.elements.len(),
+                            if match_args.elements.len() == 1 {
+                                ""
+                            } else {
+                                "s"
+                            },
+                            count
+                        )));
+                    }
+                    for name in &match_args.elements[..count] {
+                        let name = name.clone().downcast::<PyString>().map_err(|obj| {
+                            vm.new_type_error(format!(
+                                "__match_args__ elements must be strings (got {})",
+                                obj.class().name
+                            ))
+                        })?;
+                        names.push(name.as_str().to_string());
+                    }
+                }
+                None => {
+                    // Instances of these builtins match their single
+                    // positional sub-pattern themselves, like `int(x)`.
+                    let match_self = [
+                        vm.ctx.bool_type(),
+                        vm.ctx.bytearray_type(),
+                        vm.ctx.bytes_type(),
+                        vm.ctx.dict_type(),
+                        vm.ctx.float_type(),
+                        vm.ctx.frozenset_type(),
+                        vm.ctx.int_type(),
+                        vm.ctx.list_type(),
+                        vm.ctx.set_type(),
+                        vm.ctx.str_type(),
+                        vm.ctx.tuple_type(),
+                    ]
+                    .iter()
+                    .any(|builtin| objtype::issubclass(&cls, builtin));
+                    let allowed = if match_self { 1 } else { 0 };
+                    if count > allowed {
+                        return Err(vm.new_type_error(format!(
+                            "{}() accepts {} positional sub-pattern{} ({} given)",
+                            cls.name,
+                            allowed,
+                            if allowed == 1 { "" } else { "s" },
+                            count
+                        )));
+                    }
+                    attrs.push(subject.clone());
+                }
+            }
+        }
+        for name in vm.extract_elements(&kwd_attrs)? {
+            names.push(objstr::get_value(&name));
+        }
+
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                return Err(vm.new_type_error(format!(
+                    "{}() got multiple sub-patterns for attribute '{}'",
+                    cls.name, name
+                )));
+            }
+            match vm.get_attribute(subject.clone(), name.as_str()) {
+                Ok(attr) => attrs.push(attr),
+                Err(err) => {
+                    if objtype::isinstance(&err, &vm.ctx.exceptions.attribute_error) {
+                        self.push_value(vm.get_none());
+                        return Ok(None);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        self.push_value(vm.ctx.new_tuple(attrs));
+        Ok(None)
+    }
+
     fn jump(&self, label: bytecode::Label) {
         let target_pc = self.code.label_map[&label];
         #[cfg(feature = "vm-tracing-logging")]
@@ -1218,7 +1620,9 @@ impl Frame {
     }
 
     pub fn get_lineno(&self) -> bytecode::Location {
-        self.code.locations[*self.lasti.borrow()].clone()
+        // A frame that has returned points past its last instruction.
+        let lasti = (*self.lasti.borrow()).min(self.code.locations.len() - 1);
+        self.code.locations[lasti].clone()
     }
 
     fn push_block(&self, typ: BlockType) {
@@ -1292,6 +1696,15 @@ impl Frame {
     }
 }
 
+/// Whether calling `func` runs native code, which the profiler reports as
+/// `c_call` rather than through a frame of its own.
+fn is_builtin_function(func: &PyObjectRef) -> bool {
+    match func.payload::<PyMethod>() {
+        Some(method) => method.function.payload_is::<PyBuiltinFunction>(),
+        None => func.payload_is::<PyBuiltinFunction>(),
+    }
+}
+
 impl fmt::Debug for Frame {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let stack_str = self
@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::mem;
 use std::ops::RangeInclusive;
 
@@ -245,7 +244,7 @@ pub trait FromArgs: Sized {
 /// KwArgs is only for functions that accept arbitrary keyword arguments. For
 /// functions that accept only *specific* named arguments, a rust struct with
 /// an appropriate FromArgs implementation must be created.
-pub struct KwArgs<T = PyObjectRef>(HashMap<String, T>);
+pub struct KwArgs<T = PyObjectRef>(IndexMap<String, T>);
 
 impl<T> KwArgs<T> {
     pub fn pop_kwarg(&mut self, name: &str) -> Option<T> {
@@ -258,7 +257,7 @@ where
     T: TryFromObject,
 {
     fn from_args(vm: &VirtualMachine, args: &mut PyFuncArgs) -> Result<Self, ArgumentError> {
-        let mut kwargs = HashMap::new();
+        let mut kwargs = IndexMap::new();
         for (name, value) in args.remaining_keywords() {
             kwargs.insert(name, T::try_from_object(vm, value)?);
         }
@@ -268,7 +267,7 @@ where
 
 impl<T> IntoIterator for KwArgs<T> {
     type Item = (String, T);
-    type IntoIter = std::collections::hash_map::IntoIter<String, T>;
+    type IntoIter = indexmap::map::IntoIter<String, T>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
@@ -292,6 +291,12 @@ impl<T> Args<T> {
     }
 }
 
+impl<T> From<Vec<T>> for Args<T> {
+    fn from(v: Vec<T>) -> Self {
+        Args(v)
+    }
+}
+
 impl<T: PyValue> Args<PyRef<T>> {
     pub fn into_tuple(self, vm: &VirtualMachine) -> PyObjectRef {
         vm.ctx
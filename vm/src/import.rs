@@ -1,10 +1,15 @@
 /*
  * Import mechanics
  */
+use std::cell::Cell;
+use std::path::Path;
+
 use rand::Rng;
 
 use crate::bytecode::CodeObject;
-use crate::obj::{objcode, objsequence, objstr, objtype};
+use crate::obj::objstr::PyStringRef;
+use crate::obj::objtraceback::PyTraceback;
+use crate::obj::{objcode, objtype};
 use crate::pyobject::{ItemProtocol, PyObjectRef, PyResult, PyValue};
 use crate::scope::Scope;
 use crate::version::get_git_revision;
@@ -33,10 +38,40 @@ pub fn init_importlib(vm: &VirtualMachine, external: bool) -> PyResult {
             magic = rand::thread_rng().gen::<[u8; 4]>().to_vec();
         }
         vm.set_attr(&importlib_external, "MAGIC_NUMBER", vm.ctx.new_bytes(magic))?;
+
+        let path_hooks = vm.get_attribute(vm.sys_module.clone(), "path_hooks")?;
+        let zip_hook = vm.ctx.new_rustfunc(zipimport_path_hook);
+        vm.call_method(&path_hooks, "insert", vec![vm.new_int(0), zip_hook])?;
     }
     Ok(vm.get_none())
 }
 
+thread_local! {
+    static IMPORTING_ZIPIMPORT: Cell<bool> = Cell::new(false);
+}
+
+/// The `sys.path_hooks` entry for zip archives, which hands any path inside an
+/// existing file over to `zipimport.zipimporter`.
+///
+/// `zipimport` itself is only imported the first time such a path shows up. While it
+/// is being imported the hook declines every path, since finding `zipimport` walks
+/// `sys.path` and would otherwise end up back here.
+fn zipimport_path_hook(path: PyStringRef, vm: &VirtualMachine) -> PyResult {
+    let in_file = Path::new(path.as_str())
+        .ancestors()
+        .any(|ancestor| ancestor.is_file());
+    if !in_file || IMPORTING_ZIPIMPORT.with(Cell::get) {
+        return Err(vm.new_import_error("not a Zip file".to_string()));
+    }
+
+    IMPORTING_ZIPIMPORT.with(|importing| importing.set(true));
+    let zipimport = vm.import("zipimport", &[], 0);
+    IMPORTING_ZIPIMPORT.with(|importing| importing.set(false));
+
+    let zipimporter = vm.get_attribute(zipimport?, "zipimporter")?;
+    vm.invoke(&zipimporter, vec![path.into_object()])
+}
+
 pub fn import_frozen(vm: &VirtualMachine, module_name: &str) -> PyResult {
     vm.frozen
         .borrow()
@@ -92,11 +127,14 @@ pub fn import_codeobj(
     let sys_modules = vm.get_attribute(vm.sys_module.clone(), "modules")?;
     sys_modules.set_item(module_name, module.clone(), vm)?;
 
-    // Execute main code in module:
-    vm.run_code_obj(
+    // Execute main code in module, forgetting the partial module if that fails:
+    if let Err(err) = vm.run_code_obj(
         objcode::PyCode::new(code_obj).into_ref(vm),
         Scope::with_builtins(None, attrs, vm),
-    )?;
+    ) {
+        let _ = sys_modules.del_item(module_name, vm);
+        return Err(err);
+    }
     Ok(module)
 }
 
@@ -105,18 +143,16 @@ pub fn remove_importlib_frames(vm: &VirtualMachine, exc: &PyObjectRef) -> PyObje
     let always_trim = objtype::isinstance(exc, &vm.ctx.exceptions.import_error);
 
     if let Ok(tb) = vm.get_attribute(exc.clone(), "__traceback__") {
-        if objtype::isinstance(&tb, &vm.ctx.list_type()) {
-            let tb_entries = objsequence::get_elements_list(&tb).to_vec();
+        if let Ok(tb) = tb.downcast::<PyTraceback>() {
             let mut in_importlib = false;
-            let new_tb = tb_entries
+            let kept: Vec<_> = tb
                 .iter()
                 .filter(|tb_entry| {
-                    let location_attrs = objsequence::get_elements_tuple(&tb_entry);
-                    let file_name = objstr::get_value(&location_attrs[0]);
+                    let code = &tb_entry.frame.code;
+                    let file_name = &code.source_path;
                     if file_name == "_frozen_importlib" || file_name == "_frozen_importlib_external"
                     {
-                        let run_obj_name = objstr::get_value(&location_attrs[2]);
-                        if run_obj_name == "_call_with_frames_removed" {
+                        if code.obj_name == "_call_with_frames_removed" {
                             in_importlib = true;
                         }
                         !always_trim && !in_importlib
@@ -125,10 +161,21 @@ pub fn remove_importlib_frames(vm: &VirtualMachine, exc: &PyObjectRef) -> PyObje
                         true
                     }
                 })
-                .cloned()
                 .collect();
-            vm.set_attr(exc, "__traceback__", vm.ctx.new_list(new_tb))
-                .unwrap();
+            let new_tb = kept.into_iter().rev().fold(None, |next, tb_entry| {
+                let entry = PyTraceback::new(
+                    next,
+                    tb_entry.frame.clone(),
+                    tb_entry.lasti,
+                    tb_entry.lineno,
+                );
+                Some(entry.into_ref(vm))
+            });
+            let new_tb = match new_tb {
+                Some(tb) => tb.into_object(),
+                None => vm.get_none(),
+            };
+            vm.set_attr(exc, "__traceback__", new_tb).unwrap();
         }
     }
     exc.clone()
@@ -120,7 +120,9 @@ macro_rules! py_module {
         let module = $vm.new_module($module_name, $vm.ctx.new_dict());
         $vm.set_attr(&module, "__name__", $vm.ctx.new_str($module_name.to_string())).unwrap();
         $(
-            $vm.set_attr(&module, $name, $value).unwrap();
+            let value: $crate::pyobject::PyObjectRef = $value.into();
+            $crate::obj::objbuiltinfunc::set_default_name(&value, $name);
+            $vm.set_attr(&module, $name, value).unwrap();
         )*
         module
     }};
@@ -130,7 +132,9 @@ macro_rules! py_module {
 macro_rules! extend_module {
     ( $vm:expr, $module:expr, { $($name:expr => $value:expr),* $(,)* }) => {
         $(
-            $vm.set_attr(&$module, $name, $value).unwrap();
+            let value: $crate::pyobject::PyObjectRef = $value.into();
+            $crate::obj::objbuiltinfunc::set_default_name(&value, $name);
+            $vm.set_attr(&$module, $name, value).unwrap();
         )*
     }
 }
@@ -8,6 +8,8 @@ pub mod objbytes;
 pub mod objclassmethod;
 pub mod objcode;
 pub mod objcomplex;
+pub mod objcoroinner;
+pub mod objcoroutine;
 pub mod objdict;
 pub mod objellipsis;
 pub mod objenumerate;
@@ -16,6 +18,7 @@ pub mod objfloat;
 pub mod objframe;
 pub mod objfunction;
 pub mod objgenerator;
+pub mod objgenericalias;
 pub mod objint;
 pub mod objiter;
 pub mod objlist;
@@ -35,6 +38,7 @@ pub mod objstaticmethod;
 pub mod objstr;
 pub mod objsuper;
 pub mod objtuple;
+pub mod objtraceback;
 pub mod objtype;
 pub mod objweakproxy;
 pub mod objweakref;
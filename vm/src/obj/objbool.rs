@@ -1,5 +1,6 @@
 use num_traits::Zero;
 
+use crate::builtins;
 use crate::function::PyFuncArgs;
 use crate::pyobject::{
     IntoPyObject, PyContext, PyObjectRef, PyResult, TryFromObject, TypeProtocol,
@@ -29,32 +30,19 @@ pub fn boolval(vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<bool> {
             // If descriptor returns Error, propagate it further
             let method = method_or_err?;
             let bool_obj = vm.invoke(&method, PyFuncArgs::default())?;
-            match bool_obj.payload::<PyInt>() {
-                Some(int_obj) => !int_obj.as_bigint().is_zero(),
-                None => {
-                    return Err(vm.new_type_error(format!(
-                        "__bool__ should return bool, returned type {}",
-                        bool_obj.class().name
-                    )))
-                }
+            if !objtype::isinstance(&bool_obj, &vm.ctx.bool_type()) {
+                return Err(vm.new_type_error(format!(
+                    "__bool__ should return bool, returned {}",
+                    bool_obj.class().name
+                )));
             }
+            get_value(&bool_obj)
         }
-        None => match vm.get_method(obj.clone(), "__len__") {
-            Some(method_or_err) => {
-                let method = method_or_err?;
-                let bool_obj = vm.invoke(&method, PyFuncArgs::default())?;
-                match bool_obj.payload::<PyInt>() {
-                    Some(int_obj) => !int_obj.as_bigint().is_zero(),
-                    None => {
-                        return Err(vm.new_type_error(format!(
-                            "{} object cannot be interpreted as integer",
-                            bool_obj.class().name
-                        )))
-                    }
-                }
-            }
-            None => true,
-        },
+        // fall back to the length, so that empty containers are falsy
+        None if objtype::class_has_attr(&obj.class(), "__len__") => {
+            builtins::builtin_len(obj, vm)? != 0
+        }
+        None => true,
     };
     Ok(rs_bool)
 }
@@ -1,15 +1,21 @@
+use std::cell::RefCell;
 use std::fmt;
 
 use crate::function::PyNativeFunc;
 use crate::obj::objtype::PyClassRef;
-use crate::pyobject::PyValue;
+use crate::pyobject::{PyObjectRef, PyRef, PyValue};
 use crate::vm::VirtualMachine;
 
 pub struct PyBuiltinFunction {
     // TODO: shouldn't be public
     pub value: PyNativeFunc,
+    /// Builtins are created anonymously and take the name they are first
+    /// stored under on a module or class.
+    name: RefCell<Option<String>>,
 }
 
+pub type PyBuiltinFunctionRef = PyRef<PyBuiltinFunction>;
+
 impl PyValue for PyBuiltinFunction {
     fn class(vm: &VirtualMachine) -> PyClassRef {
         vm.ctx.builtin_function_or_method_type()
@@ -24,6 +30,23 @@ impl fmt::Debug for PyBuiltinFunction {
 
 impl PyBuiltinFunction {
     pub fn new(value: PyNativeFunc) -> Self {
-        Self { value }
+        Self {
+            value,
+            name: RefCell::new(None),
+        }
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.borrow().clone()
+    }
+}
+
+/// Give `obj` the name `name` if it is a builtin function that has none yet.
+pub fn set_default_name(obj: &PyObjectRef, name: &str) {
+    if let Some(function) = obj.payload::<PyBuiltinFunction>() {
+        let mut function_name = function.name.borrow_mut();
+        if function_name.is_none() {
+            *function_name = Some(name.to_string());
+        }
     }
 }
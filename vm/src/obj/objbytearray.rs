@@ -7,6 +7,7 @@ use crate::obj::objbyteinner::{
     ByteInnerTranslateOptions, ByteOr, PyByteInner,
 };
 use crate::obj::objint::PyIntRef;
+use crate::obj::objsequence::SequenceIndex;
 use crate::obj::objslice::PySliceRef;
 use crate::obj::objstr::PyStringRef;
 use crate::obj::objtuple::PyTupleRef;
@@ -162,7 +163,7 @@ impl PyByteArrayRef {
     }
 
     #[pymethod(name = "__getitem__")]
-    fn getitem(self, needle: Either<PyIntRef, PySliceRef>, vm: &VirtualMachine) -> PyResult {
+    fn getitem(self, needle: SequenceIndex, vm: &VirtualMachine) -> PyResult {
         self.inner.borrow().getitem(needle, vm)
     }
 
@@ -379,6 +380,20 @@ impl PyByteArrayRef {
         ))
     }
 
+    #[pymethod(name = "removeprefix")]
+    fn removeprefix(self, prefix: PyByteInner, vm: &VirtualMachine) -> PyResult {
+        Ok(vm
+            .ctx
+            .new_bytearray(self.inner.borrow().removeprefix(prefix)))
+    }
+
+    #[pymethod(name = "removesuffix")]
+    fn removesuffix(self, suffix: PyByteInner, vm: &VirtualMachine) -> PyResult {
+        Ok(vm
+            .ctx
+            .new_bytearray(self.inner.borrow().removesuffix(suffix)))
+    }
+
     #[pymethod(name = "split")]
     fn split(self, options: ByteInnerSplitOptions, vm: &VirtualMachine) -> PyResult {
         let as_bytes = self
@@ -540,12 +555,13 @@ impl PyByteArrayRef {
     }
 
     #[pymethod(name = "__mul__")]
-    fn repeat(self, n: PyIntRef, vm: &VirtualMachine) -> PyResult {
+    fn repeat(self, n: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let n = vm.to_index(&n)?;
         Ok(vm.ctx.new_bytearray(self.inner.borrow().repeat(n, vm)?))
     }
 
     #[pymethod(name = "__rmul__")]
-    fn rmul(self, n: PyIntRef, vm: &VirtualMachine) -> PyResult {
+    fn rmul(self, n: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         self.repeat(n, vm)
     }
 
@@ -554,6 +570,17 @@ impl PyByteArrayRef {
         self.inner.borrow_mut().irepeat(n, vm)
     }
 
+    #[pymethod(name = "__mod__")]
+    fn modulo(self, values: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let formatted = self.inner.borrow().cformat(values, vm)?;
+        Ok(vm.ctx.new_bytearray(formatted))
+    }
+
+    #[pymethod(name = "__rmod__")]
+    fn rmod(self, _values: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        Ok(vm.ctx.not_implemented())
+    }
+
     #[pymethod(name = "reverse")]
     fn reverse(self, _vm: &VirtualMachine) -> PyResult<()> {
         self.inner.borrow_mut().elements.reverse();
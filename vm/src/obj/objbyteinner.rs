@@ -11,14 +11,20 @@ use crate::pyobject::{PyIterable, PyObjectRef};
 use core::convert::TryFrom;
 use core::ops::Range;
 use num_bigint::BigInt;
+use std::str::FromStr;
 
+use crate::cformat::{
+    CFormatPreconversor, CFormatQuantity, CFormatSpec, CFormatString, CFormatType,
+};
+
+use crate::builtins::to_ascii;
 use crate::function::OptionalArg;
 use crate::pyobject::{PyResult, TypeProtocol};
 use crate::vm::VirtualMachine;
 
 use super::objint;
-use super::objsequence::{is_valid_slice_arg, PySliceableSequence};
-use super::objstr::{PyString, PyStringRef};
+use super::objsequence::{is_valid_slice_arg, PySliceableSequence, SequenceIndex};
+use super::objstr::{self, do_cformat, do_cformat_specifier, PyString, PyStringRef};
 
 use crate::obj::objint::PyInt;
 use num_integer::Integer;
@@ -27,6 +33,7 @@ use num_traits::ToPrimitive;
 use super::objbytearray::PyByteArray;
 use super::objbytes::PyBytes;
 use super::objmemory::PyMemoryView;
+use crate::stdlib::array::PyArray;
 
 use super::objsequence;
 
@@ -42,6 +49,7 @@ impl TryFromObject for PyByteInner {
             i @ PyBytes => Ok(PyByteInner{elements: i.get_value().to_vec()}),
             j @ PyByteArray => Ok(PyByteInner{elements: j.inner.borrow().elements.to_vec()}),
             k @ PyMemoryView => Ok(PyByteInner{elements: k.get_obj_value().unwrap()}),
+            a @ PyArray => Ok(PyByteInner{elements: a.get_bytes()}),
             obj => Err(vm.new_type_error(format!(
                         "a bytes-like object is required, not {}",
                         obj.class()
@@ -94,6 +102,27 @@ pub fn normalize_encoding(encoding: &str) -> String {
 }
 
 impl ByteInnerNewOptions {
+    /// Like `get_value`, but a lone argument may convert itself with `__bytes__`.
+    pub fn get_bytes_value(self, vm: &VirtualMachine) -> PyResult<PyByteInner> {
+        if let (OptionalArg::Present(obj), OptionalArg::Missing) =
+            (&self.val_option, &self.encoding)
+        {
+            if let Some(method) = vm.get_method(obj.clone(), "__bytes__") {
+                let bytes = vm.invoke(&method?, vec![])?;
+                return match bytes.payload::<PyBytes>() {
+                    Some(bytes) => Ok(PyByteInner {
+                        elements: bytes.get_value().to_vec(),
+                    }),
+                    None => Err(vm.new_type_error(format!(
+                        "__bytes__ returned non-bytes (type {})",
+                        bytes.class().name
+                    ))),
+                };
+            }
+        }
+        self.get_value(vm)
+    }
+
     pub fn get_value(self, vm: &VirtualMachine) -> PyResult<PyByteInner> {
         // First handle bytes(string, encoding[, errors])
         if let OptionalArg::Present(enc) = self.encoding {
@@ -116,11 +145,15 @@ impl ByteInnerNewOptions {
                             Ok(vec![0; size])},
                     _l @ PyString=> {return Err(vm.new_type_error("string argument without an encoding".to_string()));},
                     obj => {
-                        let elements = vm.extract_elements(&obj).or_else(|_| {Err(vm.new_type_error(format!(
-                        "cannot convert {} object to bytes", obj.class().name)))});
+                        // Buffers are copied as they are, not item by item.
+                        if let Some(bytes) = try_as_byte(&obj) {
+                            return Ok(PyByteInner { elements: bytes });
+                        }
+                        let elements = vm.extract_elements(&obj).map_err(|_| vm.new_type_error(format!(
+                        "cannot convert {} object to bytes", obj.class().name)))?;
 
                         let mut data_bytes = vec![];
-                        for elem in elements.unwrap(){
+                        for elem in elements {
                             let v = objint::to_int(vm, &elem, 10)?;
                             if let Some(i) = v.to_u8() {
                                 data_bytes.push(i);
@@ -321,16 +354,148 @@ impl ByteInnerSplitlinesOptions {
 
 impl PyByteInner {
     pub fn from_string(value: &str, encoding: &str, vm: &VirtualMachine) -> PyResult<Self> {
-        let normalized = normalize_encoding(encoding);
-        if normalized == "utf_8" || normalized == "utf8" || normalized == "u8" {
-            Ok(PyByteInner {
-                elements: value.as_bytes().to_vec(),
-            })
-        } else {
-            // TODO: different encoding
-            Err(
-                vm.new_value_error(format!("unknown encoding: {}", encoding)), // should be lookup error
-            )
+        PyByteInner::from_string_with_errors(value, encoding, "strict", vm)
+    }
+
+    /// Encode a string, dealing with characters the encoding can't represent
+    /// as the `errors` handler says.
+    pub fn from_string_with_errors(
+        value: &str,
+        encoding: &str,
+        errors: &str,
+        vm: &VirtualMachine,
+    ) -> PyResult<Self> {
+        let (name, limit) = match normalize_encoding(encoding).as_str() {
+            "utf_8" | "utf8" | "u8" => {
+                return Ok(PyByteInner {
+                    elements: value.as_bytes().to_vec(),
+                });
+            }
+            "ascii" | "us_ascii" => ("ascii", 0x80),
+            "latin_1" | "latin1" | "iso_8859_1" | "iso8859_1" | "l1" => ("latin-1", 0x100),
+            _ => {
+                // TODO: different encoding
+                return Err(vm.new_lookup_error(format!("unknown encoding: {}", encoding)));
+            }
+        };
+        let mut elements = Vec::with_capacity(value.len());
+        for (position, c) in value.chars().enumerate() {
+            let code = c as u32;
+            if code < limit {
+                elements.push(c as u8);
+                continue;
+            }
+            match errors {
+                "strict" => {
+                    return Err(vm.new_exception(
+                        vm.ctx.exceptions.unicode_encode_error.clone(),
+                        format!(
+                            "'{}' codec can't encode character '{}' in position {}: ordinal not in range({})",
+                            name,
+                            to_ascii(&c.to_string()),
+                            position,
+                            limit
+                        ),
+                    ));
+                }
+                "ignore" => {}
+                "replace" => elements.push(b'?'),
+                "backslashreplace" => {
+                    let escaped = if code < 0x100 {
+                        format!("\\x{:02x}", code)
+                    } else if code < 0x10000 {
+                        format!("\\u{:04x}", code)
+                    } else {
+                        format!("\\U{:08x}", code)
+                    };
+                    elements.extend(escaped.bytes());
+                }
+                "xmlcharrefreplace" => elements.extend(format!("&#{};", code).bytes()),
+                _ => {
+                    return Err(
+                        vm.new_lookup_error(format!("unknown error handler name '{}'", errors))
+                    );
+                }
+            }
+        }
+        Ok(PyByteInner { elements })
+    }
+
+    /// Decode the bytes, dealing with bytes that aren't valid in the encoding
+    /// as the `errors` handler says.
+    pub fn to_string_with_errors(
+        &self,
+        encoding: &str,
+        errors: &str,
+        vm: &VirtualMachine,
+    ) -> PyResult<String> {
+        let name = match normalize_encoding(encoding).as_str() {
+            "utf_8" | "utf8" | "u8" => "utf-8",
+            "ascii" | "us_ascii" => "ascii",
+            "latin_1" | "latin1" | "iso_8859_1" | "iso8859_1" | "l1" => {
+                return Ok(self.elements.iter().map(|&b| b as char).collect());
+            }
+            _ => {
+                return Err(vm.new_lookup_error(format!("unknown encoding: {}", encoding)));
+            }
+        };
+        let mut result = String::with_capacity(self.elements.len());
+        let mut rest = &self.elements[..];
+        let mut position = 0;
+        loop {
+            // Split off the bytes that decode, and the ones right after that don't.
+            let (valid, invalid, reason) = if name == "utf-8" {
+                match std::str::from_utf8(rest) {
+                    Ok(_) => (rest.len(), 0, ""),
+                    Err(err) => {
+                        let valid = err.valid_up_to();
+                        match err.error_len() {
+                            Some(len) if (0xc2..=0xf4).contains(&rest[valid]) => {
+                                (valid, len, "invalid continuation byte")
+                            }
+                            Some(len) => (valid, len, "invalid start byte"),
+                            None => (valid, rest.len() - valid, "unexpected end of data"),
+                        }
+                    }
+                }
+            } else {
+                let valid = rest.iter().take_while(|b| b.is_ascii()).count();
+                let invalid = if valid < rest.len() { 1 } else { 0 };
+                (valid, invalid, "ordinal not in range(128)")
+            };
+            result.push_str(std::str::from_utf8(&rest[..valid]).unwrap());
+            if invalid == 0 {
+                return Ok(result);
+            }
+            let bad = &rest[valid..valid + invalid];
+            match errors {
+                "strict" => {
+                    let start = position + valid;
+                    let what = if invalid == 1 {
+                        format!("byte 0x{:02x} in position {}", bad[0], start)
+                    } else {
+                        format!("bytes in position {}-{}", start, start + invalid - 1)
+                    };
+                    return Err(vm.new_unicode_decode_error(format!(
+                        "'{}' codec can't decode {}: {}",
+                        name, what, reason
+                    )));
+                }
+                "ignore" => {}
+                "replace" => result.push('\u{FFFD}'),
+                "backslashreplace" => {
+                    for b in bad {
+                        result.push_str(&format!("\\x{:02x}", b));
+                    }
+                }
+                _ => {
+                    return Err(
+                        vm.new_lookup_error(format!("unknown error handler name '{}'", errors))
+                    );
+                }
+            }
+            position += valid + invalid;
+            rest = &rest[valid + invalid..];
         }
     }
 
@@ -398,8 +563,8 @@ impl PyByteInner {
         }
     }
 
-    pub fn hash(&self) -> pyhash::PyHash {
-        pyhash::hash_value(&self.elements)
+    pub fn hash(&self, vm: &VirtualMachine) -> pyhash::PyHash {
+        vm.hash_secret.hash_bytes(&self.elements)
     }
 
     pub fn add(&self, other: PyByteInner) -> Vec<u8> {
@@ -434,16 +599,16 @@ impl PyByteInner {
         }
     }
 
-    pub fn getitem(&self, needle: Either<PyIntRef, PySliceRef>, vm: &VirtualMachine) -> PyResult {
+    pub fn getitem(&self, needle: SequenceIndex, vm: &VirtualMachine) -> PyResult {
         match needle {
-            Either::A(int) => {
-                if let Some(idx) = self.elements.get_pos(int.as_bigint().to_i32().unwrap()) {
+            SequenceIndex::Int(int) => {
+                if let Some(idx) = self.elements.get_pos(int) {
                     Ok(vm.new_int(self.elements[idx]))
                 } else {
                     Err(vm.new_index_error("index out of range".to_string()))
                 }
             }
-            Either::B(slice) => Ok(vm
+            SequenceIndex::Slice(slice) => Ok(vm
                 .ctx
                 .new_bytes(self.elements.get_slice_items(vm, slice.as_object())?)),
         }
@@ -899,6 +1064,22 @@ impl PyByteInner {
         Ok(self.elements[start..end].to_vec())
     }
 
+    pub fn removeprefix(&self, prefix: PyByteInner) -> Vec<u8> {
+        if self.elements.starts_with(&prefix.elements) {
+            self.elements[prefix.elements.len()..].to_vec()
+        } else {
+            self.elements.clone()
+        }
+    }
+
+    pub fn removesuffix(&self, suffix: PyByteInner) -> Vec<u8> {
+        if self.elements.ends_with(&suffix.elements) {
+            self.elements[..self.len() - suffix.elements.len()].to_vec()
+        } else {
+            self.elements.clone()
+        }
+    }
+
     pub fn split(&self, options: ByteInnerSplitOptions, reverse: bool) -> PyResult<Vec<&[u8]>> {
         let (sep, maxsplit) = options.get_value()?;
 
@@ -1131,6 +1312,16 @@ impl PyByteInner {
 
         Ok(())
     }
+
+    pub fn cformat(&self, values: PyObjectRef, vm: &VirtualMachine) -> PyResult<Vec<u8>> {
+        // Every byte maps to the char with the same code point, so the str machinery can be
+        // reused and the result mapped back without loss.
+        let format_text: String = self.elements.iter().map(|&b| char::from(b)).collect();
+        let format_string = CFormatString::from_str(&format_text)
+            .map_err(|err| vm.new_value_error(err.to_string()))?;
+        let result = do_cformat(vm, format_string, values, true)?;
+        Ok(result.chars().map(|c| c as u8).collect())
+    }
 }
 
 pub fn try_as_byte(obj: &PyObjectRef) -> Option<Vec<u8>> {
@@ -1138,9 +1329,76 @@ pub fn try_as_byte(obj: &PyObjectRef) -> Option<Vec<u8>> {
 
     i @ PyBytes => Some(i.get_value().to_vec()),
     j @ PyByteArray => Some(j.inner.borrow().elements.to_vec()),
+    k @ PyMemoryView => k.get_obj_value(),
+    a @ PyArray => Some(a.get_bytes()),
     _ => None)
 }
 
+/// Format a single `%` specifier of a bytes format string, producing one char per byte.
+pub fn do_cformat_bytes_specifier(
+    vm: &VirtualMachine,
+    format_spec: &mut CFormatSpec,
+    obj: PyObjectRef,
+) -> PyResult<String> {
+    let latin1 = |bytes: &[u8]| bytes.iter().map(|&b| char::from(b)).collect::<String>();
+    match format_spec.format_type {
+        CFormatType::String(CFormatPreconversor::Str)
+        | CFormatType::String(CFormatPreconversor::Bytes) => {
+            let bytes = match PyByteInner::try_from_object(vm, obj.clone()) {
+                Ok(inner) => inner.elements,
+                Err(_) => match vm.get_method(obj.clone(), "__bytes__") {
+                    Some(method) => {
+                        let result = vm.invoke(&method?, vec![])?;
+                        match try_as_byte(&result) {
+                            Some(bytes) => bytes,
+                            None => {
+                                return Err(vm.new_type_error(format!(
+                                    "__bytes__ returned non-bytes (type {})",
+                                    result.class().name
+                                )))
+                            }
+                        }
+                    }
+                    None => {
+                        return Err(vm.new_type_error(format!(
+                            "%b requires a bytes-like object, or an object that implements __bytes__, not '{}'",
+                            obj.class().name
+                        )))
+                    }
+                },
+            };
+            Ok(format_spec.format_string(latin1(&bytes)))
+        }
+        CFormatType::String(CFormatPreconversor::Repr)
+        | CFormatType::String(CFormatPreconversor::Ascii) => {
+            let repr = vm.to_ascii(&obj)?;
+            Ok(format_spec.format_string(objstr::get_value(&repr)))
+        }
+        CFormatType::Character => {
+            let byte = if let Some(value) = obj.payload::<PyInt>() {
+                match value.as_bigint().to_u8() {
+                    Some(byte) => byte,
+                    None => {
+                        return Err(vm.new_overflow_error("%c arg not in range(256)".to_string()))
+                    }
+                }
+            } else {
+                match try_as_byte(&obj) {
+                    Some(ref bytes) if bytes.len() == 1 => bytes[0],
+                    _ => {
+                        return Err(vm.new_type_error(
+                            "%c requires an integer in range(256) or a single byte".to_string(),
+                        ))
+                    }
+                }
+            };
+            format_spec.precision = Some(CFormatQuantity::Amount(1));
+            Ok(format_spec.format_string(latin1(&[byte])))
+        }
+        _ => do_cformat_specifier(vm, format_spec, obj),
+    }
+}
+
 pub trait ByteOr: ToPrimitive {
     fn byte_or(&self, vm: &VirtualMachine) -> Result<u8, PyObjectRef> {
         match self.to_u8() {
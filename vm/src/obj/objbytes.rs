@@ -1,5 +1,5 @@
 use crate::obj::objint::PyIntRef;
-use crate::obj::objslice::PySliceRef;
+use crate::obj::objsequence::SequenceIndex;
 use crate::obj::objstr::PyStringRef;
 use crate::obj::objtuple::PyTupleRef;
 use crate::pyhash;
@@ -16,8 +16,8 @@ use crate::pyobject::{
 };
 
 use super::objbyteinner::{
-    ByteInnerExpandtabsOptions, ByteInnerFindOptions, ByteInnerNewOptions, ByteInnerPaddingOptions,
-    ByteInnerPosition, ByteInnerSplitOptions, ByteInnerSplitlinesOptions,
+    normalize_encoding, ByteInnerExpandtabsOptions, ByteInnerFindOptions, ByteInnerNewOptions,
+    ByteInnerPaddingOptions, ByteInnerPosition, ByteInnerSplitOptions, ByteInnerSplitlinesOptions,
     ByteInnerTranslateOptions, PyByteInner,
 };
 use super::objiter;
@@ -55,6 +55,17 @@ impl PyBytes {
         })
     }
 
+    pub fn from_string_with_errors(
+        value: &str,
+        encoding: &str,
+        errors: &str,
+        vm: &VirtualMachine,
+    ) -> PyResult<Self> {
+        Ok(PyBytes {
+            inner: PyByteInner::from_string_with_errors(value, encoding, errors, vm)?,
+        })
+    }
+
     pub fn get_value(&self) -> &[u8] {
         &self.inner.elements
     }
@@ -104,7 +115,7 @@ impl PyBytesRef {
         vm: &VirtualMachine,
     ) -> PyResult<PyBytesRef> {
         PyBytes {
-            inner: options.get_value(vm)?,
+            inner: options.get_bytes_value(vm)?,
         }
         .into_ref_with_type(vm, cls)
     }
@@ -141,8 +152,8 @@ impl PyBytesRef {
     }
 
     #[pymethod(name = "__hash__")]
-    fn hash(self, _vm: &VirtualMachine) -> pyhash::PyHash {
-        self.inner.hash()
+    fn hash(self, vm: &VirtualMachine) -> pyhash::PyHash {
+        self.inner.hash(vm)
     }
 
     #[pymethod(name = "__iter__")]
@@ -156,7 +167,7 @@ impl PyBytesRef {
     #[pymethod(name = "__add__")]
     fn add(self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         if let Ok(other) = PyByteInner::try_from_object(vm, other) {
-            Ok(vm.ctx.new_bytearray(self.inner.add(other)))
+            Ok(vm.ctx.new_bytes(self.inner.add(other)))
         } else {
             Ok(vm.ctx.not_implemented())
         }
@@ -168,7 +179,7 @@ impl PyBytesRef {
     }
 
     #[pymethod(name = "__getitem__")]
-    fn getitem(self, needle: Either<PyIntRef, PySliceRef>, vm: &VirtualMachine) -> PyResult {
+    fn getitem(self, needle: SequenceIndex, vm: &VirtualMachine) -> PyResult {
         self.inner.getitem(needle, vm)
     }
 
@@ -342,6 +353,16 @@ impl PyBytesRef {
             .new_bytes(self.inner.strip(chars, ByteInnerPosition::Right, vm)?))
     }
 
+    #[pymethod(name = "removeprefix")]
+    fn removeprefix(self, prefix: PyByteInner, vm: &VirtualMachine) -> PyResult {
+        Ok(vm.ctx.new_bytes(self.inner.removeprefix(prefix)))
+    }
+
+    #[pymethod(name = "removesuffix")]
+    fn removesuffix(self, suffix: PyByteInner, vm: &VirtualMachine) -> PyResult {
+        Ok(vm.ctx.new_bytes(self.inner.removesuffix(suffix)))
+    }
+
     #[pymethod(name = "split")]
     fn split(self, options: ByteInnerSplitOptions, vm: &VirtualMachine) -> PyResult {
         let as_bytes = self
@@ -421,22 +442,33 @@ impl PyBytesRef {
     }
 
     #[pymethod(name = "__mul__")]
-    fn repeat(self, n: PyIntRef, vm: &VirtualMachine) -> PyResult {
+    fn repeat(self, n: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let n = vm.to_index(&n)?;
         Ok(vm.ctx.new_bytes(self.inner.repeat(n, vm)?))
     }
 
     #[pymethod(name = "__rmul__")]
-    fn rmul(self, n: PyIntRef, vm: &VirtualMachine) -> PyResult {
+    fn rmul(self, n: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         self.repeat(n, vm)
     }
 
+    #[pymethod(name = "__mod__")]
+    fn modulo(self, values: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        Ok(vm.ctx.new_bytes(self.inner.cformat(values, vm)?))
+    }
+
+    #[pymethod(name = "__rmod__")]
+    fn rmod(self, _values: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        Ok(vm.ctx.not_implemented())
+    }
+
     /// Return a string decoded from the given bytes.
     /// Default encoding is 'utf-8'.
     /// Default errors is 'strict', meaning that encoding errors raise a UnicodeError.
     /// Other possible values are 'ignore', 'replace'
     /// For a list of possible encodings,
     /// see https://docs.python.org/3/library/codecs.html#standard-encodings
-    /// currently, only 'utf-8' and 'ascii' emplemented
+    /// currently, only 'utf-8', 'ascii' and 'latin-1' emplemented
     #[pymethod(name = "decode")]
     fn decode(
         self,
@@ -467,8 +499,8 @@ impl PyBytesRef {
         let decode_error = Err(vm.new_value_error("DecodeError".to_string()));
 
         let mut decode_content = String::new();
-        match encoding_type {
-            "ascii" => {
+        match normalize_encoding(encoding_type).as_str() {
+            "ascii" | "us_ascii" => {
                 for &b in self.get_value() {
                     if b.is_ascii() {
                         decode_content.push(b as char)
@@ -477,7 +509,10 @@ impl PyBytesRef {
                     }
                 }
             }
-            "utf-8" | "utf8" | "" => {
+            "latin_1" | "latin1" | "iso_8859_1" | "iso8859_1" | "l1" => {
+                decode_content.extend(self.get_value().iter().map(|&b| b as char));
+            }
+            "utf_8" | "utf8" | "u8" | "" => {
                 let mut p: u32 = 0u32;
                 let mut remaining_bytes = 0;
                 for &b in self.get_value() {
@@ -1,3 +1,4 @@
+use super::objobject;
 use super::objtype::PyClassRef;
 use crate::pyobject::{PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue};
 use crate::vm::VirtualMachine;
@@ -62,6 +63,11 @@ impl PyClassMethod {
     fn func(&self, _vm: &VirtualMachine) -> PyObjectRef {
         self.callable.clone()
     }
+
+    #[pyproperty(name = "__isabstractmethod__")]
+    fn isabstractmethod(&self, vm: &VirtualMachine) -> PyResult<bool> {
+        objobject::is_abstract(&self.callable, vm)
+    }
 }
 
 pub fn init(context: &PyContext) {
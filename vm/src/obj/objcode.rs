@@ -3,14 +3,22 @@
 */
 
 use std::fmt;
+use std::ops::Deref;
 
 use crate::bytecode;
+use crate::obj::objbytes::PyBytes;
 use crate::obj::objtype::PyClassRef;
 use crate::pyobject::{IdProtocol, PyContext, PyObjectRef, PyRef, PyResult, PyValue};
 use crate::vm::VirtualMachine;
 
 pub type PyCodeRef = PyRef<PyCode>;
 
+// The subset of CPython's `co_flags` bits that a code object can carry here.
+const CO_VARARGS: u32 = 0x04;
+const CO_VARKEYWORDS: u32 = 0x08;
+const CO_GENERATOR: u32 = 0x20;
+const CO_COROUTINE: u32 = 0x80;
+
 pub struct PyCode {
     pub code: bytecode::CodeObject,
 }
@@ -19,6 +27,58 @@ impl PyCode {
     pub fn new(code: bytecode::CodeObject) -> PyCode {
         PyCode { code }
     }
+
+    /// The argument names, in the order of `co_varnames`.
+    fn varnames(&self) -> Vec<&String> {
+        let code = &self.code;
+        let mut names: Vec<&String> = code.arg_names.iter().collect();
+        names.extend(code.kwonlyarg_names.iter());
+        if let bytecode::Varargs::Named(ref name) = code.varargs {
+            names.push(name);
+        }
+        if let bytecode::Varargs::Named(ref name) = code.varkeywords {
+            names.push(name);
+        }
+        names
+    }
+
+    /// The names used by the instructions other than the arguments: globals,
+    /// attributes and imported modules, each once, in order of first use.
+    fn names(&self) -> Vec<&String> {
+        use bytecode::Instruction::*;
+
+        let varnames = self.varnames();
+        let mut names: Vec<&String> = vec![];
+        for instruction in &self.code.instructions {
+            let name = match instruction {
+                LoadName { name, scope } | StoreName { name, scope } => match scope {
+                    bytecode::NameScope::NonLocal => continue,
+                    _ => name,
+                },
+                DeleteName { name }
+                | LoadAttr { name }
+                | StoreAttr { name }
+                | DeleteAttr { name }
+                | ImportFrom { name } => name,
+                Import {
+                    name: Some(name), ..
+                } => name,
+                _ => continue,
+            };
+            if !varnames.contains(&name) && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+}
+
+impl Deref for PyCode {
+    type Target = bytecode::CodeObject;
+
+    fn deref(&self) -> &Self::Target {
+        &self.code
+    }
 }
 
 impl fmt::Debug for PyCode {
@@ -78,6 +138,56 @@ impl PyCodeRef {
     fn co_name(self, _vm: &VirtualMachine) -> String {
         self.code.obj_name.clone()
     }
+
+    fn co_varnames(self, vm: &VirtualMachine) -> PyObjectRef {
+        let names = self
+            .varnames()
+            .into_iter()
+            .map(|name| vm.new_str(name.clone()))
+            .collect();
+        vm.ctx.new_tuple(names)
+    }
+
+    fn co_nlocals(self, _vm: &VirtualMachine) -> usize {
+        self.varnames().len()
+    }
+
+    fn co_names(self, vm: &VirtualMachine) -> PyObjectRef {
+        let names = self
+            .names()
+            .into_iter()
+            .map(|name| vm.new_str(name.clone()))
+            .collect();
+        vm.ctx.new_tuple(names)
+    }
+
+    fn co_posonlyargcount(self, _vm: &VirtualMachine) -> usize {
+        // Positional-only parameters aren't supported by the parser yet.
+        0
+    }
+
+    /// The instructions, serialized in the same format `marshal` uses.
+    fn co_code(self, _vm: &VirtualMachine) -> PyBytes {
+        PyBytes::new(bincode::serialize(&self.code.instructions).unwrap())
+    }
+
+    fn co_flags(self, _vm: &VirtualMachine) -> u32 {
+        let code = &self.code;
+        let mut flags = 0;
+        if let bytecode::Varargs::Named(_) = code.varargs {
+            flags |= CO_VARARGS;
+        }
+        if let bytecode::Varargs::Named(_) = code.varkeywords {
+            flags |= CO_VARKEYWORDS;
+        }
+        if code.is_generator {
+            flags |= CO_GENERATOR;
+        }
+        if code.is_coroutine {
+            flags |= CO_COROUTINE;
+        }
+        flags
+    }
 }
 
 pub fn init(context: &PyContext) {
@@ -86,10 +196,16 @@ pub fn init(context: &PyContext) {
         "__repr__" => context.new_rustfunc(PyCodeRef::repr),
 
         "co_argcount" => context.new_property(PyCodeRef::co_argcount),
+        "co_code" => context.new_property(PyCodeRef::co_code),
         "co_consts" => context.new_property(PyCodeRef::co_consts),
         "co_filename" => context.new_property(PyCodeRef::co_filename),
         "co_firstlineno" => context.new_property(PyCodeRef::co_firstlineno),
+        "co_flags" => context.new_property(PyCodeRef::co_flags),
         "co_kwonlyargcount" => context.new_property(PyCodeRef::co_kwonlyargcount),
         "co_name" => context.new_property(PyCodeRef::co_name),
+        "co_names" => context.new_property(PyCodeRef::co_names),
+        "co_nlocals" => context.new_property(PyCodeRef::co_nlocals),
+        "co_posonlyargcount" => context.new_property(PyCodeRef::co_posonlyargcount),
+        "co_varnames" => context.new_property(PyCodeRef::co_varnames),
     });
 }
@@ -5,11 +5,12 @@ use std::num::Wrapping;
 use crate::function::OptionalArg;
 use crate::pyhash;
 use crate::pyobject::{
-    IntoPyObject, PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue,
+    IntoPyObject, PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue, TypeProtocol,
 };
 use crate::vm::VirtualMachine;
 
 use super::objfloat::{self, PyFloat};
+use super::objstr::PyString;
 use super::objtype::{self, PyClassRef};
 
 /// Create a complex number from a real part and an optional imaginary part.
@@ -58,6 +59,114 @@ fn try_complex(value: &PyObjectRef, vm: &VirtualMachine) -> PyResult<Option<Comp
     })
 }
 
+fn inner_div(v1: Complex64, v2: Complex64, vm: &VirtualMachine) -> PyResult<Complex64> {
+    if v2.is_zero() {
+        return Err(vm.new_zero_division_error("complex division by zero".to_string()));
+    }
+    Ok(v1 / v2)
+}
+
+fn inner_pow(v1: Complex64, v2: Complex64, vm: &VirtualMachine) -> PyResult<Complex64> {
+    if v2.is_zero() {
+        return Ok(Complex64::new(1.0, 0.0));
+    }
+    if v1.is_zero() {
+        return if v2.im != 0.0 || v2.re < 0.0 {
+            Err(vm.new_zero_division_error("0.0 to a negative or complex power".to_string()))
+        } else {
+            Ok(Complex64::zero())
+        };
+    }
+    // Small integral exponents use repeated multiplication, like CPython, so
+    // results such as (1+2j)**2 are exact.
+    if v2.im == 0.0 && v2.re.fract() == 0.0 && v2.re.abs() <= 100.0 {
+        return Ok(v1.powi(v2.re as i32));
+    }
+    Ok(v1.powc(v2))
+}
+
+/// Like the float repr, but integral parts drop their ".0", as in `(1+2j)`.
+fn repr_float_part(value: f64) -> String {
+    let mut repr = objfloat::float_repr(value);
+    if repr.ends_with(".0") {
+        repr.truncate(repr.len() - 2);
+    }
+    repr
+}
+
+/// Parse the string forms accepted by `complex()`, such as `1+2j`, `-3.5j` or `(1e3-j)`.
+fn parse_str(text: &str) -> Option<Complex64> {
+    let text = text.trim();
+    let text = if text.starts_with('(') && text.ends_with(')') {
+        text[1..text.len() - 1].trim()
+    } else {
+        text
+    };
+    if text.is_empty() {
+        return None;
+    }
+    let parse_float = |s: &str| s.parse::<f64>().ok();
+    let parse_imag = |s: &str| -> Option<f64> {
+        match s {
+            "" | "+" => Some(1.0),
+            "-" => Some(-1.0),
+            _ => parse_float(s),
+        }
+    };
+
+    if !(text.ends_with('j') || text.ends_with('J')) {
+        return parse_float(text).map(|re| Complex64::new(re, 0.0));
+    }
+    let body = &text[..text.len() - 1];
+    // the imaginary part starts at the last sign that isn't part of an exponent
+    let split = body
+        .char_indices()
+        .filter(|&(i, c)| {
+            (c == '+' || c == '-') && i > 0 && !body[..i].ends_with(|p| p == 'e' || p == 'E')
+        })
+        .map(|(i, _)| i)
+        .last();
+    match split {
+        Some(i) => {
+            let re = parse_float(&body[..i])?;
+            let im = parse_imag(&body[i..])?;
+            Some(Complex64::new(re, im))
+        }
+        None => parse_imag(body).map(|im| Complex64::new(0.0, im)),
+    }
+}
+
+/// Convert an argument of `complex()` that isn't a string.
+fn to_complex(value: &PyObjectRef, vm: &VirtualMachine) -> PyResult<Complex64> {
+    if let Some(method) = vm.get_method(value.clone(), "__complex__") {
+        let result = vm.invoke(&method?, vec![])?;
+        return if objtype::isinstance(&result, &vm.ctx.complex_type()) {
+            Ok(get_value(&result))
+        } else {
+            Err(vm.new_type_error(format!(
+                "__complex__ returned non-complex (type {})",
+                result.class().name
+            )))
+        };
+    }
+    match try_complex(value, vm)? {
+        Some(value) => Ok(value),
+        None => {
+            let cls = value.class();
+            if objtype::class_has_attr(&cls, "__float__")
+                || objtype::class_has_attr(&cls, "__index__")
+            {
+                Ok(Complex64::new(objfloat::make_float(vm, value)?, 0.0))
+            } else {
+                Err(vm.new_type_error(format!(
+                    "complex() argument must be a string or a number, not '{}'",
+                    value.class().name
+                )))
+            }
+        }
+    }
+}
+
 #[pyimpl]
 impl PyComplex {
     #[pyproperty(name = "real")]
@@ -152,7 +261,7 @@ impl PyComplex {
     fn truediv(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         try_complex(&other, vm)?.map_or_else(
             || Ok(vm.ctx.not_implemented()),
-            |other| (self.value / other).into_pyobject(vm),
+            |other| inner_div(self.value, other, vm)?.into_pyobject(vm),
         )
     }
 
@@ -160,7 +269,7 @@ impl PyComplex {
     fn rtruediv(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         try_complex(&other, vm)?.map_or_else(
             || Ok(vm.ctx.not_implemented()),
-            |other| (other / self.value).into_pyobject(vm),
+            |other| inner_div(other, self.value, vm)?.into_pyobject(vm),
         )
     }
 
@@ -199,13 +308,33 @@ impl PyComplex {
         -self.value
     }
 
+    #[pymethod(name = "__pos__")]
+    fn pos(&self, _vm: &VirtualMachine) -> Complex64 {
+        self.value
+    }
+
+    #[pymethod(name = "__complex__")]
+    fn complex(&self, _vm: &VirtualMachine) -> Complex64 {
+        self.value
+    }
+
     #[pymethod(name = "__repr__")]
     fn repr(&self, _vm: &VirtualMachine) -> String {
         let Complex64 { re, im } = self.value;
-        if re == 0.0 {
-            format!("{}j", im)
+        if re == 0.0 && re.is_sign_positive() {
+            format!("{}j", repr_float_part(im))
         } else {
-            format!("({}{:+}j)", re, im)
+            let sign = if im.is_sign_negative() && !im.is_nan() {
+                '-'
+            } else {
+                '+'
+            };
+            format!(
+                "({}{}{}j)",
+                repr_float_part(re),
+                sign,
+                repr_float_part(im.abs())
+            )
         }
     }
 
@@ -213,7 +342,7 @@ impl PyComplex {
     fn pow(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         try_complex(&other, vm)?.map_or_else(
             || Ok(vm.ctx.not_implemented()),
-            |other| (self.value.powc(other)).into_pyobject(vm),
+            |other| inner_pow(self.value, other, vm)?.into_pyobject(vm),
         )
     }
 
@@ -221,7 +350,7 @@ impl PyComplex {
     fn rpow(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         try_complex(&other, vm)?.map_or_else(
             || Ok(vm.ctx.not_implemented()),
-            |other| (other.powc(self.value)).into_pyobject(vm),
+            |other| inner_pow(other, self.value, vm)?.into_pyobject(vm),
         )
     }
 
@@ -238,16 +367,40 @@ impl PyComplex {
         vm: &VirtualMachine,
     ) -> PyResult<PyComplexRef> {
         let real = match real {
-            OptionalArg::Missing => 0.0,
-            OptionalArg::Present(ref value) => objfloat::make_float(vm, value)?,
+            OptionalArg::Missing => Complex64::zero(),
+            OptionalArg::Present(ref value) => match value.payload::<PyString>() {
+                Some(text) => {
+                    if imag.is_present() {
+                        return Err(vm.new_type_error(
+                            "complex() can't take second arg if first is a string".to_string(),
+                        ));
+                    }
+                    parse_str(text.as_str()).ok_or_else(|| {
+                        vm.new_value_error("complex() arg is a malformed string".to_string())
+                    })?
+                }
+                None => to_complex(value, vm)?,
+            },
         };
 
         let imag = match imag {
-            OptionalArg::Missing => 0.0,
-            OptionalArg::Present(ref value) => objfloat::make_float(vm, value)?,
+            OptionalArg::Missing => Complex64::zero(),
+            OptionalArg::Present(ref value) => {
+                if value.payload::<PyString>().is_some() {
+                    return Err(
+                        vm.new_type_error("complex() second arg can't be a string".to_string())
+                    );
+                }
+                // Unlike the real part, the imaginary part isn't converted through __complex__
+                match try_complex(value, vm)? {
+                    Some(imag) => imag,
+                    None => Complex64::new(objfloat::make_float(vm, value)?, 0.0),
+                }
+            }
         };
 
-        let value = Complex64::new(real, imag);
+        // real + imag * 1j, without letting infinities turn into nans
+        let value = Complex64::new(real.re - imag.im, real.im + imag.re);
         PyComplex { value }.into_ref_with_type(vm, cls)
     }
 
@@ -256,7 +409,7 @@ impl PyComplex {
         let re_hash = pyhash::hash_float(self.value.re);
         let im_hash = pyhash::hash_float(self.value.im);
         let ret = Wrapping(re_hash) + Wrapping(im_hash) * Wrapping(pyhash::IMAG);
-        ret.0
+        pyhash::fix_sentinel(ret.0)
     }
 
     #[pymethod(name = "__getnewargs__")]
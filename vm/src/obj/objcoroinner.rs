@@ -0,0 +1,189 @@
+/*
+ * Machinery shared between generators and coroutines.
+ */
+
+use std::cell::{Cell, RefCell};
+
+use crate::frame::{ExecutionResult, FrameRef};
+use crate::function::OptionalArg;
+use crate::obj::objtype::{self, PyClassRef};
+use crate::pyobject::{PyObjectRef, PyResult, TryFromObject, TypeProtocol};
+use crate::vm::VirtualMachine;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variant {
+    Gen,
+    Coroutine,
+}
+
+impl Variant {
+    fn name(self) -> &'static str {
+        match self {
+            Variant::Gen => "generator",
+            Variant::Coroutine => "coroutine",
+        }
+    }
+}
+
+/// A suspendable frame, driven by `send`, `throw` and `close`.
+#[derive(Debug)]
+pub struct Coro {
+    frame: FrameRef,
+    closed: Cell<bool>,
+    running: Cell<bool>,
+    variant: Variant,
+    /// Exceptions being handled inside the frame while it is suspended.
+    exceptions: RefCell<Vec<PyObjectRef>>,
+}
+
+impl Coro {
+    pub fn new(frame: FrameRef, variant: Variant) -> Self {
+        Coro {
+            frame,
+            closed: Cell::new(false),
+            running: Cell::new(false),
+            variant,
+            exceptions: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn frame(&self) -> FrameRef {
+        self.frame.clone()
+    }
+
+    pub fn running(&self) -> bool {
+        self.running.get()
+    }
+
+    fn run_with<F>(&self, vm: &VirtualMachine, func: F) -> PyResult<ExecutionResult>
+    where
+        F: FnOnce() -> PyResult<ExecutionResult>,
+    {
+        if self.running.get() {
+            return Err(vm.new_value_error(format!("{} already executing", self.variant.name())));
+        }
+        self.running.set(true);
+        // restore the exception state the frame had when it last yielded
+        let outer_len = vm.exceptions.borrow().len();
+        vm.exceptions
+            .borrow_mut()
+            .extend(self.exceptions.borrow_mut().drain(..));
+        let result = func();
+        self.running.set(false);
+        let inner = vm.exceptions.borrow_mut().split_off(outer_len);
+        match result {
+            Ok(ExecutionResult::Yield(_)) => *self.exceptions.borrow_mut() = inner,
+            // once the frame has returned or raised it can't be resumed
+            _ => self.closed.set(true),
+        }
+        result
+    }
+
+    fn closed_error(&self, vm: &VirtualMachine) -> PyObjectRef {
+        match self.variant {
+            Variant::Gen => new_stop_iteration_with_value(vm.get_none(), vm),
+            Variant::Coroutine => {
+                vm.new_runtime_error("cannot reuse already awaited coroutine".to_string())
+            }
+        }
+    }
+
+    pub fn send(&self, value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        if self.closed.get() {
+            return Err(self.closed_error(vm));
+        }
+        let result = self.run_with(vm, || {
+            self.frame.push_value(value);
+            vm.run_frame(self.frame.clone())
+        })?;
+        handle_execution_result(result, vm)
+    }
+
+    pub fn throw(
+        &self,
+        exc_type: PyObjectRef,
+        exc_val: OptionalArg,
+        _exc_tb: OptionalArg,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let exception = normalize_exception(exc_type, exc_val, vm)?;
+        if self.closed.get() {
+            return Err(exception);
+        }
+        let result = self.run_with(vm, || vm.frame_throw(self.frame.clone(), exception))?;
+        handle_execution_result(result, vm)
+    }
+
+    pub fn close(&self, vm: &VirtualMachine) -> PyResult<()> {
+        if self.closed.get() {
+            return Ok(());
+        }
+        let generator_exit = vm.new_empty_exception(vm.ctx.exceptions.generator_exit.clone())?;
+        let result = self.run_with(vm, || vm.frame_throw(self.frame.clone(), generator_exit));
+        self.closed.set(true);
+        match result {
+            Ok(ExecutionResult::Yield(_)) => {
+                Err(vm.new_runtime_error(format!("{} ignored GeneratorExit", self.variant.name())))
+            }
+            Ok(ExecutionResult::Return(_)) => Ok(()),
+            Err(err) => {
+                if objtype::isinstance(&err, &vm.ctx.exceptions.generator_exit)
+                    || objtype::isinstance(&err, &vm.ctx.exceptions.stop_iteration)
+                {
+                    Ok(())
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+}
+
+fn handle_execution_result(result: ExecutionResult, vm: &VirtualMachine) -> PyResult {
+    match result {
+        ExecutionResult::Yield(value) => Ok(value),
+        ExecutionResult::Return(value) => Err(new_stop_iteration_with_value(value, vm)),
+    }
+}
+
+/// Create a `StopIteration` carrying the return value of a finished frame.
+pub fn new_stop_iteration_with_value(value: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
+    let args = if vm.is_none(&value) {
+        vec![]
+    } else {
+        vec![value]
+    };
+    vm.new_exception_obj(vm.ctx.exceptions.stop_iteration.clone(), args)
+        .unwrap()
+}
+
+/// Turn the arguments of `throw()` into an exception instance.
+fn normalize_exception(
+    exc_type: PyObjectRef,
+    exc_val: OptionalArg,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let base_exception_type = &vm.ctx.exceptions.base_exception_type;
+    if objtype::isinstance(&exc_type, base_exception_type) {
+        return match exc_val.into_option() {
+            Some(ref val) if !vm.is_none(val) => {
+                Err(vm
+                    .new_type_error("instance exception may not have a separate value".to_string()))
+            }
+            _ => Ok(exc_type),
+        };
+    }
+    if let Ok(cls) = PyClassRef::try_from_object(vm, exc_type.clone()) {
+        if objtype::issubclass(&cls, base_exception_type) {
+            return match exc_val.into_option() {
+                Some(ref val) if objtype::isinstance(val, &cls) => Ok(val.clone()),
+                Some(ref val) if !vm.is_none(val) => vm.invoke(cls.as_object(), vec![val.clone()]),
+                _ => vm.new_empty_exception(cls),
+            };
+        }
+    }
+    Err(vm.new_type_error(format!(
+        "exceptions must be classes or instances deriving from BaseException, not {}",
+        exc_type.class().name
+    )))
+}
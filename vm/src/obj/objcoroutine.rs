@@ -0,0 +1,122 @@
+/*
+ * Coroutines, as created by calling an `async def` function.
+ */
+
+use crate::frame::FrameRef;
+use crate::function::OptionalArg;
+use crate::obj::objcoroinner::{Coro, Variant};
+use crate::obj::objtype::PyClassRef;
+use crate::pyobject::{PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue};
+use crate::vm::VirtualMachine;
+
+pub type PyCoroutineRef = PyRef<PyCoroutine>;
+
+#[pyclass(name = "coroutine")]
+#[derive(Debug)]
+pub struct PyCoroutine {
+    inner: Coro,
+}
+
+impl PyValue for PyCoroutine {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.ctx.types.coroutine_type.clone()
+    }
+}
+
+#[pyimpl]
+impl PyCoroutine {
+    pub fn new(frame: FrameRef, vm: &VirtualMachine) -> PyCoroutineRef {
+        PyCoroutine {
+            inner: Coro::new(frame, Variant::Coroutine),
+        }
+        .into_ref(vm)
+    }
+
+    #[pymethod]
+    fn send(&self, value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.inner.send(value, vm)
+    }
+
+    #[pymethod]
+    fn throw(
+        &self,
+        exc_type: PyObjectRef,
+        exc_val: OptionalArg,
+        exc_tb: OptionalArg,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        self.inner.throw(exc_type, exc_val, exc_tb, vm)
+    }
+
+    #[pymethod]
+    fn close(&self, vm: &VirtualMachine) -> PyResult<()> {
+        self.inner.close(vm)
+    }
+
+    #[pymethod(name = "__await__")]
+    fn await_iter(zelf: PyCoroutineRef, _vm: &VirtualMachine) -> PyCoroutineWrapper {
+        PyCoroutineWrapper { coro: zelf }
+    }
+
+    #[pyproperty]
+    fn cr_frame(&self, _vm: &VirtualMachine) -> FrameRef {
+        self.inner.frame()
+    }
+
+    #[pyproperty]
+    fn cr_running(&self, _vm: &VirtualMachine) -> bool {
+        self.inner.running()
+    }
+}
+
+/// The iterator returned by `coroutine.__await__()`.
+#[pyclass(name = "coroutine_wrapper")]
+#[derive(Debug)]
+pub struct PyCoroutineWrapper {
+    coro: PyCoroutineRef,
+}
+
+impl PyValue for PyCoroutineWrapper {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.ctx.types.coroutine_wrapper_type.clone()
+    }
+}
+
+#[pyimpl]
+impl PyCoroutineWrapper {
+    #[pymethod(name = "__iter__")]
+    fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+
+    #[pymethod(name = "__next__")]
+    fn next(&self, vm: &VirtualMachine) -> PyResult {
+        self.coro.send(vm.get_none(), vm)
+    }
+
+    #[pymethod]
+    fn send(&self, value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.coro.send(value, vm)
+    }
+
+    #[pymethod]
+    fn throw(
+        &self,
+        exc_type: PyObjectRef,
+        exc_val: OptionalArg,
+        exc_tb: OptionalArg,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        self.coro.throw(exc_type, exc_val, exc_tb, vm)
+    }
+
+    #[pymethod]
+    fn close(&self, vm: &VirtualMachine) -> PyResult<()> {
+        self.coro.close(vm)
+    }
+}
+
+pub fn init(ctx: &PyContext) {
+    PyCoroutine::extend_class(ctx, &ctx.types.coroutine_type);
+    PyCoroutineWrapper::extend_class(ctx, &ctx.types.coroutine_wrapper_type);
+}
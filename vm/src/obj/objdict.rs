@@ -1,7 +1,7 @@
 use std::cell::{Cell, RefCell};
 use std::fmt;
 
-use crate::function::{KwArgs, OptionalArg};
+use crate::function::{KwArgs, OptionalArg, PyFuncArgs};
 use crate::pyobject::{
     IdProtocol, IntoPyObject, ItemProtocol, PyAttributes, PyContext, PyIterable, PyObjectRef,
     PyRef, PyResult, PyValue,
@@ -39,20 +39,22 @@ impl PyValue for PyDict {
 
 // Python dict methods:
 impl PyDictRef {
-    fn new(
-        class: PyClassRef,
+    fn new(class: PyClassRef, _args: PyFuncArgs, vm: &VirtualMachine) -> PyResult<PyDictRef> {
+        let entries = RefCell::new(DictContentType::default());
+        PyDict { entries }.into_ref_with_type(vm, class)
+    }
+
+    /// The contents are filled in by `__init__` rather than `__new__`, so that
+    /// subclasses overriding `__setitem__` (like `OrderedDict`) see every item.
+    fn init(
+        self,
         dict_obj: OptionalArg<PyObjectRef>,
         kwargs: KwArgs,
         vm: &VirtualMachine,
-    ) -> PyResult<PyDictRef> {
-        let dict = DictContentType::default();
-
-        let entries = RefCell::new(dict);
+    ) -> PyResult<()> {
         // it's unfortunate that we can't abstract over RefCall, as we should be able to use dict
         // directly here, but that would require generic associated types
-        PyDictRef::merge(&entries, dict_obj, kwargs, vm)?;
-
-        PyDict { entries }.into_ref_with_type(vm, class)
+        PyDictRef::merge(&self.entries, dict_obj, kwargs, vm)
     }
 
     fn merge(
@@ -67,6 +69,13 @@ impl PyDictRef {
                 for (key, value) in dict_obj {
                     dict.borrow_mut().insert(vm, &key, value)?;
                 }
+            } else if let Some(keys) = vm.get_method(dict_obj.clone(), "keys") {
+                // Any other mapping: look up each of its keys.
+                let keys = objiter::get_iter(vm, &vm.invoke(&keys?, vec![])?)?;
+                while let Some(key) = objiter::get_next_object(vm, &keys)? {
+                    let value = dict_obj.get_item(&key, vm)?;
+                    dict.borrow_mut().insert(vm, &key, value)?;
+                }
             } else {
                 let iter = objiter::get_iter(vm, &dict_obj)?;
                 loop {
@@ -578,6 +587,7 @@ pub fn init(context: &PyContext) {
         "__getitem__" => context.new_rustfunc(PyDictRef::inner_getitem),
         "__iter__" => context.new_rustfunc(PyDictRef::iter),
         "__new__" => context.new_rustfunc(PyDictRef::new),
+        "__init__" => context.new_rustfunc(PyDictRef::init),
         "__repr__" => context.new_rustfunc(PyDictRef::repr),
         "__setitem__" => context.new_rustfunc(PyDictRef::inner_setitem),
         "__hash__" => context.new_rustfunc(PyDictRef::hash),
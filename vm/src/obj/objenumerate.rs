@@ -26,18 +26,25 @@ impl PyValue for PyEnumerate {
     }
 }
 
-fn enumerate_new(
-    cls: PyClassRef,
+#[derive(FromArgs)]
+struct EnumerateArgs {
+    #[pyarg(positional_or_keyword)]
     iterable: PyObjectRef,
+    #[pyarg(positional_or_keyword, optional = true)]
     start: OptionalArg<PyIntRef>,
+}
+
+fn enumerate_new(
+    cls: PyClassRef,
+    args: EnumerateArgs,
     vm: &VirtualMachine,
 ) -> PyResult<PyEnumerateRef> {
-    let counter = match start {
+    let counter = match args.start {
         OptionalArg::Present(start) => start.as_bigint().clone(),
         OptionalArg::Missing => BigInt::zero(),
     };
 
-    let iterator = objiter::get_iter(vm, &iterable)?;
+    let iterator = objiter::get_iter(vm, &args.iterable)?;
     PyEnumerate {
         counter: RefCell::new(counter.clone()),
         iterator,
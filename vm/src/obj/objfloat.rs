@@ -2,6 +2,7 @@ use super::objbytes;
 use super::objint;
 use super::objstr;
 use super::objtype;
+use crate::format::FormatSpec;
 use crate::function::OptionalArg;
 use crate::obj::objstr::PyStringRef;
 use crate::obj::objtype::PyClassRef;
@@ -13,8 +14,9 @@ use crate::pyobject::{
 use crate::vm::VirtualMachine;
 use hexf_parse;
 use num_bigint::{BigInt, ToBigInt};
+use num_integer::Integer;
 use num_rational::Ratio;
-use num_traits::{float::Float, sign::Signed, ToPrimitive, Zero};
+use num_traits::{float::Float, sign::Signed, One, Pow, ToPrimitive, Zero};
 
 /// Convert a string or number to a floating point number, if possible.
 #[pyclass(name = "float")]
@@ -66,7 +68,13 @@ macro_rules! impl_try_from_object_float {
     ($($t:ty),*) => {
         $(impl TryFromObject for $t {
             fn try_from_object(vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<Self> {
-                PyFloatRef::try_from_object(vm, obj).map(|f| f.to_f64() as $t)
+                match try_float(&obj, vm)? {
+                    Some(value) => Ok(value as $t),
+                    None => Err(vm.new_type_error(format!(
+                        "must be real number, not {}",
+                        obj.class().name
+                    ))),
+                }
             }
         })*
     };
@@ -84,13 +92,13 @@ fn inner_div(v1: f64, v2: f64, vm: &VirtualMachine) -> PyResult<f64> {
 
 fn inner_mod(v1: f64, v2: f64, vm: &VirtualMachine) -> PyResult<f64> {
     if v2 != 0.0 {
-        Ok(v1 % v2)
+        Ok(floor_divmod(v1, v2).1)
     } else {
         Err(vm.new_zero_division_error("float mod by zero".to_string()))
     }
 }
 
-fn try_to_bigint(value: f64, vm: &VirtualMachine) -> PyResult<BigInt> {
+pub fn try_to_bigint(value: f64, vm: &VirtualMachine) -> PyResult<BigInt> {
     match value.to_bigint() {
         Some(int) => Ok(int),
         None => {
@@ -114,15 +122,42 @@ fn try_to_bigint(value: f64, vm: &VirtualMachine) -> PyResult<BigInt> {
 
 fn inner_floordiv(v1: f64, v2: f64, vm: &VirtualMachine) -> PyResult<f64> {
     if v2 != 0.0 {
-        Ok((v1 / v2).floor())
+        Ok(floor_divmod(v1, v2).0)
     } else {
         Err(vm.new_zero_division_error("float floordiv by zero".to_string()))
     }
 }
 
+/// Floor division and modulo of two floats, where the modulo takes the sign
+/// of the divisor. Mirrors CPython's `float_divmod` so that results match
+/// bit for bit, including the signs of zeros. `v2` must not be zero.
+fn floor_divmod(v1: f64, v2: f64) -> (f64, f64) {
+    let mut modulo = v1 % v2;
+    let mut div = (v1 - modulo) / v2;
+    if modulo != 0.0 {
+        if (v2 < 0.0) != (modulo < 0.0) {
+            modulo += v2;
+            div -= 1.0;
+        }
+    } else {
+        modulo = 0.0f64.copysign(v2);
+    }
+    let floordiv = if div != 0.0 {
+        let floordiv = div.floor();
+        if div - floordiv > 0.5 {
+            floordiv + 1.0
+        } else {
+            floordiv
+        }
+    } else {
+        0.0f64.copysign(v1 / v2)
+    };
+    (floordiv, modulo)
+}
+
 fn inner_divmod(v1: f64, v2: f64, vm: &VirtualMachine) -> PyResult<(f64, f64)> {
     if v2 != 0.0 {
-        Ok(((v1 / v2).floor(), v1 % v2))
+        Ok(floor_divmod(v1, v2))
     } else {
         Err(vm.new_zero_division_error("float divmod()".to_string()))
     }
@@ -323,7 +358,7 @@ impl PyFloat {
         } else if objtype::isinstance(&arg, &vm.ctx.int_type()) {
             objint::get_float_value(&arg, vm)?
         } else if objtype::isinstance(&arg, &vm.ctx.str_type()) {
-            match lexical::try_parse(objstr::get_value(&arg)) {
+            match lexical::try_parse(objstr::get_value(&arg).trim_matches(objstr::is_space)) {
                 Ok(f) => f,
                 Err(_) => {
                     let arg_repr = vm.to_pystr(&arg)?;
@@ -345,7 +380,7 @@ impl PyFloat {
                 }
             }
         } else {
-            return Err(vm.new_type_error(format!("can't convert {} to float", arg.class().name)));
+            make_float(vm, &arg)?
         };
         PyFloat { value }.into_ref_with_type(vm, cls)
     }
@@ -409,11 +444,17 @@ impl PyFloat {
     }
 
     #[pymethod(name = "__repr__")]
-    fn repr(&self, vm: &VirtualMachine) -> String {
-        if self.is_integer(vm) {
-            format!("{:.1}", self.value)
-        } else {
-            self.value.to_string()
+    fn repr(&self, _vm: &VirtualMachine) -> String {
+        float_repr(self.value)
+    }
+
+    #[pymethod(name = "__format__")]
+    fn format(&self, spec: PyStringRef, vm: &VirtualMachine) -> PyResult<String> {
+        match FormatSpec::parse(&spec.value)
+            .and_then(|format_spec| format_spec.format_float(self.value))
+        {
+            Ok(string) => Ok(string),
+            Err(err) => Err(vm.new_value_error(err.to_string())),
         }
     }
 
@@ -457,50 +498,22 @@ impl PyFloat {
             OptionalArg::Missing => None,
             OptionalArg::Present(ref value) => {
                 if !vm.get_none().is(value) {
-                    if !objtype::isinstance(value, &vm.ctx.int_type()) {
-                        return Err(vm.new_type_error(format!(
-                            "'{}' object cannot be interpreted as an integer",
-                            value.class().name
-                        )));
-                    };
-                    // Only accept int type ndigits
-                    let ndigits = objint::get_value(value);
-                    Some(ndigits)
+                    Some(vm.to_index(value)?)
                 } else {
                     None
                 }
             }
         };
 
-        if let Some(ndigits) = ndigits {
-            if ndigits.is_zero() {
-                let fract = self.value.fract();
-                let value = if (fract.abs() - 0.5).abs() < std::f64::EPSILON {
-                    if self.value.trunc() % 2.0 == 0.0 {
-                        self.value - fract
-                    } else {
-                        self.value + fract
-                    }
-                } else {
-                    self.value.round()
-                };
+        match ndigits {
+            Some(ndigits) => {
+                let value = round_to_ndigits(self.value, ndigits.as_bigint(), vm)?;
                 Ok(vm.ctx.new_float(value))
-            } else {
-                Ok(vm.ctx.not_implemented())
             }
-        } else {
-            let fract = self.value.fract();
-            let value = if (fract.abs() - 0.5).abs() < std::f64::EPSILON {
-                if self.value.trunc() % 2.0 == 0.0 {
-                    self.value - fract
-                } else {
-                    self.value + fract
-                }
-            } else {
-                self.value.round()
-            };
-            let int = try_to_bigint(value, vm)?;
-            Ok(vm.ctx.new_int(int))
+            None => {
+                let int = try_to_bigint(round_half_even(self.value), vm)?;
+                Ok(vm.ctx.new_int(int))
+            }
         }
     }
 
@@ -595,6 +608,84 @@ fn to_hex(value: f64) -> String {
     }
 }
 
+/// The shortest string that reads back as `value`, spelled the way CPython's
+/// `repr()` does: exponent notation below 1e-4 and from 1e16 up.
+pub fn float_repr(value: f64) -> String {
+    if value.is_nan() {
+        return "nan".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    // `{:e}` yields the fewest digits that round-trip, but when two candidates
+    // are equally close it may not pick the correctly rounded one.
+    let mut formatted = format!("{:e}", value.abs());
+    let precision = formatted.find('e').unwrap().saturating_sub(2);
+    let rounded = format!("{:.*e}", precision, value.abs());
+    if rounded.parse::<f64>() == Ok(value.abs()) {
+        formatted = rounded;
+    }
+
+    let mut parts = formatted.splitn(2, 'e');
+    let digits: String = parts
+        .next()
+        .unwrap()
+        .chars()
+        .filter(|c| *c != '.')
+        .collect();
+    let exponent = parts.next().unwrap().parse::<i32>().unwrap();
+    // position of the decimal point relative to the start of `digits`
+    let decpt = exponent + 1;
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    let body = if decpt <= -4 || decpt > 16 {
+        let (first, rest) = digits.split_at(1);
+        let mantissa = if rest.is_empty() {
+            first.to_string()
+        } else {
+            format!("{}.{}", first, rest)
+        };
+        let exponent_sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, exponent_sign, exponent.abs())
+    } else if decpt <= 0 {
+        format!("0.{}{}", "0".repeat(-decpt as usize), digits)
+    } else if (decpt as usize) < digits.len() {
+        let (int_part, fraction) = digits.split_at(decpt as usize);
+        format!("{}.{}", int_part, fraction)
+    } else {
+        format!("{}{}.0", digits, "0".repeat(decpt as usize - digits.len()))
+    };
+    format!("{}{}", sign, body)
+}
+
+#[test]
+fn test_float_repr() {
+    assert_eq!(float_repr(0.1), "0.1");
+    assert_eq!(float_repr(1.0), "1.0");
+    assert_eq!(float_repr(-0.0), "-0.0");
+    assert_eq!(float_repr(1e16), "1e+16");
+    assert_eq!(float_repr(1234567890123456.0), "1234567890123456.0");
+    assert_eq!(float_repr(0.0001), "0.0001");
+    assert_eq!(float_repr(1e-5), "1e-05");
+    assert_eq!(float_repr(1.5e300), "1.5e+300");
+    assert_eq!(float_repr(5e-324), "5e-324");
+    assert_eq!(float_repr(f64::NAN), "nan");
+    assert_eq!(float_repr(f64::NEG_INFINITY), "-inf");
+}
+
+#[test]
+fn test_float_repr_roundtrip() {
+    use rand::Rng;
+    for _ in 0..20000 {
+        let bytes = rand::thread_rng().gen::<[u64; 1]>();
+        let f = f64::from_bits(bytes[0]);
+        if !f.is_finite() {
+            continue;
+        }
+        let repr = float_repr(f);
+        assert_eq!(repr.parse::<f64>(), Ok(f), "{}", repr);
+    }
+}
+
 #[test]
 fn test_to_hex() {
     use rand::Rng;
@@ -612,6 +703,57 @@ fn test_to_hex() {
     }
 }
 
+fn round_half_even(value: f64) -> f64 {
+    if (value - value.trunc()).abs() == 0.5 {
+        2.0 * (value / 2.0).round()
+    } else {
+        value.round()
+    }
+}
+
+/// Round to `ndigits` decimal places, where a negative `ndigits` rounds to tens, hundreds, ...
+fn round_to_ndigits(value: f64, ndigits: &BigInt, vm: &VirtualMachine) -> PyResult<f64> {
+    // Past these bounds the result is known without doing any work: every
+    // float is exactly representable with 323 decimals, and none reaches 1e309.
+    const NDIGITS_MAX: i32 = 323;
+    const NDIGITS_MIN: i32 = -308;
+    if !value.is_finite() || value == 0.0 {
+        return Ok(value);
+    }
+    let ndigits = match ndigits.to_i32() {
+        Some(ndigits) => ndigits,
+        None if ndigits.is_positive() => NDIGITS_MAX + 1,
+        None => NDIGITS_MIN - 1,
+    };
+    if ndigits > NDIGITS_MAX {
+        Ok(value)
+    } else if ndigits < NDIGITS_MIN {
+        Ok(0.0 * value)
+    } else if ndigits >= 0 {
+        // Rust's fixed precision formatting is correctly rounded, ties to even
+        Ok(format!("{:.*}", ndigits as usize, value).parse().unwrap())
+    } else {
+        // Round the exact value of the float, so that ties are real ties
+        let power = BigInt::from(10).pow(-ndigits as usize);
+        let ratio = Ratio::from_float(value).unwrap() / power;
+        let floor = ratio.floor();
+        let twice_fract = (ratio - &floor) * BigInt::from(2);
+        let floor = floor.to_integer();
+        let one = Ratio::one();
+        let quotient = if twice_fract > one || (twice_fract == one && floor.is_odd()) {
+            floor + 1
+        } else {
+            floor
+        };
+        let rounded: f64 = format!("{}e{}", quotient, -ndigits).parse().unwrap();
+        if rounded.is_infinite() {
+            Err(vm.new_overflow_error("rounded value too large to represent".to_string()))
+        } else {
+            Ok(rounded)
+        }
+    }
+}
+
 pub fn ufrexp(value: f64) -> (f64, i32) {
     if 0.0 == value {
         (0.0, 0i32)
@@ -633,15 +775,23 @@ pub fn get_value(obj: &PyObjectRef) -> f64 {
 pub fn make_float(vm: &VirtualMachine, obj: &PyObjectRef) -> PyResult<f64> {
     if objtype::isinstance(obj, &vm.ctx.float_type()) {
         Ok(get_value(obj))
+    } else if let Some(method) = vm.get_method(obj.clone(), "__float__") {
+        let result = vm.invoke(&method?, vec![])?;
+        match result.payload::<PyFloat>() {
+            Some(float) => Ok(float.value),
+            None => Err(vm.new_type_error(format!(
+                "{}.__float__ returned non-float (type {})",
+                obj.class().name,
+                result.class().name
+            ))),
+        }
+    } else if objtype::class_has_attr(&obj.class(), "__index__") {
+        objint::get_float_value(vm.to_index(obj)?.as_object(), vm)
     } else {
-        let method = vm.get_method_or_type_error(obj.clone(), "__float__", || {
-            format!(
-                "float() argument must be a string or a number, not '{}'",
-                obj.class().name
-            )
-        })?;
-        let result = vm.invoke(&method, vec![])?;
-        Ok(get_value(&result))
+        Err(vm.new_type_error(format!(
+            "float() argument must be a string or a number, not '{}'",
+            obj.class().name
+        )))
     }
 }
 
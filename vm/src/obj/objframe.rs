@@ -5,7 +5,7 @@
 use super::objcode::PyCodeRef;
 use super::objdict::PyDictRef;
 use crate::frame::FrameRef;
-use crate::pyobject::{PyContext, PyObjectRef, PyResult};
+use crate::pyobject::{IdProtocol, PyContext, PyObjectRef, PyResult};
 use crate::vm::VirtualMachine;
 
 pub fn init(context: &PyContext) {
@@ -14,9 +14,11 @@ pub fn init(context: &PyContext) {
         "__repr__" => context.new_rustfunc(FrameRef::repr),
         "f_locals" => context.new_property(FrameRef::flocals),
         "f_globals" => context.new_property(FrameRef::f_globals),
+        "f_builtins" => context.new_property(FrameRef::f_builtins),
         "f_code" => context.new_property(FrameRef::fcode),
         "f_back" => context.new_property(FrameRef::f_back),
         "f_lasti" => context.new_property(FrameRef::f_lasti),
+        "f_lineno" => context.new_property(FrameRef::f_lineno),
     });
 }
 
@@ -27,27 +29,44 @@ impl FrameRef {
     }
 
     fn repr(self, _vm: &VirtualMachine) -> String {
-        "<frame object at .. >".to_string()
+        format!(
+            "<frame at 0x{:x}, file '{}', line {}, code {}>",
+            self.get_id(),
+            self.code.source_path,
+            self.get_lineno().row(),
+            self.code.obj_name
+        )
     }
 
     fn f_globals(self, _vm: &VirtualMachine) -> PyDictRef {
         self.scope.globals.clone()
     }
 
+    fn f_builtins(self, vm: &VirtualMachine) -> PyDictRef {
+        vm.builtins.dict.as_ref().unwrap().clone()
+    }
+
     fn flocals(self, _vm: &VirtualMachine) -> PyDictRef {
         self.scope.get_locals()
     }
 
-    fn fcode(self, vm: &VirtualMachine) -> PyCodeRef {
-        vm.ctx.new_code_object(self.code.clone())
+    fn fcode(self, _vm: &VirtualMachine) -> PyCodeRef {
+        self.code.clone()
     }
 
-    fn f_back(self, vm: &VirtualMachine) -> PyObjectRef {
-        // TODO: how to retrieve the upper stack frame??
-        vm.ctx.none()
+    fn f_back(self, vm: &VirtualMachine) -> Option<FrameRef> {
+        // The caller is the frame just below this one on the VM's stack; a
+        // frame that has already finished running has no caller.
+        let frames = vm.frames.borrow();
+        let pos = frames.iter().rposition(|frame| frame.is(&self))?;
+        pos.checked_sub(1).map(|caller| frames[caller].clone())
     }
 
     fn f_lasti(self, vm: &VirtualMachine) -> PyObjectRef {
         vm.ctx.new_int(*self.lasti.borrow())
     }
+
+    fn f_lineno(self, _vm: &VirtualMachine) -> usize {
+        self.get_lineno().row()
+    }
 }
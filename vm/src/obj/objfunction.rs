@@ -1,6 +1,10 @@
+use std::cell::RefCell;
+
 use crate::function::{Args, KwArgs};
+use crate::obj::objbuiltinfunc::PyBuiltinFunctionRef;
 use crate::obj::objcode::PyCodeRef;
 use crate::obj::objdict::PyDictRef;
+use crate::obj::objproperty::PropertyBuilder;
 use crate::obj::objtuple::PyTupleRef;
 use crate::obj::objtype::PyClassRef;
 use crate::pyobject::{IdProtocol, PyContext, PyObjectRef, PyRef, PyResult, PyValue, TypeProtocol};
@@ -14,8 +18,9 @@ pub struct PyFunction {
     // TODO: these shouldn't be public
     pub code: PyCodeRef,
     pub scope: Scope,
-    pub defaults: Option<PyTupleRef>,
-    pub kw_only_defaults: Option<PyDictRef>,
+    /// Evaluated once, when the `def` statement runs, and shared by all calls.
+    pub defaults: RefCell<Option<PyTupleRef>>,
+    pub kw_only_defaults: RefCell<Option<PyDictRef>>,
 }
 
 impl PyFunction {
@@ -28,8 +33,8 @@ impl PyFunction {
         PyFunction {
             code,
             scope,
-            defaults,
-            kw_only_defaults,
+            defaults: RefCell::new(defaults),
+            kw_only_defaults: RefCell::new(kw_only_defaults),
         }
     }
 }
@@ -50,14 +55,36 @@ impl PyFunctionRef {
     }
 
     fn defaults(self, _vm: &VirtualMachine) -> Option<PyTupleRef> {
-        self.defaults.clone()
+        self.defaults.borrow().clone()
+    }
+
+    fn set_defaults(self, defaults: Option<PyTupleRef>, vm: &VirtualMachine) -> PyResult {
+        self.defaults.replace(defaults);
+        Ok(vm.get_none())
+    }
+
+    fn globals(self, _vm: &VirtualMachine) -> PyDictRef {
+        self.scope.globals.clone()
+    }
+
+    fn closure(self, _vm: &VirtualMachine) -> Option<PyTupleRef> {
+        // Variables of enclosing functions are found through the scope chain
+        // rather than through cells, so there are none to expose.
+        None
     }
 
     fn kwdefaults(self, _vm: &VirtualMachine) -> Option<PyDictRef> {
-        self.kw_only_defaults.clone()
+        self.kw_only_defaults.borrow().clone()
+    }
+
+    fn set_kwdefaults(self, kw_only_defaults: Option<PyDictRef>, vm: &VirtualMachine) -> PyResult {
+        self.kw_only_defaults.replace(kw_only_defaults);
+        Ok(vm.get_none())
     }
 }
 
+pub type PyMethodRef = PyRef<PyMethod>;
+
 #[derive(Debug)]
 pub struct PyMethod {
     // TODO: these shouldn't be public
@@ -77,22 +104,70 @@ impl PyValue for PyMethod {
     }
 }
 
+impl PyMethodRef {
+    fn func(self, _vm: &VirtualMachine) -> PyObjectRef {
+        self.function.clone()
+    }
+
+    fn self_(self, _vm: &VirtualMachine) -> PyObjectRef {
+        self.object.clone()
+    }
+
+    fn name(self, vm: &VirtualMachine) -> PyResult {
+        vm.get_attribute(self.function.clone(), "__name__")
+    }
+}
+
 pub fn init(context: &PyContext) {
     let function_type = &context.types.function_type;
     extend_class!(context, function_type, {
         "__get__" => context.new_rustfunc(bind_method),
         "__call__" => context.new_rustfunc(PyFunctionRef::call),
         "__code__" => context.new_property(PyFunctionRef::code),
-        "__defaults__" => context.new_property(PyFunctionRef::defaults),
-        "__kwdefaults__" => context.new_property(PyFunctionRef::kwdefaults),
+        "__globals__" => context.new_property(PyFunctionRef::globals),
+        "__closure__" => context.new_property(PyFunctionRef::closure),
+        "__defaults__" => PropertyBuilder::new(context)
+            .add_getter(PyFunctionRef::defaults)
+            .add_setter(PyFunctionRef::set_defaults)
+            .create(),
+        "__kwdefaults__" => PropertyBuilder::new(context)
+            .add_getter(PyFunctionRef::kwdefaults)
+            .add_setter(PyFunctionRef::set_kwdefaults)
+            .create(),
+    });
+
+    let bound_method_type = &context.types.bound_method_type;
+    extend_class!(context, bound_method_type, {
+        "__func__" => context.new_property(PyMethodRef::func),
+        "__self__" => context.new_property(PyMethodRef::self_),
+        "__name__" => context.new_property(PyMethodRef::name),
     });
 
     let builtin_function_or_method_type = &context.types.builtin_function_or_method_type;
     extend_class!(context, builtin_function_or_method_type, {
-        "__get__" => context.new_rustfunc(bind_method)
+        "__get__" => context.new_rustfunc(bind_method),
+        "__name__" => context.new_property(builtin_function_name),
+        "__repr__" => context.new_rustfunc(builtin_function_repr),
     });
 }
 
+fn builtin_function_name(function: PyBuiltinFunctionRef, vm: &VirtualMachine) -> PyResult<String> {
+    function.name().ok_or_else(|| {
+        vm.new_attribute_error("builtin function has no attribute '__name__'".to_string())
+    })
+}
+
+fn builtin_function_repr(function: PyBuiltinFunctionRef, _vm: &VirtualMachine) -> String {
+    match function.name() {
+        Some(name) => format!("<built-in function {}>", name),
+        None => format!(
+            "<{} object at 0x{:x}>",
+            function.as_object().class().name,
+            function.get_id()
+        ),
+    }
+}
+
 fn bind_method(
     function: PyObjectRef,
     obj: PyObjectRef,
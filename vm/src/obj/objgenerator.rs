@@ -2,8 +2,10 @@
  * The mythical generator.
  */
 
-use crate::frame::{ExecutionResult, FrameRef};
-use crate::obj::objtype::{isinstance, PyClassRef};
+use crate::frame::FrameRef;
+use crate::function::OptionalArg;
+use crate::obj::objcoroinner::{Coro, Variant};
+use crate::obj::objtype::PyClassRef;
 use crate::pyobject::{PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue};
 use crate::vm::VirtualMachine;
 
@@ -12,7 +14,7 @@ pub type PyGeneratorRef = PyRef<PyGenerator>;
 #[pyclass(name = "generator")]
 #[derive(Debug)]
 pub struct PyGenerator {
-    frame: FrameRef,
+    inner: Coro,
 }
 
 impl PyValue for PyGenerator {
@@ -24,7 +26,10 @@ impl PyValue for PyGenerator {
 #[pyimpl]
 impl PyGenerator {
     pub fn new(frame: FrameRef, vm: &VirtualMachine) -> PyGeneratorRef {
-        PyGenerator { frame }.into_ref(vm)
+        PyGenerator {
+            inner: Coro::new(frame, Variant::Gen),
+        }
+        .into_ref(vm)
     }
 
     #[pymethod(name = "__iter__")]
@@ -39,38 +44,33 @@ impl PyGenerator {
 
     #[pymethod]
     fn send(&self, value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-        self.frame.push_value(value.clone());
-
-        let result = vm.run_frame(self.frame.clone())?;
-        handle_execution_result(result, vm)
+        self.inner.send(value, vm)
     }
 
     #[pymethod]
     fn throw(
         &self,
-        _exc_type: PyObjectRef,
-        exc_val: PyObjectRef,
-        _exc_tb: PyObjectRef,
+        exc_type: PyObjectRef,
+        exc_val: OptionalArg,
+        exc_tb: OptionalArg,
         vm: &VirtualMachine,
     ) -> PyResult {
-        // TODO what should we do with the other parameters? CPython normalises them with
-        //      PyErr_NormalizeException, do we want to do the same.
-        if !isinstance(&exc_val, &vm.ctx.exceptions.base_exception_type) {
-            return Err(vm.new_type_error("Can't throw non exception".to_string()));
-        }
-        let result = vm.frame_throw(self.frame.clone(), exc_val)?;
-        handle_execution_result(result, vm)
+        self.inner.throw(exc_type, exc_val, exc_tb, vm)
     }
-}
 
-fn handle_execution_result(result: ExecutionResult, vm: &VirtualMachine) -> PyResult {
-    match result {
-        ExecutionResult::Yield(value) => Ok(value),
-        ExecutionResult::Return(_value) => {
-            // Stop iteration!
-            let stop_iteration = vm.ctx.exceptions.stop_iteration.clone();
-            Err(vm.new_exception(stop_iteration, "End of generator".to_string()))
-        }
+    #[pymethod]
+    fn close(&self, vm: &VirtualMachine) -> PyResult<()> {
+        self.inner.close(vm)
+    }
+
+    #[pyproperty]
+    fn gi_frame(&self, _vm: &VirtualMachine) -> FrameRef {
+        self.inner.frame()
+    }
+
+    #[pyproperty]
+    fn gi_running(&self, _vm: &VirtualMachine) -> bool {
+        self.inner.running()
     }
 }
 
@@ -0,0 +1,174 @@
+/*
+ * The result of subscripting a builtin generic class, e.g. `list[int]`.
+ */
+
+use super::objsequence::seq_equal;
+use super::objstr::{PyString, PyStringRef};
+use super::objtuple::{PyTuple, PyTupleRef};
+use super::objtype::{self, PyClassRef};
+use crate::function::{Args, KwArgs};
+use crate::pyhash;
+use crate::pyobject::{IdProtocol, PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue};
+use crate::vm::VirtualMachine;
+
+pub type PyGenericAliasRef = PyRef<PyGenericAlias>;
+
+/// Represent a parameterized generic, such as `list[int]`.
+///
+/// Calling it creates an instance of the origin class, and it can be used
+/// as a base class in place of its origin.
+#[pyclass(name = "GenericAlias")]
+#[derive(Debug)]
+pub struct PyGenericAlias {
+    origin: PyObjectRef,
+    args: PyTupleRef,
+}
+
+impl PyValue for PyGenericAlias {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.ctx.types.generic_alias_type.clone()
+    }
+}
+
+#[pyimpl]
+impl PyGenericAlias {
+    pub fn new(origin: PyObjectRef, args: PyObjectRef, vm: &VirtualMachine) -> PyGenericAlias {
+        let args = match args.downcast::<PyTuple>() {
+            Ok(args) => args,
+            Err(arg) => PyTuple::from(vec![arg]).into_ref(vm),
+        };
+        PyGenericAlias { origin, args }
+    }
+
+    #[pymethod(name = "__new__")]
+    fn tp_new(
+        cls: PyClassRef,
+        origin: PyObjectRef,
+        args: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyGenericAliasRef> {
+        PyGenericAlias::new(origin, args, vm).into_ref_with_type(vm, cls)
+    }
+
+    #[pyproperty(name = "__origin__")]
+    fn origin(&self, _vm: &VirtualMachine) -> PyObjectRef {
+        self.origin.clone()
+    }
+
+    #[pyproperty(name = "__args__")]
+    fn args(&self, _vm: &VirtualMachine) -> PyTupleRef {
+        self.args.clone()
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        let args = self
+            .args
+            .elements
+            .iter()
+            .map(|arg| repr_item(arg, vm))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(format!(
+            "{}[{}]",
+            repr_item(&self.origin, vm)?,
+            if args.is_empty() {
+                "()".to_string()
+            } else {
+                args.join(", ")
+            }
+        ))
+    }
+
+    #[pymethod(name = "__call__")]
+    fn call(&self, args: Args, kwargs: KwArgs, vm: &VirtualMachine) -> PyResult {
+        vm.invoke(&self.origin, (&args, &kwargs))
+    }
+
+    #[pymethod(name = "__mro_entries__")]
+    fn mro_entries(&self, _bases: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
+        vm.ctx.new_tuple(vec![self.origin.clone()])
+    }
+
+    #[pymethod(name = "__getattr__")]
+    fn getattr(&self, name: PyStringRef, vm: &VirtualMachine) -> PyResult {
+        vm.get_attribute(self.origin.clone(), name)
+    }
+
+    #[pymethod(name = "__eq__")]
+    fn eq(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        match other.payload::<PyGenericAlias>() {
+            Some(other) => {
+                let equal = self.origin.is(&other.origin)
+                    && seq_equal(
+                        vm,
+                        &self.args.elements.as_slice(),
+                        &other.args.elements.as_slice(),
+                    )?;
+                Ok(vm.new_bool(equal))
+            }
+            None => Ok(vm.ctx.not_implemented()),
+        }
+    }
+
+    #[pymethod(name = "__hash__")]
+    fn hash(&self, vm: &VirtualMachine) -> PyResult<pyhash::PyHash> {
+        Ok(vm._hash(&self.origin)? ^ pyhash::hash_iter(self.args.elements.iter(), vm)?)
+    }
+
+    #[pymethod(name = "__instancecheck__")]
+    fn instance_check(&self, _obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+        Err(vm.new_type_error(
+            "isinstance() argument 2 cannot be a parameterized generic".to_string(),
+        ))
+    }
+
+    #[pymethod(name = "__subclasscheck__")]
+    fn subclass_check(&self, _obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+        Err(vm.new_type_error(
+            "issubclass() argument 2 cannot be a parameterized generic".to_string(),
+        ))
+    }
+}
+
+/// Like `repr()`, but classes are shown by their (qualified) name.
+fn repr_item(obj: &PyObjectRef, vm: &VirtualMachine) -> PyResult<String> {
+    if obj.is(&vm.ctx.ellipsis()) {
+        return Ok("...".to_string());
+    }
+    if objtype::isinstance(obj, &vm.ctx.type_type()) {
+        let qualname = vm.to_str(&vm.get_attribute(obj.clone(), "__qualname__")?)?;
+        let module = vm.get_attribute(obj.clone(), "__module__")?;
+        if let Ok(module) = module.downcast::<PyString>() {
+            if module.as_str() != "builtins" {
+                return Ok(format!("{}.{}", module.as_str(), qualname.as_str()));
+            }
+        }
+        return Ok(qualname.as_str().to_string());
+    }
+    Ok(vm.to_repr(obj)?.as_str().to_string())
+}
+
+/// `cls.__class_getitem__(args)` for the builtin generic classes.
+pub fn generic_class_getitem(
+    cls: PyClassRef,
+    args: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyGenericAlias {
+    PyGenericAlias::new(cls.into_object(), args, vm)
+}
+
+pub fn init(context: &PyContext) {
+    PyGenericAlias::extend_class(context, &context.types.generic_alias_type);
+    for cls in &[
+        &context.types.list_type,
+        &context.types.dict_type,
+        &context.types.tuple_type,
+        &context.types.set_type,
+        &context.types.frozenset_type,
+    ] {
+        cls.set_str_attr(
+            "__class_getitem__",
+            context.new_classmethod(generic_class_getitem),
+        );
+    }
+}
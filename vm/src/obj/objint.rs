@@ -1,3 +1,4 @@
+use std::cmp;
 use std::fmt;
 use std::str;
 
@@ -6,20 +7,20 @@ use num_integer::Integer;
 use num_traits::{Num, One, Pow, Signed, ToPrimitive, Zero};
 
 use crate::format::FormatSpec;
-use crate::function::{KwArgs, OptionalArg, PyFuncArgs};
+use crate::function::{KwArgs, OptionalArg, OptionalOption, PyFuncArgs};
 use crate::obj::objtype::PyClassRef;
 use crate::pyhash;
 use crate::pyobject::{
-    IdProtocol, IntoPyObject, PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue,
-    TryFromObject, TypeProtocol,
+    IntoPyObject, PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue, TryFromObject,
+    TypeProtocol,
 };
 use crate::vm::VirtualMachine;
 
 use super::objbyteinner::PyByteInner;
 use super::objbytes::PyBytes;
-use super::objint;
-use super::objstr::{PyString, PyStringRef};
+use super::objstr::{self, PyString, PyStringRef};
 use super::objtype;
+use crate::stdlib::unicodedata::to_decimal;
 
 /// int(x=0) -> integer
 /// int(x, base=10) -> integer
@@ -87,7 +88,7 @@ macro_rules! impl_try_from_object_int {
     ($(($t:ty, $to_prim:ident),)*) => {$(
         impl TryFromObject for $t {
             fn try_from_object(vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<Self> {
-                match PyRef::<PyInt>::try_from_object(vm, obj)?.value.$to_prim() {
+                match vm.to_index(&obj)?.value.$to_prim() {
                     Some(value) => Ok(value),
                     None => Err(
                         vm.new_overflow_error(concat!(
@@ -478,31 +479,29 @@ impl PyInt {
     #[pymethod(name = "__round__")]
     fn round(
         zelf: PyRef<Self>,
-        _precision: OptionalArg<PyObjectRef>,
-        _vm: &VirtualMachine,
+        precision: OptionalOption<PyObjectRef>,
+        vm: &VirtualMachine,
     ) -> PyResult<PyIntRef> {
-        let _ndigits = match _precision {
-            OptionalArg::Missing => None,
-            OptionalArg::Present(ref value) => {
-                if !_vm.get_none().is(value) {
-                    if !objtype::isinstance(value, &_vm.ctx.int_type()) {
-                        return Err(_vm.new_type_error(format!(
-                            "'{}' object cannot be interpreted as an integer",
-                            value.class().name
-                        )));
-                    };
-                    // Only accept int type _ndigits
-                    let _ndigits = objint::get_value(value);
-                    Some(_ndigits)
-                } else {
-                    return Err(_vm.new_type_error(format!(
-                        "'{}' object cannot be interpreted as an integer",
-                        value.class().name
-                    )));
-                }
-            }
+        let ndigits = match precision.flat_option() {
+            None => return Ok(zelf),
+            Some(ref value) => vm.to_index(value)?,
+        };
+        let ndigits = ndigits.as_bigint();
+        if !ndigits.is_negative() {
+            return Ok(zelf);
+        }
+
+        // round half to even at the 10 ** -ndigits position
+        let power = match (-ndigits).to_u32() {
+            Some(exponent) => BigInt::from(10).pow(exponent),
+            // no int can have that many digits
+            None => return Ok(PyInt::new(0).into_ref(vm)),
         };
-        Ok(zelf)
+        let (quotient, remainder) = zelf.value.div_mod_floor(&power);
+        let twice_remainder = remainder * 2;
+        let round_up = twice_remainder > power || (twice_remainder == power && quotient.is_odd());
+        let quotient = if round_up { quotient + 1 } else { quotient };
+        Ok(PyInt::new(quotient * power).into_ref(vm))
     }
 
     #[pymethod(name = "__int__")]
@@ -554,8 +553,9 @@ impl PyInt {
 
     #[pymethod(name = "__format__")]
     fn format(&self, spec: PyStringRef, vm: &VirtualMachine) -> PyResult<String> {
-        let format_spec = FormatSpec::parse(&spec.value);
-        match format_spec.format_int(&self.value) {
+        match FormatSpec::parse(&spec.value)
+            .and_then(|format_spec| format_spec.format_int(&self.value))
+        {
             Ok(string) => Ok(string),
             Err(err) => Err(vm.new_value_error(err.to_string())),
         }
@@ -746,97 +746,112 @@ pub fn to_int(vm: &VirtualMachine, obj: &PyObjectRef, base: u32) -> PyResult<Big
 
     match_class!(obj.clone(),
         string @ PyString => {
-            let s = string.value.as_str().trim();
-            str_to_int(vm, s, base)
+            parse_int_literal(string.as_str(), base)
+                .ok_or_else(|| invalid_literal(vm, obj, base))
         },
         bytes @ PyBytes => {
-            let bytes = bytes.get_value();
-            let s = std::str::from_utf8(bytes)
-                .map(|s| s.trim())
-                .map_err(|e| vm.new_value_error(format!("utf8 decode error: {}", e)))?;
-            str_to_int(vm, s, base)
+            std::str::from_utf8(bytes.get_value())
+                .ok()
+                .and_then(|s| parse_int_literal(s, base))
+                .ok_or_else(|| invalid_literal(vm, obj, base))
         },
         obj => {
-            let method = vm.get_method_or_type_error(obj.clone(), "__int__", || {
+            if let Some(method) = vm.get_method(obj.clone(), "__int__") {
+                let result = vm.invoke(&method?, PyFuncArgs::default())?;
+                return match result.payload::<PyInt>() {
+                    Some(int_obj) => Ok(int_obj.as_bigint().clone()),
+                    None => Err(vm.new_type_error(format!(
+                        "__int__ returned non-int (type {})", result.class().name))),
+                };
+            }
+            if objtype::class_has_attr(&obj.class(), "__index__") {
+                return Ok(vm.to_index(&obj)?.as_bigint().clone());
+            }
+            // Integral types that aren't int, like Fraction, only need to define __trunc__.
+            let method = vm.get_method_or_type_error(obj.clone(), "__trunc__", || {
                 format!("int() argument must be a string or a number, not '{}'", obj.class().name)
             })?;
             let result = vm.invoke(&method, PyFuncArgs::default())?;
-            match result.payload::<PyInt>() {
-                Some(int_obj) => Ok(int_obj.as_bigint().clone()),
-                None => Err(vm.new_type_error(format!(
-                    "TypeError: __int__ returned non-int (type '{}')", result.class().name))),
+            if let Some(int_obj) = result.payload::<PyInt>() {
+                return Ok(int_obj.as_bigint().clone());
             }
+            if !objtype::class_has_attr(&result.class(), "__index__") {
+                return Err(vm.new_type_error(format!(
+                    "__trunc__ returned non-Integral (type {})", result.class().name)));
+            }
+            Ok(vm.to_index(&result)?.as_bigint().clone())
         }
     )
 }
 
-fn str_to_int(vm: &VirtualMachine, literal: &str, mut base: u32) -> PyResult<BigInt> {
-    let mut buf = validate_literal(vm, literal, base)?;
-    let is_signed = buf.starts_with('+') || buf.starts_with('-');
-    let radix_range = if is_signed { 1..3 } else { 0..2 };
-    let radix_candidate = buf.get(radix_range.clone());
-
-    // try to find base
-    if let Some(radix_candidate) = radix_candidate {
-        if let Some(matched_radix) = detect_base(&radix_candidate) {
-            if base != 0 && base != matched_radix {
-                return Err(invalid_literal(vm, literal, base));
-            } else {
-                base = matched_radix;
-            }
+/// Parse an integer literal the way `int(literal, base)` does: surrounding whitespace, a sign,
+/// a `0x`/`0o`/`0b` prefix matching the base (or selecting it when `base` is 0) and
+/// single underscores between digits are accepted.
+fn parse_int_literal(literal: &str, base: u32) -> Option<BigInt> {
+    let literal = literal.trim_matches(objstr::is_space);
+    let (negative, unsigned) = if literal.starts_with('-') {
+        (true, &literal[1..])
+    } else if literal.starts_with('+') {
+        (false, &literal[1..])
+    } else {
+        (false, literal)
+    };
 
-            buf.drain(radix_range);
+    let prefix_base = unsigned.get(..2).and_then(|prefix| match prefix {
+        "0x" | "0X" => Some(16),
+        "0o" | "0O" => Some(8),
+        "0b" | "0B" => Some(2),
+        _ => None,
+    });
+    let (base, digits, has_prefix) = match prefix_base {
+        Some(prefix_base) if base == 0 || base == prefix_base => {
+            (prefix_base, &unsigned[2..], true)
+        }
+        _ if base == 0 => {
+            // decimal literals can't have leading zeros, unless the value is zero
+            if unsigned.starts_with('0') && unsigned.chars().any(|c| c != '0' && c != '_') {
+                return None;
+            }
+            (10, unsigned, false)
         }
-    }
+        _ => (base, unsigned, false),
+    };
 
-    // base still not found, try to use default
-    if base == 0 {
-        if buf.starts_with('0') {
-            return Err(invalid_literal(vm, literal, base));
+    // an underscore may follow the prefix or a digit, and must be followed by a digit
+    let mut underscore_allowed = has_prefix;
+    let mut buf = String::with_capacity(digits.len());
+    for c in digits.chars() {
+        if c == '_' {
+            if !underscore_allowed {
+                return None;
+            }
+            underscore_allowed = false;
+        } else {
+            // non-ASCII decimal digits (e.g. Arabic-Indic) count like their ASCII forms
+            let c = to_decimal(c).map_or(c, |d| std::char::from_digit(d, 10).unwrap());
+            if !c.is_digit(base) {
+                return None;
+            }
+            buf.push(c);
+            underscore_allowed = true;
         }
-
-        base = 10;
     }
-
-    BigInt::from_str_radix(&buf, base).map_err(|_err| invalid_literal(vm, literal, base))
-}
-
-fn validate_literal(vm: &VirtualMachine, literal: &str, base: u32) -> PyResult<String> {
-    if literal.starts_with('_') || literal.ends_with('_') {
-        return Err(invalid_literal(vm, literal, base));
+    if buf.is_empty() || digits.ends_with('_') {
+        return None;
     }
 
-    let mut buf = String::with_capacity(literal.len());
-    let mut last_tok = None;
-    for c in literal.chars() {
-        if !(c.is_ascii_alphanumeric() || c == '_' || c == '+' || c == '-') {
-            return Err(invalid_literal(vm, literal, base));
-        }
-
-        if c == '_' && Some(c) == last_tok {
-            return Err(invalid_literal(vm, literal, base));
-        }
-
-        last_tok = Some(c);
-        buf.push(c);
-    }
-
-    Ok(buf)
-}
-
-fn detect_base(literal: &str) -> Option<u32> {
-    match literal {
-        "0x" | "0X" => Some(16),
-        "0o" | "0O" => Some(8),
-        "0b" | "0B" => Some(2),
-        _ => None,
-    }
+    let value = BigInt::from_str_radix(&buf, base).ok()?;
+    Some(if negative { -value } else { value })
 }
 
-fn invalid_literal(vm: &VirtualMachine, literal: &str, base: u32) -> PyObjectRef {
+fn invalid_literal(vm: &VirtualMachine, literal: &PyObjectRef, base: u32) -> PyObjectRef {
+    let repr = match vm.to_repr(literal) {
+        Ok(repr) => repr.as_str().to_string(),
+        Err(err) => return err,
+    };
     vm.new_value_error(format!(
-        "invalid literal for int() with base {}: '{}'",
-        base, literal
+        "invalid literal for int() with base {}: {}",
+        base, repr
     ))
 }
 
@@ -854,30 +869,69 @@ fn div_ints(vm: &VirtualMachine, i1: &BigInt, i2: &BigInt) -> PyResult {
     if i2.is_zero() {
         return Err(vm.new_zero_division_error("integer division by zero".to_string()));
     }
+    Ok(vm.ctx.new_float(true_divide(i1, i2).ok_or_else(|| {
+        vm.new_overflow_error("integer division result too large for a float".to_string())
+    })?))
+}
+
+/// Divide two ints, rounding correctly to the nearest float like CPython's
+/// long_true_divide. Returns None if the result is too large for a float.
+fn true_divide(a: &BigInt, b: &BigInt) -> Option<f64> {
+    const MANT_DIG: isize = std::f64::MANTISSA_DIGITS as isize;
+    const MAX_EXP: isize = std::f64::MAX_EXP as isize;
+    const MIN_EXP: isize = std::f64::MIN_EXP as isize;
+
+    let negative = a.is_negative() != b.is_negative();
+    let (a, b) = (a.abs(), b.abs());
+    let (a_bits, b_bits) = (a.bits() as isize, b.bits() as isize);
+
+    // Both operands are exactly representable, so one rounding is all it takes.
+    if a_bits <= MANT_DIG && b_bits <= MANT_DIG {
+        let result = a.to_f64().unwrap() / b.to_f64().unwrap();
+        return Some(if negative { -result } else { result });
+    }
 
-    if let (Some(f1), Some(f2)) = (i1.to_f64(), i2.to_f64()) {
-        Ok(vm.ctx.new_float(f1 / f2))
-    } else {
-        let (quotient, mut rem) = i1.div_rem(i2);
-        let mut divisor = i2.clone();
-
-        if let Some(quotient) = quotient.to_f64() {
-            let rem_part = loop {
-                if rem.is_zero() {
-                    break 0.0;
-                } else if let (Some(rem), Some(divisor)) = (rem.to_f64(), divisor.to_f64()) {
-                    break rem / divisor;
-                } else {
-                    // try with smaller numbers
-                    rem /= 2;
-                    divisor /= 2;
-                }
-            };
+    let diff = a_bits - b_bits;
+    if diff > MAX_EXP {
+        return None;
+    } else if a.is_zero() || diff < MIN_EXP - MANT_DIG - 1 {
+        return Some(if negative { -0.0 } else { 0.0 });
+    }
 
-            Ok(vm.ctx.new_float(quotient + rem_part))
-        } else {
-            Err(vm.new_overflow_error("int too large to convert to float".to_string()))
-        }
+    // Scale a so that the quotient has two or three bits more than the
+    // mantissa, and remember whether anything was lost on the way.
+    let shift = cmp::max(diff, MIN_EXP) - MANT_DIG - 2;
+    let mut inexact = false;
+    let x = if shift <= 0 {
+        a << (-shift as usize)
+    } else {
+        let x = &a >> (shift as usize);
+        inexact = (&x << (shift as usize)) != a;
+        x
+    };
+    let (q, r) = x.div_rem(&b);
+    inexact |= !r.is_zero();
+
+    // Round half to even at the precision the result has, which is less than
+    // the mantissa for subnormal results.
+    let mut q = q.to_u64().unwrap();
+    let q_bits = 64 - q.leading_zeros() as isize;
+    let extra_bits = cmp::max(q_bits, MIN_EXP - shift) - MANT_DIG;
+    let mask = 1u64 << (extra_bits - 1);
+    let low = q | inexact as u64;
+    q = if low & mask != 0 && low & (3 * mask - 1) != 0 {
+        low + mask
+    } else {
+        low
+    } & !(2 * mask - 1);
+
+    // Both steps are exact, since q has been rounded to fit.
+    let half = shift / 2;
+    let result = q as f64 * 2f64.powi(half as i32) * 2f64.powi((shift - half) as i32);
+    if result.is_infinite() {
+        None
+    } else {
+        Some(if negative { -result } else { result })
     }
 }
 
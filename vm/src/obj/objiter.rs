@@ -17,14 +17,37 @@ use super::objtype::PyClassRef;
  * in the vm when a for loop is entered. Next, it is used when the builtin
  * function 'iter' is called.
  */
+/// Whether `iter()` would accept the object, without calling anything.
+pub fn is_iterable(obj: &PyObjectRef) -> bool {
+    let cls = obj.class();
+    objtype::class_has_attr(&cls, "__iter__") || objtype::class_has_attr(&cls, "__getitem__")
+}
+
 pub fn get_iter(vm: &VirtualMachine, iter_target: &PyObjectRef) -> PyResult {
+    let not_iterable = || {
+        vm.new_type_error(format!(
+            "'{}' object is not iterable",
+            iter_target.class().name
+        ))
+    };
     if let Some(method_or_err) = vm.get_method(iter_target.clone(), "__iter__") {
         let method = method_or_err?;
-        vm.invoke(&method, vec![])
+        // `__iter__ = None` marks a class as explicitly not iterable
+        if vm.is_none(&method) {
+            return Err(not_iterable());
+        }
+        let iter = vm.invoke(&method, vec![])?;
+        if !objtype::class_has_attr(&iter.class(), "__next__") {
+            return Err(vm.new_type_error(format!(
+                "iter() returned non-iterator of type '{}'",
+                iter.class().name
+            )));
+        }
+        Ok(iter)
     } else {
-        vm.get_method_or_type_error(iter_target.clone(), "__getitem__", || {
-            format!("Cannot iterate over {}", iter_target.class().name)
-        })?;
+        if !objtype::class_has_attr(&iter_target.class(), "__getitem__") {
+            return Err(not_iterable());
+        }
         let obj_iterator = PySequenceIterator {
             position: Cell::new(0),
             obj: iter_target.clone(),
@@ -75,7 +98,7 @@ pub fn get_all(vm: &VirtualMachine, iter_obj: &PyObjectRef) -> PyResult<Vec<PyOb
 
 pub fn new_stop_iteration(vm: &VirtualMachine) -> PyObjectRef {
     let stop_iteration_type = vm.ctx.exceptions.stop_iteration.clone();
-    vm.new_exception(stop_iteration_type, "End of iterator".to_string())
+    vm.new_empty_exception(stop_iteration_type).unwrap()
 }
 
 #[pyclass]
@@ -1,5 +1,6 @@
 use std::cell::{Cell, RefCell};
 use std::fmt;
+use std::{cmp, mem};
 
 use std::ops::Range;
 
@@ -724,45 +725,48 @@ fn list_new(
     PyList::from(elements).into_ref_with_type(vm, cls)
 }
 
-fn quicksort(
+/// Sort `values` by `keys` with a bottom-up merge sort, which is stable as
+/// Python requires, comparing keys with `<` only.
+fn merge_sort(
     vm: &VirtualMachine,
-    keys: &mut [PyObjectRef],
-    values: &mut [PyObjectRef],
+    keys: &mut Vec<PyObjectRef>,
+    values: &mut Vec<PyObjectRef>,
 ) -> PyResult<()> {
     let len = values.len();
-    if len >= 2 {
-        let pivot = partition(vm, keys, values)?;
-        quicksort(vm, &mut keys[0..pivot], &mut values[0..pivot])?;
-        quicksort(vm, &mut keys[pivot + 1..len], &mut values[pivot + 1..len])?;
-    }
-    Ok(())
-}
-
-fn partition(
-    vm: &VirtualMachine,
-    keys: &mut [PyObjectRef],
-    values: &mut [PyObjectRef],
-) -> PyResult<usize> {
-    let len = values.len();
-    let pivot = len / 2;
-
-    values.swap(pivot, len - 1);
-    keys.swap(pivot, len - 1);
-
-    let mut store_idx = 0;
-    for i in 0..len - 1 {
-        let result = vm._lt(keys[i].clone(), keys[len - 1].clone())?;
-        let boolval = objbool::boolval(vm, result)?;
-        if boolval {
-            values.swap(i, store_idx);
-            keys.swap(i, store_idx);
-            store_idx += 1;
+    let mut sorted_keys = Vec::with_capacity(len);
+    let mut sorted_values = Vec::with_capacity(len);
+    let mut width = 1;
+    while width < len {
+        for start in (0..len).step_by(2 * width) {
+            let mid = cmp::min(start + width, len);
+            let end = cmp::min(start + 2 * width, len);
+            let (mut left, mut right) = (start, mid);
+            while left < mid && right < end {
+                // Only take from the right run when it's strictly smaller, so
+                // that equal elements keep their order.
+                let result = vm._lt(keys[right].clone(), keys[left].clone())?;
+                let take_right = objbool::boolval(vm, result)?;
+                let index = if take_right { right } else { left };
+                sorted_keys.push(keys[index].clone());
+                sorted_values.push(values[index].clone());
+                if take_right {
+                    right += 1;
+                } else {
+                    left += 1;
+                }
+            }
+            sorted_keys.extend_from_slice(&keys[left..mid]);
+            sorted_values.extend_from_slice(&values[left..mid]);
+            sorted_keys.extend_from_slice(&keys[right..end]);
+            sorted_values.extend_from_slice(&values[right..end]);
         }
+        mem::swap(keys, &mut sorted_keys);
+        mem::swap(values, &mut sorted_values);
+        sorted_keys.clear();
+        sorted_values.clear();
+        width *= 2;
     }
-
-    values.swap(store_idx, len - 1);
-    keys.swap(store_idx, len - 1);
-    Ok(store_idx)
+    Ok(())
 }
 
 fn do_sort(
@@ -780,8 +784,13 @@ fn do_sort(
         });
     }
 
-    quicksort(vm, &mut keys, values)?;
-
+    // Reversing before and after the sort keeps equal elements in their
+    // original order for a reversed sort too.
+    if reverse {
+        keys.reverse();
+        values.reverse();
+    }
+    merge_sort(vm, &mut keys, values)?;
     if reverse {
         values.reverse();
     }
@@ -1,8 +1,14 @@
+use super::objdict::PyDictRef;
+use super::objiter;
 use super::objstr::PyStringRef;
-use super::objtype::{self, PyClassRef};
-use crate::pyobject::{PyClassImpl, PyContext, PyRef, PyResult, PyValue};
+use super::objtype::PyClassRef;
+use crate::function::OptionalArg;
+use crate::pyobject::{
+    ItemProtocol, PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue,
+};
 use crate::vm::VirtualMachine;
 
+/// A read-only view of a class's own attributes, as returned by `type.__dict__`.
 #[pyclass]
 #[derive(Debug)]
 pub struct PyMappingProxy {
@@ -25,15 +31,62 @@ impl PyMappingProxy {
 
     #[pymethod(name = "__getitem__")]
     pub fn getitem(&self, key: PyStringRef, vm: &VirtualMachine) -> PyResult {
-        if let Some(value) = objtype::class_get_attr(&self.class, key.as_str()) {
-            return Ok(value);
+        if let Some(value) = self.class.attributes.borrow().get(key.as_str()) {
+            return Ok(value.clone());
         }
         Err(vm.new_key_error(key.into_object()))
     }
 
+    #[pymethod(name = "get")]
+    fn get(
+        &self,
+        key: PyStringRef,
+        default: OptionalArg<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyObjectRef {
+        match self.class.attributes.borrow().get(key.as_str()) {
+            Some(value) => value.clone(),
+            None => default.unwrap_or_else(|| vm.get_none()),
+        }
+    }
+
     #[pymethod(name = "__contains__")]
     pub fn contains(&self, attr: PyStringRef, _vm: &VirtualMachine) -> bool {
-        objtype::class_has_attr(&self.class, attr.as_str())
+        self.class.attributes.borrow().contains_key(attr.as_str())
+    }
+
+    #[pymethod(name = "__len__")]
+    fn len(&self, _vm: &VirtualMachine) -> usize {
+        self.class.attributes.borrow().len()
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(&self, vm: &VirtualMachine) -> PyResult {
+        objiter::get_iter(vm, self.copy(vm)?.as_object())
+    }
+
+    #[pymethod(name = "copy")]
+    fn copy(&self, vm: &VirtualMachine) -> PyResult<PyDictRef> {
+        let dict = vm.ctx.new_dict();
+        for (key, value) in self.class.attributes.borrow().iter() {
+            dict.set_item(key.as_str(), value.clone(), vm)?;
+        }
+        Ok(dict)
+    }
+
+    #[pymethod(name = "keys")]
+    fn keys(&self, vm: &VirtualMachine) -> PyResult {
+        vm.call_method(self.copy(vm)?.as_object(), "keys", vec![])
+    }
+
+    #[pymethod(name = "values")]
+    fn values(&self, vm: &VirtualMachine) -> PyResult {
+        vm.call_method(self.copy(vm)?.as_object(), "values", vec![])
+    }
+
+    #[pymethod(name = "items")]
+    fn items(&self, vm: &VirtualMachine) -> PyResult {
+        vm.call_method(self.copy(vm)?.as_object(), "items", vec![])
     }
 }
 
@@ -1,6 +1,10 @@
 use crate::obj::objbyteinner::try_as_byte;
+use crate::obj::objbytes::PyBytes;
 use crate::obj::objtype::PyClassRef;
-use crate::pyobject::{PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue};
+use crate::pyobject::{
+    PyClassImpl, PyContext, PyObjectRef, PyRef, PyResult, PyValue, TypeProtocol,
+};
+use crate::stdlib::array::PyArray;
 use crate::vm::VirtualMachine;
 
 #[pyclass(name = "memoryview")]
@@ -23,6 +27,12 @@ impl PyMemoryView {
         bytes_object: PyObjectRef,
         vm: &VirtualMachine,
     ) -> PyResult<PyMemoryViewRef> {
+        if try_as_byte(&bytes_object).is_none() {
+            return Err(vm.new_type_error(format!(
+                "memoryview: a bytes-like object is required, not '{}'",
+                bytes_object.class().name
+            )));
+        }
         PyMemoryView {
             obj_ref: bytes_object.clone(),
         }
@@ -34,6 +44,49 @@ impl PyMemoryView {
         self.obj_ref.clone()
     }
 
+    #[pyproperty]
+    fn format(&self, _vm: &VirtualMachine) -> String {
+        match self.obj_ref.payload::<PyArray>() {
+            Some(array) => array.get_typecode().to_string(),
+            None => "B".to_string(),
+        }
+    }
+
+    #[pyproperty]
+    fn itemsize(&self, _vm: &VirtualMachine) -> usize {
+        match self.obj_ref.payload::<PyArray>() {
+            Some(array) => array.get_itemsize(),
+            None => 1,
+        }
+    }
+
+    #[pyproperty]
+    fn nbytes(&self, _vm: &VirtualMachine) -> usize {
+        self.get_obj_value().map_or(0, |bytes| bytes.len())
+    }
+
+    #[pyproperty]
+    fn readonly(&self, _vm: &VirtualMachine) -> bool {
+        self.obj_ref.payload_is::<PyBytes>()
+    }
+
+    #[pymethod]
+    fn tobytes(&self, _vm: &VirtualMachine) -> Vec<u8> {
+        self.get_obj_value().unwrap_or_default()
+    }
+
+    #[pymethod]
+    fn tolist(&self, vm: &VirtualMachine) -> PyResult {
+        // Items are read with the format of the underlying object.
+        let items = vm.extract_elements(&self.obj_ref)?;
+        Ok(vm.ctx.new_list(items))
+    }
+
+    #[pymethod(name = "__len__")]
+    fn len(&self, vm: &VirtualMachine) -> PyResult {
+        vm.call_method(&self.obj_ref, "__len__", vec![])
+    }
+
     #[pymethod(name = "__getitem__")]
     fn getitem(&self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         vm.call_method(&self.obj_ref, "__getitem__", vec![needle])
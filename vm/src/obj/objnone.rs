@@ -94,8 +94,6 @@ impl PyNoneRef {
             } else {
                 Ok(attr)
             }
-        } else if let Some(getter) = class_get_attr(&cls, "__getattr__") {
-            vm.invoke(&getter, vec![self.into_object(), name.into_object()])
         } else {
             Err(vm.new_attribute_error(format!("{} has no attribute '{}'", self.as_object(), name)))
         }
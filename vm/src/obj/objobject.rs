@@ -1,3 +1,4 @@
+use super::objbool;
 use super::objdict::PyDictRef;
 use super::objlist::PyList;
 use super::objstr::PyStringRef;
@@ -24,6 +25,7 @@ impl PyValue for PyInstance {
 pub fn new_instance(vm: &VirtualMachine, mut args: PyFuncArgs) -> PyResult {
     // more or less __new__ operator
     let cls = PyClassRef::try_from_object(vm, args.shift())?;
+    check_abstract_methods(&cls, vm)?;
     let dict = if cls.is(&vm.ctx.object()) {
         None
     } else {
@@ -32,12 +34,58 @@ pub fn new_instance(vm: &VirtualMachine, mut args: PyFuncArgs) -> PyResult {
     Ok(PyObject::new(PyInstance, cls, dict))
 }
 
-fn object_eq(_zelf: PyObjectRef, _other: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
-    vm.ctx.not_implemented()
+/// Refuse to instantiate a class that still has abstract methods, as
+/// recorded in `__abstractmethods__` by `abc.ABCMeta`.
+fn check_abstract_methods(cls: &PyClassRef, vm: &VirtualMachine) -> PyResult<()> {
+    let abstract_methods = match objtype::class_get_attr(cls, "__abstractmethods__") {
+        Some(abstract_methods) => vm.extract_elements(&abstract_methods)?,
+        None => return Ok(()),
+    };
+    if abstract_methods.is_empty() {
+        return Ok(());
+    }
+    let mut names = abstract_methods
+        .iter()
+        .map(|name| Ok(vm.to_str(name)?.as_str().to_string()))
+        .collect::<PyResult<Vec<_>>>()?;
+    names.sort();
+    Err(vm.new_type_error(format!(
+        "Can't instantiate abstract class {} with abstract methods {}",
+        cls.name,
+        names.join(", ")
+    )))
 }
 
-fn object_ne(_zelf: PyObjectRef, _other: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
-    vm.ctx.not_implemented()
+/// Whether `obj` is marked with a true `__isabstractmethod__` attribute.
+pub fn is_abstract(obj: &PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    match vm.get_attribute(obj.clone(), "__isabstractmethod__") {
+        Ok(value) => objbool::boolval(vm, value),
+        Err(err) => {
+            if objtype::isinstance(&err, &vm.ctx.exceptions.attribute_error) {
+                Ok(false)
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+fn object_eq(zelf: PyObjectRef, other: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
+    if zelf.is(&other) {
+        vm.new_bool(true)
+    } else {
+        vm.ctx.not_implemented()
+    }
+}
+
+/// By default `!=` is the negation of `__eq__`, unless that isn't implemented.
+fn object_ne(zelf: PyObjectRef, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let eq = vm.call_method(&zelf, "__eq__", vec![other])?;
+    if eq.is(&vm.ctx.not_implemented()) {
+        Ok(eq)
+    } else {
+        objbool::not(vm, &eq)
+    }
 }
 
 fn object_lt(_zelf: PyObjectRef, _other: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
@@ -2,6 +2,7 @@
 
 */
 use crate::function::{IntoPyNativeFunc, OptionalArg};
+use crate::obj::objobject;
 use crate::obj::objtype::PyClassRef;
 use crate::pyobject::{
     IdProtocol, PyClassImpl, PyContext, PyObject, PyObjectRef, PyRef, PyResult, PyValue,
@@ -184,6 +185,18 @@ impl PyProperty {
         self.deleter.clone()
     }
 
+    #[pyproperty(name = "__isabstractmethod__")]
+    fn isabstractmethod(&self, vm: &VirtualMachine) -> PyResult<bool> {
+        for func in [&self.getter, &self.setter, &self.deleter].iter() {
+            if let Some(func) = func {
+                if objobject::is_abstract(func, vm)? {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
     fn doc_getter(&self, _vm: &VirtualMachine) -> Option<PyObjectRef> {
         self.doc.borrow().clone()
     }
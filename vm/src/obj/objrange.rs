@@ -114,10 +114,10 @@ type PyRangeRef = PyRef<PyRange>;
 #[pyimpl]
 impl PyRange {
     #[pymethod(name = "__new__")]
-    fn new(cls: PyClassRef, stop: PyIntRef, vm: &VirtualMachine) -> PyResult<PyRangeRef> {
+    fn new(cls: PyClassRef, stop: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyRangeRef> {
         PyRange {
             start: PyInt::new(BigInt::zero()).into_ref(vm),
-            stop: stop.clone(),
+            stop: vm.to_index(&stop)?,
             step: PyInt::new(BigInt::one()).into_ref(vm),
         }
         .into_ref_with_type(vm, cls)
@@ -125,12 +125,17 @@ impl PyRange {
 
     fn new_from(
         cls: PyClassRef,
-        start: PyIntRef,
-        stop: PyIntRef,
-        step: OptionalArg<PyIntRef>,
+        start: PyObjectRef,
+        stop: PyObjectRef,
+        step: OptionalArg<PyObjectRef>,
         vm: &VirtualMachine,
     ) -> PyResult<PyRangeRef> {
-        let step = step.unwrap_or_else(|| PyInt::new(BigInt::one()).into_ref(vm));
+        let start = vm.to_index(&start)?;
+        let stop = vm.to_index(&stop)?;
+        let step = match step {
+            OptionalArg::Present(step) => vm.to_index(&step)?,
+            OptionalArg::Missing => PyInt::new(BigInt::one()).into_ref(vm),
+        };
         if step.as_bigint().is_zero() {
             return Err(vm.new_value_error("range() arg 3 must not be zero".to_string()));
         }
@@ -396,10 +401,14 @@ impl TryFromObject for RangeIndex {
         match_class!(obj,
             i @ PyInt => Ok(RangeIndex::Int(i)),
             s @ PySlice => Ok(RangeIndex::Slice(s)),
-            obj => Err(vm.new_type_error(format!(
-                "sequence indices be integers or slices, not {}",
-                obj.class(),
-            )))
+            obj => if objtype::class_has_attr(&obj.class(), "__index__") {
+                Ok(RangeIndex::Int(vm.to_index(&obj)?))
+            } else {
+                Err(vm.new_type_error(format!(
+                    "range indices must be integers or slices, not {}",
+                    obj.class().name,
+                )))
+            }
         )
     }
 }
@@ -15,6 +15,7 @@ use super::objint::{PyInt, PyIntRef};
 use super::objlist::PyList;
 use super::objslice::{PySlice, PySliceRef};
 use super::objtuple::PyTuple;
+use super::objtype;
 
 pub trait PySliceableSequence {
     type Sliced;
@@ -171,10 +172,14 @@ impl TryFromObject for SequenceIndex {
         match_class!(obj,
             i @ PyInt => Ok(SequenceIndex::Int(i32::try_from_object(vm, i.into_object())?)),
             s @ PySlice => Ok(SequenceIndex::Slice(s)),
-            obj => Err(vm.new_type_error(format!(
-                "sequence indices be integers or slices, not {}",
-                obj.class(),
-            )))
+            obj => if objtype::class_has_attr(&obj.class(), "__index__") {
+                Ok(SequenceIndex::Int(i32::try_from_object(vm, obj)?))
+            } else {
+                Err(vm.new_type_error(format!(
+                    "sequence indices must be integers or slices, not {}",
+                    obj.class().name,
+                )))
+            }
         )
     }
 }
@@ -210,6 +215,7 @@ pub fn get_item(
     elements: &[PyObjectRef],
     subscript: PyObjectRef,
 ) -> PyResult {
+    let subscript = index_subscript(vm, subscript)?;
     if let Some(i) = subscript.payload::<PyInt>() {
         return match i.as_bigint().to_i32() {
             Some(value) => {
@@ -244,12 +250,26 @@ pub fn get_item(
         }
     } else {
         Err(vm.new_type_error(format!(
-            "indexing type {:?} with index {:?} is not supported (yet?)",
-            sequence, subscript
+            "{} indices must be integers or slices, not {}",
+            sequence.class().name,
+            subscript.class().name
         )))
     }
 }
 
+/// Turn a subscript that is neither an int nor a slice into an int through
+/// its `__index__` method, if it has one.
+pub fn index_subscript(vm: &VirtualMachine, subscript: PyObjectRef) -> PyResult {
+    if subscript.payload_is::<PyInt>()
+        || subscript.payload_is::<PySlice>()
+        || !objtype::class_has_attr(&subscript.class(), "__index__")
+    {
+        Ok(subscript)
+    } else {
+        Ok(vm.to_index(&subscript)?.into_object())
+    }
+}
+
 type DynPyIter<'a> = Box<dyn ExactSizeIterator<Item = &'a PyObjectRef> + 'a>;
 
 #[allow(clippy::len_without_is_empty)]
@@ -299,83 +319,42 @@ pub fn seq_equal(
     }
 }
 
-pub fn seq_lt(
+/// Compare sequences lexicographically: the first pair of items that differ
+/// decides, and if one sequence is a prefix of the other the lengths do.
+fn seq_cmp(
     vm: &VirtualMachine,
     zelf: &dyn SimpleSeq,
     other: &dyn SimpleSeq,
+    item_cmp: fn(&VirtualMachine, PyObjectRef, PyObjectRef) -> PyResult,
+    len_cmp: fn(&usize, &usize) -> bool,
 ) -> Result<bool, PyObjectRef> {
-    if zelf.len() == other.len() {
-        for (a, b) in Iterator::zip(zelf.iter(), other.iter()) {
-            let lt = vm._lt(a.clone(), b.clone())?;
-            let value = objbool::boolval(vm, lt)?;
-            if !value {
-                return Ok(false);
-            }
-        }
-        Ok(true)
-    } else {
-        // This case is more complicated because it can still return true if
-        // `zelf` is the head of `other` e.g. [1,2,3] < [1,2,3,4] should return true
-        let mut head = true; // true if `zelf` is the head of `other`
-
-        for (a, b) in Iterator::zip(zelf.iter(), other.iter()) {
-            let lt = vm._lt(a.clone(), b.clone())?;
-            let eq = vm._eq(a.clone(), b.clone())?;
-            let lt_value = objbool::boolval(vm, lt)?;
-            let eq_value = objbool::boolval(vm, eq)?;
-
-            if !lt_value && !eq_value {
-                return Ok(false);
-            } else if !eq_value {
-                head = false;
-            }
+    for (a, b) in Iterator::zip(zelf.iter(), other.iter()) {
+        if a.is(b) {
+            continue;
         }
-
-        if head {
-            Ok(zelf.len() < other.len())
-        } else {
-            Ok(true)
+        let eq = vm._eq(a.clone(), b.clone())?;
+        if !objbool::boolval(vm, eq)? {
+            let result = item_cmp(vm, a.clone(), b.clone())?;
+            return objbool::boolval(vm, result);
         }
     }
+    Ok(len_cmp(&zelf.len(), &other.len()))
 }
 
-pub fn seq_gt(
+pub fn seq_lt(
     vm: &VirtualMachine,
     zelf: &dyn SimpleSeq,
     other: &dyn SimpleSeq,
 ) -> Result<bool, PyObjectRef> {
-    if zelf.len() == other.len() {
-        for (a, b) in Iterator::zip(zelf.iter(), other.iter()) {
-            let gt = vm._gt(a.clone(), b.clone())?;
-            let value = objbool::boolval(vm, gt)?;
-            if !value {
-                return Ok(false);
-            }
-        }
-        Ok(true)
-    } else {
-        let mut head = true; // true if `other` is the head of `zelf`
-        for (a, b) in Iterator::zip(zelf.iter(), other.iter()) {
-            // This case is more complicated because it can still return true if
-            // `other` is the head of `zelf` e.g. [1,2,3,4] > [1,2,3] should return true
-            let gt = vm._gt(a.clone(), b.clone())?;
-            let eq = vm._eq(a.clone(), b.clone())?;
-            let gt_value = objbool::boolval(vm, gt)?;
-            let eq_value = objbool::boolval(vm, eq)?;
-
-            if !gt_value && !eq_value {
-                return Ok(false);
-            } else if !eq_value {
-                head = false;
-            }
-        }
+    seq_cmp(vm, zelf, other, VirtualMachine::_lt, PartialOrd::lt)
+}
 
-        if head {
-            Ok(zelf.len() > other.len())
-        } else {
-            Ok(true)
-        }
-    }
+pub fn seq_gt(
+    vm: &VirtualMachine,
+    zelf: &dyn SimpleSeq,
+    other: &dyn SimpleSeq,
+) -> Result<bool, PyObjectRef> {
+    seq_cmp(vm, zelf, other, VirtualMachine::_gt, PartialOrd::gt)
 }
 
 pub fn seq_ge(
@@ -383,7 +362,7 @@ pub fn seq_ge(
     zelf: &dyn SimpleSeq,
     other: &dyn SimpleSeq,
 ) -> Result<bool, PyObjectRef> {
-    Ok(seq_gt(vm, zelf, other)? || seq_equal(vm, zelf, other)?)
+    seq_cmp(vm, zelf, other, VirtualMachine::_ge, PartialOrd::ge)
 }
 
 pub fn seq_le(
@@ -391,7 +370,7 @@ pub fn seq_le(
     zelf: &dyn SimpleSeq,
     other: &dyn SimpleSeq,
 ) -> Result<bool, PyObjectRef> {
-    Ok(seq_lt(vm, zelf, other)? || seq_equal(vm, zelf, other)?)
+    seq_cmp(vm, zelf, other, VirtualMachine::_le, PartialOrd::le)
 }
 
 pub struct SeqMul<'a> {
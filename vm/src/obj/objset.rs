@@ -6,7 +6,7 @@ use std::cell::{Cell, RefCell};
 use std::fmt;
 
 use crate::dictdatatype;
-use crate::function::OptionalArg;
+use crate::function::{Args, OptionalArg};
 use crate::pyobject::{
     PyClassImpl, PyContext, PyIterable, PyObjectRef, PyRef, PyResult, PyValue, TryFromObject,
     TypeProtocol,
@@ -169,31 +169,21 @@ impl PySetInner {
         )
     }
 
-    fn union(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<PySetInner> {
+    fn union(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<PySetInner> {
         let mut set = self.clone();
-        for item in other.iter(vm)? {
-            set.add(&item?, vm)?;
-        }
-
+        set.update(others, vm)?;
         Ok(set)
     }
 
-    fn intersection(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<PySetInner> {
-        let mut set = PySetInner::default();
-        for item in other.iter(vm)? {
-            let obj = item?;
-            if self.contains(&obj, vm)? {
-                set.add(&obj, vm)?;
-            }
-        }
+    fn intersection(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<PySetInner> {
+        let mut set = self.clone();
+        set.intersection_update(others, vm)?;
         Ok(set)
     }
 
-    fn difference(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<PySetInner> {
+    fn difference(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<PySetInner> {
         let mut set = self.copy();
-        for item in other.iter(vm)? {
-            set.content.delete_if_exists(vm, &item?)?;
-        }
+        set.difference_update(others, vm)?;
         Ok(set)
     }
 
@@ -274,28 +264,38 @@ impl PySetInner {
         }
     }
 
-    fn update(&mut self, iterable: PyIterable, vm: &VirtualMachine) -> PyResult<()> {
-        for item in iterable.iter(vm)? {
-            self.add(&item?, vm)?;
+    fn update(&mut self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<()> {
+        for iterable in others {
+            for item in iterable.iter(vm)? {
+                self.add(&item?, vm)?;
+            }
         }
         Ok(())
     }
 
-    fn intersection_update(&mut self, iterable: PyIterable, vm: &VirtualMachine) -> PyResult<()> {
-        let temp_inner = self.copy();
-        self.clear();
-        for item in iterable.iter(vm)? {
-            let obj = item?;
-            if temp_inner.contains(&obj, vm)? {
-                self.add(&obj, vm)?;
+    fn intersection_update(
+        &mut self,
+        others: Args<PyIterable>,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        for iterable in others {
+            let temp_inner = self.copy();
+            self.clear();
+            for item in iterable.iter(vm)? {
+                let obj = item?;
+                if temp_inner.contains(&obj, vm)? {
+                    self.add(&obj, vm)?;
+                }
             }
         }
         Ok(())
     }
 
-    fn difference_update(&mut self, iterable: PyIterable, vm: &VirtualMachine) -> PyResult<()> {
-        for item in iterable.iter(vm)? {
-            self.content.delete_if_exists(vm, &item?)?;
+    fn difference_update(&mut self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<()> {
+        for iterable in others {
+            for item in iterable.iter(vm)? {
+                self.content.delete_if_exists(vm, &item?)?;
+            }
         }
         Ok(())
     }
@@ -379,23 +379,23 @@ impl PySet {
     }
 
     #[pymethod]
-    fn union(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<Self> {
+    fn union(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<Self> {
         Ok(Self {
-            inner: RefCell::new(self.inner.borrow().union(other, vm)?),
+            inner: RefCell::new(self.inner.borrow().union(others, vm)?),
         })
     }
 
     #[pymethod]
-    fn intersection(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<Self> {
+    fn intersection(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<Self> {
         Ok(Self {
-            inner: RefCell::new(self.inner.borrow().intersection(other, vm)?),
+            inner: RefCell::new(self.inner.borrow().intersection(others, vm)?),
         })
     }
 
     #[pymethod]
-    fn difference(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<Self> {
+    fn difference(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<Self> {
         Ok(Self {
-            inner: RefCell::new(self.inner.borrow().difference(other, vm)?),
+            inner: RefCell::new(self.inner.borrow().difference(others, vm)?),
         })
     }
 
@@ -423,7 +423,7 @@ impl PySet {
 
     #[pymethod(name = "__or__")]
     fn or(&self, other: SetIterable, vm: &VirtualMachine) -> PyResult<Self> {
-        self.union(other.iterable, vm)
+        self.union(vec![other.iterable].into(), vm)
     }
 
     #[pymethod(name = "__ror__")]
@@ -433,7 +433,7 @@ impl PySet {
 
     #[pymethod(name = "__and__")]
     fn and(&self, other: SetIterable, vm: &VirtualMachine) -> PyResult<Self> {
-        self.intersection(other.iterable, vm)
+        self.intersection(vec![other.iterable].into(), vm)
     }
 
     #[pymethod(name = "__rand__")]
@@ -443,7 +443,7 @@ impl PySet {
 
     #[pymethod(name = "__sub__")]
     fn sub(&self, other: SetIterable, vm: &VirtualMachine) -> PyResult<Self> {
-        self.difference(other.iterable, vm)
+        self.difference(vec![other.iterable].into(), vm)
     }
 
     #[pymethod(name = "__rsub__")]
@@ -508,19 +508,21 @@ impl PySet {
 
     #[pymethod(name = "__ior__")]
     fn ior(zelf: PyRef<Self>, iterable: SetIterable, vm: &VirtualMachine) -> PyResult {
-        zelf.inner.borrow_mut().update(iterable.iterable, vm)?;
+        zelf.inner
+            .borrow_mut()
+            .update(vec![iterable.iterable].into(), vm)?;
         Ok(zelf.as_object().clone())
     }
 
     #[pymethod]
-    fn update(&self, iterable: PyIterable, vm: &VirtualMachine) -> PyResult {
-        self.inner.borrow_mut().update(iterable, vm)?;
+    fn update(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult {
+        self.inner.borrow_mut().update(others, vm)?;
         Ok(vm.get_none())
     }
 
     #[pymethod]
-    fn intersection_update(&self, iterable: PyIterable, vm: &VirtualMachine) -> PyResult {
-        self.inner.borrow_mut().intersection_update(iterable, vm)?;
+    fn intersection_update(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult {
+        self.inner.borrow_mut().intersection_update(others, vm)?;
         Ok(vm.get_none())
     }
 
@@ -528,13 +530,13 @@ impl PySet {
     fn iand(zelf: PyRef<Self>, iterable: SetIterable, vm: &VirtualMachine) -> PyResult {
         zelf.inner
             .borrow_mut()
-            .intersection_update(iterable.iterable, vm)?;
+            .intersection_update(vec![iterable.iterable].into(), vm)?;
         Ok(zelf.as_object().clone())
     }
 
     #[pymethod]
-    fn difference_update(&self, iterable: PyIterable, vm: &VirtualMachine) -> PyResult {
-        self.inner.borrow_mut().difference_update(iterable, vm)?;
+    fn difference_update(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult {
+        self.inner.borrow_mut().difference_update(others, vm)?;
         Ok(vm.get_none())
     }
 
@@ -542,7 +544,7 @@ impl PySet {
     fn isub(zelf: PyRef<Self>, iterable: SetIterable, vm: &VirtualMachine) -> PyResult {
         zelf.inner
             .borrow_mut()
-            .difference_update(iterable.iterable, vm)?;
+            .difference_update(vec![iterable.iterable].into(), vm)?;
         Ok(zelf.as_object().clone())
     }
 
@@ -625,23 +627,23 @@ impl PyFrozenSet {
     }
 
     #[pymethod]
-    fn union(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<Self> {
+    fn union(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<Self> {
         Ok(Self {
-            inner: self.inner.union(other, vm)?,
+            inner: self.inner.union(others, vm)?,
         })
     }
 
     #[pymethod]
-    fn intersection(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<Self> {
+    fn intersection(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<Self> {
         Ok(Self {
-            inner: self.inner.intersection(other, vm)?,
+            inner: self.inner.intersection(others, vm)?,
         })
     }
 
     #[pymethod]
-    fn difference(&self, other: PyIterable, vm: &VirtualMachine) -> PyResult<Self> {
+    fn difference(&self, others: Args<PyIterable>, vm: &VirtualMachine) -> PyResult<Self> {
         Ok(Self {
-            inner: self.inner.difference(other, vm)?,
+            inner: self.inner.difference(others, vm)?,
         })
     }
 
@@ -669,7 +671,7 @@ impl PyFrozenSet {
 
     #[pymethod(name = "__or__")]
     fn or(&self, other: SetIterable, vm: &VirtualMachine) -> PyResult<Self> {
-        self.union(other.iterable, vm)
+        self.union(vec![other.iterable].into(), vm)
     }
 
     #[pymethod(name = "__ror__")]
@@ -679,7 +681,7 @@ impl PyFrozenSet {
 
     #[pymethod(name = "__and__")]
     fn and(&self, other: SetIterable, vm: &VirtualMachine) -> PyResult<Self> {
-        self.intersection(other.iterable, vm)
+        self.intersection(vec![other.iterable].into(), vm)
     }
 
     #[pymethod(name = "__rand__")]
@@ -689,7 +691,7 @@ impl PyFrozenSet {
 
     #[pymethod(name = "__sub__")]
     fn sub(&self, other: SetIterable, vm: &VirtualMachine) -> PyResult<Self> {
-        self.difference(other.iterable, vm)
+        self.difference(vec![other.iterable].into(), vm)
     }
 
     #[pymethod(name = "__rsub__")]
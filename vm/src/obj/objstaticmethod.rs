@@ -1,3 +1,5 @@
+use super::objobject;
+use super::objproperty::PropertyBuilder;
 use super::objtype::PyClassRef;
 use crate::pyobject::{PyContext, PyObjectRef, PyRef, PyResult, PyValue};
 use crate::vm::VirtualMachine;
@@ -29,6 +31,10 @@ impl PyStaticMethodRef {
     fn get(self, _inst: PyObjectRef, _owner: PyObjectRef, _vm: &VirtualMachine) -> PyResult {
         Ok(self.callable.clone())
     }
+
+    fn isabstractmethod(self, vm: &VirtualMachine) -> PyResult<bool> {
+        objobject::is_abstract(&self.callable, vm)
+    }
 }
 
 pub fn init(context: &PyContext) {
@@ -36,5 +42,9 @@ pub fn init(context: &PyContext) {
     extend_class!(context, staticmethod_type, {
         "__get__" => context.new_rustfunc(PyStaticMethodRef::get),
         "__new__" => context.new_rustfunc(PyStaticMethodRef::new),
+        "__isabstractmethod__" =>
+        PropertyBuilder::new(context)
+            .add_getter(PyStaticMethodRef::isabstractmethod)
+            .create(),
     });
 }
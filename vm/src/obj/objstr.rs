@@ -1,4 +1,3 @@
-extern crate unicode_categories;
 extern crate unicode_xid;
 
 use std::cell::Cell;
@@ -14,10 +13,13 @@ use unicode_segmentation::UnicodeSegmentation;
 use unicode_xid::UnicodeXID;
 
 use crate::cformat::{
-    CFormatPart, CFormatPreconversor, CFormatQuantity, CFormatSpec, CFormatString, CFormatType,
-    CNumberType,
+    CFormatError, CFormatErrorType, CFormatPart, CFormatPreconversor, CFormatQuantity, CFormatSpec,
+    CFormatString, CFormatType, CNumberType,
+};
+use crate::format::{
+    FieldName, FieldNamePart, FieldType, FormatParseError, FormatPart, FormatPreconversor,
+    FormatSpec, FormatString,
 };
-use crate::format::{FormatParseError, FormatPart, FormatPreconversor, FormatString};
 use crate::function::{single_or_tuple_any, OptionalArg, PyFuncArgs};
 use crate::pyhash;
 use crate::pyobject::{
@@ -26,18 +28,20 @@ use crate::pyobject::{
 };
 use crate::vm::VirtualMachine;
 
+use super::objbyteinner::do_cformat_bytes_specifier;
 use super::objbytes::PyBytes;
 use super::objdict::PyDict;
 use super::objfloat;
 use super::objint::{self, PyInt};
 use super::objiter;
 use super::objnone::PyNone;
-use super::objsequence::PySliceableSequence;
+use super::objsequence::{self, PySliceableSequence};
 use super::objslice::PySlice;
 use super::objtuple;
 use super::objtype::{self, PyClassRef};
+use crate::stdlib::unicodedata::{to_decimal, to_digit, to_numeric};
 
-use unicode_categories::UnicodeCategories;
+use unic::ucd::category::GeneralCategory;
 
 /// str(object='') -> str
 /// str(bytes_or_buffer[, encoding[, errors]]) -> str
@@ -166,6 +170,38 @@ impl PyStringReverseIterator {
     }
 }
 
+#[derive(FromArgs)]
+struct ExpandtabsArgs {
+    #[pyarg(positional_or_keyword, optional = true)]
+    tabsize: OptionalArg<isize>,
+}
+
+#[derive(FromArgs)]
+struct SplitlinesArgs {
+    #[pyarg(positional_or_keyword, optional = true)]
+    keepends: OptionalArg<bool>,
+}
+
+#[derive(FromArgs)]
+struct SplitArgs {
+    #[pyarg(positional_or_keyword, optional = true)]
+    sep: OptionalArg<Option<PyStringRef>>,
+    #[pyarg(positional_or_keyword, optional = true)]
+    maxsplit: OptionalArg<isize>,
+}
+
+impl SplitArgs {
+    fn get_value(self, vm: &VirtualMachine) -> PyResult<(Option<PyStringRef>, isize)> {
+        let sep = self.sep.into_option().and_then(|sep| sep);
+        if let Some(ref sep) = sep {
+            if sep.value.is_empty() {
+                return Err(vm.new_value_error("empty separator".to_string()));
+            }
+        }
+        Ok((sep, self.maxsplit.into_option().unwrap_or(-1)))
+    }
+}
+
 #[pyimpl]
 impl PyString {
     // TODO: should with following format
@@ -203,11 +239,11 @@ impl PyString {
     }
 
     #[pymethod(name = "__eq__")]
-    fn eq(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> bool {
+    fn eq(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
         if objtype::isinstance(&rhs, &vm.ctx.str_type()) {
-            self.value == get_value(&rhs)
+            vm.new_bool(self.value == get_value(&rhs))
         } else {
-            false
+            vm.ctx.not_implemented()
         }
     }
 
@@ -222,44 +258,44 @@ impl PyString {
     }
 
     #[pymethod(name = "__gt__")]
-    fn gt(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    fn gt(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
         if objtype::isinstance(&rhs, &vm.ctx.str_type()) {
-            Ok(self.value > get_value(&rhs))
+            vm.new_bool(self.value > get_value(&rhs))
         } else {
-            Err(vm.new_type_error(format!("Cannot compare {} and {}", self, rhs)))
+            vm.ctx.not_implemented()
         }
     }
 
     #[pymethod(name = "__ge__")]
-    fn ge(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    fn ge(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
         if objtype::isinstance(&rhs, &vm.ctx.str_type()) {
-            Ok(self.value >= get_value(&rhs))
+            vm.new_bool(self.value >= get_value(&rhs))
         } else {
-            Err(vm.new_type_error(format!("Cannot compare {} and {}", self, rhs)))
+            vm.ctx.not_implemented()
         }
     }
 
     #[pymethod(name = "__lt__")]
-    fn lt(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    fn lt(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
         if objtype::isinstance(&rhs, &vm.ctx.str_type()) {
-            Ok(self.value < get_value(&rhs))
+            vm.new_bool(self.value < get_value(&rhs))
         } else {
-            Err(vm.new_type_error(format!("Cannot compare {} and {}", self, rhs)))
+            vm.ctx.not_implemented()
         }
     }
 
     #[pymethod(name = "__le__")]
-    fn le(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    fn le(&self, rhs: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
         if objtype::isinstance(&rhs, &vm.ctx.str_type()) {
-            Ok(self.value <= get_value(&rhs))
+            vm.new_bool(self.value <= get_value(&rhs))
         } else {
-            Err(vm.new_type_error(format!("Cannot compare {} and {}", self, rhs)))
+            vm.ctx.not_implemented()
         }
     }
 
     #[pymethod(name = "__hash__")]
-    fn hash(&self, _vm: &VirtualMachine) -> pyhash::PyHash {
-        pyhash::hash_value(&self.value)
+    fn hash(&self, vm: &VirtualMachine) -> pyhash::PyHash {
+        vm.hash_secret.hash_str(&self.value)
     }
 
     #[pymethod(name = "__len__")]
@@ -269,10 +305,11 @@ impl PyString {
 
     #[pymethod(name = "__mul__")]
     fn mul(&self, val: PyObjectRef, vm: &VirtualMachine) -> PyResult<String> {
-        if !objtype::isinstance(&val, &vm.ctx.int_type()) {
+        if !objtype::class_has_attr(&val.class(), "__index__") {
             return Err(vm.new_type_error(format!("Cannot multiply {} and {}", self, val)));
         }
-        objint::get_value(&val)
+        vm.to_index(&val)?
+            .as_bigint()
             .to_isize()
             .map(|multiplier| multiplier.max(0))
             .and_then(|multiplier| multiplier.to_usize())
@@ -292,6 +329,16 @@ impl PyString {
         zelf
     }
 
+    #[pymethod(name = "__format__")]
+    fn format_str(&self, spec: PyStringRef, vm: &VirtualMachine) -> PyResult<String> {
+        match FormatSpec::parse(&spec.value)
+            .and_then(|format_spec| format_spec.format_string(&self.value))
+        {
+            Ok(string) => Ok(string),
+            Err(err) => Err(vm.new_value_error(err.to_string())),
+        }
+    }
+
     #[pymethod(name = "__repr__")]
     fn repr(&self, _vm: &VirtualMachine) -> String {
         let value = &self.value;
@@ -328,7 +375,8 @@ impl PyString {
         self.value.to_lowercase()
     }
 
-    // casefold is much more aggressive than lower
+    /// casefold is much more aggressive than lower: it applies the full unicode
+    /// case folding, which may expand a character into several (`ß` -> `ss`).
     #[pymethod]
     fn casefold(&self, _vm: &VirtualMachine) -> String {
         caseless::default_case_fold_str(&self.value)
@@ -346,78 +394,42 @@ impl PyString {
     }
 
     #[pymethod]
-    fn split(
-        &self,
-        pattern: OptionalArg<PyStringRef>,
-        num: OptionalArg<isize>,
-        vm: &VirtualMachine,
-    ) -> PyObjectRef {
+    fn split(&self, args: SplitArgs, vm: &VirtualMachine) -> PyResult {
         let value = &self.value;
-        let pattern = match pattern {
-            OptionalArg::Present(ref s) => Some(s.as_str()),
-            OptionalArg::Missing => None,
-        };
-        let num_splits = num.into_option().unwrap_or(-1);
+        let (pattern, num_splits) = args.get_value(vm)?;
         let elements: Vec<_> = match (pattern, num_splits.is_negative()) {
-            (Some(pattern), true) => value
-                .split(pattern)
-                .map(|o| vm.ctx.new_str(o.to_string()))
-                .collect(),
-            (Some(pattern), false) => value
-                .splitn(num_splits as usize + 1, pattern)
-                .map(|o| vm.ctx.new_str(o.to_string()))
-                .collect(),
-            (None, true) => value
-                .split(|c: char| c.is_ascii_whitespace())
-                .filter(|s| !s.is_empty())
-                .map(|o| vm.ctx.new_str(o.to_string()))
-                .collect(),
-            (None, false) => value
-                .splitn(num_splits as usize + 1, |c: char| c.is_ascii_whitespace())
-                .filter(|s| !s.is_empty())
-                .map(|o| vm.ctx.new_str(o.to_string()))
+            (Some(ref pattern), true) => value.split(pattern.as_str()).collect(),
+            (Some(ref pattern), false) => value
+                .splitn(num_splits as usize + 1, pattern.as_str())
                 .collect(),
+            (None, _) => split_whitespace(value, num_splits),
         };
-        vm.ctx.new_list(elements)
+        let elements = elements
+            .into_iter()
+            .map(|o| vm.ctx.new_str(o.to_string()))
+            .collect();
+        Ok(vm.ctx.new_list(elements))
     }
 
     #[pymethod]
-    fn rsplit(
-        &self,
-        pattern: OptionalArg<PyStringRef>,
-        num: OptionalArg<isize>,
-        vm: &VirtualMachine,
-    ) -> PyObjectRef {
+    fn rsplit(&self, args: SplitArgs, vm: &VirtualMachine) -> PyResult {
         let value = &self.value;
-        let pattern = match pattern {
-            OptionalArg::Present(ref s) => Some(s.as_str()),
-            OptionalArg::Missing => None,
-        };
-        let num_splits = num.into_option().unwrap_or(-1);
+        let (pattern, num_splits) = args.get_value(vm)?;
         let mut elements: Vec<_> = match (pattern, num_splits.is_negative()) {
-            (Some(pattern), true) => value
-                .rsplit(pattern)
-                .map(|o| vm.ctx.new_str(o.to_string()))
-                .collect(),
-            (Some(pattern), false) => value
-                .rsplitn(num_splits as usize + 1, pattern)
-                .map(|o| vm.ctx.new_str(o.to_string()))
-                .collect(),
-            (None, true) => value
-                .rsplit(|c: char| c.is_ascii_whitespace())
-                .filter(|s| !s.is_empty())
-                .map(|o| vm.ctx.new_str(o.to_string()))
-                .collect(),
-            (None, false) => value
-                .rsplitn(num_splits as usize + 1, |c: char| c.is_ascii_whitespace())
-                .filter(|s| !s.is_empty())
-                .map(|o| vm.ctx.new_str(o.to_string()))
+            (Some(ref pattern), true) => value.rsplit(pattern.as_str()).collect(),
+            (Some(ref pattern), false) => value
+                .rsplitn(num_splits as usize + 1, pattern.as_str())
                 .collect(),
+            (None, _) => rsplit_whitespace(value, num_splits),
         };
         // Unlike Python rsplit, Rust rsplitn returns an iterator that
         // starts from the end of the string.
         elements.reverse();
-        vm.ctx.new_list(elements)
+        let elements = elements
+            .into_iter()
+            .map(|o| vm.ctx.new_str(o.to_string()))
+            .collect();
+        Ok(vm.ctx.new_list(elements))
     }
 
     #[pymethod]
@@ -451,6 +463,26 @@ impl PyString {
             .to_string()
     }
 
+    /// Return a copy without `prefix` at the start, if it is there.
+    #[pymethod]
+    fn removeprefix(&self, prefix: PyStringRef, _vm: &VirtualMachine) -> String {
+        if self.value.starts_with(&prefix.value) {
+            self.value[prefix.value.len()..].to_string()
+        } else {
+            self.value.clone()
+        }
+    }
+
+    /// Return a copy without `suffix` at the end, if it is there.
+    #[pymethod]
+    fn removesuffix(&self, suffix: PyStringRef, _vm: &VirtualMachine) -> String {
+        if self.value.ends_with(&suffix.value) {
+            self.value[..self.value.len() - suffix.value.len()].to_string()
+        } else {
+            self.value.clone()
+        }
+    }
+
     #[pymethod]
     fn endswith(
         &self,
@@ -505,38 +537,27 @@ impl PyString {
 
     #[pymethod]
     fn isalnum(&self, _vm: &VirtualMachine) -> bool {
-        !self.value.is_empty() && self.value.chars().all(char::is_alphanumeric)
+        !self.value.is_empty() && self.value.chars().all(|c| is_alpha(c) || is_numeric(c))
     }
 
+    /// Return true if all characters have a numeric value in the unicode
+    /// database, which includes digits, fractions and roman numerals.
     #[pymethod]
     fn isnumeric(&self, _vm: &VirtualMachine) -> bool {
-        !self.value.is_empty() && self.value.chars().all(char::is_numeric)
+        !self.value.is_empty() && self.value.chars().all(is_numeric)
     }
 
+    /// Return true if all characters are digits, which includes decimal
+    /// digits as well as superscripts and other compatibility digits.
     #[pymethod]
     fn isdigit(&self, _vm: &VirtualMachine) -> bool {
-        // python's isdigit also checks if exponents are digits, these are the unicodes for exponents
-        let valid_unicodes: [u16; 10] = [
-            0x2070, 0x00B9, 0x00B2, 0x00B3, 0x2074, 0x2075, 0x2076, 0x2077, 0x2078, 0x2079,
-        ];
-
-        if self.value.is_empty() {
-            false
-        } else {
-            self.value
-                .chars()
-                .filter(|c| !c.is_digit(10))
-                .all(|c| valid_unicodes.contains(&(c as u16)))
-        }
+        !self.value.is_empty() && self.value.chars().all(|c| to_digit(c).is_some())
     }
 
+    /// Return true if all characters are decimal digits (category `Nd`).
     #[pymethod]
     fn isdecimal(&self, _vm: &VirtualMachine) -> bool {
-        if self.value.is_empty() {
-            false
-        } else {
-            self.value.chars().all(|c| c.is_ascii_digit())
-        }
+        !self.value.is_empty() && self.value.chars().all(|c| to_decimal(c).is_some())
     }
 
     #[pymethod(name = "__mod__")]
@@ -544,7 +565,20 @@ impl PyString {
         let format_string_text = &self.value;
         let format_string = CFormatString::from_str(format_string_text)
             .map_err(|err| vm.new_value_error(err.to_string()))?;
-        do_cformat(vm, format_string, values.clone())
+        // %b is only meaningful when formatting bytes
+        for (index, part) in &format_string.format_parts {
+            if let CFormatPart::Spec(spec) = part {
+                if spec.format_type == CFormatType::String(CFormatPreconversor::Bytes) {
+                    let err = CFormatError {
+                        typ: CFormatErrorType::UnsupportedFormatChar(spec.format_char),
+                        index: index + spec.chars_consumed - 1,
+                    };
+                    return Err(vm.new_value_error(err.to_string()));
+                }
+            }
+        }
+        let result = do_cformat(vm, format_string, values.clone(), false)?;
+        Ok(vm.ctx.new_str(result))
     }
 
     #[pymethod(name = "__rmod__")]
@@ -572,12 +606,7 @@ impl PyString {
         let format_string_text = get_value(zelf);
         match FormatString::from_str(format_string_text.as_str()) {
             Ok(format_string) => perform_format(vm, &format_string, &args),
-            Err(err) => match err {
-                FormatParseError::UnmatchedBracket => {
-                    Err(vm.new_value_error("expected '}' before end of string".to_string()))
-                }
-                _ => Err(vm.new_value_error("Unexpected error parsing format string".to_string())),
-            },
+            Err(err) => Err(new_format_parse_error(vm, err)),
         }
     }
 
@@ -626,9 +655,11 @@ impl PyString {
         swapped_str
     }
 
+    /// Return true if all characters are letters, i.e. in one of the `L*`
+    /// unicode categories.
     #[pymethod]
     fn isalpha(&self, _vm: &VirtualMachine) -> bool {
-        !self.value.is_empty() && self.value.chars().all(char::is_alphanumeric)
+        !self.value.is_empty() && self.value.chars().all(is_alpha)
     }
 
     #[pymethod]
@@ -662,37 +693,55 @@ impl PyString {
     ///   * Zs (Separator, Space) other than ASCII space('\x20').
     #[pymethod]
     fn isprintable(&self, _vm: &VirtualMachine) -> bool {
-        self.value.chars().all(|c| match c {
-            '\u{0020}' => true,
-            _ => !(c.is_other_control() | c.is_separator()),
+        self.value.chars().all(|c| {
+            c == '\u{0020}'
+                || match GeneralCategory::of(c) {
+                    GeneralCategory::Control
+                    | GeneralCategory::Format
+                    | GeneralCategory::Surrogate
+                    | GeneralCategory::PrivateUse
+                    | GeneralCategory::Unassigned
+                    | GeneralCategory::LineSeparator
+                    | GeneralCategory::ParagraphSeparator
+                    | GeneralCategory::SpaceSeparator => false,
+                    _ => true,
+                }
         })
     }
 
-    // cpython's isspace ignores whitespace, including \t and \n, etc, unless the whole string is empty
-    // which is why isspace is using is_ascii_whitespace. Same for isupper & islower
     #[pymethod]
     fn isspace(&self, _vm: &VirtualMachine) -> bool {
-        !self.value.is_empty() && self.value.chars().all(|c| c.is_ascii_whitespace())
+        !self.value.is_empty() && self.value.chars().all(is_space)
     }
 
+    /// Return true if all cased characters are uppercase and there is at
+    /// least one cased character.
     #[pymethod]
     fn isupper(&self, _vm: &VirtualMachine) -> bool {
-        !self.value.is_empty()
-            && self
-                .value
-                .chars()
-                .filter(|x| !x.is_ascii_whitespace())
-                .all(char::is_uppercase)
+        let mut cased = false;
+        for c in self.value.chars() {
+            if c.is_lowercase() || c.is_titlecase() {
+                return false;
+            } else if c.is_uppercase() {
+                cased = true;
+            }
+        }
+        cased
     }
 
+    /// Return true if all cased characters are lowercase and there is at
+    /// least one cased character.
     #[pymethod]
     fn islower(&self, _vm: &VirtualMachine) -> bool {
-        !self.value.is_empty()
-            && self
-                .value
-                .chars()
-                .filter(|x| !x.is_ascii_whitespace())
-                .all(char::is_lowercase)
+        let mut cased = false;
+        for c in self.value.chars() {
+            if c.is_uppercase() || c.is_titlecase() {
+                return false;
+            } else if c.is_lowercase() {
+                cased = true;
+            }
+        }
+        cased
     }
 
     #[pymethod]
@@ -700,14 +749,34 @@ impl PyString {
         !self.value.is_empty() && self.value.chars().all(|c| c.is_ascii())
     }
 
-    // doesn't implement keep new line delimiter just yet
+    /// Split on all unicode line boundaries; unlike `split('\n')` a trailing
+    /// line break doesn't produce an empty last line.
     #[pymethod]
-    fn splitlines(&self, vm: &VirtualMachine) -> PyObjectRef {
-        let elements = self
-            .value
-            .split('\n')
-            .map(|e| vm.ctx.new_str(e.to_string()))
-            .collect();
+    fn splitlines(&self, args: SplitlinesArgs, vm: &VirtualMachine) -> PyObjectRef {
+        let keepends = args.keepends.into_option().unwrap_or(false);
+        let mut elements = vec![];
+        let mut chars = self.value.char_indices().peekable();
+        let mut line_start = 0;
+        while let Some((index, ch)) = chars.next() {
+            let mut line_end = index + ch.len_utf8();
+            match ch {
+                '\r' => {
+                    if let Some((_, '\n')) = chars.peek() {
+                        chars.next();
+                        line_end += 1;
+                    }
+                }
+                '\n' | '\x0b' | '\x0c' | '\x1c' | '\x1d' | '\x1e' | '\u{85}' | '\u{2028}'
+                | '\u{2029}' => {}
+                _ => continue,
+            }
+            let end = if keepends { line_end } else { index };
+            elements.push(vm.ctx.new_str(self.value[line_start..end].to_string()));
+            line_start = line_end;
+        }
+        if line_start < self.value.len() {
+            elements.push(vm.ctx.new_str(self.value[line_start..].to_string()));
+        }
         vm.ctx.new_list(elements)
     }
 
@@ -803,46 +872,48 @@ impl PyString {
     }
 
     #[pymethod]
-    fn partition(&self, sub: PyStringRef, vm: &VirtualMachine) -> PyObjectRef {
-        let value = &self.value;
-        let sub = &sub.value;
-        let mut new_tup = Vec::new();
-        if value.contains(sub) {
-            new_tup = value
-                .splitn(2, sub)
-                .map(|s| vm.ctx.new_str(s.to_string()))
-                .collect();
-            new_tup.insert(1, vm.ctx.new_str(sub.clone()));
-        } else {
-            new_tup.push(vm.ctx.new_str(value.clone()));
-            new_tup.push(vm.ctx.new_str("".to_string()));
-            new_tup.push(vm.ctx.new_str("".to_string()));
+    fn partition(&self, sep: PyStringRef, vm: &VirtualMachine) -> PyResult {
+        if sep.value.is_empty() {
+            return Err(vm.new_value_error("empty separator".to_string()));
         }
-        vm.ctx.new_tuple(new_tup)
+        let (before, sep, after) = match self.value.find(&sep.value) {
+            Some(index) => (
+                &self.value[..index],
+                sep.value.as_str(),
+                &self.value[index + sep.value.len()..],
+            ),
+            None => (self.value.as_str(), "", ""),
+        };
+        Ok(vm.ctx.new_tuple(vec![
+            vm.new_str(before.to_string()),
+            vm.new_str(sep.to_string()),
+            vm.new_str(after.to_string()),
+        ]))
     }
 
     #[pymethod]
-    fn rpartition(&self, sub: PyStringRef, vm: &VirtualMachine) -> PyObjectRef {
-        let value = &self.value;
-        let sub = &sub.value;
-        let mut new_tup = Vec::new();
-        if value.contains(sub) {
-            new_tup = value
-                .rsplitn(2, sub)
-                .map(|s| vm.ctx.new_str(s.to_string()))
-                .collect();
-            new_tup.swap(0, 1); // so it's in the right order
-            new_tup.insert(1, vm.ctx.new_str(sub.clone()));
-        } else {
-            new_tup.push(vm.ctx.new_str("".to_string()));
-            new_tup.push(vm.ctx.new_str("".to_string()));
-            new_tup.push(vm.ctx.new_str(value.clone()));
+    fn rpartition(&self, sep: PyStringRef, vm: &VirtualMachine) -> PyResult {
+        if sep.value.is_empty() {
+            return Err(vm.new_value_error("empty separator".to_string()));
         }
-        vm.ctx.new_tuple(new_tup)
+        let (before, sep, after) = match self.value.rfind(&sep.value) {
+            Some(index) => (
+                &self.value[..index],
+                sep.value.as_str(),
+                &self.value[index + sep.value.len()..],
+            ),
+            None => ("", "", self.value.as_str()),
+        };
+        Ok(vm.ctx.new_tuple(vec![
+            vm.new_str(before.to_string()),
+            vm.new_str(sep.to_string()),
+            vm.new_str(after.to_string()),
+        ]))
     }
 
-    /// Return `true` if the sequence is ASCII titlecase and the sequence is not
-    /// empty, `false` otherwise.
+    /// Return `true` if the string is titlecased and not empty, `false`
+    /// otherwise: uppercase characters may only follow uncased characters
+    /// and lowercase characters only cased ones.
     #[pymethod]
     fn istitle(&self, _vm: &VirtualMachine) -> bool {
         if self.value.is_empty() {
@@ -897,112 +968,116 @@ impl PyString {
         }
     }
 
-    fn get_fill_char<'a>(
-        rep: &'a OptionalArg<PyStringRef>,
-        vm: &VirtualMachine,
-    ) -> PyResult<&'a str> {
+    fn get_fill_char(rep: OptionalArg<PyStringRef>, vm: &VirtualMachine) -> PyResult<char> {
         let rep_str = match rep {
             OptionalArg::Present(ref st) => &st.value,
-            OptionalArg::Missing => " ",
+            OptionalArg::Missing => return Ok(' '),
         };
-        if rep_str.len() == 1 {
-            Ok(rep_str)
-        } else {
-            Err(vm.new_type_error(
+        let mut chars = rep_str.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(vm.new_type_error(
                 "The fill character must be exactly one character long".to_string(),
-            ))
+            )),
+        }
+    }
+
+    /// Surround the string with `left` and `right` fill characters.
+    fn pad(&self, left: usize, right: usize, fill: char) -> String {
+        let mut padded = String::with_capacity(self.value.len() + left + right);
+        padded.extend(std::iter::repeat(fill).take(left));
+        padded.push_str(&self.value);
+        padded.extend(std::iter::repeat(fill).take(right));
+        padded
+    }
+
+    /// The number of fill characters needed to reach `width`, if any.
+    fn padding_for(&self, width: isize) -> Option<usize> {
+        let len = self.value.chars().count();
+        if width <= len as isize {
+            None
+        } else {
+            Some(width as usize - len)
         }
     }
 
     #[pymethod]
     fn ljust(
         &self,
-        len: usize,
-        rep: OptionalArg<PyStringRef>,
+        width: isize,
+        fillchar: OptionalArg<PyStringRef>,
         vm: &VirtualMachine,
     ) -> PyResult<String> {
-        let value = &self.value;
-        let rep_char = Self::get_fill_char(&rep, vm)?;
-        if len <= value.len() {
-            Ok(value.to_string())
-        } else {
-            Ok(format!("{}{}", value, rep_char.repeat(len - value.len())))
-        }
+        let fill = Self::get_fill_char(fillchar, vm)?;
+        Ok(match self.padding_for(width) {
+            Some(padding) => self.pad(0, padding, fill),
+            None => self.value.clone(),
+        })
     }
 
     #[pymethod]
     fn rjust(
         &self,
-        len: usize,
-        rep: OptionalArg<PyStringRef>,
+        width: isize,
+        fillchar: OptionalArg<PyStringRef>,
         vm: &VirtualMachine,
     ) -> PyResult<String> {
-        let value = &self.value;
-        let rep_char = Self::get_fill_char(&rep, vm)?;
-        if len <= value.len() {
-            Ok(value.to_string())
-        } else {
-            Ok(format!("{}{}", rep_char.repeat(len - value.len()), value))
-        }
+        let fill = Self::get_fill_char(fillchar, vm)?;
+        Ok(match self.padding_for(width) {
+            Some(padding) => self.pad(padding, 0, fill),
+            None => self.value.clone(),
+        })
     }
 
     #[pymethod]
     fn center(
         &self,
-        len: usize,
-        rep: OptionalArg<PyStringRef>,
+        width: isize,
+        fillchar: OptionalArg<PyStringRef>,
         vm: &VirtualMachine,
     ) -> PyResult<String> {
-        let value = &self.value;
-        let rep_char = Self::get_fill_char(&rep, vm)?;
-        let value_len = self.value.chars().count();
-
-        if len <= value_len {
-            return Ok(value.to_string());
-        }
-        let diff: usize = len - value_len;
-        let mut left_buff: usize = diff / 2;
-        let mut right_buff: usize = left_buff;
-
-        if diff % 2 != 0 && value_len % 2 == 0 {
-            left_buff += 1
-        }
-
-        if diff % 2 != 0 && value_len % 2 != 0 {
-            right_buff += 1
-        }
-        Ok(format!(
-            "{}{}{}",
-            rep_char.repeat(left_buff),
-            value,
-            rep_char.repeat(right_buff)
-        ))
+        let fill = Self::get_fill_char(fillchar, vm)?;
+        Ok(match self.padding_for(width) {
+            Some(padding) => {
+                // same rounding as CPython: the odd fill character goes to
+                // the left only when both the padding and the width are odd
+                let left = padding / 2 + (padding & width as usize & 1);
+                self.pad(left, padding - left, fill)
+            }
+            None => self.value.clone(),
+        })
     }
 
     #[pymethod]
-    fn expandtabs(&self, tab_stop: OptionalArg<usize>, _vm: &VirtualMachine) -> String {
-        let tab_stop = tab_stop.into_option().unwrap_or(8 as usize);
+    fn expandtabs(&self, args: ExpandtabsArgs, _vm: &VirtualMachine) -> String {
+        let tabsize = args.tabsize.into_option().unwrap_or(8);
         let mut expanded_str = String::with_capacity(self.value.len());
-        let mut tab_size = tab_stop;
-        let mut col_count = 0 as usize;
+        let mut column = 0;
         for ch in self.value.chars() {
-            // 0x0009 is tab
-            if ch == 0x0009 as char {
-                let num_spaces = tab_size - col_count;
-                col_count += num_spaces;
-                let expand = " ".repeat(num_spaces);
-                expanded_str.push_str(&expand);
-            } else {
-                expanded_str.push(ch);
-                col_count += 1;
-            }
-            if col_count >= tab_size {
-                tab_size += tab_stop;
+            match ch {
+                '\t' => {
+                    // a non-positive tab size just drops the tabs
+                    if tabsize > 0 {
+                        let num_spaces = tabsize as usize - column % tabsize as usize;
+                        expanded_str.extend(std::iter::repeat(' ').take(num_spaces));
+                        column += num_spaces;
+                    }
+                }
+                '\n' | '\r' => {
+                    expanded_str.push(ch);
+                    column = 0;
+                }
+                _ => {
+                    expanded_str.push(ch);
+                    column += 1;
+                }
             }
         }
         expanded_str
     }
 
+    /// Return true if the string is a valid identifier: an `XID_Start`
+    /// character or an underscore followed by `XID_Continue` characters.
     #[pymethod]
     fn isidentifier(&self, _vm: &VirtualMachine) -> bool {
         let mut chars = self.value.chars();
@@ -1118,7 +1193,7 @@ impl PyString {
     fn encode(
         &self,
         encoding: OptionalArg<PyObjectRef>,
-        _errors: OptionalArg<PyObjectRef>,
+        errors: OptionalArg<PyStringRef>,
         vm: &VirtualMachine,
     ) -> PyResult {
         let encoding = encoding.map_or_else(
@@ -1135,7 +1210,9 @@ impl PyString {
             },
         )?;
 
-        let encoded = PyBytes::from_string(&self.value, &encoding, vm)?;
+        let errors = errors.into_option();
+        let errors = errors.as_ref().map_or("strict", |errors| errors.as_str());
+        let encoded = PyBytes::from_string_with_errors(&self.value, &encoding, errors, vm)?;
         Ok(encoded.into_pyobject(vm)?)
     }
 
@@ -1197,6 +1274,57 @@ pub fn borrow_value(obj: &PyObjectRef) -> &str {
     &obj.payload::<PyString>().unwrap().value
 }
 
+fn is_alpha(c: char) -> bool {
+    GeneralCategory::of(c).is_letter()
+}
+
+fn is_numeric(c: char) -> bool {
+    c.is_numeric() || to_numeric(c).is_some()
+}
+
+/// Python treats the information separators `\x1c`-`\x1f` as whitespace in
+/// addition to the unicode `White_Space` property.
+pub fn is_space(c: char) -> bool {
+    c.is_whitespace() || ('\x1c'..='\x1f').contains(&c)
+}
+
+/// Split on runs of whitespace, like `str.split()`: empty fields are dropped,
+/// and once `maxsplit` splits are made the rest is kept with only its leading
+/// whitespace removed.
+fn split_whitespace(value: &str, maxsplit: isize) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut rest = value.trim_start_matches(is_space);
+    while !rest.is_empty() {
+        if maxsplit >= 0 && parts.len() as isize == maxsplit {
+            parts.push(rest);
+            break;
+        }
+        let end = rest.find(is_space).unwrap_or(rest.len());
+        parts.push(&rest[..end]);
+        rest = rest[end..].trim_start_matches(is_space);
+    }
+    parts
+}
+
+/// The mirror image of `split_whitespace`, with the parts from last to first.
+fn rsplit_whitespace(value: &str, maxsplit: isize) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut rest = value.trim_end_matches(is_space);
+    while !rest.is_empty() {
+        if maxsplit >= 0 && parts.len() as isize == maxsplit {
+            parts.push(rest);
+            break;
+        }
+        let start = rest
+            .rfind(is_space)
+            .map(|i| i + rest[i..].chars().next().unwrap().len_utf8())
+            .unwrap_or(0);
+        parts.push(&rest[start..]);
+        rest = rest[..start].trim_end_matches(is_space);
+    }
+    parts
+}
+
 fn count_char(s: &str, c: char) -> usize {
     s.chars().filter(|x| *x == c).count()
 }
@@ -1205,15 +1333,24 @@ fn call_getitem(vm: &VirtualMachine, container: &PyObjectRef, key: &PyObjectRef)
     vm.call_method(container, "__getitem__", vec![key.clone()])
 }
 
-fn call_object_format(vm: &VirtualMachine, argument: PyObjectRef, format_spec: &str) -> PyResult {
-    let (preconversor, new_format_spec) = FormatPreconversor::parse_and_consume(format_spec);
-    let argument = match preconversor {
-        Some(FormatPreconversor::Str) => vm.call_method(&argument, "__str__", vec![])?,
-        Some(FormatPreconversor::Repr) => vm.call_method(&argument, "__repr__", vec![])?,
-        Some(FormatPreconversor::Ascii) => vm.call_method(&argument, "__repr__", vec![])?,
+fn call_object_format(
+    vm: &VirtualMachine,
+    argument: PyObjectRef,
+    preconversion_spec: Option<char>,
+    format_spec: &str,
+) -> PyResult {
+    let argument = match preconversion_spec.map(|c| (c, FormatPreconversor::from_char(c))) {
+        Some((_, Some(FormatPreconversor::Str))) => vm.call_method(&argument, "__str__", vec![])?,
+        Some((_, Some(FormatPreconversor::Repr))) => {
+            vm.call_method(&argument, "__repr__", vec![])?
+        }
+        Some((_, Some(FormatPreconversor::Ascii))) => vm.to_ascii(&argument)?,
+        Some((c, None)) => {
+            return Err(vm.new_value_error(format!("Unknown conversion specifier {}", c)));
+        }
         None => argument,
     };
-    let returned_type = vm.ctx.new_str(new_format_spec.to_string());
+    let returned_type = vm.ctx.new_str(format_spec.to_string());
 
     let result = vm.call_method(&argument, "__format__", vec![returned_type])?;
     if !objtype::isinstance(&result, &vm.ctx.str_type()) {
@@ -1224,7 +1361,7 @@ fn call_object_format(vm: &VirtualMachine, argument: PyObjectRef, format_spec: &
     Ok(result)
 }
 
-fn do_cformat_specifier(
+pub fn do_cformat_specifier(
     vm: &VirtualMachine,
     format_spec: &mut CFormatSpec,
     obj: PyObjectRef,
@@ -1238,40 +1375,40 @@ fn do_cformat_specifier(
             let result = match preconversor {
                 CFormatPreconversor::Str => vm.call_method(&obj.clone(), "__str__", vec![])?,
                 CFormatPreconversor::Repr => vm.call_method(&obj.clone(), "__repr__", vec![])?,
-                CFormatPreconversor::Ascii => vm.call_method(&obj.clone(), "__repr__", vec![])?,
+                CFormatPreconversor::Ascii => vm.to_ascii(&obj)?,
+                CFormatPreconversor::Bytes => unreachable!(), // rejected by str.__mod__
             };
             Ok(format_spec.format_string(get_value(&result)))
         }
         CFormatType::Number(_) => {
-            if !objtype::isinstance(&obj, &vm.ctx.int_type()) {
-                let required_type_string = match format_type {
-                    CFormatType::Number(Decimal) => "a number",
-                    CFormatType::Number(_) => "an integer",
-                    _ => unreachable!(),
-                };
-                return Err(vm.new_type_error(format!(
-                    "%{} format: {} is required, not {}",
-                    format_spec.format_char,
-                    required_type_string,
-                    obj.class()
-                )));
+            if objtype::isinstance(&obj, &vm.ctx.int_type()) {
+                return Ok(format_spec.format_number(objint::get_value(&obj)));
             }
-            Ok(format_spec.format_number(objint::get_value(&obj)))
-        }
-        CFormatType::Float(_) => if objtype::isinstance(&obj, &vm.ctx.float_type()) {
-            format_spec.format_float(objfloat::get_value(&obj))
-        } else if objtype::isinstance(&obj, &vm.ctx.int_type()) {
-            format_spec.format_float(objint::get_value(&obj).to_f64().unwrap())
-        } else {
-            let required_type_string = "an floating point or integer";
-            return Err(vm.new_type_error(format!(
+            // %d, %i and %u truncate floats, the other integer formats do not
+            if *format_type == CFormatType::Number(Decimal)
+                && objtype::isinstance(&obj, &vm.ctx.float_type())
+            {
+                let value = objfloat::try_to_bigint(objfloat::get_value(&obj).trunc(), vm)?;
+                return Ok(format_spec.format_number(&value));
+            }
+            let required_type_string = match format_type {
+                CFormatType::Number(Decimal) => "a number",
+                CFormatType::Number(_) => "an integer",
+                _ => unreachable!(),
+            };
+            Err(vm.new_type_error(format!(
                 "%{} format: {} is required, not {}",
                 format_spec.format_char,
                 required_type_string,
-                obj.class()
-            )));
+                obj.class().name
+            )))
         }
-        .map_err(|e| vm.new_not_implemented_error(e)),
+        CFormatType::Float(_) => match objfloat::try_float(&obj, vm)? {
+            Some(value) => Ok(format_spec.format_float(value)),
+            None => {
+                Err(vm.new_type_error(format!("must be real number, not {}", obj.class().name)))
+            }
+        },
         CFormatType::Character => {
             let char_string = {
                 if objtype::isinstance(&obj, &vm.ctx.int_type()) {
@@ -1331,11 +1468,14 @@ fn try_update_quantity_from_tuple(
     }
 }
 
-fn do_cformat(
+/// Apply printf-style formatting, shared by `str.__mod__` and `bytes.__mod__`.
+/// For bytes, the format string holds one char per byte and mapping keys are looked up as bytes.
+pub fn do_cformat(
     vm: &VirtualMachine,
     mut format_string: CFormatString,
     values_obj: PyObjectRef,
-) -> PyResult {
+    is_bytes: bool,
+) -> PyResult<String> {
     let mut final_string = String::new();
     let num_specifiers = format_string
         .format_parts
@@ -1384,8 +1524,13 @@ fn do_cformat(
                 // try to get the object
                 let obj: PyObjectRef = match &format_spec.mapping_key {
                     Some(key) => {
+                        let key = if is_bytes {
+                            vm.ctx.new_bytes(key.chars().map(|c| c as u8).collect())
+                        } else {
+                            vm.ctx.new_str(key.to_string())
+                        };
                         // TODO: change the KeyError message to match the one in cpython
-                        call_getitem(vm, &values, &vm.ctx.new_str(key.to_string()))?
+                        call_getitem(vm, &values, &key)?
                     }
                     None => {
                         let mut elements =
@@ -1415,7 +1560,11 @@ fn do_cformat(
                         obj
                     }
                 };
-                do_cformat_specifier(vm, format_spec, obj)
+                if is_bytes {
+                    do_cformat_bytes_specifier(vm, format_spec, obj)
+                } else {
+                    do_cformat_specifier(vm, format_spec, obj)
+                }
             }
             CFormatPart::Literal(literal) => Ok(literal.clone()),
         }?;
@@ -1434,7 +1583,27 @@ fn do_cformat(
             vm.new_type_error("not all arguments converted during string formatting".to_string())
         );
     }
-    Ok(vm.ctx.new_str(final_string))
+    Ok(final_string)
+}
+
+pub fn new_format_parse_error(vm: &VirtualMachine, err: FormatParseError) -> PyObjectRef {
+    let msg = match err {
+        FormatParseError::UnmatchedBracket => "expected '}' before end of string",
+        FormatParseError::EmptyAttribute => "Empty attribute in format string",
+        FormatParseError::MissingRightBracket => "Missing ']' in format string",
+        FormatParseError::InvalidCharacterAfterRightBracket => {
+            "Only '.' or '[' may follow ']' in format field specifier"
+        }
+        FormatParseError::InvalidConversionSpecifier => "expected ':' after conversion specifier",
+        _ => "Unexpected error parsing format string",
+    };
+    vm.new_value_error(msg.to_string())
+}
+
+/// Argument numbering shared by a format string and the format specs nested in it.
+struct FormatState {
+    auto_argument_index: usize,
+    seen_index: bool,
 }
 
 fn perform_format(
@@ -1442,51 +1611,91 @@ fn perform_format(
     format_string: &FormatString,
     arguments: &PyFuncArgs,
 ) -> PyResult {
+    let mut state = FormatState {
+        auto_argument_index: 1,
+        seen_index: false,
+    };
+    let result = format_parts(vm, format_string, arguments, &mut state, 2)?;
+    Ok(vm.ctx.new_str(result))
+}
+
+fn format_parts(
+    vm: &VirtualMachine,
+    format_string: &FormatString,
+    arguments: &PyFuncArgs,
+    state: &mut FormatState,
+    recursion_depth: usize,
+) -> PyResult<String> {
     let mut final_string = String::new();
-    if format_string.format_parts.iter().any(FormatPart::is_auto)
-        && format_string.format_parts.iter().any(FormatPart::is_index)
-    {
-        return Err(vm.new_value_error(
-            "cannot switch from automatic field numbering to manual field specification"
-                .to_string(),
-        ));
-    }
-    let mut auto_argument_index: usize = 1;
     for part in &format_string.format_parts {
-        let result_string: String = match part {
-            FormatPart::AutoSpec(format_spec) => {
-                let result = match arguments.args.get(auto_argument_index) {
-                    Some(argument) => call_object_format(vm, argument.clone(), &format_spec)?,
-                    None => {
-                        return Err(vm.new_index_error("tuple index out of range".to_string()));
+        let (field_name, preconversion_spec, format_spec) = match part {
+            FormatPart::Field {
+                field_name,
+                preconversion_spec,
+                format_spec,
+            } => (field_name, preconversion_spec, format_spec),
+            FormatPart::Literal(literal) => {
+                final_string.push_str(literal);
+                continue;
+            }
+        };
+        let FieldName { field_type, parts } =
+            FieldName::parse(field_name).map_err(|err| new_format_parse_error(vm, err))?;
+
+        let mut argument = match field_type {
+            FieldType::Auto | FieldType::Index(_) => {
+                let index = match field_type {
+                    FieldType::Index(index) => {
+                        state.seen_index = true;
+                        index + 1
+                    }
+                    _ => {
+                        state.auto_argument_index += 1;
+                        state.auto_argument_index - 1
                     }
                 };
-                auto_argument_index += 1;
-                get_value(&result)
-            }
-            FormatPart::IndexSpec(index, format_spec) => {
-                let result = match arguments.args.get(*index + 1) {
-                    Some(argument) => call_object_format(vm, argument.clone(), &format_spec)?,
+                if state.seen_index && state.auto_argument_index > 1 {
+                    return Err(vm.new_value_error(
+                        "cannot switch from automatic field numbering to manual field specification"
+                            .to_string(),
+                    ));
+                }
+                match arguments.args.get(index) {
+                    Some(argument) => argument.clone(),
                     None => {
                         return Err(vm.new_index_error("tuple index out of range".to_string()));
                     }
-                };
-                get_value(&result)
+                }
             }
-            FormatPart::KeywordSpec(keyword, format_spec) => {
-                let result = match arguments.get_optional_kwarg(&keyword) {
-                    Some(argument) => call_object_format(vm, argument.clone(), &format_spec)?,
-                    None => {
-                        return Err(vm.new_key_error(vm.new_str(keyword.to_string())));
-                    }
-                };
-                get_value(&result)
+            FieldType::Keyword(keyword) => match arguments.get_optional_kwarg(&keyword) {
+                Some(argument) => argument,
+                None => return Err(vm.new_key_error(vm.new_str(keyword))),
+            },
+        };
+        for name_part in parts {
+            argument = match name_part {
+                FieldNamePart::Attribute(attribute) => vm.get_attribute(argument, attribute)?,
+                FieldNamePart::Index(index) => call_getitem(vm, &argument, &vm.ctx.new_int(index))?,
+                FieldNamePart::StringIndex(key) => call_getitem(vm, &argument, &vm.new_str(key))?,
+            };
+        }
+
+        // replacement fields inside the format spec are expanded first
+        let format_spec = if format_spec.contains('{') {
+            if recursion_depth == 0 {
+                return Err(vm.new_value_error("Max string recursion exceeded".to_string()));
             }
-            FormatPart::Literal(literal) => literal.clone(),
+            let nested = FormatString::from_str(format_spec)
+                .map_err(|err| new_format_parse_error(vm, err))?;
+            format_parts(vm, &nested, arguments, state, recursion_depth - 1)?
+        } else {
+            format_spec.clone()
         };
-        final_string.push_str(&result_string);
+
+        let result = call_object_format(vm, argument, *preconversion_spec, &format_spec)?;
+        final_string.push_str(&get_value(&result));
     }
-    Ok(vm.ctx.new_str(final_string))
+    Ok(final_string)
 }
 
 impl PySliceableSequence for String {
@@ -1554,6 +1763,7 @@ fn to_graphemes<S: AsRef<str>>(value: S) -> Vec<String> {
 }
 
 pub fn subscript(vm: &VirtualMachine, value: &str, b: PyObjectRef) -> PyResult {
+    let b = objsequence::index_subscript(vm, b)?;
     if objtype::isinstance(&b, &vm.ctx.int_type()) {
         match objint::get_value(&b).to_i32() {
             Some(pos) => {
@@ -1572,10 +1782,10 @@ pub fn subscript(vm: &VirtualMachine, value: &str, b: PyObjectRef) -> PyResult {
         let string = value.to_string().get_slice_items(vm, &b)?;
         Ok(vm.new_str(string))
     } else {
-        panic!(
-            "TypeError: indexing type {:?} with index {:?} is not supported (yet?)",
-            value, b
-        )
+        Err(vm.new_type_error(format!(
+            "string indices must be integers, not {}",
+            b.class().name
+        )))
     }
 }
 
@@ -61,8 +61,9 @@ impl PySuper {
             Some(PyClass { ref mro, .. }) => {
                 for class in mro {
                     if let Ok(item) = vm.get_attribute(class.as_object().clone(), name.clone()) {
-                        if item.payload_is::<PyMethod>() {
-                            // This is a classmethod
+                        if item.payload_is::<PyMethod>() || name.as_str() == "__new__" {
+                            // This is a classmethod, or `__new__` which is
+                            // implicitly a staticmethod
                             return Ok(item);
                         }
                         return Ok(vm.ctx.new_bound_method(item, inst.clone()));
@@ -9,7 +9,10 @@ use crate::pyobject::{
 };
 use crate::vm::VirtualMachine;
 
+use super::objbuiltinfunc;
+use super::objclassmethod::PyClassMethod;
 use super::objdict::PyDictRef;
+use super::objfunction::PyFunction;
 use super::objlist::PyList;
 use super::objmappingproxy::PyMappingProxy;
 use super::objproperty::PropertyBuilder;
@@ -79,6 +82,12 @@ impl PyClassRef {
         PyTuple::from(elements)
     }
 
+    fn mro_method(self, _vm: &VirtualMachine) -> PyList {
+        let elements: Vec<PyObjectRef> =
+            _mro(&self).iter().map(|x| x.as_object().clone()).collect();
+        PyList::from(elements)
+    }
+
     fn set_mro(self, _value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         Err(vm.new_attribute_error("read-only attribute".to_string()))
     }
@@ -92,8 +101,25 @@ impl PyClassRef {
         PyList::from(attributes)
     }
 
-    fn instance_check(self, obj: PyObjectRef, _vm: &VirtualMachine) -> bool {
-        isinstance(&obj, &self)
+    fn instance_check(self, obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+        if isinstance(&obj, &self) {
+            return Ok(true);
+        }
+        // An object may claim a different class through `__class__`, as proxies do.
+        let cls = match vm.get_attribute(obj.clone(), "__class__") {
+            Ok(cls) => cls,
+            Err(err) => {
+                return if isinstance(&err, &vm.ctx.exceptions.attribute_error) {
+                    Ok(false)
+                } else {
+                    Err(err)
+                };
+            }
+        };
+        match cls.downcast::<PyClass>() {
+            Ok(cls) => Ok(!cls.is(&obj.class()) && issubclass(&cls, &self)),
+            Err(_) => Ok(false),
+        }
     }
 
     fn subclass_check(self, subclass: PyClassRef, _vm: &VirtualMachine) -> bool {
@@ -158,8 +184,6 @@ impl PyClassRef {
             Ok(cls_attr)
         } else if let Some(attr) = class_get_attr(&mcl, &name) {
             vm.call_get_descriptor(attr, self.into_object())
-        } else if let Some(ref getter) = class_get_attr(&self, "__getattr__") {
-            vm.invoke(getter, vec![mcl.into_object(), name_ref.into_object()])
         } else {
             Err(vm.new_attribute_error(format!("{} has no attribute '{}'", self, name)))
         }
@@ -184,11 +208,30 @@ impl PyClassRef {
         Ok(())
     }
 
+    fn del_attr(self, attr_name: PyStringRef, vm: &VirtualMachine) -> PyResult<()> {
+        if let Some(attr) = class_get_attr(&self.class(), &attr_name.value) {
+            if let Some(ref descriptor) = class_get_attr(&attr.class(), "__delete__") {
+                vm.invoke(descriptor, vec![attr, self.into_object()])?;
+                return Ok(());
+            }
+        }
+
+        match self.attributes.borrow_mut().remove(&attr_name.value) {
+            Some(_) => Ok(()),
+            None => Err(vm.new_attribute_error(format!(
+                "type object '{}' has no attribute '{}'",
+                self.name, &attr_name.value
+            ))),
+        }
+    }
+
     // This is used for class initialisation where the vm is not yet available.
     pub fn set_str_attr<V: Into<PyObjectRef>>(&self, attr_name: &str, value: V) {
+        let value = value.into();
+        objbuiltinfunc::set_default_name(&value, attr_name);
         self.attributes
             .borrow_mut()
-            .insert(attr_name.to_string(), value.into());
+            .insert(attr_name.to_string(), value);
     }
 
     fn subclasses(self, _vm: &VirtualMachine) -> PyList {
@@ -225,6 +268,7 @@ pub fn init(ctx: &PyContext) {
                 .add_getter(PyClassRef::mro)
                 .add_setter(PyClassRef::set_mro)
                 .create(),
+        "mro" => ctx.new_rustfunc(PyClassRef::mro_method),
         "__name__" => ctx.new_property(PyClassRef::name),
         "__repr__" => ctx.new_rustfunc(PyClassRef::repr),
         "__qualname__" => ctx.new_property(PyClassRef::qualname),
@@ -232,6 +276,7 @@ pub fn init(ctx: &PyContext) {
         "__prepare__" => ctx.new_rustfunc(PyClassRef::prepare),
         "__getattribute__" => ctx.new_rustfunc(PyClassRef::getattribute),
         "__setattr__" => ctx.new_rustfunc(PyClassRef::set_attr),
+        "__delattr__" => ctx.new_rustfunc(PyClassRef::del_attr),
         "__subclasses__" => ctx.new_rustfunc(PyClassRef::subclasses),
         "__getattribute__" => ctx.new_rustfunc(PyClassRef::getattribute),
         "__instancecheck__" => ctx.new_rustfunc(PyClassRef::instance_check),
@@ -260,6 +305,21 @@ pub fn issubclass(subclass: &PyClassRef, cls: &PyClassRef) -> bool {
     subclass.is(cls) || mro.iter().any(|c| c.is(cls.as_object()))
 }
 
+/// Subscript an object that has no `__getitem__`: classes may still be
+/// subscripted through their `__class_getitem__` classmethod.
+pub fn class_getitem(obj: &PyObjectRef, key: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    if let Ok(cls) = obj.clone().downcast::<PyClass>() {
+        if class_has_attr(&cls, "__class_getitem__") {
+            let func = vm.get_attribute(cls.into_object(), "__class_getitem__")?;
+            return vm.invoke(&func, key);
+        }
+    }
+    Err(vm.new_type_error(format!(
+        "'{}' object is not subscriptable",
+        obj.class().name
+    )))
+}
+
 pub fn type_new(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     vm_trace!("type.__new__ {:?}", args);
     if args.args.len() == 2 {
@@ -281,7 +341,30 @@ pub fn type_new_class(
 ) -> PyResult<PyClassRef> {
     let mut bases: Vec<PyClassRef> = bases.iter(vm)?.collect::<Result<Vec<_>, _>>()?;
     bases.push(vm.ctx.object());
-    new(typ.clone(), &name.value, bases, dict.to_attributes())
+    let mut attributes = dict.to_attributes();
+    // __class_getitem__ is implicitly a classmethod
+    if let Some(func) = attributes.get_mut("__class_getitem__") {
+        if func.payload_is::<PyFunction>() {
+            *func = PyClassMethod {
+                callable: func.clone(),
+            }
+            .into_ref(vm)
+            .into_object();
+        }
+    }
+    // Like CPython, the first class in a hierarchy that gives its instances
+    // a __dict__ exposes the descriptor in its own namespace.
+    let object = vm.ctx.object();
+    let inherits_dict = bases
+        .iter()
+        .flat_map(|base| base.iter_mro())
+        .any(|cls| !cls.is(&object) && cls.attributes.borrow().contains_key("__dict__"));
+    if !inherits_dict && !attributes.contains_key("__dict__") {
+        if let Some(descriptor) = object.attributes.borrow().get("__dict__") {
+            attributes.insert("__dict__".to_string(), descriptor.clone());
+        }
+    }
+    new(typ.clone(), &name.value, bases, attributes)
 }
 
 pub fn type_call(class: PyClassRef, args: Args, kwargs: KwArgs, vm: &VirtualMachine) -> PyResult {
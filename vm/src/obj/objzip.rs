@@ -11,6 +11,7 @@ pub type PyZipRef = PyRef<PyZip>;
 #[derive(Debug)]
 pub struct PyZip {
     iterators: Vec<PyObjectRef>,
+    strict: bool,
 }
 
 impl PyValue for PyZip {
@@ -19,12 +20,27 @@ impl PyValue for PyZip {
     }
 }
 
-fn zip_new(cls: PyClassRef, iterables: Args, vm: &VirtualMachine) -> PyResult<PyZipRef> {
+#[derive(FromArgs)]
+struct ZipOptions {
+    #[pyarg(keyword_only, default = "false")]
+    strict: bool,
+}
+
+fn zip_new(
+    cls: PyClassRef,
+    iterables: Args,
+    options: ZipOptions,
+    vm: &VirtualMachine,
+) -> PyResult<PyZipRef> {
     let iterators = iterables
         .into_iter()
         .map(|iterable| objiter::get_iter(vm, &iterable))
         .collect::<Result<Vec<_>, _>>()?;
-    PyZip { iterators }.into_ref_with_type(vm, cls)
+    PyZip {
+        iterators,
+        strict: options.strict,
+    }
+    .into_ref_with_type(vm, cls)
 }
 
 #[pyimpl]
@@ -34,12 +50,31 @@ impl PyZip {
         if self.iterators.is_empty() {
             Err(objiter::new_stop_iteration(vm))
         } else {
-            let next_objs = self
-                .iterators
-                .iter()
-                .map(|iterator| objiter::call_next(vm, iterator))
-                .collect::<Result<Vec<_>, _>>()?;
+            if !self.strict {
+                let next_objs = self
+                    .iterators
+                    .iter()
+                    .map(|iterator| objiter::call_next(vm, iterator))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(vm.ctx.new_tuple(next_objs));
+            }
 
+            let mut next_objs = Vec::with_capacity(self.iterators.len());
+            for (i, iterator) in self.iterators.iter().enumerate() {
+                match objiter::get_next_object(vm, iterator)? {
+                    Some(obj) => next_objs.push(obj),
+                    None if i > 0 => return Err(length_mismatch(i, "shorter", vm)),
+                    None => {
+                        // The first iterator is exhausted; so must all the others be.
+                        for (i, iterator) in self.iterators.iter().enumerate().skip(1) {
+                            if objiter::get_next_object(vm, iterator)?.is_some() {
+                                return Err(length_mismatch(i, "longer", vm));
+                            }
+                        }
+                        return Err(objiter::new_stop_iteration(vm));
+                    }
+                }
+            }
             Ok(vm.ctx.new_tuple(next_objs))
         }
     }
@@ -50,6 +85,20 @@ impl PyZip {
     }
 }
 
+fn length_mismatch(index: usize, relation: &str, vm: &VirtualMachine) -> PyObjectRef {
+    let preceding = if index == 1 {
+        " 1".to_string()
+    } else {
+        format!("s 1-{}", index)
+    };
+    vm.new_value_error(format!(
+        "zip() argument {} is {} than argument{}",
+        index + 1,
+        relation,
+        preceding
+    ))
+}
+
 pub fn init(context: &PyContext) {
     PyZip::extend_class(context, &context.types.zip_type);
     extend_class!(context, &context.types.zip_type, {
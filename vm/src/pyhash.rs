@@ -1,6 +1,5 @@
 use num_bigint::BigInt;
 use num_traits::ToPrimitive;
-use std::hash::{Hash, Hasher};
 
 use crate::obj::objfloat;
 use crate::pyobject::PyObjectRef;
@@ -18,6 +17,13 @@ pub const MODULUS: PyUHash = (1 << BITS) - 1;
 pub const INF: PyHash = 314_159;
 pub const NAN: PyHash = 0;
 pub const IMAG: PyHash = MULTIPLIER;
+/// -1 is reserved for errors in CPython, so no hash ever takes that value.
+pub const ERROR_SENTINEL: PyHash = -1;
+
+// Constants of the xxHash-based tuple hash.
+const XXPRIME_1: PyUHash = 11_400_714_785_074_694_791;
+const XXPRIME_2: PyUHash = 14_029_467_366_897_019_727;
+const XXPRIME_5: PyUHash = 2_870_177_450_012_600_261;
 
 // pub const CUTOFF: usize = 7;
 
@@ -63,30 +69,184 @@ pub fn hash_float(value: f64) -> PyHash {
     };
     x = ((x << e) & MODULUS) | x >> (BITS32 - e);
 
-    x as PyHash * value.signum() as PyHash
+    fix_sentinel(x as PyHash * value.signum() as PyHash)
+}
+
+/// The key of the string and bytes hash, chosen once per process.
+///
+/// Without `PYTHONHASHSEED` the key is random, so the hash of a str or bytes
+/// object (and with it the iteration order of sets and dicts keyed by them)
+/// differs between runs. A seed of 0 disables the randomization.
+#[derive(Debug, Clone, Copy)]
+pub struct HashSecret {
+    k0: u64,
+    k1: u64,
+}
+
+impl HashSecret {
+    /// Derive the key from a seed as CPython does, or pick a random one.
+    pub fn new(seed: Option<u32>) -> Self {
+        match seed {
+            Some(0) => HashSecret { k0: 0, k1: 0 },
+            Some(seed) => {
+                // the linear congruential generator of CPython's bootstrap_hash.c
+                let mut x = seed;
+                let mut key = [0u8; 16];
+                for byte in key.iter_mut() {
+                    x = x.wrapping_mul(214_013).wrapping_add(2_531_011);
+                    *byte = (x >> 16) as u8;
+                }
+                let mut k0 = [0u8; 8];
+                let mut k1 = [0u8; 8];
+                k0.copy_from_slice(&key[..8]);
+                k1.copy_from_slice(&key[8..]);
+                HashSecret {
+                    k0: u64::from_le_bytes(k0),
+                    k1: u64::from_le_bytes(k1),
+                }
+            }
+            None => HashSecret {
+                k0: rand::random(),
+                k1: rand::random(),
+            },
+        }
+    }
+
+    pub fn hash_bytes(&self, data: &[u8]) -> PyHash {
+        if data.is_empty() {
+            return 0;
+        }
+        fix_sentinel(siphash13(self.k0, self.k1, data) as PyHash)
+    }
+
+    /// Hash a string through the same bytes CPython hashes: one, two or four
+    /// bytes per character depending on the widest character.
+    pub fn hash_str(&self, value: &str) -> PyHash {
+        let max_char = value.chars().map(|c| c as u32).max().unwrap_or(0);
+        if max_char < 0x80 {
+            return self.hash_bytes(value.as_bytes());
+        }
+        let data: Vec<u8> = if max_char <= 0xff {
+            value.chars().map(|c| c as u8).collect()
+        } else if max_char <= 0xffff {
+            value
+                .chars()
+                .flat_map(|c| (c as u16).to_le_bytes().to_vec())
+                .collect()
+        } else {
+            value
+                .chars()
+                .flat_map(|c| (c as u32).to_le_bytes().to_vec())
+                .collect()
+        };
+        self.hash_bytes(&data)
+    }
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
 }
 
-pub fn hash_value<T: Hash>(data: &T) -> PyHash {
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    data.hash(&mut hasher);
-    hasher.finish() as PyHash
+/// SipHash-1-3, the string hash of CPython.
+pub fn siphash13(k0: u64, k1: u64, data: &[u8]) -> u64 {
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(chunk);
+        let m = u64::from_le_bytes(word);
+        v[3] ^= m;
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+    let mut last = (data.len() as u64) << 56;
+    for (i, byte) in chunks.remainder().iter().enumerate() {
+        last |= u64::from(*byte) << (8 * i);
+    }
+    v[3] ^= last;
+    sip_round(&mut v);
+    v[0] ^= last;
+    v[2] ^= 0xff;
+    for _ in 0..3 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
 }
 
-pub fn hash_iter<'a, I: std::iter::Iterator<Item = &'a PyObjectRef>>(
+/// Hash a sequence of objects the way CPython hashes tuples.
+pub fn hash_iter<'a, I: std::iter::ExactSizeIterator<Item = &'a PyObjectRef>>(
     iter: I,
     vm: &VirtualMachine,
 ) -> PyResult<PyHash> {
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let len = iter.len() as PyUHash;
+    let mut acc = XXPRIME_5;
     for element in iter {
-        let item_hash = vm._hash(&element)?;
-        item_hash.hash(&mut hasher);
+        let lane = vm._hash(&element)? as PyUHash;
+        acc = acc.wrapping_add(lane.wrapping_mul(XXPRIME_2));
+        acc = acc.rotate_left(31);
+        acc = acc.wrapping_mul(XXPRIME_1);
+    }
+    acc = acc.wrapping_add(len ^ (XXPRIME_5 ^ 3_527_539));
+    if acc as PyHash == ERROR_SENTINEL {
+        return Ok(1_546_275_796);
     }
-    Ok(hasher.finish() as PyHash)
+    Ok(acc as PyHash)
 }
 
 pub fn hash_bigint(value: &BigInt) -> PyHash {
-    match value.to_i64() {
+    fix_sentinel(match value.to_i64() {
         Some(i64_value) => (i64_value % MODULUS as i64),
         None => (value % MODULUS).to_i64().unwrap(),
+    })
+}
+
+/// Replace the reserved hash value -1 by -2, as CPython does.
+pub fn fix_sentinel(x: PyHash) -> PyHash {
+    if x == ERROR_SENTINEL {
+        -2
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashSecret;
+
+    #[test]
+    fn test_seeded_hash_matches_cpython() {
+        let secret = HashSecret::new(Some(0));
+        assert_eq!(secret.hash_bytes(b""), 0);
+        assert_eq!(secret.hash_bytes(b"abc"), -4_594_863_902_769_663_758);
+        assert_eq!(secret.hash_str("abc"), -4_594_863_902_769_663_758);
+        assert_eq!(secret.hash_bytes(b"abcdefghij"), -829_746_140_550_000_655);
+        assert_eq!(secret.hash_str("\u{e9}"), 6_047_309_291_227_476_195);
+        assert_eq!(secret.hash_str("h\u{20ac}llo"), 8_923_438_597_601_333_813);
+        assert_eq!(secret.hash_str("a\u{1f600}"), -6_970_120_874_385_731_343);
+
+        let secret = HashSecret::new(Some(42));
+        assert_eq!(secret.hash_bytes(b"abc"), 3_869_580_338_025_362_921);
+        assert_eq!(secret.hash_bytes(b"abcdefghij"), -7_829_893_295_273_403_573);
+    }
+
+    #[test]
+    fn test_random_secrets_differ() {
+        let a = HashSecret::new(None);
+        let b = HashSecret::new(None);
+        assert_ne!(a.hash_str("some text"), b.hash_str("some text"));
     }
 }
@@ -3,6 +3,7 @@ use std::cell::Cell;
 use std::collections::HashMap;
 use std::fmt;
 use std::marker::PhantomData;
+use std::mem;
 use std::ops::Deref;
 use std::rc::Rc;
 
@@ -37,6 +38,7 @@ use crate::obj::objstr;
 use crate::obj::objtuple::{PyTuple, PyTupleRef};
 use crate::obj::objtype::{self, PyClass, PyClassRef};
 use crate::scope::Scope;
+use crate::stdlib::tracemalloc;
 use crate::types::{create_type, initialize_types, TypeZoo};
 use crate::vm::VirtualMachine;
 use indexmap::IndexMap;
@@ -347,6 +349,10 @@ impl PyContext {
         self.types.weakproxy_type.clone()
     }
 
+    pub fn traceback_type(&self) -> PyClassRef {
+        self.types.traceback_type.clone()
+    }
+
     pub fn type_type(&self) -> PyClassRef {
         self.types.type_type.clone()
     }
@@ -780,7 +786,14 @@ pub trait ItemProtocol {
 
 impl ItemProtocol for PyObjectRef {
     fn get_item<T: IntoPyObject>(&self, key: T, vm: &VirtualMachine) -> PyResult {
-        vm.call_method(self, "__getitem__", key.into_pyobject(vm)?)
+        let key = key.into_pyobject(vm)?;
+        match objtype::class_get_attr(&self.class(), "__getitem__") {
+            Some(func) => {
+                let wrapped = vm.call_get_descriptor(func, self.clone())?;
+                vm.invoke(&wrapped, key)
+            }
+            None => objtype::class_getitem(self, key, vm),
+        }
     }
 
     fn set_item<T: IntoPyObject>(
@@ -1005,7 +1018,22 @@ where
 
     // Move this object into a reference object, transferring ownership.
     pub fn into_ref(self) -> PyObjectRef {
-        Rc::new(self)
+        let obj: PyObjectRef = Rc::new(self);
+        if tracemalloc::is_tracing() {
+            tracemalloc::record_alloc(&obj, mem::size_of::<Self>());
+        }
+        obj
+    }
+}
+
+impl<T> Drop for PyObject<T>
+where
+    T: ?Sized + PyObjectPayload,
+{
+    fn drop(&mut self) {
+        if tracemalloc::is_tracing() {
+            tracemalloc::record_free(self as *const Self as *const u8 as usize);
+        }
     }
 }
 
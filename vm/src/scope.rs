@@ -113,11 +113,14 @@ impl NameProtocol for Scope {
     }
 
     fn store_cell(&self, vm: &VirtualMachine, name: &str, value: PyObjectRef) {
-        self.locals
-            .get(1)
-            .expect("no outer scope for non-local")
-            .set_item(name, value, vm)
-            .unwrap();
+        // Rebind the variable in the nearest enclosing scope that defines it.
+        let outer_scopes = self.locals.get(1..).unwrap_or(&[]);
+        let dict = outer_scopes
+            .iter()
+            .find(|dict| dict.contains_key(name, vm))
+            .or_else(|| outer_scopes.first())
+            .expect("no outer scope for non-local");
+        dict.set_item(name, value, vm).unwrap();
     }
 
     fn store_name(&self, vm: &VirtualMachine, key: &str, value: PyObjectRef) {
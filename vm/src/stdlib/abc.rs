@@ -0,0 +1,296 @@
+/*
+ * The `_abc` module, the native backend of `abc.ABCMeta`.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::obj::objbool;
+use crate::obj::objlist::PyList;
+use crate::obj::objobject;
+use crate::obj::objtype::{self, PyClassRef};
+use crate::obj::objweakref::PyWeak;
+use crate::pyobject::{
+    IdProtocol, PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue, TryFromObject, TypeProtocol,
+};
+use crate::vm::VirtualMachine;
+
+/// Bumped on every `register()` call, which invalidates all negative caches.
+static ABC_INVALIDATION_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A set of classes, held by weak references like `WeakSet` in `_py_abc`.
+#[derive(Debug, Default)]
+struct WeakClassSet {
+    items: RefCell<Vec<PyWeak>>,
+}
+
+impl WeakClassSet {
+    fn contains(&self, cls: &PyClassRef) -> bool {
+        self.items
+            .borrow()
+            .iter()
+            .any(|item| item.upgrade().map_or(false, |obj| obj.is(cls)))
+    }
+
+    fn add(&self, cls: &PyClassRef) {
+        if !self.contains(cls) {
+            let mut items = self.items.borrow_mut();
+            items.retain(|item| item.upgrade().is_some());
+            items.push(PyWeak::downgrade(cls.as_object()));
+        }
+    }
+
+    fn clear(&self) {
+        self.items.borrow_mut().clear();
+    }
+
+    fn classes(&self) -> Vec<PyClassRef> {
+        self.items
+            .borrow()
+            .iter()
+            .filter_map(|item| item.upgrade())
+            .filter_map(|obj| obj.downcast().ok())
+            .collect()
+    }
+
+    fn to_set(&self, vm: &VirtualMachine) -> PyResult {
+        let classes: Vec<PyObjectRef> = self
+            .classes()
+            .into_iter()
+            .map(PyClassRef::into_object)
+            .collect();
+        vm.invoke(
+            vm.ctx.set_type().as_object(),
+            vec![PyList::from(classes).into_ref(vm).into_object()],
+        )
+    }
+}
+
+/// Per-class state of an ABC, stored as `cls._abc_impl`.
+#[pyclass(name = "_abc_data")]
+#[derive(Debug, Default)]
+struct AbcData {
+    registry: WeakClassSet,
+    cache: WeakClassSet,
+    negative_cache: WeakClassSet,
+    negative_cache_version: Cell<usize>,
+}
+
+impl PyValue for AbcData {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("_abc", "_abc_data")
+    }
+}
+
+#[pyimpl]
+impl AbcData {}
+
+fn get_impl(cls: &PyClassRef, vm: &VirtualMachine) -> PyResult<PyRef<AbcData>> {
+    vm.get_attribute(cls.as_object().clone(), "_abc_impl")?
+        .downcast()
+        .map_err(|_| vm.new_type_error("_abc_impl is set to a wrong type".to_string()))
+}
+
+fn get_cache_token(_vm: &VirtualMachine) -> usize {
+    ABC_INVALIDATION_COUNTER.load(Ordering::SeqCst)
+}
+
+/// Compute `cls.__abstractmethods__` and set up the registry and caches.
+fn abc_init(cls: PyClassRef, vm: &VirtualMachine) -> PyResult<()> {
+    let mut abstracts: Vec<String> = vec![];
+    let namespace: Vec<(String, PyObjectRef)> = cls
+        .attributes
+        .borrow()
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    for (name, value) in namespace {
+        if objobject::is_abstract(&value, vm)? {
+            abstracts.push(name);
+        }
+    }
+    for base in &cls.mro {
+        let names = match objtype::class_get_attr(base, "__abstractmethods__") {
+            Some(names) => vm.extract_elements(&names)?,
+            None => continue,
+        };
+        for name in names {
+            let name = vm.to_str(&name)?.as_str().to_string();
+            if abstracts.contains(&name) {
+                continue;
+            }
+            if let Some(value) = objtype::class_get_attr(&cls, &name) {
+                if objobject::is_abstract(&value, vm)? {
+                    abstracts.push(name);
+                }
+            }
+        }
+    }
+    let abstracts: Vec<PyObjectRef> = abstracts.into_iter().map(|n| vm.new_str(n)).collect();
+    let abstracts = vm.invoke(
+        vm.ctx.frozenset_type().as_object(),
+        vec![PyList::from(abstracts).into_ref(vm).into_object()],
+    )?;
+    vm.set_attr(cls.as_object(), "__abstractmethods__", abstracts)?;
+
+    let data = AbcData::default();
+    data.negative_cache_version.set(get_cache_token(vm));
+    vm.set_attr(cls.as_object(), "_abc_impl", data.into_ref(vm))?;
+    Ok(())
+}
+
+/// Register `subclass` as a virtual subclass of `cls`.
+fn abc_register(cls: PyClassRef, subclass: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let subclass_cls = PyClassRef::try_from_object(vm, subclass.clone())
+        .map_err(|_| vm.new_type_error("Can only register classes".to_string()))?;
+    if vm.issubclass(&subclass_cls, &cls)? {
+        // Already a subclass
+        return Ok(subclass);
+    }
+    // Test for cycles after the "already a subclass" check, so that
+    // X.register(X) is allowed as a no-op.
+    if vm.issubclass(&cls, &subclass_cls)? {
+        return Err(vm.new_runtime_error("Refusing to create an inheritance cycle".to_string()));
+    }
+    get_impl(&cls, vm)?.registry.add(&subclass_cls);
+    ABC_INVALIDATION_COUNTER.fetch_add(1, Ordering::SeqCst);
+    Ok(subclass)
+}
+
+fn abc_instancecheck(cls: PyClassRef, instance: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let data = get_impl(&cls, vm)?;
+    let subclass = vm.get_attribute(instance.clone(), "__class__")?;
+    if let Ok(subclass) = subclass.clone().downcast::<objtype::PyClass>() {
+        if data.cache.contains(&subclass) {
+            return Ok(vm.new_bool(true));
+        }
+    }
+    let subtype = instance.class().into_object();
+    if subtype.is(&subclass) {
+        if data.negative_cache_version.get() == get_cache_token(vm) {
+            if let Ok(subclass) = subclass.clone().downcast::<objtype::PyClass>() {
+                if data.negative_cache.contains(&subclass) {
+                    return Ok(vm.new_bool(false));
+                }
+            }
+        }
+        return vm.call_method(cls.as_object(), "__subclasscheck__", vec![subclass]);
+    }
+    let result = vm.call_method(cls.as_object(), "__subclasscheck__", vec![subclass])?;
+    if objbool::boolval(vm, result.clone())? {
+        return Ok(result);
+    }
+    vm.call_method(cls.as_object(), "__subclasscheck__", vec![subtype])
+}
+
+fn abc_subclasscheck(
+    cls: PyClassRef,
+    subclass: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<bool> {
+    let subclass = PyClassRef::try_from_object(vm, subclass)
+        .map_err(|_| vm.new_type_error("issubclass() arg 1 must be a class".to_string()))?;
+    let data = get_impl(&cls, vm)?;
+
+    // Check the positive cache, then the negative one (after invalidating it
+    // if anything got registered in the meantime).
+    if data.cache.contains(&subclass) {
+        return Ok(true);
+    }
+    let token = get_cache_token(vm);
+    if data.negative_cache_version.get() < token {
+        data.negative_cache.clear();
+        data.negative_cache_version.set(token);
+    } else if data.negative_cache.contains(&subclass) {
+        return Ok(false);
+    }
+
+    // Let the subclass hook have a say.
+    let hook = vm.get_attribute(cls.as_object().clone(), "__subclasshook__")?;
+    let ok = vm.invoke(&hook, vec![subclass.as_object().clone()])?;
+    if !ok.is(&vm.ctx.not_implemented()) {
+        if !objtype::isinstance(&ok, &vm.ctx.bool_type()) {
+            return Err(vm.new_exception(
+                vm.ctx.exceptions.assertion_error.clone(),
+                "__subclasshook__ must return either False, True, or NotImplemented".to_string(),
+            ));
+        }
+        let ok = objbool::get_value(&ok);
+        if ok {
+            data.cache.add(&subclass);
+        } else {
+            data.negative_cache.add(&subclass);
+        }
+        return Ok(ok);
+    }
+
+    // A real subclass, or a subclass of a registered or real subclass.
+    let mut is_subclass = objtype::issubclass(&subclass, &cls);
+    if !is_subclass {
+        for registered in data.registry.classes() {
+            if vm.issubclass(&subclass, &registered)? {
+                is_subclass = true;
+                break;
+            }
+        }
+    }
+    if !is_subclass {
+        let subclasses: Vec<PyClassRef> = cls
+            .subclasses
+            .borrow()
+            .iter()
+            .filter_map(|item| item.upgrade())
+            .filter_map(|obj| obj.downcast().ok())
+            .collect();
+        for scls in subclasses {
+            if vm.issubclass(&subclass, &scls)? {
+                is_subclass = true;
+                break;
+            }
+        }
+    }
+    if is_subclass {
+        data.cache.add(&subclass);
+    } else {
+        data.negative_cache.add(&subclass);
+    }
+    Ok(is_subclass)
+}
+
+fn get_dump(cls: PyClassRef, vm: &VirtualMachine) -> PyResult {
+    let data = get_impl(&cls, vm)?;
+    Ok(vm.ctx.new_tuple(vec![
+        data.registry.to_set(vm)?,
+        data.cache.to_set(vm)?,
+        data.negative_cache.to_set(vm)?,
+        vm.new_int(data.negative_cache_version.get()),
+    ]))
+}
+
+fn reset_registry(cls: PyClassRef, vm: &VirtualMachine) -> PyResult<()> {
+    get_impl(&cls, vm)?.registry.clear();
+    Ok(())
+}
+
+fn reset_caches(cls: PyClassRef, vm: &VirtualMachine) -> PyResult<()> {
+    let data = get_impl(&cls, vm)?;
+    data.cache.clear();
+    data.negative_cache.clear();
+    Ok(())
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    py_module!(vm, "_abc", {
+        "_abc_data" => AbcData::make_class(ctx),
+        "get_cache_token" => ctx.new_rustfunc(get_cache_token),
+        "_abc_init" => ctx.new_rustfunc(abc_init),
+        "_abc_register" => ctx.new_rustfunc(abc_register),
+        "_abc_instancecheck" => ctx.new_rustfunc(abc_instancecheck),
+        "_abc_subclasscheck" => ctx.new_rustfunc(abc_subclasscheck),
+        "_get_dump" => ctx.new_rustfunc(get_dump),
+        "_reset_registry" => ctx.new_rustfunc(reset_registry),
+        "_reset_caches" => ctx.new_rustfunc(reset_caches),
+    })
+}
@@ -1,15 +1,21 @@
 use crate::function::OptionalArg;
 use crate::obj::objbytes::PyBytesRef;
+use crate::obj::objlist::PyListRef;
+use crate::obj::objsequence::{PySliceableSequence, SequenceIndex};
+use crate::obj::objslice::PySliceRef;
 use crate::obj::objstr::PyStringRef;
 use crate::obj::objtype::PyClassRef;
 use crate::obj::{objbool, objiter};
 use crate::pyobject::{
     IntoPyObject, PyClassImpl, PyIterable, PyObjectRef, PyRef, PyResult, PyValue, TryFromObject,
+    TypeProtocol,
 };
 use crate::VirtualMachine;
 
 use std::cell::{Cell, RefCell};
 use std::fmt;
+use std::ops::Range;
+use std::os::raw::{c_int, c_long, c_longlong, c_short, c_uint, c_ulong, c_ulonglong, c_ushort};
 
 struct ArrayTypeSpecifierError {
     _priv: (),
@@ -26,7 +32,7 @@ impl fmt::Display for ArrayTypeSpecifierError {
 
 macro_rules! def_array_enum {
     ($(($n:ident, $t:ident, $c:literal)),*$(,)?) => {
-        #[derive(Debug)]
+        #[derive(Debug, Clone)]
         enum ArrayContentType {
             $($n(Vec<$t>),)*
         }
@@ -147,6 +153,58 @@ macro_rules! def_array_enum {
                 }
             }
 
+            fn getitem_by_slice(&self, slice: PySliceRef, vm: &VirtualMachine) -> PyResult<Self> {
+                match self {
+                    $(ArrayContentType::$n(v) => {
+                        Ok(ArrayContentType::$n(v.get_slice_items(vm, slice.as_object())?))
+                    })*
+                }
+            }
+
+            fn setitem(&mut self, i: usize, obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+                match self {
+                    $(ArrayContentType::$n(v) => {
+                        v[i] = $t::try_from_object(vm, obj)?;
+                    })*
+                }
+                Ok(())
+            }
+
+            /// Replace `range` with the items of `other`, which may have a different length.
+            fn set_range(&mut self, range: Range<usize>, other: &Self) -> Result<(), ()> {
+                match (self, other) {
+                    $((ArrayContentType::$n(v), ArrayContentType::$n(o)) => {
+                        v.splice(range, o.iter().cloned());
+                        Ok(())
+                    })*
+                    _ => Err(()),
+                }
+            }
+
+            /// Assign the items of `other`, of the same length, to the given indices.
+            fn set_indices(&mut self, indices: &[usize], other: &Self) -> Result<(), ()> {
+                match (self, other) {
+                    $((ArrayContentType::$n(v), ArrayContentType::$n(o)) => {
+                        for (&i, &x) in indices.iter().zip(o.iter()) {
+                            v[i] = x;
+                        }
+                        Ok(())
+                    })*
+                    _ => Err(()),
+                }
+            }
+
+            /// Remove the items at `indices`, which must be in ascending order.
+            fn remove_indices(&mut self, indices: &[usize]) {
+                match self {
+                    $(ArrayContentType::$n(v) => {
+                        for &i in indices.iter().rev() {
+                            v.remove(i);
+                        }
+                    })*
+                }
+            }
+
             fn iter<'a>(&'a self, vm: &'a VirtualMachine) -> impl Iterator<Item = PyResult> + 'a {
                 let mut i = 0;
                 std::iter::from_fn(move || {
@@ -159,18 +217,19 @@ macro_rules! def_array_enum {
     };
 }
 
+// The integer typecodes have the sizes of the C types they are named after.
 def_array_enum!(
     (SignedByte, i8, 'b'),
     (UnsignedByte, u8, 'B'),
     // TODO: support unicode char
-    (SignedShort, i16, 'h'),
-    (UnsignedShort, u16, 'H'),
-    (SignedInt, i16, 'i'),
-    (UnsignedInt, u16, 'I'),
-    (SignedLong, i32, 'l'),
-    (UnsignedLong, u32, 'L'),
-    (SignedLongLong, i64, 'q'),
-    (UnsignedLongLong, u64, 'Q'),
+    (SignedShort, c_short, 'h'),
+    (UnsignedShort, c_ushort, 'H'),
+    (SignedInt, c_int, 'i'),
+    (UnsignedInt, c_uint, 'I'),
+    (SignedLong, c_long, 'l'),
+    (UnsignedLong, c_ulong, 'L'),
+    (SignedLongLong, c_longlong, 'q'),
+    (UnsignedLongLong, c_ulonglong, 'Q'),
     (Float, f32, 'f'),
     (Double, f64, 'd'),
 );
@@ -188,6 +247,44 @@ impl PyValue for PyArray {
     }
 }
 
+/// The indices of an array of length `len` selected by `slice`, in the order they are visited.
+fn slice_indices(len: usize, slice: &PySliceRef, vm: &VirtualMachine) -> PyResult<Vec<usize>> {
+    (0..len)
+        .collect::<Vec<_>>()
+        .get_slice_items(vm, slice.as_object())
+}
+
+/// The range replaced by assigning to a slice without a step, which may be empty.
+fn slice_range(
+    len: usize,
+    slice: &PySliceRef,
+    vm: &VirtualMachine,
+) -> PyResult<Option<Range<usize>>> {
+    match slice.step_index(vm)? {
+        Some(ref step) if *step != 1.into() => Ok(None),
+        _ => {
+            let range =
+                vec![(); len].get_slice_range(&slice.start_index(vm)?, &slice.stop_index(vm)?);
+            Ok(Some(range.start..range.end.max(range.start)))
+        }
+    }
+}
+
+impl PyArray {
+    /// The raw contents in native byte order, for objects that read arrays as buffers.
+    pub fn get_bytes(&self) -> Vec<u8> {
+        self.array.borrow().tobytes()
+    }
+
+    pub fn get_itemsize(&self) -> usize {
+        self.array.borrow().itemsize()
+    }
+
+    pub fn get_typecode(&self) -> char {
+        self.array.borrow().typecode()
+    }
+}
+
 #[pyimpl]
 impl PyArray {
     #[pymethod(name = "__new__")]
@@ -247,15 +344,17 @@ impl PyArray {
         if len == 0 {
             return Err(vm.new_index_error("pop from empty array".to_owned()));
         }
-        let i = if i.is_negative() {
-            len - i.abs() as usize
+        self.checked_idx(i, "pop index out of range", vm)
+    }
+
+    fn checked_idx(&self, i: isize, msg: &str, vm: &VirtualMachine) -> PyResult<usize> {
+        let len = self.array.borrow().len() as isize;
+        let i = if i.is_negative() { i + len } else { i };
+        if 0 <= i && i < len {
+            Ok(i as usize)
         } else {
-            i as usize
-        };
-        if i > len - 1 {
-            return Err(vm.new_index_error("pop index out of range".to_owned()));
+            Err(vm.new_index_error(msg.to_owned()))
         }
-        Ok(i)
     }
 
     #[pymethod]
@@ -288,9 +387,27 @@ impl PyArray {
             .ok_or_else(|| vm.new_value_error("x not in array".to_owned()))
     }
 
+    #[pymethod]
+    fn fromlist(&self, list: PyListRef, vm: &VirtualMachine) -> PyResult<()> {
+        // Like extend, but nothing is added unless every item fits.
+        let mut array = self.array.borrow_mut();
+        let len = array.len();
+        for elem in list.elements.borrow().iter() {
+            if let Err(err) = array.push(elem.clone(), vm) {
+                let added: Vec<_> = (len..array.len()).collect();
+                array.remove_indices(&added);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
     #[pymethod]
     fn insert(&self, i: isize, x: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
-        let i = self.idx(i, vm)?;
+        // Out of range positions insert at either end, as for lists.
+        let len = self.array.borrow().len() as isize;
+        let i = if i.is_negative() { i + len } else { i };
+        let i = i.max(0).min(len) as usize;
         self.array.borrow_mut().insert(i, x, vm)
     }
 
@@ -300,23 +417,127 @@ impl PyArray {
         self.array.borrow_mut().pop(i, vm)
     }
 
+    #[pymethod]
+    fn remove(&self, x: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+        let i = self.index(x, vm)?;
+        self.array.borrow_mut().remove_indices(&[i]);
+        Ok(())
+    }
+
     #[pymethod]
     fn tobytes(&self, _vm: &VirtualMachine) -> Vec<u8> {
         self.array.borrow().tobytes()
     }
 
+    #[pymethod]
+    fn tolist(&self, vm: &VirtualMachine) -> PyResult {
+        let items = self.array.borrow().iter(vm).collect::<PyResult<Vec<_>>>()?;
+        Ok(vm.ctx.new_list(items))
+    }
+
     #[pymethod]
     fn reverse(&self, _vm: &VirtualMachine) {
         self.array.borrow_mut().reverse()
     }
 
     #[pymethod(name = "__getitem__")]
-    fn getitem(&self, i: isize, vm: &VirtualMachine) -> PyResult {
-        let i = self.idx(i, vm)?;
-        self.array
-            .borrow()
-            .getitem(i, vm)
-            .unwrap_or_else(|| Err(vm.new_index_error("array index out of range".to_owned())))
+    fn getitem(&self, needle: SequenceIndex, vm: &VirtualMachine) -> PyResult {
+        match needle {
+            SequenceIndex::Int(i) => {
+                let i = self.checked_idx(i as isize, "array index out of range", vm)?;
+                self.array.borrow().getitem(i, vm).unwrap()
+            }
+            SequenceIndex::Slice(slice) => {
+                let array = self.array.borrow().getitem_by_slice(slice, vm)?;
+                Ok(PyArray {
+                    array: RefCell::new(array),
+                }
+                .into_ref(vm)
+                .into_object())
+            }
+        }
+    }
+
+    #[pymethod(name = "__setitem__")]
+    fn setitem(
+        &self,
+        needle: SequenceIndex,
+        obj: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        match needle {
+            SequenceIndex::Int(i) => {
+                let i = self.checked_idx(i as isize, "array assignment index out of range", vm)?;
+                self.array.borrow_mut().setitem(i, obj, vm)
+            }
+            SequenceIndex::Slice(slice) => {
+                // Copy first, the value may be this very array.
+                let other = match obj.payload::<PyArray>() {
+                    Some(other) => other.array.borrow().clone(),
+                    None => {
+                        return Err(vm.new_type_error(format!(
+                            "can only assign array (not \"{}\") to array slice",
+                            obj.class().name
+                        )))
+                    }
+                };
+                let mut array = self.array.borrow_mut();
+                let len = array.len();
+                let result = match slice_range(len, &slice, vm)? {
+                    Some(range) => array.set_range(range, &other),
+                    None => {
+                        let indices = slice_indices(len, &slice, vm)?;
+                        if indices.len() != other.len() {
+                            return Err(vm.new_value_error(format!(
+                                "attempt to assign array of size {} to extended slice of size {}",
+                                other.len(),
+                                indices.len()
+                            )));
+                        }
+                        array.set_indices(&indices, &other)
+                    }
+                };
+                result.map_err(|()| {
+                    vm.new_type_error("bad argument type for built-in operation".to_owned())
+                })
+            }
+        }
+    }
+
+    #[pymethod(name = "__delitem__")]
+    fn delitem(&self, needle: SequenceIndex, vm: &VirtualMachine) -> PyResult<()> {
+        let len = self.array.borrow().len();
+        let mut indices = match needle {
+            SequenceIndex::Int(i) => {
+                vec![self.checked_idx(i as isize, "array assignment index out of range", vm)?]
+            }
+            SequenceIndex::Slice(slice) => slice_indices(len, &slice, vm)?,
+        };
+        indices.sort();
+        self.array.borrow_mut().remove_indices(&indices);
+        Ok(())
+    }
+
+    #[pymethod(name = "__len__")]
+    fn len(&self, _vm: &VirtualMachine) -> usize {
+        self.array.borrow().len()
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        let array = self.array.borrow();
+        if array.len() == 0 {
+            return Ok(format!("array('{}')", array.typecode()));
+        }
+        let mut items = Vec::with_capacity(array.len());
+        for item in array.iter(vm) {
+            items.push(vm.to_repr(&item?)?.as_str().to_string());
+        }
+        Ok(format!(
+            "array('{}', [{}])",
+            array.typecode(),
+            items.join(", ")
+        ))
     }
 
     #[pymethod(name = "__eq__")]
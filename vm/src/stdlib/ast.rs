@@ -158,6 +158,10 @@ fn statement_to_ast(vm: &VirtualMachine, statement: &ast::Statement) -> PyResult
             body => statements_to_ast(vm, body)?,
             orelse => optional_statements_to_ast(vm, orelse)?
         }),
+        Match { subject, cases } => node!(vm, Match, {
+            subject => expression_to_ast(vm, subject)?,
+            cases => map_ast(match_case_to_ast, vm, cases)?
+        }),
         With {
             is_async,
             items,
@@ -274,6 +278,72 @@ fn handler_to_ast(vm: &VirtualMachine, handler: &ast::ExceptHandler) -> PyResult
     Ok(node)
 }
 
+fn match_case_to_ast(vm: &VirtualMachine, case: &ast::MatchCase) -> PyResult<AstNodeRef> {
+    let node = node!(vm, match_case, {
+        pattern => pattern_to_ast(vm, &case.pattern)?,
+        guard => optional_expression_to_ast(vm, &case.guard)?,
+        body => statements_to_ast(vm, &case.body)?,
+    });
+    Ok(node)
+}
+
+fn pattern_to_ast(vm: &VirtualMachine, pattern: &ast::Pattern) -> PyResult<AstNodeRef> {
+    use ast::PatternType::*;
+    let node = match &pattern.node {
+        MatchValue { value } => node!(vm, MatchValue, {
+            value => expression_to_ast(vm, value)?
+        }),
+        MatchSingleton { value } => {
+            let value = match value.node {
+                ast::ExpressionType::True => vm.ctx.new_bool(true),
+                ast::ExpressionType::False => vm.ctx.new_bool(false),
+                _ => vm.ctx.none(),
+            };
+            node!(vm, MatchSingleton, { value => value })
+        }
+        MatchSequence { patterns } => node!(vm, MatchSequence, {
+            patterns => map_ast(pattern_to_ast, vm, patterns)?
+        }),
+        MatchMapping {
+            keys,
+            patterns,
+            rest,
+        } => node!(vm, MatchMapping, {
+            keys => expressions_to_ast(vm, keys)?,
+            patterns => map_ast(pattern_to_ast, vm, patterns)?,
+            rest => optional_string_to_py_obj(vm, rest)
+        }),
+        MatchClass {
+            cls,
+            patterns,
+            kwd_attrs,
+            kwd_patterns,
+        } => node!(vm, MatchClass, {
+            cls => expression_to_ast(vm, cls)?,
+            patterns => map_ast(pattern_to_ast, vm, patterns)?,
+            kwd_attrs => make_string_list(vm, kwd_attrs),
+            kwd_patterns => map_ast(pattern_to_ast, vm, kwd_patterns)?
+        }),
+        MatchStar { name } => node!(vm, MatchStar, {
+            name => optional_string_to_py_obj(vm, name)
+        }),
+        MatchAs { pattern, name } => {
+            let pattern = match pattern {
+                Some(pattern) => pattern_to_ast(vm, pattern)?.into_object(),
+                None => vm.ctx.none(),
+            };
+            node!(vm, MatchAs, {
+                pattern => pattern,
+                name => optional_string_to_py_obj(vm, name)
+            })
+        }
+        MatchOr { patterns } => node!(vm, MatchOr, {
+            patterns => map_ast(pattern_to_ast, vm, patterns)?
+        }),
+    };
+    Ok(node)
+}
+
 fn make_string_list(vm: &VirtualMachine, names: &[String]) -> PyObjectRef {
     vm.ctx.new_list(
         names
@@ -690,6 +760,16 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "Lambda" => py_class!(ctx, "Lambda", ast_base.clone(), {}),
         "List" => py_class!(ctx, "List", ast_base.clone(), {}),
         "ListComp" => py_class!(ctx, "ListComp", ast_base.clone(), {}),
+        "Match" => py_class!(ctx, "Match", ast_base.clone(), {}),
+        "match_case" => py_class!(ctx, "match_case", ast_base.clone(), {}),
+        "MatchAs" => py_class!(ctx, "MatchAs", ast_base.clone(), {}),
+        "MatchClass" => py_class!(ctx, "MatchClass", ast_base.clone(), {}),
+        "MatchMapping" => py_class!(ctx, "MatchMapping", ast_base.clone(), {}),
+        "MatchOr" => py_class!(ctx, "MatchOr", ast_base.clone(), {}),
+        "MatchSequence" => py_class!(ctx, "MatchSequence", ast_base.clone(), {}),
+        "MatchSingleton" => py_class!(ctx, "MatchSingleton", ast_base.clone(), {}),
+        "MatchStar" => py_class!(ctx, "MatchStar", ast_base.clone(), {}),
+        "MatchValue" => py_class!(ctx, "MatchValue", ast_base.clone(), {}),
         "Module" => py_class!(ctx, "Module", ast_base.clone(), {}),
         "Name" => py_class!(ctx, "Name", ast_base.clone(), {}),
         "NameConstant" => py_class!(ctx, "NameConstant", ast_base.clone(), {}),
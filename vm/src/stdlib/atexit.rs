@@ -0,0 +1,86 @@
+/// Implementation of the `atexit` module: callbacks that run when the
+/// interpreter shuts down.
+use crate::exceptions::print_exception;
+use crate::function::PyFuncArgs;
+use crate::obj::{objbool, objtype};
+use crate::pyobject::{PyObjectRef, PyResult};
+use crate::vm::VirtualMachine;
+
+fn atexit_register(vm: &VirtualMachine, mut args: PyFuncArgs) -> PyResult {
+    if args.args.is_empty() {
+        return Err(vm.new_type_error("register() takes at least 1 argument (0 given)".to_string()));
+    }
+    let func = args.shift();
+    if !vm.is_callable(&func) {
+        return Err(vm.new_type_error("the first argument must be callable".to_string()));
+    }
+    vm.atexit_funcs.borrow_mut().push((func.clone(), args));
+    Ok(func)
+}
+
+fn atexit_unregister(func: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+    let funcs: Vec<_> = vm.atexit_funcs.borrow_mut().drain(..).collect();
+    let mut kept = Vec::with_capacity(funcs.len());
+    for (registered, args) in funcs {
+        let equal = vm._eq(registered.clone(), func.clone())?;
+        if !objbool::boolval(vm, equal)? {
+            kept.push((registered, args));
+        }
+    }
+    // Callbacks registered by the comparisons go after the ones kept.
+    let mut funcs = vm.atexit_funcs.borrow_mut();
+    kept.extend(funcs.drain(..));
+    *funcs = kept;
+    Ok(())
+}
+
+/// Run the registered callbacks, last registered first, and forget them.
+///
+/// An exception in a callback is printed and doesn't stop the others; the
+/// last one is returned.
+pub fn run_exitfuncs(vm: &VirtualMachine) -> PyResult<()> {
+    let mut last_error = None;
+    loop {
+        // Callbacks may register more callbacks, which run next.
+        let entry = vm.atexit_funcs.borrow_mut().pop();
+        let (func, args) = match entry {
+            Some(entry) => entry,
+            None => break,
+        };
+        if let Err(err) = vm.invoke(&func, args) {
+            if !objtype::isinstance(&err, &vm.ctx.exceptions.system_exit) {
+                eprintln!("Error in atexit._run_exitfuncs:");
+                print_exception(vm, &err);
+            }
+            last_error = Some(err);
+        }
+    }
+    match last_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+fn atexit_run_exitfuncs(vm: &VirtualMachine) -> PyResult<()> {
+    run_exitfuncs(vm)
+}
+
+fn atexit_clear(vm: &VirtualMachine) {
+    vm.atexit_funcs.borrow_mut().clear();
+}
+
+fn atexit_ncallbacks(vm: &VirtualMachine) -> usize {
+    vm.atexit_funcs.borrow().len()
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    py_module!(vm, "atexit", {
+        "register" => ctx.new_rustfunc(atexit_register),
+        "unregister" => ctx.new_rustfunc(atexit_unregister),
+        "_run_exitfuncs" => ctx.new_rustfunc(atexit_run_exitfuncs),
+        "_clear" => ctx.new_rustfunc(atexit_clear),
+        "_ncallbacks" => ctx.new_rustfunc(atexit_ncallbacks),
+    })
+}
@@ -1,6 +1,9 @@
 use crate::function::OptionalArg;
+use crate::obj::objiter::new_stop_iteration;
 use crate::obj::{objbool, objsequence, objtype::PyClassRef};
-use crate::pyobject::{IdProtocol, PyClassImpl, PyIterable, PyObjectRef, PyRef, PyResult, PyValue};
+use crate::pyobject::{
+    IdProtocol, PyClassImpl, PyIterable, PyObjectRef, PyRef, PyResult, PyValue, TypeProtocol,
+};
 use crate::vm::ReprGuard;
 use crate::VirtualMachine;
 use itertools::Itertools;
@@ -20,10 +23,35 @@ impl PyValue for PyDeque {
     }
 }
 
+type PyDequeRef = PyRef<PyDeque>;
+
 #[derive(FromArgs)]
 struct PyDequeOptions {
     #[pyarg(positional_or_keyword, default = "None")]
-    maxlen: Option<usize>,
+    maxlen: Option<isize>,
+}
+
+impl PyDeque {
+    /// Turn a possibly negative index into a position in the deque.
+    fn position(&self, idx: isize, vm: &VirtualMachine) -> PyResult<usize> {
+        let len = self.deque.borrow().len() as isize;
+        let pos = if idx < 0 { idx + len } else { idx };
+        if pos < 0 || pos >= len {
+            Err(vm.new_index_error("deque index out of range".to_string()))
+        } else {
+            Ok(pos as usize)
+        }
+    }
+
+    fn contains_item(&self, needle: &PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+        let elements: Vec<_> = self.deque.borrow().iter().cloned().collect();
+        for elem in elements {
+            if elem.is(needle) || objbool::boolval(vm, vm._eq(elem, needle.clone())?)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
 
 #[pyimpl]
@@ -35,6 +63,12 @@ impl PyDeque {
         PyDequeOptions { maxlen }: PyDequeOptions,
         vm: &VirtualMachine,
     ) -> PyResult<PyRef<Self>> {
+        let maxlen = match maxlen {
+            Some(maxlen) if maxlen < 0 => {
+                return Err(vm.new_value_error("maxlen must be non-negative".to_string()));
+            }
+            maxlen => maxlen.map(|maxlen| maxlen as usize),
+        };
         let py_deque = PyDeque {
             deque: RefCell::default(),
             maxlen: maxlen.into(),
@@ -48,6 +82,9 @@ impl PyDeque {
     #[pymethod]
     fn append(&self, obj: PyObjectRef, _vm: &VirtualMachine) {
         let mut deque = self.deque.borrow_mut();
+        if self.maxlen.get() == Some(0) {
+            return;
+        }
         if self.maxlen.get() == Some(deque.len()) {
             deque.pop_front();
         }
@@ -57,6 +94,9 @@ impl PyDeque {
     #[pymethod]
     fn appendleft(&self, obj: PyObjectRef, _vm: &VirtualMachine) {
         let mut deque = self.deque.borrow_mut();
+        if self.maxlen.get() == Some(0) {
+            return;
+        }
         if self.maxlen.get() == Some(deque.len()) {
             deque.pop_back();
         }
@@ -109,11 +149,16 @@ impl PyDeque {
         stop: OptionalArg<usize>,
         vm: &VirtualMachine,
     ) -> PyResult<usize> {
-        let deque = self.deque.borrow();
+        let elements: Vec<_> = self.deque.borrow().iter().cloned().collect();
         let start = start.unwrap_or(0);
-        let stop = stop.unwrap_or_else(|| deque.len());
-        for (i, elem) in deque.iter().skip(start).take(stop - start).enumerate() {
-            if objbool::boolval(vm, vm._eq(elem.clone(), obj.clone())?)? {
+        let stop = stop.unwrap_or_else(|| elements.len());
+        let candidates = elements
+            .into_iter()
+            .enumerate()
+            .skip(start)
+            .take(stop.saturating_sub(start));
+        for (i, elem) in candidates {
+            if objbool::boolval(vm, vm._eq(elem, obj.clone())?)? {
                 return Ok(i);
             }
         }
@@ -139,7 +184,7 @@ impl PyDeque {
                 deque.len() - ((-idx) as usize)
             }
         } else if idx as usize >= deque.len() {
-            deque.len() - 1
+            deque.len()
         } else {
             idx as usize
         };
@@ -188,7 +233,11 @@ impl PyDeque {
     #[pymethod]
     fn rotate(&self, mid: OptionalArg<isize>, _vm: &VirtualMachine) {
         let mut deque = self.deque.borrow_mut();
-        let mid = mid.unwrap_or(1);
+        if deque.is_empty() {
+            return;
+        }
+        let len = deque.len() as isize;
+        let mid = mid.unwrap_or(1) % len;
         if mid < 0 {
             deque.rotate_left(-mid as usize);
         } else {
@@ -248,7 +297,7 @@ impl PyDeque {
     #[pymethod(name = "__lt__")]
     fn lt(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         if zelf.as_object().is(&other) {
-            return Ok(vm.new_bool(true));
+            return Ok(vm.new_bool(false));
         }
 
         let other = match_class!(other,
@@ -266,7 +315,7 @@ impl PyDeque {
     #[pymethod(name = "__gt__")]
     fn gt(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
         if zelf.as_object().is(&other) {
-            return Ok(vm.new_bool(true));
+            return Ok(vm.new_bool(false));
         }
 
         let other = match_class!(other,
@@ -322,7 +371,7 @@ impl PyDeque {
         let deque: &VecDeque<_> = &self.deque.borrow();
         let mul = objsequence::seq_mul(deque, n);
         let skipped = if let Some(maxlen) = self.maxlen.get() {
-            mul.len() - maxlen
+            mul.len().saturating_sub(maxlen)
         } else {
             0
         };
@@ -337,10 +386,128 @@ impl PyDeque {
     fn len(&self, _vm: &VirtualMachine) -> usize {
         self.deque.borrow().len()
     }
+
+    #[pymethod(name = "__getitem__")]
+    fn getitem(&self, idx: isize, vm: &VirtualMachine) -> PyResult {
+        let pos = self.position(idx, vm)?;
+        Ok(self.deque.borrow()[pos].clone())
+    }
+
+    #[pymethod(name = "__setitem__")]
+    fn setitem(&self, idx: isize, value: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+        let pos = self.position(idx, vm)?;
+        self.deque.borrow_mut()[pos] = value;
+        Ok(())
+    }
+
+    #[pymethod(name = "__delitem__")]
+    fn delitem(&self, idx: isize, vm: &VirtualMachine) -> PyResult<()> {
+        let pos = self.position(idx, vm)?;
+        self.deque.borrow_mut().remove(pos);
+        Ok(())
+    }
+
+    #[pymethod(name = "__contains__")]
+    fn contains(&self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+        self.contains_item(&needle, vm)
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyDequeIterator {
+        PyDequeIterator::new(zelf, false)
+    }
+
+    #[pymethod(name = "__reversed__")]
+    fn reversed(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyDequeIterator {
+        PyDequeIterator::new(zelf, true)
+    }
+
+    #[pymethod(name = "__add__")]
+    fn add(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let other = match other.downcast::<PyDeque>() {
+            Ok(other) => other,
+            Err(other) => {
+                return Err(vm.new_type_error(format!(
+                    "can only concatenate deque (not \"{}\") to deque",
+                    other.class().name
+                )))
+            }
+        };
+        let new_deque = self.clone();
+        let elements: Vec<_> = other.deque.borrow().iter().cloned().collect();
+        for elem in elements {
+            new_deque.append(elem, vm);
+        }
+        Ok(new_deque.into_ref(vm).into_object())
+    }
+
+    #[pymethod(name = "__iadd__")]
+    fn iadd(zelf: PyRef<Self>, other: PyIterable, vm: &VirtualMachine) -> PyResult<PyDequeRef> {
+        zelf.extend(other, vm)?;
+        Ok(zelf)
+    }
+}
+
+#[pyclass(name = "_deque_iterator")]
+#[derive(Debug)]
+struct PyDequeIterator {
+    deque: PyDequeRef,
+    position: Cell<usize>,
+    len: usize,
+    reversed: bool,
+}
+
+impl PyValue for PyDequeIterator {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("_collections", "_deque_iterator")
+    }
+}
+
+#[pyimpl]
+impl PyDequeIterator {
+    fn new(deque: PyDequeRef, reversed: bool) -> Self {
+        let len = deque.deque.borrow().len();
+        PyDequeIterator {
+            deque,
+            position: Cell::new(0),
+            len,
+            reversed,
+        }
+    }
+
+    #[pymethod(name = "__next__")]
+    fn next(&self, vm: &VirtualMachine) -> PyResult {
+        let deque = self.deque.deque.borrow();
+        if deque.len() != self.len {
+            return Err(vm.new_runtime_error("deque mutated during iteration".to_string()));
+        }
+        let pos = self.position.get();
+        if pos >= self.len {
+            return Err(new_stop_iteration(vm));
+        }
+        self.position.set(pos + 1);
+        let idx = if self.reversed {
+            self.len - 1 - pos
+        } else {
+            pos
+        };
+        Ok(deque[idx].clone())
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+
+    #[pymethod(name = "__length_hint__")]
+    fn length_hint(&self, _vm: &VirtualMachine) -> usize {
+        self.len.saturating_sub(self.position.get())
+    }
 }
 
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     py_module!(vm, "_collections", {
         "deque" => PyDeque::make_class(&vm.ctx),
+        "_deque_iterator" => PyDequeIterator::make_class(&vm.ctx),
     })
 }
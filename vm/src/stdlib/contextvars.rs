@@ -0,0 +1,380 @@
+/*
+ * Context variables: state that is local to a context, such as the task
+ * that runs some code, rather than global to the interpreter.
+ *
+ * The interpreter keeps a stack of the contexts being run; the bottom one is
+ * created on first use and stays entered.
+ */
+
+use std::cell::{Cell, RefCell};
+
+use indexmap::IndexMap;
+
+use crate::function::{OptionalArg, PyFuncArgs};
+use crate::obj::objiter;
+use crate::obj::objstr::PyStringRef;
+use crate::obj::objtype::PyClassRef;
+use crate::pyobject::{IdProtocol, PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue};
+use crate::vm::VirtualMachine;
+
+/// Values by the id of their variable, which is kept alive alongside.
+type ContextVars = IndexMap<usize, (PyContextVarRef, PyObjectRef)>;
+
+#[pyclass(name = "Context")]
+#[derive(Debug)]
+struct PyContextObject {
+    vars: RefCell<ContextVars>,
+    entered: Cell<bool>,
+}
+type PyContextObjectRef = PyRef<PyContextObject>;
+
+impl PyValue for PyContextObject {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("contextvars", "Context")
+    }
+}
+
+fn current_context(vm: &VirtualMachine) -> PyContextObjectRef {
+    if vm.contexts.borrow().is_empty() {
+        let base = PyContextObject {
+            vars: RefCell::new(IndexMap::new()),
+            entered: Cell::new(true),
+        }
+        .into_ref(vm);
+        vm.contexts.borrow_mut().push(base.into_object());
+    }
+    let context = vm.contexts.borrow().last().unwrap().clone();
+    context.downcast().expect("only contexts are entered")
+}
+
+fn context_key(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyContextVarRef> {
+    obj.downcast().map_err(|obj| {
+        vm.new_type_error(format!(
+            "a ContextVar key was expected, got {}",
+            vm.to_repr(&obj)
+                .map(|repr| repr.as_str().to_string())
+                .unwrap_or_default()
+        ))
+    })
+}
+
+#[pyimpl]
+impl PyContextObject {
+    #[pymethod(name = "__new__")]
+    fn new(cls: PyClassRef, vm: &VirtualMachine) -> PyResult<PyContextObjectRef> {
+        PyContextObject {
+            vars: RefCell::new(IndexMap::new()),
+            entered: Cell::new(false),
+        }
+        .into_ref_with_type(vm, cls)
+    }
+
+    /// Call `callable` with this context as the current one.
+    #[pymethod]
+    fn run(
+        zelf: PyRef<Self>,
+        callable: PyObjectRef,
+        args: PyFuncArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        if zelf.entered.get() {
+            return Err(vm.new_runtime_error(format!(
+                "cannot enter context: {} is already entered",
+                vm.to_repr(zelf.as_object())?
+            )));
+        }
+        // Make sure the base context exists below this one.
+        current_context(vm);
+        zelf.entered.set(true);
+        vm.contexts.borrow_mut().push(zelf.as_object().clone());
+        let result = vm.invoke(&callable, args);
+        vm.contexts.borrow_mut().pop();
+        zelf.entered.set(false);
+        result
+    }
+
+    #[pymethod]
+    fn copy(&self, vm: &VirtualMachine) -> PyResult<PyContextObjectRef> {
+        Ok(PyContextObject {
+            vars: RefCell::new(self.vars.borrow().clone()),
+            entered: Cell::new(false),
+        }
+        .into_ref(vm))
+    }
+
+    #[pymethod(name = "__getitem__")]
+    fn getitem(&self, var: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let var = context_key(var, vm)?;
+        match self.vars.borrow().get(&var.get_id()) {
+            Some((_, value)) => Ok(value.clone()),
+            None => Err(vm.new_key_error(var.into_object())),
+        }
+    }
+
+    #[pymethod]
+    fn get(
+        &self,
+        var: PyObjectRef,
+        default: OptionalArg<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let var = context_key(var, vm)?;
+        match self.vars.borrow().get(&var.get_id()) {
+            Some((_, value)) => Ok(value.clone()),
+            None => Ok(default.unwrap_or_else(|| vm.get_none())),
+        }
+    }
+
+    #[pymethod(name = "__contains__")]
+    fn contains(&self, var: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+        let var = context_key(var, vm)?;
+        Ok(self.vars.borrow().contains_key(&var.get_id()))
+    }
+
+    #[pymethod(name = "__len__")]
+    fn len(&self, _vm: &VirtualMachine) -> usize {
+        self.vars.borrow().len()
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(&self, vm: &VirtualMachine) -> PyResult {
+        objiter::get_iter(vm, &self.keys(vm))
+    }
+
+    #[pymethod]
+    fn keys(&self, vm: &VirtualMachine) -> PyObjectRef {
+        let keys = self
+            .vars
+            .borrow()
+            .values()
+            .map(|(var, _)| var.as_object().clone())
+            .collect();
+        vm.ctx.new_list(keys)
+    }
+
+    #[pymethod]
+    fn values(&self, vm: &VirtualMachine) -> PyObjectRef {
+        let values = self
+            .vars
+            .borrow()
+            .values()
+            .map(|(_, value)| value.clone())
+            .collect();
+        vm.ctx.new_list(values)
+    }
+
+    #[pymethod]
+    fn items(&self, vm: &VirtualMachine) -> PyObjectRef {
+        let items = self
+            .vars
+            .borrow()
+            .values()
+            .map(|(var, value)| {
+                vm.ctx
+                    .new_tuple(vec![var.as_object().clone(), value.clone()])
+            })
+            .collect();
+        vm.ctx.new_list(items)
+    }
+}
+
+#[pyclass(name = "ContextVar")]
+#[derive(Debug)]
+struct PyContextVar {
+    name: String,
+    default: Option<PyObjectRef>,
+}
+type PyContextVarRef = PyRef<PyContextVar>;
+
+impl PyValue for PyContextVar {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("contextvars", "ContextVar")
+    }
+}
+
+#[derive(FromArgs)]
+struct ContextVarArgs {
+    #[pyarg(positional_only)]
+    name: PyStringRef,
+    #[pyarg(keyword_only, optional = true)]
+    default: OptionalArg<PyObjectRef>,
+}
+
+#[pyimpl]
+impl PyContextVar {
+    #[pymethod(name = "__new__")]
+    fn new(
+        cls: PyClassRef,
+        args: ContextVarArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyContextVarRef> {
+        PyContextVar {
+            name: args.name.as_str().to_string(),
+            default: args.default.into_option(),
+        }
+        .into_ref_with_type(vm, cls)
+    }
+
+    #[pyproperty]
+    fn name(&self, _vm: &VirtualMachine) -> String {
+        self.name.clone()
+    }
+
+    /// The value in the current context, else `default`, else the variable's default.
+    #[pymethod]
+    fn get(zelf: PyRef<Self>, default: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult {
+        let context = current_context(vm);
+        if let Some((_, value)) = context.vars.borrow().get(&zelf.get_id()) {
+            return Ok(value.clone());
+        }
+        match default.into_option().or_else(|| zelf.default.clone()) {
+            Some(value) => Ok(value),
+            None => Err(vm.new_lookup_error(vm.to_repr(zelf.as_object())?.as_str().to_string())),
+        }
+    }
+
+    #[pymethod]
+    fn set(
+        zelf: PyRef<Self>,
+        value: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyContextTokenRef> {
+        let context = current_context(vm);
+        let old_value = context
+            .vars
+            .borrow_mut()
+            .insert(zelf.get_id(), (zelf.clone(), value))
+            .map(|(_, old_value)| old_value);
+        Ok(PyContextToken {
+            context,
+            var: zelf,
+            old_value,
+            used: Cell::new(false),
+        }
+        .into_ref(vm))
+    }
+
+    /// Restore the value the variable had before the `set` that returned `token`.
+    #[pymethod]
+    fn reset(zelf: PyRef<Self>, token: PyContextTokenRef, vm: &VirtualMachine) -> PyResult<()> {
+        if token.used.get() {
+            return Err(vm.new_runtime_error(format!(
+                "{} has already been used once",
+                vm.to_repr(token.as_object())?
+            )));
+        }
+        if !token.var.is(&zelf) {
+            return Err(vm.new_value_error(format!(
+                "{} was created by a different ContextVar",
+                vm.to_repr(token.as_object())?
+            )));
+        }
+        let context = current_context(vm);
+        if !token.context.is(&context) {
+            return Err(vm.new_value_error(format!(
+                "{} was created in a different Context",
+                vm.to_repr(token.as_object())?
+            )));
+        }
+        token.used.set(true);
+        let mut vars = context.vars.borrow_mut();
+        match token.old_value {
+            Some(ref old_value) => {
+                vars.insert(zelf.get_id(), (zelf.clone(), old_value.clone()));
+            }
+            None => {
+                vars.swap_remove(&zelf.get_id());
+            }
+        }
+        Ok(())
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        let default = match self.default {
+            Some(ref default) => format!(" default={}", vm.to_repr(default)?),
+            None => String::new(),
+        };
+        Ok(format!(
+            "<ContextVar name={}{} at {:#x}>",
+            vm.to_repr(&vm.new_str(self.name.clone()))?,
+            default,
+            self as *const Self as usize
+        ))
+    }
+}
+
+#[pyclass(name = "Token")]
+#[derive(Debug)]
+struct PyContextToken {
+    context: PyContextObjectRef,
+    var: PyContextVarRef,
+    old_value: Option<PyObjectRef>,
+    used: Cell<bool>,
+}
+type PyContextTokenRef = PyRef<PyContextToken>;
+
+impl PyValue for PyContextToken {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("contextvars", "Token")
+    }
+}
+
+#[pyimpl]
+impl PyContextToken {
+    #[pymethod(name = "__new__")]
+    fn py_new(_cls: PyClassRef, vm: &VirtualMachine) -> PyResult {
+        Err(vm.new_runtime_error("Tokens can only be created by ContextVars".to_string()))
+    }
+
+    #[pyproperty]
+    fn var(&self, _vm: &VirtualMachine) -> PyContextVarRef {
+        self.var.clone()
+    }
+
+    /// The value before the `set`, or `Token.MISSING` if there was none.
+    #[pyproperty]
+    fn old_value(&self, vm: &VirtualMachine) -> PyResult {
+        match self.old_value {
+            Some(ref old_value) => Ok(old_value.clone()),
+            None => vm.get_attribute(Self::class(vm).into_object(), "MISSING"),
+        }
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        Ok(format!(
+            "<Token{} var={} at {:#x}>",
+            if self.used.get() { " used" } else { "" },
+            vm.to_repr(self.var.as_object())?,
+            self as *const Self as usize
+        ))
+    }
+}
+
+fn missing_repr(_zelf: PyObjectRef, _vm: &VirtualMachine) -> String {
+    "<Token.MISSING>".to_string()
+}
+
+fn contextvars_copy_context(vm: &VirtualMachine) -> PyResult<PyContextObjectRef> {
+    current_context(vm).copy(vm)
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    let token_type = PyContextToken::make_class(ctx);
+    let missing_type = py_class!(ctx, "Token.MISSING", ctx.object(), {
+        "__repr__" => ctx.new_rustfunc(missing_repr),
+    });
+    let missing = vm.invoke(&missing_type.into_object(), vec![]).unwrap();
+    vm.set_attr(token_type.as_object(), "MISSING", missing)
+        .unwrap();
+
+    py_module!(vm, "contextvars", {
+        "Context" => PyContextObject::make_class(ctx),
+        "ContextVar" => PyContextVar::make_class(ctx),
+        "Token" => token_type,
+        "copy_context" => ctx.new_rustfunc(contextvars_copy_context),
+    })
+}
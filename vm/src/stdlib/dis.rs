@@ -1,20 +1,492 @@
-use crate::obj::objcode::PyCodeRef;
-use crate::pyobject::{PyObjectRef, PyResult, TryFromObject};
+/*
+ * The `dis` module: disassembly of code objects.
+ */
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::bytecode::{self, BinaryOperator, ComparisonOperator, NameScope, UnaryOperator};
+use crate::function::OptionalArg;
+use crate::obj::objcode::{PyCode, PyCodeRef};
+use crate::obj::objiter;
+use crate::obj::objstr::PyString;
+use crate::obj::objtype::{PyClass, PyClassRef};
+use crate::pyobject::{PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue, TypeProtocol};
 use crate::vm::VirtualMachine;
 
-fn dis_dis(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-    // Method or function:
-    if let Ok(co) = vm.get_attribute(obj.clone(), "__code__") {
-        return dis_disassemble(co, vm);
+const OPNAME_WIDTH: usize = 20;
+const OPARG_WIDTH: usize = 5;
+
+#[pystruct_sequence(name = "Instruction")]
+#[derive(Debug)]
+struct Instruction {
+    opname: String,
+    arg: Option<usize>,
+    argval: PyObjectRef,
+    argrepr: String,
+    offset: usize,
+    starts_line: Option<usize>,
+    is_jump_target: bool,
+}
+
+impl Instruction {
+    /// Render this instruction as one line of a `dis` listing.
+    fn disassemble(&self, lineno_width: usize, mark_as_current: bool) -> String {
+        let mut fields = vec![];
+        fields.push(match self.starts_line {
+            Some(lineno) => format!("{:>width$}", lineno, width = lineno_width),
+            None => " ".repeat(lineno_width),
+        });
+        fields.push(if mark_as_current { "-->" } else { "   " }.to_string());
+        fields.push(if self.is_jump_target { ">>" } else { "  " }.to_string());
+        fields.push(format!("{:>4}", self.offset));
+        fields.push(format!("{:<width$}", self.opname, width = OPNAME_WIDTH));
+        if self.arg.is_some() || !self.argrepr.is_empty() {
+            fields.push(match self.arg {
+                Some(arg) => format!("{:>width$}", arg, width = OPARG_WIDTH),
+                None => " ".repeat(OPARG_WIDTH),
+            });
+            if !self.argrepr.is_empty() {
+                fields.push(format!("({})", self.argrepr));
+            }
+        }
+        fields.join(" ").trim_end().to_string()
+    }
+}
+
+/// The name, numeric argument, resolved argument value and its description
+/// for one instruction.
+fn instruction_parts(
+    instruction: &bytecode::Instruction,
+    code: &bytecode::CodeObject,
+    vm: &VirtualMachine,
+) -> PyResult<(String, Option<usize>, PyObjectRef, String)> {
+    use bytecode::Instruction::*;
+
+    let none = || vm.get_none();
+    let simple = |opname: &str| (opname.to_string(), None, none(), String::new());
+    let named = |opname: &str, name: &str| {
+        (
+            opname.to_string(),
+            None,
+            vm.new_str(name.to_string()),
+            name.to_string(),
+        )
+    };
+    let counted = |opname: &str, count: usize| {
+        (
+            opname.to_string(),
+            Some(count),
+            vm.new_int(count),
+            String::new(),
+        )
+    };
+    let jump = |opname: &str, target: &bytecode::Label| {
+        let target = code.label_map[target];
+        (
+            opname.to_string(),
+            Some(target),
+            vm.new_int(target),
+            format!("to {}", target),
+        )
+    };
+    let scoped = |prefix: &str, name: &str, scope: &NameScope| {
+        let suffix = match scope {
+            NameScope::Global => "GLOBAL",
+            NameScope::NonLocal => "DEREF",
+            NameScope::Local | NameScope::Free => "NAME",
+        };
+        named(&format!("{}_{}", prefix, suffix), name)
+    };
+
+    let parts = match instruction {
+        Import { name, .. } => match name {
+            Some(name) => named("IMPORT_NAME", name),
+            None => simple("IMPORT_NAME"),
+        },
+        ImportStar => simple("IMPORT_STAR"),
+        ImportFrom { name } => named("IMPORT_FROM", name),
+        LoadName { name, scope } => scoped("LOAD", name, scope),
+        StoreName { name, scope } => scoped("STORE", name, scope),
+        DeleteName { name } => named("DELETE_NAME", name),
+        Subscript => simple("BINARY_SUBSCR"),
+        StoreSubscript => simple("STORE_SUBSCR"),
+        DeleteSubscript => simple("DELETE_SUBSCR"),
+        StoreAttr { name } => named("STORE_ATTR", name),
+        DeleteAttr { name } => named("DELETE_ATTR", name),
+        LoadConst { value } => {
+            let value = vm.ctx.unwrap_constant(value);
+            let argrepr = vm.to_repr(&value)?.as_str().to_string();
+            ("LOAD_CONST".to_string(), None, value, argrepr)
+        }
+        UnaryOperation { op } => simple(match op {
+            UnaryOperator::Not => "UNARY_NOT",
+            UnaryOperator::Invert => "UNARY_INVERT",
+            UnaryOperator::Minus => "UNARY_NEGATIVE",
+            UnaryOperator::Plus => "UNARY_POSITIVE",
+        }),
+        BinaryOperation { op, inplace } => {
+            let name = match op {
+                BinaryOperator::Power => "POWER",
+                BinaryOperator::Multiply => "MULTIPLY",
+                BinaryOperator::MatrixMultiply => "MATRIX_MULTIPLY",
+                BinaryOperator::Divide => "TRUE_DIVIDE",
+                BinaryOperator::FloorDivide => "FLOOR_DIVIDE",
+                BinaryOperator::Modulo => "MODULO",
+                BinaryOperator::Add => "ADD",
+                BinaryOperator::Subtract => "SUBTRACT",
+                BinaryOperator::Lshift => "LSHIFT",
+                BinaryOperator::Rshift => "RSHIFT",
+                BinaryOperator::And => "AND",
+                BinaryOperator::Xor => "XOR",
+                BinaryOperator::Or => "OR",
+            };
+            let prefix = if *inplace { "INPLACE" } else { "BINARY" };
+            simple(&format!("{}_{}", prefix, name))
+        }
+        LoadAttr { name } => named("LOAD_ATTR", name),
+        CompareOperation { op } => {
+            let op = match op {
+                ComparisonOperator::Greater => ">",
+                ComparisonOperator::GreaterOrEqual => ">=",
+                ComparisonOperator::Less => "<",
+                ComparisonOperator::LessOrEqual => "<=",
+                ComparisonOperator::Equal => "==",
+                ComparisonOperator::NotEqual => "!=",
+                ComparisonOperator::In => "in",
+                ComparisonOperator::NotIn => "not in",
+                ComparisonOperator::Is => "is",
+                ComparisonOperator::IsNot => "is not",
+            };
+            named("COMPARE_OP", op)
+        }
+        Pop => simple("POP_TOP"),
+        Rotate { amount } => counted("ROTATE", *amount),
+        Duplicate => simple("DUP_TOP"),
+        GetIter => simple("GET_ITER"),
+        Continue => simple("CONTINUE_LOOP"),
+        Break => simple("BREAK_LOOP"),
+        Jump { target } => jump("JUMP_ABSOLUTE", target),
+        JumpIfTrue { target } => jump("POP_JUMP_IF_TRUE", target),
+        JumpIfFalse { target } => jump("POP_JUMP_IF_FALSE", target),
+        JumpIfTrueOrPop { target } => jump("JUMP_IF_TRUE_OR_POP", target),
+        JumpIfFalseOrPop { target } => jump("JUMP_IF_FALSE_OR_POP", target),
+        MakeFunction { flags } => (
+            "MAKE_FUNCTION".to_string(),
+            Some(flags.bits() as usize),
+            vm.new_int(flags.bits()),
+            String::new(),
+        ),
+        CallFunction { typ } => match typ {
+            bytecode::CallType::Positional(count) => counted("CALL_FUNCTION", *count),
+            bytecode::CallType::Keyword(count) => counted("CALL_FUNCTION_KW", *count),
+            bytecode::CallType::Ex(has_kwargs) => counted("CALL_FUNCTION_EX", *has_kwargs as usize),
+        },
+        ForIter { target } => jump("FOR_ITER", target),
+        ReturnValue => simple("RETURN_VALUE"),
+        YieldValue => simple("YIELD_VALUE"),
+        YieldFrom => simple("YIELD_FROM"),
+        GetAwaitable => simple("GET_AWAITABLE"),
+        SetupLoop { end, .. } => jump("SETUP_LOOP", end),
+        SetupFinally { handler } => jump("SETUP_FINALLY", handler),
+        EnterFinally => simple("ENTER_FINALLY"),
+        EndFinally => simple("END_FINALLY"),
+        SetupExcept { handler } => jump("SETUP_EXCEPT", handler),
+        SetupWith { end } => jump("SETUP_WITH", end),
+        CleanupWith { end } => jump("WITH_CLEANUP", end),
+        BeforeAsyncWith => simple("BEFORE_ASYNC_WITH"),
+        SetupAsyncWith { end } => jump("SETUP_ASYNC_WITH", end),
+        WithCleanupStart => simple("WITH_CLEANUP_START"),
+        WithCleanupFinish => simple("WITH_CLEANUP_FINISH"),
+        GetAIter => simple("GET_AITER"),
+        GetANext => simple("GET_ANEXT"),
+        PopBlock => simple("POP_BLOCK"),
+        Raise { argc } => counted("RAISE_VARARGS", *argc),
+        BuildString { size } => counted("BUILD_STRING", *size),
+        BuildTuple { size, unpack } => counted(
+            if *unpack {
+                "BUILD_TUPLE_UNPACK"
+            } else {
+                "BUILD_TUPLE"
+            },
+            *size,
+        ),
+        BuildList { size, unpack } => counted(
+            if *unpack {
+                "BUILD_LIST_UNPACK"
+            } else {
+                "BUILD_LIST"
+            },
+            *size,
+        ),
+        BuildSet { size, unpack } => counted(
+            if *unpack {
+                "BUILD_SET_UNPACK"
+            } else {
+                "BUILD_SET"
+            },
+            *size,
+        ),
+        BuildMap {
+            size,
+            unpack,
+            for_call,
+        } => counted(
+            match (unpack, for_call) {
+                (true, true) => "BUILD_MAP_UNPACK_WITH_CALL",
+                (true, false) => "BUILD_MAP_UNPACK",
+                (false, _) => "BUILD_MAP",
+            },
+            *size,
+        ),
+        BuildSlice { size } => counted("BUILD_SLICE", *size),
+        ListAppend { i } => counted("LIST_APPEND", *i),
+        SetAdd { i } => counted("SET_ADD", *i),
+        MapAdd { i } => counted("MAP_ADD", *i),
+        PrintExpr => simple("PRINT_EXPR"),
+        LoadBuildClass => simple("LOAD_BUILD_CLASS"),
+        SetupAnnotations => simple("SETUP_ANNOTATIONS"),
+        UnpackSequence { size } => counted("UNPACK_SEQUENCE", *size),
+        UnpackEx { before, after } => (
+            "UNPACK_EX".to_string(),
+            Some(before | (after << 8)),
+            vm.new_int(before | (after << 8)),
+            format!("{} before, {} after", before, after),
+        ),
+        Unpack => simple("UNPACK"),
+        FormatValue { conversion } => match conversion {
+            Some(conversion) => named("FORMAT_VALUE", &format!("{:?}", conversion)),
+            None => simple("FORMAT_VALUE"),
+        },
+        PopException => simple("POP_EXCEPT"),
+        Reverse { amount } => counted("REVERSE", *amount),
+        MatchSequence => simple("MATCH_SEQUENCE"),
+        MatchMapping => simple("MATCH_MAPPING"),
+        GetLen => simple("GET_LEN"),
+        MatchKeys => simple("MATCH_KEYS"),
+        CopyDictWithoutKeys => simple("COPY_DICT_WITHOUT_KEYS"),
+        MatchClass { count } => counted("MATCH_CLASS", *count),
+    };
+    Ok(parts)
+}
+
+fn get_instructions_list(
+    code: &bytecode::CodeObject,
+    vm: &VirtualMachine,
+) -> PyResult<Vec<Instruction>> {
+    let label_targets: HashSet<&usize> = code.label_map.values().collect();
+    let mut last_line = None;
+    let mut instructions = Vec::with_capacity(code.instructions.len());
+    for (offset, instruction) in code.instructions.iter().enumerate() {
+        let line = code.locations.get(offset).map(|location| location.row());
+        let starts_line = if line != last_line { line } else { None };
+        last_line = line;
+        let (opname, arg, argval, argrepr) = instruction_parts(instruction, code, vm)?;
+        instructions.push(Instruction {
+            opname,
+            arg,
+            argval,
+            argrepr,
+            offset,
+            starts_line,
+            is_jump_target: label_targets.contains(&offset),
+        });
+    }
+    Ok(instructions)
+}
+
+/// The listing of a single code object, without its nested code objects.
+fn disassemble_code(
+    code: &bytecode::CodeObject,
+    lasti: Option<usize>,
+    vm: &VirtualMachine,
+) -> PyResult<String> {
+    let instructions = get_instructions_list(code, vm)?;
+    let max_line = code
+        .locations
+        .iter()
+        .map(|location| location.row())
+        .max()
+        .unwrap_or(0);
+    let lineno_width = std::cmp::max(3, max_line.to_string().len());
+
+    let mut output = String::new();
+    for instruction in instructions {
+        if instruction.starts_line.is_some() && instruction.offset > 0 {
+            output.push('\n');
+        }
+        let is_current = lasti == Some(instruction.offset);
+        output.push_str(&instruction.disassemble(lineno_width, is_current));
+        output.push('\n');
     }
+    Ok(output)
+}
+
+/// The listing of a code object followed by those of the code objects it
+/// contains, such as nested functions, classes and comprehensions.
+fn disassemble_recursive(code: &PyCodeRef, vm: &VirtualMachine) -> PyResult<String> {
+    let mut output = disassemble_code(&code.code, None, vm)?;
+    for constant in code.code.get_constants() {
+        if let bytecode::Constant::Code { code } = constant {
+            let code = PyCode::new(*code.clone()).into_ref(vm);
+            let code_repr = vm.to_repr(code.as_object())?;
+            write!(output, "\nDisassembly of {}:\n", code_repr.as_str()).unwrap();
+            output.push_str(&disassemble_recursive(&code, vm)?);
+        }
+    }
+    Ok(output)
+}
+
+/// Find the code object behind a function, method, code object or source string.
+fn get_code_object(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyCodeRef> {
+    let obj = vm.get_attribute(obj.clone(), "__func__").unwrap_or(obj);
+    let obj = vm.get_attribute(obj.clone(), "__code__").unwrap_or(obj);
+    if obj.payload_is::<PyString>() {
+        let compile = vm.get_attribute(vm.builtins.clone(), "compile")?;
+        let filename = vm.new_str("<dis>".to_string());
+        let compile_mode = |mode: &str| {
+            vm.invoke(
+                &compile,
+                vec![obj.clone(), filename.clone(), vm.new_str(mode.to_string())],
+            )
+        };
+        let code = compile_mode("eval").or_else(|_| compile_mode("exec"))?;
+        return get_code_object(code, vm);
+    }
+    obj.downcast::<PyCode>().map_err(|obj| {
+        vm.new_type_error(format!(
+            "don't know how to disassemble {} objects",
+            obj.class().name
+        ))
+    })
+}
+
+fn write_output(output: String, file: Option<PyObjectRef>, vm: &VirtualMachine) -> PyResult<()> {
+    let file = match file {
+        Some(file) => file,
+        None => vm.get_attribute(vm.sys_module.clone(), "stdout")?,
+    };
+    vm.call_method(&file, "write", vec![vm.new_str(output)])?;
+    Ok(())
+}
 
-    dis_disassemble(obj, vm)
+#[derive(FromArgs)]
+struct DisArgs {
+    #[pyarg(positional_or_keyword, optional = true)]
+    x: OptionalArg<PyObjectRef>,
+    #[pyarg(keyword_only, default = "None")]
+    file: Option<PyObjectRef>,
 }
 
-fn dis_disassemble(co: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-    let code = &PyCodeRef::try_from_object(vm, co)?.code;
-    print!("{}", code);
-    Ok(vm.get_none())
+fn dis_dis(args: DisArgs, vm: &VirtualMachine) -> PyResult<()> {
+    let x = match args.x {
+        OptionalArg::Present(x) => x,
+        OptionalArg::Missing => {
+            return Err(vm.new_runtime_error("no last traceback to disassemble".to_string()))
+        }
+    };
+
+    let output = if let Ok(class) = x.clone().downcast::<PyClass>() {
+        // Disassemble every method of a class, in name order.
+        let mut attributes: Vec<_> = class.attributes.borrow().clone().into_iter().collect();
+        attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut output = String::new();
+        for (name, value) in attributes {
+            if value.payload_is::<PyString>() {
+                continue;
+            }
+            if let Ok(code) = get_code_object(value, vm) {
+                writeln!(output, "Disassembly of {}:", name).unwrap();
+                output.push_str(&disassemble_recursive(&code, vm)?);
+                output.push('\n');
+            }
+        }
+        output
+    } else {
+        disassemble_recursive(&get_code_object(x, vm)?, vm)?
+    };
+    write_output(output, args.file, vm)
+}
+
+#[derive(FromArgs)]
+struct DisassembleArgs {
+    #[pyarg(positional_or_keyword)]
+    co: PyCodeRef,
+    #[pyarg(positional_or_keyword, default = "-1")]
+    lasti: isize,
+    #[pyarg(keyword_only, default = "None")]
+    file: Option<PyObjectRef>,
+}
+
+fn dis_disassemble(args: DisassembleArgs, vm: &VirtualMachine) -> PyResult<()> {
+    let lasti = if args.lasti < 0 {
+        None
+    } else {
+        Some(args.lasti as usize)
+    };
+    let output = disassemble_code(&args.co.code, lasti, vm)?;
+    write_output(output, args.file, vm)
+}
+
+fn dis_get_instructions(x: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let code = get_code_object(x, vm)?;
+    let instruction_type = vm.class("dis", "Instruction");
+    let instructions = get_instructions_list(&code.code, vm)?
+        .into_iter()
+        .map(|instruction| {
+            instruction
+                .into_struct_sequence(vm, instruction_type.clone())
+                .map(|instruction| instruction.into_object())
+        })
+        .collect::<PyResult<_>>()?;
+    objiter::get_iter(vm, &vm.ctx.new_list(instructions))
+}
+
+#[pyclass(name = "Bytecode")]
+#[derive(Debug)]
+struct PyBytecode {
+    code: PyCodeRef,
+}
+
+impl PyValue for PyBytecode {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("dis", "Bytecode")
+    }
+}
+
+#[pyimpl]
+impl PyBytecode {
+    #[pymethod(name = "__new__")]
+    fn new(cls: PyClassRef, x: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyRef<Self>> {
+        let code = get_code_object(x, vm)?;
+        PyBytecode { code }.into_ref_with_type(vm, cls)
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(&self, vm: &VirtualMachine) -> PyResult {
+        dis_get_instructions(self.code.clone().into_object(), vm)
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        let code_repr = vm.to_repr(self.code.as_object())?;
+        Ok(format!("Bytecode({})", code_repr.as_str()))
+    }
+
+    #[pymethod]
+    fn dis(&self, vm: &VirtualMachine) -> PyResult<String> {
+        disassemble_code(&self.code.code, None, vm)
+    }
+
+    #[pyproperty]
+    fn codeobj(&self, _vm: &VirtualMachine) -> PyCodeRef {
+        self.code.clone()
+    }
+
+    #[pyproperty]
+    fn first_line(&self, _vm: &VirtualMachine) -> usize {
+        self.code.code.first_line_number
+    }
 }
 
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
@@ -22,6 +494,9 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
 
     py_module!(vm, "dis", {
         "dis" => ctx.new_rustfunc(dis_dis),
-        "disassemble" => ctx.new_rustfunc(dis_disassemble)
+        "disassemble" => ctx.new_rustfunc(dis_disassemble),
+        "get_instructions" => ctx.new_rustfunc(dis_get_instructions),
+        "Instruction" => Instruction::make_class(ctx),
+        "Bytecode" => PyBytecode::make_class(ctx),
     })
 }
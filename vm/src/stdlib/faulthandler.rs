@@ -0,0 +1,293 @@
+/* Dump the Python traceback when the interpreter crashes.
+ *
+ * Once enabled, a Rust panic or a fatal signal (segmentation fault, abort,
+ * ...) prints the Python stack of the crashing interpreter before the process
+ * goes down. The stack is written straight to a file descriptor, without
+ * allocating, as the interpreter may be in no state to run Python code.
+ */
+
+use std::cell::Cell;
+use std::fmt::{self, Write};
+use std::panic;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::Once;
+
+use crate::function::OptionalArg;
+use crate::pyobject::{PyObjectRef, PyResult, TryFromObject};
+use crate::vm::VirtualMachine;
+
+/// Stacks deeper than this are cut short.
+const MAX_FRAME_DEPTH: usize = 100;
+
+/// RustPython runs a single thread; this is what `_thread.get_ident()` returns.
+const THREAD_IDENT: u64 = 1;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static FD: AtomicI32 = AtomicI32::new(2);
+static ALL_THREADS: AtomicBool = AtomicBool::new(true);
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    /// The interpreter whose stack is dumped when this thread crashes.
+    static CURRENT_VM: Cell<*const VirtualMachine> = Cell::new(ptr::null());
+}
+
+/// Writes directly to a file descriptor, bypassing any buffering.
+struct FdWriter(i32);
+
+impl Write for FdWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut buf = s.as_bytes();
+        while !buf.is_empty() {
+            let written =
+                unsafe { libc::write(self.0, buf.as_ptr() as *const libc::c_void, buf.len() as _) };
+            if written <= 0 {
+                return Err(fmt::Error);
+            }
+            buf = &buf[written as usize..];
+        }
+        Ok(())
+    }
+}
+
+fn dump_traceback(fd: i32, all_threads: bool, vm: &VirtualMachine) -> fmt::Result {
+    let mut out = FdWriter(fd);
+    if all_threads {
+        writeln!(
+            out,
+            "Current thread 0x{:016x} (most recent call first):",
+            THREAD_IDENT
+        )?;
+    } else {
+        writeln!(out, "Stack (most recent call first):")?;
+    }
+
+    // Only look at state that isn't being modified; the crash may have
+    // happened halfway through an update.
+    let frames = match vm.frames.try_borrow() {
+        Ok(frames) => frames,
+        Err(_) => return writeln!(out, "  <unknown>"),
+    };
+    if frames.is_empty() {
+        return writeln!(out, "  <no Python frame>");
+    }
+    for (depth, frame) in frames.iter().rev().enumerate() {
+        if depth >= MAX_FRAME_DEPTH {
+            return writeln!(out, "  ...");
+        }
+        let code = &frame.code;
+        write!(out, "  File \"{}\", line ", code.source_path)?;
+        let location = frame
+            .lasti
+            .try_borrow()
+            .ok()
+            .and_then(|lasti| code.locations.get(*lasti).or_else(|| code.locations.last()));
+        match location {
+            Some(location) => write!(out, "{}", location.row())?,
+            None => write!(out, "???")?,
+        }
+        writeln!(out, " in {}", code.obj_name)?;
+    }
+    Ok(())
+}
+
+/// Report a fatal error, and the Python stack of this thread's interpreter.
+fn fatal_error(message: &str) {
+    let fd = FD.load(Ordering::Relaxed);
+    let _ = write!(FdWriter(fd), "Fatal Python error: {}\n\n", message);
+    let vm = CURRENT_VM.try_with(Cell::get).unwrap_or(ptr::null());
+    let _ = match unsafe { vm.as_ref() } {
+        Some(vm) => dump_traceback(fd, ALL_THREADS.load(Ordering::Relaxed), vm),
+        None => writeln!(FdWriter(fd), "<no Python frame>"),
+    };
+}
+
+fn disable() {
+    if ENABLED.swap(false, Ordering::Relaxed) {
+        signals::uninstall();
+    }
+    let _ = CURRENT_VM.try_with(|current| current.set(ptr::null()));
+}
+
+/// Stop reporting crashes of an interpreter that is going away.
+pub fn forget_vm(vm: &VirtualMachine) {
+    let current = CURRENT_VM.try_with(Cell::get).unwrap_or(ptr::null());
+    if ptr::eq(current, vm) {
+        disable();
+    }
+}
+
+#[cfg(all(unix, not(target_os = "redox")))]
+mod signals {
+    use std::mem;
+
+    use super::fatal_error;
+
+    const FATAL_SIGNALS: [(libc::c_int, &str); 5] = [
+        (libc::SIGSEGV, "Segmentation fault"),
+        (libc::SIGFPE, "Floating point exception"),
+        (libc::SIGABRT, "Aborted"),
+        (libc::SIGBUS, "Bus error"),
+        (libc::SIGILL, "Illegal instruction"),
+    ];
+
+    /// The handlers that were in place before ours.
+    static mut PREVIOUS: [Option<libc::sigaction>; 5] = [None; 5];
+
+    extern "C" fn fatal_signal_handler(signum: libc::c_int) {
+        let name = FATAL_SIGNALS
+            .iter()
+            .find(|(fatal, _)| *fatal == signum)
+            .map_or("Unknown signal", |(_, name)| name);
+        fatal_error(name);
+        // Hand the signal over to the previous handler, which usually
+        // terminates the process.
+        uninstall();
+        unsafe {
+            libc::raise(signum);
+        }
+    }
+
+    pub fn install() {
+        for (i, (signum, _)) in FATAL_SIGNALS.iter().enumerate() {
+            unsafe {
+                let mut action: libc::sigaction = mem::zeroed();
+                action.sa_sigaction = fatal_signal_handler as libc::sighandler_t;
+                // Run on the alternate stack, so that a stack overflow can
+                // be reported too.
+                action.sa_flags = libc::SA_NODEFER | libc::SA_ONSTACK;
+                libc::sigemptyset(&mut action.sa_mask);
+                let mut previous: libc::sigaction = mem::zeroed();
+                if libc::sigaction(*signum, &action, &mut previous) == 0 {
+                    PREVIOUS[i] = Some(previous);
+                }
+            }
+        }
+    }
+
+    pub fn uninstall() {
+        for (i, (signum, _)) in FATAL_SIGNALS.iter().enumerate() {
+            unsafe {
+                if let Some(previous) = PREVIOUS[i] {
+                    libc::sigaction(*signum, &previous, std::ptr::null_mut());
+                    PREVIOUS[i] = None;
+                }
+            }
+        }
+    }
+
+    pub fn sigsegv() {
+        // Unlike a real fault, a raised signal doesn't come back once a
+        // handler returns, so raise it until the previous handler (or the
+        // default action) is reached.
+        loop {
+            unsafe {
+                libc::raise(libc::SIGSEGV);
+            }
+        }
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "redox"))))]
+mod signals {
+    pub fn install() {}
+    pub fn uninstall() {}
+
+    pub fn sigsegv() {
+        std::process::abort();
+    }
+}
+
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            default_hook(info);
+            if ENABLED.load(Ordering::Relaxed) {
+                fatal_error("Rust panic");
+            }
+        }));
+    });
+}
+
+/// Get the file descriptor to write to from a `file` argument.
+fn get_fileno(file: Option<PyObjectRef>, vm: &VirtualMachine) -> PyResult<i32> {
+    let file = match file {
+        Some(file) => file,
+        None => {
+            let stderr = vm.get_attribute(vm.sys_module.clone(), "stderr")?;
+            if vm.is_none(&stderr) {
+                return Err(vm.new_runtime_error("sys.stderr is None".to_string()));
+            }
+            stderr
+        }
+    };
+    let fd = match i32::try_from_object(vm, file.clone()) {
+        Ok(fd) => fd,
+        Err(_) => {
+            let fd = vm.call_method(&file, "fileno", vec![])?;
+            let fd = i32::try_from_object(vm, fd)?;
+            // Whatever is still buffered should come before the traceback.
+            let _ = vm.call_method(&file, "flush", vec![]);
+            fd
+        }
+    };
+    if fd < 0 {
+        return Err(vm.new_value_error("file is not a valid file descriptor".to_string()));
+    }
+    Ok(fd)
+}
+
+#[derive(FromArgs)]
+struct DumpArgs {
+    #[pyarg(positional_or_keyword, default = "None")]
+    file: Option<PyObjectRef>,
+    #[pyarg(positional_or_keyword, default = "true")]
+    all_threads: bool,
+}
+
+fn faulthandler_dump_traceback(args: DumpArgs, vm: &VirtualMachine) -> PyResult<()> {
+    let fd = get_fileno(args.file, vm)?;
+    dump_traceback(fd, args.all_threads, vm)
+        .map_err(|_| vm.new_os_error("failed to write the traceback".to_string()))
+}
+
+fn faulthandler_enable(args: DumpArgs, vm: &VirtualMachine) -> PyResult<()> {
+    let fd = get_fileno(args.file, vm)?;
+    FD.store(fd, Ordering::Relaxed);
+    ALL_THREADS.store(args.all_threads, Ordering::Relaxed);
+    CURRENT_VM.with(|current| current.set(vm));
+    install_panic_hook();
+    if !ENABLED.swap(true, Ordering::Relaxed) {
+        signals::install();
+    }
+    Ok(())
+}
+
+fn faulthandler_disable(_vm: &VirtualMachine) -> bool {
+    let enabled = ENABLED.load(Ordering::Relaxed);
+    disable();
+    enabled
+}
+
+fn faulthandler_is_enabled(_vm: &VirtualMachine) -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Crash the interpreter, to test the handler.
+fn faulthandler_sigsegv(_release_gil: OptionalArg<bool>, _vm: &VirtualMachine) {
+    signals::sigsegv();
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    py_module!(vm, "faulthandler", {
+        "dump_traceback" => ctx.new_rustfunc(faulthandler_dump_traceback),
+        "enable" => ctx.new_rustfunc(faulthandler_enable),
+        "disable" => ctx.new_rustfunc(faulthandler_disable),
+        "is_enabled" => ctx.new_rustfunc(faulthandler_is_enabled),
+        "_sigsegv" => ctx.new_rustfunc(faulthandler_sigsegv),
+    })
+}
@@ -0,0 +1,156 @@
+/*
+ * Unix shell style pattern matching of file names.
+ *
+ * Patterns are translated to regular expressions that both the regex crate
+ * and Python's re module understand, so `translate` can be handed to `re`.
+ */
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::obj::objstr::PyStringRef;
+use crate::pyobject::{PyIterable, PyObjectRef, PyResult};
+use crate::vm::VirtualMachine;
+
+/// The cache is cleared once this many patterns have been compiled.
+const MAX_CACHE: usize = 256;
+
+thread_local! {
+    /// A pattern is usually matched against many names, so keep it compiled.
+    static CACHE: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+/// Case-normalize a name the way `os.path.normcase` does.
+#[cfg(windows)]
+fn normcase(name: &str) -> Cow<'_, str> {
+    Cow::Owned(name.to_lowercase().replace('/', "\\"))
+}
+
+#[cfg(not(windows))]
+fn normcase(name: &str) -> Cow<'_, str> {
+    Cow::Borrowed(name)
+}
+
+/// Translate the characters of a `[seq]`, without its brackets and `!`.
+fn translate_set(set: &[char], res: &mut String) {
+    // A hyphen between two characters makes a range, but the end of a range
+    // can't start another one.
+    let mut range_from = 1;
+    for (i, &c) in set.iter().enumerate() {
+        match c {
+            '-' if i >= range_from && i + 1 < set.len() => {
+                res.push(c);
+                range_from = i + 3;
+            }
+            // Everything else is a plain character, even what either regex
+            // syntax would take for a nested set or a set operation.
+            '\\' | '[' | ']' | '-' | '^' | '&' | '~' | '|' => {
+                res.push('\\');
+                res.push(c);
+            }
+            c => res.push(c),
+        }
+    }
+}
+
+fn translate_body(pat: &str) -> String {
+    let pat: Vec<char> = pat.chars().collect();
+    let n = pat.len();
+    let mut res = String::with_capacity(n);
+    let mut i = 0;
+    while i < n {
+        let c = pat[i];
+        i += 1;
+        match c {
+            '*' => res.push_str(".*"),
+            '?' => res.push('.'),
+            '[' => {
+                let mut j = i;
+                if j < n && pat[j] == '!' {
+                    j += 1;
+                }
+                if j < n && pat[j] == ']' {
+                    j += 1;
+                }
+                while j < n && pat[j] != ']' {
+                    j += 1;
+                }
+                if j >= n {
+                    // An unclosed bracket is just a bracket.
+                    res.push_str("\\[");
+                } else {
+                    res.push('[');
+                    if pat[i] == '!' {
+                        res.push('^');
+                        i += 1;
+                    }
+                    translate_set(&pat[i..j], &mut res);
+                    res.push(']');
+                    i = j + 1;
+                }
+            }
+            c => res.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    res
+}
+
+fn compile_pattern(pat: &str, vm: &VirtualMachine) -> PyResult<Regex> {
+    if let Some(regex) = CACHE.with(|cache| cache.borrow().get(pat).cloned()) {
+        return Ok(regex);
+    }
+    let regex = Regex::new(&format!("\\A(?s:{})\\z", translate_body(pat)))
+        .map_err(|err| vm.new_value_error(format!("Error in regex: {}", err)))?;
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= MAX_CACHE {
+            cache.clear();
+        }
+        cache.insert(pat.to_string(), regex.clone());
+    });
+    Ok(regex)
+}
+
+fn fnmatch_fnmatch(name: PyStringRef, pat: PyStringRef, vm: &VirtualMachine) -> PyResult<bool> {
+    let regex = compile_pattern(&normcase(pat.as_str()), vm)?;
+    Ok(regex.is_match(&normcase(name.as_str())))
+}
+
+fn fnmatch_fnmatchcase(name: PyStringRef, pat: PyStringRef, vm: &VirtualMachine) -> PyResult<bool> {
+    let regex = compile_pattern(pat.as_str(), vm)?;
+    Ok(regex.is_match(name.as_str()))
+}
+
+fn fnmatch_filter(
+    names: PyIterable<PyStringRef>,
+    pat: PyStringRef,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let regex = compile_pattern(&normcase(pat.as_str()), vm)?;
+    let mut matching = vec![];
+    for name in names.iter(vm)? {
+        let name = name?;
+        if regex.is_match(&normcase(name.as_str())) {
+            matching.push(name.into_object());
+        }
+    }
+    Ok(vm.ctx.new_list(matching))
+}
+
+fn fnmatch_translate(pat: PyStringRef, _vm: &VirtualMachine) -> String {
+    format!("(?s:{})\\Z", translate_body(pat.as_str()))
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    py_module!(vm, "fnmatch", {
+        "fnmatch" => ctx.new_rustfunc(fnmatch_fnmatch),
+        "fnmatchcase" => ctx.new_rustfunc(fnmatch_fnmatchcase),
+        "filter" => ctx.new_rustfunc(fnmatch_filter),
+        "translate" => ctx.new_rustfunc(fnmatch_translate),
+    })
+}
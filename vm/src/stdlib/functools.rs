@@ -1,7 +1,7 @@
 use crate::function::OptionalArg;
 use crate::obj::objiter;
-use crate::obj::objtype;
-use crate::pyobject::{PyObjectRef, PyResult};
+use crate::obj::objtype::{self, PyClassRef};
+use crate::pyobject::{PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue};
 use crate::vm::VirtualMachine;
 
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
@@ -9,6 +9,8 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
 
     py_module!(vm, "_functools", {
         "reduce" => ctx.new_rustfunc(functools_reduce),
+        "cmp_to_key" => ctx.new_rustfunc(functools_cmp_to_key),
+        "KeyWrapper" => PyKeyWrapper::make_class(ctx),
     })
 }
 
@@ -44,3 +46,103 @@ fn functools_reduce(
 
     Ok(accumulator)
 }
+
+fn functools_cmp_to_key(mycmp: PyObjectRef, vm: &VirtualMachine) -> PyRef<PyKeyWrapper> {
+    PyKeyWrapper {
+        cmp: mycmp,
+        obj: None,
+    }
+    .into_ref(vm)
+}
+
+/// The key class returned by `cmp_to_key`. Calling it wraps a value, and
+/// wrapped values compare by the sign of `cmp(self.obj, other.obj)`.
+#[pyclass(name = "KeyWrapper")]
+#[derive(Debug)]
+struct PyKeyWrapper {
+    cmp: PyObjectRef,
+    obj: Option<PyObjectRef>,
+}
+
+type PyKeyWrapperRef = PyRef<PyKeyWrapper>;
+
+impl PyValue for PyKeyWrapper {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("_functools", "KeyWrapper")
+    }
+}
+
+#[pyimpl]
+impl PyKeyWrapper {
+    #[pymethod(name = "__call__")]
+    fn call(&self, obj: PyObjectRef, vm: &VirtualMachine) -> PyKeyWrapperRef {
+        PyKeyWrapper {
+            cmp: self.cmp.clone(),
+            obj: Some(obj),
+        }
+        .into_ref(vm)
+    }
+
+    #[pyproperty(name = "obj")]
+    fn obj(&self, vm: &VirtualMachine) -> PyResult {
+        self.obj
+            .clone()
+            .ok_or_else(|| vm.new_attribute_error("obj".to_string()))
+    }
+
+    /// Call the comparison function and compare its result against zero.
+    fn compare(
+        &self,
+        other: PyObjectRef,
+        op: fn(&VirtualMachine, PyObjectRef, PyObjectRef) -> PyResult,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let other = match other.downcast::<PyKeyWrapper>() {
+            Ok(other) => other,
+            Err(_) => {
+                return Err(vm.new_type_error("other argument must be K instance".to_string()))
+            }
+        };
+        let (a, b) = match (&self.obj, &other.obj) {
+            (Some(a), Some(b)) => (a.clone(), b.clone()),
+            _ => return Err(vm.new_attribute_error("object".to_string())),
+        };
+        let result = vm.invoke(&self.cmp, vec![a, b])?;
+        op(vm, result, vm.new_int(0))
+    }
+
+    #[pymethod(name = "__lt__")]
+    fn lt(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.compare(other, VirtualMachine::_lt, vm)
+    }
+
+    #[pymethod(name = "__le__")]
+    fn le(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.compare(other, VirtualMachine::_le, vm)
+    }
+
+    #[pymethod(name = "__eq__")]
+    fn eq(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.compare(other, VirtualMachine::_eq, vm)
+    }
+
+    #[pymethod(name = "__ne__")]
+    fn ne(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.compare(other, VirtualMachine::_ne, vm)
+    }
+
+    #[pymethod(name = "__gt__")]
+    fn gt(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.compare(other, VirtualMachine::_gt, vm)
+    }
+
+    #[pymethod(name = "__ge__")]
+    fn ge(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.compare(other, VirtualMachine::_ge, vm)
+    }
+
+    #[pymethod(name = "__hash__")]
+    fn hash(&self, vm: &VirtualMachine) -> PyResult<()> {
+        Err(vm.new_type_error("unhashable type".to_string()))
+    }
+}
@@ -8,7 +8,8 @@ use std::cell::RefCell;
 use std::fmt;
 
 use blake2::{Blake2b, Blake2s};
-use digest::DynDigest;
+use digest::generic_array::typenum::Unsigned;
+use digest::{BlockInput, DynDigest};
 use md5::Md5;
 use sha1::Sha1;
 use sha2::{Sha224, Sha256, Sha384, Sha512};
@@ -58,6 +59,11 @@ impl PyHasher {
         Ok(vm.ctx.new_int(self.buffer.borrow().digest_size()))
     }
 
+    #[pyproperty(name = "block_size")]
+    fn block_size(&self, vm: &VirtualMachine) -> PyResult {
+        Ok(vm.ctx.new_int(self.buffer.borrow().block_size))
+    }
+
     #[pymethod(name = "update")]
     fn update(&self, data: PyBytesRef, vm: &VirtualMachine) -> PyResult {
         self.buffer.borrow_mut().input(data.get_value());
@@ -76,6 +82,11 @@ impl PyHasher {
         hex::encode(result)
     }
 
+    #[pymethod(name = "copy")]
+    fn copy(&self, _vm: &VirtualMachine) -> PyHasher {
+        PyHasher::new(&self.name, self.buffer.borrow().clone())
+    }
+
     fn get_digest(&self) -> Vec<u8> {
         self.buffer.borrow().get_digest()
     }
@@ -103,52 +114,59 @@ fn hashlib_new(
         "blake2s" => Ok(PyHasher::new("blake2s", HashWrapper::blake2s())),
         other => Err(vm.new_value_error(format!("Unknown hashing algorithm: {}", other))),
     }?;
+    init(hasher, data, vm)
+}
 
+/// Feed the optional initial data of a constructor to a new hasher.
+fn init(
+    hasher: PyHasher,
+    data: OptionalArg<PyBytesRef>,
+    vm: &VirtualMachine,
+) -> PyResult<PyHasher> {
     if let OptionalArg::Present(data) = data {
         hasher.update(data, vm)?;
     }
-
     Ok(hasher)
 }
 
-fn md5(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("md5", HashWrapper::md5()))
+fn md5(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("md5", HashWrapper::md5()), data, vm)
 }
 
-fn sha1(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha1", HashWrapper::sha1()))
+fn sha1(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha1", HashWrapper::sha1()), data, vm)
 }
 
-fn sha224(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha224", HashWrapper::sha224()))
+fn sha224(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha224", HashWrapper::sha224()), data, vm)
 }
 
-fn sha256(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha256", HashWrapper::sha256()))
+fn sha256(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha256", HashWrapper::sha256()), data, vm)
 }
 
-fn sha384(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha384", HashWrapper::sha384()))
+fn sha384(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha384", HashWrapper::sha384()), data, vm)
 }
 
-fn sha512(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha512", HashWrapper::sha512()))
+fn sha512(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha512", HashWrapper::sha512()), data, vm)
 }
 
-fn sha3_224(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha3_224", HashWrapper::sha3_224()))
+fn sha3_224(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha3_224", HashWrapper::sha3_224()), data, vm)
 }
 
-fn sha3_256(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha3_256", HashWrapper::sha3_256()))
+fn sha3_256(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha3_256", HashWrapper::sha3_256()), data, vm)
 }
 
-fn sha3_384(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha3_384", HashWrapper::sha3_384()))
+fn sha3_384(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha3_384", HashWrapper::sha3_384()), data, vm)
 }
 
-fn sha3_512(_vm: &VirtualMachine) -> PyResult<PyHasher> {
-    Ok(PyHasher::new("sha3_512", HashWrapper::sha3_512()))
+fn sha3_512(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
+    init(PyHasher::new("sha3_512", HashWrapper::sha3_512()), data, vm)
 }
 
 fn shake128(vm: &VirtualMachine) -> PyResult<PyHasher> {
@@ -161,14 +179,14 @@ fn shake256(vm: &VirtualMachine) -> PyResult<PyHasher> {
     // TODO: Ok(PyHasher::new("shake256", HashWrapper::shake256()))
 }
 
-fn blake2b(_vm: &VirtualMachine) -> PyResult<PyHasher> {
+fn blake2b(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
     // TODO: handle parameters
-    Ok(PyHasher::new("blake2b", HashWrapper::blake2b()))
+    init(PyHasher::new("blake2b", HashWrapper::blake2b()), data, vm)
 }
 
-fn blake2s(_vm: &VirtualMachine) -> PyResult<PyHasher> {
+fn blake2s(data: OptionalArg<PyBytesRef>, vm: &VirtualMachine) -> PyResult<PyHasher> {
     // TODO: handle parameters
-    Ok(PyHasher::new("blake2s", HashWrapper::blake2s()))
+    init(PyHasher::new("blake2s", HashWrapper::blake2s()), data, vm)
 }
 
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
@@ -197,17 +215,22 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
 }
 
 /// Generic wrapper patching around the hashing libraries.
+#[derive(Clone)]
 struct HashWrapper {
     inner: Box<dyn DynDigest>,
+    /// Size in bytes of the blocks the hash consumes, which HMAC pads keys to.
+    block_size: usize,
 }
 
 impl HashWrapper {
-    fn new<D: 'static>(d: D) -> Self
+    fn new<D>(d: D) -> Self
     where
-        D: DynDigest,
-        D: Sized,
+        D: DynDigest + BlockInput + Sized + 'static,
     {
-        HashWrapper { inner: Box::new(d) }
+        HashWrapper {
+            inner: Box::new(d),
+            block_size: D::BlockSize::to_usize(),
+        }
     }
 
     fn md5() -> Self {
@@ -1,8 +1,10 @@
 use crate::import;
+use crate::obj::objbyteinner::PyByteInner;
 use crate::obj::objcode::PyCode;
 use crate::obj::objmodule::PyModuleRef;
 use crate::obj::objstr;
 use crate::obj::objstr::PyStringRef;
+use crate::pyhash;
 use crate::pyobject::{ItemProtocol, PyObjectRef, PyResult};
 use crate::vm::VirtualMachine;
 
@@ -80,6 +82,13 @@ fn imp_is_frozen_package(name: PyStringRef, vm: &VirtualMachine) -> PyResult<boo
         })
 }
 
+/// The 8 byte hash stored in hash-based pycs, keyed by the bytecode magic number.
+fn imp_source_hash(key: u64, source: PyByteInner, _vm: &VirtualMachine) -> Vec<u8> {
+    pyhash::siphash13(key, 0, &source.elements)
+        .to_le_bytes()
+        .to_vec()
+}
+
 fn imp_fix_co_filename(_code: PyObjectRef, _path: PyStringRef, _vm: &VirtualMachine) {
     // TODO:
 }
@@ -99,6 +108,8 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "init_frozen" => ctx.new_rustfunc(imp_init_frozen),
         "is_frozen_package" => ctx.new_rustfunc(imp_is_frozen_package),
         "_fix_co_filename" => ctx.new_rustfunc(imp_fix_co_filename),
+        "source_hash" => ctx.new_rustfunc(imp_source_hash),
+        "check_hash_based_pycs" => ctx.new_str(vm.settings.check_hash_based_pycs.clone()),
     });
 
     module
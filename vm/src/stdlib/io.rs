@@ -1,27 +1,33 @@
 /*
  * I/O core tools.
  */
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::io::prelude::*;
 use std::io::Cursor;
 use std::io::SeekFrom;
 
 use num_bigint::ToBigInt;
-use num_traits::ToPrimitive;
+use num_traits::{Signed, ToPrimitive};
 
 use super::os;
 use crate::function::{OptionalArg, PyFuncArgs};
+use crate::obj::objbool;
 use crate::obj::objbytearray::PyByteArray;
+use crate::obj::objbyteinner::{self, PyByteInner};
 use crate::obj::objbytes;
-use crate::obj::objbytes::PyBytes;
-use crate::obj::objint;
+use crate::obj::objint::{self, PyIntRef};
+use crate::obj::objproperty::PropertyBuilder;
 use crate::obj::objstr;
+use crate::obj::objstr::PyStringRef;
 use crate::obj::objtype;
 use crate::obj::objtype::PyClassRef;
-use crate::pyobject::TypeProtocol;
-use crate::pyobject::{BufferProtocol, PyObjectRef, PyRef, PyResult, PyValue};
+use crate::pyobject::{
+    BufferProtocol, PyAttributes, PyObjectRef, PyRef, PyResult, PyValue, TryFromObject,
+};
 use crate::vm::VirtualMachine;
 
+const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
 fn byte_count(bytes: OptionalArg<Option<PyObjectRef>>) -> i64 {
     match bytes {
         OptionalArg::Present(Some(ref int)) => objint::get_value(int).to_i64().unwrap(),
@@ -53,12 +59,17 @@ impl BufferedIO {
         self.cursor.clone().into_inner()
     }
 
+    fn tell(&self) -> u64 {
+        self.cursor.position()
+    }
+
     //skip to the jth position
-    fn seek(&mut self, offset: u64) -> Option<u64> {
-        match self.cursor.seek(SeekFrom::Start(offset)) {
-            Ok(_) => Some(offset),
-            Err(_) => None,
-        }
+    fn seek(&mut self, position: SeekFrom) -> Option<u64> {
+        // seeking back past the start stops at the start
+        self.cursor
+            .seek(position)
+            .or_else(|_| self.cursor.seek(SeekFrom::Start(0)))
+            .ok()
     }
 
     //Read k bytes from the object and return.
@@ -66,7 +77,7 @@ impl BufferedIO {
         let mut buffer = Vec::new();
 
         //for a defined number of bytes, i.e. bytes != -1
-        if bytes > 0 {
+        if bytes >= 0 {
             let mut handle = self.cursor.clone().take(bytes as u64);
             //read handle into buffer
 
@@ -85,11 +96,61 @@ impl BufferedIO {
 
         Some(buffer)
     }
+
+    //Read up to and including the next newline.
+    fn readline(&mut self) -> Option<Vec<u8>> {
+        let mut buffer = Vec::new();
+        match self.cursor.read_until(b'\n', &mut buffer) {
+            Ok(_) => Some(buffer),
+            Err(_) => None,
+        }
+    }
+
+    //Resize to the given size, or to the current position; the position is unchanged.
+    fn truncate(&mut self, size: Option<usize>) -> usize {
+        let size = size.unwrap_or(self.cursor.position() as usize);
+        self.cursor.get_mut().truncate(size);
+        size
+    }
+}
+
+fn truncate_size(
+    size: OptionalArg<Option<PyIntRef>>,
+    vm: &VirtualMachine,
+) -> PyResult<Option<usize>> {
+    match size {
+        OptionalArg::Present(Some(ref size)) => {
+            let size = size.as_bigint();
+            if size.is_negative() {
+                return Err(vm.new_value_error(format!("negative size value {}", size)));
+            }
+            match size.to_usize() {
+                Some(size) => Ok(Some(size)),
+                None => Err(vm.new_overflow_error(format!("size {} is too large", size))),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+// The byte offset of the first `chars` code points in UTF-8 `data`.
+fn char_offset(data: &[u8], chars: usize) -> usize {
+    data.iter()
+        .enumerate()
+        .filter(|(_, b)| *b & 0xc0 != 0x80)
+        .nth(chars)
+        .map_or(data.len(), |(i, _)| i)
+}
+
+// The number of code points in UTF-8 `data`.
+fn char_count(data: &[u8]) -> usize {
+    data.iter().filter(|b| *b & 0xc0 != 0x80).count()
 }
 
 #[derive(Debug)]
 struct PyStringIO {
     buffer: RefCell<BufferedIO>,
+    closed: Cell<bool>,
 }
 
 type PyStringIORef = PyRef<PyStringIO>;
@@ -120,8 +181,8 @@ impl PyStringIORef {
     }
 
     //skip to the jth position
-    fn seek(self, offset: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-        let position = objint::get_value(&offset).to_u64().unwrap();
+    fn seek(self, offset: i64, how: OptionalArg<i32>, vm: &VirtualMachine) -> PyResult {
+        let position = seek_position(offset, how, vm)?;
         match self.buffer.borrow_mut().seek(position) {
             Some(value) => Ok(vm.ctx.new_int(value)),
             None => Err(vm.new_value_error("Error Performing Operation".to_string())),
@@ -132,6 +193,10 @@ impl PyStringIORef {
         true
     }
 
+    fn tell(self, _vm: &VirtualMachine) -> u64 {
+        self.buffer.borrow().tell()
+    }
+
     //Read k bytes from the object and return.
     //If k is undefined || k == -1, then we read all bytes until the end of the file.
     //This also increments the stream position by the value of k
@@ -146,6 +211,42 @@ impl PyStringIORef {
             Err(_) => Err(vm.new_value_error("Error Retrieving Value".to_string())),
         }
     }
+
+    fn readline(self, vm: &VirtualMachine) -> PyResult {
+        let data = self.buffer.borrow_mut().readline().unwrap_or_default();
+
+        match String::from_utf8(data) {
+            Ok(value) => Ok(vm.ctx.new_str(value)),
+            Err(_) => Err(vm.new_value_error("Error Retrieving Value".to_string())),
+        }
+    }
+
+    //Sizes count code points, while the buffer holds UTF-8.
+    fn truncate(self, size: OptionalArg<Option<PyIntRef>>, vm: &VirtualMachine) -> PyResult<usize> {
+        let mut buffer = self.buffer.borrow_mut();
+        match truncate_size(size, vm)? {
+            Some(size) => {
+                let byte_size = char_offset(buffer.cursor.get_ref(), size);
+                buffer.truncate(Some(byte_size));
+                Ok(size)
+            }
+            None => {
+                let data = buffer.cursor.get_ref();
+                let position = (buffer.tell() as usize).min(data.len());
+                let chars = char_count(&data[..position]);
+                buffer.truncate(None);
+                Ok(chars)
+            }
+        }
+    }
+
+    fn close(self, _vm: &VirtualMachine) {
+        self.closed.set(true);
+    }
+
+    fn closed(self, _vm: &VirtualMachine) -> bool {
+        self.closed.get()
+    }
 }
 
 fn string_io_new(
@@ -160,6 +261,7 @@ fn string_io_new(
 
     PyStringIO {
         buffer: RefCell::new(BufferedIO::new(Cursor::new(raw_string.into_bytes()))),
+        closed: Cell::new(false),
     }
     .into_ref_with_type(vm, cls)
 }
@@ -167,6 +269,7 @@ fn string_io_new(
 #[derive(Debug)]
 struct PyBytesIO {
     buffer: RefCell<BufferedIO>,
+    closed: Cell<bool>,
 }
 
 type PyBytesIORef = PyRef<PyBytesIO>;
@@ -202,8 +305,8 @@ impl PyBytesIORef {
     }
 
     //skip to the jth position
-    fn seek(self, offset: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-        let position = objint::get_value(&offset).to_u64().unwrap();
+    fn seek(self, offset: i64, how: OptionalArg<i32>, vm: &VirtualMachine) -> PyResult {
+        let position = seek_position(offset, how, vm)?;
         match self.buffer.borrow_mut().seek(position) {
             Some(value) => Ok(vm.ctx.new_int(value)),
             None => Err(vm.new_value_error("Error Performing Operation".to_string())),
@@ -213,6 +316,29 @@ impl PyBytesIORef {
     fn seekable(self, _vm: &VirtualMachine) -> bool {
         true
     }
+
+    fn tell(self, _vm: &VirtualMachine) -> u64 {
+        self.buffer.borrow().tell()
+    }
+
+    fn readline(self, vm: &VirtualMachine) -> PyResult {
+        match self.buffer.borrow_mut().readline() {
+            Some(value) => Ok(vm.ctx.new_bytes(value)),
+            None => Err(vm.new_value_error("Error Retrieving Value".to_string())),
+        }
+    }
+
+    fn truncate(self, size: OptionalArg<Option<PyIntRef>>, vm: &VirtualMachine) -> PyResult<usize> {
+        Ok(self.buffer.borrow_mut().truncate(truncate_size(size, vm)?))
+    }
+
+    fn close(self, _vm: &VirtualMachine) {
+        self.closed.set(true);
+    }
+
+    fn closed(self, _vm: &VirtualMachine) -> bool {
+        self.closed.get()
+    }
 }
 
 fn bytes_io_new(
@@ -227,6 +353,7 @@ fn bytes_io_new(
 
     PyBytesIO {
         buffer: RefCell::new(BufferedIO::new(Cursor::new(raw_bytes))),
+        closed: Cell::new(false),
     }
     .into_ref_with_type(vm, cls)
 }
@@ -242,15 +369,42 @@ fn io_base_cm_exit(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
         args,
         // The context manager protocol requires these, but we don't use them
         required = [
-            (_instance, None),
+            (instance, None),
             (_exception_type, None),
             (_exception_value, None),
             (_traceback, None)
         ]
     );
+    vm.call_method(instance, "close", vec![])?;
     Ok(vm.get_none())
 }
 
+fn is_closed(instance: &PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    let closed = vm.get_attribute(instance.clone(), "closed")?;
+    objbool::boolval(vm, closed)
+}
+
+fn io_base_close(instance: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+    if !is_closed(&instance, vm)? {
+        vm.call_method(&instance, "flush", vec![])?;
+        vm.set_attr(&instance, "closed", vm.new_bool(true))?;
+    }
+    Ok(())
+}
+
+/// Flush a wrapping stream and close the stream it wraps, which is where
+/// the stream keeps whether it is closed.
+fn close_wrapped(wrapper: PyObjectRef, attr: &str, vm: &VirtualMachine) -> PyResult<()> {
+    let wrapped = vm.get_attribute(wrapper.clone(), attr)?;
+    if is_closed(&wrapped, vm)? {
+        return Ok(());
+    }
+    // The wrapped stream is closed even if flushing fails.
+    let flushed = vm.call_method(&wrapper, "flush", vec![]);
+    vm.call_method(&wrapped, "close", vec![])?;
+    flushed.map(drop)
+}
+
 // TODO Check if closed, then if so raise ValueError
 fn io_base_flush(_self: PyObjectRef, _vm: &VirtualMachine) {}
 
@@ -258,15 +412,118 @@ fn io_base_seekable(vm: &VirtualMachine, _args: PyFuncArgs) -> PyResult {
     Ok(vm.ctx.new_bool(false))
 }
 
+fn io_base_isatty(_self: PyObjectRef, _vm: &VirtualMachine) -> bool {
+    false
+}
+
+/// Read a line a byte at a time, for streams that only implement read.
+fn io_base_readline(
+    instance: PyObjectRef,
+    size: OptionalArg<Option<PyObjectRef>>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let size = byte_count(size);
+    let mut line = vec![];
+    while size < 0 || line.len() < size as usize {
+        let read = vm.call_method(&instance, "read", vec![vm.new_int(1)])?;
+        let read = objbyteinner::try_as_byte(&read).ok_or_else(|| {
+            vm.new_type_error("read() should have returned a bytes object".to_string())
+        })?;
+        if read.is_empty() {
+            break;
+        }
+        line.extend(read);
+        if line.ends_with(b"\n") {
+            break;
+        }
+    }
+    Ok(vm.ctx.new_bytes(line))
+}
+
+fn io_base_readlines(instance: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let mut lines = vec![];
+    loop {
+        let line = vm.call_method(&instance, "readline", vec![])?;
+        if !objbool::boolval(vm, line.clone())? {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(vm.ctx.new_list(lines))
+}
+
 fn buffered_io_base_init(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(vm, args, required = [(buffered, None), (raw, None)]);
     vm.set_attr(buffered, "raw", raw.clone())?;
     Ok(vm.get_none())
 }
 
+fn buffered_io_base_close(buffered: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+    close_wrapped(buffered, "raw", vm)
+}
+
+fn buffered_io_base_closed(buffered: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let raw = vm.get_attribute(buffered, "raw")?;
+    vm.get_attribute(raw, "closed")
+}
+
+fn buffered_io_base_fileno(buffered: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let raw = vm.get_attribute(buffered, "raw")?;
+    vm.call_method(&raw, "fileno", vec![])
+}
+
+fn buffered_io_base_isatty(buffered: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let raw = vm.get_attribute(buffered, "raw")?;
+    vm.call_method(&raw, "isatty", vec![])
+}
+
+fn buffered_io_base_seek(
+    buffered: PyObjectRef,
+    offset: PyObjectRef,
+    how: OptionalArg<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let raw = vm.get_attribute(buffered, "raw")?;
+    let mut args = vec![offset];
+    args.extend(how.into_option());
+    vm.call_method(&raw, "seek", args)
+}
+
+fn buffered_io_base_tell(buffered: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let raw = vm.get_attribute(buffered, "raw")?;
+    vm.call_method(&raw, "tell", vec![])
+}
+
 fn buffered_reader_read(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
-    arg_check!(vm, args, required = [(buffered, None)]);
-    let buff_size = 8 * 1024;
+    arg_check!(
+        vm,
+        args,
+        required = [(buffered, None)],
+        optional = [(size, None)]
+    );
+
+    if let Some(size) = size {
+        if objtype::isinstance(size, &vm.ctx.int_type()) && !objint::get_value(size).is_negative() {
+            // A raw read may return fewer bytes than asked for, so keep going
+            // until there are enough or the file ends.
+            let size = objint::get_value(size)
+                .to_usize()
+                .unwrap_or_else(usize::max_value);
+            let raw = vm.get_attribute(buffered.clone(), "raw")?;
+            let mut result = vec![];
+            while result.len() < size {
+                let chunk = vm.call_method(&raw, "read", vec![vm.new_int(size - result.len())])?;
+                let chunk = PyByteInner::try_from_object(vm, chunk)?.elements;
+                if chunk.is_empty() {
+                    break;
+                }
+                result.extend(chunk);
+            }
+            return Ok(vm.ctx.new_bytes(result));
+        }
+    }
+
+    let buff_size = DEFAULT_BUFFER_SIZE;
     let buffer = vm.ctx.new_bytearray(vec![0; buff_size]);
 
     //buffer method
@@ -294,6 +551,20 @@ fn buffered_reader_read(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     Ok(vm.ctx.new_bytes(result))
 }
 
+/// Read at most `size` bytes with a single raw read.
+fn buffered_reader_read1(
+    buffered: PyObjectRef,
+    size: OptionalArg<isize>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let size = match size {
+        OptionalArg::Present(size) if size >= 0 => size as usize,
+        _ => DEFAULT_BUFFER_SIZE,
+    };
+    let raw = vm.get_attribute(buffered, "raw")?;
+    vm.call_method(&raw, "read", vec![vm.new_int(size)])
+}
+
 fn buffered_reader_seekable(vm: &VirtualMachine, _args: PyFuncArgs) -> PyResult {
     Ok(vm.ctx.new_bool(true))
 }
@@ -301,18 +572,32 @@ fn buffered_reader_seekable(vm: &VirtualMachine, _args: PyFuncArgs) -> PyResult
 fn compute_c_flag(mode: &str) -> u32 {
     let flags = match mode.chars().next() {
         Some(mode) => match mode {
-            'w' => os::FileCreationFlags::O_WRONLY | os::FileCreationFlags::O_CREAT,
+            'w' => {
+                os::FileCreationFlags::O_WRONLY
+                    | os::FileCreationFlags::O_CREAT
+                    | os::FileCreationFlags::O_TRUNC
+            }
             'x' => {
                 os::FileCreationFlags::O_WRONLY
                     | os::FileCreationFlags::O_CREAT
                     | os::FileCreationFlags::O_EXCL
             }
-            'a' => os::FileCreationFlags::O_APPEND,
+            'a' => {
+                os::FileCreationFlags::O_WRONLY
+                    | os::FileCreationFlags::O_CREAT
+                    | os::FileCreationFlags::O_APPEND
+            }
             '+' => os::FileCreationFlags::O_RDWR,
             _ => os::FileCreationFlags::O_RDONLY,
         },
         None => os::FileCreationFlags::O_RDONLY,
     };
+    // updating opens the file for both reading and writing
+    let flags = if mode.contains('+') {
+        (flags - os::FileCreationFlags::O_WRONLY) | os::FileCreationFlags::O_RDWR
+    } else {
+        flags
+    };
     flags.bits()
 }
 
@@ -324,23 +609,32 @@ fn file_io_init(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
         optional = [(mode, Some(vm.ctx.str_type()))]
     );
 
-    let file_no = if objtype::isinstance(&name, &vm.ctx.str_type()) {
+    // closefd=False leaves the file descriptor open when the file is closed
+    let closefd = match args.get_optional_kwarg("closefd") {
+        Some(closefd) => objbool::boolval(vm, closefd)?,
+        None => true,
+    };
+
+    let file_no = if objtype::isinstance(name, &vm.ctx.int_type()) {
+        name.clone()
+    } else if !closefd {
+        return Err(vm.new_value_error("Cannot use closefd=False with file name".to_string()));
+    } else {
+        let path = os::PyPathLike::try_from_object(vm, name.clone()).map_err(|_| {
+            vm.new_type_error("name parameter must be string, os.PathLike or int".to_string())
+        })?;
         let rust_mode = mode.map_or("r".to_string(), objstr::get_value);
         let args = vec![
-            name.clone(),
+            vm.new_str(path.as_str().to_string()),
             vm.ctx
                 .new_int(compute_c_flag(&rust_mode).to_bigint().unwrap()),
         ];
         os::os_open(vm, PyFuncArgs::new(args, vec![]))?
-    } else if objtype::isinstance(&name, &vm.ctx.int_type()) {
-        name.clone()
-    } else {
-        return Err(vm.new_type_error("name parameter must be string or int".to_string()));
     };
 
     vm.set_attr(file_io, "name", name.clone())?;
-    vm.set_attr(file_io, "fileno", file_no)?;
-    vm.set_attr(file_io, "closefd", vm.new_bool(false))?;
+    vm.set_attr(file_io, "__fileno", file_no)?;
+    vm.set_attr(file_io, "closefd", vm.new_bool(closefd))?;
     vm.set_attr(file_io, "closed", vm.new_bool(false))?;
     Ok(vm.get_none())
 }
@@ -353,29 +647,33 @@ fn file_io_read(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
         optional = [(read_byte, Some(vm.ctx.int_type()))]
     );
 
-    let file_no = vm.get_attribute(file_io.clone(), "fileno")?;
+    let file_no = vm.get_attribute(file_io.clone(), "__fileno")?;
     let raw_fd = objint::get_value(&file_no).to_i64().unwrap();
 
     let mut handle = os::rust_file(raw_fd);
 
-    let bytes = match read_byte {
-        None => {
-            let mut bytes = vec![];
-            handle
-                .read_to_end(&mut bytes)
-                .map_err(|_| vm.new_value_error("Error reading from Buffer".to_string()))?;
-            bytes
-        }
-        Some(read_byte) => {
-            let mut bytes = vec![0; objint::get_value(&read_byte).to_usize().unwrap()];
-            handle
-                .read_exact(&mut bytes)
-                .map_err(|_| vm.new_value_error("Error reading from Buffer".to_string()))?;
-            let updated = os::raw_file_number(handle);
-            vm.set_attr(file_io, "fileno", vm.ctx.new_int(updated))?;
-            bytes
+    // a negative size means reading until EOF; otherwise a single read of at
+    // most that many bytes, which like the OS call may return fewer
+    let mut bytes = vec![];
+    let result = match read_byte.and_then(|read_byte| objint::get_value(read_byte).to_u64()) {
+        None => handle.read_to_end(&mut bytes),
+        Some(count) => {
+            bytes.resize(count.min(64 * DEFAULT_BUFFER_SIZE as u64) as usize, 0);
+            loop {
+                match handle.read(&mut bytes) {
+                    Err(ref err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                    Ok(len) => {
+                        bytes.truncate(len);
+                        break Ok(len);
+                    }
+                    Err(err) => break Err(err),
+                }
+            }
         }
     };
+    let updated = os::raw_file_number(handle);
+    vm.set_attr(file_io, "__fileno", vm.ctx.new_int(updated))?;
+    result.map_err(|_| vm.new_value_error("Error reading from Buffer".to_string()))?;
 
     Ok(vm.ctx.new_bytes(bytes))
 }
@@ -393,7 +691,7 @@ fn file_io_readinto(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     let py_length = vm.call_method(obj, "__len__", PyFuncArgs::default())?;
     let length = objint::get_value(&py_length).to_u64().unwrap();
 
-    let file_no = vm.get_attribute(file_io.clone(), "fileno")?;
+    let file_no = vm.get_attribute(file_io.clone(), "__fileno")?;
     let raw_fd = objint::get_value(&file_no).to_i64().unwrap();
 
     //extract unix file descriptor.
@@ -412,14 +710,16 @@ fn file_io_readinto(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     };
 
     let updated = os::raw_file_number(f.into_inner());
-    vm.set_attr(file_io, "fileno", vm.ctx.new_int(updated))?;
+    vm.set_attr(file_io, "__fileno", vm.ctx.new_int(updated))?;
     Ok(vm.get_none())
 }
 
 fn file_io_write(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(vm, args, required = [(file_io, None), (obj, None)]);
 
-    let file_no = vm.get_attribute(file_io.clone(), "fileno")?;
+    let bytes = PyByteInner::try_from_object(vm, obj.clone())?.elements;
+
+    let file_no = vm.get_attribute(file_io.clone(), "__fileno")?;
     let raw_fd = objint::get_value(&file_no).to_i64().unwrap();
 
     //unsafe block - creates file handle from the UNIX file descriptor
@@ -427,20 +727,11 @@ fn file_io_write(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     //to support windows - i.e. raw file_handles
     let mut handle = os::rust_file(raw_fd);
 
-    let bytes = match_class!(obj.clone(),
-        i @ PyBytes => Ok(i.get_value().to_vec()),
-        j @ PyByteArray => Ok(j.inner.borrow().elements.to_vec()),
-        obj => Err(vm.new_type_error(format!(
-                    "a bytes-like object is required, not {}",
-                    obj.class()
-                )))
-    );
-
-    match handle.write(&bytes?) {
+    match handle.write(&bytes) {
         Ok(len) => {
             //reset raw fd on the FileIO object
             let updated = os::raw_file_number(handle);
-            vm.set_attr(file_io, "fileno", vm.ctx.new_int(updated))?;
+            vm.set_attr(file_io, "__fileno", vm.ctx.new_int(updated))?;
 
             //return number of bytes written
             Ok(vm.ctx.new_int(len))
@@ -453,14 +744,19 @@ fn file_io_write(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
 fn file_io_close(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     use std::os::windows::io::IntoRawHandle;
     arg_check!(vm, args, required = [(file_io, None)]);
-    let file_no = vm.get_attribute(file_io.clone(), "fileno")?;
-    let raw_fd = objint::get_value(&file_no).to_i64().unwrap();
-    let handle = os::rust_file(raw_fd);
-    let raw_handle = handle.into_raw_handle();
-    unsafe {
-        kernel32::CloseHandle(raw_handle);
+    if is_closed(file_io, vm)? {
+        return Ok(vm.ctx.none());
+    }
+    let closefd = vm.get_attribute(file_io.clone(), "closefd")?;
+    if objbool::boolval(vm, closefd)? {
+        let file_no = vm.get_attribute(file_io.clone(), "__fileno")?;
+        let raw_fd = objint::get_value(&file_no).to_i64().unwrap();
+        let handle = os::rust_file(raw_fd);
+        let raw_handle = handle.into_raw_handle();
+        unsafe {
+            kernel32::CloseHandle(raw_handle);
+        }
     }
-    vm.set_attr(file_io, "closefd", vm.new_bool(true))?;
     vm.set_attr(file_io, "closed", vm.new_bool(true))?;
     Ok(vm.ctx.none())
 }
@@ -468,12 +764,17 @@ fn file_io_close(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
 #[cfg(unix)]
 fn file_io_close(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(vm, args, required = [(file_io, None)]);
-    let file_no = vm.get_attribute(file_io.clone(), "fileno")?;
-    let raw_fd = objint::get_value(&file_no).to_i32().unwrap();
-    unsafe {
-        libc::close(raw_fd);
+    if is_closed(file_io, vm)? {
+        return Ok(vm.ctx.none());
+    }
+    let closefd = vm.get_attribute(file_io.clone(), "closefd")?;
+    if objbool::boolval(vm, closefd)? {
+        let file_no = vm.get_attribute(file_io.clone(), "__fileno")?;
+        let raw_fd = objint::get_value(&file_no).to_i32().unwrap();
+        unsafe {
+            libc::close(raw_fd);
+        }
     }
-    vm.set_attr(file_io, "closefd", vm.new_bool(true))?;
     vm.set_attr(file_io, "closed", vm.new_bool(true))?;
     Ok(vm.ctx.none())
 }
@@ -482,12 +783,57 @@ fn file_io_seekable(vm: &VirtualMachine, _args: PyFuncArgs) -> PyResult {
     Ok(vm.ctx.new_bool(true))
 }
 
+/// Interpret the arguments of seek, where `how` is 0 for the start of the
+/// stream, 1 for the current position and 2 for the end.
+fn seek_position(offset: i64, how: OptionalArg<i32>, vm: &VirtualMachine) -> PyResult<SeekFrom> {
+    match how.unwrap_or(0) {
+        0 if offset >= 0 => Ok(SeekFrom::Start(offset as u64)),
+        1 => Ok(SeekFrom::Current(offset)),
+        2 => Ok(SeekFrom::End(offset)),
+        _ => Err(vm.new_os_error("Invalid argument".to_string())),
+    }
+}
+
+fn file_io_seek(
+    file_io: PyObjectRef,
+    offset: i64,
+    how: OptionalArg<i32>,
+    vm: &VirtualMachine,
+) -> PyResult<u64> {
+    let position = seek_position(offset, how, vm)?;
+
+    let file_no = vm.get_attribute(file_io.clone(), "__fileno")?;
+    let raw_fd = objint::get_value(&file_no).to_i64().unwrap();
+    let mut handle = os::rust_file(raw_fd);
+    let result = handle.seek(position);
+    let updated = os::raw_file_number(handle);
+    vm.set_attr(&file_io, "__fileno", vm.ctx.new_int(updated))?;
+    result.map_err(|err| os::convert_io_error(vm, err))
+}
+
+fn file_io_tell(file_io: PyObjectRef, vm: &VirtualMachine) -> PyResult<u64> {
+    file_io_seek(file_io, 0, OptionalArg::Present(1), vm)
+}
+
+fn file_io_fileno(file_io: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    vm.get_attribute(file_io, "__fileno")
+}
+
+#[cfg(unix)]
+fn file_io_isatty(file_io: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    let file_no = vm.get_attribute(file_io, "__fileno")?;
+    let raw_fd = objint::get_value(&file_no).to_i32().unwrap();
+    Ok(unsafe { libc::isatty(raw_fd) } != 0)
+}
+
+#[cfg(not(unix))]
+fn file_io_isatty(_file_io: PyObjectRef, _vm: &VirtualMachine) -> bool {
+    false
+}
+
 fn buffered_writer_write(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
-    arg_check!(
-        vm,
-        args,
-        required = [(buffered, None), (obj, Some(vm.ctx.bytes_type()))]
-    );
+    // the raw stream checks that obj is bytes-like
+    arg_check!(vm, args, required = [(buffered, None), (obj, None)]);
 
     let raw = vm.get_attribute(buffered.clone(), "raw").unwrap();
 
@@ -499,15 +845,321 @@ fn buffered_writer_seekable(vm: &VirtualMachine, _args: PyFuncArgs) -> PyResult
     Ok(vm.ctx.new_bool(true))
 }
 
-fn text_io_wrapper_init(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
-    arg_check!(
+#[derive(FromArgs)]
+struct TextIOWrapperArgs {
+    #[pyarg(positional_or_keyword, default = "None")]
+    encoding: Option<PyStringRef>,
+    #[pyarg(positional_or_keyword, default = "None")]
+    errors: Option<PyStringRef>,
+    #[pyarg(positional_or_keyword, default = "None")]
+    newline: Option<PyStringRef>,
+    #[pyarg(positional_or_keyword, default = "false")]
+    line_buffering: bool,
+    #[pyarg(positional_or_keyword, default = "false")]
+    write_through: bool,
+}
+
+/// Raise a LookupError for encodings that text files can't write.
+fn check_encoding(encoding: &PyStringRef, vm: &VirtualMachine) -> PyResult<()> {
+    PyByteInner::from_string("", encoding.as_str(), vm)?;
+    Ok(())
+}
+
+fn check_newline(newline: &PyStringRef, vm: &VirtualMachine) -> PyResult<()> {
+    match newline.as_str() {
+        "" | "\n" | "\r" | "\r\n" => Ok(()),
+        other => Err(vm.new_value_error(format!("illegal newline value: {}", other))),
+    }
+}
+
+fn text_io_wrapper_init(
+    text_io_wrapper: PyObjectRef,
+    buffer: PyObjectRef,
+    TextIOWrapperArgs {
+        encoding,
+        errors,
+        newline,
+        line_buffering,
+        write_through,
+    }: TextIOWrapperArgs,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let encoding = match encoding {
+        Some(encoding) => {
+            check_encoding(&encoding, vm)?;
+            encoding.into_object()
+        }
+        None => vm.new_str("utf-8".to_string()),
+    };
+    let errors = match errors {
+        Some(errors) => errors.into_object(),
+        None => vm.new_str("strict".to_string()),
+    };
+    let newline = match newline {
+        Some(newline) => {
+            check_newline(&newline, vm)?;
+            newline.into_object()
+        }
+        None => vm.get_none(),
+    };
+
+    vm.set_attr(&text_io_wrapper, "buffer", buffer)?;
+    vm.set_attr(&text_io_wrapper, "__encoding", encoding)?;
+    vm.set_attr(&text_io_wrapper, "__errors", errors)?;
+    vm.set_attr(&text_io_wrapper, "__newline", newline)?;
+    // bytes read from the buffer past the last line returned by readline()
+    vm.set_attr(&text_io_wrapper, "__pending", vm.ctx.new_bytes(vec![]))?;
+    vm.set_attr(
+        &text_io_wrapper,
+        "line_buffering",
+        vm.new_bool(line_buffering),
+    )?;
+    vm.set_attr(
+        &text_io_wrapper,
+        "write_through",
+        vm.new_bool(write_through),
+    )?;
+    Ok(())
+}
+
+#[derive(FromArgs)]
+struct TextIOWrapperReconfigureArgs {
+    #[pyarg(keyword_only, default = "None")]
+    encoding: Option<PyStringRef>,
+    #[pyarg(keyword_only, default = "None")]
+    errors: Option<PyStringRef>,
+    #[pyarg(keyword_only, default = "None")]
+    newline: Option<PyStringRef>,
+    #[pyarg(keyword_only, default = "None")]
+    line_buffering: Option<bool>,
+    #[pyarg(keyword_only, default = "None")]
+    write_through: Option<bool>,
+}
+
+fn text_io_wrapper_reconfigure(
+    text_io_wrapper: PyObjectRef,
+    TextIOWrapperReconfigureArgs {
+        encoding,
+        errors,
+        newline,
+        line_buffering,
+        write_through,
+    }: TextIOWrapperReconfigureArgs,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    if let Some(ref encoding) = encoding {
+        check_encoding(encoding, vm)?;
+    }
+    if let Some(ref newline) = newline {
+        check_newline(newline, vm)?;
+    }
+    // What was written so far goes out with the old settings.
+    vm.call_method(&text_io_wrapper, "flush", vec![])?;
+
+    // A new encoding starts over with strict errors, unless told otherwise.
+    let errors = match (&encoding, errors) {
+        (_, Some(errors)) => Some(errors.into_object()),
+        (Some(_), None) => Some(vm.new_str("strict".to_string())),
+        (None, None) => None,
+    };
+    if let Some(encoding) = encoding {
+        vm.set_attr(&text_io_wrapper, "__encoding", encoding)?;
+    }
+    if let Some(errors) = errors {
+        vm.set_attr(&text_io_wrapper, "__errors", errors)?;
+    }
+    if let Some(newline) = newline {
+        vm.set_attr(&text_io_wrapper, "__newline", newline)?;
+    }
+    if let Some(line_buffering) = line_buffering {
+        vm.set_attr(
+            &text_io_wrapper,
+            "line_buffering",
+            vm.new_bool(line_buffering),
+        )?;
+    }
+    if let Some(write_through) = write_through {
+        vm.set_attr(
+            &text_io_wrapper,
+            "write_through",
+            vm.new_bool(write_through),
+        )?;
+    }
+    Ok(())
+}
+
+fn text_io_wrapper_newline(
+    text_io_wrapper: &PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<Option<PyStringRef>> {
+    let newline = vm.get_attribute(text_io_wrapper.clone(), "__newline")?;
+    TryFromObject::try_from_object(vm, newline)
+}
+
+fn text_io_wrapper_pending(
+    text_io_wrapper: &PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<Vec<u8>> {
+    let pending = vm.get_attribute(text_io_wrapper.clone(), "__pending")?;
+    Ok(PyByteInner::try_from_object(vm, pending)?.elements)
+}
+
+/// Put the buffer back where the text read so far ends, dropping the bytes
+/// readline() read ahead.
+fn text_io_wrapper_unread_pending(
+    text_io_wrapper: &PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let pending = text_io_wrapper_pending(text_io_wrapper, vm)?;
+    if !pending.is_empty() {
+        let buffer = vm.get_attribute(text_io_wrapper.clone(), "buffer")?;
+        let offset = vm.new_int(-(pending.len() as i64));
+        vm.call_method(&buffer, "seek", vec![offset, vm.new_int(1)])?;
+        vm.set_attr(text_io_wrapper, "__pending", vm.ctx.new_bytes(vec![]))?;
+    }
+    Ok(())
+}
+
+/// Decode bytes from the buffer with the file's encoding and errors, and in
+/// universal newlines mode translate "\r\n" and "\r" to "\n".
+fn text_io_wrapper_decode(
+    text_io_wrapper: &PyObjectRef,
+    bytes: Vec<u8>,
+    vm: &VirtualMachine,
+) -> PyResult<String> {
+    let encoding = vm.get_attribute(text_io_wrapper.clone(), "encoding")?;
+    let encoding = PyStringRef::try_from_object(vm, encoding)?;
+    let errors = vm.get_attribute(text_io_wrapper.clone(), "errors")?;
+    let errors = PyStringRef::try_from_object(vm, errors)?;
+    let text = PyByteInner { elements: bytes }.to_string_with_errors(
+        encoding.as_str(),
+        errors.as_str(),
         vm,
-        args,
-        required = [(text_io_wrapper, None), (buffer, None)]
-    );
+    )?;
+    Ok(match text_io_wrapper_newline(text_io_wrapper, vm)? {
+        None => text.replace("\r\n", "\n").replace('\r', "\n"),
+        Some(_) => text,
+    })
+}
 
-    vm.set_attr(text_io_wrapper, "buffer", buffer.clone())?;
-    Ok(vm.get_none())
+/// Find where the first line in `data` ends, after its line ending. Returns
+/// `None` when that can't be told without reading more.
+fn find_line_end(data: &[u8], newline: Option<&str>, eof: bool) -> Option<usize> {
+    match newline {
+        // "\r", "\n" and "\r\n" all end a line
+        None | Some("") => {
+            let index = data.iter().position(|&b| b == b'\r' || b == b'\n')?;
+            if data[index] == b'\n' {
+                Some(index + 1)
+            } else if index + 1 < data.len() {
+                Some(if data[index + 1] == b'\n' {
+                    index + 2
+                } else {
+                    index + 1
+                })
+            } else if eof {
+                Some(index + 1)
+            } else {
+                None
+            }
+        }
+        Some(newline) => data
+            .windows(newline.len())
+            .position(|window| window == newline.as_bytes())
+            .map(|index| index + newline.len()),
+    }
+}
+
+fn text_io_wrapper_readline(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let buffer = vm.get_attribute(text_io_wrapper.clone(), "buffer")?;
+    let newline = text_io_wrapper_newline(&text_io_wrapper, vm)?;
+    let newline = newline.as_ref().map(|newline| newline.as_str());
+
+    // Read a chunk at a time and keep what follows the line for later reads.
+    // The encodings text files support are all ASCII compatible, so line
+    // endings can be found before decoding.
+    let mut data = text_io_wrapper_pending(&text_io_wrapper, vm)?;
+    let mut eof = false;
+    let end = loop {
+        if let Some(end) = find_line_end(&data, newline, eof) {
+            break end;
+        }
+        if eof {
+            break data.len();
+        }
+        let chunk = vm.call_method(&buffer, "read1", vec![vm.new_int(DEFAULT_BUFFER_SIZE)])?;
+        let chunk = PyByteInner::try_from_object(vm, chunk)?.elements;
+        eof = chunk.is_empty();
+        data.extend(chunk);
+    };
+    let rest = data.split_off(end);
+    vm.set_attr(&text_io_wrapper, "__pending", vm.ctx.new_bytes(rest))?;
+
+    let line = text_io_wrapper_decode(&text_io_wrapper, data, vm)?;
+    Ok(vm.new_str(line))
+}
+
+fn text_io_wrapper_flush(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let buffer = vm.get_attribute(text_io_wrapper, "buffer")?;
+    vm.call_method(&buffer, "flush", vec![])
+}
+
+fn text_io_wrapper_seek(
+    text_io_wrapper: PyObjectRef,
+    offset: PyObjectRef,
+    how: OptionalArg<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    text_io_wrapper_unread_pending(&text_io_wrapper, vm)?;
+    let buffer = vm.get_attribute(text_io_wrapper, "buffer")?;
+    let mut args = vec![offset];
+    args.extend(how.into_option());
+    vm.call_method(&buffer, "seek", args)
+}
+
+fn text_io_wrapper_tell(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let buffer = vm.get_attribute(text_io_wrapper.clone(), "buffer")?;
+    let position = vm.call_method(&buffer, "tell", vec![])?;
+    let pending = text_io_wrapper_pending(&text_io_wrapper, vm)?;
+    vm._sub(position, vm.new_int(pending.len()))
+}
+
+fn text_io_wrapper_close(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+    close_wrapped(text_io_wrapper, "buffer", vm)
+}
+
+fn text_io_wrapper_closed(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let buffer = vm.get_attribute(text_io_wrapper, "buffer")?;
+    vm.get_attribute(buffer, "closed")
+}
+
+fn text_io_wrapper_encoding(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    vm.get_attribute(text_io_wrapper, "__encoding")
+}
+
+fn text_io_wrapper_errors(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    vm.get_attribute(text_io_wrapper, "__errors")
+}
+
+// encoding and errors only change through reconfigure(). A property without
+// a setter could still be shadowed by an instance attribute, so refuse
+// assignment explicitly.
+fn text_io_wrapper_set_readonly(
+    _text_io_wrapper: PyObjectRef,
+    _value: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult {
+    Err(vm.new_attribute_error("readonly attribute".to_string()))
+}
+
+fn text_io_wrapper_fileno(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let buffer = vm.get_attribute(text_io_wrapper, "buffer")?;
+    vm.call_method(&buffer, "fileno", vec![])
+}
+
+fn text_io_wrapper_isatty(text_io_wrapper: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let buffer = vm.get_attribute(text_io_wrapper, "buffer")?;
+    vm.call_method(&buffer, "isatty", vec![])
 }
 
 fn text_io_wrapper_seekable(vm: &VirtualMachine, _args: PyFuncArgs) -> PyResult {
@@ -526,24 +1178,18 @@ fn text_io_base_read(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     }
 
     if let Ok(bytes) = vm.call_method(&raw, "read", PyFuncArgs::default()) {
-        let value = objbytes::get_value(&bytes).to_vec();
-
-        //format bytes into string
-        let rust_string = String::from_utf8(value).map_err(|e| {
-            vm.new_unicode_decode_error(format!(
-                "cannot decode byte at index: {}",
-                e.utf8_error().valid_up_to()
-            ))
-        })?;
-        Ok(vm.ctx.new_str(rust_string))
+        let mut value = text_io_wrapper_pending(text_io_base, vm)?;
+        value.extend_from_slice(&objbytes::get_value(&bytes));
+        vm.set_attr(text_io_base, "__pending", vm.ctx.new_bytes(vec![]))?;
+
+        let text = text_io_wrapper_decode(text_io_base, value, vm)?;
+        Ok(vm.ctx.new_str(text))
     } else {
         Err(vm.new_value_error("Error unpacking Bytes".to_string()))
     }
 }
 
 fn text_io_base_write(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
-    use std::str::from_utf8;
-
     arg_check!(
         vm,
         args,
@@ -558,19 +1204,32 @@ fn text_io_base_write(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
         return Err(vm.new_value_error("not writable".to_string()));
     }
 
-    let bytes = objstr::get_value(obj).into_bytes();
+    text_io_wrapper_unread_pending(text_io_base, vm)?;
 
-    let len = vm.call_method(&raw, "write", vec![vm.ctx.new_bytes(bytes.clone())])?;
-    let len = objint::get_value(&len).to_usize().ok_or_else(|| {
-        vm.new_overflow_error("int to large to convert to Rust usize".to_string())
-    })?;
+    let text = objstr::get_value(obj);
+    let newline = text_io_wrapper_newline(text_io_base, vm)?;
+    let translated = match newline.as_ref().map(|newline| newline.as_str()) {
+        None if cfg!(windows) => text.replace('\n', "\r\n"),
+        Some(newline @ "\r") | Some(newline @ "\r\n") => text.replace('\n', newline),
+        _ => text.clone(),
+    };
+    let encoding = vm.get_attribute(text_io_base.clone(), "encoding")?;
+    let encoding = PyStringRef::try_from_object(vm, encoding)?;
+    let errors = vm.get_attribute(text_io_base.clone(), "errors")?;
+    let errors = PyStringRef::try_from_object(vm, errors)?;
+    let bytes =
+        PyByteInner::from_string_with_errors(&translated, encoding.as_str(), errors.as_str(), vm)?
+            .elements;
+
+    vm.call_method(&raw, "write", vec![vm.ctx.new_bytes(bytes)])?;
+
+    let line_buffering = vm.get_attribute(text_io_base.clone(), "line_buffering")?;
+    if objbool::boolval(vm, line_buffering)? && text.contains(&['\n', '\r'][..]) {
+        vm.call_method(&raw, "flush", vec![])?;
+    }
 
     // returns the count of unicode code points written
-    let len = from_utf8(&bytes[..len])
-        .unwrap_or_else(|e| from_utf8(&bytes[..e.valid_up_to()]).unwrap())
-        .chars()
-        .count();
-    Ok(vm.ctx.new_int(len))
+    Ok(vm.ctx.new_int(text.chars().count()))
 }
 
 fn split_mode_string(mode_string: String) -> Result<(String, String), String> {
@@ -648,53 +1307,128 @@ pub fn io_open(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
         }
     };
 
+    // Options for the text layer
+    let text_kwargs: Vec<(String, PyObjectRef)> = ["encoding", "errors", "newline"]
+        .iter()
+        .filter_map(|&name| {
+            args.get_optional_kwarg(name)
+                .filter(|value| !vm.is_none(value))
+                .map(|value| (name.to_string(), value))
+        })
+        .collect();
+
     let io_module = vm.import("_io", &[], 0)?;
 
     // Construct a FileIO (subclass of RawIOBase)
     // This is subsequently consumed by a Buffered Class.
     let file_io_class = vm.get_attribute(io_module.clone(), "FileIO").unwrap();
+    let mut file_io_args = vec![file.clone(), vm.ctx.new_str(mode.clone())];
+    let mut file_io_kwarg_names = vec![];
+    if let Some(closefd) = args.get_optional_kwarg("closefd") {
+        file_io_args.push(closefd);
+        file_io_kwarg_names.push("closefd".to_string());
+    }
     let file_io_obj = vm.invoke(
         &file_io_class,
-        vec![file.clone(), vm.ctx.new_str(mode.clone())],
+        PyFuncArgs::new(file_io_args, file_io_kwarg_names),
     )?;
 
     // Create Buffered class to consume FileIO. The type of buffered class depends on
     // the operation in the mode.
     // There are 3 possible classes here, each inheriting from the RawBaseIO
-    // creating || writing || appending => BufferedWriter
-    let buffered = match mode.chars().next().unwrap() {
-        'w' => {
-            let buffered_writer_class = vm
-                .get_attribute(io_module.clone(), "BufferedWriter")
-                .unwrap();
-            vm.invoke(&buffered_writer_class, vec![file_io_obj.clone()])
-        }
-        'r' => {
-            let buffered_reader_class = vm
-                .get_attribute(io_module.clone(), "BufferedReader")
-                .unwrap();
-            vm.invoke(&buffered_reader_class, vec![file_io_obj.clone()])
-        }
-        //TODO: updating => PyBufferedRandom
-        _ => unimplemented!("'a' mode is not yet implemented"),
+    // updating => BufferedRandom
+    // reading => BufferedReader
+    // writing || appending => BufferedWriter
+    let buffered_class_name = if mode.ends_with('+') {
+        "BufferedRandom"
+    } else if mode.starts_with('r') {
+        "BufferedReader"
+    } else {
+        "BufferedWriter"
     };
+    let buffered_class = vm
+        .get_attribute(io_module.clone(), buffered_class_name)
+        .unwrap();
+    let buffered = vm.invoke(&buffered_class, vec![file_io_obj.clone()]);
 
     let io_obj = match typ.chars().next().unwrap() {
         // If the mode is text this buffer type is consumed on construction of
         // a TextIOWrapper which is subsequently returned.
         't' => {
             let text_io_wrapper_class = vm.get_attribute(io_module, "TextIOWrapper").unwrap();
-            vm.invoke(&text_io_wrapper_class, vec![buffered.unwrap()])
+            let (names, values): (Vec<_>, Vec<_>) = text_kwargs.into_iter().unzip();
+            let mut text_args = vec![buffered?];
+            text_args.extend(values);
+            vm.invoke(&text_io_wrapper_class, PyFuncArgs::new(text_args, names))
         }
         // If the mode is binary this Buffered class is returned directly at
         // this point.
         // For Buffered class construct "raw" IO class e.g. FileIO and pass this into corresponding field
-        'b' => buffered,
+        'b' => {
+            if let Some((name, _)) = text_kwargs.first() {
+                let article = if name == "newline" { "a" } else { "an" };
+                return Err(vm.new_value_error(format!(
+                    "binary mode doesn't take {} {} argument",
+                    article, name
+                )));
+            }
+            buffered
+        }
         _ => unreachable!(),
     };
     io_obj
 }
 
+#[cfg(windows)]
+fn stdio_file_numbers() -> [i64; 3] {
+    use std::os::windows::io::AsRawHandle;
+
+    [
+        std::io::stdin().as_raw_handle() as i64,
+        std::io::stdout().as_raw_handle() as i64,
+        std::io::stderr().as_raw_handle() as i64,
+    ]
+}
+
+#[cfg(not(windows))]
+fn stdio_file_numbers() -> [i64; 3] {
+    [0, 1, 2]
+}
+
+/// Set `sys.stdin`, `sys.stdout` and `sys.stderr` (and the `__stdin__` style
+/// originals) to text files wrapping the process' standard streams.
+pub fn init_stdio(vm: &VirtualMachine) -> PyResult<()> {
+    let [stdin_no, stdout_no, stderr_no] = stdio_file_numbers();
+    let open_std_stream = |file_no: i64, mode: &str, errors: &str| {
+        io_open(
+            vm,
+            PyFuncArgs::new(
+                vec![
+                    vm.new_int(file_no),
+                    vm.new_str(mode.to_string()),
+                    vm.new_str(errors.to_string()),
+                ],
+                vec!["errors".to_string()],
+            ),
+        )
+    };
+
+    // Like CPython, stderr escapes what it can't encode rather than failing.
+    let streams = vec![
+        ("stdin", open_std_stream(stdin_no, "r", "strict")?),
+        ("stdout", open_std_stream(stdout_no, "w", "strict")?),
+        (
+            "stderr",
+            open_std_stream(stderr_no, "w", "backslashreplace")?,
+        ),
+    ];
+    for (name, stream) in streams {
+        vm.set_attr(&vm.sys_module, name, stream.clone())?;
+        vm.set_attr(&vm.sys_module, format!("__{}__", name).as_str(), stream)?;
+    }
+    Ok(())
+}
+
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let ctx = &vm.ctx;
 
@@ -702,7 +1436,12 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let io_base = py_class!(ctx, "IOBase", ctx.object(), {
         "__enter__" => ctx.new_rustfunc(io_base_cm_enter),
         "__exit__" => ctx.new_rustfunc(io_base_cm_exit),
+        "close" => ctx.new_rustfunc(io_base_close),
+        "closed" => ctx.new_bool(false),
         "seekable" => ctx.new_rustfunc(io_base_seekable),
+        "isatty" => ctx.new_rustfunc(io_base_isatty),
+        "readline" => ctx.new_rustfunc(io_base_readline),
+        "readlines" => ctx.new_rustfunc(io_base_readlines),
         "flush" => ctx.new_rustfunc(io_base_flush)
     });
 
@@ -726,7 +1465,11 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "readinto" => ctx.new_rustfunc(file_io_readinto),
         "write" => ctx.new_rustfunc(file_io_write),
         "close" => ctx.new_rustfunc(file_io_close),
-        "seekable" => ctx.new_rustfunc(file_io_seekable)
+        "seekable" => ctx.new_rustfunc(file_io_seekable),
+        "seek" => ctx.new_rustfunc(file_io_seek),
+        "tell" => ctx.new_rustfunc(file_io_tell),
+        "fileno" => ctx.new_rustfunc(file_io_fileno),
+        "isatty" => ctx.new_rustfunc(file_io_isatty)
     });
 
     // BufferedIOBase Subclasses
@@ -736,7 +1479,14 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         //For more info see: https://github.com/RustPython/RustPython/issues/547
         "__init__" => ctx.new_rustfunc(buffered_io_base_init),
         "read" => ctx.new_rustfunc(buffered_reader_read),
-        "seekable" => ctx.new_rustfunc(buffered_reader_seekable)
+        "read1" => ctx.new_rustfunc(buffered_reader_read1),
+        "seekable" => ctx.new_rustfunc(buffered_reader_seekable),
+        "seek" => ctx.new_rustfunc(buffered_io_base_seek),
+        "tell" => ctx.new_rustfunc(buffered_io_base_tell),
+        "close" => ctx.new_rustfunc(buffered_io_base_close),
+        "closed" => ctx.new_property(buffered_io_base_closed),
+        "fileno" => ctx.new_rustfunc(buffered_io_base_fileno),
+        "isatty" => ctx.new_rustfunc(buffered_io_base_isatty)
     });
 
     let buffered_writer = py_class!(ctx, "BufferedWriter", buffered_io_base.clone(), {
@@ -745,13 +1495,45 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         //For more info see: https://github.com/RustPython/RustPython/issues/547
         "__init__" => ctx.new_rustfunc(buffered_io_base_init),
         "write" => ctx.new_rustfunc(buffered_writer_write),
-        "seekable" => ctx.new_rustfunc(buffered_writer_seekable)
+        "seekable" => ctx.new_rustfunc(buffered_writer_seekable),
+        "seek" => ctx.new_rustfunc(buffered_io_base_seek),
+        "tell" => ctx.new_rustfunc(buffered_io_base_tell),
+        "close" => ctx.new_rustfunc(buffered_io_base_close),
+        "closed" => ctx.new_property(buffered_io_base_closed),
+        "fileno" => ctx.new_rustfunc(buffered_io_base_fileno),
+        "isatty" => ctx.new_rustfunc(buffered_io_base_isatty)
     });
 
+    // Reads and writes, like both of the classes above.
+    let buffered_random = objtype::new(
+        ctx.type_type(),
+        "BufferedRandom",
+        vec![buffered_reader.clone(), buffered_writer.clone()],
+        PyAttributes::new(),
+    )
+    .unwrap();
+
     //TextIOBase Subclass
     let text_io_wrapper = py_class!(ctx, "TextIOWrapper", text_io_base.clone(), {
         "__init__" => ctx.new_rustfunc(text_io_wrapper_init),
-        "seekable" => ctx.new_rustfunc(text_io_wrapper_seekable)
+        "seekable" => ctx.new_rustfunc(text_io_wrapper_seekable),
+        "readline" => ctx.new_rustfunc(text_io_wrapper_readline),
+        "reconfigure" => ctx.new_rustfunc(text_io_wrapper_reconfigure),
+        "flush" => ctx.new_rustfunc(text_io_wrapper_flush),
+        "seek" => ctx.new_rustfunc(text_io_wrapper_seek),
+        "tell" => ctx.new_rustfunc(text_io_wrapper_tell),
+        "close" => ctx.new_rustfunc(text_io_wrapper_close),
+        "closed" => ctx.new_property(text_io_wrapper_closed),
+        "encoding" => PropertyBuilder::new(ctx)
+            .add_getter(text_io_wrapper_encoding)
+            .add_setter(text_io_wrapper_set_readonly)
+            .create(),
+        "errors" => PropertyBuilder::new(ctx)
+            .add_getter(text_io_wrapper_errors)
+            .add_setter(text_io_wrapper_set_readonly)
+            .create(),
+        "fileno" => ctx.new_rustfunc(text_io_wrapper_fileno),
+        "isatty" => ctx.new_rustfunc(text_io_wrapper_isatty)
     });
 
     //StringIO: in-memory text
@@ -759,9 +1541,14 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "__new__" => ctx.new_rustfunc(string_io_new),
         "seek" => ctx.new_rustfunc(PyStringIORef::seek),
         "seekable" => ctx.new_rustfunc(PyStringIORef::seekable),
+        "tell" => ctx.new_rustfunc(PyStringIORef::tell),
         "read" => ctx.new_rustfunc(PyStringIORef::read),
+        "readline" => ctx.new_rustfunc(PyStringIORef::readline),
         "write" => ctx.new_rustfunc(PyStringIORef::write),
-        "getvalue" => ctx.new_rustfunc(PyStringIORef::getvalue)
+        "truncate" => ctx.new_rustfunc(PyStringIORef::truncate),
+        "getvalue" => ctx.new_rustfunc(PyStringIORef::getvalue),
+        "close" => ctx.new_rustfunc(PyStringIORef::close),
+        "closed" => ctx.new_property(PyStringIORef::closed)
     });
 
     //BytesIO: in-memory bytes
@@ -769,10 +1556,15 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "__new__" => ctx.new_rustfunc(bytes_io_new),
         "read" => ctx.new_rustfunc(PyBytesIORef::read),
         "read1" => ctx.new_rustfunc(PyBytesIORef::read),
+        "readline" => ctx.new_rustfunc(PyBytesIORef::readline),
         "seek" => ctx.new_rustfunc(PyBytesIORef::seek),
         "seekable" => ctx.new_rustfunc(PyBytesIORef::seekable),
+        "tell" => ctx.new_rustfunc(PyBytesIORef::tell),
         "write" => ctx.new_rustfunc(PyBytesIORef::write),
-        "getvalue" => ctx.new_rustfunc(PyBytesIORef::getvalue)
+        "truncate" => ctx.new_rustfunc(PyBytesIORef::truncate),
+        "getvalue" => ctx.new_rustfunc(PyBytesIORef::getvalue),
+        "close" => ctx.new_rustfunc(PyBytesIORef::close),
+        "closed" => ctx.new_property(PyBytesIORef::closed)
     });
 
     py_module!(vm, "_io", {
@@ -784,10 +1576,11 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "FileIO" => file_io,
         "BufferedReader" => buffered_reader,
         "BufferedWriter" => buffered_writer,
+        "BufferedRandom" => buffered_random,
         "TextIOWrapper" => text_io_wrapper,
         "StringIO" => string_io,
         "BytesIO" => bytes_io,
-        "DEFAULT_BUFFER_SIZE" => ctx.new_int(8 * 1024),
+        "DEFAULT_BUFFER_SIZE" => ctx.new_int(DEFAULT_BUFFER_SIZE),
     })
 }
 
@@ -896,10 +1689,23 @@ mod tests {
             cursor: Cursor::new(data.clone()),
         };
 
-        assert_eq!(buffered.seek(count.clone()).unwrap(), count);
+        assert_eq!(buffered.seek(SeekFrom::Start(count)).unwrap(), count);
         assert_eq!(buffered.read(count.clone() as i64).unwrap(), vec![3, 4]);
     }
 
+    #[test]
+    fn test_buffered_seek_relative() {
+        let data = vec![1, 2, 3, 4];
+        let mut buffered = BufferedIO {
+            cursor: Cursor::new(data.clone()),
+        };
+
+        assert_eq!(buffered.seek(SeekFrom::End(-1)).unwrap(), 3);
+        assert_eq!(buffered.seek(SeekFrom::Current(-2)).unwrap(), 1);
+        assert_eq!(buffered.read(0).unwrap(), Vec::<u8>::new());
+        assert_eq!(buffered.seek(SeekFrom::End(-10)).unwrap(), 0);
+    }
+
     #[test]
     fn test_buffered_value() {
         let data = vec![1, 2, 3, 4];
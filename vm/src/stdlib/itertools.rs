@@ -1,6 +1,8 @@
 use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
-use std::ops::{AddAssign, SubAssign};
+use std::fmt;
+use std::ops::SubAssign;
+use std::rc::Rc;
 
 use num_bigint::BigInt;
 use num_traits::ToPrimitive;
@@ -8,18 +10,20 @@ use num_traits::ToPrimitive;
 use crate::function::{OptionalArg, PyFuncArgs};
 use crate::obj::objbool;
 use crate::obj::objint;
-use crate::obj::objint::{PyInt, PyIntRef};
-use crate::obj::objiter::{call_next, get_iter, new_stop_iteration};
+use crate::obj::objint::PyIntRef;
+use crate::obj::objiter::{call_next, get_iter, get_next_object, new_stop_iteration};
 use crate::obj::objtype;
 use crate::obj::objtype::PyClassRef;
-use crate::pyobject::{IdProtocol, PyCallable, PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue};
+use crate::pyobject::{
+    IdProtocol, PyCallable, PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue, TypeProtocol,
+};
 use crate::vm::VirtualMachine;
 
 #[pyclass(name = "chain")]
 #[derive(Debug)]
 struct PyItertoolsChain {
-    iterables: Vec<PyObjectRef>,
-    cur: RefCell<(usize, Option<PyObjectRef>)>,
+    source: PyObjectRef,
+    active: RefCell<Option<PyObjectRef>>,
 }
 
 impl PyValue for PyItertoolsChain {
@@ -32,39 +36,50 @@ impl PyValue for PyItertoolsChain {
 impl PyItertoolsChain {
     #[pymethod(name = "__new__")]
     #[allow(clippy::new_ret_no_self)]
-    fn new(_cls: PyClassRef, args: PyFuncArgs, vm: &VirtualMachine) -> PyResult {
+    fn new(cls: PyClassRef, args: PyFuncArgs, vm: &VirtualMachine) -> PyResult {
+        let source = get_iter(vm, &vm.ctx.new_tuple(args.args))?;
         Ok(PyItertoolsChain {
-            iterables: args.args,
-            cur: RefCell::new((0, None)),
+            source,
+            active: RefCell::new(None),
         }
-        .into_ref(vm)
+        .into_ref_with_type(vm, cls)?
         .into_object())
     }
 
+    #[pyclassmethod(name = "from_iterable")]
+    fn from_iterable(
+        cls: PyClassRef,
+        iterable: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyRef<Self>> {
+        PyItertoolsChain {
+            source: get_iter(vm, &iterable)?,
+            active: RefCell::new(None),
+        }
+        .into_ref_with_type(vm, cls)
+    }
+
     #[pymethod(name = "__next__")]
     fn next(&self, vm: &VirtualMachine) -> PyResult {
-        let (ref mut cur_idx, ref mut cur_iter) = *self.cur.borrow_mut();
-        while *cur_idx < self.iterables.len() {
-            if cur_iter.is_none() {
-                *cur_iter = Some(get_iter(vm, &self.iterables[*cur_idx])?);
-            }
-
-            // can't be directly inside the 'match' clause, otherwise the borrows collide.
-            let obj = call_next(vm, cur_iter.as_ref().unwrap());
-            match obj {
-                Ok(ok) => return Ok(ok),
-                Err(err) => {
-                    if objtype::isinstance(&err, &vm.ctx.exceptions.stop_iteration) {
-                        *cur_idx += 1;
-                        *cur_iter = None;
-                    } else {
-                        return Err(err);
+        loop {
+            let active = self.active.borrow().clone();
+            let active = match active {
+                Some(active) => active,
+                None => match get_next_object(vm, &self.source)? {
+                    Some(iterable) => {
+                        let iter = get_iter(vm, &iterable)?;
+                        *self.active.borrow_mut() = Some(iter.clone());
+                        iter
                     }
-                }
+                    None => return Err(new_stop_iteration(vm)),
+                },
+            };
+
+            match get_next_object(vm, &active)? {
+                Some(obj) => return Ok(obj),
+                None => *self.active.borrow_mut() = None,
             }
         }
-
-        Err(new_stop_iteration(vm))
     }
 
     #[pymethod(name = "__iter__")]
@@ -76,8 +91,8 @@ impl PyItertoolsChain {
 #[pyclass]
 #[derive(Debug)]
 struct PyItertoolsCount {
-    cur: RefCell<BigInt>,
-    step: BigInt,
+    cur: RefCell<PyObjectRef>,
+    step: PyObjectRef,
 }
 
 impl PyValue for PyItertoolsCount {
@@ -86,38 +101,116 @@ impl PyValue for PyItertoolsCount {
     }
 }
 
+#[derive(FromArgs)]
+struct CountArgs {
+    #[pyarg(positional_or_keyword, optional = true)]
+    start: OptionalArg<PyObjectRef>,
+    #[pyarg(positional_or_keyword, optional = true)]
+    step: OptionalArg<PyObjectRef>,
+}
+
+fn is_number(obj: &PyObjectRef) -> bool {
+    let cls = obj.class();
+    ["__index__", "__int__", "__float__", "__complex__"]
+        .iter()
+        .any(|name| objtype::class_has_attr(&cls, name))
+}
+
 #[pyimpl]
 impl PyItertoolsCount {
     #[pymethod(name = "__new__")]
     #[allow(clippy::new_ret_no_self)]
-    fn new(
-        _cls: PyClassRef,
-        start: OptionalArg<PyIntRef>,
-        step: OptionalArg<PyIntRef>,
-        vm: &VirtualMachine,
-    ) -> PyResult {
-        let start = match start.into_option() {
-            Some(int) => int.as_bigint().clone(),
-            None => BigInt::from(0),
-        };
-        let step = match step.into_option() {
-            Some(int) => int.as_bigint().clone(),
-            None => BigInt::from(1),
-        };
+    fn new(cls: PyClassRef, args: CountArgs, vm: &VirtualMachine) -> PyResult {
+        let start = args.start.unwrap_or_else(|| vm.new_int(0));
+        let step = args.step.unwrap_or_else(|| vm.new_int(1));
+        if !is_number(&start) || !is_number(&step) {
+            return Err(vm.new_type_error("a number is required".to_string()));
+        }
 
         Ok(PyItertoolsCount {
             cur: RefCell::new(start),
             step,
         }
-        .into_ref(vm)
+        .into_ref_with_type(vm, cls)?
         .into_object())
     }
 
     #[pymethod(name = "__next__")]
-    fn next(&self, _vm: &VirtualMachine) -> PyResult<PyInt> {
+    fn next(&self, vm: &VirtualMachine) -> PyResult {
         let result = self.cur.borrow().clone();
-        AddAssign::add_assign(&mut self.cur.borrow_mut() as &mut BigInt, &self.step);
-        Ok(PyInt::new(result))
+        *self.cur.borrow_mut() = vm._add(result.clone(), self.step.clone())?;
+        Ok(result)
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        let cur = vm.to_repr(&self.cur.borrow())?;
+        let step_is_one = objtype::isinstance(&self.step, &vm.ctx.int_type())
+            && *objint::get_value(&self.step) == BigInt::from(1);
+        if step_is_one {
+            Ok(format!("count({})", cur.as_str()))
+        } else {
+            let step = vm.to_repr(&self.step)?;
+            Ok(format!("count({}, {})", cur.as_str(), step.as_str()))
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug)]
+struct PyItertoolsCycle {
+    iter: RefCell<Option<PyObjectRef>>,
+    saved: RefCell<Vec<PyObjectRef>>,
+    index: Cell<usize>,
+}
+
+impl PyValue for PyItertoolsCycle {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("itertools", "cycle")
+    }
+}
+
+#[pyimpl]
+impl PyItertoolsCycle {
+    #[pymethod(name = "__new__")]
+    #[allow(clippy::new_ret_no_self)]
+    fn new(cls: PyClassRef, iterable: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let iter = get_iter(vm, &iterable)?;
+
+        Ok(PyItertoolsCycle {
+            iter: RefCell::new(Some(iter)),
+            saved: RefCell::new(Vec::new()),
+            index: Cell::new(0),
+        }
+        .into_ref_with_type(vm, cls)?
+        .into_object())
+    }
+
+    #[pymethod(name = "__next__")]
+    fn next(&self, vm: &VirtualMachine) -> PyResult {
+        // The first pass reads from the iterable and remembers each element,
+        // later passes replay the saved elements.
+        let iter = self.iter.borrow().clone();
+        if let Some(iter) = iter {
+            if let Some(obj) = get_next_object(vm, &iter)? {
+                self.saved.borrow_mut().push(obj.clone());
+                return Ok(obj);
+            }
+            *self.iter.borrow_mut() = None;
+        }
+
+        let saved = self.saved.borrow();
+        if saved.is_empty() {
+            return Err(new_stop_iteration(vm));
+        }
+        let index = self.index.get();
+        self.index.set((index + 1) % saved.len());
+        Ok(saved[index].clone())
     }
 
     #[pymethod(name = "__iter__")]
@@ -399,7 +492,7 @@ impl PyItertoolsIslice {
                     PyObjectRef,
                     PyObjectRef,
                     PyObjectRef,
-                    PyObjectRef,
+                    OptionalArg<PyObjectRef>,
                 ) = args.bind(vm)?;
 
                 let start = if !start.is(&vm.get_none()) {
@@ -412,14 +505,18 @@ impl PyItertoolsIslice {
                     0usize
                 };
 
-                let step = if !step.is(&vm.get_none()) {
-                    pyobject_to_opt_usize(step, &vm).ok_or_else(|| {
-                        vm.new_value_error(
-                            "Step for islice() must be a positive integer or None.".to_string(),
-                        )
-                    })?
-                } else {
-                    1usize
+                let step = match step {
+                    OptionalArg::Present(step) if !step.is(&vm.get_none()) => {
+                        pyobject_to_opt_usize(step, &vm)
+                            .filter(|step| *step > 0)
+                            .ok_or_else(|| {
+                                vm.new_value_error(
+                                    "Step for islice() must be a positive integer or None."
+                                        .to_string(),
+                                )
+                            })?
+                    }
+                    _ => 1usize,
                 };
 
                 (iter, start, stop, step)
@@ -466,9 +563,13 @@ impl PyItertoolsIslice {
         let obj = call_next(vm, &self.iterable)?;
         *self.cur.borrow_mut() += 1;
 
-        // TODO is this overflow check required? attempts to copy CPython.
-        let (next, ovf) = (*self.next.borrow()).overflowing_add(self.step);
-        *self.next.borrow_mut() = if ovf { self.stop.unwrap() } else { next };
+        // Never skip past `stop`, so that no more of the iterable is consumed than necessary.
+        let next = (*self.next.borrow()).checked_add(self.step);
+        *self.next.borrow_mut() = match (next, self.stop) {
+            (Some(next), Some(stop)) => next.min(stop),
+            (Some(next), None) => next,
+            (None, stop) => stop.unwrap_or(std::usize::MAX),
+        };
 
         Ok(obj)
     }
@@ -537,6 +638,241 @@ impl PyItertoolsFilterFalse {
     }
 }
 
+#[derive(Debug, Default)]
+struct GroupByState {
+    /// The key of the last element read from the iterator.
+    current_key: Option<PyObjectRef>,
+    /// That element, until a group iterator hands it out.
+    current_value: Option<PyObjectRef>,
+    /// The key of the group started last.
+    target_key: Option<PyObjectRef>,
+    /// Counts the groups started, so that older group iterators know they're done.
+    group_id: usize,
+}
+
+#[pyclass(name = "groupby")]
+#[derive(Debug)]
+struct PyItertoolsGroupBy {
+    iterator: PyObjectRef,
+    key_func: Option<PyObjectRef>,
+    state: RefCell<GroupByState>,
+}
+
+impl PyValue for PyItertoolsGroupBy {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("itertools", "groupby")
+    }
+}
+
+#[derive(FromArgs)]
+struct GroupByArgs {
+    #[pyarg(positional_or_keyword)]
+    iterable: PyObjectRef,
+    #[pyarg(positional_or_keyword, default = "None")]
+    key: Option<PyObjectRef>,
+}
+
+#[pyimpl]
+impl PyItertoolsGroupBy {
+    #[pymethod(name = "__new__")]
+    fn new(cls: PyClassRef, args: GroupByArgs, vm: &VirtualMachine) -> PyResult<PyRef<Self>> {
+        PyItertoolsGroupBy {
+            iterator: get_iter(vm, &args.iterable)?,
+            key_func: args.key,
+            state: RefCell::new(GroupByState::default()),
+        }
+        .into_ref_with_type(vm, cls)
+    }
+
+    /// Read the next element and its key.
+    fn advance(&self, vm: &VirtualMachine) -> PyResult<()> {
+        let value = call_next(vm, &self.iterator)?;
+        let key = match self.key_func {
+            Some(ref key_func) => vm.invoke(key_func, vec![value.clone()])?,
+            None => value.clone(),
+        };
+        let mut state = self.state.borrow_mut();
+        state.current_key = Some(key);
+        state.current_value = Some(value);
+        Ok(())
+    }
+
+    /// Whether the last element read belongs to the group started last.
+    fn in_target_group(&self, vm: &VirtualMachine) -> PyResult<bool> {
+        let (current, target) = {
+            let state = self.state.borrow();
+            (state.current_key.clone(), state.target_key.clone())
+        };
+        match (current, target) {
+            (Some(current), Some(target)) => {
+                Ok(current.is(&target) || objbool::boolval(vm, vm._eq(target, current)?)?)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    #[pymethod(name = "__next__")]
+    fn next(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult {
+        // Skip whatever the previous group didn't consume.
+        while zelf.state.borrow().current_key.is_none() || zelf.in_target_group(vm)? {
+            zelf.advance(vm)?;
+        }
+        let (key, group_id) = {
+            let mut state = zelf.state.borrow_mut();
+            state.target_key = state.current_key.clone();
+            state.group_id += 1;
+            (state.current_key.clone().unwrap(), state.group_id)
+        };
+        let grouper = PyItertoolsGrouper {
+            groupby: zelf,
+            group_id,
+        }
+        .into_ref(vm);
+        Ok(vm.ctx.new_tuple(vec![key, grouper.into_object()]))
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+}
+
+/// The iterator over one group of a `groupby`, sharing its underlying iterator.
+#[pyclass(name = "_grouper")]
+#[derive(Debug)]
+struct PyItertoolsGrouper {
+    groupby: PyRef<PyItertoolsGroupBy>,
+    group_id: usize,
+}
+
+impl PyValue for PyItertoolsGrouper {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("itertools", "_grouper")
+    }
+}
+
+#[pyimpl]
+impl PyItertoolsGrouper {
+    #[pymethod(name = "__next__")]
+    fn next(&self, vm: &VirtualMachine) -> PyResult {
+        let groupby = &self.groupby;
+        if groupby.state.borrow().group_id != self.group_id {
+            return Err(new_stop_iteration(vm));
+        }
+        if groupby.state.borrow().current_value.is_none() {
+            groupby.advance(vm)?;
+        }
+        if !groupby.in_target_group(vm)? {
+            return Err(new_stop_iteration(vm));
+        }
+        Ok(groupby.state.borrow_mut().current_value.take().unwrap())
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+}
+
+/// One element of the buffer shared by the iterators returned from `tee()`.
+///
+/// Each iterator holds the link it will read next; links are freed once the
+/// slowest iterator has moved past them.
+#[derive(Default)]
+struct TeeLink {
+    next: RefCell<Option<(PyObjectRef, Rc<TeeLink>)>>,
+}
+
+impl Drop for TeeLink {
+    fn drop(&mut self) {
+        // Unlink iteratively, so dropping a long buffer doesn't overflow the stack.
+        let mut next = self.next.borrow_mut().take();
+        while let Some((_, link)) = next {
+            next = match Rc::try_unwrap(link) {
+                Ok(link) => link.next.borrow_mut().take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+#[pyclass(name = "_tee")]
+struct PyItertoolsTee {
+    iterator: PyObjectRef,
+    link: RefCell<Rc<TeeLink>>,
+}
+
+impl fmt::Debug for PyItertoolsTee {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("_tee")
+    }
+}
+
+impl PyValue for PyItertoolsTee {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("itertools", "_tee")
+    }
+}
+
+#[pyimpl]
+impl PyItertoolsTee {
+    fn from_iter(iterable: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyRef<Self>> {
+        let iterator = get_iter(vm, &iterable)?;
+        if let Ok(tee) = iterator.clone().downcast::<PyItertoolsTee>() {
+            return Ok(tee.copy(vm));
+        }
+        Ok(PyItertoolsTee {
+            iterator,
+            link: RefCell::new(Rc::new(TeeLink::default())),
+        }
+        .into_ref(vm))
+    }
+
+    #[pymethod(name = "__copy__")]
+    fn copy(&self, vm: &VirtualMachine) -> PyRef<Self> {
+        PyItertoolsTee {
+            iterator: self.iterator.clone(),
+            link: RefCell::new(self.link.borrow().clone()),
+        }
+        .into_ref(vm)
+    }
+
+    #[pymethod(name = "__next__")]
+    fn next(&self, vm: &VirtualMachine) -> PyResult {
+        let link = self.link.borrow().clone();
+        if link.next.borrow().is_none() {
+            // This iterator is the furthest ahead, so read a new element for everyone.
+            let obj = call_next(vm, &self.iterator)?;
+            *link.next.borrow_mut() = Some((obj, Rc::new(TeeLink::default())));
+        }
+        let (obj, next) = link.next.borrow().clone().unwrap();
+        *self.link.borrow_mut() = next;
+        Ok(obj)
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+}
+
+fn itertools_tee(iterable: PyObjectRef, n: OptionalArg<isize>, vm: &VirtualMachine) -> PyResult {
+    let n = n.unwrap_or(2);
+    if n < 0 {
+        return Err(vm.new_value_error("n must be >= 0".to_string()));
+    }
+
+    let mut tees = Vec::with_capacity(n as usize);
+    if n > 0 {
+        let first = PyItertoolsTee::from_iter(iterable, vm)?;
+        for _ in 1..n {
+            tees.push(first.copy(vm).into_object());
+        }
+        tees.insert(0, first.into_object());
+    }
+    Ok(vm.ctx.new_tuple(tees))
+}
+
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let ctx = &vm.ctx;
 
@@ -545,6 +881,9 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let count = ctx.new_class("count", ctx.object());
     PyItertoolsCount::extend_class(ctx, &count);
 
+    let cycle = ctx.new_class("cycle", ctx.object());
+    PyItertoolsCycle::extend_class(ctx, &cycle);
+
     let dropwhile = ctx.new_class("dropwhile", ctx.object());
     PyItertoolsDropwhile::extend_class(ctx, &dropwhile);
 
@@ -561,14 +900,24 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let filterfalse = ctx.new_class("filterfalse", ctx.object());
     PyItertoolsFilterFalse::extend_class(ctx, &filterfalse);
 
+    let tee = PyItertoolsTee::make_class(ctx);
+
+    let groupby = PyItertoolsGroupBy::make_class(ctx);
+    let grouper = PyItertoolsGrouper::make_class(ctx);
+
     py_module!(vm, "itertools", {
         "chain" => chain,
         "count" => count,
+        "cycle" => cycle,
         "dropwhile" => dropwhile,
         "repeat" => repeat,
         "starmap" => starmap,
         "takewhile" => takewhile,
         "islice" => islice,
         "filterfalse" => filterfalse,
+        "_tee" => tee,
+        "groupby" => groupby,
+        "_grouper" => grouper,
+        "tee" => ctx.new_rustfunc(itertools_tee),
     })
 }
@@ -3,7 +3,6 @@
  *
  */
 
-use statrs::function::erf::{erf, erfc};
 use statrs::function::gamma::{gamma, ln_gamma};
 
 use num_bigint::BigInt;
@@ -13,8 +12,8 @@ use num_traits::{One, Zero};
 use crate::function::PyFuncArgs;
 use crate::obj::objfloat::PyFloatRef;
 use crate::obj::objint::PyIntRef;
-use crate::obj::{objfloat, objint, objtype};
-use crate::pyobject::{PyObjectRef, PyResult, TypeProtocol};
+use crate::obj::{objfloat, objtype};
+use crate::pyobject::{PyIterable, PyObjectRef, PyResult, TypeProtocol};
 use crate::vm::VirtualMachine;
 
 // Helper macro:
@@ -130,6 +129,77 @@ make_math_func!(math_sinh, sinh);
 make_math_func!(math_tanh, tanh);
 
 // Special functions:
+
+// erf and erfc as CPython computes them where libm lacks them: a power
+// series for small arguments and a continued fraction for erfc of larger
+// ones, both good to a few ulps.
+const ERF_SERIES_CUTOFF: f64 = 1.5;
+const ERF_SERIES_TERMS: usize = 25;
+const ERFC_CONTFRAC_CUTOFF: f64 = 30.0;
+const ERFC_CONTFRAC_TERMS: usize = 50;
+const SQRT_PI: f64 = 1.772_453_850_905_516;
+
+fn erf_series(x: f64) -> f64 {
+    let x2 = x * x;
+    let mut acc = 0.0;
+    let mut fk = ERF_SERIES_TERMS as f64 + 0.5;
+    for _ in 0..ERF_SERIES_TERMS {
+        acc = 2.0 + x2 * acc / fk;
+        fk -= 1.0;
+    }
+    acc * x * (-x2).exp() / SQRT_PI
+}
+
+/// erfc(x) for x >= ERF_SERIES_CUTOFF.
+fn erfc_contfrac(x: f64) -> f64 {
+    if x >= ERFC_CONTFRAC_CUTOFF {
+        return 0.0;
+    }
+    let x2 = x * x;
+    let mut a = 0.0;
+    let mut da = 0.5;
+    let (mut p, mut p_last) = (1.0, 0.0);
+    let (mut q, mut q_last) = (da + x2, 1.0);
+    for _ in 0..ERFC_CONTFRAC_TERMS {
+        a += da;
+        da += 2.0;
+        let b = da + x2;
+        let p_next = b * p - a * p_last;
+        p_last = p;
+        p = p_next;
+        let q_next = b * q - a * q_last;
+        q_last = q;
+        q = q_next;
+    }
+    p / q * x * (-x2).exp() / SQRT_PI
+}
+
+fn erf(x: f64) -> f64 {
+    if x.abs() < ERF_SERIES_CUTOFF {
+        erf_series(x)
+    } else {
+        let cf = erfc_contfrac(x.abs());
+        if x > 0.0 {
+            1.0 - cf
+        } else {
+            cf - 1.0
+        }
+    }
+}
+
+fn erfc(x: f64) -> f64 {
+    if x.abs() < ERF_SERIES_CUTOFF {
+        1.0 - erf_series(x)
+    } else {
+        let cf = erfc_contfrac(x.abs());
+        if x > 0.0 {
+            cf
+        } else {
+            2.0 - cf
+        }
+    }
+}
+
 fn math_erf(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(vm, args, required = [(value, None)]);
     let x = objfloat::make_float(vm, value)?;
@@ -196,21 +266,21 @@ fn math_trunc(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
 
 fn math_ceil(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(vm, args, required = [(value, None)]);
-    if objtype::isinstance(value, &vm.ctx.float_type()) {
-        let v = objfloat::get_value(value);
-        Ok(vm.ctx.new_float(v.ceil()))
-    } else {
+    if objtype::class_has_attr(&value.class(), "__ceil__") {
         try_magic_method("__ceil__", vm, value)
+    } else {
+        let v = objfloat::make_float(vm, value)?;
+        Ok(vm.ctx.new_int(objfloat::try_to_bigint(v.ceil(), vm)?))
     }
 }
 
 fn math_floor(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(vm, args, required = [(value, None)]);
-    if objtype::isinstance(value, &vm.ctx.float_type()) {
-        let v = objfloat::get_value(value);
-        Ok(vm.ctx.new_float(v.floor()))
-    } else {
+    if objtype::class_has_attr(&value.class(), "__floor__") {
         try_magic_method("__floor__", vm, value)
+    } else {
+        let v = objfloat::make_float(vm, value)?;
+        Ok(vm.ctx.new_int(objfloat::try_to_bigint(v.floor(), vm)?))
     }
 }
 
@@ -242,9 +312,9 @@ fn math_gcd(a: PyIntRef, b: PyIntRef, vm: &VirtualMachine) -> PyResult {
     Ok(vm.new_int(a.as_bigint().gcd(b.as_bigint())))
 }
 
-fn math_factorial(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
-    arg_check!(vm, args, required = [(value, None)]);
-    let value = objint::get_value(value);
+fn math_factorial(value: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let value = vm.to_index(&value)?;
+    let value = value.as_bigint();
     if *value < BigInt::zero() {
         return Err(vm.new_value_error("factorial() not defined for negative values".to_string()));
     } else if *value <= BigInt::one() {
@@ -254,6 +324,80 @@ fn math_factorial(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     Ok(vm.ctx.new_int(ret))
 }
 
+/// Sum floats without accumulating rounding errors, with the algorithm of
+/// CPython: `partials` holds non-overlapping partial sums, smallest first.
+fn math_fsum(iterable: PyIterable, vm: &VirtualMachine) -> PyResult<f64> {
+    let mut partials: Vec<f64> = Vec::new();
+    let mut special_sum = 0.0;
+    let mut inf_sum = 0.0;
+
+    for obj in iterable.iter(vm)? {
+        let mut x = objfloat::make_float(vm, &obj?)?;
+        let original = x;
+        let mut i = 0;
+        for j in 0..partials.len() {
+            let mut y = partials[j];
+            if x.abs() < y.abs() {
+                std::mem::swap(&mut x, &mut y);
+            }
+            let hi = x + y;
+            let lo = y - (hi - x);
+            if lo != 0.0 {
+                partials[i] = lo;
+                i += 1;
+            }
+            x = hi;
+        }
+        partials.truncate(i);
+
+        if x != 0.0 {
+            if x.is_finite() {
+                partials.push(x);
+            } else {
+                // Either an infinite or nan input, or an intermediate overflow.
+                if original.is_finite() {
+                    return Err(vm.new_overflow_error("intermediate overflow in fsum".to_string()));
+                }
+                if original.is_infinite() {
+                    inf_sum += original;
+                }
+                special_sum += original;
+                partials.clear();
+            }
+        }
+    }
+
+    if special_sum != 0.0 {
+        if inf_sum.is_nan() {
+            return Err(vm.new_value_error("-inf + inf in fsum".to_string()));
+        }
+        return Ok(special_sum);
+    }
+
+    // Add the partials from the largest down, until the sum becomes inexact.
+    let mut hi = 0.0;
+    let mut lo = 0.0;
+    while let Some(y) = partials.pop() {
+        let x = hi;
+        hi = x + y;
+        lo = y - (hi - x);
+        if lo != 0.0 {
+            break;
+        }
+    }
+    // Make half-even rounding work across multiple partials.
+    if let Some(&next) = partials.last() {
+        if (lo < 0.0 && next < 0.0) || (lo > 0.0 && next > 0.0) {
+            let y = lo * 2.0;
+            let x = hi + y;
+            if y == x - hi {
+                hi = x;
+            }
+        }
+    }
+    Ok(hi)
+}
+
 fn math_modf(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(vm, args, required = [(value, None)]);
     let x = objfloat::make_float(vm, value)?;
@@ -324,6 +468,7 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
 
         // Gcd function
         "gcd" => ctx.new_rustfunc(math_gcd),
+        "fsum" => ctx.new_rustfunc(math_fsum),
 
         // Factorial function
         "factorial" => ctx.new_rustfunc(math_factorial),
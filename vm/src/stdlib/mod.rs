@@ -1,11 +1,15 @@
-mod array;
+mod abc;
+pub mod array;
 #[cfg(feature = "rustpython-parser")]
 mod ast;
+pub mod atexit;
 mod binascii;
 mod codecs;
 mod collections;
+mod contextvars;
 mod dis;
 mod errno;
+mod fnmatch;
 mod functools;
 mod hashlib;
 mod imp;
@@ -15,10 +19,12 @@ mod json;
 mod keyword;
 mod marshal;
 mod math;
+mod operator;
 mod platform;
 mod pystruct;
 mod random;
 mod re;
+mod secrets;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod socket;
 mod string;
@@ -28,13 +34,16 @@ mod thread;
 mod time_module;
 #[cfg(feature = "rustpython-parser")]
 mod tokenize;
-mod unicodedata;
+pub mod tracemalloc;
+pub mod unicodedata;
 mod warnings;
 mod weakref;
 use std::collections::HashMap;
 
 use crate::vm::VirtualMachine;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod faulthandler;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod io;
 #[cfg(not(target_arch = "wasm32"))]
@@ -44,6 +53,10 @@ mod pwd;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod signal;
 #[cfg(not(target_arch = "wasm32"))]
+mod sqlite3;
+#[cfg(not(target_arch = "wasm32"))]
+mod ssl;
+#[cfg(not(target_arch = "wasm32"))]
 mod subprocess;
 #[cfg(not(target_arch = "wasm32"))]
 mod zlib;
@@ -55,25 +68,32 @@ pub type StdlibInitFunc = Box<dyn Fn(&VirtualMachine) -> PyObjectRef>;
 pub fn get_module_inits() -> HashMap<String, StdlibInitFunc> {
     #[allow(unused_mut)]
     let mut modules = hashmap! {
-        "array".to_string() => Box::new(array::make_module) as StdlibInitFunc,
+        "_abc".to_string() => Box::new(abc::make_module) as StdlibInitFunc,
+        "array".to_string() => Box::new(array::make_module),
+        "atexit".to_string() => Box::new(atexit::make_module),
         "binascii".to_string() => Box::new(binascii::make_module),
         "dis".to_string() => Box::new(dis::make_module),
         "_codecs".to_string() => Box::new(codecs::make_module),
         "_collections".to_string() => Box::new(collections::make_module),
+        "contextvars".to_string() => Box::new(contextvars::make_module),
         "_functools".to_string() => Box::new(functools::make_module),
         "errno".to_string() => Box::new(errno::make_module),
+        "fnmatch".to_string() => Box::new(fnmatch::make_module),
         "hashlib".to_string() => Box::new(hashlib::make_module),
         "itertools".to_string() => Box::new(itertools::make_module),
         "json".to_string() => Box::new(json::make_module),
         "marshal".to_string() => Box::new(marshal::make_module),
         "math".to_string() => Box::new(math::make_module),
+        "_operator".to_string() => Box::new(operator::make_module),
         "platform".to_string() => Box::new(platform::make_module),
         "re".to_string() => Box::new(re::make_module),
         "random".to_string() => Box::new(random::make_module),
+        "secrets".to_string() => Box::new(secrets::make_module),
         "_string".to_string() => Box::new(string::make_module),
         "struct".to_string() => Box::new(pystruct::make_module),
         "_thread".to_string() => Box::new(thread::make_module),
         "time".to_string() => Box::new(time_module::make_module),
+        "_tracemalloc".to_string() => Box::new(tracemalloc::make_module),
         "_weakref".to_string() => Box::new(weakref::make_module),
         "_imp".to_string() => Box::new(imp::make_module),
         "unicodedata".to_string() => Box::new(unicodedata::make_module),
@@ -100,10 +120,16 @@ pub fn get_module_inits() -> HashMap<String, StdlibInitFunc> {
     // disable some modules on WASM
     #[cfg(not(target_arch = "wasm32"))]
     {
+        modules.insert(
+            "faulthandler".to_string(),
+            Box::new(faulthandler::make_module),
+        );
         modules.insert("_io".to_string(), Box::new(io::make_module));
         modules.insert("_os".to_string(), Box::new(os::make_module));
         modules.insert("socket".to_string(), Box::new(socket::make_module));
         modules.insert("signal".to_string(), Box::new(signal::make_module));
+        modules.insert("sqlite3".to_string(), Box::new(sqlite3::make_module));
+        modules.insert("ssl".to_string(), Box::new(ssl::make_module));
         modules.insert("subprocess".to_string(), Box::new(subprocess::make_module));
         modules.insert("zlib".to_string(), Box::new(zlib::make_module));
     }
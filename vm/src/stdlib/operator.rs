@@ -0,0 +1,495 @@
+/*
+ * The `_operator` module: the native counterpart of `Lib/operator.py`.
+ */
+
+use crate::function::{OptionalArg, PyFuncArgs};
+use crate::obj::objbool;
+use crate::obj::objbyteinner;
+use crate::obj::objint::{self, PyIntRef};
+use crate::obj::objiter;
+use crate::obj::objstr::{PyString, PyStringRef};
+use crate::obj::objtype::{self, PyClassRef};
+use crate::pyobject::{
+    IdProtocol, PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue, TryFromObject, TypeProtocol,
+};
+use crate::vm::VirtualMachine;
+
+use num_traits::Signed;
+
+macro_rules! binary_op {
+    ($name:ident, $op:ident) => {
+        fn $name(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            vm.$op(a, b)
+        }
+    };
+}
+
+macro_rules! unary_op {
+    ($name:ident, $method:expr) => {
+        fn $name(a: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+            vm.call_method(&a, $method, vec![])
+        }
+    };
+}
+
+binary_op!(operator_lt, _lt);
+binary_op!(operator_le, _le);
+binary_op!(operator_eq, _eq);
+binary_op!(operator_ne, _ne);
+binary_op!(operator_ge, _ge);
+binary_op!(operator_gt, _gt);
+
+binary_op!(operator_add, _add);
+binary_op!(operator_and, _and);
+binary_op!(operator_floordiv, _floordiv);
+binary_op!(operator_lshift, _lshift);
+binary_op!(operator_mod, _mod);
+binary_op!(operator_mul, _mul);
+binary_op!(operator_matmul, _matmul);
+binary_op!(operator_or, _or);
+binary_op!(operator_pow, _pow);
+binary_op!(operator_rshift, _rshift);
+binary_op!(operator_sub, _sub);
+binary_op!(operator_truediv, _truediv);
+binary_op!(operator_xor, _xor);
+
+binary_op!(operator_iadd, _iadd);
+binary_op!(operator_iand, _iand);
+binary_op!(operator_ifloordiv, _ifloordiv);
+binary_op!(operator_ilshift, _ilshift);
+binary_op!(operator_imod, _imod);
+binary_op!(operator_imul, _imul);
+binary_op!(operator_imatmul, _imatmul);
+binary_op!(operator_ior, _ior);
+binary_op!(operator_ipow, _ipow);
+binary_op!(operator_irshift, _irshift);
+binary_op!(operator_isub, _isub);
+binary_op!(operator_itruediv, _itruediv);
+binary_op!(operator_ixor, _ixor);
+
+unary_op!(operator_abs, "__abs__");
+unary_op!(operator_inv, "__invert__");
+unary_op!(operator_neg, "__neg__");
+unary_op!(operator_pos, "__pos__");
+
+fn operator_not(a: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    Ok(!objbool::boolval(vm, a)?)
+}
+
+fn operator_truth(a: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    objbool::boolval(vm, a)
+}
+
+fn operator_is(a: PyObjectRef, b: PyObjectRef, _vm: &VirtualMachine) -> bool {
+    a.is(&b)
+}
+
+fn operator_is_not(a: PyObjectRef, b: PyObjectRef, _vm: &VirtualMachine) -> bool {
+    !a.is(&b)
+}
+
+fn operator_index(a: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyIntRef> {
+    vm.to_index(&a)
+}
+
+fn check_sequence(obj: &PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+    if objtype::class_has_attr(&obj.class(), "__getitem__") {
+        Ok(())
+    } else {
+        Err(vm.new_type_error(format!(
+            "'{}' object can't be concatenated",
+            obj.class().name
+        )))
+    }
+}
+
+fn operator_concat(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    check_sequence(&a, vm)?;
+    vm._add(a, b)
+}
+
+fn operator_iconcat(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    check_sequence(&a, vm)?;
+    vm._iadd(a, b)
+}
+
+fn operator_contains(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    objbool::boolval(vm, vm._membership(a, b)?)
+}
+
+/// Iterate over `a`, calling `f` with the index of each item identical or equal to `b`.
+/// Stops early once `f` returns false.
+fn for_each_match<F>(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine, mut f: F) -> PyResult<()>
+where
+    F: FnMut(usize) -> bool,
+{
+    let iter = objiter::get_iter(vm, &a)?;
+    let mut index = 0;
+    while let Some(item) = objiter::get_next_object(vm, &iter)? {
+        if (item.is(&b) || objbool::boolval(vm, vm._eq(item, b.clone())?)?) && !f(index) {
+            break;
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+fn operator_count_of(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult<usize> {
+    let mut count = 0;
+    for_each_match(a, b, vm, |_| {
+        count += 1;
+        true
+    })?;
+    Ok(count)
+}
+
+fn operator_index_of(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult<usize> {
+    let mut found = None;
+    for_each_match(a, b, vm, |index| {
+        found = Some(index);
+        false
+    })?;
+    found.ok_or_else(|| vm.new_value_error("sequence.index(x): x not in sequence".to_string()))
+}
+
+fn operator_getitem(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    vm.call_method(&a, "__getitem__", vec![b])
+}
+
+fn operator_setitem(
+    a: PyObjectRef,
+    b: PyObjectRef,
+    c: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    vm.call_method(&a, "__setitem__", vec![b, c])?;
+    Ok(())
+}
+
+fn operator_delitem(a: PyObjectRef, b: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+    vm.call_method(&a, "__delitem__", vec![b])?;
+    Ok(())
+}
+
+fn operator_length_hint(
+    obj: PyObjectRef,
+    default: OptionalArg<PyIntRef>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let default = match default {
+        OptionalArg::Present(default) => default.into_object(),
+        OptionalArg::Missing => vm.new_int(0),
+    };
+
+    if objtype::class_has_attr(&obj.class(), "__len__") {
+        match vm.call_method(&obj, "__len__", vec![]) {
+            Ok(len) => return Ok(len),
+            Err(err) => {
+                if !objtype::isinstance(&err, &vm.ctx.exceptions.type_error) {
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    let hint = match vm.get_method(obj.clone(), "__length_hint__") {
+        Some(hint) => hint?,
+        None => return Ok(default),
+    };
+    let value = match vm.invoke(&hint, vec![]) {
+        Ok(value) => value,
+        Err(err) => {
+            return if objtype::isinstance(&err, &vm.ctx.exceptions.type_error) {
+                Ok(default)
+            } else {
+                Err(err)
+            };
+        }
+    };
+    if value.is(&vm.ctx.not_implemented()) {
+        return Ok(default);
+    }
+    if !objtype::isinstance(&value, &vm.ctx.int_type()) {
+        return Err(vm.new_type_error(format!(
+            "__length_hint__ must be integer, not {}",
+            value.class().name
+        )));
+    }
+    if objint::get_value(&value).is_negative() {
+        return Err(vm.new_value_error("__length_hint__() should return >= 0".to_string()));
+    }
+    Ok(value)
+}
+
+/// Compare two byte strings in time that depends only on the length of `b`:
+/// every byte is looked at, so the position of the first difference doesn't
+/// leak through timing.
+fn timing_safe_eq(a: &[u8], b: &[u8]) -> bool {
+    // With mismatched lengths, compare `b` with itself to do the same work.
+    let (left, mut result) = if a.len() == b.len() { (a, 0) } else { (b, 1) };
+    for (x, y) in left.iter().zip(b.iter()) {
+        result |= x ^ y;
+    }
+    result == 0
+}
+
+pub fn operator_compare_digest(
+    a: PyObjectRef,
+    b: PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<bool> {
+    if let (Some(a), Some(b)) = (a.payload::<PyString>(), b.payload::<PyString>()) {
+        if !a.as_str().is_ascii() || !b.as_str().is_ascii() {
+            return Err(vm.new_type_error(
+                "comparing strings with non-ASCII characters is not supported".to_string(),
+            ));
+        }
+        return Ok(timing_safe_eq(a.as_str().as_bytes(), b.as_str().as_bytes()));
+    }
+    match (objbyteinner::try_as_byte(&a), objbyteinner::try_as_byte(&b)) {
+        (Some(a), Some(b)) => Ok(timing_safe_eq(&a, &b)),
+        _ => Err(vm.new_type_error(format!(
+            "unsupported operand types(s) or combination of types: '{}' and '{}'",
+            a.class().name,
+            b.class().name
+        ))),
+    }
+}
+
+fn repr_args(args: &[PyObjectRef], vm: &VirtualMachine) -> PyResult<Vec<String>> {
+    args.iter()
+        .map(|arg| Ok(vm.to_repr(arg)?.as_str().to_string()))
+        .collect()
+}
+
+#[pyclass(name = "itemgetter")]
+#[derive(Debug)]
+struct PyItemGetter {
+    items: Vec<PyObjectRef>,
+}
+
+impl PyValue for PyItemGetter {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("_operator", "itemgetter")
+    }
+}
+
+#[pyimpl]
+impl PyItemGetter {
+    #[pymethod(name = "__new__")]
+    fn new(cls: PyClassRef, args: PyFuncArgs, vm: &VirtualMachine) -> PyResult<PyRef<Self>> {
+        if !args.kwargs.is_empty() {
+            return Err(vm.new_type_error("itemgetter() takes no keyword arguments".to_string()));
+        }
+        if args.args.is_empty() {
+            return Err(vm.new_type_error("itemgetter expected 1 argument, got 0".to_string()));
+        }
+        PyItemGetter { items: args.args }.into_ref_with_type(vm, cls)
+    }
+
+    #[pymethod(name = "__call__")]
+    fn call(&self, obj: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        if let [item] = self.items.as_slice() {
+            return operator_getitem(obj, item.clone(), vm);
+        }
+        let values = self
+            .items
+            .iter()
+            .map(|item| operator_getitem(obj.clone(), item.clone(), vm))
+            .collect::<PyResult<_>>()?;
+        Ok(vm.ctx.new_tuple(values))
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        Ok(format!(
+            "operator.itemgetter({})",
+            repr_args(&self.items, vm)?.join(", ")
+        ))
+    }
+
+    #[pymethod(name = "__reduce__")]
+    fn reduce(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyObjectRef {
+        vm.ctx.new_tuple(vec![
+            zelf.class().into_object(),
+            vm.ctx.new_tuple(zelf.items.clone()),
+        ])
+    }
+}
+
+#[pyclass(name = "attrgetter")]
+#[derive(Debug)]
+struct PyAttrGetter {
+    attrs: Vec<PyStringRef>,
+}
+
+impl PyValue for PyAttrGetter {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("_operator", "attrgetter")
+    }
+}
+
+#[pyimpl]
+impl PyAttrGetter {
+    #[pymethod(name = "__new__")]
+    fn new(cls: PyClassRef, args: PyFuncArgs, vm: &VirtualMachine) -> PyResult<PyRef<Self>> {
+        if !args.kwargs.is_empty() {
+            return Err(vm.new_type_error("attrgetter() takes no keyword arguments".to_string()));
+        }
+        if args.args.is_empty() {
+            return Err(vm.new_type_error("attrgetter expected 1 argument, got 0".to_string()));
+        }
+        let attrs = args
+            .args
+            .iter()
+            .map(|attr| {
+                attr.clone()
+                    .downcast::<PyString>()
+                    .map_err(|_| vm.new_type_error("attribute name must be a string".to_string()))
+            })
+            .collect::<PyResult<_>>()?;
+        PyAttrGetter { attrs }.into_ref_with_type(vm, cls)
+    }
+
+    /// Look up a possibly dotted attribute path, such as `"name.first"`.
+    fn get_path(obj: PyObjectRef, path: &str, vm: &VirtualMachine) -> PyResult {
+        path.split('.')
+            .try_fold(obj, |obj, name| vm.get_attribute(obj, name))
+    }
+
+    #[pymethod(name = "__call__")]
+    fn call(&self, obj: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        if let [attr] = self.attrs.as_slice() {
+            return Self::get_path(obj, attr.as_str(), vm);
+        }
+        let values = self
+            .attrs
+            .iter()
+            .map(|attr| Self::get_path(obj.clone(), attr.as_str(), vm))
+            .collect::<PyResult<_>>()?;
+        Ok(vm.ctx.new_tuple(values))
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        let attrs: Vec<_> = self.attrs.iter().map(|a| a.clone().into_object()).collect();
+        Ok(format!(
+            "operator.attrgetter({})",
+            repr_args(&attrs, vm)?.join(", ")
+        ))
+    }
+
+    #[pymethod(name = "__reduce__")]
+    fn reduce(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyObjectRef {
+        let attrs = zelf.attrs.iter().map(|a| a.clone().into_object()).collect();
+        vm.ctx
+            .new_tuple(vec![zelf.class().into_object(), vm.ctx.new_tuple(attrs)])
+    }
+}
+
+#[pyclass(name = "methodcaller")]
+#[derive(Debug)]
+struct PyMethodCaller {
+    name: PyStringRef,
+    args: PyFuncArgs,
+}
+
+impl PyValue for PyMethodCaller {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("_operator", "methodcaller")
+    }
+}
+
+#[pyimpl]
+impl PyMethodCaller {
+    #[pymethod(name = "__new__")]
+    fn new(cls: PyClassRef, mut args: PyFuncArgs, vm: &VirtualMachine) -> PyResult<PyRef<Self>> {
+        if args.args.is_empty() {
+            return Err(vm.new_type_error(
+                "methodcaller needs at least one argument, the method name".to_string(),
+            ));
+        }
+        let name = PyStringRef::try_from_object(vm, args.args.remove(0))
+            .map_err(|_| vm.new_type_error("method name must be a string".to_string()))?;
+        PyMethodCaller { name, args }.into_ref_with_type(vm, cls)
+    }
+
+    #[pymethod(name = "__call__")]
+    fn call(&self, obj: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        let method = vm.get_attribute(obj, self.name.clone())?;
+        vm.invoke(&method, self.args.clone())
+    }
+
+    #[pymethod(name = "__repr__")]
+    fn repr(&self, vm: &VirtualMachine) -> PyResult<String> {
+        let mut args = vec![vm.to_repr(self.name.as_object())?.as_str().to_string()];
+        args.extend(repr_args(&self.args.args, vm)?);
+        for (key, value) in &self.args.kwargs {
+            args.push(format!("{}={}", key, vm.to_repr(value)?.as_str()));
+        }
+        Ok(format!("operator.methodcaller({})", args.join(", ")))
+    }
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    py_module!(vm, "_operator", {
+        "__doc__" => ctx.new_str("Operator interface.".to_string()),
+        "lt" => ctx.new_rustfunc(operator_lt),
+        "le" => ctx.new_rustfunc(operator_le),
+        "eq" => ctx.new_rustfunc(operator_eq),
+        "ne" => ctx.new_rustfunc(operator_ne),
+        "ge" => ctx.new_rustfunc(operator_ge),
+        "gt" => ctx.new_rustfunc(operator_gt),
+        "not_" => ctx.new_rustfunc(operator_not),
+        "truth" => ctx.new_rustfunc(operator_truth),
+        "is_" => ctx.new_rustfunc(operator_is),
+        "is_not" => ctx.new_rustfunc(operator_is_not),
+        "abs" => ctx.new_rustfunc(operator_abs),
+        "add" => ctx.new_rustfunc(operator_add),
+        "and_" => ctx.new_rustfunc(operator_and),
+        "floordiv" => ctx.new_rustfunc(operator_floordiv),
+        "index" => ctx.new_rustfunc(operator_index),
+        "inv" => ctx.new_rustfunc(operator_inv),
+        "invert" => ctx.new_rustfunc(operator_inv),
+        "lshift" => ctx.new_rustfunc(operator_lshift),
+        "mod" => ctx.new_rustfunc(operator_mod),
+        "mul" => ctx.new_rustfunc(operator_mul),
+        "matmul" => ctx.new_rustfunc(operator_matmul),
+        "neg" => ctx.new_rustfunc(operator_neg),
+        "or_" => ctx.new_rustfunc(operator_or),
+        "pos" => ctx.new_rustfunc(operator_pos),
+        "pow" => ctx.new_rustfunc(operator_pow),
+        "rshift" => ctx.new_rustfunc(operator_rshift),
+        "sub" => ctx.new_rustfunc(operator_sub),
+        "truediv" => ctx.new_rustfunc(operator_truediv),
+        "xor" => ctx.new_rustfunc(operator_xor),
+        "concat" => ctx.new_rustfunc(operator_concat),
+        "contains" => ctx.new_rustfunc(operator_contains),
+        "countOf" => ctx.new_rustfunc(operator_count_of),
+        "delitem" => ctx.new_rustfunc(operator_delitem),
+        "getitem" => ctx.new_rustfunc(operator_getitem),
+        "indexOf" => ctx.new_rustfunc(operator_index_of),
+        "setitem" => ctx.new_rustfunc(operator_setitem),
+        "length_hint" => ctx.new_rustfunc(operator_length_hint),
+        "_compare_digest" => ctx.new_rustfunc(operator_compare_digest),
+        "iadd" => ctx.new_rustfunc(operator_iadd),
+        "iand" => ctx.new_rustfunc(operator_iand),
+        "iconcat" => ctx.new_rustfunc(operator_iconcat),
+        "ifloordiv" => ctx.new_rustfunc(operator_ifloordiv),
+        "ilshift" => ctx.new_rustfunc(operator_ilshift),
+        "imod" => ctx.new_rustfunc(operator_imod),
+        "imul" => ctx.new_rustfunc(operator_imul),
+        "imatmul" => ctx.new_rustfunc(operator_imatmul),
+        "ior" => ctx.new_rustfunc(operator_ior),
+        "ipow" => ctx.new_rustfunc(operator_ipow),
+        "irshift" => ctx.new_rustfunc(operator_irshift),
+        "isub" => ctx.new_rustfunc(operator_isub),
+        "itruediv" => ctx.new_rustfunc(operator_itruediv),
+        "ixor" => ctx.new_rustfunc(operator_ixor),
+        "itemgetter" => PyItemGetter::make_class(ctx),
+        "attrgetter" => PyAttrGetter::make_class(ctx),
+        "methodcaller" => PyMethodCaller::make_class(ctx),
+    })
+}
@@ -1,9 +1,10 @@
 use num_cpus;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::ffi;
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::io::{self, ErrorKind, Read, Write};
+use std::ops::Deref;
 use std::time::{Duration, SystemTime};
 use std::{env, fs};
 
@@ -17,16 +18,19 @@ use num_traits::cast::ToPrimitive;
 
 use bitflags::bitflags;
 
-use crate::function::{IntoPyNativeFunc, PyFuncArgs};
+use crate::function::{IntoPyNativeFunc, OptionalArg, PyFuncArgs};
 use crate::obj::objbytes::PyBytesRef;
 use crate::obj::objdict::PyDictRef;
+use crate::obj::objfloat;
 use crate::obj::objint::{self, PyInt, PyIntRef};
 use crate::obj::objiter;
 use crate::obj::objset::PySet;
 use crate::obj::objstr::{self, PyString, PyStringRef};
+use crate::obj::objtuple::PyTupleRef;
 use crate::obj::objtype::{self, PyClassRef};
 use crate::pyobject::{
-    ItemProtocol, PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue, TryIntoRef, TypeProtocol,
+    ItemProtocol, PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue, TryFromObject, TryIntoRef,
+    TypeProtocol,
 };
 use crate::vm::VirtualMachine;
 
@@ -69,14 +73,45 @@ pub fn raw_file_number(handle: File) -> i64 {
     unimplemented!();
 }
 
-fn make_path(_vm: &VirtualMachine, path: PyStringRef, dir_fd: &DirFd) -> PyStringRef {
+/// A path argument: a `str`, or an `os.PathLike` object whose `__fspath__()`
+/// returns one.
+#[derive(Debug)]
+pub struct PyPathLike(PyStringRef);
+
+impl Deref for PyPathLike {
+    type Target = PyString;
+
+    fn deref(&self) -> &PyString {
+        &self.0
+    }
+}
+
+impl TryFromObject for PyPathLike {
+    fn try_from_object(vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<Self> {
+        let path = os_fspath(obj, vm)?;
+        PyStringRef::try_from_object(vm, path).map(PyPathLike)
+    }
+}
+
+fn make_path(_vm: &VirtualMachine, path: PyPathLike, dir_fd: &DirFd) -> PyStringRef {
     if dir_fd.dir_fd.is_some() {
         unimplemented!();
     } else {
-        path
+        path.0
     }
 }
 
+#[cfg(unix)]
+pub fn os_close(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
+    arg_check!(vm, args, required = [(fileno, Some(vm.ctx.int_type()))]);
+
+    let raw_fileno = objint::get_value(&fileno);
+    unistd::close(raw_fileno.to_i32().unwrap()).map_err(|err| convert_nix_error(vm, err))?;
+
+    Ok(vm.get_none())
+}
+
+#[cfg(not(unix))]
 pub fn os_close(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(vm, args, required = [(fileno, Some(vm.ctx.int_type()))]);
 
@@ -98,6 +133,7 @@ bitflags! {
         const O_RDWR = 0o0000_0002;
         const O_CREAT = 0o0000_0100;
         const O_EXCL = 0o0000_0200;
+        const O_TRUNC = 0o0000_1000;
         const O_APPEND = 0o0000_2000;
         const O_NONBLOCK = 0o0000_4000;
     }
@@ -107,17 +143,14 @@ pub fn os_open(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     arg_check!(
         vm,
         args,
-        required = [
-            (name, Some(vm.ctx.str_type())),
-            (flags, Some(vm.ctx.int_type()))
-        ],
+        required = [(name, None), (flags, Some(vm.ctx.int_type()))],
         optional = [
-            (_mode, Some(vm.ctx.int_type())),
+            (mode, Some(vm.ctx.int_type())),
             (dir_fd, Some(vm.ctx.int_type()))
         ]
     );
 
-    let name = name.clone().downcast::<PyString>().unwrap();
+    let name = PyPathLike::try_from_object(vm, name.clone())?;
     let dir_fd = if let Some(obj) = dir_fd {
         DirFd {
             dir_fd: Some(obj.clone().downcast::<PyInt>().unwrap()),
@@ -127,7 +160,9 @@ pub fn os_open(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     };
     let fname = &make_path(vm, name, &dir_fd).value;
 
-    let flags = FileCreationFlags::from_bits(objint::get_value(flags).to_u32().unwrap())
+    let flags = objint::get_value(flags)
+        .to_u32()
+        .and_then(FileCreationFlags::from_bits)
         .ok_or_else(|| vm.new_value_error("Unsupported flag".to_string()))?;
 
     let mut options = &mut OpenOptions::new();
@@ -144,6 +179,10 @@ pub fn os_open(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
         options = options.append(true);
     }
 
+    if flags.contains(FileCreationFlags::O_TRUNC) {
+        options = options.truncate(true);
+    }
+
     if flags.contains(FileCreationFlags::O_CREAT) {
         if flags.contains(FileCreationFlags::O_EXCL) {
             options = options.create_new(true);
@@ -152,6 +191,20 @@ pub fn os_open(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
         }
     }
 
+    // the permissions of a file that is created
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        if let Some(mode) = mode {
+            let mode = objint::get_value(mode)
+                .to_u32()
+                .ok_or_else(|| vm.new_overflow_error("mode is out of range".to_string()))?;
+            options = options.mode(mode);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+
     let handle = options
         .open(&fname)
         .map_err(|err| convert_io_error(vm, err))?;
@@ -299,7 +352,7 @@ fn getgroups() -> nix::Result<Vec<Gid>> {
 }
 
 #[cfg(unix)]
-fn os_access(path: PyStringRef, mode: u8, vm: &VirtualMachine) -> PyResult<bool> {
+fn os_access(path: PyPathLike, mode: u8, vm: &VirtualMachine) -> PyResult<bool> {
     use std::os::unix::fs::MetadataExt;
 
     let path = path.as_str();
@@ -353,53 +406,79 @@ fn os_error(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
 
 fn os_fsync(fd: PyIntRef, vm: &VirtualMachine) -> PyResult<()> {
     let file = rust_file(fd.as_bigint().to_i64().unwrap());
-    file.sync_all().map_err(|err| convert_io_error(vm, err))?;
+    let result = file.sync_all();
     // Avoid closing the fd
     raw_file_number(file);
-    Ok(())
+    result.map_err(|err| convert_io_error(vm, err))
 }
 
 fn os_read(fd: PyIntRef, n: PyIntRef, vm: &VirtualMachine) -> PyResult {
     let mut buffer = vec![0u8; n.as_bigint().to_usize().unwrap()];
     let mut file = rust_file(fd.as_bigint().to_i64().unwrap());
-    file.read_exact(&mut buffer)
-        .map_err(|err| convert_io_error(vm, err))?;
+    let result = file.read_exact(&mut buffer);
 
     // Avoid closing the fd
     raw_file_number(file);
+    result.map_err(|err| convert_io_error(vm, err))?;
     Ok(vm.ctx.new_bytes(buffer))
 }
 
 fn os_write(fd: PyIntRef, data: PyBytesRef, vm: &VirtualMachine) -> PyResult {
     let mut file = rust_file(fd.as_bigint().to_i64().unwrap());
-    let written = file.write(&data).map_err(|err| convert_io_error(vm, err))?;
+    let result = file.write(&data);
 
     // Avoid closing the fd
     raw_file_number(file);
+    let written = result.map_err(|err| convert_io_error(vm, err))?;
     Ok(vm.ctx.new_int(written))
 }
 
-fn os_remove(path: PyStringRef, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<()> {
+fn os_remove(path: PyPathLike, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<()> {
     let path = make_path(vm, path, &dir_fd);
     fs::remove_file(&path.value).map_err(|err| convert_io_error(vm, err))
 }
 
-fn os_mkdir(path: PyStringRef, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<()> {
+#[cfg(unix)]
+fn os_mkdir(
+    path: PyPathLike,
+    mode: OptionalArg<u32>,
+    dir_fd: DirFd,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    let path = make_path(vm, path, &dir_fd);
+    fs::DirBuilder::new()
+        .mode(mode.unwrap_or(0o777))
+        .create(&path.value)
+        .map_err(|err| convert_io_error(vm, err))
+}
+
+#[cfg(not(unix))]
+fn os_mkdir(
+    path: PyPathLike,
+    _mode: OptionalArg<u32>,
+    dir_fd: DirFd,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
     let path = make_path(vm, path, &dir_fd);
     fs::create_dir(&path.value).map_err(|err| convert_io_error(vm, err))
 }
 
-fn os_mkdirs(path: PyStringRef, vm: &VirtualMachine) -> PyResult<()> {
+fn os_mkdirs(path: PyPathLike, vm: &VirtualMachine) -> PyResult<()> {
     fs::create_dir_all(&path.value).map_err(|err| convert_io_error(vm, err))
 }
 
-fn os_rmdir(path: PyStringRef, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<()> {
+fn os_rmdir(path: PyPathLike, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<()> {
     let path = make_path(vm, path, &dir_fd);
     fs::remove_dir(&path.value).map_err(|err| convert_io_error(vm, err))
 }
 
-fn os_listdir(path: PyStringRef, vm: &VirtualMachine) -> PyResult {
-    match fs::read_dir(&path.value) {
+fn os_listdir(path: OptionalArg<PyPathLike>, vm: &VirtualMachine) -> PyResult {
+    let path = match path {
+        OptionalArg::Present(ref path) => path.as_str(),
+        OptionalArg::Missing => ".",
+    };
+    match fs::read_dir(path) {
         Ok(iter) => {
             let res: PyResult<Vec<PyObjectRef>> = iter
                 .map(|entry| match entry {
@@ -409,7 +488,7 @@ fn os_listdir(path: PyStringRef, vm: &VirtualMachine) -> PyResult {
                 .collect();
             Ok(vm.ctx.new_list(res?))
         }
-        Err(s) => Err(vm.new_os_error(s.to_string())),
+        Err(s) => Err(convert_io_error(vm, s)),
     }
 }
 
@@ -429,7 +508,7 @@ fn _os_environ(vm: &VirtualMachine) -> PyDictRef {
     environ
 }
 
-fn os_readlink(path: PyStringRef, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult {
+fn os_readlink(path: PyPathLike, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult {
     let path = make_path(vm, path, &dir_fd);
     let path = fs::read_link(path.as_str()).map_err(|err| convert_io_error(vm, err))?;
     let path = path.into_os_string().into_string().map_err(|_osstr| {
@@ -483,8 +562,13 @@ impl DirEntryRef {
             true => fs::metadata(self.entry.path()),
             false => fs::symlink_metadata(self.entry.path()),
         };
-        let meta = metadata.map_err(|err| convert_io_error(vm, err))?;
-        Ok(action(meta))
+        match metadata {
+            Ok(meta) => Ok(action(meta)),
+            // A dangling symlink, or an entry removed since it was listed,
+            // is neither a file nor a directory.
+            Err(ref err) if err.kind() == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(convert_io_error(vm, err)),
+        }
     }
 
     fn is_dir(self, follow_symlinks: FollowSymlinks, vm: &VirtualMachine) -> PyResult<bool> {
@@ -517,7 +601,27 @@ impl DirEntryRef {
         follow_symlinks: FollowSymlinks,
         vm: &VirtualMachine,
     ) -> PyResult<StatResult> {
-        os_stat(self.path(vm).try_into_ref(vm)?, dir_fd, follow_symlinks, vm)
+        let path = PyPathLike(self.path(vm).try_into_ref(vm)?);
+        os_stat(path, dir_fd, follow_symlinks, vm)
+    }
+
+    #[cfg(unix)]
+    fn inode(self, _vm: &VirtualMachine) -> u64 {
+        use std::os::unix::fs::DirEntryExt;
+        self.entry.ino()
+    }
+
+    #[cfg(not(unix))]
+    fn inode(self, _vm: &VirtualMachine) -> u64 {
+        0 // TODO: Not implemented in std::os::windows::fs::MetadataExt.
+    }
+
+    fn fspath(self, vm: &VirtualMachine) -> String {
+        self.path(vm)
+    }
+
+    fn repr(self, vm: &VirtualMachine) -> String {
+        format!("<DirEntry '{}'>", self.name(vm))
     }
 }
 
@@ -525,6 +629,7 @@ impl DirEntryRef {
 #[derive(Debug)]
 struct ScandirIterator {
     entries: RefCell<fs::ReadDir>,
+    exhausted: Cell<bool>,
 }
 
 impl PyValue for ScandirIterator {
@@ -537,25 +642,51 @@ impl PyValue for ScandirIterator {
 impl ScandirIterator {
     #[pymethod(name = "__next__")]
     fn next(&self, vm: &VirtualMachine) -> PyResult {
+        if self.exhausted.get() {
+            return Err(objiter::new_stop_iteration(vm));
+        }
         match self.entries.borrow_mut().next() {
             Some(entry) => match entry {
                 Ok(entry) => Ok(DirEntry { entry }.into_ref(vm).into_object()),
                 Err(s) => Err(convert_io_error(vm, s)),
             },
-            None => Err(objiter::new_stop_iteration(vm)),
+            None => {
+                self.exhausted.set(true);
+                Err(objiter::new_stop_iteration(vm))
+            }
         }
     }
 
+    #[pymethod]
+    fn close(&self, _vm: &VirtualMachine) {
+        self.exhausted.set(true);
+    }
+
     #[pymethod(name = "__iter__")]
     fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
         zelf
     }
+
+    #[pymethod(name = "__enter__")]
+    fn enter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+
+    #[pymethod(name = "__exit__")]
+    fn exit(zelf: PyRef<Self>, _args: PyFuncArgs, vm: &VirtualMachine) {
+        zelf.close(vm)
+    }
 }
 
-fn os_scandir(path: PyStringRef, vm: &VirtualMachine) -> PyResult {
-    match fs::read_dir(&path.value) {
+fn os_scandir(path: OptionalArg<PyPathLike>, vm: &VirtualMachine) -> PyResult {
+    let path = match path {
+        OptionalArg::Present(ref path) => path.as_str(),
+        OptionalArg::Missing => ".",
+    };
+    match fs::read_dir(path) {
         Ok(iter) => Ok(ScandirIterator {
             entries: RefCell::new(iter),
+            exhausted: Cell::new(false),
         }
         .into_ref(vm)
         .into_object()),
@@ -575,6 +706,9 @@ struct StatResult {
     st_atime: f64,
     st_ctime: f64,
     st_mtime: f64,
+    st_atime_ns: i64,
+    st_ctime_ns: i64,
+    st_mtime_ns: i64,
 }
 
 impl PyValue for StatResult {
@@ -625,6 +759,18 @@ impl StatResultRef {
     fn st_mtime(self, _vm: &VirtualMachine) -> f64 {
         self.st_mtime
     }
+
+    fn st_atime_ns(self, _vm: &VirtualMachine) -> i64 {
+        self.st_atime_ns
+    }
+
+    fn st_ctime_ns(self, _vm: &VirtualMachine) -> i64 {
+        self.st_ctime_ns
+    }
+
+    fn st_mtime_ns(self, _vm: &VirtualMachine) -> i64 {
+        self.st_mtime_ns
+    }
 }
 
 // Copied code from Duration::as_secs_f64 as it's still unstable
@@ -639,6 +785,17 @@ fn to_seconds_from_unix_epoch(sys_time: SystemTime) -> f64 {
     }
 }
 
+fn duration_as_nanos(duration: Duration) -> i64 {
+    duration.as_secs() as i64 * 1_000_000_000 + i64::from(duration.subsec_nanos())
+}
+
+fn to_nanos_from_unix_epoch(sys_time: SystemTime) -> i64 {
+    match sys_time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(duration) => duration_as_nanos(duration),
+        Err(err) => -duration_as_nanos(err.duration()),
+    }
+}
+
 #[cfg(unix)]
 fn to_seconds_from_nanos(secs: i64, nanos: i64) -> f64 {
     let duration = Duration::new(secs as u64, nanos as u32);
@@ -666,6 +823,9 @@ macro_rules! os_unix_stat_inner {
                 st_atime: to_seconds_from_unix_epoch(meta.accessed()?),
                 st_mtime: to_seconds_from_unix_epoch(meta.modified()?),
                 st_ctime: to_seconds_from_nanos(meta.st_ctime(), meta.st_ctime_nsec()),
+                st_atime_ns: to_nanos_from_unix_epoch(meta.accessed()?),
+                st_mtime_ns: to_nanos_from_unix_epoch(meta.modified()?),
+                st_ctime_ns: meta.st_ctime() * 1_000_000_000 + meta.st_ctime_nsec(),
             })
         }
 
@@ -676,7 +836,7 @@ macro_rules! os_unix_stat_inner {
 
 #[cfg(target_os = "linux")]
 fn os_stat(
-    path: PyStringRef,
+    path: PyPathLike,
     dir_fd: DirFd,
     follow_symlinks: FollowSymlinks,
     vm: &VirtualMachine,
@@ -688,7 +848,7 @@ fn os_stat(
 
 #[cfg(target_os = "macos")]
 fn os_stat(
-    path: PyStringRef,
+    path: PyPathLike,
     dir_fd: DirFd,
     follow_symlinks: FollowSymlinks,
     vm: &VirtualMachine,
@@ -700,7 +860,7 @@ fn os_stat(
 
 #[cfg(target_os = "android")]
 fn os_stat(
-    path: PyStringRef,
+    path: PyPathLike,
     dir_fd: DirFd,
     follow_symlinks: FollowSymlinks,
     vm: &VirtualMachine,
@@ -712,7 +872,7 @@ fn os_stat(
 
 #[cfg(target_os = "redox")]
 fn os_stat(
-    path: PyStringRef,
+    path: PyPathLike,
     dir_fd: DirFd,
     follow_symlinks: FollowSymlinks,
     vm: &VirtualMachine,
@@ -745,7 +905,7 @@ fn attributes_to_mode(attr: u32) -> u32 {
 
 #[cfg(windows)]
 fn os_stat(
-    path: PyStringRef,
+    path: PyPathLike,
     _dir_fd: DirFd, // TODO: error
     follow_symlinks: FollowSymlinks,
     vm: &VirtualMachine,
@@ -769,6 +929,9 @@ fn os_stat(
             st_atime: to_seconds_from_unix_epoch(meta.accessed()?),
             st_mtime: to_seconds_from_unix_epoch(meta.modified()?),
             st_ctime: to_seconds_from_unix_epoch(meta.created()?),
+            st_atime_ns: to_nanos_from_unix_epoch(meta.accessed()?),
+            st_mtime_ns: to_nanos_from_unix_epoch(meta.modified()?),
+            st_ctime_ns: to_nanos_from_unix_epoch(meta.created()?),
         })
     }
 
@@ -792,7 +955,7 @@ fn os_stat(
     unimplemented!();
 }
 
-fn os_lstat(path: PyStringRef, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<StatResult> {
+fn os_lstat(path: PyPathLike, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<StatResult> {
     os_stat(
         path,
         dir_fd,
@@ -805,8 +968,8 @@ fn os_lstat(path: PyStringRef, dir_fd: DirFd, vm: &VirtualMachine) -> PyResult<S
 
 #[cfg(unix)]
 fn os_symlink(
-    src: PyStringRef,
-    dst: PyStringRef,
+    src: PyPathLike,
+    dst: PyPathLike,
     dir_fd: DirFd,
     vm: &VirtualMachine,
 ) -> PyResult<()> {
@@ -817,8 +980,8 @@ fn os_symlink(
 
 #[cfg(windows)]
 fn os_symlink(
-    src: PyStringRef,
-    dst: PyStringRef,
+    src: PyPathLike,
+    dst: PyPathLike,
     _dir_fd: DirFd,
     vm: &VirtualMachine,
 ) -> PyResult<()> {
@@ -840,8 +1003,8 @@ fn os_symlink(
 
 #[cfg(all(not(unix), not(windows)))]
 fn os_symlink(
-    src: PyStringRef,
-    dst: PyStringRef,
+    src: PyPathLike,
+    dst: PyPathLike,
     dir_fd: DirFd,
     vm: &VirtualMachine,
 ) -> PyResult<()> {
@@ -857,13 +1020,13 @@ fn os_getcwd(vm: &VirtualMachine) -> PyResult<String> {
         .to_string())
 }
 
-fn os_chdir(path: PyStringRef, vm: &VirtualMachine) -> PyResult<()> {
+fn os_chdir(path: PyPathLike, vm: &VirtualMachine) -> PyResult<()> {
     env::set_current_dir(&path.value).map_err(|err| convert_io_error(vm, err))
 }
 
 #[cfg(unix)]
 fn os_chmod(
-    path: PyStringRef,
+    path: PyPathLike,
     dir_fd: DirFd,
     mode: u32,
     follow_symlinks: FollowSymlinks,
@@ -883,20 +1046,133 @@ fn os_chmod(
     Ok(())
 }
 
+#[cfg(unix)]
+#[derive(FromArgs)]
+struct UtimeArgs {
+    #[pyarg(positional_or_keyword)]
+    path: PyPathLike,
+    #[pyarg(positional_or_keyword, default = "None")]
+    times: Option<PyTupleRef>,
+    #[pyarg(keyword_only, default = "None")]
+    ns: Option<PyTupleRef>,
+}
+
+#[cfg(unix)]
+fn time_pair(
+    times: PyTupleRef,
+    convert: impl Fn(&PyObjectRef) -> PyResult<Option<libc::timespec>>,
+    error: &str,
+    vm: &VirtualMachine,
+) -> PyResult<[libc::timespec; 2]> {
+    if let [atime, mtime] = times.elements.as_slice() {
+        if let (Some(atime), Some(mtime)) = (convert(atime)?, convert(mtime)?) {
+            return Ok([atime, mtime]);
+        }
+    }
+    Err(vm.new_type_error(error.to_string()))
+}
+
+#[cfg(unix)]
+fn os_utime(
+    args: UtimeArgs,
+    dir_fd: DirFd,
+    follow_symlinks: FollowSymlinks,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let path = make_path(vm, args.path, &dir_fd);
+    let times = match (args.times, args.ns) {
+        (Some(_), Some(_)) => {
+            return Err(vm.new_value_error(
+                "utime: you may specify either 'times' or 'ns' but not both".to_string(),
+            ))
+        }
+        (Some(times), None) => time_pair(
+            times,
+            |time| {
+                Ok(objfloat::try_float(time, vm)?.map(|time| {
+                    let secs = time.floor();
+                    libc::timespec {
+                        tv_sec: secs as libc::time_t,
+                        tv_nsec: ((time - secs) * 1e9) as libc::c_long,
+                    }
+                }))
+            },
+            "utime: 'times' must be either a tuple of two numbers or None",
+            vm,
+        )?,
+        (None, Some(ns)) => time_pair(
+            ns,
+            |time| {
+                if !objtype::isinstance(time, &vm.ctx.int_type()) {
+                    return Ok(None);
+                }
+                let ns = i64::try_from_object(vm, time.clone())?;
+                let (mut secs, mut nsecs) = (ns / 1_000_000_000, ns % 1_000_000_000);
+                if nsecs < 0 {
+                    secs -= 1;
+                    nsecs += 1_000_000_000;
+                }
+                Ok(Some(libc::timespec {
+                    tv_sec: secs as libc::time_t,
+                    tv_nsec: nsecs as libc::c_long,
+                }))
+            },
+            "utime: 'ns' must be a tuple of two ints",
+            vm,
+        )?,
+        (None, None) => {
+            let now = libc::timespec {
+                tv_sec: 0,
+                tv_nsec: libc::UTIME_NOW,
+            };
+            [now, now]
+        }
+    };
+    let path = ffi::CString::new(path.as_str())
+        .map_err(|_| vm.new_value_error("embedded null byte".to_string()))?;
+    let flags = if follow_symlinks.follow_symlinks {
+        0
+    } else {
+        libc::AT_SYMLINK_NOFOLLOW
+    };
+    let ret = unsafe { libc::utimensat(libc::AT_FDCWD, path.as_ptr(), times.as_ptr(), flags) };
+    if ret < 0 {
+        return Err(convert_io_error(vm, io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+fn is_str_or_bytes(obj: &PyObjectRef, vm: &VirtualMachine) -> bool {
+    objtype::issubclass(&obj.class(), &vm.ctx.str_type())
+        || objtype::issubclass(&obj.class(), &vm.ctx.bytes_type())
+}
+
 fn os_fspath(path: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-    if objtype::issubclass(&path.class(), &vm.ctx.str_type())
-        || objtype::issubclass(&path.class(), &vm.ctx.bytes_type())
-    {
-        Ok(path)
+    if is_str_or_bytes(&path, vm) {
+        return Ok(path);
+    }
+    let method = match vm.get_method(path.clone(), "__fspath__") {
+        Some(method) => method?,
+        None => {
+            return Err(vm.new_type_error(format!(
+                "expected str, bytes or os.PathLike object, not {}",
+                path.class().name
+            )))
+        }
+    };
+    let result = vm.invoke(&method, vec![])?;
+    if is_str_or_bytes(&result, vm) {
+        Ok(result)
     } else {
         Err(vm.new_type_error(format!(
-            "expected str or bytes object, not {}",
-            path.class()
+            "expected {}.__fspath__() to return str or bytes, not {}",
+            path.class().name,
+            result.class().name
         )))
     }
 }
 
-fn os_rename(src: PyStringRef, dst: PyStringRef, vm: &VirtualMachine) -> PyResult<()> {
+fn os_rename(src: PyPathLike, dst: PyPathLike, vm: &VirtualMachine) -> PyResult<()> {
     fs::rename(&src.value, &dst.value).map_err(|err| convert_io_error(vm, err))
 }
 
@@ -1051,6 +1327,9 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
          "is_file" => ctx.new_rustfunc(DirEntryRef::is_file),
          "is_symlink" => ctx.new_rustfunc(DirEntryRef::is_symlink),
          "stat" => ctx.new_rustfunc(DirEntryRef::stat),
+         "inode" => ctx.new_rustfunc(DirEntryRef::inode),
+         "__fspath__" => ctx.new_rustfunc(DirEntryRef::fspath),
+         "__repr__" => ctx.new_rustfunc(DirEntryRef::repr),
     });
 
     let stat_result = py_class!(ctx, "stat_result", ctx.object(), {
@@ -1064,6 +1343,9 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
          "st_atime" => ctx.new_property(StatResultRef::st_atime),
          "st_ctime" => ctx.new_property(StatResultRef::st_ctime),
          "st_mtime" => ctx.new_property(StatResultRef::st_mtime),
+         "st_atime_ns" => ctx.new_property(StatResultRef::st_atime_ns),
+         "st_ctime_ns" => ctx.new_property(StatResultRef::st_ctime_ns),
+         "st_mtime_ns" => ctx.new_property(StatResultRef::st_mtime_ns),
     });
 
     struct SupportFunc<'a> {
@@ -1111,24 +1393,19 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         SupportFunc::new(vm, "readlink", os_readlink, Some(false), Some(false), None),
         SupportFunc::new(vm, "remove", os_remove, Some(false), Some(false), None),
         SupportFunc::new(vm, "rename", os_rename, Some(false), Some(false), None),
-        SupportFunc::new(vm, "replace", os_rename, Some(false), Some(false), None), // TODO: Fix replace
+        SupportFunc::new(vm, "replace", os_rename, Some(false), Some(false), None),
         SupportFunc::new(vm, "rmdir", os_rmdir, Some(false), Some(false), None),
         SupportFunc::new(vm, "scandir", os_scandir, Some(false), None, None),
         SupportFunc::new(vm, "stat", os_stat, Some(false), Some(false), Some(false)),
         SupportFunc::new(vm, "symlink", os_symlink, None, Some(false), None),
         // truncate Some None None
         SupportFunc::new(vm, "unlink", os_remove, Some(false), Some(false), None),
-        // utime Some Some Some
     ];
     #[cfg(unix)]
-    support_funcs.extend(vec![SupportFunc::new(
-        vm,
-        "chmod",
-        os_chmod,
-        Some(false),
-        Some(false),
-        Some(false),
-    )]);
+    support_funcs.extend(vec![
+        SupportFunc::new(vm, "chmod", os_chmod, Some(false), Some(false), Some(false)),
+        SupportFunc::new(vm, "utime", os_utime, Some(false), Some(false), Some(true)),
+    ]);
     let supports_fd = PySet::default().into_ref(vm);
     let supports_dir_fd = PySet::default().into_ref(vm);
     let supports_follow_symlinks = PySet::default().into_ref(vm);
@@ -1157,6 +1434,7 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "O_NONBLOCK" => ctx.new_int(FileCreationFlags::O_NONBLOCK.bits()),
         "O_APPEND" => ctx.new_int(FileCreationFlags::O_APPEND.bits()),
         "O_EXCL" => ctx.new_int(FileCreationFlags::O_EXCL.bits()),
+        "O_TRUNC" => ctx.new_int(FileCreationFlags::O_TRUNC.bits()),
         "O_CREAT" => ctx.new_int(FileCreationFlags::O_CREAT.bits()),
         "F_OK" => ctx.new_int(AccessFlags::F_OK.bits()),
         "R_OK" => ctx.new_int(AccessFlags::R_OK.bits()),
@@ -17,14 +17,28 @@ use num_bigint::BigInt;
 use num_traits::ToPrimitive;
 
 use crate::function::PyFuncArgs;
-use crate::obj::{objbool, objbytes, objfloat, objint, objstr, objtype};
-use crate::pyobject::{PyObjectRef, PyResult};
+use crate::obj::objtype::PyClassRef;
+use crate::obj::{objbool, objbyteinner, objbytes, objfloat, objint, objstr, objtype};
+use crate::pyobject::{PyClassImpl, PyObjectRef, PyRef, PyResult, PyValue, TypeProtocol};
+use crate::types::create_type;
 use crate::VirtualMachine;
 
 #[derive(Debug)]
 struct FormatSpec {
     endianness: Endianness,
     codes: Vec<FormatCode>,
+    /// Number of bytes all the codes pack into, checked when parsing.
+    size: usize,
+}
+
+impl FormatSpec {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn arg_count(&self) -> usize {
+        self.codes.iter().map(FormatCode::arg_count).sum()
+    }
 }
 
 #[derive(Debug)]
@@ -37,9 +51,51 @@ enum Endianness {
 
 #[derive(Debug)]
 struct FormatCode {
+    repeat: usize,
     code: char,
 }
 
+impl FormatCode {
+    /// Number of bytes this code packs into, or None if that overflows.
+    fn size(&self) -> Option<usize> {
+        let item_size: usize = match self.code {
+            'x' | 's' | 'b' | 'B' | '?' => 1,
+            'h' | 'H' => 2,
+            'i' | 'I' | 'l' | 'L' | 'f' => 4,
+            'q' | 'Q' | 'd' => 8,
+            c => panic!("Unsupported format code {:?}", c),
+        };
+        item_size.checked_mul(self.repeat)
+    }
+
+    /// Number of values this code packs, since a repeated `s` is one string.
+    fn arg_count(&self) -> usize {
+        match self.code {
+            'x' => 0,
+            's' => 1,
+            _ => self.repeat,
+        }
+    }
+}
+
+/// Parse a format given as either str or bytes.
+fn get_format_spec(vm: &VirtualMachine, fmt: &PyObjectRef) -> PyResult<FormatSpec> {
+    let fmt_str = if objtype::isinstance(fmt, &vm.ctx.str_type()) {
+        objstr::get_value(fmt)
+    } else if objtype::isinstance(fmt, &vm.ctx.bytes_type()) {
+        objbytes::get_value(fmt)
+            .iter()
+            .map(|&b| char::from(b))
+            .collect()
+    } else {
+        return Err(vm.new_type_error(format!(
+            "Struct() argument 1 must be a str or bytes object, not {}",
+            fmt.class().name
+        )));
+    };
+    parse_format_string(fmt_str).map_err(|e| new_struct_error(vm, e))
+}
+
 fn parse_format_string(fmt: String) -> Result<FormatSpec, String> {
     let mut chars = fmt.chars().peekable();
 
@@ -49,7 +105,21 @@ fn parse_format_string(fmt: String) -> Result<FormatSpec, String> {
     // Now, analyze struct string furter:
     let codes = parse_format_codes(&mut chars)?;
 
-    Ok(FormatSpec { endianness, codes })
+    // Like CPython, sizes are limited to what an isize can hold, which is
+    // also the most a Vec can allocate.
+    let size = codes
+        .iter()
+        .try_fold(0usize, |total, code| {
+            code.size().and_then(|size| total.checked_add(size))
+        })
+        .filter(|&size| size <= isize::max_value() as usize)
+        .ok_or_else(|| "total struct size too long".to_string())?;
+
+    Ok(FormatSpec {
+        endianness,
+        codes,
+        size,
+    })
 }
 
 /// Parse endianness
@@ -90,14 +160,34 @@ where
     I: Iterator<Item = char>,
 {
     let mut codes = vec![];
-    for c in chars {
-        match c {
-            'b' | 'B' | 'h' | 'H' | 'i' | 'I' | 'l' | 'L' | 'q' | 'Q' | 'f' | 'd' => {
-                codes.push(FormatCode { code: c })
-            }
-            c => {
-                return Err(format!("Illegal format code {:?}", c));
+    while let Some(c) = chars.next() {
+        if c.is_ascii_whitespace() {
+            continue;
+        }
+
+        // An optional repeat count comes before the code itself
+        let (repeat, code) = match c.to_digit(10) {
+            Some(digit) => {
+                let mut repeat = digit as usize;
+                while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    chars.next();
+                    repeat = repeat
+                        .checked_mul(10)
+                        .and_then(|repeat| repeat.checked_add(digit as usize))
+                        .ok_or_else(|| "total struct size too long".to_string())?;
+                }
+                match chars.next() {
+                    Some(code) => (repeat, code),
+                    None => return Err("repeat count given without format specifier".to_string()),
+                }
             }
+            None => (1, c),
+        };
+
+        match code {
+            'x' | 's' | 'b' | 'B' | '?' | 'h' | 'H' | 'i' | 'I' | 'l' | 'L' | 'q' | 'Q' | 'f'
+            | 'd' => codes.push(FormatCode { repeat, code }),
+            _ => return Err("bad char in struct format".to_string()),
         }
     }
 
@@ -108,26 +198,49 @@ fn get_int(vm: &VirtualMachine, arg: &PyObjectRef) -> PyResult<BigInt> {
     objint::to_int(vm, arg, 10)
 }
 
+fn out_of_range(vm: &VirtualMachine) -> PyObjectRef {
+    new_struct_error(vm, "argument out of range".to_string())
+}
+
 fn pack_i8(vm: &VirtualMachine, arg: &PyObjectRef, data: &mut dyn Write) -> PyResult<()> {
-    let v = get_int(vm, arg)?.to_i8().unwrap();
+    let v = get_int(vm, arg)?.to_i8().ok_or_else(|| out_of_range(vm))?;
     data.write_i8(v).unwrap();
     Ok(())
 }
 
 fn pack_u8(vm: &VirtualMachine, arg: &PyObjectRef, data: &mut dyn Write) -> PyResult<()> {
-    let v = get_int(vm, arg)?.to_u8().unwrap();
+    let v = get_int(vm, arg)?.to_u8().ok_or_else(|| out_of_range(vm))?;
     data.write_u8(v).unwrap();
     Ok(())
 }
 
 fn pack_bool(vm: &VirtualMachine, arg: &PyObjectRef, data: &mut dyn Write) -> PyResult<()> {
-    if objtype::isinstance(&arg, &vm.ctx.bool_type()) {
-        let v = if objbool::get_value(arg) { 1 } else { 0 };
-        data.write_u8(v).unwrap();
-        Ok(())
+    let v = if objbool::boolval(vm, arg.clone())? {
+        1
     } else {
-        Err(vm.new_type_error("Expected boolean".to_string()))
+        0
+    };
+    data.write_u8(v).unwrap();
+    Ok(())
+}
+
+/// Pack a `s` code, padding the string with zeros or truncating it to fit.
+fn pack_string(
+    vm: &VirtualMachine,
+    arg: &PyObjectRef,
+    size: usize,
+    data: &mut dyn Write,
+) -> PyResult<()> {
+    if !objtype::isinstance(arg, &vm.ctx.bytes_type()) {
+        return Err(new_struct_error(
+            vm,
+            "argument for 's' must be a bytes object".to_string(),
+        ));
     }
+    let mut v = objbytes::get_value(arg).to_vec();
+    v.resize(size, 0);
+    data.write_all(&v).unwrap();
+    Ok(())
 }
 
 fn pack_i16<Endianness>(
@@ -138,7 +251,7 @@ fn pack_i16<Endianness>(
 where
     Endianness: byteorder::ByteOrder,
 {
-    let v = get_int(vm, arg)?.to_i16().unwrap();
+    let v = get_int(vm, arg)?.to_i16().ok_or_else(|| out_of_range(vm))?;
     data.write_i16::<Endianness>(v).unwrap();
     Ok(())
 }
@@ -151,7 +264,7 @@ fn pack_u16<Endianness>(
 where
     Endianness: byteorder::ByteOrder,
 {
-    let v = get_int(vm, arg)?.to_u16().unwrap();
+    let v = get_int(vm, arg)?.to_u16().ok_or_else(|| out_of_range(vm))?;
     data.write_u16::<Endianness>(v).unwrap();
     Ok(())
 }
@@ -164,7 +277,7 @@ fn pack_i32<Endianness>(
 where
     Endianness: byteorder::ByteOrder,
 {
-    let v = get_int(vm, arg)?.to_i32().unwrap();
+    let v = get_int(vm, arg)?.to_i32().ok_or_else(|| out_of_range(vm))?;
     data.write_i32::<Endianness>(v).unwrap();
     Ok(())
 }
@@ -177,7 +290,7 @@ fn pack_u32<Endianness>(
 where
     Endianness: byteorder::ByteOrder,
 {
-    let v = get_int(vm, arg)?.to_u32().unwrap();
+    let v = get_int(vm, arg)?.to_u32().ok_or_else(|| out_of_range(vm))?;
     data.write_u32::<Endianness>(v).unwrap();
     Ok(())
 }
@@ -190,7 +303,7 @@ fn pack_i64<Endianness>(
 where
     Endianness: byteorder::ByteOrder,
 {
-    let v = get_int(vm, arg)?.to_i64().unwrap();
+    let v = get_int(vm, arg)?.to_i64().ok_or_else(|| out_of_range(vm))?;
     data.write_i64::<Endianness>(v).unwrap();
     Ok(())
 }
@@ -203,7 +316,7 @@ fn pack_u64<Endianness>(
 where
     Endianness: byteorder::ByteOrder,
 {
-    let v = get_int(vm, arg)?.to_u64().unwrap();
+    let v = get_int(vm, arg)?.to_u64().ok_or_else(|| out_of_range(vm))?;
     data.write_u64::<Endianness>(v).unwrap();
     Ok(())
 }
@@ -272,23 +385,39 @@ where
 
 fn struct_pack(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     if args.args.is_empty() {
-        Err(vm.new_type_error(format!(
+        return Err(vm.new_type_error(format!(
             "Expected at least 1 argument (got: {})",
             args.args.len()
-        )))
-    } else {
-        let fmt_arg = args.args[0].clone();
-        if objtype::isinstance(&fmt_arg, &vm.ctx.str_type()) {
-            let fmt_str = objstr::get_value(&fmt_arg);
-
-            let format_spec = parse_format_string(fmt_str).map_err(|e| vm.new_value_error(e))?;;
-
-            if format_spec.codes.len() + 1 == args.args.len() {
-                // Create data vector:
-                let mut data = Vec::<u8>::new();
-                // Loop over all opcodes:
-                for (code, arg) in format_spec.codes.iter().zip(args.args.iter().skip(1)) {
-                    debug!("code: {:?}", code);
+        )));
+    }
+
+    let format_spec = get_format_spec(vm, &args.args[0])?;
+    pack_data(vm, &format_spec, &args.args[1..])
+}
+
+fn pack_data(vm: &VirtualMachine, format_spec: &FormatSpec, args: &[PyObjectRef]) -> PyResult {
+    if format_spec.arg_count() != args.len() {
+        return Err(new_struct_error(
+            vm,
+            format!(
+                "pack expected {} items for packing (got {})",
+                format_spec.arg_count(),
+                args.len()
+            ),
+        ));
+    }
+
+    // Create data vector:
+    let mut data = Vec::<u8>::with_capacity(format_spec.size());
+    let mut args = args.iter();
+    // Loop over all opcodes:
+    for code in format_spec.codes.iter() {
+        debug!("code: {:?}", code);
+        match code.code {
+            'x' => data.resize(data.len() + code.repeat, 0),
+            's' => pack_string(vm, args.next().unwrap(), code.repeat, &mut data)?,
+            _ => {
+                for arg in args.by_ref().take(code.repeat) {
                     match format_spec.endianness {
                         Endianness::Little => {
                             pack_item::<byteorder::LittleEndian>(vm, code, arg, &mut data)?
@@ -304,19 +433,11 @@ fn struct_pack(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
                         }
                     }
                 }
-
-                Ok(vm.ctx.new_bytes(data))
-            } else {
-                Err(vm.new_type_error(format!(
-                    "Expected {} arguments (got: {})",
-                    format_spec.codes.len() + 1,
-                    args.args.len()
-                )))
             }
-        } else {
-            Err(vm.new_type_error("First argument must be of str type".to_string()))
         }
     }
+
+    Ok(vm.ctx.new_bytes(data))
 }
 
 fn unpack_i8(vm: &VirtualMachine, rdr: &mut dyn Read) -> PyResult {
@@ -420,37 +541,118 @@ where
     }
 }
 
-fn struct_unpack(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
-    arg_check!(
-        vm,
-        args,
-        required = [
-            (fmt, Some(vm.ctx.str_type())),
-            (buffer, Some(vm.ctx.bytes_type()))
-        ]
-    );
-
-    let fmt_str = objstr::get_value(&fmt);
+fn struct_unpack(fmt: PyObjectRef, buffer: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let format_spec = get_format_spec(vm, &fmt)?;
+    unpack_data(vm, &format_spec, &buffer)
+}
 
-    let format_spec = parse_format_string(fmt_str).map_err(|e| vm.new_value_error(e))?;
-    let data = objbytes::get_value(buffer).to_vec();
+fn unpack_data(vm: &VirtualMachine, format_spec: &FormatSpec, buffer: &PyObjectRef) -> PyResult {
+    let data = objbyteinner::try_as_byte(buffer).ok_or_else(|| {
+        vm.new_type_error(format!(
+            "a bytes-like object is required, not '{}'",
+            buffer.class().name
+        ))
+    })?;
+    if data.len() != format_spec.size() {
+        return Err(new_struct_error(
+            vm,
+            format!("unpack requires a buffer of {} bytes", format_spec.size()),
+        ));
+    }
     let mut rdr = Cursor::new(data);
 
     let mut items = vec![];
-    for code in format_spec.codes {
+    for code in format_spec.codes.iter() {
         debug!("unpack code: {:?}", code);
-        let item = match format_spec.endianness {
-            Endianness::Little => unpack_code::<byteorder::LittleEndian>(vm, &code, &mut rdr)?,
-            Endianness::Big => unpack_code::<byteorder::BigEndian>(vm, &code, &mut rdr)?,
-            Endianness::Network => unpack_code::<byteorder::NetworkEndian>(vm, &code, &mut rdr)?,
-            Endianness::Native => unpack_code::<byteorder::NativeEndian>(vm, &code, &mut rdr)?,
-        };
-        items.push(item);
+        match code.code {
+            'x' => rdr.set_position(rdr.position() + code.repeat as u64),
+            's' => {
+                let mut v = vec![0; code.repeat];
+                rdr.read_exact(&mut v).unwrap();
+                items.push(vm.ctx.new_bytes(v));
+            }
+            _ => {
+                for _ in 0..code.repeat {
+                    let item = match format_spec.endianness {
+                        Endianness::Little => {
+                            unpack_code::<byteorder::LittleEndian>(vm, code, &mut rdr)?
+                        }
+                        Endianness::Big => unpack_code::<byteorder::BigEndian>(vm, code, &mut rdr)?,
+                        Endianness::Network => {
+                            unpack_code::<byteorder::NetworkEndian>(vm, code, &mut rdr)?
+                        }
+                        Endianness::Native => {
+                            unpack_code::<byteorder::NativeEndian>(vm, code, &mut rdr)?
+                        }
+                    };
+                    items.push(item);
+                }
+            }
+        }
     }
 
     Ok(vm.ctx.new_tuple(items))
 }
 
+/// A compiled format, for packing and unpacking with it repeatedly.
+#[pyclass(name = "Struct")]
+#[derive(Debug)]
+struct PyStruct {
+    format: String,
+    format_spec: FormatSpec,
+}
+
+impl PyValue for PyStruct {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("struct", "Struct")
+    }
+}
+
+#[pyimpl]
+impl PyStruct {
+    #[pymethod(name = "__new__")]
+    fn new(cls: PyClassRef, fmt: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyRef<Self>> {
+        let format_spec = get_format_spec(vm, &fmt)?;
+        let format = if objtype::isinstance(&fmt, &vm.ctx.str_type()) {
+            objstr::get_value(&fmt)
+        } else {
+            objbytes::get_value(&fmt)
+                .iter()
+                .map(|&b| char::from(b))
+                .collect()
+        };
+        PyStruct {
+            format,
+            format_spec,
+        }
+        .into_ref_with_type(vm, cls)
+    }
+
+    #[pyproperty(name = "format")]
+    fn format(&self, _vm: &VirtualMachine) -> String {
+        self.format.clone()
+    }
+
+    #[pyproperty(name = "size")]
+    fn size(&self, _vm: &VirtualMachine) -> usize {
+        self.format_spec.size()
+    }
+
+    #[pymethod(name = "pack")]
+    fn pack(&self, args: PyFuncArgs, vm: &VirtualMachine) -> PyResult {
+        pack_data(vm, &self.format_spec, &args.args)
+    }
+
+    #[pymethod(name = "unpack")]
+    fn unpack(&self, buffer: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        unpack_data(vm, &self.format_spec, &buffer)
+    }
+}
+
+fn struct_calcsize(fmt: PyObjectRef, vm: &VirtualMachine) -> PyResult<usize> {
+    Ok(get_format_spec(vm, &fmt)?.size())
+}
+
 fn unpack_code<Endianness>(vm: &VirtualMachine, code: &FormatCode, rdr: &mut dyn Read) -> PyResult
 where
     Endianness: byteorder::ByteOrder,
@@ -476,11 +678,24 @@ where
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let ctx = &vm.ctx;
 
-    let struct_error = ctx.new_class("struct.error", ctx.object());
+    let struct_error = create_type(
+        "error",
+        &ctx.types.type_type,
+        &ctx.exceptions.exception_type,
+    );
 
     py_module!(vm, "struct", {
         "pack" => ctx.new_rustfunc(struct_pack),
         "unpack" => ctx.new_rustfunc(struct_unpack),
+        "calcsize" => ctx.new_rustfunc(struct_calcsize),
+        "Struct" => PyStruct::make_class(ctx),
         "error" => struct_error,
     })
 }
+
+fn new_struct_error(vm: &VirtualMachine, msg: String) -> PyObjectRef {
+    match vm.try_class("struct", "error") {
+        Ok(struct_error) => vm.new_exception(struct_error, msg),
+        Err(err) => err,
+    }
+}
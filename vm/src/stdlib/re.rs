@@ -4,7 +4,7 @@
  * This module fits the python re interface onto the rust regular expression
  * system.
  */
-use regex::bytes::{Captures, Regex, RegexBuilder};
+use regex::bytes::{Regex, RegexBuilder};
 
 use std::fmt;
 
@@ -12,7 +12,7 @@ use crate::function::{Args, OptionalArg};
 use crate::obj::objint::{PyInt, PyIntRef};
 use crate::obj::objstr::{PyString, PyStringRef};
 use crate::obj::objtype::PyClassRef;
-use crate::pyobject::{PyClassImpl, PyObjectRef, PyResult, PyValue, TryFromObject};
+use crate::pyobject::{ItemProtocol, PyClassImpl, PyObjectRef, PyResult, PyValue, TryFromObject};
 use crate::vm::VirtualMachine;
 use num_traits::{Signed, ToPrimitive};
 
@@ -21,6 +21,7 @@ use num_traits::{Signed, ToPrimitive};
 struct PyPattern {
     regex: Regex,
     pattern: String,
+    flags: PyRegexFlags,
 }
 
 const IGNORECASE: usize = 2;
@@ -32,7 +33,7 @@ const VERBOSE: usize = 64;
 const DEBUG: usize = 128;
 const ASCII: usize = 256;
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy)]
 struct PyRegexFlags {
     ignorecase: bool,
     #[allow(unused)]
@@ -73,6 +74,8 @@ impl PyValue for PyPattern {
 struct PyMatch {
     haystack: PyStringRef,
     captures: Vec<Option<(usize, usize)>>,
+    /// The name of each group, if it has one.
+    names: Vec<Option<String>>,
 }
 
 impl fmt::Debug for PyMatch {
@@ -98,7 +101,20 @@ fn re_match(
 ) -> PyResult {
     let flags = extract_flags(flags);
     let regex = make_regex(vm, &pattern.value, flags)?;
-    do_match(vm, &regex, string)
+    let range = (0, string.as_str().len());
+    do_match(vm, &regex, string, range)
+}
+
+fn re_fullmatch(
+    pattern: PyStringRef,
+    string: PyStringRef,
+    flags: OptionalArg<PyIntRef>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let flags = extract_flags(flags);
+    let regex = make_regex(vm, &pattern.value, flags)?;
+    let range = (0, string.as_str().len());
+    do_fullmatch(vm, &regex, string, range)
 }
 
 fn re_search(
@@ -109,7 +125,8 @@ fn re_search(
 ) -> PyResult {
     let flags = extract_flags(flags);
     let regex = make_regex(vm, &pattern.value, flags)?;
-    do_search(vm, &regex, string)
+    let range = (0, string.as_str().len());
+    do_search(vm, &regex, string, range)
 }
 
 fn re_sub(
@@ -156,32 +173,103 @@ fn do_sub(
     search_text: PyStringRef,
     limit: usize,
 ) -> PyResult {
-    let out = pattern.regex.replacen(
-        search_text.as_str().as_bytes(),
-        limit,
-        repl.as_str().as_bytes(),
-    );
+    let repl = translate_replacement(repl.as_str(), vm)?;
+    let out = pattern
+        .regex
+        .replacen(search_text.as_str().as_bytes(), limit, repl.as_bytes());
     let out = String::from_utf8_lossy(&out).into_owned();
     Ok(vm.new_str(out))
 }
 
-fn do_match(vm: &VirtualMachine, pattern: &PyPattern, search_text: PyStringRef) -> PyResult {
-    // I really wish there was a better way to do this; I don't think there is
-    let mut regex = r"\A".to_owned();
-    regex.push_str(pattern.regex.as_str());
-    let regex = Regex::new(&regex).unwrap();
+/// Recompile a pattern with the same flags, anchored at the start and
+/// optionally at the end of the text.
+fn anchor_pattern(vm: &VirtualMachine, pattern: &PyPattern, end: &str) -> PyResult<PyPattern> {
+    // In verbose mode a trailing comment would swallow the closing paren.
+    let newline = if pattern.flags.verbose { "\n" } else { "" };
+    make_regex(
+        vm,
+        &format!(r"\A(?:{}{}){}", pattern.pattern, newline, end),
+        pattern.flags,
+    )
+}
 
-    match regex.captures(search_text.as_str().as_bytes()) {
-        None => Ok(vm.get_none()),
-        Some(captures) => Ok(create_match(vm, search_text.clone(), captures)),
-    }
+/// The byte offsets of the part of `text` that the `pos` and `endpos`
+/// arguments, which count characters, select.
+fn byte_range(text: &str, pos: OptionalArg<isize>, endpos: OptionalArg<isize>) -> (usize, usize) {
+    let offset = |index: isize| {
+        if index <= 0 {
+            0
+        } else {
+            text.char_indices()
+                .nth(index as usize)
+                .map_or(text.len(), |(offset, _)| offset)
+        }
+    };
+    let start = pos.into_option().map_or(0, offset);
+    let end = endpos.into_option().map_or(text.len(), offset);
+    (start, end)
 }
 
-fn do_search(vm: &VirtualMachine, regex: &PyPattern, search_text: PyStringRef) -> PyResult {
-    match regex.regex.captures(search_text.as_str().as_bytes()) {
-        None => Ok(vm.get_none()),
-        Some(captures) => Ok(create_match(vm, search_text.clone(), captures)),
+/// Look for `regex` in a byte range of `text`, reporting positions in the
+/// whole text. An anchored pattern sees the range as if it were the whole
+/// text; otherwise `^` still only matches at the real beginning.
+fn captures_in(
+    vm: &VirtualMachine,
+    regex: &PyPattern,
+    text: PyStringRef,
+    (start, end): (usize, usize),
+    anchored: bool,
+) -> PyResult {
+    if start > end {
+        return Ok(vm.get_none());
     }
+    let bytes = text.as_str().as_bytes();
+    let (haystack, from, offset) = if anchored {
+        (&bytes[start..end], 0, start)
+    } else {
+        (&bytes[..end], start, 0)
+    };
+    let mut locations = regex.regex.capture_locations();
+    if regex
+        .regex
+        .captures_read_at(&mut locations, haystack, from)
+        .is_none()
+    {
+        return Ok(vm.get_none());
+    }
+    let captures = (0..locations.len())
+        .map(|i| locations.get(i).map(|(s, e)| (s + offset, e + offset)))
+        .collect();
+    Ok(create_match(vm, regex, text.clone(), captures))
+}
+
+fn do_match(
+    vm: &VirtualMachine,
+    pattern: &PyPattern,
+    search_text: PyStringRef,
+    range: (usize, usize),
+) -> PyResult {
+    let regex = anchor_pattern(vm, pattern, "")?;
+    captures_in(vm, &regex, search_text, range, true)
+}
+
+fn do_fullmatch(
+    vm: &VirtualMachine,
+    pattern: &PyPattern,
+    search_text: PyStringRef,
+    range: (usize, usize),
+) -> PyResult {
+    let regex = anchor_pattern(vm, pattern, r"\z")?;
+    captures_in(vm, &regex, search_text, range, true)
+}
+
+fn do_search(
+    vm: &VirtualMachine,
+    regex: &PyPattern,
+    search_text: PyStringRef,
+    range: (usize, usize),
+) -> PyResult {
+    captures_in(vm, regex, search_text, range, false)
 }
 
 fn do_findall(vm: &VirtualMachine, pattern: &PyPattern, search_text: PyStringRef) -> PyResult {
@@ -264,13 +352,133 @@ fn do_split(
     Ok(vm.ctx.new_list(split))
 }
 
+/// Rewrite the Python syntax that the regex crate spells differently.
+fn translate_pattern(pattern: &str) -> String {
+    let mut translated = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+    let mut in_set = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                translated.push(c);
+                match chars.next() {
+                    // Python spells "only at the end of the text" `\Z`.
+                    Some('Z') if !in_set => translated.push('z'),
+                    Some(c) => translated.push(c),
+                    None => {}
+                }
+            }
+            '[' if !in_set => {
+                in_set = true;
+                translated.push(c);
+                if chars.peek() == Some(&'^') {
+                    translated.push(chars.next().unwrap());
+                }
+                // A leading `]` is part of the set rather than its end.
+                if chars.peek() == Some(&']') {
+                    chars.next();
+                    translated.push_str("\\]");
+                }
+            }
+            ']' if in_set => {
+                in_set = false;
+                translated.push(c);
+            }
+            // Plain characters in a Python set, but nested sets and set
+            // operations for the regex crate.
+            '[' | '&' | '~' if in_set => {
+                translated.push('\\');
+                translated.push(c);
+            }
+            c => translated.push(c),
+        }
+    }
+    translated
+}
+
+/// Rewrite a Python replacement template, with its `\1` and `\g<name>`
+/// group references, into the regex crate's `${1}` and `${name}`.
+fn translate_replacement(repl: &str, vm: &VirtualMachine) -> PyResult<String> {
+    let mut translated = String::with_capacity(repl.len());
+    let mut chars = repl.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' {
+            translated.push_str("$$");
+            continue;
+        } else if c != '\\' {
+            translated.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('0') => {
+                let mut code = 0;
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                translated.push(std::char::from_u32(code).unwrap());
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let mut group = c.to_string();
+                if let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() {
+                        group.push(c);
+                        chars.next();
+                    }
+                }
+                translated.push_str(&format!("${{{}}}", group));
+            }
+            Some('g') => {
+                if chars.next() != Some('<') {
+                    return Err(vm.new_value_error("missing < in group reference".to_string()));
+                }
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('>') => break,
+                        Some(c) => name.push(c),
+                        None => {
+                            return Err(
+                                vm.new_value_error("missing >, unterminated name".to_string())
+                            )
+                        }
+                    }
+                }
+                translated.push_str(&format!("${{{}}}", name));
+            }
+            Some('n') => translated.push('\n'),
+            Some('r') => translated.push('\r'),
+            Some('t') => translated.push('\t'),
+            Some('f') => translated.push('\x0c'),
+            Some('v') => translated.push('\x0b'),
+            Some('a') => translated.push('\x07'),
+            Some('b') => translated.push('\x08'),
+            Some('\\') => translated.push('\\'),
+            Some(c) if c.is_ascii_alphabetic() => {
+                return Err(vm.new_value_error(format!("bad escape \\{}", c)));
+            }
+            Some(c) => {
+                translated.push('\\');
+                translated.push(c);
+            }
+            None => return Err(vm.new_value_error("bad escape (end of pattern)".to_string())),
+        }
+    }
+    Ok(translated)
+}
+
 fn make_regex(vm: &VirtualMachine, pattern: &str, flags: PyRegexFlags) -> PyResult<PyPattern> {
     let unicode = if flags.unicode && flags.ascii {
         return Err(vm.new_value_error("ASCII and UNICODE flags are incompatible".to_string()));
     } else {
         !flags.ascii
     };
-    let r = RegexBuilder::new(pattern)
+    let r = RegexBuilder::new(&translate_pattern(pattern))
         .case_insensitive(flags.ignorecase)
         .multi_line(flags.multiline)
         .dot_matches_new_line(flags.dotall)
@@ -284,16 +492,29 @@ fn make_regex(vm: &VirtualMachine, pattern: &str, flags: PyRegexFlags) -> PyResu
     Ok(PyPattern {
         regex: r,
         pattern: pattern.to_string(),
+        flags,
     })
 }
 
 /// Take a found regular expression and convert it to proper match object.
-fn create_match(vm: &VirtualMachine, haystack: PyStringRef, captures: Captures) -> PyObjectRef {
-    let captures = captures
-        .iter()
-        .map(|opt| opt.map(|m| (m.start(), m.end())))
+fn create_match(
+    vm: &VirtualMachine,
+    pattern: &PyPattern,
+    haystack: PyStringRef,
+    captures: Vec<Option<(usize, usize)>>,
+) -> PyObjectRef {
+    let names = pattern
+        .regex
+        .capture_names()
+        .map(|name| name.map(str::to_string))
         .collect();
-    PyMatch { haystack, captures }.into_ref(vm).into_object()
+    PyMatch {
+        haystack,
+        captures,
+        names,
+    }
+    .into_ref(vm)
+    .into_object()
 }
 
 fn extract_flags(flags: OptionalArg<PyIntRef>) -> PyRegexFlags {
@@ -323,20 +544,47 @@ fn re_purge(_vm: &VirtualMachine) {}
 #[pyimpl]
 impl PyPattern {
     #[pymethod(name = "match")]
-    fn match_(&self, text: PyStringRef, vm: &VirtualMachine) -> PyResult {
-        do_match(vm, self, text)
+    fn match_(
+        &self,
+        text: PyStringRef,
+        pos: OptionalArg<isize>,
+        endpos: OptionalArg<isize>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let range = byte_range(text.as_str(), pos, endpos);
+        do_match(vm, self, text, range)
+    }
+
+    #[pymethod]
+    fn fullmatch(
+        &self,
+        text: PyStringRef,
+        pos: OptionalArg<isize>,
+        endpos: OptionalArg<isize>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let range = byte_range(text.as_str(), pos, endpos);
+        do_fullmatch(vm, self, text, range)
     }
 
     #[pymethod(name = "search")]
-    fn search(&self, text: PyStringRef, vm: &VirtualMachine) -> PyResult {
-        do_search(vm, self, text)
+    fn search(
+        &self,
+        text: PyStringRef,
+        pos: OptionalArg<isize>,
+        endpos: OptionalArg<isize>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let range = byte_range(text.as_str(), pos, endpos);
+        do_search(vm, self, text, range)
     }
 
     #[pymethod(name = "sub")]
     fn sub(&self, repl: PyStringRef, text: PyStringRef, vm: &VirtualMachine) -> PyResult {
+        let repl = translate_replacement(repl.as_str(), vm)?;
         let replaced_text = self
             .regex
-            .replace_all(text.value.as_bytes(), repl.as_str().as_bytes());
+            .replace_all(text.value.as_bytes(), repl.as_bytes());
         let replaced_text = String::from_utf8_lossy(&replaced_text).into_owned();
         Ok(vm.ctx.new_str(replaced_text))
     }
@@ -401,7 +649,14 @@ impl PyMatch {
                     Some(Some(bounds)) => Ok(Some(*bounds)),
                 }
             },
-            _s @ PyString => unimplemented!(),
+            s @ PyString => {
+                let i = self
+                    .names
+                    .iter()
+                    .position(|name| name.as_ref().map(String::as_str) == Some(s.as_str()))
+                    .ok_or_else(|| vm.new_index_error("no such group".to_owned()))?;
+                Ok(self.captures[i])
+            },
             _ => Err(vm.new_index_error("No such group".to_owned())),
         )
     }
@@ -446,6 +701,20 @@ impl PyMatch {
             .collect();
         vm.ctx.new_tuple(groups)
     }
+
+    #[pymethod]
+    fn groupdict(&self, default: OptionalArg, vm: &VirtualMachine) -> PyResult {
+        let default = default.unwrap_or_else(|| vm.get_none());
+        let dict = vm.ctx.new_dict();
+        for (name, capture) in self.names.iter().zip(self.captures.iter()) {
+            if let Some(name) = name {
+                let group =
+                    capture.map_or_else(|| default.clone(), |bounds| self.subgroup(bounds, vm));
+                dict.set_item(name.as_str(), group, vm)?;
+            }
+        }
+        Ok(dict.into_object())
+    }
 }
 
 /// Create the python `re` module with all its members.
@@ -461,6 +730,7 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "purge" => ctx.new_rustfunc(re_purge),
         "Match" => match_type,
         "match" => ctx.new_rustfunc(re_match),
+        "fullmatch" => ctx.new_rustfunc(re_fullmatch),
         "Pattern" => pattern_type,
         "search" => ctx.new_rustfunc(re_search),
         "sub" => ctx.new_rustfunc(re_sub),
@@ -0,0 +1,132 @@
+/*
+ * Cryptographically strong random numbers for tokens, passwords and the like.
+ *
+ * Every value is drawn from the operating system's generator through
+ * `rand::rngs::OsRng`: getrandom(2) or /dev/urandom on Linux, the
+ * equivalent system calls on the other Unixes, and RtlGenRandom on Windows.
+ * Unlike the `random` module, nothing here uses a seedable generator.
+ */
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::Signed;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::builtins::builtin_len;
+use crate::function::OptionalArg;
+use crate::obj::objint::PyIntRef;
+use crate::pyobject::{PyObjectRef, PyResult};
+use crate::vm::VirtualMachine;
+
+use super::operator::operator_compare_digest;
+
+/// Number of bytes in a token when the caller doesn't ask for a size.
+const DEFAULT_ENTROPY: usize = 32;
+
+const URLSAFE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn os_random_bytes(n: usize, vm: &VirtualMachine) -> PyResult<Vec<u8>> {
+    let mut buf = vec![0; n];
+    OsRng::new()
+        .and_then(|mut rng| rng.try_fill_bytes(&mut buf))
+        .map_err(|err| vm.new_os_error(format!("no source of randomness: {}", err)))?;
+    Ok(buf)
+}
+
+/// A random non-negative integer with `k` random bits.
+fn random_bits(k: usize, vm: &VirtualMachine) -> PyResult<BigUint> {
+    // Round up to whole bytes, then drop the surplus low bits.
+    let surplus = (8 - k % 8) % 8;
+    let bytes = os_random_bytes((k + surplus) / 8, vm)?;
+    Ok(BigUint::from_bytes_be(&bytes) >> surplus)
+}
+
+fn token_size(nbytes: OptionalArg<Option<usize>>) -> usize {
+    nbytes
+        .into_option()
+        .and_then(|n| n)
+        .unwrap_or(DEFAULT_ENTROPY)
+}
+
+/// Base64 with the URL and filename safe alphabet, without padding.
+fn urlsafe_b64encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len() / 3 * 4 + 3);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | u32::from(b) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            let index = (group >> (18 - 6 * i)) & 0x3f;
+            encoded.push(char::from(URLSAFE_ALPHABET[index as usize]));
+        }
+    }
+    encoded
+}
+
+fn secrets_token_bytes(nbytes: OptionalArg<Option<usize>>, vm: &VirtualMachine) -> PyResult {
+    Ok(vm.ctx.new_bytes(os_random_bytes(token_size(nbytes), vm)?))
+}
+
+fn secrets_token_hex(nbytes: OptionalArg<Option<usize>>, vm: &VirtualMachine) -> PyResult<String> {
+    Ok(hex::encode(os_random_bytes(token_size(nbytes), vm)?))
+}
+
+fn secrets_token_urlsafe(
+    nbytes: OptionalArg<Option<usize>>,
+    vm: &VirtualMachine,
+) -> PyResult<String> {
+    Ok(urlsafe_b64encode(&os_random_bytes(token_size(nbytes), vm)?))
+}
+
+fn secrets_randbits(k: usize, vm: &VirtualMachine) -> PyResult<BigInt> {
+    if k == 0 {
+        return Err(vm.new_value_error("number of bits must be greater than zero".to_string()));
+    }
+    Ok(BigInt::from_biguint(Sign::Plus, random_bits(k, vm)?))
+}
+
+fn randbelow(upper: &BigInt, vm: &VirtualMachine) -> PyResult<BigInt> {
+    if !upper.is_positive() {
+        return Err(vm.new_value_error("Upper bound must be positive.".to_string()));
+    }
+    // Draw just enough bits and retry until the value is in range, so that
+    // every result is equally likely.
+    let upper = upper.to_biguint().unwrap();
+    let k = upper.bits();
+    loop {
+        let value = random_bits(k, vm)?;
+        if value < upper {
+            return Ok(BigInt::from_biguint(Sign::Plus, value));
+        }
+    }
+}
+
+fn secrets_randbelow(exclusive_upper_bound: PyIntRef, vm: &VirtualMachine) -> PyResult<BigInt> {
+    randbelow(exclusive_upper_bound.as_bigint(), vm)
+}
+
+fn secrets_choice(seq: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+    let len = builtin_len(seq.clone(), vm)?;
+    if len == 0 {
+        return Err(vm.new_index_error("Cannot choose from an empty sequence".to_string()));
+    }
+    let index = randbelow(&BigInt::from(len), vm)?;
+    vm.call_method(&seq, "__getitem__", vec![vm.ctx.new_int(index)])
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    py_module!(vm, "secrets", {
+        "DEFAULT_ENTROPY" => ctx.new_int(DEFAULT_ENTROPY),
+        "token_bytes" => ctx.new_rustfunc(secrets_token_bytes),
+        "token_hex" => ctx.new_rustfunc(secrets_token_hex),
+        "token_urlsafe" => ctx.new_rustfunc(secrets_token_urlsafe),
+        "randbits" => ctx.new_rustfunc(secrets_randbits),
+        "randbelow" => ctx.new_rustfunc(secrets_randbelow),
+        "choice" => ctx.new_rustfunc(secrets_choice),
+        "compare_digest" => ctx.new_rustfunc(operator_compare_digest),
+    })
+}
@@ -5,7 +5,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use arr_macro::arr;
 
-#[cfg(unix)]
+#[cfg(all(unix, not(target_os = "redox")))]
 use nix::unistd::alarm as sig_alarm;
 
 use libc;
@@ -30,6 +30,44 @@ extern "C" fn run_signal(signum: i32) {
     TRIGGERS[signum as usize].store(true, Ordering::Relaxed);
 }
 
+/// Mark a signal as received, so that its Python handler runs at the next
+/// check, just like when the OS delivers it.
+fn trigger_signal(signum: i32) {
+    run_signal(signum);
+}
+
+/// Handle a Ctrl-C that was caught before it became a signal, such as by a
+/// line editor. By default this raises KeyboardInterrupt.
+pub fn interrupt(vm: &VirtualMachine) -> PyResult<()> {
+    trigger_signal(libc::SIGINT);
+    check_signals(vm)
+}
+
+/// Install an OS level handler, returning the previous one.
+#[cfg(not(target_os = "redox"))]
+fn set_os_handler(signalnum: i32, sig_handler: libc::sighandler_t) -> libc::sighandler_t {
+    let old = unsafe { libc::signal(signalnum, sig_handler) };
+    #[cfg(unix)]
+    {
+        extern "C" {
+            fn siginterrupt(sig: i32, flag: i32);
+        }
+        if old != SIG_ERR {
+            unsafe {
+                siginterrupt(signalnum, 1);
+            }
+        }
+    }
+    old
+}
+
+// Redox doesn't deliver signals to us; handlers are only kept track of, and
+// run by raise_signal().
+#[cfg(target_os = "redox")]
+fn set_os_handler(_signalnum: i32, _sig_handler: libc::sighandler_t) -> libc::sighandler_t {
+    SIG_DFL
+}
+
 fn assert_in_range(signum: i32, vm: &VirtualMachine) -> PyResult<()> {
     if (1..NSIG as i32).contains(&signum) {
         Ok(())
@@ -54,19 +92,10 @@ fn signal(signalnum: i32, handler: PyObjectRef, vm: &VirtualMachine) -> PyResult
     };
     check_signals(vm)?;
 
-    let old = unsafe { libc::signal(signalnum, sig_handler) };
+    let old = set_os_handler(signalnum, sig_handler);
     if old == SIG_ERR {
         return Err(vm.new_os_error("Failed to set signal".to_string()));
     }
-    #[cfg(unix)]
-    {
-        extern "C" {
-            fn siginterrupt(sig: i32, flag: i32);
-        }
-        unsafe {
-            siginterrupt(signalnum, 1);
-        }
-    }
 
     let mut old_handler = handler;
     std::mem::swap(
@@ -81,7 +110,23 @@ fn getsignal(signalnum: i32, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
     Ok(vm.signal_handlers.borrow()[signalnum as usize].clone())
 }
 
-#[cfg(unix)]
+fn raise_signal(signalnum: i32, vm: &VirtualMachine) -> PyResult<()> {
+    #[cfg(not(target_os = "redox"))]
+    {
+        if unsafe { libc::raise(signalnum) } != 0 {
+            return Err(vm.new_os_error("Failed to raise signal".to_string()));
+        }
+    }
+    #[cfg(target_os = "redox")]
+    {
+        assert_in_range(signalnum, vm)?;
+        trigger_signal(signalnum);
+    }
+    // Run the handler right away, as CPython does.
+    check_signals(vm)
+}
+
+#[cfg(all(unix, not(target_os = "redox")))]
 fn alarm(time: u32, _vm: &VirtualMachine) -> u32 {
     let prev_time = if time == 0 {
         sig_alarm::cancel()
@@ -99,9 +144,19 @@ pub fn check_signals(vm: &VirtualMachine) -> PyResult<()> {
     for (signum, trigger) in TRIGGERS.iter().enumerate().skip(1) {
         let triggerd = trigger.swap(false, Ordering::Relaxed);
         if triggerd {
-            let handler = &vm.signal_handlers.borrow()[signum];
-            if vm.is_callable(handler) {
-                vm.invoke(handler, vec![vm.new_int(signum), vm.get_none()])?;
+            // The handler may replace itself, so don't hold on to the table.
+            let handler = vm.signal_handlers.borrow()[signum].clone();
+            if vm.is_callable(&handler) {
+                let frame = match vm.current_frame() {
+                    Some(frame) => frame.clone().into_object(),
+                    None => vm.get_none(),
+                };
+                if let Err(err) = vm.invoke(&handler, vec![vm.new_int(signum), frame]) {
+                    // Leave the signals that haven't been handled for the
+                    // next check.
+                    ANY_TRIGGERED.store(true, Ordering::Relaxed);
+                    return Err(err);
+                }
             }
         }
     }
@@ -123,6 +178,8 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let module = py_module!(vm, "signal", {
         "signal" => ctx.new_rustfunc(signal),
         "getsignal" => ctx.new_rustfunc(getsignal),
+        "raise_signal" => ctx.new_rustfunc(raise_signal),
+        "NSIG" => ctx.new_int(NSIG),
         "SIG_DFL" => sig_dfl.clone(),
         "SIG_IGN" => sig_ign.clone(),
         "SIGABRT" => ctx.new_int(libc::SIGABRT as u8),
@@ -136,10 +193,7 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     extend_module_platform_specific(vm, &module);
 
     for signum in 1..NSIG {
-        let handler = unsafe { libc::signal(signum as i32, SIG_IGN) };
-        if handler != SIG_ERR {
-            unsafe { libc::signal(signum as i32, handler) };
-        }
+        let handler = get_os_handler(signum as i32);
         let py_handler = if handler == SIG_DFL {
             sig_dfl.clone()
         } else if handler == SIG_IGN {
@@ -155,12 +209,30 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     module
 }
 
+#[cfg(not(target_os = "redox"))]
+fn get_os_handler(signalnum: i32) -> libc::sighandler_t {
+    let handler = unsafe { libc::signal(signalnum, SIG_IGN) };
+    if handler != SIG_ERR {
+        unsafe { libc::signal(signalnum, handler) };
+    }
+    handler
+}
+
+#[cfg(target_os = "redox")]
+fn get_os_handler(_signalnum: i32) -> libc::sighandler_t {
+    SIG_DFL
+}
+
 #[cfg(unix)]
 fn extend_module_platform_specific(vm: &VirtualMachine, module: &PyObjectRef) {
     let ctx = &vm.ctx;
 
+    #[cfg(not(target_os = "redox"))]
     extend_module!(vm, module, {
         "alarm" => ctx.new_rustfunc(alarm),
+    });
+
+    extend_module!(vm, module, {
         "SIGHUP" => ctx.new_int(libc::SIGHUP as u8),
         "SIGQUIT" => ctx.new_int(libc::SIGQUIT as u8),
         "SIGTRAP" => ctx.new_int(libc::SIGTRAP as u8),
@@ -2,7 +2,7 @@ use std::cell::RefCell;
 use std::io;
 use std::io::Read;
 use std::io::Write;
-use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
 use std::time::Duration;
 
 #[cfg(all(unix, not(target_os = "redox")))]
@@ -12,8 +12,9 @@ use gethostname::gethostname;
 
 use byteorder::{BigEndian, ByteOrder};
 
-use crate::function::PyFuncArgs;
+use crate::function::{OptionalArg, PyFuncArgs};
 use crate::obj::objbytes::PyBytesRef;
+use crate::obj::objfloat;
 use crate::obj::objint::PyIntRef;
 use crate::obj::objstr::PyStringRef;
 use crate::obj::objtuple::PyTupleRef;
@@ -21,11 +22,17 @@ use crate::pyobject::{PyObjectRef, PyRef, PyResult, PyValue, TryFromObject};
 use crate::vm::VirtualMachine;
 
 use crate::obj::objtype::PyClassRef;
+use crate::stdlib::os::convert_io_error;
 #[cfg(unix)]
 use crate::stdlib::os::convert_nix_error;
 use num_bigint::Sign;
 use num_traits::ToPrimitive;
 
+const IPPROTO_TCP: i32 = 6;
+const IPPROTO_UDP: i32 = 17;
+
+const AI_PASSIVE: i32 = 1;
+
 #[derive(Debug, Copy, Clone)]
 enum AddressFamily {
     Unix = 1,
@@ -132,18 +139,44 @@ impl Connection {
         }
     }
 
-    fn settimeout(&mut self, duration: Duration) -> io::Result<()> {
+    fn settimeout(&mut self, duration: Option<Duration>) -> io::Result<()> {
         match self {
             // net
             Connection::TcpListener(_con) => Ok(()),
             Connection::UdpSocket(con) => con
-                .set_read_timeout(Some(duration))
-                .and_then(|_| con.set_write_timeout(Some(duration))),
+                .set_read_timeout(duration)
+                .and_then(|_| con.set_write_timeout(duration)),
             Connection::TcpStream(con) => con
-                .set_read_timeout(Some(duration))
-                .and_then(|_| con.set_write_timeout(Some(duration))),
+                .set_read_timeout(duration)
+                .and_then(|_| con.set_write_timeout(duration)),
+        }
+    }
+
+    #[cfg(unix)]
+    fn setsockopt(&self, level: i32, name: i32, value: &[u8]) -> io::Result<()> {
+        let ret = unsafe {
+            libc::setsockopt(
+                self.fileno() as libc::c_int,
+                level,
+                name,
+                value.as_ptr() as *const libc::c_void,
+                value.len() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
         }
     }
+
+    #[cfg(not(unix))]
+    fn setsockopt(&self, _level: i32, _name: i32, _value: &[u8]) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "setsockopt is not supported on this platform",
+        ))
+    }
 }
 
 impl Read for Connection {
@@ -175,6 +208,8 @@ pub struct Socket {
     socket_kind: SocketKind,
     con: RefCell<Option<Connection>>,
     timeout: RefCell<Option<Duration>>,
+    // Options set before the underlying OS socket exists, applied once it does.
+    sockopts: RefCell<Vec<(i32, i32, Vec<u8>)>>,
 }
 
 impl PyValue for Socket {
@@ -190,19 +225,130 @@ impl Socket {
             socket_kind,
             con: RefCell::new(None),
             timeout: RefCell::new(None),
+            sockopts: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Resolve an address tuple to a socket address of this socket's family.
+    fn resolve(&self, address: &Address, vm: &VirtualMachine) -> PyResult<SocketAddr> {
+        let host = match (address.host.as_str(), self.address_family) {
+            ("", AddressFamily::Inet6) => "::",
+            ("", _) => "0.0.0.0",
+            (host, _) => host,
+        };
+        let mut sock_addrs = (host, address.port)
+            .to_socket_addrs()
+            .map_err(|err| new_gaierror(err.to_string(), vm))?;
+        sock_addrs
+            .find(|addr| match self.address_family {
+                AddressFamily::Inet => addr.is_ipv4(),
+                AddressFamily::Inet6 => addr.is_ipv6(),
+                AddressFamily::Unix => true,
+            })
+            .ok_or_else(|| {
+                new_gaierror("Address family for hostname not supported".to_string(), vm)
+            })
+    }
+
+    fn connect_stream(&self, sock_addr: SocketAddr, vm: &VirtualMachine) -> PyResult<()> {
+        let timeout = *self.timeout.borrow();
+        let con = match timeout {
+            Some(duration) if duration != Duration::from_secs(0) => {
+                TcpStream::connect_timeout(&sock_addr, duration)
+            }
+            _ => TcpStream::connect(sock_addr),
+        };
+        let stream = con.map_err(|err| self.convert_error(err, vm))?;
+        self.set_connection(Connection::TcpStream(stream), vm)
+    }
+
+    fn unspecified_address(&self) -> SocketAddr {
+        match self.address_family {
+            AddressFamily::Inet6 => (Ipv6Addr::UNSPECIFIED, 0).into(),
+            _ => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        }
+    }
+
+    /// Store a freshly created OS socket, configuring it with the current
+    /// timeout and any options set so far.
+    fn set_connection(&self, mut con: Connection, vm: &VirtualMachine) -> PyResult<()> {
+        self.apply_timeout(&mut con)
+            .map_err(|err| convert_io_error(vm, err))?;
+        for (level, name, value) in self.sockopts.borrow().iter() {
+            con.setsockopt(*level, *name, value)
+                .map_err(|err| convert_io_error(vm, err))?;
+        }
+        self.con.borrow_mut().replace(con);
+        Ok(())
+    }
+
+    fn apply_timeout(&self, con: &mut Connection) -> io::Result<()> {
+        match *self.timeout.borrow() {
+            None => con.setblocking(true).and_then(|_| con.settimeout(None)),
+            Some(duration) if duration == Duration::from_secs(0) => con.setblocking(false),
+            Some(duration) => con
+                .setblocking(true)
+                .and_then(|_| con.settimeout(Some(duration))),
+        }
+    }
+
+    pub(crate) fn convert_error(&self, err: io::Error, vm: &VirtualMachine) -> PyObjectRef {
+        let has_timeout = match *self.timeout.borrow() {
+            Some(duration) => duration != Duration::from_secs(0),
+            None => false,
+        };
+        match err.kind() {
+            io::ErrorKind::TimedOut => new_timeout_error(vm),
+            io::ErrorKind::WouldBlock if has_timeout => new_timeout_error(vm),
+            _ => convert_io_error(vm, err),
+        }
+    }
+}
+
+// Lets Rust code such as the ssl module drive a connected socket as a plain
+// byte stream, honouring its timeout.
+impl Read for &Socket {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.con.borrow_mut().as_mut() {
+            Some(con) => con.read(buf),
+            None => Err(io::Error::from(io::ErrorKind::NotConnected)),
         }
     }
 }
 
-type SocketRef = PyRef<Socket>;
+impl Write for &Socket {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.con.borrow_mut().as_mut() {
+            Some(con) => con.write(buf),
+            None => Err(io::Error::from(io::ErrorKind::NotConnected)),
+        }
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn new_timeout_error(vm: &VirtualMachine) -> PyObjectRef {
+    let socket_timeout = vm.class("socket", "timeout");
+    vm.new_exception(socket_timeout, "timed out".to_string())
+}
+
+fn new_gaierror(msg: String, vm: &VirtualMachine) -> PyObjectRef {
+    let error_type = vm.class("socket", "gaierror");
+    vm.new_exception(error_type, msg)
+}
+
+pub(crate) type SocketRef = PyRef<Socket>;
 
 impl SocketRef {
     fn new(
         cls: PyClassRef,
-        family: AddressFamily,
-        kind: SocketKind,
+        family: OptionalArg<AddressFamily>,
+        kind: OptionalArg<SocketKind>,
         vm: &VirtualMachine,
     ) -> PyResult<SocketRef> {
+        let family = family.unwrap_or(AddressFamily::Inet);
+        let kind = kind.unwrap_or(SocketKind::Stream);
         Socket::new(family, kind).into_ref_with_type(vm, cls)
     }
 
@@ -215,81 +361,41 @@ impl SocketRef {
     }
 
     fn connect(self, address: Address, vm: &VirtualMachine) -> PyResult<()> {
-        let address_string = address.get_address_string();
+        let sock_addr = self.resolve(&address, vm)?;
 
         match self.socket_kind {
-            SocketKind::Stream => {
-                let con = if let Some(duration) = self.timeout.borrow().as_ref() {
-                    let sock_addr = match address_string.to_socket_addrs() {
-                        Ok(mut sock_addrs) => {
-                            if sock_addrs.len() == 0 {
-                                let error_type = vm.class("socket", "gaierror");
-                                return Err(vm.new_exception(
-                                    error_type,
-                                    "nodename nor servname provided, or not known".to_string(),
-                                ));
-                            } else {
-                                sock_addrs.next().unwrap()
-                            }
-                        }
-                        Err(e) => {
-                            let error_type = vm.class("socket", "gaierror");
-                            return Err(vm.new_exception(error_type, e.to_string()));
-                        }
-                    };
-                    TcpStream::connect_timeout(&sock_addr, *duration)
-                } else {
-                    TcpStream::connect(address_string)
-                };
-                match con {
-                    Ok(stream) => {
-                        self.con.borrow_mut().replace(Connection::TcpStream(stream));
-                        Ok(())
-                    }
-                    Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {
-                        let socket_timeout = vm.class("socket", "timeout");
-                        Err(vm.new_exception(socket_timeout, "Timed out".to_string()))
-                    }
-                    Err(s) => Err(vm.new_os_error(s.to_string())),
-                }
-            }
+            SocketKind::Stream => self.connect_stream(sock_addr, vm),
             SocketKind::Dgram => {
+                if self.con.borrow().is_none() {
+                    // Doing implicit bind
+                    let dgram = UdpSocket::bind(self.unspecified_address())
+                        .map_err(|err| convert_io_error(vm, err))?;
+                    self.set_connection(Connection::UdpSocket(dgram), vm)?;
+                }
                 if let Some(Connection::UdpSocket(con)) = self.con.borrow().as_ref() {
-                    match con.connect(address_string) {
-                        Ok(_) => Ok(()),
-                        Err(s) => Err(vm.new_os_error(s.to_string())),
-                    }
+                    con.connect(sock_addr)
+                        .map_err(|err| convert_io_error(vm, err))
                 } else {
-                    Err(vm.new_type_error("".to_string()))
+                    Err(vm.new_os_error("socket is not a datagram socket".to_string()))
                 }
             }
         }
     }
 
     fn bind(self, address: Address, vm: &VirtualMachine) -> PyResult<()> {
-        let address_string = address.get_address_string();
+        let sock_addr = self.resolve(&address, vm)?;
 
-        match self.socket_kind {
-            SocketKind::Stream => match TcpListener::bind(address_string) {
-                Ok(stream) => {
-                    self.con
-                        .borrow_mut()
-                        .replace(Connection::TcpListener(stream));
-                    Ok(())
-                }
-                Err(s) => Err(vm.new_os_error(s.to_string())),
-            },
-            SocketKind::Dgram => match UdpSocket::bind(address_string) {
-                Ok(dgram) => {
-                    self.con.borrow_mut().replace(Connection::UdpSocket(dgram));
-                    Ok(())
-                }
-                Err(s) => Err(vm.new_os_error(s.to_string())),
-            },
-        }
+        let con = match self.socket_kind {
+            SocketKind::Stream => TcpListener::bind(sock_addr).map(Connection::TcpListener),
+            SocketKind::Dgram => UdpSocket::bind(sock_addr).map(Connection::UdpSocket),
+        };
+        let con = con.map_err(|err| convert_io_error(vm, err))?;
+        self.set_connection(con, vm)
     }
 
-    fn listen(self, _num: PyIntRef, _vm: &VirtualMachine) {}
+    // The standard library already puts a bound TCP socket into the listening
+    // state, so there is nothing left to do here.
+    fn listen(self, _backlog: OptionalArg<i32>, _vm: &VirtualMachine) {}
 
     fn accept(self, vm: &VirtualMachine) -> PyResult {
         let ret = match self.con.borrow_mut().as_mut() {
@@ -297,93 +403,79 @@ impl SocketRef {
             None => return Err(vm.new_type_error("".to_string())),
         };
 
-        let (tcp_stream, addr) = match ret {
-            Ok((socket, addr)) => (socket, addr),
-            Err(s) => return Err(vm.new_os_error(s.to_string())),
-        };
+        let (tcp_stream, addr) = ret.map_err(|err| self.convert_error(err, vm))?;
 
-        let socket = Socket {
-            address_family: self.address_family,
-            socket_kind: self.socket_kind,
-            con: RefCell::new(Some(Connection::TcpStream(tcp_stream))),
-            timeout: RefCell::new(None),
-        }
-        .into_ref(vm);
+        let socket = Socket::new(self.address_family, self.socket_kind);
+        socket
+            .con
+            .borrow_mut()
+            .replace(Connection::TcpStream(tcp_stream));
+        let socket = socket.into_ref(vm);
 
         let addr_tuple = get_addr_tuple(vm, addr)?;
 
         Ok(vm.ctx.new_tuple(vec![socket.into_object(), addr_tuple]))
     }
 
-    fn recv(self, bufsize: PyIntRef, vm: &VirtualMachine) -> PyResult {
-        let mut buffer = vec![0u8; bufsize.as_bigint().to_usize().unwrap()];
-        match self.con.borrow_mut().as_mut() {
-            Some(v) => match v.read_exact(&mut buffer) {
-                Ok(_) => (),
-                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {
-                    let socket_timeout = vm.class("socket", "timeout");
-                    return Err(vm.new_exception(socket_timeout, "Timed out".to_string()));
-                }
-                Err(s) => return Err(vm.new_os_error(s.to_string())),
-            },
+    fn recv(self, bufsize: usize, vm: &VirtualMachine) -> PyResult {
+        let mut buffer = vec![0u8; bufsize];
+        let size = match self.con.borrow_mut().as_mut() {
+            Some(v) => v
+                .read(&mut buffer)
+                .map_err(|err| self.convert_error(err, vm))?,
             None => return Err(vm.new_type_error("".to_string())),
         };
+        buffer.truncate(size);
         Ok(vm.ctx.new_bytes(buffer))
     }
 
-    fn recvfrom(self, bufsize: PyIntRef, vm: &VirtualMachine) -> PyResult {
-        let mut buffer = vec![0u8; bufsize.as_bigint().to_usize().unwrap()];
+    fn recvfrom(self, bufsize: usize, vm: &VirtualMachine) -> PyResult {
+        let mut buffer = vec![0u8; bufsize];
         let ret = match self.con.borrow().as_ref() {
             Some(v) => v.recv_from(&mut buffer),
             None => return Err(vm.new_type_error("".to_string())),
         };
 
-        let addr = match ret {
-            Ok((_size, addr)) => addr,
-            Err(s) => return Err(vm.new_os_error(s.to_string())),
-        };
+        let (size, addr) = ret.map_err(|err| self.convert_error(err, vm))?;
+        buffer.truncate(size);
 
         let addr_tuple = get_addr_tuple(vm, addr)?;
 
         Ok(vm.ctx.new_tuple(vec![vm.ctx.new_bytes(buffer), addr_tuple]))
     }
 
-    fn send(self, bytes: PyBytesRef, vm: &VirtualMachine) -> PyResult<()> {
+    fn send(self, bytes: PyBytesRef, vm: &VirtualMachine) -> PyResult<usize> {
         match self.con.borrow_mut().as_mut() {
-            Some(v) => match v.write(&bytes) {
-                Ok(_) => (),
-                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {
-                    let socket_timeout = vm.class("socket", "timeout");
-                    return Err(vm.new_exception(socket_timeout, "Timed out".to_string()));
-                }
-                Err(s) => return Err(vm.new_os_error(s.to_string())),
-            },
-            None => return Err(vm.new_type_error("Socket is not connected".to_string())),
-        };
-        Ok(())
+            Some(v) => v.write(&bytes).map_err(|err| self.convert_error(err, vm)),
+            None => Err(vm.new_type_error("Socket is not connected".to_string())),
+        }
     }
 
-    fn sendto(self, bytes: PyBytesRef, address: Address, vm: &VirtualMachine) -> PyResult<()> {
-        let address_string = address.get_address_string();
+    fn sendall(self, bytes: PyBytesRef, vm: &VirtualMachine) -> PyResult<()> {
+        match self.con.borrow_mut().as_mut() {
+            Some(v) => v
+                .write_all(&bytes)
+                .map_err(|err| self.convert_error(err, vm)),
+            None => Err(vm.new_type_error("Socket is not connected".to_string())),
+        }
+    }
+
+    fn sendto(self, bytes: PyBytesRef, address: Address, vm: &VirtualMachine) -> PyResult<usize> {
+        let sock_addr = self.resolve(&address, vm)?;
 
         match self.socket_kind {
             SocketKind::Dgram => {
-                if let Some(v) = self.con.borrow().as_ref() {
-                    return match v.send_to(&bytes, address_string) {
-                        Ok(_) => Ok(()),
-                        Err(s) => Err(vm.new_os_error(s.to_string())),
-                    };
+                if self.con.borrow().is_none() {
+                    // Doing implicit bind
+                    let dgram = UdpSocket::bind(self.unspecified_address())
+                        .map_err(|err| convert_io_error(vm, err))?;
+                    self.set_connection(Connection::UdpSocket(dgram), vm)?;
                 }
-                // Doing implicit bind
-                match UdpSocket::bind("0.0.0.0:0") {
-                    Ok(dgram) => match dgram.send_to(&bytes, address_string) {
-                        Ok(_) => {
-                            self.con.borrow_mut().replace(Connection::UdpSocket(dgram));
-                            Ok(())
-                        }
-                        Err(s) => Err(vm.new_os_error(s.to_string())),
-                    },
-                    Err(s) => Err(vm.new_os_error(s.to_string())),
+                match self.con.borrow().as_ref() {
+                    Some(v) => v
+                        .send_to(&bytes, sock_addr)
+                        .map_err(|err| self.convert_error(err, vm)),
+                    None => unreachable!(),
                 }
             }
             _ => Err(vm.new_not_implemented_error("".to_string())),
@@ -410,13 +502,37 @@ impl SocketRef {
 
         match addr {
             Ok(addr) => get_addr_tuple(vm, addr),
-            Err(s) => Err(vm.new_os_error(s.to_string())),
+            Err(err) => Err(convert_io_error(vm, err)),
+        }
+    }
+
+    fn setsockopt(
+        self,
+        level: i32,
+        name: i32,
+        value: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let value = match i32::try_from_object(vm, value.clone()) {
+            Ok(int) => int.to_ne_bytes().to_vec(),
+            Err(_) => PyBytesRef::try_from_object(vm, value)?.get_value().to_vec(),
+        };
+        match self.con.borrow().as_ref() {
+            Some(con) => con
+                .setsockopt(level, name, &value)
+                .map_err(|err| convert_io_error(vm, err)),
+            None => {
+                self.sockopts.borrow_mut().push((level, name, value));
+                Ok(())
+            }
         }
     }
 
     fn gettimeout(self, _vm: &VirtualMachine) -> PyResult<Option<f64>> {
         match self.timeout.borrow().as_ref() {
-            Some(duration) => Ok(Some(duration.as_secs() as f64)),
+            Some(duration) => Ok(Some(
+                duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9,
+            )),
             None => Ok(None),
         }
     }
@@ -427,16 +543,13 @@ impl SocketRef {
                 if value {
                     self.timeout.replace(None);
                 } else {
-                    self.timeout.borrow_mut().replace(Duration::from_secs(0));
+                    self.timeout.replace(Some(Duration::from_secs(0)));
                 }
-                if let Some(conn) = self.con.borrow_mut().as_mut() {
-                    match conn.setblocking(value) {
-                        Ok(_) => Ok(()),
-                        Err(err) => Err(vm.new_os_error(err.to_string())),
-                    }
-                } else {
-                    Ok(())
+                if let Some(con) = self.con.borrow_mut().as_mut() {
+                    self.apply_timeout(con)
+                        .map_err(|err| convert_io_error(vm, err))?;
                 }
+                Ok(())
             }
             None => {
                 // Avoid converting None to bool
@@ -447,68 +560,56 @@ impl SocketRef {
 
     fn getblocking(self, _vm: &VirtualMachine) -> PyResult<Option<bool>> {
         match self.timeout.borrow().as_ref() {
-            Some(duration) => {
-                if duration.as_secs() != 0 {
-                    Ok(Some(true))
-                } else {
-                    Ok(Some(false))
-                }
-            }
+            Some(duration) => Ok(Some(*duration != Duration::from_secs(0))),
             None => Ok(Some(true)),
         }
     }
 
-    fn settimeout(self, timeout: Option<f64>, vm: &VirtualMachine) -> PyResult<()> {
-        match timeout {
+    fn settimeout(self, timeout: Option<PyObjectRef>, vm: &VirtualMachine) -> PyResult<()> {
+        let timeout = match timeout {
             Some(timeout) => {
-                self.timeout
-                    .borrow_mut()
-                    .replace(Duration::from_secs(timeout as u64));
-
-                let block = timeout > 0.0;
-
-                if let Some(conn) = self.con.borrow_mut().as_mut() {
-                    conn.setblocking(block)
-                        .and_then(|_| conn.settimeout(Duration::from_secs(timeout as u64)))
-                        .map_err(|err| vm.new_os_error(err.to_string()))
-                        .map(|_| ())
-                } else {
-                    Ok(())
+                let timeout = objfloat::make_float(vm, &timeout)?;
+                if timeout < 0.0 || timeout.is_nan() {
+                    return Err(vm.new_value_error("Timeout value out of range".to_string()));
                 }
+                Some(Duration::new(
+                    timeout.trunc() as u64,
+                    (timeout.fract() * 1e9) as u32,
+                ))
             }
-            None => {
-                self.timeout.replace(None);
-                Ok(())
-            }
+            None => None,
+        };
+        self.timeout.replace(timeout);
+        if let Some(con) = self.con.borrow_mut().as_mut() {
+            self.apply_timeout(con)
+                .map_err(|err| convert_io_error(vm, err))?;
         }
+        Ok(())
     }
 }
 
 struct Address {
     host: String,
-    port: usize,
-}
-
-impl Address {
-    fn get_address_string(self) -> String {
-        format!("{}:{}", self.host, self.port.to_string())
-    }
+    port: u16,
 }
 
 impl TryFromObject for Address {
     fn try_from_object(vm: &VirtualMachine, obj: PyObjectRef) -> PyResult<Self> {
         let tuple = PyTupleRef::try_from_object(vm, obj)?;
-        if tuple.elements.len() != 2 {
-            Err(vm.new_type_error("Address tuple should have only 2 values".to_string()))
+        // AF_INET6 addresses may also carry a flowinfo and scope id, which
+        // are not used here.
+        if tuple.elements.len() != 2 && tuple.elements.len() != 4 {
+            Err(vm.new_type_error("Address tuple should have 2 or 4 values".to_string()))
         } else {
+            let port = PyIntRef::try_from_object(vm, tuple.elements[1].clone())?;
             Ok(Address {
                 host: PyStringRef::try_from_object(vm, tuple.elements[0].clone())?
                     .value
                     .to_string(),
-                port: PyIntRef::try_from_object(vm, tuple.elements[1].clone())?
+                port: port
                     .as_bigint()
-                    .to_usize()
-                    .unwrap(),
+                    .to_u16()
+                    .ok_or_else(|| vm.new_overflow_error("port must be 0-65535.".to_string()))?,
             })
         }
     }
@@ -518,7 +619,151 @@ fn get_addr_tuple(vm: &VirtualMachine, addr: SocketAddr) -> PyResult {
     let port = vm.ctx.new_int(addr.port());
     let ip = vm.ctx.new_str(addr.ip().to_string());
 
-    Ok(vm.ctx.new_tuple(vec![ip, port]))
+    match addr {
+        SocketAddr::V4(_) => Ok(vm.ctx.new_tuple(vec![ip, port])),
+        SocketAddr::V6(addr) => Ok(vm.ctx.new_tuple(vec![
+            ip,
+            port,
+            vm.ctx.new_int(addr.flowinfo()),
+            vm.ctx.new_int(addr.scope_id()),
+        ])),
+    }
+}
+
+#[derive(FromArgs)]
+struct GAIOptions {
+    #[pyarg(positional_or_keyword, default = "0")]
+    family: i32,
+    #[pyarg(positional_or_keyword, name = "type", default = "0")]
+    kind: i32,
+    #[pyarg(positional_or_keyword, default = "0")]
+    proto: i32,
+    #[pyarg(positional_or_keyword, default = "0")]
+    flags: i32,
+}
+
+fn socket_getaddrinfo(
+    host: Option<PyStringRef>,
+    port: PyObjectRef,
+    GAIOptions {
+        family,
+        kind,
+        proto,
+        flags,
+    }: GAIOptions,
+    vm: &VirtualMachine,
+) -> PyResult {
+    if flags & !AI_PASSIVE != 0 {
+        return Err(new_gaierror("Bad value for ai_flags".to_string(), vm));
+    }
+    let port = if vm.is_none(&port) {
+        0
+    } else if let Ok(port) = PyStringRef::try_from_object(vm, port.clone()) {
+        port.as_str()
+            .parse::<u16>()
+            .map_err(|_| new_gaierror("Servname not supported for ai_socktype".to_string(), vm))?
+    } else {
+        u16::try_from_object(vm, port)?
+    };
+
+    let kinds = match kind {
+        0 => vec![SocketKind::Stream, SocketKind::Dgram],
+        _ => vec![SocketKind::try_from_object(vm, vm.new_int(kind))?],
+    };
+    let addrs = match host {
+        // Without a host, passive sockets bind to every interface.
+        None if flags & AI_PASSIVE != 0 => vec![
+            SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port),
+            SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), port),
+        ],
+        _ => {
+            let host = host.as_ref().map_or("localhost", |host| host.as_str());
+            (host, port)
+                .to_socket_addrs()
+                .map_err(|err| new_gaierror(err.to_string(), vm))?
+                .collect()
+        }
+    };
+
+    let mut list = vec![];
+    for addr in addrs {
+        let addr_family = if addr.is_ipv4() {
+            AddressFamily::Inet
+        } else {
+            AddressFamily::Inet6
+        };
+        if family != 0 && family != addr_family as i32 {
+            continue;
+        }
+        for addr_kind in &kinds {
+            let addr_proto = match addr_kind {
+                SocketKind::Stream => IPPROTO_TCP,
+                SocketKind::Dgram => IPPROTO_UDP,
+            };
+            if proto != 0 && proto != addr_proto {
+                continue;
+            }
+            list.push(vm.ctx.new_tuple(vec![
+                vm.new_int(addr_family as i32),
+                vm.new_int(*addr_kind as i32),
+                vm.new_int(addr_proto),
+                vm.new_str("".to_string()),
+                get_addr_tuple(vm, addr)?,
+            ]));
+        }
+    }
+    if list.is_empty() {
+        return Err(new_gaierror(
+            "Address family for hostname not supported".to_string(),
+            vm,
+        ));
+    }
+    Ok(vm.ctx.new_list(list))
+}
+
+fn socket_create_connection(
+    address: Address,
+    timeout: OptionalArg<Option<PyObjectRef>>,
+    source_address: OptionalArg<Option<Address>>,
+    vm: &VirtualMachine,
+) -> PyResult<SocketRef> {
+    if let OptionalArg::Present(Some(_)) = source_address {
+        return Err(vm.new_not_implemented_error("source_address is not supported yet".to_string()));
+    }
+    let timeout = timeout.into_option();
+    let sock_addrs = (address.host.as_str(), address.port)
+        .to_socket_addrs()
+        .map_err(|err| new_gaierror(err.to_string(), vm))?;
+
+    // Try each resolved address in turn, like CPython does.
+    let mut last_error = None;
+    for sock_addr in sock_addrs {
+        let family = if sock_addr.is_ipv4() {
+            AddressFamily::Inet
+        } else {
+            AddressFamily::Inet6
+        };
+        let socket = Socket::new(family, SocketKind::Stream).into_ref(vm);
+        if let Some(timeout) = timeout.clone() {
+            socket.clone().settimeout(timeout, vm)?;
+        }
+        match socket.connect_stream(sock_addr, vm) {
+            Ok(()) => return Ok(socket),
+            Err(err) => last_error = Some(err),
+        }
+    }
+    Err(last_error
+        .unwrap_or_else(|| new_gaierror("getaddrinfo returns an empty list".to_string(), vm)))
+}
+
+fn socket_gethostbyname(name: PyStringRef, vm: &VirtualMachine) -> PyResult<String> {
+    let mut addrs = (name.as_str(), 0)
+        .to_socket_addrs()
+        .map_err(|err| new_gaierror(err.to_string(), vm))?;
+    addrs
+        .find(SocketAddr::is_ipv4)
+        .map(|addr| addr.ip().to_string())
+        .ok_or_else(|| new_gaierror("Name or service not known".to_string(), vm))
 }
 
 fn socket_gethostname(vm: &VirtualMachine) -> PyResult {
@@ -572,6 +817,7 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "connect" => ctx.new_rustfunc(SocketRef::connect),
         "recv" => ctx.new_rustfunc(SocketRef::recv),
         "send" => ctx.new_rustfunc(SocketRef::send),
+        "sendall" => ctx.new_rustfunc(SocketRef::sendall),
         "bind" => ctx.new_rustfunc(SocketRef::bind),
         "accept" => ctx.new_rustfunc(SocketRef::accept),
         "listen" => ctx.new_rustfunc(SocketRef::listen),
@@ -584,19 +830,28 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "setblocking" => ctx.new_rustfunc(SocketRef::setblocking),
         "gettimeout" => ctx.new_rustfunc(SocketRef::gettimeout),
         "settimeout" => ctx.new_rustfunc(SocketRef::settimeout),
+        "setsockopt" => ctx.new_rustfunc(SocketRef::setsockopt),
     });
 
     let module = py_module!(vm, "socket", {
         "error" => ctx.exceptions.os_error.clone(),
         "timeout" => socket_timeout,
         "gaierror" => socket_gaierror,
+        "AF_UNSPEC" => ctx.new_int(0),
         "AF_INET" => ctx.new_int(AddressFamily::Inet as i32),
+        "AF_INET6" => ctx.new_int(AddressFamily::Inet6 as i32),
         "SOCK_STREAM" => ctx.new_int(SocketKind::Stream as i32),
         "SOCK_DGRAM" => ctx.new_int(SocketKind::Dgram as i32),
+        "IPPROTO_TCP" => ctx.new_int(IPPROTO_TCP),
+        "IPPROTO_UDP" => ctx.new_int(IPPROTO_UDP),
+        "AI_PASSIVE" => ctx.new_int(AI_PASSIVE),
         "socket" => socket,
         "inet_aton" => ctx.new_rustfunc(socket_inet_aton),
         "inet_ntoa" => ctx.new_rustfunc(socket_inet_ntoa),
         "gethostname" => ctx.new_rustfunc(socket_gethostname),
+        "gethostbyname" => ctx.new_rustfunc(socket_gethostbyname),
+        "getaddrinfo" => ctx.new_rustfunc(socket_getaddrinfo),
+        "create_connection" => ctx.new_rustfunc(socket_create_connection),
         "htonl" => ctx.new_rustfunc(socket_htonl),
     });
 
@@ -612,6 +867,14 @@ fn extend_module_platform_specific(_vm: &VirtualMachine, module: PyObjectRef) ->
 fn extend_module_platform_specific(vm: &VirtualMachine, module: PyObjectRef) -> PyObjectRef {
     let ctx = &vm.ctx;
 
+    extend_module!(vm, module, {
+        "SOL_SOCKET" => ctx.new_int(libc::SOL_SOCKET),
+        "SO_REUSEADDR" => ctx.new_int(libc::SO_REUSEADDR),
+        "SO_KEEPALIVE" => ctx.new_int(libc::SO_KEEPALIVE),
+        "SO_BROADCAST" => ctx.new_int(libc::SO_BROADCAST),
+        "TCP_NODELAY" => ctx.new_int(libc::TCP_NODELAY),
+    });
+
     #[cfg(not(target_os = "redox"))]
     extend_module!(vm, module, {
         "sethostname" => ctx.new_rustfunc(socket_sethostname),
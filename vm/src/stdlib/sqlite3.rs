@@ -0,0 +1,868 @@
+/*
+ * SQLite database access, over the SQLite that libsqlite3-sys bundles.
+ *
+ * Docs: https://docs.python.org/3/library/sqlite3.html
+ *
+ * Rows are fetched as soon as a statement is executed, so that no statement
+ * outlives its execute call and closing a connection never finds one pending.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+use std::slice;
+
+use libsqlite3_sys as ffi;
+use num_traits::ToPrimitive;
+
+use super::os::PyPathLike;
+use crate::function::OptionalArg;
+use crate::obj::objbyteinner;
+use crate::obj::objfloat;
+use crate::obj::objint;
+use crate::obj::objiter;
+use crate::obj::objstr::{self, PyStringRef};
+use crate::obj::objtype::{self, PyClassRef};
+use crate::pyobject::{
+    ItemProtocol, PyClassImpl, PyIterable, PyObjectRef, PyRef, PyResult, PyValue,
+};
+use crate::types::create_type;
+use crate::vm::VirtualMachine;
+
+/// A prepared statement, finalized when dropped. It is null for SQL without
+/// any statement in it, such as a comment.
+struct Statement {
+    stmt: *mut ffi::sqlite3_stmt,
+}
+
+impl Drop for Statement {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::sqlite3_finalize(self.stmt);
+        }
+    }
+}
+
+impl Statement {
+    fn column_count(&self) -> c_int {
+        if self.stmt.is_null() {
+            0
+        } else {
+            unsafe { ffi::sqlite3_column_count(self.stmt) }
+        }
+    }
+
+    /// Bind a sequence to `?` parameters, or a mapping to `:name` ones.
+    fn bind(
+        &self,
+        conn: &PyConnection,
+        parameters: Option<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let count = if self.stmt.is_null() {
+            0
+        } else {
+            unsafe { ffi::sqlite3_bind_parameter_count(self.stmt) }
+        };
+        match parameters {
+            Some(ref mapping) if objtype::isinstance(mapping, &vm.ctx.dict_type()) => {
+                for i in 1..=count {
+                    let name = unsafe { ffi::sqlite3_bind_parameter_name(self.stmt, i) };
+                    if name.is_null() {
+                        return Err(sqlite_error(
+                            vm,
+                            "ProgrammingError",
+                            format!(
+                                "Binding {} has no name, but you supplied a dictionary \
+                                 (which has only names).",
+                                i
+                            ),
+                        ));
+                    }
+                    // skip the `:` of the name
+                    let name = unsafe { CStr::from_ptr(name.offset(1)) }.to_string_lossy();
+                    let value = mapping.get_item(name.as_ref(), vm).map_err(|err| {
+                        if objtype::isinstance(&err, &vm.ctx.exceptions.key_error) {
+                            sqlite_error(
+                                vm,
+                                "ProgrammingError",
+                                format!("You did not supply a value for binding {}.", i),
+                            )
+                        } else {
+                            err
+                        }
+                    })?;
+                    self.bind_value(conn, i, &value, vm)?;
+                }
+            }
+            parameters => {
+                let values = match parameters {
+                    Some(sequence) => vm.extract_elements(&sequence)?,
+                    None => vec![],
+                };
+                if values.len() != count as usize {
+                    return Err(sqlite_error(
+                        vm,
+                        "ProgrammingError",
+                        format!(
+                            "Incorrect number of bindings supplied. The current statement \
+                             uses {}, and there are {} supplied.",
+                            count,
+                            values.len()
+                        ),
+                    ));
+                }
+                for (i, value) in values.iter().enumerate() {
+                    self.bind_value(conn, i as c_int + 1, value, vm)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn bind_value(
+        &self,
+        conn: &PyConnection,
+        i: c_int,
+        value: &PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let rc = if vm.is_none(value) {
+            unsafe { ffi::sqlite3_bind_null(self.stmt, i) }
+        } else if objtype::isinstance(value, &vm.ctx.int_type()) {
+            let value = objint::get_value(value).to_i64().ok_or_else(|| {
+                vm.new_overflow_error(
+                    "Python int too large to convert to SQLite INTEGER".to_string(),
+                )
+            })?;
+            unsafe { ffi::sqlite3_bind_int64(self.stmt, i, value) }
+        } else if objtype::isinstance(value, &vm.ctx.float_type()) {
+            unsafe { ffi::sqlite3_bind_double(self.stmt, i, objfloat::get_value(value)) }
+        } else if objtype::isinstance(value, &vm.ctx.str_type()) {
+            let value = objstr::get_value(value);
+            unsafe {
+                ffi::sqlite3_bind_text(
+                    self.stmt,
+                    i,
+                    value.as_ptr() as *const c_char,
+                    value.len() as c_int,
+                    ffi::SQLITE_TRANSIENT(),
+                )
+            }
+        } else if let Some(value) = objbyteinner::try_as_byte(value) {
+            unsafe {
+                ffi::sqlite3_bind_blob(
+                    self.stmt,
+                    i,
+                    value.as_ptr() as *const c_void,
+                    value.len() as c_int,
+                    ffi::SQLITE_TRANSIENT(),
+                )
+            }
+        } else {
+            return Err(sqlite_error(
+                vm,
+                "InterfaceError",
+                format!(
+                    "Error binding parameter {} - probably unsupported type.",
+                    i - 1
+                ),
+            ));
+        };
+        if rc != ffi::SQLITE_OK {
+            return Err(conn.error(rc, vm));
+        }
+        Ok(())
+    }
+
+    /// Step through the statement, collecting the rows it returns.
+    fn run(&self, conn: &PyConnection, vm: &VirtualMachine) -> PyResult<Vec<PyObjectRef>> {
+        let mut rows = vec![];
+        if self.stmt.is_null() {
+            return Ok(rows);
+        }
+        let columns = self.column_count();
+        loop {
+            match unsafe { ffi::sqlite3_step(self.stmt) } {
+                ffi::SQLITE_ROW => {
+                    let row = (0..columns)
+                        .map(|i| self.column(i, vm))
+                        .collect::<PyResult<_>>()?;
+                    rows.push(vm.ctx.new_tuple(row));
+                }
+                ffi::SQLITE_DONE => break,
+                rc => return Err(conn.error(rc, vm)),
+            }
+        }
+        unsafe {
+            ffi::sqlite3_reset(self.stmt);
+        }
+        Ok(rows)
+    }
+
+    fn column(&self, i: c_int, vm: &VirtualMachine) -> PyResult {
+        let stmt = self.stmt;
+        unsafe {
+            match ffi::sqlite3_column_type(stmt, i) {
+                ffi::SQLITE_INTEGER => Ok(vm.ctx.new_int(ffi::sqlite3_column_int64(stmt, i))),
+                ffi::SQLITE_FLOAT => Ok(vm.ctx.new_float(ffi::sqlite3_column_double(stmt, i))),
+                ffi::SQLITE_TEXT => {
+                    let text = ffi::sqlite3_column_text(stmt, i);
+                    let len = ffi::sqlite3_column_bytes(stmt, i) as usize;
+                    let text = if text.is_null() {
+                        vec![]
+                    } else {
+                        slice::from_raw_parts(text, len).to_vec()
+                    };
+                    let text = String::from_utf8(text).map_err(|_| {
+                        sqlite_error(
+                            vm,
+                            "OperationalError",
+                            "Could not decode to UTF-8".to_string(),
+                        )
+                    })?;
+                    Ok(vm.new_str(text))
+                }
+                ffi::SQLITE_BLOB => {
+                    let blob = ffi::sqlite3_column_blob(stmt, i) as *const u8;
+                    let len = ffi::sqlite3_column_bytes(stmt, i) as usize;
+                    let blob = if blob.is_null() {
+                        vec![]
+                    } else {
+                        slice::from_raw_parts(blob, len).to_vec()
+                    };
+                    Ok(vm.ctx.new_bytes(blob))
+                }
+                _ => Ok(vm.get_none()),
+            }
+        }
+    }
+
+    /// The `description` of a statement's result, which is None if it
+    /// doesn't return rows.
+    fn description(&self, vm: &VirtualMachine) -> Option<PyObjectRef> {
+        let columns = self.column_count();
+        if columns == 0 {
+            return None;
+        }
+        let description = (0..columns)
+            .map(|i| {
+                let name = unsafe { CStr::from_ptr(ffi::sqlite3_column_name(self.stmt, i)) };
+                let mut column = vec![vm.new_str(name.to_string_lossy().into_owned())];
+                column.resize(7, vm.get_none());
+                vm.ctx.new_tuple(column)
+            })
+            .collect();
+        Some(vm.ctx.new_tuple(description))
+    }
+}
+
+/// The leading keyword of a statement, in upper case.
+fn statement_keyword(sql: &str) -> String {
+    sql.trim_start()
+        .chars()
+        .take_while(char::is_ascii_alphabetic)
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+/// Whether a statement changes rows, and so has a rowcount.
+fn is_dml(keyword: &str) -> bool {
+    ["INSERT", "UPDATE", "DELETE", "REPLACE"].contains(&keyword)
+}
+
+fn check_isolation_level(level: &str, vm: &VirtualMachine) -> PyResult<()> {
+    match level.to_ascii_uppercase().as_str() {
+        "" | "DEFERRED" | "IMMEDIATE" | "EXCLUSIVE" => Ok(()),
+        _ => Err(vm.new_value_error(
+            "isolation_level string must be '', 'DEFERRED', 'IMMEDIATE', or 'EXCLUSIVE'"
+                .to_string(),
+        )),
+    }
+}
+
+#[derive(FromArgs)]
+struct ConnectArgs {
+    #[pyarg(positional_or_keyword)]
+    database: PyPathLike,
+    #[pyarg(positional_or_keyword, default = "5.0")]
+    timeout: f64,
+    #[pyarg(positional_or_keyword, optional = true)]
+    isolation_level: OptionalArg<Option<PyStringRef>>,
+}
+
+#[pyclass(name = "Connection")]
+struct PyConnection {
+    /// Null once the connection is closed.
+    db: Cell<*mut ffi::sqlite3>,
+    /// None for autocommit mode, otherwise the kind of transaction to begin
+    /// implicitly before statements that change data.
+    isolation_level: RefCell<Option<String>>,
+}
+
+type PyConnectionRef = PyRef<PyConnection>;
+
+impl fmt::Debug for PyConnection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Connection")
+    }
+}
+
+impl PyValue for PyConnection {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("sqlite3", "Connection")
+    }
+}
+
+impl Drop for PyConnection {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::sqlite3_close(self.db.get());
+        }
+    }
+}
+
+impl PyConnection {
+    fn open(args: ConnectArgs, vm: &VirtualMachine) -> PyResult<PyConnection> {
+        let isolation_level = match args.isolation_level {
+            OptionalArg::Present(Some(level)) => {
+                check_isolation_level(level.as_str(), vm)?;
+                Some(level.as_str().to_string())
+            }
+            OptionalArg::Present(None) => None,
+            OptionalArg::Missing => Some("".to_string()),
+        };
+        let database = CString::new(args.database.as_str()).map_err(|_| {
+            vm.new_value_error("embedded null character in database name".to_string())
+        })?;
+
+        let mut db = ptr::null_mut();
+        let rc = unsafe {
+            ffi::sqlite3_open_v2(
+                database.as_ptr(),
+                &mut db,
+                ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE,
+                ptr::null(),
+            )
+        };
+        // the connection is closed again if opening it fails
+        let conn = PyConnection {
+            db: Cell::new(db),
+            isolation_level: RefCell::new(isolation_level),
+        };
+        if rc != ffi::SQLITE_OK {
+            return Err(conn.error(rc, vm));
+        }
+        unsafe {
+            ffi::sqlite3_busy_timeout(db, (args.timeout * 1000.0) as c_int);
+        }
+        Ok(conn)
+    }
+
+    fn db(&self, vm: &VirtualMachine) -> PyResult<*mut ffi::sqlite3> {
+        let db = self.db.get();
+        if db.is_null() {
+            Err(sqlite_error(
+                vm,
+                "ProgrammingError",
+                "Cannot operate on a closed database.".to_string(),
+            ))
+        } else {
+            Ok(db)
+        }
+    }
+
+    /// The exception for a failed call, with SQLite's message for it.
+    fn error(&self, rc: c_int, vm: &VirtualMachine) -> PyObjectRef {
+        let message = unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(self.db.get())) }
+            .to_string_lossy()
+            .into_owned();
+        let name = match rc & 0xff {
+            ffi::SQLITE_NOMEM => {
+                return vm.new_exception(vm.ctx.exceptions.memory_error.clone(), message)
+            }
+            ffi::SQLITE_CONSTRAINT | ffi::SQLITE_MISMATCH => "IntegrityError",
+            ffi::SQLITE_INTERNAL | ffi::SQLITE_NOTFOUND => "InternalError",
+            ffi::SQLITE_TOOBIG => "DataError",
+            ffi::SQLITE_MISUSE | ffi::SQLITE_RANGE => "InterfaceError",
+            ffi::SQLITE_CORRUPT | ffi::SQLITE_NOTADB => "DatabaseError",
+            _ => "OperationalError",
+        };
+        sqlite_error(vm, name, message)
+    }
+
+    fn prepare(&self, sql: &str, vm: &VirtualMachine) -> PyResult<Statement> {
+        let db = self.db(vm)?;
+        let mut stmt = ptr::null_mut();
+        let mut tail = ptr::null();
+        let rc = unsafe {
+            ffi::sqlite3_prepare_v2(
+                db,
+                sql.as_ptr() as *const c_char,
+                sql.len() as c_int,
+                &mut stmt,
+                &mut tail,
+            )
+        };
+        if rc != ffi::SQLITE_OK {
+            return Err(self.error(rc, vm));
+        }
+        let statement = Statement { stmt };
+
+        let rest = &sql[tail as usize - sql.as_ptr() as usize..];
+        if !rest
+            .trim_matches(|c: char| c.is_whitespace() || c == ';')
+            .is_empty()
+        {
+            return Err(sqlite_error(
+                vm,
+                "Warning",
+                "You can only execute one statement at a time.".to_string(),
+            ));
+        }
+        Ok(statement)
+    }
+
+    /// Execute any number of statements that don't return rows.
+    fn exec(&self, sql: &str, vm: &VirtualMachine) -> PyResult<()> {
+        let db = self.db(vm)?;
+        let sql = CString::new(sql)
+            .map_err(|_| vm.new_value_error("the query contains a null character".to_string()))?;
+        let rc =
+            unsafe { ffi::sqlite3_exec(db, sql.as_ptr(), None, ptr::null_mut(), ptr::null_mut()) };
+        if rc != ffi::SQLITE_OK {
+            return Err(self.error(rc, vm));
+        }
+        Ok(())
+    }
+
+    /// Begin a transaction before a statement that changes data, unless
+    /// one is already open or the connection is in autocommit mode.
+    fn begin_implicitly(&self, keyword: &str, vm: &VirtualMachine) -> PyResult<()> {
+        if !is_dml(keyword) || self.is_in_transaction() {
+            return Ok(());
+        }
+        let isolation_level = self.isolation_level.borrow().clone();
+        match isolation_level {
+            Some(level) => self.exec(&format!("BEGIN {}", level), vm),
+            None => Ok(()),
+        }
+    }
+
+    fn is_in_transaction(&self) -> bool {
+        let db = self.db.get();
+        !db.is_null() && unsafe { ffi::sqlite3_get_autocommit(db) } == 0
+    }
+}
+
+#[pyimpl]
+impl PyConnection {
+    #[pymethod(name = "__new__")]
+    fn new(cls: PyClassRef, args: ConnectArgs, vm: &VirtualMachine) -> PyResult<PyConnectionRef> {
+        PyConnection::open(args, vm)?.into_ref_with_type(vm, cls)
+    }
+
+    #[pymethod(name = "cursor")]
+    fn cursor(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult<PyCursor> {
+        zelf.db(vm)?;
+        Ok(PyCursor::new(zelf))
+    }
+
+    /// Execute a statement on a new cursor, and return the cursor.
+    #[pymethod(name = "execute")]
+    fn execute(
+        zelf: PyRef<Self>,
+        sql: PyStringRef,
+        parameters: OptionalArg<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyCursorRef> {
+        let cursor = PyConnection::cursor(zelf, vm)?.into_ref(vm);
+        PyCursor::execute(cursor, sql, parameters, vm)
+    }
+
+    #[pymethod(name = "executemany")]
+    fn executemany(
+        zelf: PyRef<Self>,
+        sql: PyStringRef,
+        seq_of_parameters: PyIterable,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyCursorRef> {
+        let cursor = PyConnection::cursor(zelf, vm)?.into_ref(vm);
+        PyCursor::executemany(cursor, sql, seq_of_parameters, vm)
+    }
+
+    #[pymethod(name = "executescript")]
+    fn executescript(
+        zelf: PyRef<Self>,
+        script: PyStringRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyCursorRef> {
+        let cursor = PyConnection::cursor(zelf, vm)?.into_ref(vm);
+        PyCursor::executescript(cursor, script, vm)
+    }
+
+    #[pymethod(name = "commit")]
+    fn commit(&self, vm: &VirtualMachine) -> PyResult<()> {
+        self.db(vm)?;
+        if self.is_in_transaction() {
+            self.exec("COMMIT", vm)?;
+        }
+        Ok(())
+    }
+
+    #[pymethod(name = "rollback")]
+    fn rollback(&self, vm: &VirtualMachine) -> PyResult<()> {
+        self.db(vm)?;
+        if self.is_in_transaction() {
+            self.exec("ROLLBACK", vm)?;
+        }
+        Ok(())
+    }
+
+    /// Close the connection, without committing an open transaction.
+    #[pymethod(name = "close")]
+    fn close(&self, vm: &VirtualMachine) -> PyResult<()> {
+        let db = self.db.get();
+        let rc = unsafe { ffi::sqlite3_close(db) };
+        if rc != ffi::SQLITE_OK {
+            return Err(self.error(rc, vm));
+        }
+        self.db.set(ptr::null_mut());
+        Ok(())
+    }
+
+    #[pymethod(name = "__enter__")]
+    fn enter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+
+    /// Commit when the block succeeds and roll back when it raises.
+    #[pymethod(name = "__exit__")]
+    fn exit(
+        &self,
+        exc_type: PyObjectRef,
+        _exc_value: PyObjectRef,
+        _traceback: PyObjectRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<bool> {
+        if vm.is_none(&exc_type) {
+            self.commit(vm)?;
+        } else {
+            self.rollback(vm)?;
+        }
+        Ok(false)
+    }
+
+    #[pyproperty(name = "isolation_level")]
+    fn isolation_level(&self, _vm: &VirtualMachine) -> Option<String> {
+        self.isolation_level.borrow().clone()
+    }
+
+    #[pyproperty(setter)]
+    fn set_isolation_level(
+        &self,
+        isolation_level: Option<PyStringRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        match isolation_level {
+            Some(level) => {
+                check_isolation_level(level.as_str(), vm)?;
+                self.isolation_level
+                    .replace(Some(level.as_str().to_string()));
+            }
+            None => {
+                // switching to autocommit mode commits what is pending
+                self.commit(vm)?;
+                self.isolation_level.replace(None);
+            }
+        }
+        Ok(vm.get_none())
+    }
+
+    #[pyproperty(name = "in_transaction")]
+    fn in_transaction(&self, _vm: &VirtualMachine) -> bool {
+        self.is_in_transaction()
+    }
+}
+
+#[pyclass(name = "Cursor")]
+struct PyCursor {
+    connection: PyConnectionRef,
+    rows: RefCell<VecDeque<PyObjectRef>>,
+    description: RefCell<Option<PyObjectRef>>,
+    rowcount: Cell<i64>,
+    lastrowid: Cell<Option<i64>>,
+    arraysize: Cell<usize>,
+    closed: Cell<bool>,
+}
+
+type PyCursorRef = PyRef<PyCursor>;
+
+impl fmt::Debug for PyCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cursor")
+    }
+}
+
+impl PyValue for PyCursor {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("sqlite3", "Cursor")
+    }
+}
+
+impl PyCursor {
+    fn new(connection: PyConnectionRef) -> PyCursor {
+        PyCursor {
+            connection,
+            rows: RefCell::new(VecDeque::new()),
+            description: RefCell::new(None),
+            rowcount: Cell::new(-1),
+            lastrowid: Cell::new(None),
+            arraysize: Cell::new(1),
+            closed: Cell::new(false),
+        }
+    }
+
+    fn check_open(&self, vm: &VirtualMachine) -> PyResult<()> {
+        if self.closed.get() {
+            return Err(sqlite_error(
+                vm,
+                "ProgrammingError",
+                "Cannot operate on a closed cursor.".to_string(),
+            ));
+        }
+        self.connection.db(vm)?;
+        Ok(())
+    }
+
+    /// Forget the results of the previous statement.
+    fn reset(&self) {
+        self.rows.borrow_mut().clear();
+        self.description.replace(None);
+        self.rowcount.set(-1);
+    }
+
+    /// Record what a statement did, once it has run for every set of
+    /// parameters.
+    fn finish(&self, keyword: &str, changes: i64) {
+        if is_dml(keyword) {
+            self.rowcount.set(changes);
+        }
+        if keyword == "INSERT" || keyword == "REPLACE" {
+            let db = self.connection.db.get();
+            self.lastrowid
+                .set(Some(unsafe { ffi::sqlite3_last_insert_rowid(db) }));
+        }
+    }
+
+    fn changes(&self) -> i64 {
+        i64::from(unsafe { ffi::sqlite3_changes(self.connection.db.get()) })
+    }
+}
+
+#[pyimpl]
+impl PyCursor {
+    #[pymethod(name = "__new__")]
+    fn py_new(
+        cls: PyClassRef,
+        connection: PyConnectionRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyCursorRef> {
+        PyCursor::new(connection).into_ref_with_type(vm, cls)
+    }
+
+    #[pymethod(name = "execute")]
+    fn execute(
+        zelf: PyRef<Self>,
+        sql: PyStringRef,
+        parameters: OptionalArg<PyObjectRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyCursorRef> {
+        zelf.check_open(vm)?;
+        zelf.reset();
+        let conn = &zelf.connection;
+        let keyword = statement_keyword(sql.as_str());
+        conn.begin_implicitly(&keyword, vm)?;
+
+        let statement = conn.prepare(sql.as_str(), vm)?;
+        statement.bind(conn, parameters.into_option(), vm)?;
+        let rows = statement.run(conn, vm)?;
+        zelf.rows.borrow_mut().extend(rows);
+        zelf.description.replace(statement.description(vm));
+        zelf.finish(&keyword, zelf.changes());
+        Ok(zelf)
+    }
+
+    /// Execute a statement that changes data once for each set of parameters.
+    #[pymethod(name = "executemany")]
+    fn executemany(
+        zelf: PyRef<Self>,
+        sql: PyStringRef,
+        seq_of_parameters: PyIterable,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyCursorRef> {
+        zelf.check_open(vm)?;
+        zelf.reset();
+        let conn = &zelf.connection;
+        let keyword = statement_keyword(sql.as_str());
+        conn.begin_implicitly(&keyword, vm)?;
+
+        let statement = conn.prepare(sql.as_str(), vm)?;
+        if statement.column_count() > 0 {
+            return Err(sqlite_error(
+                vm,
+                "ProgrammingError",
+                "executemany() can only execute DML statements.".to_string(),
+            ));
+        }
+        let mut changes = 0;
+        for parameters in seq_of_parameters.iter(vm)? {
+            statement.bind(conn, Some(parameters?), vm)?;
+            statement.run(conn, vm)?;
+            changes += zelf.changes();
+        }
+        zelf.finish(&keyword, changes);
+        Ok(zelf)
+    }
+
+    /// Commit, then execute a script of statements separated by semicolons.
+    #[pymethod(name = "executescript")]
+    fn executescript(
+        zelf: PyRef<Self>,
+        script: PyStringRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyCursorRef> {
+        zelf.check_open(vm)?;
+        zelf.reset();
+        zelf.connection.commit(vm)?;
+        zelf.connection.exec(script.as_str(), vm)?;
+        Ok(zelf)
+    }
+
+    #[pymethod(name = "fetchone")]
+    fn fetchone(&self, vm: &VirtualMachine) -> PyResult {
+        self.check_open(vm)?;
+        Ok(self
+            .rows
+            .borrow_mut()
+            .pop_front()
+            .unwrap_or_else(|| vm.get_none()))
+    }
+
+    #[pymethod(name = "fetchmany")]
+    fn fetchmany(&self, size: OptionalArg<usize>, vm: &VirtualMachine) -> PyResult {
+        self.check_open(vm)?;
+        let mut rows = self.rows.borrow_mut();
+        let size = size.unwrap_or_else(|| self.arraysize.get()).min(rows.len());
+        Ok(vm.ctx.new_list(rows.drain(..size).collect()))
+    }
+
+    #[pymethod(name = "fetchall")]
+    fn fetchall(&self, vm: &VirtualMachine) -> PyResult {
+        self.check_open(vm)?;
+        Ok(vm.ctx.new_list(self.rows.borrow_mut().drain(..).collect()))
+    }
+
+    #[pymethod(name = "close")]
+    fn close(&self, _vm: &VirtualMachine) {
+        self.reset();
+        self.closed.set(true);
+    }
+
+    #[pymethod(name = "__iter__")]
+    fn iter(zelf: PyRef<Self>, _vm: &VirtualMachine) -> PyRef<Self> {
+        zelf
+    }
+
+    #[pymethod(name = "__next__")]
+    fn next(&self, vm: &VirtualMachine) -> PyResult {
+        self.check_open(vm)?;
+        self.rows
+            .borrow_mut()
+            .pop_front()
+            .ok_or_else(|| objiter::new_stop_iteration(vm))
+    }
+
+    #[pyproperty(name = "connection")]
+    fn connection(&self, _vm: &VirtualMachine) -> PyConnectionRef {
+        self.connection.clone()
+    }
+
+    #[pyproperty(name = "description")]
+    fn description(&self, vm: &VirtualMachine) -> PyObjectRef {
+        self.description
+            .borrow()
+            .clone()
+            .unwrap_or_else(|| vm.get_none())
+    }
+
+    #[pyproperty(name = "rowcount")]
+    fn rowcount(&self, _vm: &VirtualMachine) -> i64 {
+        self.rowcount.get()
+    }
+
+    #[pyproperty(name = "lastrowid")]
+    fn lastrowid(&self, _vm: &VirtualMachine) -> Option<i64> {
+        self.lastrowid.get()
+    }
+
+    #[pyproperty(name = "arraysize")]
+    fn arraysize(&self, _vm: &VirtualMachine) -> usize {
+        self.arraysize.get()
+    }
+
+    #[pyproperty(setter)]
+    fn set_arraysize(&self, arraysize: usize, vm: &VirtualMachine) -> PyResult {
+        self.arraysize.set(arraysize);
+        Ok(vm.get_none())
+    }
+}
+
+fn sqlite3_connect(args: ConnectArgs, vm: &VirtualMachine) -> PyResult<PyConnection> {
+    PyConnection::open(args, vm)
+}
+
+fn sqlite_error(vm: &VirtualMachine, name: &str, message: String) -> PyObjectRef {
+    match vm.try_class("sqlite3", name) {
+        Ok(error) => vm.new_exception(error, message),
+        Err(err) => err,
+    }
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    let new_error = |name, base| create_type(name, &ctx.types.type_type, base);
+    let warning = new_error("Warning", &ctx.exceptions.exception_type);
+    let error = new_error("Error", &ctx.exceptions.exception_type);
+    let interface_error = new_error("InterfaceError", &error);
+    let database_error = new_error("DatabaseError", &error);
+
+    let sqlite_version = unsafe { CStr::from_ptr(ffi::sqlite3_libversion()) }
+        .to_string_lossy()
+        .into_owned();
+
+    py_module!(vm, "sqlite3", {
+        "connect" => ctx.new_rustfunc(sqlite3_connect),
+        "Connection" => PyConnection::make_class(ctx),
+        "Cursor" => PyCursor::make_class(ctx),
+        "Warning" => warning,
+        "Error" => error.clone(),
+        "InterfaceError" => interface_error,
+        "DataError" => new_error("DataError", &database_error),
+        "OperationalError" => new_error("OperationalError", &database_error),
+        "IntegrityError" => new_error("IntegrityError", &database_error),
+        "InternalError" => new_error("InternalError", &database_error),
+        "ProgrammingError" => new_error("ProgrammingError", &database_error),
+        "NotSupportedError" => new_error("NotSupportedError", &database_error),
+        "DatabaseError" => database_error,
+        "version" => ctx.new_str("2.6.0".to_string()),
+        "sqlite_version" => ctx.new_str(sqlite_version),
+        "apilevel" => ctx.new_str("2.0".to_string()),
+        "paramstyle" => ctx.new_str("qmark".to_string()),
+        "threadsafety" => ctx.new_int(1),
+    })
+}
@@ -3,12 +3,84 @@
  *
  */
 
-use crate::pyobject::PyObjectRef;
+use std::str::FromStr;
+
+use crate::format::{FieldName, FieldNamePart, FieldType, FormatPart, FormatString};
+use crate::obj::objiter;
+use crate::obj::objstr::{self, PyStringRef};
+use crate::pyobject::{PyObjectRef, PyResult};
 use crate::vm::VirtualMachine;
 
+/// Split a format string into `(literal_text, field_name, format_spec, conversion)`
+/// tuples, the building blocks used by `string.Formatter.parse`.
+fn formatter_parser(text: PyStringRef, vm: &VirtualMachine) -> PyResult {
+    let format_string = FormatString::from_str(text.as_str())
+        .map_err(|err| objstr::new_format_parse_error(vm, err))?;
+
+    let mut result = Vec::new();
+    let mut literal = None;
+    for part in format_string.format_parts {
+        match part {
+            FormatPart::Literal(text) => literal = Some(text),
+            FormatPart::Field {
+                field_name,
+                preconversion_spec,
+                format_spec,
+            } => {
+                result.push(vm.ctx.new_tuple(vec![
+                    vm.new_str(literal.take().unwrap_or_default()),
+                    vm.new_str(field_name),
+                    vm.new_str(format_spec),
+                    preconversion_spec.map_or_else(|| vm.get_none(), |c| vm.new_str(c.to_string())),
+                ]));
+            }
+        }
+    }
+    if let Some(literal) = literal {
+        result.push(vm.ctx.new_tuple(vec![
+            vm.new_str(literal),
+            vm.get_none(),
+            vm.get_none(),
+            vm.get_none(),
+        ]));
+    }
+
+    objiter::get_iter(vm, &vm.ctx.new_list(result))
+}
+
+/// Split a field name into its first component and an iterator over the
+/// `(is_attribute, key)` lookups that follow it.
+fn formatter_field_name_split(text: PyStringRef, vm: &VirtualMachine) -> PyResult {
+    let FieldName { field_type, parts } =
+        FieldName::parse(text.as_str()).map_err(|err| objstr::new_format_parse_error(vm, err))?;
+
+    let first = match field_type {
+        FieldType::Auto => vm.new_str(String::new()),
+        FieldType::Index(index) => vm.ctx.new_int(index),
+        FieldType::Keyword(keyword) => vm.new_str(keyword),
+    };
+    let rest = parts
+        .into_iter()
+        .map(|part| {
+            let (is_attribute, key) = match part {
+                FieldNamePart::Attribute(attribute) => (true, vm.new_str(attribute)),
+                FieldNamePart::Index(index) => (false, vm.ctx.new_int(index)),
+                FieldNamePart::StringIndex(key) => (false, vm.new_str(key)),
+            };
+            vm.ctx.new_tuple(vec![vm.ctx.new_bool(is_attribute), key])
+        })
+        .collect();
+
+    Ok(vm
+        .ctx
+        .new_tuple(vec![first, objiter::get_iter(vm, &vm.ctx.new_list(rest))?]))
+}
+
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
-    // let ctx = &vm.ctx;
+    let ctx = &vm.ctx;
 
-    // Constants:
-    py_module!(vm, "_string", {})
+    py_module!(vm, "_string", {
+        "formatter_parser" => ctx.new_rustfunc(formatter_parser),
+        "formatter_field_name_split" => ctx.new_rustfunc(formatter_field_name_split),
+    })
 }
@@ -176,10 +176,15 @@ impl PopenRef {
         stdin: OptionalArg<PyBytesRef>,
         vm: &VirtualMachine,
     ) -> PyResult<(Option<Vec<u8>>, Option<Vec<u8>>)> {
-        self.process
-            .borrow_mut()
+        let mut process = self.process.borrow_mut();
+        let output = process
             .communicate_bytes(stdin.into_option().as_ref().map(|bytes| bytes.get_value()))
-            .map_err(|err| convert_io_error(vm, err))
+            .map_err(|err| convert_io_error(vm, err))?;
+        // The process has closed its output; wait for it to set returncode.
+        process
+            .wait()
+            .map_err(|err| vm.new_os_error(err.to_string()))?;
+        Ok(output)
     }
 
     fn pid(self, _vm: &VirtualMachine) -> Option<u32> {
@@ -1,7 +1,7 @@
 /// Implementation of the _thread module, currently noop implementation as RustPython doesn't yet
 /// support threading
 use super::super::pyobject::PyObjectRef;
-use crate::function::PyFuncArgs;
+use crate::function::{OptionalArg, PyFuncArgs};
 use crate::pyobject::PyResult;
 use crate::vm::VirtualMachine;
 
@@ -35,9 +35,9 @@ fn get_ident(_vm: &VirtualMachine) -> u32 {
     1
 }
 
-fn allocate_lock(vm: &VirtualMachine) -> PyResult {
-    let lock_class = vm.class("_thread", "RLock");
-    vm.invoke(&lock_class.into_object(), vec![])
+/// The stack size of new threads; 0 means the platform default, which is all there is.
+fn stack_size(_size: OptionalArg<usize>, _vm: &VirtualMachine) -> usize {
+    0
 }
 
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
@@ -50,9 +50,12 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "__exit__" => ctx.new_rustfunc(rlock_exit),
     });
 
+    // allocate_lock is the class itself, so that it keeps working while another module stands
+    // in for _thread in sys.modules, as dummy_threading arranges.
     py_module!(vm, "_thread", {
-        "RLock" => rlock_type,
+        "RLock" => rlock_type.clone(),
         "get_ident" => ctx.new_rustfunc(get_ident),
-        "allocate_lock" => ctx.new_rustfunc(allocate_lock),
+        "stack_size" => ctx.new_rustfunc(stack_size),
+        "allocate_lock" => rlock_type,
     })
 }
@@ -3,6 +3,8 @@
 /// https://docs.python.org/3/library/time.html
 use std::fmt;
 use std::ops::Range;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::function::OptionalArg;
@@ -19,8 +21,8 @@ use crate::vm::VirtualMachine;
 
 use num_traits::cast::ToPrimitive;
 
-use chrono::naive::NaiveDateTime;
-use chrono::{Datelike, Timelike};
+use chrono::naive::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{Datelike, Offset, TimeZone, Timelike};
 
 #[cfg(unix)]
 fn time_sleep(seconds: PyFloatRef, vm: &VirtualMachine) -> PyResult<()> {
@@ -66,6 +68,37 @@ fn time_time(_vm: &VirtualMachine) -> f64 {
 
 #[cfg(target_arch = "wasm32")]
 fn time_time(_vm: &VirtualMachine) -> f64 {
+    time_time_wasm()
+}
+
+/// Elapsed time on the monotonic clock since an arbitrary reference point.
+///
+/// `Instant` is backed by `clock_gettime(CLOCK_MONOTONIC)` on unix,
+/// `mach_absolute_time` on macOS and `QueryPerformanceCounter` on windows, so
+/// successive readings never go backwards.
+#[cfg(not(target_arch = "wasm32"))]
+fn monotonic_duration() -> Duration {
+    lazy_static! {
+        static ref START: Instant = Instant::now();
+    }
+    START.elapsed()
+}
+
+/// `performance.now()` counts milliseconds since the page (or worker) started
+/// and, unlike `Date.now()`, is not affected by changes to the system clock.
+#[cfg(target_arch = "wasm32")]
+fn monotonic_duration() -> Duration {
+    use wasm_bindgen::prelude::*;
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = performance)]
+        fn now() -> f64;
+    }
+    Duration::from_micros((now() * 1000.0) as u64)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn time_time_wasm() -> f64 {
     use wasm_bindgen::prelude::*;
     #[wasm_bindgen]
     extern "C" {
@@ -77,14 +110,59 @@ fn time_time(_vm: &VirtualMachine) -> f64 {
     Date::now() / 1000.0
 }
 
+fn duration_to_nanos(d: Duration) -> u64 {
+    d.as_secs() * 1_000_000_000 + u64::from(d.subsec_nanos())
+}
+
 fn time_monotonic(_vm: &VirtualMachine) -> f64 {
-    // TODO: implement proper monotonic time!
-    match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(v) => duration_to_f64(v),
-        Err(err) => panic!("Time error: {:?}", err),
+    duration_to_f64(monotonic_duration())
+}
+
+fn time_monotonic_ns(_vm: &VirtualMachine) -> u64 {
+    duration_to_nanos(monotonic_duration())
+}
+
+/// `perf_counter` uses the same clock as `monotonic`, which already is the
+/// highest resolution clock that `std` exposes.
+fn time_perf_counter(_vm: &VirtualMachine) -> f64 {
+    duration_to_f64(monotonic_duration())
+}
+
+fn time_perf_counter_ns(_vm: &VirtualMachine) -> u64 {
+    duration_to_nanos(monotonic_duration())
+}
+
+/// CPU time of the current process, read from
+/// `clock_gettime(CLOCK_PROCESS_CPUTIME_ID)`.
+#[cfg(unix)]
+fn process_duration() -> Duration {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    let res = unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut ts) };
+    if res == 0 {
+        Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+    } else {
+        monotonic_duration()
     }
 }
 
+/// No portable process CPU clock is available here, so the monotonic clock
+/// is used instead. This over-counts time spent sleeping.
+#[cfg(not(unix))]
+fn process_duration() -> Duration {
+    monotonic_duration()
+}
+
+fn time_process_time(_vm: &VirtualMachine) -> f64 {
+    duration_to_f64(process_duration())
+}
+
+fn time_process_time_ns(_vm: &VirtualMachine) -> u64 {
+    duration_to_nanos(process_duration())
+}
+
 fn pyfloat_to_secs_and_nanos(seconds: &PyObjectRef) -> (i64, u32) {
     let seconds = objfloat::get_value(seconds);
     let secs: i64 = seconds.trunc() as i64;
@@ -116,18 +194,27 @@ fn time_gmtime(secs: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<
         OptionalArg::Present(secs) => pyobj_to_naive_date_time(&secs, vm)?.unwrap_or(default),
         OptionalArg::Missing => default,
     };
-    let value = PyStructTime::new(instant, 0);
+    let value = PyStructTime::new(instant, 0, 0);
     Ok(value)
 }
 
 fn time_localtime(secs: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<PyStructTime> {
     let instant = optional_or_localtime(secs, vm)?;
+    let gmtoff = local_utc_offset(&instant);
     // TODO: isdst flag must be valid value here
     // https://docs.python.org/3/library/time.html#time.localtime
-    let value = PyStructTime::new(instant, -1);
+    let value = PyStructTime::new(instant, -1, gmtoff);
     Ok(value)
 }
 
+/// Offset of the local timezone from UTC in seconds, at the given local time.
+fn local_utc_offset(instant: &NaiveDateTime) -> i32 {
+    chrono::offset::Local
+        .offset_from_local_datetime(instant)
+        .earliest()
+        .map_or(0, |offset| offset.fix().local_minus_utc())
+}
+
 fn time_mktime(t: PyStructTimeRef, vm: &VirtualMachine) -> PyResult {
     let datetime = t.get_date_time();
     let seconds_since_epoch = datetime.timestamp() as f64;
@@ -141,7 +228,9 @@ fn optional_or_localtime(
 ) -> PyResult<NaiveDateTime> {
     let default = chrono::offset::Local::now().naive_local();
     let instant = match secs {
-        OptionalArg::Present(secs) => pyobj_to_naive_date_time(&secs, vm)?.unwrap_or(default),
+        OptionalArg::Present(secs) => pyobj_to_naive_date_time(&secs, vm)?
+            .map(|utc| chrono::offset::Local.from_utc_datetime(&utc).naive_local())
+            .unwrap_or(default),
         OptionalArg::Missing => default,
     };
     Ok(instant)
@@ -170,34 +259,293 @@ fn time_strftime(
     t: OptionalArg<PyStructTimeRef>,
     vm: &VirtualMachine,
 ) -> PyResult {
-    let default = chrono::offset::Local::now().naive_local();
-    let instant = match t {
-        OptionalArg::Present(t) => t.get_date_time(),
-        OptionalArg::Missing => default,
+    let formatted_time = match t {
+        OptionalArg::Present(t) => strftime(&t, &format.value),
+        OptionalArg::Missing => {
+            let instant = chrono::offset::Local::now().naive_local();
+            let gmtoff = local_utc_offset(&instant);
+            strftime(&PyStructTime::new(instant, -1, gmtoff), &format.value)
+        }
     };
-    let formatted_time = instant.format(&format.value).to_string();
     Ok(vm.ctx.new_str(formatted_time))
 }
 
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+fn format_utc_offset(gmtoff: i32) -> String {
+    let sign = if gmtoff < 0 { '-' } else { '+' };
+    let minutes = gmtoff.abs() / 60;
+    format!("{}{:02}{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// Format a struct_time like C's `strftime`.
+///
+/// The directives documented for the python `time` module are handled here,
+/// anything else is handed to chrono and kept verbatim if chrono rejects it.
+fn strftime(t: &PyStructTime, format: &str) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let directive = match chars.next() {
+            Some(d) => d,
+            None => {
+                out.push('%');
+                break;
+            }
+        };
+        let weekday = t._wday() as usize;
+        let month = (t._mon() - 1) as usize;
+        match directive {
+            'Y' => write!(out, "{}", t._year()).unwrap(),
+            'y' => write!(out, "{:02}", (t._year() % 100 + 100) % 100).unwrap(),
+            'm' => write!(out, "{:02}", t._mon()).unwrap(),
+            'd' => write!(out, "{:02}", t._mday()).unwrap(),
+            'H' => write!(out, "{:02}", t._hour()).unwrap(),
+            'I' => write!(out, "{:02}", (t._hour() + 11) % 12 + 1).unwrap(),
+            'M' => write!(out, "{:02}", t._min()).unwrap(),
+            'S' => write!(out, "{:02}", t._sec()).unwrap(),
+            'j' => write!(out, "{:03}", t._yday()).unwrap(),
+            'a' => out.push_str(&WEEKDAY_NAMES[weekday][..3]),
+            'A' => out.push_str(WEEKDAY_NAMES[weekday]),
+            'b' => out.push_str(&MONTH_NAMES[month][..3]),
+            'B' => out.push_str(MONTH_NAMES[month]),
+            'p' => out.push_str(if t._hour() < 12 { "AM" } else { "PM" }),
+            'w' => write!(out, "{}", (weekday + 1) % 7).unwrap(),
+            'z' => out.push_str(&format_utc_offset(t.gmtoff)),
+            '%' => out.push('%'),
+            other => {
+                let spec = format!("%{}", other);
+                let mut formatted = String::new();
+                if write!(formatted, "{}", t.tm.format(&spec)).is_ok() {
+                    out.push_str(&formatted);
+                } else {
+                    out.push_str(&spec);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Parse state for `strptime`, filled in directive by directive.
+struct StrptimeFields {
+    year: i32,
+    month: u32,
+    day: u32,
+    yday: Option<u32>,
+    hour: u32,
+    hour12: Option<u32>,
+    pm: Option<bool>,
+    minute: u32,
+    second: u32,
+    gmtoff: Option<i32>,
+}
+
+impl Default for StrptimeFields {
+    fn default() -> Self {
+        StrptimeFields {
+            year: 1900,
+            month: 1,
+            day: 1,
+            yday: None,
+            hour: 0,
+            hour12: None,
+            pm: None,
+            minute: 0,
+            second: 0,
+            gmtoff: None,
+        }
+    }
+}
+
+/// Consume up to `max_digits` ascii digits from the front of `input`.
+fn parse_number(input: &mut &str, max_digits: usize) -> Option<u32> {
+    let len = input
+        .char_indices()
+        .take(max_digits)
+        .take_while(|(_, c)| c.is_ascii_digit())
+        .count();
+    if len == 0 {
+        return None;
+    }
+    let value = input[..len].parse().ok()?;
+    *input = &input[len..];
+    Some(value)
+}
+
+/// Consume one of `names` (either spelled out or abbreviated to three
+/// letters, ignoring case) and return its index.
+fn parse_name(input: &mut &str, names: &[&str]) -> Option<usize> {
+    for (index, name) in names.iter().enumerate() {
+        for candidate in &[&name[..], &name[..3]] {
+            let matches = input
+                .get(..candidate.len())
+                .map_or(false, |prefix| prefix.eq_ignore_ascii_case(candidate));
+            if matches {
+                *input = &input[candidate.len()..];
+                return Some(index);
+            }
+        }
+    }
+    None
+}
+
+fn parse_utc_offset(input: &mut &str) -> Option<i32> {
+    if input.starts_with('Z') {
+        *input = &input[1..];
+        return Some(0);
+    }
+    let sign = match input.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits = &input[1..];
+    let offset = digits.get(..4)?;
+    if !offset.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = offset[..2].parse().ok()?;
+    let minutes: i32 = offset[2..].parse().ok()?;
+    *input = &digits[4..];
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Parse `input` according to `format`, returning `None` on any mismatch.
+fn strptime(input: &str, format: &str) -> Option<PyStructTime> {
+    let mut fields = StrptimeFields::default();
+    let mut rest = input;
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            rest = rest.trim_start();
+            continue;
+        }
+        if c != '%' {
+            if !rest.starts_with(c) {
+                return None;
+            }
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        match chars.next()? {
+            'Y' => fields.year = parse_number(&mut rest, 4)? as i32,
+            'y' => {
+                let year = parse_number(&mut rest, 2)? as i32;
+                // POSIX: 69-99 map to 1969-1999, 0-68 to 2000-2068
+                fields.year = if year < 69 { 2000 + year } else { 1900 + year };
+            }
+            'm' => fields.month = parse_number(&mut rest, 2)?,
+            'd' => fields.day = parse_number(&mut rest, 2)?,
+            'H' => fields.hour = parse_number(&mut rest, 2)?,
+            'I' => fields.hour12 = Some(parse_number(&mut rest, 2)?),
+            'M' => fields.minute = parse_number(&mut rest, 2)?,
+            'S' => fields.second = parse_number(&mut rest, 2)?,
+            'j' => fields.yday = Some(parse_number(&mut rest, 3)?),
+            'a' | 'A' => {
+                parse_name(&mut rest, &WEEKDAY_NAMES)?;
+            }
+            'b' | 'B' => fields.month = parse_name(&mut rest, &MONTH_NAMES)? as u32 + 1,
+            'p' => {
+                let lower = rest.get(..2)?.to_lowercase();
+                fields.pm = match lower.as_str() {
+                    "am" => Some(false),
+                    "pm" => Some(true),
+                    _ => return None,
+                };
+                rest = &rest[2..];
+            }
+            'w' => {
+                if parse_number(&mut rest, 1)? > 6 {
+                    return None;
+                }
+            }
+            'z' => fields.gmtoff = Some(parse_utc_offset(&mut rest)?),
+            '%' => {
+                if !rest.starts_with('%') {
+                    return None;
+                }
+                rest = &rest[1..];
+            }
+            _ => return None,
+        }
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+
+    if let Some(hour12) = fields.hour12 {
+        if hour12 < 1 || hour12 > 12 {
+            return None;
+        }
+        fields.hour = hour12 % 12;
+        if fields.pm == Some(true) {
+            fields.hour += 12;
+        }
+    }
+    let date = match fields.yday {
+        Some(yday) => NaiveDate::from_yo_opt(fields.year, yday)?,
+        None => NaiveDate::from_ymd_opt(fields.year, fields.month, fields.day)?,
+    };
+    let time = NaiveTime::from_hms_opt(fields.hour, fields.minute, fields.second)?;
+    Some(PyStructTime::new(
+        NaiveDateTime::new(date, time),
+        -1,
+        fields.gmtoff.unwrap_or(0),
+    ))
+}
+
 fn time_strptime(
     string: PyStringRef,
     format: OptionalArg<PyStringRef>,
     vm: &VirtualMachine,
 ) -> PyResult<PyStructTime> {
-    let format: String = match format {
-        OptionalArg::Present(format) => format.value.clone(),
-        OptionalArg::Missing => "%a %b %H:%M:%S %Y".to_string(),
+    let format = match format {
+        OptionalArg::Present(ref format) => format.value.as_str(),
+        OptionalArg::Missing => "%a %b %d %H:%M:%S %Y",
     };
-    let instant = NaiveDateTime::parse_from_str(&string.value, &format)
-        .map_err(|e| vm.new_value_error(format!("Parse error: {:?}", e)))?;
-    let struct_time = PyStructTime::new(instant, -1);
-    Ok(struct_time)
+    strptime(&string.value, format).ok_or_else(|| {
+        vm.new_value_error(format!(
+            "time data {:?} does not match format {:?}",
+            string.value, format
+        ))
+    })
 }
 
 #[pyclass(name = "struct_time")]
 struct PyStructTime {
     tm: NaiveDateTime,
     isdst: i32,
+    gmtoff: i32,
 }
 
 type PyStructTimeRef = PyRef<PyStructTime>;
@@ -216,8 +564,8 @@ impl PyValue for PyStructTime {
 
 #[pyimpl]
 impl PyStructTime {
-    fn new(tm: NaiveDateTime, isdst: i32) -> Self {
-        PyStructTime { tm, isdst }
+    fn new(tm: NaiveDateTime, isdst: i32, gmtoff: i32) -> Self {
+        PyStructTime { tm, isdst, gmtoff }
     }
 
     #[pymethod(name = "__repr__")]
@@ -348,6 +696,11 @@ impl PyStructTime {
     fn tm_isdst(&self, _vm: &VirtualMachine) -> i32 {
         self._isdst()
     }
+
+    #[pyproperty(name = "tm_gmtoff")]
+    fn tm_gmtoff(&self, _vm: &VirtualMachine) -> i32 {
+        self.gmtoff
+    }
 }
 
 type TmFunction = fn(&PyStructTime) -> i32;
@@ -428,6 +781,11 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "mktime" => ctx.new_rustfunc(time_mktime),
         "localtime" => ctx.new_rustfunc(time_localtime),
         "monotonic" => ctx.new_rustfunc(time_monotonic),
+        "monotonic_ns" => ctx.new_rustfunc(time_monotonic_ns),
+        "perf_counter" => ctx.new_rustfunc(time_perf_counter),
+        "perf_counter_ns" => ctx.new_rustfunc(time_perf_counter_ns),
+        "process_time" => ctx.new_rustfunc(time_process_time),
+        "process_time_ns" => ctx.new_rustfunc(time_process_time_ns),
         "strftime" => ctx.new_rustfunc(time_strftime),
         "strptime" => ctx.new_rustfunc(time_strptime),
         "sleep" => ctx.new_rustfunc(time_sleep),
@@ -23,12 +23,20 @@ fn tokenize_tokenize(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
     Ok(vm.ctx.new_list(tokens))
 }
 
+fn tokenize_open(vm: &VirtualMachine, args: PyFuncArgs) -> PyResult {
+    arg_check!(vm, args, required = [(filename, None)]);
+    // TODO: detect the encoding from a coding cookie or BOM.
+    let open = vm.get_attribute(vm.builtins.clone(), "open")?;
+    vm.invoke(&open, vec![filename.clone()])
+}
+
 // TODO: create main function when called with -m
 
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let ctx = &vm.ctx;
 
     py_module!(vm, "tokenize", {
-        "tokenize" => ctx.new_rustfunc(tokenize_tokenize)
+        "tokenize" => ctx.new_rustfunc(tokenize_tokenize),
+        "open" => ctx.new_rustfunc(tokenize_open)
     })
 }
@@ -0,0 +1,224 @@
+/* Object allocation tracing, the native half of the tracemalloc module.
+ *
+ * RustPython has no allocator hooks, so rather than raw memory blocks this
+ * traces Python objects: an object is recorded when it is created and
+ * forgotten when it is freed. Sizes are approximate: they cover the object
+ * header and its inline payload, but not heap buffers the payload owns
+ * (string data, list storage, ...). Each object is attributed to the line
+ * the innermost Python frame was executing, so the traceback limit is
+ * always one frame.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::mem;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::function::OptionalArg;
+use crate::pyobject::{PyObjectRef, PyResult};
+use crate::vm::VirtualMachine;
+
+thread_local! {
+    static TRACING: Cell<bool> = Cell::new(false);
+    static TRACER: RefCell<Tracer> = RefCell::new(Tracer::default());
+}
+
+#[derive(Default)]
+struct Tracer {
+    traceback_limit: usize,
+    /// The line the innermost running frame is executing.
+    filename: Option<Rc<str>>,
+    lineno: usize,
+    /// Live traced objects, keyed by address.
+    traces: HashMap<usize, Trace>,
+    current: usize,
+    peak: usize,
+}
+
+impl Tracer {
+    fn clear(&mut self) {
+        self.traces.clear();
+        self.current = 0;
+        self.peak = 0;
+    }
+}
+
+struct Trace {
+    size: usize,
+    filename: Rc<str>,
+    lineno: usize,
+}
+
+/// The number of threads that are tracing. The hooks on every object
+/// allocation and every instruction check this before the thread local, so
+/// that they cost a single load when nothing is traced.
+static TRACING_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+#[inline]
+pub fn is_tracing() -> bool {
+    TRACING_THREADS.load(Ordering::Relaxed) != 0 && TRACING.try_with(Cell::get).unwrap_or(false)
+}
+
+fn set_tracing(tracing: bool) {
+    TRACING.with(|cell| {
+        if cell.replace(tracing) != tracing {
+            if tracing {
+                TRACING_THREADS.fetch_add(1, Ordering::Relaxed);
+            } else {
+                TRACING_THREADS.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    });
+}
+
+fn with_tracer<R>(f: impl FnOnce(&mut Tracer) -> R) -> Option<R> {
+    // Objects are created and freed while the tracer is being inspected,
+    // and after the thread locals are gone; those events are skipped.
+    TRACER
+        .try_with(|tracer| tracer.try_borrow_mut().ok().map(|mut t| f(&mut t)))
+        .ok()
+        .and_then(|result| result)
+}
+
+/// Record the line a frame is about to execute.
+pub fn set_location(filename: &str, lineno: usize) {
+    with_tracer(|tracer| {
+        if tracer.filename.as_ref().map(|f| &**f) != Some(filename) {
+            tracer.filename = Some(Rc::from(filename));
+        }
+        tracer.lineno = lineno;
+    });
+}
+
+pub fn record_alloc(obj: &PyObjectRef, size: usize) {
+    let address = &**obj as *const _ as *const u8 as usize;
+    with_tracer(|tracer| {
+        let filename = match &tracer.filename {
+            Some(filename) => filename.clone(),
+            None => Rc::from("<unknown>"),
+        };
+        let trace = Trace {
+            size,
+            filename,
+            lineno: tracer.lineno,
+        };
+        if let Some(old) = tracer.traces.insert(address, trace) {
+            tracer.current -= old.size;
+        }
+        tracer.current += size;
+        tracer.peak = tracer.peak.max(tracer.current);
+    });
+}
+
+pub fn record_free(address: usize) {
+    with_tracer(|tracer| {
+        if let Some(trace) = tracer.traces.remove(&address) {
+            tracer.current -= trace.size;
+        }
+    });
+}
+
+fn tracemalloc_start(nframe: OptionalArg<usize>, vm: &VirtualMachine) -> PyResult<()> {
+    let nframe = nframe.unwrap_or(1);
+    if !(1..=65535).contains(&nframe) {
+        return Err(
+            vm.new_value_error("the number of frames must be in range [1; 65535]".to_string())
+        );
+    }
+    with_tracer(|tracer| tracer.traceback_limit = nframe);
+    set_tracing(true);
+    Ok(())
+}
+
+fn tracemalloc_stop(_vm: &VirtualMachine) {
+    set_tracing(false);
+    with_tracer(Tracer::clear);
+}
+
+fn tracemalloc_is_tracing(_vm: &VirtualMachine) -> bool {
+    is_tracing()
+}
+
+fn tracemalloc_clear_traces(_vm: &VirtualMachine) {
+    with_tracer(Tracer::clear);
+}
+
+fn tracemalloc_get_traced_memory(_vm: &VirtualMachine) -> (usize, usize) {
+    with_tracer(|tracer| (tracer.current, tracer.peak)).unwrap_or((0, 0))
+}
+
+fn tracemalloc_get_tracemalloc_memory(_vm: &VirtualMachine) -> usize {
+    with_tracer(|tracer| tracer.traces.capacity() * mem::size_of::<(usize, Trace)>()).unwrap_or(0)
+}
+
+fn tracemalloc_get_traceback_limit(_vm: &VirtualMachine) -> usize {
+    with_tracer(|tracer| tracer.traceback_limit.max(1)).unwrap_or(1)
+}
+
+fn new_traceback(filename: &str, lineno: usize, vm: &VirtualMachine) -> PyObjectRef {
+    let frame = vm.ctx.new_tuple(vec![
+        vm.new_str(filename.to_string()),
+        vm.ctx.new_int(lineno),
+    ]);
+    vm.ctx.new_tuple(vec![frame])
+}
+
+fn tracemalloc_get_traces(vm: &VirtualMachine) -> PyObjectRef {
+    let traces: Vec<(usize, Rc<str>, usize)> = with_tracer(|tracer| {
+        tracer
+            .traces
+            .values()
+            .map(|trace| (trace.size, trace.filename.clone(), trace.lineno))
+            .collect()
+    })
+    .unwrap_or_default();
+
+    // Don't trace the objects describing the traces.
+    let tracing = is_tracing();
+    set_tracing(false);
+    let traces = traces
+        .into_iter()
+        .map(|(size, filename, lineno)| {
+            vm.ctx.new_tuple(vec![
+                vm.ctx.new_int(0),
+                vm.ctx.new_int(size),
+                new_traceback(&filename, lineno, vm),
+            ])
+        })
+        .collect();
+    let traces = vm.ctx.new_list(traces);
+    set_tracing(tracing);
+    traces
+}
+
+fn tracemalloc_get_object_traceback(obj: PyObjectRef, vm: &VirtualMachine) -> PyObjectRef {
+    let address = &*obj as *const _ as *const u8 as usize;
+    let location = with_tracer(|tracer| {
+        tracer
+            .traces
+            .get(&address)
+            .map(|trace| (trace.filename.clone(), trace.lineno))
+    })
+    .and_then(|location| location);
+    match location {
+        Some((filename, lineno)) => new_traceback(&filename, lineno, vm),
+        None => vm.get_none(),
+    }
+}
+
+pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+
+    py_module!(vm, "_tracemalloc", {
+        "start" => ctx.new_rustfunc(tracemalloc_start),
+        "stop" => ctx.new_rustfunc(tracemalloc_stop),
+        "is_tracing" => ctx.new_rustfunc(tracemalloc_is_tracing),
+        "clear_traces" => ctx.new_rustfunc(tracemalloc_clear_traces),
+        "get_traced_memory" => ctx.new_rustfunc(tracemalloc_get_traced_memory),
+        "get_tracemalloc_memory" => ctx.new_rustfunc(tracemalloc_get_tracemalloc_memory),
+        "get_traceback_limit" => ctx.new_rustfunc(tracemalloc_get_traceback_limit),
+        "_get_traces" => ctx.new_rustfunc(tracemalloc_get_traces),
+        "_get_object_traceback" => ctx.new_rustfunc(tracemalloc_get_object_traceback),
+    })
+}
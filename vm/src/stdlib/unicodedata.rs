@@ -1,14 +1,22 @@
 /* Access to the unicode database.
    See also: https://docs.python.org/3/library/unicodedata.html
+
+   General category, names, bidirectional class, combining class and the
+   normalization forms come from the full UCD via `unic`. Numeric values are
+   derived from the general category for decimal digits and from the tables
+   below for everything else; `NUMERIC_RANGES` only covers the Latin-1 and
+   number-form fractions, Roman and enclosed numerals and the common CJK
+   numerals, so `numeric()` raises for the rarer historic number systems.
 */
 
 use crate::function::OptionalArg;
 use crate::obj::objstr::PyStringRef;
-use crate::pyobject::{PyObjectRef, PyResult};
+use crate::pyobject::{IntoPyObject, PyObjectRef, PyResult};
 use crate::vm::VirtualMachine;
 
 use unic::char::property::EnumeratedCharProperty;
 use unic::ucd::category::GeneralCategory;
+use unic::ucd::normal::CanonicalCombiningClass;
 use unic::ucd::Name;
 use unicode_names2;
 
@@ -20,6 +28,10 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     py_module!(vm, "unicodedata", {
         "bidirectional" => ctx.new_rustfunc(bidirectional),
         "category" => ctx.new_rustfunc(category),
+        "combining" => ctx.new_rustfunc(combining),
+        "decimal" => ctx.new_rustfunc(decimal),
+        "digit" => ctx.new_rustfunc(digit),
+        "numeric" => ctx.new_rustfunc(numeric),
         "name" => ctx.new_rustfunc(name),
         "lookup" => ctx.new_rustfunc(lookup),
         "normalize" => ctx.new_rustfunc(normalize),
@@ -68,6 +80,51 @@ fn bidirectional(character: PyStringRef, vm: &VirtualMachine) -> PyResult {
     Ok(vm.new_str(cls.abbr_name().to_string()))
 }
 
+fn combining(character: PyStringRef, vm: &VirtualMachine) -> PyResult<u8> {
+    let my_char = extract_char(character, vm)?;
+    Ok(CanonicalCombiningClass::of(my_char).number())
+}
+
+fn numeric_value_or_default<T: IntoPyObject>(
+    value: Option<T>,
+    default: OptionalArg<PyObjectRef>,
+    msg: &str,
+    vm: &VirtualMachine,
+) -> PyResult {
+    match (value, default) {
+        (Some(value), _) => value.into_pyobject(vm),
+        (None, OptionalArg::Present(obj)) => Ok(obj),
+        (None, OptionalArg::Missing) => Err(vm.new_value_error(msg.to_string())),
+    }
+}
+
+fn decimal(
+    character: PyStringRef,
+    default: OptionalArg<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let my_char = extract_char(character, vm)?;
+    numeric_value_or_default(to_decimal(my_char), default, "not a decimal", vm)
+}
+
+fn digit(
+    character: PyStringRef,
+    default: OptionalArg<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let my_char = extract_char(character, vm)?;
+    numeric_value_or_default(to_digit(my_char), default, "not a digit", vm)
+}
+
+fn numeric(
+    character: PyStringRef,
+    default: OptionalArg<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult {
+    let my_char = extract_char(character, vm)?;
+    numeric_value_or_default(to_numeric(my_char), default, "not a numeric character", vm)
+}
+
 fn normalize(form: PyStringRef, unistr: PyStringRef, vm: &VirtualMachine) -> PyResult {
     use unic::normal::StrNormalForm;
     let text = &unistr.value;
@@ -77,7 +134,7 @@ fn normalize(form: PyStringRef, unistr: PyStringRef, vm: &VirtualMachine) -> PyR
         "NFD" => text.nfd().collect::<String>(),
         "NFKD" => text.nfkd().collect::<String>(),
         _ => {
-            return Err(vm.new_value_error("unistr must be one of NFC, NFD".to_string()));
+            return Err(vm.new_value_error("invalid normalization form".to_string()));
         }
     };
 
@@ -85,10 +142,157 @@ fn normalize(form: PyStringRef, unistr: PyStringRef, vm: &VirtualMachine) -> PyR
 }
 
 fn extract_char(character: PyStringRef, vm: &VirtualMachine) -> PyResult<char> {
-    if character.value.len() != 1 {
-        return Err(vm.new_type_error("argument must be an unicode character, not str".to_string()));
+    let mut chars = character.value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(my_char), None) => Ok(my_char),
+        _ => Err(vm.new_type_error("argument must be an unicode character, not str".to_string())),
     }
+}
 
-    let my_char: char = character.value.chars().next().unwrap();
-    Ok(my_char)
+/// The value of a character with the `Decimal` numeric type.
+///
+/// Unicode guarantees that decimal digits (`Nd`) are encoded in contiguous
+/// runs of ten starting at zero, so the value follows from the position
+/// inside the run.
+pub fn to_decimal(c: char) -> Option<u32> {
+    let is_decimal = |c: char| GeneralCategory::of(c) == GeneralCategory::DecimalNumber;
+    if !is_decimal(c) {
+        return None;
+    }
+    let mut start = c as u32;
+    while let Some(prev) = start.checked_sub(1).and_then(std::char::from_u32) {
+        if !is_decimal(prev) {
+            break;
+        }
+        start -= 1;
+    }
+    Some((c as u32 - start) % 10)
+}
+
+/// The value of a character with the `Decimal` or `Digit` numeric type.
+pub fn to_digit(c: char) -> Option<u32> {
+    to_decimal(c).or_else(|| {
+        DIGIT_RANGES
+            .iter()
+            .find(|(start, end, _)| *start <= c && c <= *end)
+            .map(|(start, _, value)| value + (c as u32 - *start as u32))
+    })
 }
+
+/// The value of a character with any numeric type, see the module
+/// documentation for the characters covered.
+pub fn to_numeric(c: char) -> Option<f64> {
+    to_digit(c).map(f64::from).or_else(|| {
+        NUMERIC_RANGES
+            .iter()
+            .find(|(start, end, _)| *start <= c && c <= *end)
+            .map(|(start, _, value)| value + f64::from(c as u32 - *start as u32))
+    })
+}
+
+/// Characters with `Numeric_Type=Digit` that are not decimal digits, as
+/// `(first, last, value of first)` runs of consecutive values.
+const DIGIT_RANGES: &[(char, char, u32)] = &[
+    ('\u{b2}', '\u{b3}', 2),
+    ('\u{b9}', '\u{b9}', 1),
+    ('\u{1369}', '\u{1371}', 1),
+    ('\u{19da}', '\u{19da}', 1),
+    ('\u{2070}', '\u{2070}', 0),
+    ('\u{2074}', '\u{2079}', 4),
+    ('\u{2080}', '\u{2089}', 0),
+    ('\u{2460}', '\u{2468}', 1),
+    ('\u{2474}', '\u{247c}', 1),
+    ('\u{2488}', '\u{2490}', 1),
+    ('\u{24ea}', '\u{24ea}', 0),
+    ('\u{24f5}', '\u{24fd}', 1),
+    ('\u{24ff}', '\u{24ff}', 0),
+    ('\u{2776}', '\u{277e}', 1),
+    ('\u{2780}', '\u{2788}', 1),
+    ('\u{278a}', '\u{2792}', 1),
+    ('\u{10a40}', '\u{10a43}', 1),
+    ('\u{10e60}', '\u{10e68}', 1),
+    ('\u{11052}', '\u{1105a}', 1),
+];
+
+/// A subset of the characters with `Numeric_Type=Numeric`, in the same
+/// layout as `DIGIT_RANGES`. Single entries are used for fractions.
+const NUMERIC_RANGES: &[(char, char, f64)] = &[
+    ('\u{bc}', '\u{bc}', 1.0 / 4.0),
+    ('\u{bd}', '\u{bd}', 1.0 / 2.0),
+    ('\u{be}', '\u{be}', 3.0 / 4.0),
+    ('\u{2150}', '\u{2150}', 1.0 / 7.0),
+    ('\u{2151}', '\u{2151}', 1.0 / 9.0),
+    ('\u{2152}', '\u{2152}', 1.0 / 10.0),
+    ('\u{2153}', '\u{2153}', 1.0 / 3.0),
+    ('\u{2154}', '\u{2154}', 2.0 / 3.0),
+    ('\u{2155}', '\u{2155}', 1.0 / 5.0),
+    ('\u{2156}', '\u{2156}', 2.0 / 5.0),
+    ('\u{2157}', '\u{2157}', 3.0 / 5.0),
+    ('\u{2158}', '\u{2158}', 4.0 / 5.0),
+    ('\u{2159}', '\u{2159}', 1.0 / 6.0),
+    ('\u{215a}', '\u{215a}', 5.0 / 6.0),
+    ('\u{215b}', '\u{215b}', 1.0 / 8.0),
+    ('\u{215c}', '\u{215c}', 3.0 / 8.0),
+    ('\u{215d}', '\u{215d}', 5.0 / 8.0),
+    ('\u{215e}', '\u{215e}', 7.0 / 8.0),
+    ('\u{215f}', '\u{215f}', 1.0),
+    ('\u{2160}', '\u{216b}', 1.0),
+    ('\u{216c}', '\u{216c}', 50.0),
+    ('\u{216d}', '\u{216d}', 100.0),
+    ('\u{216e}', '\u{216e}', 500.0),
+    ('\u{216f}', '\u{216f}', 1000.0),
+    ('\u{2170}', '\u{217b}', 1.0),
+    ('\u{217c}', '\u{217c}', 50.0),
+    ('\u{217d}', '\u{217d}', 100.0),
+    ('\u{217e}', '\u{217e}', 500.0),
+    ('\u{217f}', '\u{217f}', 1000.0),
+    ('\u{2180}', '\u{2180}', 1000.0),
+    ('\u{2181}', '\u{2181}', 5000.0),
+    ('\u{2182}', '\u{2182}', 10000.0),
+    ('\u{2185}', '\u{2185}', 6.0),
+    ('\u{2186}', '\u{2186}', 50.0),
+    ('\u{2187}', '\u{2187}', 50000.0),
+    ('\u{2188}', '\u{2188}', 100000.0),
+    ('\u{2189}', '\u{2189}', 0.0),
+    ('\u{2469}', '\u{2473}', 10.0),
+    ('\u{247d}', '\u{2487}', 10.0),
+    ('\u{2491}', '\u{249b}', 10.0),
+    ('\u{24eb}', '\u{24f4}', 11.0),
+    ('\u{24fe}', '\u{24fe}', 10.0),
+    ('\u{277f}', '\u{277f}', 10.0),
+    ('\u{2789}', '\u{2789}', 10.0),
+    ('\u{2793}', '\u{2793}', 10.0),
+    ('\u{3007}', '\u{3007}', 0.0),
+    ('\u{3021}', '\u{3029}', 1.0),
+    ('\u{3038}', '\u{3038}', 10.0),
+    ('\u{3039}', '\u{3039}', 20.0),
+    ('\u{303a}', '\u{303a}', 30.0),
+    ('\u{3192}', '\u{3195}', 1.0),
+    ('\u{3220}', '\u{3229}', 1.0),
+    ('\u{3248}', '\u{3248}', 10.0),
+    ('\u{3249}', '\u{3249}', 20.0),
+    ('\u{324a}', '\u{324a}', 30.0),
+    ('\u{324b}', '\u{324b}', 40.0),
+    ('\u{324c}', '\u{324c}', 50.0),
+    ('\u{324d}', '\u{324d}', 60.0),
+    ('\u{324e}', '\u{324e}', 70.0),
+    ('\u{324f}', '\u{324f}', 80.0),
+    ('\u{3251}', '\u{325f}', 21.0),
+    ('\u{3280}', '\u{3289}', 1.0),
+    ('\u{32b1}', '\u{32bf}', 36.0),
+    ('\u{4e00}', '\u{4e00}', 1.0),
+    ('\u{4e03}', '\u{4e03}', 7.0),
+    ('\u{4e07}', '\u{4e07}', 10000.0),
+    ('\u{4e09}', '\u{4e09}', 3.0),
+    ('\u{4e5d}', '\u{4e5d}', 9.0),
+    ('\u{4e8c}', '\u{4e8c}', 2.0),
+    ('\u{4e94}', '\u{4e94}', 5.0),
+    ('\u{5104}', '\u{5104}', 100000000.0),
+    ('\u{5146}', '\u{5146}', 1000000000000.0),
+    ('\u{516b}', '\u{516b}', 8.0),
+    ('\u{516d}', '\u{516d}', 6.0),
+    ('\u{5341}', '\u{5341}', 10.0),
+    ('\u{5343}', '\u{5343}', 1000.0),
+    ('\u{56db}', '\u{56db}', 4.0),
+    ('\u{767e}', '\u{767e}', 100.0),
+];
@@ -1,36 +1,443 @@
 use crate::function::OptionalArg;
-use crate::obj::objstr::PyStringRef;
-use crate::pyobject::PyObjectRef;
+use crate::obj::objbool;
+use crate::obj::objdict::PyDictRef;
+use crate::obj::objint::PyIntRef;
+use crate::obj::objstr::{PyString, PyStringRef};
+use crate::obj::objtuple::PyTuple;
+use crate::obj::objtype::{self, PyClassRef};
+use crate::pyobject::{ItemProtocol, PyObjectRef, PyResult, TryFromObject, TypeProtocol};
 use crate::vm::VirtualMachine;
 
 #[derive(FromArgs)]
 struct WarnArgs {
-    #[pyarg(positional_only, optional = false)]
-    message: PyStringRef,
+    #[pyarg(positional_or_keyword, optional = false)]
+    message: PyObjectRef,
     #[pyarg(positional_or_keyword, optional = true)]
     category: OptionalArg<PyObjectRef>,
     #[pyarg(positional_or_keyword, optional = true)]
-    stacklevel: OptionalArg<u32>,
+    stacklevel: OptionalArg<isize>,
+    #[pyarg(positional_or_keyword, optional = true)]
+    source: OptionalArg<PyObjectRef>,
 }
 
-fn warnings_warn(args: WarnArgs, _vm: &VirtualMachine) {
-    // TODO: Implement correctly
-    let level = match args.stacklevel {
-        OptionalArg::Present(l) => l,
-        OptionalArg::Missing => 1,
-    };
-    eprintln!(
-        "Warning: {} , category: {:?}, level: {}",
-        args.message.as_str(),
-        args.category,
-        level
+#[derive(FromArgs)]
+struct WarnExplicitArgs {
+    #[pyarg(positional_or_keyword, optional = false)]
+    message: PyObjectRef,
+    #[pyarg(positional_or_keyword, optional = false)]
+    category: PyObjectRef,
+    #[pyarg(positional_or_keyword, optional = false)]
+    filename: PyStringRef,
+    #[pyarg(positional_or_keyword, optional = false)]
+    lineno: usize,
+    #[pyarg(positional_or_keyword, default = "None")]
+    module: Option<PyStringRef>,
+    #[pyarg(positional_or_keyword, default = "None")]
+    registry: Option<PyDictRef>,
+    #[pyarg(positional_or_keyword, default = "None")]
+    #[allow(dead_code)]
+    module_globals: Option<PyObjectRef>,
+    #[pyarg(positional_or_keyword, default = "None")]
+    source: Option<PyObjectRef>,
+}
+
+/// Where a warning is reported from, as determined by `setup_context`.
+struct WarningContext {
+    filename: PyStringRef,
+    lineno: usize,
+    module: PyStringRef,
+    registry: PyDictRef,
+}
+
+fn warnings_warn(args: WarnArgs, vm: &VirtualMachine) -> PyResult<()> {
+    let category = get_category(&args.message, args.category.into_option(), vm)?;
+    let context = setup_context(args.stacklevel.unwrap_or(1), vm)?;
+    warn_explicit(
+        category,
+        args.message,
+        context.filename,
+        context.lineno,
+        Some(context.module),
+        Some(context.registry),
+        args.source.into_option(),
+        vm,
     )
 }
 
+fn warnings_warn_explicit(args: WarnExplicitArgs, vm: &VirtualMachine) -> PyResult<()> {
+    let category = PyClassRef::try_from_object(vm, args.category)?;
+    warn_explicit(
+        category,
+        args.message,
+        args.filename,
+        args.lineno,
+        args.module,
+        args.registry,
+        args.source,
+        vm,
+    )
+}
+
+fn warnings_filters_mutated(vm: &VirtualMachine) -> PyResult<()> {
+    let module = vm.import("_warnings", &[], 0)?;
+    let version = vm.get_attribute(module.clone(), "_filters_version")?;
+    let version = PyIntRef::try_from_object(vm, version)?.as_bigint() + 1;
+    vm.set_attr(&module, "_filters_version", vm.new_int(version))?;
+    Ok(())
+}
+
+fn get_category(
+    message: &PyObjectRef,
+    category: Option<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<PyClassRef> {
+    if objtype::isinstance(message, &vm.ctx.exceptions.warning) {
+        return Ok(message.class());
+    }
+    let category = match category {
+        Some(category) if !vm.is_none(&category) => category,
+        _ => return Ok(vm.ctx.exceptions.user_warning.clone()),
+    };
+    match category.clone().downcast::<objtype::PyClass>() {
+        Ok(cls) if objtype::issubclass(&cls, &vm.ctx.exceptions.warning) => Ok(cls),
+        _ => Err(vm.new_type_error(format!(
+            "category must be a Warning subclass, not '{}'",
+            category.class().name
+        ))),
+    }
+}
+
+/// Find the globals, location and registry of the frame `stacklevel` levels up.
+fn setup_context(stacklevel: isize, vm: &VirtualMachine) -> PyResult<WarningContext> {
+    let frame = {
+        let frames = vm.frames.borrow();
+        if stacklevel > 0 && stacklevel as usize <= frames.len() {
+            Some(frames[frames.len() - stacklevel as usize].clone())
+        } else {
+            None
+        }
+    };
+
+    let (globals, filename, lineno) = match frame {
+        Some(frame) => (
+            frame.scope.globals.clone(),
+            frame.code.source_path.clone(),
+            frame.get_lineno().row(),
+        ),
+        None => {
+            let globals = vm.get_attribute(vm.sys_module.clone(), "__dict__")?;
+            (
+                PyDictRef::try_from_object(vm, globals)?,
+                "sys".to_string(),
+                1,
+            )
+        }
+    };
+
+    let registry = match globals.get_item_option("__warningregistry__", vm)? {
+        Some(registry) => PyDictRef::try_from_object(vm, registry)?,
+        None => {
+            let registry = vm.ctx.new_dict();
+            globals.set_item("__warningregistry__", registry.clone().into_object(), vm)?;
+            registry
+        }
+    };
+
+    let module = match globals.get_item_option("__name__", vm)? {
+        Some(name) => match name.downcast::<PyString>() {
+            Ok(name) => name,
+            Err(_) => vm.new_str("<string>".to_string()).downcast().unwrap(),
+        },
+        None => vm.new_str("<string>".to_string()).downcast().unwrap(),
+    };
+
+    Ok(WarningContext {
+        filename: vm.new_str(filename).downcast().unwrap(),
+        lineno,
+        module,
+        registry,
+    })
+}
+
+/// Look up an attribute of the `warnings` module if it has been imported,
+/// and of this module otherwise.
+fn get_warnings_attr(name: &str, vm: &VirtualMachine) -> PyResult {
+    let modules = vm.get_attribute(vm.sys_module.clone(), "modules")?;
+    let modules = PyDictRef::try_from_object(vm, modules)?;
+    if let Some(warnings) = modules.get_item_option("warnings", vm)? {
+        if let Ok(attr) = vm.get_attribute(warnings, name) {
+            return Ok(attr);
+        }
+    }
+    let module = vm.import("_warnings", &[], 0)?;
+    let private_name = match name {
+        "defaultaction" | "onceregistry" => format!("_{}", name),
+        _ => name.to_string(),
+    };
+    vm.get_attribute(module, private_name)
+}
+
+/// A filter field matches if it is None, an equal string, or a regex that matches.
+fn check_matched(pattern: &PyObjectRef, arg: &PyObjectRef, vm: &VirtualMachine) -> PyResult<bool> {
+    if vm.is_none(pattern) {
+        return Ok(true);
+    }
+    if let Some(pattern) = pattern.payload::<PyString>() {
+        return match arg.payload::<PyString>() {
+            Some(arg) => Ok(pattern.as_str() == arg.as_str()),
+            None => Ok(false),
+        };
+    }
+    let result = vm.call_method(pattern, "match", vec![arg.clone()])?;
+    objbool::boolval(vm, result)
+}
+
+/// Find the action of the first filter that applies to this warning.
+fn get_filter(
+    category: &PyClassRef,
+    text: &PyObjectRef,
+    lineno: usize,
+    module: &PyObjectRef,
+    vm: &VirtualMachine,
+) -> PyResult<PyObjectRef> {
+    let filters = get_warnings_attr("filters", vm)?;
+    if !objtype::isinstance(&filters, &vm.ctx.list_type()) {
+        return Err(vm.new_value_error("_warnings.filters must be a list".to_string()));
+    }
+
+    for (i, filter) in vm.extract_elements(&filters)?.into_iter().enumerate() {
+        let filter: Vec<PyObjectRef> = match filter.payload::<PyTuple>() {
+            Some(tuple) if tuple.elements.len() == 5 => tuple.elements.clone(),
+            _ => {
+                return Err(
+                    vm.new_value_error(format!("_warnings.filters item {} isn't a 5-tuple", i))
+                )
+            }
+        };
+        let (action, msg, cat, module_pattern, ln) =
+            (&filter[0], &filter[1], &filter[2], &filter[3], &filter[4]);
+
+        let cat = PyClassRef::try_from_object(vm, cat.clone())?;
+        let ln = PyIntRef::try_from_object(vm, ln.clone())?;
+        let ln = ln.as_bigint();
+        if check_matched(msg, text, vm)?
+            && objtype::issubclass(category, &cat)
+            && check_matched(module_pattern, module, vm)?
+            && (*ln == 0.into() || *ln == lineno.into())
+        {
+            return Ok(action.clone());
+        }
+    }
+
+    get_warnings_attr("defaultaction", vm)
+}
+
+/// Check whether `key` is already in the registry, and optionally add it.
+/// The registry is reset whenever the filters have changed.
+fn already_warned(
+    registry: &PyDictRef,
+    key: PyObjectRef,
+    should_set: bool,
+    vm: &VirtualMachine,
+) -> PyResult<bool> {
+    let module = vm.import("_warnings", &[], 0)?;
+    let version = vm.get_attribute(module, "_filters_version")?;
+    let registry_version = registry.get_item_option("version", vm)?;
+    let up_to_date = match registry_version {
+        Some(registry_version) => objbool::boolval(vm, vm._eq(registry_version, version.clone())?)?,
+        None => false,
+    };
+    if !up_to_date {
+        vm.call_method(registry.as_object(), "clear", vec![])?;
+        registry.set_item("version", version, vm)?;
+    }
+
+    if let Some(warned) = registry.get_item_option(&key, vm)? {
+        if objbool::boolval(vm, warned)? {
+            return Ok(true);
+        }
+    }
+    if should_set {
+        registry.set_item(&key, vm.new_bool(true), vm)?;
+    }
+    Ok(false)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn warn_explicit(
+    category: PyClassRef,
+    message: PyObjectRef,
+    filename: PyStringRef,
+    lineno: usize,
+    module: Option<PyStringRef>,
+    registry: Option<PyDictRef>,
+    source: Option<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let module = match module {
+        Some(module) => module.into_object(),
+        None => {
+            let filename = filename.as_str();
+            let module = if filename.ends_with(".py") {
+                &filename[..filename.len() - 3]
+            } else {
+                filename
+            };
+            vm.new_str(module.to_string())
+        }
+    };
+
+    let (text, category, message) = if objtype::isinstance(&message, &vm.ctx.exceptions.warning) {
+        (vm.to_str(&message)?.into_object(), message.class(), message)
+    } else {
+        let warning = vm.invoke(category.as_object(), vec![message.clone()])?;
+        (message, category, warning)
+    };
+
+    let key = vm.ctx.new_tuple(vec![
+        text.clone(),
+        category.clone().into_object(),
+        vm.new_int(lineno),
+    ]);
+    if let Some(registry) = &registry {
+        if already_warned(registry, key.clone(), false, vm)? {
+            return Ok(());
+        }
+    }
+
+    let action = get_filter(&category, &text, lineno, &module, vm)?;
+    let action_str = vm.to_str(&action)?;
+    match action_str.as_str() {
+        "error" => return Err(message),
+        "ignore" => return Ok(()),
+        "always" => {}
+        action_name => {
+            // Remember that this location has warned, unless the action is "always".
+            if let Some(registry) = &registry {
+                registry.set_item(&key, vm.new_bool(true), vm)?;
+            }
+            let already = match action_name {
+                "once" => {
+                    let onceregistry = get_warnings_attr("onceregistry", vm)?;
+                    let onceregistry = PyDictRef::try_from_object(vm, onceregistry)?;
+                    let oncekey = vm
+                        .ctx
+                        .new_tuple(vec![text.clone(), category.clone().into_object()]);
+                    already_warned(&onceregistry, oncekey, true, vm)?
+                }
+                "module" => match &registry {
+                    Some(registry) => {
+                        let altkey = vm.ctx.new_tuple(vec![
+                            text.clone(),
+                            category.clone().into_object(),
+                            vm.new_int(0),
+                        ]);
+                        already_warned(registry, altkey, true, vm)?
+                    }
+                    None => false,
+                },
+                "default" => false,
+                _ => {
+                    return Err(vm.new_exception(
+                        vm.ctx.exceptions.runtime_error.clone(),
+                        format!(
+                            "Unrecognized action ({}) in warnings.filters",
+                            vm.to_repr(&action)?.as_str()
+                        ),
+                    ))
+                }
+            };
+            if already {
+                return Ok(());
+            }
+        }
+    }
+
+    show_warning(category, text, message, filename, lineno, source, vm)
+}
+
+/// Display a warning through `warnings._showwarnmsg` if the `warnings`
+/// module is loaded, or by writing it to `sys.stderr` otherwise.
+fn show_warning(
+    category: PyClassRef,
+    text: PyObjectRef,
+    message: PyObjectRef,
+    filename: PyStringRef,
+    lineno: usize,
+    source: Option<PyObjectRef>,
+    vm: &VirtualMachine,
+) -> PyResult<()> {
+    let modules = vm.get_attribute(vm.sys_module.clone(), "modules")?;
+    let modules = PyDictRef::try_from_object(vm, modules)?;
+    if let Some(warnings) = modules.get_item_option("warnings", vm)? {
+        let show = vm.get_attribute(warnings.clone(), "_showwarnmsg");
+        let message_cls = vm.get_attribute(warnings, "WarningMessage");
+        if let (Ok(show), Ok(message_cls)) = (show, message_cls) {
+            let source = source.unwrap_or_else(|| vm.get_none());
+            let warning_message = vm.invoke(
+                &message_cls,
+                vec![
+                    message,
+                    category.into_object(),
+                    filename.into_object(),
+                    vm.new_int(lineno),
+                    vm.get_none(),
+                    vm.get_none(),
+                    source,
+                ],
+            )?;
+            vm.invoke(&show, vec![warning_message])?;
+            return Ok(());
+        }
+    }
+
+    let stderr = vm.get_attribute(vm.sys_module.clone(), "stderr")?;
+    if vm.is_none(&stderr) {
+        return Ok(());
+    }
+    let text = vm.to_str(&text)?;
+    let line = format!(
+        "{}:{}: {}: {}\n",
+        filename.as_str(),
+        lineno,
+        category.name,
+        text.as_str()
+    );
+    vm.call_method(&stderr, "write", vec![vm.new_str(line)])?;
+    Ok(())
+}
+
+fn default_filters(vm: &VirtualMachine) -> PyObjectRef {
+    let ctx = &vm.ctx;
+    let filter = |action: &str, category: &PyClassRef, module: Option<&str>| {
+        ctx.new_tuple(vec![
+            ctx.new_str(action.to_string()),
+            ctx.none(),
+            category.clone().into_object(),
+            module.map_or_else(|| ctx.none(), |module| ctx.new_str(module.to_string())),
+            ctx.new_int(0),
+        ])
+    };
+    let exceptions = &ctx.exceptions;
+    ctx.new_list(vec![
+        filter("default", &exceptions.deprecation_warning, Some("__main__")),
+        filter("ignore", &exceptions.deprecation_warning, None),
+        filter("ignore", &exceptions.pending_deprecation_warning, None),
+        filter("ignore", &exceptions.import_warning, None),
+        filter("ignore", &exceptions.resource_warning, None),
+    ])
+}
+
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let ctx = &vm.ctx;
     let module = py_module!(vm, "_warnings", {
+         "filters" => default_filters(vm),
+         "_defaultaction" => ctx.new_str("default".to_string()),
+         "_onceregistry" => ctx.new_dict(),
+         "_filters_version" => ctx.new_int(1),
+         "_filters_mutated" => ctx.new_rustfunc(warnings_filters_mutated),
          "warn" => ctx.new_rustfunc(warnings_warn),
+         "warn_explicit" => ctx.new_rustfunc(warnings_warn_explicit),
     });
 
     module
@@ -1,15 +1,21 @@
 use crate::function::OptionalArg;
-use crate::obj::{objbytes::PyBytesRef, objint::PyIntRef};
-use crate::pyobject::{ItemProtocol, PyObjectRef, PyResult};
+use crate::obj::objbytes::{PyBytes, PyBytesRef};
+use crate::obj::objint::PyIntRef;
+use crate::obj::objtype::PyClassRef;
+use crate::pyobject::{ItemProtocol, PyClassImpl, PyObjectRef, PyResult, PyValue};
 use crate::types::create_type;
 use crate::vm::VirtualMachine;
 
 use adler32::RollingAdler32 as Adler32;
 use crc32fast::Hasher as Crc32;
-use flate2::{write::ZlibEncoder, Compression, Decompress, FlushDecompress, Status};
+use flate2::{
+    write::ZlibEncoder, Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status,
+};
 use libz_sys as libz;
 use num_traits::cast::ToPrimitive;
 
+use std::cell::{Cell, RefCell};
+use std::fmt;
 use std::io::Write;
 
 // copied from zlibmodule.c (commit 530f506ac91338)
@@ -30,7 +36,16 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "adler32" => ctx.new_rustfunc(zlib_adler32),
         "compress" => ctx.new_rustfunc(zlib_compress),
         "decompress" => ctx.new_rustfunc(zlib_decompress),
+        "compressobj" => ctx.new_rustfunc(zlib_compressobj),
+        "decompressobj" => ctx.new_rustfunc(zlib_decompressobj),
+        "Compress" => PyCompress::make_class(ctx),
+        "Decompress" => PyDecompress::make_class(ctx),
         "error" => zlib_error,
+        "DEFLATED" => ctx.new_int(libz::Z_DEFLATED),
+        "Z_NO_FLUSH" => ctx.new_int(libz::Z_NO_FLUSH),
+        "Z_SYNC_FLUSH" => ctx.new_int(libz::Z_SYNC_FLUSH),
+        "Z_FULL_FLUSH" => ctx.new_int(libz::Z_FULL_FLUSH),
+        "Z_FINISH" => ctx.new_int(libz::Z_FINISH),
         "Z_DEFAULT_COMPRESSION" => ctx.new_int(libz::Z_DEFAULT_COMPRESSION),
         "Z_NO_COMPRESSION" => ctx.new_int(libz::Z_NO_COMPRESSION),
         "Z_BEST_SPEED" => ctx.new_int(libz::Z_BEST_SPEED),
@@ -92,19 +107,7 @@ fn zlib_compress(
 ) -> PyResult<PyObjectRef> {
     let input_bytes = data.get_value();
 
-    let level = level
-        .into_option()
-        .as_ref()
-        .map(|v| v.as_bigint().to_i32().unwrap())
-        .unwrap_or(libz::Z_DEFAULT_COMPRESSION);
-
-    let compression = match level {
-        valid_level @ libz::Z_NO_COMPRESSION..=libz::Z_BEST_COMPRESSION => {
-            Compression::new(valid_level as u32)
-        }
-        libz::Z_DEFAULT_COMPRESSION => Compression::default(),
-        _ => return Err(zlib_error("Bad compression level", vm)),
-    };
+    let compression = get_compression(level, vm)?;
 
     let mut encoder = ZlibEncoder::new(Vec::new(), compression);
     encoder.write_all(input_bytes).unwrap();
@@ -122,25 +125,289 @@ fn zlib_decompress(
 ) -> PyResult<PyObjectRef> {
     let encoded_bytes = data.get_value();
 
-    let wbits = wbits
-        .into_option()
-        .as_ref()
-        .map(|wbits| wbits.as_bigint().to_u8().unwrap())
-        .unwrap_or(MAX_WBITS);
+    let (zlib_header, wbits) = get_window_bits(wbits, vm)?;
 
     let bufsize = bufsize
         .into_option()
         .as_ref()
         .map(|bufsize| bufsize.as_bigint().to_usize().unwrap())
-        .unwrap_or(DEF_BUF_SIZE);
+        .unwrap_or(DEF_BUF_SIZE)
+        .max(1);
 
-    let mut decompressor = Decompress::new_with_window_bits(true, wbits);
+    let mut decompressor = Decompress::new_with_window_bits(zlib_header, wbits);
     let mut decoded_bytes = Vec::with_capacity(bufsize);
 
-    match decompressor.decompress_vec(&encoded_bytes, &mut decoded_bytes, FlushDecompress::Finish) {
-        Ok(Status::BufError) => Err(zlib_error("inconsistent or truncated state", vm)),
-        Err(_) => Err(zlib_error("invalid input data", vm)),
-        _ => Ok(vm.ctx.new_bytes(decoded_bytes)),
+    // decompress_vec only fills the spare capacity, so grow the buffer until the
+    // whole stream has been decoded
+    loop {
+        let consumed = decompressor.total_in() as usize;
+        match decompressor.decompress_vec(
+            &encoded_bytes[consumed..],
+            &mut decoded_bytes,
+            FlushDecompress::Finish,
+        ) {
+            Ok(Status::StreamEnd) => return Ok(vm.ctx.new_bytes(decoded_bytes)),
+            Ok(_) if decoded_bytes.len() == decoded_bytes.capacity() => {
+                decoded_bytes.reserve(bufsize);
+            }
+            Ok(_) => return Err(zlib_error("inconsistent or truncated state", vm)),
+            Err(_) => return Err(zlib_error("invalid input data", vm)),
+        }
+    }
+}
+
+fn get_compression(level: OptionalArg<PyIntRef>, vm: &VirtualMachine) -> PyResult<Compression> {
+    let level = level
+        .into_option()
+        .as_ref()
+        .map(|v| v.as_bigint().to_i32().unwrap())
+        .unwrap_or(libz::Z_DEFAULT_COMPRESSION);
+
+    match level {
+        valid_level @ libz::Z_NO_COMPRESSION..=libz::Z_BEST_COMPRESSION => {
+            Ok(Compression::new(valid_level as u32))
+        }
+        libz::Z_DEFAULT_COMPRESSION => Ok(Compression::default()),
+        _ => Err(zlib_error("Bad compression level", vm)),
+    }
+}
+
+/// Split wbits into whether there is a zlib header and the window size.
+fn get_window_bits(wbits: OptionalArg<PyIntRef>, vm: &VirtualMachine) -> PyResult<(bool, u8)> {
+    // a negative wbits means a raw deflate stream without the zlib header
+    match wbits.into_option() {
+        None => Ok((true, MAX_WBITS)),
+        Some(wbits) => match wbits.as_bigint().to_i8() {
+            Some(wbits @ 9..=15) => Ok((true, wbits as u8)),
+            Some(wbits @ -15..=-9) => Ok((false, -wbits as u8)),
+            _ => Err(zlib_error("Invalid initialization option", vm)),
+        },
+    }
+}
+
+/// Returns a compression object, for compressing data streams that won't fit
+/// into memory at once.
+fn zlib_compressobj(
+    level: OptionalArg<PyIntRef>,
+    method: OptionalArg<PyIntRef>,
+    wbits: OptionalArg<PyIntRef>,
+    vm: &VirtualMachine,
+) -> PyResult<PyCompress> {
+    let compression = get_compression(level, vm)?;
+    if let OptionalArg::Present(method) = method {
+        if method.as_bigint().to_i32() != Some(libz::Z_DEFLATED) {
+            return Err(vm.new_value_error("Invalid initialization option".to_string()));
+        }
+    }
+    let (zlib_header, wbits) = get_window_bits(wbits, vm)?;
+
+    Ok(PyCompress {
+        compress: RefCell::new(Compress::new_with_window_bits(
+            compression,
+            zlib_header,
+            wbits,
+        )),
+    })
+}
+
+/// Returns a decompression object, for decompressing data streams that won't
+/// fit into memory at once.
+fn zlib_decompressobj(wbits: OptionalArg<PyIntRef>, vm: &VirtualMachine) -> PyResult<PyDecompress> {
+    let (zlib_header, wbits) = get_window_bits(wbits, vm)?;
+
+    Ok(PyDecompress {
+        decompress: RefCell::new(Decompress::new_with_window_bits(zlib_header, wbits)),
+        eof: Cell::new(false),
+        unconsumed_tail: RefCell::new(vec![]),
+        unused_data: RefCell::new(vec![]),
+    })
+}
+
+#[pyclass(name = "Compress")]
+struct PyCompress {
+    compress: RefCell<Compress>,
+}
+
+impl fmt::Debug for PyCompress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Compress")
+    }
+}
+
+impl PyValue for PyCompress {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("zlib", "Compress")
+    }
+}
+
+#[pyimpl]
+impl PyCompress {
+    /// Compress data, returning the compressed data that is ready so far.
+    #[pymethod(name = "compress")]
+    fn compress(&self, data: PyBytesRef, vm: &VirtualMachine) -> PyResult<PyBytes> {
+        let data = data.get_value();
+        let mut compress = self.compress.borrow_mut();
+        let start = compress.total_in();
+        let mut compressed = Vec::with_capacity(DEF_BUF_SIZE);
+
+        // compress_vec only fills the spare capacity, so grow the buffer until
+        // all the input has been taken in
+        loop {
+            let consumed = (compress.total_in() - start) as usize;
+            compress
+                .compress_vec(&data[consumed..], &mut compressed, FlushCompress::None)
+                .map_err(|_| zlib_error("Error while compressing data", vm))?;
+            if compressed.len() < compressed.capacity() {
+                return Ok(PyBytes::new(compressed));
+            }
+            compressed.reserve(DEF_BUF_SIZE);
+        }
+    }
+
+    /// Return the remaining compressed data. With the default Z_FINISH mode
+    /// the stream is finished and the object can't be used anymore.
+    #[pymethod(name = "flush")]
+    fn flush(&self, mode: OptionalArg<PyIntRef>, vm: &VirtualMachine) -> PyResult<PyBytes> {
+        let mode = match mode.into_option() {
+            None => libz::Z_FINISH,
+            Some(mode) => mode.as_bigint().to_i32().unwrap_or(-1),
+        };
+        let flush = match mode {
+            libz::Z_NO_FLUSH => return Ok(PyBytes::new(vec![])),
+            libz::Z_SYNC_FLUSH => FlushCompress::Sync,
+            libz::Z_FULL_FLUSH => FlushCompress::Full,
+            libz::Z_FINISH => FlushCompress::Finish,
+            _ => return Err(vm.new_value_error("Invalid flush mode".to_string())),
+        };
+
+        let mut compress = self.compress.borrow_mut();
+        let mut compressed = Vec::with_capacity(DEF_BUF_SIZE);
+        loop {
+            let status = compress
+                .compress_vec(&[], &mut compressed, flush)
+                .map_err(|_| zlib_error("Error while flushing", vm))?;
+            if status == Status::StreamEnd
+                || (mode != libz::Z_FINISH && compressed.len() < compressed.capacity())
+            {
+                return Ok(PyBytes::new(compressed));
+            }
+            compressed.reserve(DEF_BUF_SIZE);
+        }
+    }
+}
+
+#[pyclass(name = "Decompress")]
+struct PyDecompress {
+    decompress: RefCell<Decompress>,
+    eof: Cell<bool>,
+    unconsumed_tail: RefCell<Vec<u8>>,
+    unused_data: RefCell<Vec<u8>>,
+}
+
+impl fmt::Debug for PyDecompress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Decompress")
+    }
+}
+
+impl PyValue for PyDecompress {
+    fn class(vm: &VirtualMachine) -> PyClassRef {
+        vm.class("zlib", "Decompress")
+    }
+}
+
+#[pyimpl]
+impl PyDecompress {
+    /// Whether the end of the compressed stream has been reached.
+    #[pyproperty(name = "eof")]
+    fn eof(&self, _vm: &VirtualMachine) -> bool {
+        self.eof.get()
+    }
+
+    /// Input left over after reaching max_length, to be passed in again.
+    #[pyproperty(name = "unconsumed_tail")]
+    fn unconsumed_tail(&self, _vm: &VirtualMachine) -> PyBytes {
+        PyBytes::new(self.unconsumed_tail.borrow().clone())
+    }
+
+    /// Input found after the end of the compressed stream.
+    #[pyproperty(name = "unused_data")]
+    fn unused_data(&self, _vm: &VirtualMachine) -> PyBytes {
+        PyBytes::new(self.unused_data.borrow().clone())
+    }
+
+    /// Decompress data, returning at most max_length bytes if it isn't zero.
+    #[pymethod(name = "decompress")]
+    fn decompress(
+        &self,
+        data: PyBytesRef,
+        max_length: OptionalArg<PyIntRef>,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyBytes> {
+        let max_length = match max_length.into_option() {
+            None => 0,
+            Some(max_length) => max_length
+                .as_bigint()
+                .to_usize()
+                .ok_or_else(|| vm.new_value_error("max_length must be non-negative".to_string()))?,
+        };
+        self.decompress_data(data.get_value(), max_length, vm)
+    }
+
+    /// Decompress whatever is left in unconsumed_tail.
+    #[pymethod(name = "flush")]
+    fn flush(&self, vm: &VirtualMachine) -> PyResult<PyBytes> {
+        let data = self.unconsumed_tail.replace(vec![]);
+        self.decompress_data(&data, 0, vm)
+    }
+
+    fn decompress_data(
+        &self,
+        data: &[u8],
+        max_length: usize,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyBytes> {
+        let mut decompress = self.decompress.borrow_mut();
+        let start = decompress.total_in();
+        let mut decompressed = vec![];
+
+        loop {
+            let consumed = (decompress.total_in() - start) as usize;
+            let len = decompressed.len();
+            let chunk = if max_length == 0 {
+                DEF_BUF_SIZE
+            } else {
+                DEF_BUF_SIZE.min(max_length - len)
+            };
+            decompressed.resize(len + chunk, 0);
+            let before = decompress.total_out();
+            let status = decompress
+                .decompress(
+                    &data[consumed..],
+                    &mut decompressed[len..],
+                    FlushDecompress::None,
+                )
+                .map_err(|_| zlib_error("invalid input data", vm))?;
+            decompressed.truncate(len + (decompress.total_out() - before) as usize);
+
+            let consumed = (decompress.total_in() - start) as usize;
+            if status == Status::StreamEnd {
+                self.eof.set(true);
+                self.unused_data
+                    .borrow_mut()
+                    .extend_from_slice(&data[consumed..]);
+                self.unconsumed_tail.borrow_mut().clear();
+                break;
+            }
+            // stop when the output has room left, since then all the input
+            // has been used up, or when it has reached the limit
+            if decompressed.len() < len + chunk || decompressed.len() == max_length {
+                self.unconsumed_tail.replace(data[consumed..].to_vec());
+                break;
+            }
+        }
+
+        Ok(PyBytes::new(decompressed))
     }
 }
 
@@ -4,6 +4,7 @@ use std::{env, mem};
 use crate::frame::FrameRef;
 use crate::function::OptionalArg;
 use crate::obj::objstr::PyStringRef;
+use crate::pyhash;
 use crate::pyobject::{
     IntoPyObject, ItemProtocol, PyClassImpl, PyContext, PyObjectRef, PyResult, TypeProtocol,
 };
@@ -93,6 +94,7 @@ impl SysFlags {
         flags.verbose = settings.verbose;
         flags.quiet = settings.quiet;
         flags.dont_write_bytecode = settings.dont_write_bytecode;
+        flags.hash_randomization = settings.hash_seed != Some(0);
         flags
     }
 }
@@ -121,6 +123,32 @@ fn sys_getfilesystemencodeerrors(_vm: &VirtualMachine) -> String {
     "surrogatepass".to_string()
 }
 
+fn sys_getrecursionlimit(vm: &VirtualMachine) -> usize {
+    vm.recursion_limit.get()
+}
+
+fn sys_setrecursionlimit(limit: i32, vm: &VirtualMachine) -> PyResult<()> {
+    if limit < 1 {
+        return Err(
+            vm.new_value_error("recursion limit must be greater or equal than 1".to_string())
+        );
+    }
+    let limit = limit as usize;
+    if limit <= vm.frames.borrow().len() {
+        let recursion_error = vm.ctx.exceptions.recursion_error.clone();
+        return Err(vm.new_exception(
+            recursion_error,
+            format!(
+                "cannot set the recursion limit to {} at the recursion depth {}: the limit is too low",
+                limit,
+                vm.frames.borrow().len()
+            ),
+        ));
+    }
+    vm.recursion_limit.set(limit);
+    Ok(())
+}
+
 fn sys_getprofile(vm: &VirtualMachine) -> PyObjectRef {
     vm.profile_func.borrow().clone()
 }
@@ -157,16 +185,16 @@ fn sys_exc_info(vm: &VirtualMachine) -> PyResult {
         Some(exception) => vec![
             exception.class().into_object(),
             exception.clone(),
-            vm.get_none(),
+            vm.get_attribute(exception.clone(), "__traceback__")?,
         ],
         None => vec![vm.get_none(), vm.get_none(), vm.get_none()],
     }))
 }
 
-// TODO: raise a SystemExit here
-fn sys_exit(code: OptionalArg<i32>, _vm: &VirtualMachine) -> PyResult<()> {
-    let code = code.unwrap_or(0);
-    std::process::exit(code)
+fn sys_exit(code: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<()> {
+    let code = code.unwrap_or_else(|| vm.get_none());
+    let system_exit = vm.ctx.exceptions.system_exit.clone();
+    Err(vm.new_exception_obj(system_exit, vec![code])?)
 }
 
 #[pystruct_sequence(name = "version_info")]
@@ -179,6 +207,24 @@ struct VersionInfo {
     serial: usize,
 }
 
+/// sys.hash_info
+///
+/// The parameters of the numeric hash, so that pure Python numeric types can
+/// hash equal to the builtin numbers they compare equal to.
+#[pystruct_sequence(name = "hash_info")]
+#[derive(Debug)]
+struct HashInfo {
+    width: usize,
+    modulus: pyhash::PyUHash,
+    inf: pyhash::PyHash,
+    nan: pyhash::PyHash,
+    imag: pyhash::PyHash,
+    algorithm: String,
+    hash_bits: usize,
+    seed_bits: usize,
+    cutoff: usize,
+}
+
 pub fn make_module(vm: &VirtualMachine, module: PyObjectRef, builtins: PyObjectRef) {
     let ctx = &vm.ctx;
 
@@ -198,10 +244,27 @@ pub fn make_module(vm: &VirtualMachine, module: PyObjectRef, builtins: PyObjectR
     .into_struct_sequence(vm, version_info_type)
     .unwrap();
 
+    let hash_info_type = HashInfo::make_class(ctx);
+    let hash_info = HashInfo {
+        width: mem::size_of::<pyhash::PyHash>() * 8,
+        modulus: pyhash::MODULUS,
+        inf: pyhash::INF,
+        nan: pyhash::NAN,
+        imag: pyhash::IMAG,
+        algorithm: "siphash13".to_owned(),
+        hash_bits: 64,
+        seed_bits: 128,
+        cutoff: 0,
+    }
+    .into_struct_sequence(vm, hash_info_type)
+    .unwrap();
+
     // TODO Add crate version to this namespace
     let implementation = py_namespace!(vm, {
         "name" => ctx.new_str("RustPython".to_string()),
-        "cache_tag" => ctx.new_str("rustpython-01".to_string()),
+        // Bump this along with MAGIC_NUMBER in _bootstrap_external.py whenever
+        // the bytecode changes, so stale caches aren't loaded.
+        "cache_tag" => ctx.new_str("rustpython-02".to_string()),
     });
 
     let path = ctx.new_list(
@@ -335,6 +398,8 @@ settrace() -- set the global debug tracing function
       "getfilesystemencoding" => ctx.new_rustfunc(sys_getfilesystemencoding),
       "getfilesystemencodeerrors" => ctx.new_rustfunc(sys_getfilesystemencodeerrors),
       "getprofile" => ctx.new_rustfunc(sys_getprofile),
+      "getrecursionlimit" => ctx.new_rustfunc(sys_getrecursionlimit),
+      "setrecursionlimit" => ctx.new_rustfunc(sys_setrecursionlimit),
       "gettrace" => ctx.new_rustfunc(sys_gettrace),
       "intern" => ctx.new_rustfunc(sys_intern),
       "maxunicode" => ctx.new_int(0x0010_FFFF),
@@ -345,7 +410,13 @@ settrace() -- set the global debug tracing function
       "__doc__" => ctx.new_str(sys_doc.to_string()),
       "_getframe" => ctx.new_rustfunc(getframe),
       "modules" => modules.clone(),
-      "warnoptions" => ctx.new_list(vec![]),
+      "warnoptions" => ctx.new_list(
+          vm.settings
+              .warnopts
+              .iter()
+              .map(|opt| vm.new_str(opt.to_owned()))
+              .collect(),
+      ),
       "platform" => ctx.new_str(platform),
       "meta_path" => ctx.new_list(vec![]),
       "path_hooks" => ctx.new_list(vec![]),
@@ -356,6 +427,7 @@ settrace() -- set the global debug tracing function
       "settrace" => ctx.new_rustfunc(sys_settrace),
       "version" => vm.new_str(version::get_version()),
       "version_info" => version_info,
+      "hash_info" => hash_info,
       "exc_info" => ctx.new_rustfunc(sys_exc_info),
       "prefix" => ctx.new_str(prefix.to_string()),
       "base_prefix" => ctx.new_str(base_prefix.to_string()),
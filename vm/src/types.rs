@@ -4,6 +4,7 @@ use crate::obj::objbytes;
 use crate::obj::objclassmethod;
 use crate::obj::objcode;
 use crate::obj::objcomplex;
+use crate::obj::objcoroutine;
 use crate::obj::objdict;
 use crate::obj::objellipsis;
 use crate::obj::objenumerate;
@@ -12,6 +13,7 @@ use crate::obj::objfloat;
 use crate::obj::objframe;
 use crate::obj::objfunction;
 use crate::obj::objgenerator;
+use crate::obj::objgenericalias;
 use crate::obj::objint;
 use crate::obj::objiter;
 use crate::obj::objlist;
@@ -29,6 +31,7 @@ use crate::obj::objslice;
 use crate::obj::objstaticmethod;
 use crate::obj::objstr;
 use crate::obj::objsuper;
+use crate::obj::objtraceback;
 use crate::obj::objtuple;
 use crate::obj::objtype::{self, PyClass, PyClassRef};
 use crate::obj::objweakproxy;
@@ -54,8 +57,12 @@ pub struct TypeZoo {
     pub filter_type: PyClassRef,
     pub float_type: PyClassRef,
     pub frame_type: PyClassRef,
+    pub traceback_type: PyClassRef,
     pub frozenset_type: PyClassRef,
     pub generator_type: PyClassRef,
+    pub generic_alias_type: PyClassRef,
+    pub coroutine_type: PyClassRef,
+    pub coroutine_wrapper_type: PyClassRef,
     pub int_type: PyClassRef,
     pub iter_type: PyClassRef,
     pub complex_type: PyClassRef,
@@ -120,6 +127,9 @@ impl TypeZoo {
         let weakref_type = create_type("ref", &type_type, &object_type);
         let weakproxy_type = create_type("weakproxy", &type_type, &object_type);
         let generator_type = create_type("generator", &type_type, &object_type);
+        let generic_alias_type = create_type("GenericAlias", &type_type, &object_type);
+        let coroutine_type = create_type("coroutine", &type_type, &object_type);
+        let coroutine_wrapper_type = create_type("coroutine_wrapper", &type_type, &object_type);
         let bound_method_type = create_type("method", &type_type, &object_type);
         let str_type = create_type("str", &type_type, &object_type);
         let list_type = create_type("list", &type_type, &object_type);
@@ -139,6 +149,7 @@ impl TypeZoo {
         let int_type = create_type("int", &type_type, &object_type);
         let float_type = create_type("float", &type_type, &object_type);
         let frame_type = create_type("frame", &type_type, &object_type);
+        let traceback_type = create_type("traceback", &type_type, &object_type);
         let complex_type = create_type("complex", &type_type, &object_type);
         let bytes_type = create_type("bytes", &type_type, &object_type);
         let bytesiterator_type = create_type("bytes_iterator", &type_type, &object_type);
@@ -172,6 +183,7 @@ impl TypeZoo {
             int_type,
             float_type,
             frame_type,
+            traceback_type,
             staticmethod_type,
             list_type,
             listiterator_type,
@@ -206,6 +218,9 @@ impl TypeZoo {
             property_type,
             readonly_property_type,
             generator_type,
+            generic_alias_type,
+            coroutine_type,
+            coroutine_wrapper_type,
             module_type,
             namespace_type,
             bound_method_type,
@@ -283,6 +298,8 @@ pub fn initialize_types(context: &PyContext) {
     objstaticmethod::init(&context);
     objclassmethod::init(&context);
     objgenerator::init(&context);
+    objcoroutine::init(&context);
+    objgenericalias::init(&context);
     objint::init(&context);
     objfloat::init(&context);
     objcomplex::init(&context);
@@ -304,6 +321,7 @@ pub fn initialize_types(context: &PyContext) {
     objbool::init(&context);
     objcode::init(&context);
     objframe::init(&context);
+    objtraceback::init(&context);
     objweakref::init(&context);
     objweakproxy::init(&context);
     objnone::init(&context);
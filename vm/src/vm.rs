@@ -4,7 +4,7 @@
 //!   https://github.com/ProgVal/pythonvm-rust/blob/master/src/processor/mod.rs
 //!
 
-use std::cell::{Ref, RefCell};
+use std::cell::{Cell, Ref, RefCell};
 use std::collections::hash_map::HashMap;
 use std::collections::hash_set::HashSet;
 use std::fmt;
@@ -20,10 +20,11 @@ use crate::import;
 use crate::obj::objbool;
 use crate::obj::objbuiltinfunc::PyBuiltinFunction;
 use crate::obj::objcode::{PyCode, PyCodeRef};
+use crate::obj::objcoroutine::PyCoroutine;
 use crate::obj::objdict::PyDictRef;
 use crate::obj::objfunction::{PyFunction, PyMethod};
 use crate::obj::objgenerator::PyGenerator;
-use crate::obj::objint::PyInt;
+use crate::obj::objint::{PyInt, PyIntRef};
 use crate::obj::objiter;
 use crate::obj::objmodule::{self, PyModule};
 use crate::obj::objsequence;
@@ -31,7 +32,7 @@ use crate::obj::objstr::{PyString, PyStringRef};
 use crate::obj::objtuple::PyTupleRef;
 use crate::obj::objtype;
 use crate::obj::objtype::PyClassRef;
-use crate::pyhash;
+use crate::pyhash::{self, HashSecret};
 use crate::pyobject::{
     IdProtocol, ItemProtocol, PyContext, PyObject, PyObjectRef, PyResult, PyValue, TryFromObject,
     TryIntoRef, TypeProtocol,
@@ -41,6 +42,7 @@ use crate::stdlib;
 use crate::sysmodule;
 use arr_macro::arr;
 use num_bigint::BigInt;
+use num_traits::ToPrimitive;
 #[cfg(feature = "rustpython-compiler")]
 use rustpython_compiler::{compile, error::CompileError};
 
@@ -56,6 +58,7 @@ pub struct VirtualMachine {
     pub stdlib_inits: RefCell<HashMap<String, stdlib::StdlibInitFunc>>,
     pub ctx: PyContext,
     pub frames: RefCell<Vec<FrameRef>>,
+    pub recursion_limit: Cell<usize>,
     pub wasm_id: Option<String>,
     pub exceptions: RefCell<Vec<PyObjectRef>>,
     pub frozen: RefCell<HashMap<String, bytecode::FrozenModule>>,
@@ -64,11 +67,18 @@ pub struct VirtualMachine {
     pub trace_func: RefCell<PyObjectRef>,
     pub use_tracing: RefCell<bool>,
     pub signal_handlers: RefCell<[PyObjectRef; NSIG]>,
+    pub atexit_funcs: RefCell<Vec<(PyObjectRef, PyFuncArgs)>>,
+    /// The `contextvars.Context` objects being run, innermost last.
+    pub contexts: RefCell<Vec<PyObjectRef>>,
+    pub hash_secret: HashSecret,
     pub settings: PySettings,
 }
 
 pub const NSIG: usize = 64;
 
+/// Initial value of `sys.getrecursionlimit()`.
+pub const DEFAULT_RECURSION_LIMIT: usize = 1000;
+
 /// Struct containing all kind of settings for the python vm.
 pub struct PySettings {
     /// -d command line switch
@@ -98,17 +108,29 @@ pub struct PySettings {
     /// -B
     pub dont_write_bytecode: bool,
 
+    /// --check-hash-based-pycs: "default", "always" or "never"
+    pub check_hash_based_pycs: String,
+
     /// Environment PYTHONPATH and RUSTPYTHONPATH:
     pub path_list: Vec<String>,
 
     /// sys.argv
     pub argv: Vec<String>,
+
+    /// PYTHONHASHSEED, None for a random seed
+    pub hash_seed: Option<u32>,
+
+    /// -W options and PYTHONWARNINGS entries, in that order of precedence
+    pub warnopts: Vec<String>,
 }
 
 /// Trace events for sys.settrace and sys.setprofile.
-enum TraceEvent {
+pub enum TraceEvent {
     Call,
     Return,
+    CCall,
+    CReturn,
+    CException,
 }
 
 impl fmt::Display for TraceEvent {
@@ -117,6 +139,9 @@ impl fmt::Display for TraceEvent {
         match self {
             Call => write!(f, "call"),
             Return => write!(f, "return"),
+            CCall => write!(f, "c_call"),
+            CReturn => write!(f, "c_return"),
+            CException => write!(f, "c_exception"),
         }
     }
 }
@@ -134,8 +159,11 @@ impl Default for PySettings {
             verbose: 0,
             quiet: false,
             dont_write_bytecode: false,
+            check_hash_based_pycs: "default".to_string(),
             path_list: vec![],
             argv: vec![],
+            hash_seed: None,
+            warnopts: vec![],
         }
     }
 }
@@ -177,6 +205,7 @@ impl VirtualMachine {
             stdlib_inits,
             ctx,
             frames: RefCell::new(vec![]),
+            recursion_limit: Cell::new(DEFAULT_RECURSION_LIMIT),
             wasm_id: None,
             exceptions: RefCell::new(vec![]),
             frozen,
@@ -185,6 +214,9 @@ impl VirtualMachine {
             trace_func,
             use_tracing: RefCell::new(false),
             signal_handlers,
+            atexit_funcs: RefCell::new(vec![]),
+            contexts: RefCell::new(vec![]),
+            hash_secret: HashSecret::new(settings.hash_seed),
             settings,
         };
 
@@ -206,6 +238,11 @@ impl VirtualMachine {
 
         #[cfg(not(target_arch = "wasm32"))]
         import::import_builtin(&vm, "signal").expect("Couldn't initialize signal module");
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            import::import_builtin(&vm, "_io").expect("Couldn't initialize io module");
+            stdlib::io::init_stdio(&vm).expect("Couldn't initialize standard streams");
+        }
 
         vm
     }
@@ -223,17 +260,31 @@ impl VirtualMachine {
     }
 
     pub fn run_frame(&self, frame: FrameRef) -> PyResult<ExecutionResult> {
+        self.check_recursion_depth()?;
         self.frames.borrow_mut().push(frame.clone());
         let result = frame.run(self);
         self.frames.borrow_mut().pop();
         result
     }
 
+    fn check_recursion_depth(&self) -> PyResult<()> {
+        if self.frames.borrow().len() >= self.recursion_limit.get() {
+            let recursion_error = self.ctx.exceptions.recursion_error.clone();
+            Err(self.new_exception(
+                recursion_error,
+                "maximum recursion depth exceeded".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn frame_throw(
         &self,
         frame: FrameRef,
         exception: PyObjectRef,
     ) -> PyResult<ExecutionResult> {
+        self.check_recursion_depth()?;
         self.frames.borrow_mut().push(frame.clone());
         let result = frame.throw(self, exception);
         self.frames.borrow_mut().pop();
@@ -259,11 +310,8 @@ impl VirtualMachine {
     }
 
     pub fn try_class(&self, module: &str, class: &str) -> PyResult<PyClassRef> {
-        let class = self
-            .get_attribute(self.import(module, &[], 0)?, class)?
-            .downcast()
-            .expect("not a class");
-        Ok(class)
+        let class = self.get_attribute(self.import(module, &[], 0)?, class)?;
+        PyClassRef::try_from_object(self, class)
     }
 
     pub fn class(&self, module: &str, class: &str) -> PyClassRef {
@@ -303,7 +351,7 @@ impl VirtualMachine {
     }
 
     #[cfg_attr(feature = "flame-it", flame("VirtualMachine"))]
-    fn new_exception_obj(&self, exc_type: PyClassRef, args: Vec<PyObjectRef>) -> PyResult {
+    pub fn new_exception_obj(&self, exc_type: PyClassRef, args: Vec<PyObjectRef>) -> PyResult {
         // TODO: add repr of args into logging?
         vm_trace!("New exception created: {}", exc_type.name);
         self.invoke(&exc_type.into_object(), args)
@@ -395,6 +443,11 @@ impl VirtualMachine {
         self.new_exception(overflow_error, msg)
     }
 
+    pub fn new_runtime_error(&self, msg: String) -> PyObjectRef {
+        let runtime_error = self.ctx.exceptions.runtime_error.clone();
+        self.new_exception(runtime_error, msg)
+    }
+
     #[cfg(feature = "rustpython-compiler")]
     pub fn new_syntax_error(&self, error: &CompileError) -> PyObjectRef {
         let syntax_error_type = self.ctx.exceptions.syntax_error.clone();
@@ -461,6 +514,26 @@ impl VirtualMachine {
         Ok(self.new_str(ascii))
     }
 
+    /// Convert an object to an int through `__index__`, as `operator.index` does.
+    pub fn to_index(&self, obj: &PyObjectRef) -> PyResult<PyIntRef> {
+        if let Ok(i) = obj.clone().downcast::<PyInt>() {
+            return Ok(i);
+        }
+        let method = self.get_method_or_type_error(obj.clone(), "__index__", || {
+            format!(
+                "'{}' object cannot be interpreted as an integer",
+                obj.class().name
+            )
+        })?;
+        let result = self.invoke(&method, vec![])?;
+        result.downcast::<PyInt>().map_err(|result| {
+            self.new_type_error(format!(
+                "__index__ returned non-int (type {})",
+                result.class().name
+            ))
+        })
+    }
+
     pub fn import(&self, module: &str, from_list: &[String], level: usize) -> PyResult {
         // if the import inputs seem weird, e.g a package import or something, rather than just
         // a straight `import ident`
@@ -470,7 +543,11 @@ impl VirtualMachine {
             None
         } else {
             let sys_modules = self.get_attribute(self.sys_module.clone(), "modules")?;
-            sys_modules.get_item(module, self).ok()
+            // a None entry blocks the import, and __import__ reports that
+            sys_modules
+                .get_item(module, self)
+                .ok()
+                .filter(|module| !self.is_none(module))
         };
 
         match cached_module {
@@ -577,22 +654,24 @@ impl VirtualMachine {
             ref kw_only_defaults,
         }) = func_ref.payload()
         {
-            self.trace_event(TraceEvent::Call)?;
-            let res = self.invoke_python_function(code, scope, defaults, kw_only_defaults, args);
-            self.trace_event(TraceEvent::Return)?;
-            res
+            // Copy the defaults out, the function may reassign them while running
+            let defaults = defaults.borrow().clone();
+            let kw_only_defaults = kw_only_defaults.borrow().clone();
+            self.invoke_python_function(code, scope, &defaults, &kw_only_defaults, args)
         } else if let Some(PyMethod {
             ref function,
             ref object,
         }) = func_ref.payload()
         {
             self.invoke(&function, args.insert(object.clone()))
-        } else if let Some(PyBuiltinFunction { ref value }) = func_ref.payload() {
+        } else if let Some(PyBuiltinFunction { ref value, .. }) = func_ref.payload() {
             value(self, args)
         } else {
-            // TODO: is it safe to just invoke __call__ otherwise?
             vm_trace!("invoke __call__ for: {:?}", &func_ref.payload);
-            self.call_method(&func_ref, "__call__", args)
+            let call = self.get_method_or_type_error(func_ref.clone(), "__call__", || {
+                format!("'{}' object is not callable", func_ref.class().name)
+            })?;
+            self.invoke(&call, args)
         }
     }
 
@@ -605,17 +684,24 @@ impl VirtualMachine {
         res
     }
 
-    /// Call registered trace function.
-    fn trace_event(&self, event: TraceEvent) -> PyResult<()> {
+    /// Call registered trace and profile functions. The trace function only
+    /// sees Python-level call and return events; `arg` is the return value
+    /// for `return` and the called builtin for the `c_*` events.
+    pub fn trace_event(&self, event: TraceEvent, arg: PyObjectRef) -> PyResult<()> {
         if *self.use_tracing.borrow() {
-            let frame = self.get_none();
+            let trace_func = match event {
+                TraceEvent::Call | TraceEvent::Return => self.trace_func.borrow().clone(),
+                _ => self.get_none(),
+            };
+            let frame = match self.current_frame() {
+                Some(frame) => frame.clone().into_object(),
+                None => self.get_none(),
+            };
             let event = self.new_str(event.to_string());
-            let arg = self.get_none();
             let args = vec![frame, event, arg];
 
             // temporarily disable tracing, during the call to the
             // tracing function itself.
-            let trace_func = self.trace_func.borrow().clone();
             if !self.is_none(&trace_func) {
                 self.use_tracing.replace(false);
                 let res = self.invoke(&trace_func, args.clone());
@@ -654,8 +740,10 @@ impl VirtualMachine {
         // Construct frame:
         let frame = Frame::new(code.clone(), scope).into_ref(self);
 
-        // If we have a generator, create a new generator
-        if code.code.is_generator {
+        // If we have a generator or a coroutine, wrap the frame instead of running it
+        if code.code.is_coroutine {
+            Ok(PyCoroutine::new(frame, self).into_object())
+        } else if code.code.is_generator {
             Ok(PyGenerator::new(frame, self).into_object())
         } else {
             self.run_frame_full(frame)
@@ -841,7 +929,22 @@ impl VirtualMachine {
     {
         let attr_name = attr_name.try_into_ref(self)?;
         vm_trace!("vm.__getattribute__: {:?} {:?}", obj, attr_name);
-        self.call_method(&obj, "__getattribute__", vec![attr_name.into_object()])
+        self.call_method(
+            &obj,
+            "__getattribute__",
+            vec![attr_name.clone().into_object()],
+        )
+        .or_else(|exc| {
+            // Like CPython, fall back to __getattr__ only when the
+            // regular lookup raised AttributeError.
+            if !objtype::isinstance(&exc, &self.ctx.exceptions.attribute_error) {
+                return Err(exc);
+            }
+            match objtype::class_get_attr(&obj.class(), "__getattr__") {
+                Some(getter) => self.invoke(&getter, vec![obj, attr_name.into_object()]),
+                None => Err(exc),
+            }
+        })
     }
 
     pub fn set_attr<K, V>(&self, obj: &PyObjectRef, attr_name: K, attr_value: V) -> PyResult
@@ -965,9 +1068,6 @@ impl VirtualMachine {
             Ok(Some(obj_attr))
         } else if let Some(attr) = objtype::class_get_attr(&cls, &name) {
             self.call_get_descriptor(attr, obj).map(Some)
-        } else if let Some(getter) = objtype::class_get_attr(&cls, "__getattr__") {
-            self.invoke(&getter, vec![obj, name_str.into_object()])
-                .map(Some)
         } else {
             Ok(None)
         }
@@ -989,7 +1089,20 @@ impl VirtualMachine {
         mode: compile::Mode,
         source_path: String,
     ) -> Result<PyCodeRef, CompileError> {
-        compile::compile(source, mode, source_path, self.settings.optimize)
+        self.compile_with_optimize(source, mode, source_path, self.settings.optimize)
+    }
+
+    /// Compile `source` at the given optimization level instead of the
+    /// interpreter-wide one, as `compile(..., optimize=n)` does.
+    #[cfg(feature = "rustpython-compiler")]
+    pub fn compile_with_optimize(
+        &self,
+        source: &str,
+        mode: compile::Mode,
+        source_path: String,
+        optimize: u8,
+    ) -> Result<PyCodeRef, CompileError> {
+        compile::compile(source, mode, source_path, optimize)
             .map(|codeobj| PyCode::new(codeobj).into_ref(self))
     }
 
@@ -1175,47 +1288,111 @@ impl VirtualMachine {
         })
     }
 
+    /// Calls a rich comparison method, returning `None` if it is missing
+    /// or returns `NotImplemented`.
+    fn call_rich_compare(
+        &self,
+        obj: &PyObjectRef,
+        method: &str,
+        other: &PyObjectRef,
+    ) -> PyResult<Option<PyObjectRef>> {
+        if let Some(method_or_err) = self.get_method(obj.clone(), method) {
+            let result = self.invoke(&method_or_err?, vec![other.clone()])?;
+            if !result.is(&self.ctx.not_implemented()) {
+                return Ok(Some(result));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The rich comparison protocol: `a.method(b)`, then `b.reflection(a)`,
+    /// and finally `unsupported`. The reflection goes first when `b` is an
+    /// instance of a strict subclass of `a`'s type, so subclasses can
+    /// override comparisons with their base.
+    fn rich_compare<F>(
+        &self,
+        a: PyObjectRef,
+        b: PyObjectRef,
+        method: &str,
+        reflection: &str,
+        unsupported: F,
+    ) -> PyResult
+    where
+        F: FnOnce(&VirtualMachine, PyObjectRef, PyObjectRef) -> PyResult,
+    {
+        let a_cls = a.class();
+        let b_cls = b.class();
+        let reflect_first = !a_cls.is(&b_cls) && objtype::issubclass(&b_cls, &a_cls);
+        if reflect_first {
+            if let Some(result) = self.call_rich_compare(&b, reflection, &a)? {
+                return Ok(result);
+            }
+        }
+        if let Some(result) = self.call_rich_compare(&a, method, &b)? {
+            return Ok(result);
+        }
+        if !reflect_first {
+            if let Some(result) = self.call_rich_compare(&b, reflection, &a)? {
+                return Ok(result);
+            }
+        }
+        unsupported(self, a, b)
+    }
+
+    fn new_unorderable_error(&self, a: PyObjectRef, b: PyObjectRef, op: &str) -> PyObjectRef {
+        self.new_type_error(format!(
+            "'{}' not supported between instances of '{}' and '{}'",
+            op,
+            a.class().name,
+            b.class().name
+        ))
+    }
+
     pub fn _eq(&self, a: PyObjectRef, b: PyObjectRef) -> PyResult {
-        self.call_or_reflection(a, b, "__eq__", "__eq__", |vm, a, b| {
+        self.rich_compare(a, b, "__eq__", "__eq__", |vm, a, b| {
             Ok(vm.new_bool(a.is(&b)))
         })
     }
 
     pub fn _ne(&self, a: PyObjectRef, b: PyObjectRef) -> PyResult {
-        self.call_or_reflection(a, b, "__ne__", "__ne__", |vm, a, b| {
-            let eq = vm._eq(a, b)?;
-            objbool::not(vm, &eq)
+        self.rich_compare(a, b, "__ne__", "__ne__", |vm, a, b| {
+            Ok(vm.new_bool(!a.is(&b)))
         })
     }
 
     pub fn _lt(&self, a: PyObjectRef, b: PyObjectRef) -> PyResult {
-        self.call_or_reflection(a, b, "__lt__", "__gt__", |vm, a, b| {
-            Err(vm.new_unsupported_operand_error(a, b, "<"))
+        self.rich_compare(a, b, "__lt__", "__gt__", |vm, a, b| {
+            Err(vm.new_unorderable_error(a, b, "<"))
         })
     }
 
     pub fn _le(&self, a: PyObjectRef, b: PyObjectRef) -> PyResult {
-        self.call_or_reflection(a, b, "__le__", "__ge__", |vm, a, b| {
-            Err(vm.new_unsupported_operand_error(a, b, "<="))
+        self.rich_compare(a, b, "__le__", "__ge__", |vm, a, b| {
+            Err(vm.new_unorderable_error(a, b, "<="))
         })
     }
 
     pub fn _gt(&self, a: PyObjectRef, b: PyObjectRef) -> PyResult {
-        self.call_or_reflection(a, b, "__gt__", "__lt__", |vm, a, b| {
-            Err(vm.new_unsupported_operand_error(a, b, ">"))
+        self.rich_compare(a, b, "__gt__", "__lt__", |vm, a, b| {
+            Err(vm.new_unorderable_error(a, b, ">"))
         })
     }
 
     pub fn _ge(&self, a: PyObjectRef, b: PyObjectRef) -> PyResult {
-        self.call_or_reflection(a, b, "__ge__", "__le__", |vm, a, b| {
-            Err(vm.new_unsupported_operand_error(a, b, ">="))
+        self.rich_compare(a, b, "__ge__", "__le__", |vm, a, b| {
+            Err(vm.new_unorderable_error(a, b, ">="))
         })
     }
 
     pub fn _hash(&self, obj: &PyObjectRef) -> PyResult<pyhash::PyHash> {
         let hash_obj = self.call_method(obj, "__hash__", vec![])?;
         if objtype::isinstance(&hash_obj, &self.ctx.int_type()) {
-            Ok(hash_obj.payload::<PyInt>().unwrap().hash(self))
+            // A result that fits is used as is; bigger ones are hashed again.
+            let value = hash_obj.payload::<PyInt>().unwrap();
+            Ok(match value.as_bigint().to_i64() {
+                Some(hash) => pyhash::fix_sentinel(hash),
+                None => value.hash(self),
+            })
         } else {
             Err(self.new_type_error("__hash__ method should return an integer".to_string()))
         }
@@ -1242,8 +1419,13 @@ impl VirtualMachine {
         if let Some(method_or_err) = self.get_method(haystack.clone(), "__contains__") {
             let method = method_or_err?;
             self.invoke(&method, vec![needle])
-        } else {
+        } else if objiter::is_iterable(&haystack) {
             self._membership_iter_search(haystack, needle)
+        } else {
+            Err(self.new_type_error(format!(
+                "argument of type '{}' is not iterable",
+                haystack.class().name
+            )))
         }
     }
 
@@ -1266,6 +1448,13 @@ impl Default for VirtualMachine {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for VirtualMachine {
+    fn drop(&mut self) {
+        stdlib::faulthandler::forget_vm(self);
+    }
+}
+
 lazy_static! {
     static ref REPR_GUARDS: Mutex<HashSet<usize>> = { Mutex::new(HashSet::new()) };
 }
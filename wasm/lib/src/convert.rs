@@ -1,10 +1,10 @@
 use js_sys::{Array, ArrayBuffer, Object, Promise, Reflect, Uint8Array};
-use num_traits::cast::ToPrimitive;
 use serde_wasm_bindgen;
 use wasm_bindgen::{closure::Closure, prelude::*, JsCast};
 
 use rustpython_vm::function::PyFuncArgs;
-use rustpython_vm::obj::{objbytes, objint, objsequence, objtype};
+use rustpython_vm::obj::objtraceback::PyTraceback;
+use rustpython_vm::obj::{objbytes, objtype};
 use rustpython_vm::py_serde;
 use rustpython_vm::pyobject::{ItemProtocol, PyObjectRef, PyResult, PyValue};
 use rustpython_vm::VirtualMachine;
@@ -37,19 +37,9 @@ pub fn py_err_to_js_err(vm: &VirtualMachine, py_err: &PyObjectRef) -> JsValue {
         &vm.ctx.exceptions.syntax_error => js_sys::SyntaxError::new,
     });
     if let Ok(tb) = vm.get_attribute(py_err.clone(), "__traceback__") {
-        if objtype::isinstance(&tb, &vm.ctx.list_type()) {
-            let elements = objsequence::get_elements_list(&tb).to_vec();
-            if let Some(top) = elements.get(0) {
-                if objtype::isinstance(&top, &vm.ctx.tuple_type()) {
-                    let element = objsequence::get_elements_tuple(&top);
-
-                    if let Some(lineno) = objint::to_int(vm, &element[1], 10)
-                        .ok()
-                        .and_then(|lineno| lineno.to_u32())
-                    {
-                        let _ = Reflect::set(&js_err, &"row".into(), &lineno.into());
-                    }
-                }
+        if let Ok(tb) = tb.downcast::<PyTraceback>() {
+            if let Some(top) = tb.iter().last() {
+                let _ = Reflect::set(&js_err, &"row".into(), &(top.lineno as u32).into());
             }
         }
     }